@@ -0,0 +1,175 @@
+use std::convert::TryFrom;
+use std::env;
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use serde_json::json;
+use version_compare::Cmp;
+
+use kepler::db::{self, Database};
+use kepler::search::{self, Query};
+use kepler::sources::nist::{cpe, cve::item, cve::list, cve::node};
+use kepler::utils::version_cmp;
+
+const CPES: &[&str] = &[
+    "cpe:2.3:a:xmlsoft:libxml2:2.9.10:*:*:*:*:*:*:*",
+    "cpe:2.3:o:linux:linux_kernel:5.10.78:*:*:*:*:*:*:*",
+    "cpe:2.3:a:openssl:openssl:1.1.1k:*:*:*:*:*:*:*",
+    "cpe:2.3:a:lodash:lodash:*:*:*:*:*:node.js:*:*",
+];
+
+/// A configuration node like the NIST ones: a few vulnerable ranges of the product along
+/// with unrelated products.
+fn node(product: &str) -> node::Node {
+    serde_json::from_value(json!({
+        "operator": "OR",
+        "children": [],
+        "cpe_match": [
+            {
+                "vulnerable": true,
+                "cpe23Uri": format!("cpe:2.3:a:vendor:{}:*:*:*:*:*:*:*:*", product),
+                "versionStartIncluding": "1.0.0",
+                "versionEndExcluding": "1.2.5"
+            },
+            {
+                "vulnerable": true,
+                "cpe23Uri": format!("cpe:2.3:a:vendor:{}:2.0.1:*:*:*:*:*:*:*", product),
+            },
+            {
+                "vulnerable": true,
+                "cpe23Uri": "cpe:2.3:a:other:unrelated:*:*:*:*:*:*:*:*",
+                "versionEndIncluding": "3.4"
+            }
+        ]
+    }))
+    .unwrap()
+}
+
+fn cve(product: &str) -> item::CVE {
+    let mut cve = item::CVE::default();
+    cve.configurations.nodes = vec![node(product)];
+    cve
+}
+
+fn cpe_parsing(c: &mut Criterion) {
+    c.bench_function("cpe parsing", |b| {
+        b.iter(|| {
+            for uri in CPES {
+                black_box(cpe::CPE23::try_from(*uri).unwrap());
+            }
+        })
+    });
+}
+
+fn version_comparison(c: &mut Criterion) {
+    c.bench_function("version comparison", |b| {
+        b.iter(|| {
+            black_box(version_cmp("2.9.10", "2.9.4", Cmp::Ge));
+            black_box(version_cmp("1.1.1k", "1.1.1l", Cmp::Lt));
+            black_box(version_cmp("5.10.78", "5.10", Cmp::Eq));
+        })
+    });
+}
+
+fn node_matching(c: &mut Criterion) {
+    let mut node = node("libxml2");
+    let libxml2 = Query {
+        vendor: None,
+        product: "libxml2".into(),
+        version: None,
+        target_sw: None,
+        part: None,
+        distro: None,
+        os: None,
+        update: None,
+        edition: None,
+        strictness: None,
+    };
+
+    c.bench_function("node matching", |b| {
+        b.iter(|| {
+            black_box(node.is_match(&libxml2, "1.2.3"));
+            black_box(node.is_match(&libxml2, "2.0.1"));
+            black_box(node.is_match(&libxml2, "3.0.0"));
+        })
+    });
+
+    let mut items: Vec<item::CVE> = (0..1000).map(|i| cve(&format!("product{}", i))).collect();
+    let query = Query {
+        vendor: None,
+        product: "product999".into(),
+        version: Some("1.2.0".into()),
+        target_sw: None,
+        part: None,
+        distro: None,
+        os: None,
+        update: None,
+        edition: None,
+        strictness: None,
+    };
+
+    c.bench_function("matching 1000 cves", |b| {
+        b.iter(|| {
+            items
+                .iter_mut()
+                .filter(|item| item.is_match(black_box(&query)))
+                .count()
+        })
+    });
+}
+
+fn feed_parsing(c: &mut Criterion) {
+    let items: Vec<item::CVE> = (0..1000).map(|i| cve(&format!("product{}", i))).collect();
+    let feed = json!({ "CVE_Items": items }).to_string();
+
+    c.bench_function("parsing a feed of 1000 cves", |b| {
+        b.iter(|| black_box(serde_json::from_str::<list::List>(&feed).unwrap()))
+    });
+}
+
+/// End to end search, only run when `DATABASE_URL` points to a database with some data
+/// imported, for instance from the fixtures generator.
+fn search(c: &mut Criterion) {
+    let pool = match env::var("DATABASE_URL") {
+        Ok(url) => db::setup(&url).expect("could not connect to the database"),
+        Err(_) => {
+            eprintln!("DATABASE_URL not set, skipping the search benchmarks");
+            return;
+        }
+    };
+    let database = Database(pool.get().unwrap());
+
+    // search results are cached, every iteration queries a different patch version so that
+    // the cold path is measured
+    let mut patch = 0;
+    c.bench_function("search", |b| {
+        b.iter(|| {
+            patch += 1;
+            let queries: Vec<Query> = ["libxml2", "openssl", "busybox"]
+                .iter()
+                .map(|product| Query {
+                    vendor: None,
+                    product: product.to_string(),
+                    version: Some(format!("1.0.{}", patch)),
+                    target_sw: None,
+                    part: None,
+                    distro: None,
+                    os: None,
+                    update: None,
+                    edition: None,
+                    strictness: None,
+                })
+                .collect();
+            black_box(search::query_all(&database, &queries).unwrap())
+        })
+    });
+}
+
+criterion_group!(
+    benches,
+    cpe_parsing,
+    version_comparison,
+    node_matching,
+    feed_parsing,
+    search
+);
+criterion_main!(benches);
@@ -0,0 +1,377 @@
+//! Requests and responses of the kepler APIs.
+
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+/// Search query for the CVEs affecting a product.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Hash, Clone, Default)]
+pub struct Query {
+    pub vendor: Option<String>,
+    pub product: String,
+    pub version: Option<String>,
+    /// Software the product runs on, like `wordpress` for its plugins, required to match the
+    /// CPEs specifying it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target_sw: Option<String>,
+    /// Part of the CPEs to match, `a` for the applications, `o` for the operating systems and
+    /// the firmware, and `h` for the hardware, any of them if missing.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub part: Option<String>,
+    /// Distribution release of the package, like `alpine:3.19` or `debian:11`, to match the
+    /// fixes backported by the distribution rather than the upstream versions.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub distro: Option<String>,
+    /// Operating system the product runs on, given by a CPE like `o:linux:linux_kernel:5.10`,
+    /// to leave out the CVEs of the product on the other operating systems.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub os: Option<String>,
+    /// Update of the version, like `p1` for `7.4 update p1`, matching the update of the CPEs.
+    /// It can be given in the version too, like `7.4 p1`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub update: Option<String>,
+    /// Edition of the product, like `enterprise`, matching the edition or the software edition
+    /// of the CPEs which specify one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub edition: Option<String>,
+    /// Matching strictness, trading the false positives for the false negatives, `default` if
+    /// missing.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub strictness: Option<Strictness>,
+}
+
+/// Strictness of the matching, trading the false positives for the false negatives.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Hash, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum Strictness {
+    /// Only the CPEs of the versions or version ranges of the product.
+    Strict,
+    /// The CPEs of any version too, and the CVEs without configurations.
+    Default,
+    /// The CPEs of the product on any target software too.
+    Paranoid,
+}
+
+impl Strictness {
+    /// Names of the strictness presets.
+    pub const NAMES: &'static [&'static str] = &["strict", "default", "paranoid"];
+
+    /// Strictness of the query, the default one if missing.
+    pub fn of(query: &Query) -> Self {
+        query.strictness.unwrap_or_default()
+    }
+
+    /// Whether the CPEs of any version, without a version range, match every version.
+    pub fn allows_unversioned(self) -> bool {
+        self != Strictness::Strict
+    }
+
+    /// Whether the CVEs without configurations match through the products and the versions
+    /// mentioned in their summary.
+    pub fn allows_incomplete(self) -> bool {
+        self != Strictness::Strict
+    }
+
+    /// Whether the CPEs of a target software match the product on any target software, like
+    /// the `node.js` CPEs of `tar` matching the GNU `tar`.
+    pub fn allows_fuzzy(self) -> bool {
+        self == Strictness::Paranoid
+    }
+}
+
+impl Default for Strictness {
+    fn default() -> Self {
+        Strictness::Default
+    }
+}
+
+impl FromStr for Strictness {
+    type Err = String;
+
+    fn from_str(val: &str) -> Result<Self, Self::Err> {
+        match val {
+            "strict" => Ok(Strictness::Strict),
+            "default" => Ok(Strictness::Default),
+            "paranoid" => Ok(Strictness::Paranoid),
+            _ => Err(format!(
+                "invalid strictness {}, must be one of {}",
+                val,
+                Strictness::NAMES.join(", ")
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+pub struct Product {
+    pub vendor: String,
+    pub product: String,
+    /// Software the product runs on, according to the CPEs of its records.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub target_sw: Vec<String>,
+    /// Name to show for the product, like `Siemens SIMATIC S7-1200` for
+    /// `simatic_s7-1200_firmware`, when set by an operator.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub display_name: Option<DisplayName>,
+    /// Name to show for the vendor, when set by an operator.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vendor_display_name: Option<DisplayName>,
+}
+
+/// Human readable name of a vendor or a product, along with its translations.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+pub struct DisplayName {
+    pub name: String,
+    /// Names by language tag, like `ja` or `pt-BR`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub localized: BTreeMap<String, String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Default)]
+pub struct Reference {
+    pub url: String,
+    pub tags: Vec<String>,
+}
+
+pub type References = Vec<Reference>;
+
+/// Whether a CVE is in the CISA catalog of known exploited vulnerabilities, along with the
+/// date by which it must be remediated and the action required.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+pub struct Exploitation {
+    #[serde(default)]
+    pub known_exploited: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub due_date: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub required_action: Option<String>,
+}
+
+/// Probability of a CVE being exploited in the next 30 days according to the FIRST EPSS
+/// model, along with its percentile among all the CVEs, when scored.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+pub struct Epss {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub epss_score: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub epss_percentile: Option<f64>,
+}
+
+/// Whether a public exploit of a CVE is archived by Exploit-DB, along with the URLs of the
+/// exploits.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+pub struct Exploits {
+    #[serde(default)]
+    pub exploit_available: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub exploits: Vec<String>,
+}
+
+/// Whether a Metasploit module exploits a CVE, along with the full names of the modules.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+pub struct Metasploit {
+    #[serde(default)]
+    pub metasploit_module: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub metasploit_modules: Vec<String>,
+}
+
+/// Weakness of a CVE by its CWE id, like `CWE-787`, along with its name in the CWE catalog
+/// when imported.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+pub struct Weakness {
+    pub id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+/// Weaknesses of a CVE according to the NVD.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+pub struct Weaknesses {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub weaknesses: Vec<Weakness>,
+}
+
+/// A CVE as returned by the v1 API, one record for every product it affects.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+pub struct Cve {
+    pub source: String,
+    pub vendor: String,
+    pub product: String,
+    pub cve: String,
+    pub summary: String,
+    pub score: f64,
+    pub severity: String,
+    pub vector: Option<String>,
+    pub references: References,
+    /// Score recalibrated by the scoring profile, when one is applied.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub adjusted_score: Option<f64>,
+    #[serde(flatten)]
+    pub exploitation: Exploitation,
+    #[serde(flatten)]
+    pub epss: Epss,
+    #[serde(flatten)]
+    pub exploits: Exploits,
+    #[serde(flatten)]
+    pub metasploit: Metasploit,
+    #[serde(flatten)]
+    pub weaknesses: Weaknesses,
+}
+
+/// Record that a CVE has been matched for a product by a source.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+pub struct Provenance {
+    pub source: String,
+    pub vendor: String,
+    pub product: String,
+}
+
+/// A CVE as returned by the v2 API, with all the records matching it merged together.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+pub struct Vulnerability {
+    pub id: String,
+    pub summary: String,
+    pub score: f64,
+    pub severity: String,
+    pub vector: Option<String>,
+    pub references: References,
+    pub provenance: Vec<Provenance>,
+    /// Score recalibrated by the scoring profile, when one is applied.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub adjusted_score: Option<f64>,
+    #[serde(flatten)]
+    pub exploitation: Exploitation,
+    #[serde(flatten)]
+    pub epss: Epss,
+    #[serde(flatten)]
+    pub exploits: Exploits,
+    #[serde(flatten)]
+    pub metasploit: Metasploit,
+    #[serde(flatten)]
+    pub weaknesses: Weaknesses,
+}
+
+/// v2 response of the search API.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+pub struct SearchResult {
+    pub query: Query,
+    pub vulnerabilities: Vec<Vulnerability>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Product, Query, SearchResult, Strictness};
+
+    #[test]
+    fn can_round_trip_search_results() {
+        let json = r#"{
+            "query": { "vendor": null, "product": "libxml2", "version": "2.9.10" },
+            "vulnerabilities": [
+                {
+                    "id": "CVE-2021-3517",
+                    "summary": "There is a flaw in libxml2.",
+                    "score": 8.6,
+                    "severity": "HIGH",
+                    "vector": "NETWORK",
+                    "references": [{ "url": "https://bugzilla.redhat.com", "tags": [] }],
+                    "provenance": [{ "source": "nist", "vendor": "xmlsoft", "product": "libxml2" }],
+                    "known_exploited": true,
+                    "due_date": "2022-05-03",
+                    "required_action": "Apply updates per vendor instructions.",
+                    "epss_score": 0.97,
+                    "epss_percentile": 0.99,
+                    "exploit_available": true,
+                    "exploits": ["https://www.exploit-db.com/exploits/50592"],
+                    "metasploit_module": true,
+                    "metasploit_modules": ["exploit/multi/http/log4shell_header_injection"],
+                    "weaknesses": [{ "id": "CWE-502", "name": "Deserialization of Untrusted Data" }]
+                }
+            ]
+        }"#;
+
+        let result: SearchResult = serde_json::from_str(json).unwrap();
+        assert!(result.vulnerabilities[0].exploitation.known_exploited);
+        assert_eq!(
+            Some("2022-05-03"),
+            result.vulnerabilities[0].exploitation.due_date.as_deref()
+        );
+        assert_eq!(Some(0.97), result.vulnerabilities[0].epss.epss_score);
+        assert!(result.vulnerabilities[0].exploits.exploit_available);
+        assert!(result.vulnerabilities[0].metasploit.metasploit_module);
+        assert_eq!(
+            "CWE-502",
+            result.vulnerabilities[0].weaknesses.weaknesses[0].id
+        );
+        assert_eq!(
+            Query {
+                vendor: None,
+                product: "libxml2".into(),
+                version: Some("2.9.10".into()),
+                ..Default::default()
+            },
+            result.query
+        );
+
+        let again: SearchResult =
+            serde_json::from_str(&serde_json::to_string(&result).unwrap()).unwrap();
+        assert_eq!(result, again);
+    }
+
+    #[test]
+    fn query_vendor_and_version_are_optional() {
+        let query: Query = serde_json::from_str(r#"{"product": "libxml2"}"#).unwrap();
+        assert_eq!(None, query.vendor);
+        assert_eq!(None, query.version);
+    }
+
+    #[test]
+    fn can_name_products() {
+        let product: Product = serde_json::from_str(
+            r#"{
+                "vendor": "siemens",
+                "product": "simatic_s7-1200_firmware",
+                "display_name": { "name": "Siemens SIMATIC S7-1200", "localized": { "ja": "シーメンス SIMATIC S7-1200" } }
+            }"#,
+        )
+        .unwrap();
+        let name = product.display_name.unwrap();
+        assert_eq!("Siemens SIMATIC S7-1200", name.name);
+        assert_eq!("シーメンス SIMATIC S7-1200", name.localized["ja"]);
+        assert_eq!(None, product.vendor_display_name);
+
+        let product = Product {
+            vendor: "xmlsoft".into(),
+            product: "libxml2".into(),
+            target_sw: vec![],
+            display_name: None,
+            vendor_display_name: None,
+        };
+        assert_eq!(
+            r#"{"vendor":"xmlsoft","product":"libxml2"}"#,
+            serde_json::to_string(&product).unwrap()
+        );
+    }
+
+    #[test]
+    fn can_parse_strictness_presets() {
+        assert_eq!(Ok(Strictness::Strict), "strict".parse());
+        assert_eq!(Ok(Strictness::Paranoid), "paranoid".parse());
+        assert!("lenient".parse::<Strictness>().is_err());
+
+        let query: Query =
+            serde_json::from_str(r#"{"product": "tar", "strictness": "strict"}"#).unwrap();
+        assert_eq!(Strictness::Strict, Strictness::of(&query));
+        assert_eq!(Strictness::Default, Strictness::of(&Query::default()));
+        assert!(
+            serde_json::from_str::<Query>(r#"{"product": "tar", "strictness": "lenient"}"#)
+                .is_err()
+        );
+
+        assert!(!Strictness::Strict.allows_unversioned());
+        assert!(Strictness::Default.allows_incomplete());
+        assert!(!Strictness::Default.allows_fuzzy());
+        assert!(Strictness::Paranoid.allows_fuzzy());
+    }
+}
@@ -0,0 +1,8 @@
+//! Serde models shared by kepler and the tools talking to it: the [`api`] requests and
+//! responses and the [`nvd`] data feed records.
+//!
+//! This crate has no dependencies besides serde so that it can be used by clients, including
+//! WASM builds, without pulling in the database and web server stacks.
+
+pub mod api;
+pub mod nvd;
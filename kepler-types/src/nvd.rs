@@ -0,0 +1,240 @@
+//! Records of the NVD JSON 1.1 data feeds.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Default, Serialize, Deserialize, Clone)]
+pub struct Meta {
+    #[serde(rename = "ID")]
+    pub id: String,
+    #[serde(rename = "ASSIGNER")]
+    pub assigner: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, Clone)]
+pub struct Reference {
+    pub url: String,
+    pub name: String,
+    pub tags: Vec<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, Clone)]
+pub struct References {
+    pub reference_data: Vec<Reference>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, Clone)]
+pub struct DescriptionData {
+    pub lang: String,
+    pub value: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, Clone)]
+pub struct Description {
+    pub description_data: Vec<DescriptionData>,
+}
+
+/// Weaknesses of a CVE, like `CWE-787`.
+#[derive(Debug, Default, Serialize, Deserialize, Clone)]
+pub struct ProblemType {
+    pub problemtype_data: Vec<ProblemTypeData>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, Clone)]
+pub struct ProblemTypeData {
+    pub description: Vec<DescriptionData>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, Clone)]
+pub struct Info {
+    #[serde(rename = "CVE_data_meta")]
+    pub meta: Meta,
+    #[serde(default)]
+    pub problemtype: ProblemType,
+    pub references: References,
+    pub description: Description,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, Clone)]
+pub struct CVSSV2 {
+    pub version: String,
+    #[serde(rename = "vectorString")]
+    pub vector_string: String,
+    #[serde(rename = "accessVector")]
+    pub access_vector: String,
+    #[serde(rename = "accessComplexity")]
+    pub access_complexity: String,
+    pub authentication: String,
+    #[serde(rename = "confidentialityImpact")]
+    pub confidentiality_impact: String,
+    #[serde(rename = "integrityImpact")]
+    pub integrity_impact: String,
+    #[serde(rename = "availabilityImpact")]
+    pub availability_impact: String,
+    #[serde(rename = "baseScore")]
+    pub base_score: f64,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, Clone)]
+pub struct CVSSV3 {
+    pub version: String,
+    #[serde(rename = "vectorString")]
+    pub vector_string: String,
+    #[serde(rename = "attackVector")]
+    pub attack_vector: String,
+    #[serde(rename = "attackComplexity")]
+    pub attack_complexity: String,
+    #[serde(rename = "privilegesRequired")]
+    pub privileges_required: String,
+    #[serde(rename = "userInteraction")]
+    pub user_interaction: String,
+    pub scope: String,
+    #[serde(rename = "confidentialityImpact")]
+    pub confidentiality_impact: String,
+    #[serde(rename = "integrityImpact")]
+    pub integrity_impact: String,
+    #[serde(rename = "availabilityImpact")]
+    pub availability_impact: String,
+    #[serde(rename = "baseScore")]
+    pub base_score: f64,
+    #[serde(rename = "baseSeverity")]
+    pub base_severity: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, Clone)]
+pub struct ImpactMetricV2 {
+    #[serde(rename = "cvssV2")]
+    pub cvss: CVSSV2,
+    #[serde(rename = "exploitabilityScore")]
+    pub exploitability_score: f32,
+    #[serde(rename = "impactScore")]
+    pub impact_score: f32,
+    pub severity: String,
+    #[serde(rename = "acInsufInfo")]
+    pub ac_insuf_info: Option<bool>,
+    #[serde(rename = "obtainAllPrivilege")]
+    pub obtain_all_privilege: bool,
+    #[serde(rename = "obtainUserPrivilege")]
+    pub obtain_user_privilege: bool,
+    #[serde(rename = "obtainOtherPrivilege")]
+    pub obtain_other_privilege: bool,
+    #[serde(rename = "userInteractionRequired")]
+    pub user_interaction_required: Option<bool>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, Clone)]
+pub struct ImpactMetricV3 {
+    #[serde(rename = "cvssV3")]
+    pub cvss: CVSSV3,
+    #[serde(rename = "exploitabilityScore")]
+    pub exploitability_score: f32,
+    #[serde(rename = "impactScore")]
+    pub impact_score: f32,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, Clone)]
+pub struct Impact {
+    // TODO: Implement V1?
+    #[serde(rename = "baseMetricV2")]
+    pub metric_v2: Option<ImpactMetricV2>,
+    #[serde(rename = "baseMetricV3")]
+    pub metric_v3: Option<ImpactMetricV3>,
+}
+
+impl Impact {
+    pub fn score(&self) -> f64 {
+        if let Some(metric) = &self.metric_v2 {
+            return metric.cvss.base_score;
+        } else if let Some(metric) = &self.metric_v3 {
+            return metric.cvss.base_score;
+        }
+        0.0
+    }
+
+    pub fn severity(&self) -> &str {
+        if let Some(metric) = &self.metric_v2 {
+            return &metric.severity;
+        } else if let Some(metric) = &self.metric_v3 {
+            return &metric.cvss.base_severity;
+        }
+        ""
+    }
+
+    pub fn vector(&self) -> &str {
+        if let Some(metric) = &self.metric_v2 {
+            return &metric.cvss.access_vector;
+        } else if let Some(metric) = &self.metric_v3 {
+            return &metric.cvss.attack_vector;
+        }
+        ""
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Impact, Info};
+
+    #[test]
+    fn can_round_trip_info() {
+        let json = r#"{
+            "CVE_data_meta": { "ID": "CVE-2021-3517", "ASSIGNER": "secalert@redhat.com" },
+            "references": {
+                "reference_data": [
+                    {
+                        "url": "https://bugzilla.redhat.com/show_bug.cgi?id=1950515",
+                        "name": "https://bugzilla.redhat.com/show_bug.cgi?id=1950515",
+                        "tags": ["Issue Tracking"]
+                    }
+                ]
+            },
+            "description": {
+                "description_data": [{ "lang": "en", "value": "There is a flaw in libxml2." }]
+            }
+        }"#;
+
+        let info: Info = serde_json::from_str(json).unwrap();
+        assert_eq!("CVE-2021-3517", info.meta.id);
+        assert_eq!(1, info.references.reference_data.len());
+
+        let again: Info = serde_json::from_str(&serde_json::to_string(&info).unwrap()).unwrap();
+        assert_eq!(info.meta.id, again.meta.id);
+        assert_eq!(info.meta.assigner, again.meta.assigner);
+        assert_eq!(
+            info.description.description_data[0].value,
+            again.description.description_data[0].value
+        );
+    }
+
+    #[test]
+    fn can_round_trip_impact() {
+        let json = r#"{
+            "baseMetricV3": {
+                "cvssV3": {
+                    "version": "3.1",
+                    "vectorString": "CVSS:3.1/AV:N/AC:L/PR:N/UI:R/S:U/C:H/I:H/A:H",
+                    "attackVector": "NETWORK",
+                    "attackComplexity": "LOW",
+                    "privilegesRequired": "NONE",
+                    "userInteraction": "REQUIRED",
+                    "scope": "UNCHANGED",
+                    "confidentialityImpact": "HIGH",
+                    "integrityImpact": "HIGH",
+                    "availabilityImpact": "HIGH",
+                    "baseScore": 8.8,
+                    "baseSeverity": "HIGH"
+                },
+                "exploitabilityScore": 2.8,
+                "impactScore": 5.9
+            }
+        }"#;
+
+        let impact: Impact = serde_json::from_str(json).unwrap();
+        assert_eq!(8.8, impact.score());
+        assert_eq!("HIGH", impact.severity());
+        assert_eq!("NETWORK", impact.vector());
+
+        let again: Impact = serde_json::from_str(&serde_json::to_string(&impact).unwrap()).unwrap();
+        assert_eq!(impact.score(), again.score());
+        assert_eq!(impact.severity(), again.severity());
+        assert!(again.metric_v2.is_none());
+    }
+}
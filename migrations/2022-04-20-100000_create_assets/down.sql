@@ -0,0 +1 @@
+DROP TABLE assets;
\ No newline at end of file
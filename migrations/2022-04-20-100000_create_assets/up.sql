@@ -0,0 +1,11 @@
+CREATE TABLE assets (
+    id SERIAL PRIMARY KEY,
+    created_at timestamp without time zone NOT NULL,
+    updated_at timestamp without time zone,
+    name text NOT NULL,
+    kind text NOT NULL,
+    format text NOT NULL,
+    components JSONB NOT NULL
+);
+
+CREATE INDEX assets_name ON assets USING btree (name);
@@ -0,0 +1 @@
+DROP TABLE findings;
\ No newline at end of file
@@ -0,0 +1,14 @@
+CREATE TABLE findings (
+    id SERIAL PRIMARY KEY,
+    created_at timestamp without time zone NOT NULL,
+    asset_id integer NOT NULL REFERENCES assets (id) ON DELETE CASCADE,
+    source text NOT NULL,
+    vendor text NOT NULL,
+    product text NOT NULL,
+    cve text NOT NULL,
+    score double precision NOT NULL,
+    severity text NOT NULL,
+    UNIQUE (asset_id, source, vendor, product, cve)
+);
+
+CREATE INDEX findings_asset_id ON findings USING btree (asset_id);
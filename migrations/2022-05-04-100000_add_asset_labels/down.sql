@@ -0,0 +1 @@
+ALTER TABLE assets DROP COLUMN labels;
\ No newline at end of file
@@ -0,0 +1 @@
+ALTER TABLE assets ADD COLUMN labels JSONB NOT NULL DEFAULT '{}';
@@ -0,0 +1,8 @@
+CREATE TABLE releases (
+    id SERIAL PRIMARY KEY,
+    created_at timestamp without time zone NOT NULL,
+    asset_id integer NOT NULL REFERENCES assets (id) ON DELETE CASCADE,
+    version text NOT NULL,
+    components JSONB NOT NULL,
+    UNIQUE (asset_id, version)
+);
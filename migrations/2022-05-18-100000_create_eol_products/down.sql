@@ -0,0 +1 @@
+DROP TABLE eol_products;
\ No newline at end of file
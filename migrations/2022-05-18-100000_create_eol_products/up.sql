@@ -0,0 +1,7 @@
+CREATE TABLE eol_products (
+    id SERIAL PRIMARY KEY,
+    created_at timestamp without time zone NOT NULL,
+    updated_at timestamp without time zone,
+    product text NOT NULL UNIQUE,
+    cycles JSONB NOT NULL
+);
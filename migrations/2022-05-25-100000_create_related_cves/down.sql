@@ -0,0 +1 @@
+DROP TABLE related_cves;
\ No newline at end of file
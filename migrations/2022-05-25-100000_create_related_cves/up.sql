@@ -0,0 +1,9 @@
+CREATE TABLE related_cves (
+    id SERIAL PRIMARY KEY,
+    created_at timestamp without time zone NOT NULL,
+    cve text NOT NULL,
+    related text NOT NULL,
+    score double precision NOT NULL
+);
+
+CREATE INDEX related_cves_cve ON related_cves USING btree (cve);
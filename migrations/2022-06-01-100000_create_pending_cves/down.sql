@@ -0,0 +1 @@
+DROP TABLE pending_cves;
\ No newline at end of file
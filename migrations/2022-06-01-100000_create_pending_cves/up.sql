@@ -0,0 +1,9 @@
+CREATE TABLE pending_cves (
+    id SERIAL PRIMARY KEY,
+    created_at timestamp without time zone NOT NULL,
+    reconciled_at timestamp without time zone,
+    cve text NOT NULL UNIQUE,
+    summary text NOT NULL
+);
+
+CREATE INDEX pending_cves_reconciled_at ON pending_cves USING btree (reconciled_at);
@@ -0,0 +1,4 @@
+DROP TRIGGER cves_log_change ON cves;
+DROP FUNCTION log_cve_change();
+DROP INDEX cves_cve;
+DROP TABLE cve_changes;
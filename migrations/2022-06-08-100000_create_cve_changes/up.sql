@@ -0,0 +1,42 @@
+CREATE TABLE cve_changes (
+    id SERIAL PRIMARY KEY,
+    created_at timestamp without time zone NOT NULL,
+    cve text NOT NULL,
+    records_before integer NOT NULL,
+    records_after integer NOT NULL
+);
+
+CREATE INDEX cve_changes_created_at ON cve_changes USING btree (created_at);
+CREATE INDEX cves_cve ON cves USING btree (cve);
+
+-- every change of the records is logged with the number of records of the CVE before and
+-- after it, the id of the last change being the revision of the dataset
+CREATE FUNCTION log_cve_change() RETURNS trigger AS $$
+DECLARE
+    records integer;
+BEGIN
+    IF TG_OP = 'DELETE' THEN
+        SELECT count(*) INTO records FROM cves WHERE cve = OLD.cve;
+        INSERT INTO cve_changes (created_at, cve, records_before, records_after)
+            VALUES (timezone('utc', now()), OLD.cve, records, records - 1);
+        RETURN OLD;
+    END IF;
+
+    SELECT count(*) INTO records FROM cves WHERE cve = NEW.cve;
+    IF TG_OP = 'INSERT' THEN
+        INSERT INTO cve_changes (created_at, cve, records_before, records_after)
+            VALUES (timezone('utc', now()), NEW.cve, records, records + 1);
+    ELSE
+        INSERT INTO cve_changes (created_at, cve, records_before, records_after)
+            VALUES (timezone('utc', now()), NEW.cve, records, records);
+    END IF;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER cves_log_change BEFORE INSERT OR UPDATE OR DELETE ON cves
+    FOR EACH ROW EXECUTE PROCEDURE log_cve_change();
+
+-- the records imported so far are the first revision
+INSERT INTO cve_changes (created_at, cve, records_before, records_after)
+    SELECT timezone('utc', now()), cve, 0, count(*) FROM cves GROUP BY cve ORDER BY cve;
@@ -0,0 +1,25 @@
+CREATE OR REPLACE FUNCTION log_cve_change() RETURNS trigger AS $$
+DECLARE
+    records integer;
+BEGIN
+    IF TG_OP = 'DELETE' THEN
+        SELECT count(*) INTO records FROM cves WHERE cve = OLD.cve;
+        INSERT INTO cve_changes (created_at, cve, records_before, records_after)
+            VALUES (timezone('utc', now()), OLD.cve, records, records - 1);
+        RETURN OLD;
+    END IF;
+
+    SELECT count(*) INTO records FROM cves WHERE cve = NEW.cve;
+    IF TG_OP = 'INSERT' THEN
+        INSERT INTO cve_changes (created_at, cve, records_before, records_after)
+            VALUES (timezone('utc', now()), NEW.cve, records, records + 1);
+    ELSE
+        INSERT INTO cve_changes (created_at, cve, records_before, records_after)
+            VALUES (timezone('utc', now()), NEW.cve, records, records);
+    END IF;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TABLE product_revisions;
+DROP TABLE cve_revisions;
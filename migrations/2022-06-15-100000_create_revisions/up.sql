@@ -0,0 +1,66 @@
+CREATE TABLE cve_revisions (
+    cve text PRIMARY KEY,
+    revision integer NOT NULL,
+    records integer NOT NULL
+);
+
+CREATE TABLE product_revisions (
+    vendor text NOT NULL,
+    product text NOT NULL,
+    revision integer NOT NULL,
+    records integer NOT NULL,
+    PRIMARY KEY (vendor, product)
+);
+
+-- along with the change, the revision that last changed the CVE and the product is kept
+-- with their number of records, outliving the pruning of the changes
+CREATE OR REPLACE FUNCTION log_cve_change() RETURNS trigger AS $$
+DECLARE
+    changed cves%ROWTYPE;
+    delta integer;
+    num_records integer;
+    change_id integer;
+BEGIN
+    IF TG_OP = 'DELETE' THEN
+        changed := OLD;
+        delta := -1;
+    ELSIF TG_OP = 'INSERT' THEN
+        changed := NEW;
+        delta := 1;
+    ELSE
+        changed := NEW;
+        delta := 0;
+    END IF;
+
+    SELECT count(*) INTO num_records FROM cves WHERE cve = changed.cve;
+    INSERT INTO cve_changes (created_at, cve, records_before, records_after)
+        VALUES (timezone('utc', now()), changed.cve, num_records, num_records + delta)
+        RETURNING id INTO change_id;
+
+    INSERT INTO cve_revisions (cve, revision, records)
+        VALUES (changed.cve, change_id, num_records + delta)
+        ON CONFLICT (cve) DO UPDATE
+        SET revision = EXCLUDED.revision, records = EXCLUDED.records;
+    INSERT INTO product_revisions (vendor, product, revision, records)
+        VALUES (changed.vendor, changed.product, change_id, greatest(delta, 0))
+        ON CONFLICT (vendor, product) DO UPDATE
+        SET revision = EXCLUDED.revision, records = product_revisions.records + delta;
+
+    IF TG_OP = 'DELETE' THEN
+        RETURN OLD;
+    END IF;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+-- the CVEs logged so far, the products taking the latest change of their CVEs
+INSERT INTO cve_revisions (cve, revision, records)
+    SELECT cve, max(id), 0 FROM cve_changes GROUP BY cve;
+UPDATE cve_revisions SET records = counted.records
+    FROM (SELECT cve, count(*) AS records FROM cves GROUP BY cve) counted
+    WHERE cve_revisions.cve = counted.cve;
+
+INSERT INTO product_revisions (vendor, product, revision, records)
+    SELECT cves.vendor, cves.product, max(cve_revisions.revision), count(*)
+    FROM cves JOIN cve_revisions ON cve_revisions.cve = cves.cve
+    GROUP BY cves.vendor, cves.product;
@@ -0,0 +1,11 @@
+CREATE TABLE idempotency_keys (
+    id SERIAL PRIMARY KEY,
+    created_at timestamp without time zone NOT NULL,
+    key text NOT NULL UNIQUE,
+    fingerprint text NOT NULL,
+    status integer,
+    headers JSONB,
+    body bytea
+);
+
+CREATE INDEX idempotency_keys_created_at ON idempotency_keys USING btree (created_at);
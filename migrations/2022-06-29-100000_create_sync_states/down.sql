@@ -0,0 +1 @@
+DROP TABLE sync_states;
\ No newline at end of file
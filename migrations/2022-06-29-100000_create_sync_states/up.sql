@@ -0,0 +1,7 @@
+CREATE TABLE sync_states (
+    id SERIAL PRIMARY KEY,
+    created_at timestamp without time zone NOT NULL,
+    updated_at timestamp without time zone,
+    source text NOT NULL UNIQUE,
+    synced_at timestamp without time zone NOT NULL
+);
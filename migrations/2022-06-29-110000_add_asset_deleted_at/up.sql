@@ -0,0 +1,4 @@
+-- deleted assets are kept until purged, so that they can be restored
+ALTER TABLE assets ADD COLUMN deleted_at timestamp without time zone;
+
+CREATE INDEX assets_deleted_at ON assets USING btree (deleted_at);
@@ -0,0 +1 @@
+DROP TABLE advisory_aliases;
\ No newline at end of file
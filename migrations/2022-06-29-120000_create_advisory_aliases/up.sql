@@ -0,0 +1,8 @@
+CREATE TABLE advisory_aliases (
+    id SERIAL PRIMARY KEY,
+    created_at timestamp without time zone NOT NULL,
+    advisory text NOT NULL UNIQUE,
+    cve text NOT NULL
+);
+
+CREATE INDEX advisory_aliases_cve ON advisory_aliases USING btree (cve);
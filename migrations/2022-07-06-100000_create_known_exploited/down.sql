@@ -0,0 +1 @@
+DROP TABLE known_exploited;
\ No newline at end of file
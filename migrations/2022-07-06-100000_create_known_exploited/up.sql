@@ -0,0 +1,13 @@
+CREATE TABLE known_exploited (
+    id SERIAL PRIMARY KEY,
+    created_at timestamp without time zone NOT NULL,
+    updated_at timestamp without time zone,
+    cve text NOT NULL UNIQUE,
+    vendor text NOT NULL,
+    product text NOT NULL,
+    name text NOT NULL,
+    date_added text NOT NULL,
+    due_date text NOT NULL,
+    required_action text NOT NULL,
+    ransomware boolean NOT NULL
+);
@@ -0,0 +1 @@
+DROP TABLE epss_scores;
\ No newline at end of file
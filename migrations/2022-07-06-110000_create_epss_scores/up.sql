@@ -0,0 +1,8 @@
+CREATE TABLE epss_scores (
+    id SERIAL PRIMARY KEY,
+    created_at timestamp without time zone NOT NULL,
+    cve text NOT NULL UNIQUE,
+    score double precision NOT NULL,
+    percentile double precision NOT NULL,
+    score_date text NOT NULL
+);
@@ -0,0 +1 @@
+DROP TABLE exploits;
\ No newline at end of file
@@ -0,0 +1,11 @@
+CREATE TABLE exploits (
+    id SERIAL PRIMARY KEY,
+    created_at timestamp without time zone NOT NULL,
+    edb_id integer NOT NULL,
+    cve text NOT NULL,
+    description text NOT NULL,
+    published text NOT NULL,
+    UNIQUE (edb_id, cve)
+);
+
+CREATE INDEX exploits_cve_idx ON exploits (cve);
@@ -0,0 +1 @@
+DROP TABLE metasploit_modules;
\ No newline at end of file
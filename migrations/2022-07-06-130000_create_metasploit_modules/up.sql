@@ -0,0 +1,12 @@
+CREATE TABLE metasploit_modules (
+    id SERIAL PRIMARY KEY,
+    created_at timestamp without time zone NOT NULL,
+    fullname text NOT NULL,
+    cve text NOT NULL,
+    name text NOT NULL,
+    rank integer NOT NULL,
+    disclosure_date text,
+    UNIQUE (fullname, cve)
+);
+
+CREATE INDEX metasploit_modules_cve_idx ON metasploit_modules (cve);
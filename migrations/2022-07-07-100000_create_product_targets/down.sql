@@ -0,0 +1 @@
+DROP TABLE product_targets;
\ No newline at end of file
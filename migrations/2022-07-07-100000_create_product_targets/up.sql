@@ -0,0 +1,8 @@
+CREATE TABLE product_targets (
+    id SERIAL PRIMARY KEY,
+    created_at timestamp without time zone NOT NULL,
+    vendor text NOT NULL,
+    product text NOT NULL,
+    target_sw text NOT NULL,
+    UNIQUE (vendor, product, target_sw)
+);
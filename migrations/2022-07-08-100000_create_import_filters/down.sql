@@ -0,0 +1 @@
+DROP TABLE import_filters;
\ No newline at end of file
@@ -0,0 +1,8 @@
+CREATE TABLE import_filters (
+    id SERIAL PRIMARY KEY,
+    created_at timestamp without time zone NOT NULL,
+    updated_at timestamp without time zone,
+    command text NOT NULL UNIQUE,
+    allowlist text,
+    skipped int8 NOT NULL
+);
@@ -0,0 +1 @@
+ALTER TABLE import_filters DROP COLUMN since_year;
\ No newline at end of file
@@ -0,0 +1,2 @@
+-- the NIST imports can skip the CVEs of the years before this one
+ALTER TABLE import_filters ADD COLUMN since_year int4;
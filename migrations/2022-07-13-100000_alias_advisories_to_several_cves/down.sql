@@ -0,0 +1,3 @@
+DELETE FROM advisory_aliases a USING advisory_aliases b WHERE a.advisory = b.advisory AND a.id > b.id;
+ALTER TABLE advisory_aliases DROP CONSTRAINT advisory_aliases_advisory_cve_key;
+ALTER TABLE advisory_aliases ADD CONSTRAINT advisory_aliases_advisory_key UNIQUE (advisory);
@@ -0,0 +1,3 @@
+-- advisories like the JVN iPedia ones are about several CVEs
+ALTER TABLE advisory_aliases DROP CONSTRAINT advisory_aliases_advisory_key;
+ALTER TABLE advisory_aliases ADD CONSTRAINT advisory_aliases_advisory_cve_key UNIQUE (advisory, cve);
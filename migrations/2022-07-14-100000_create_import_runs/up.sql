@@ -0,0 +1,13 @@
+CREATE TABLE import_runs (
+    id SERIAL PRIMARY KEY,
+    started_at timestamp without time zone NOT NULL,
+    finished_at timestamp without time zone,
+    command text NOT NULL,
+    outcome text NOT NULL,
+    records int8 NOT NULL DEFAULT 0,
+    skipped int8 NOT NULL DEFAULT 0,
+    downloads jsonb NOT NULL DEFAULT '[]',
+    error text
+);
+
+CREATE INDEX import_runs_started_at ON import_runs USING btree (started_at);
@@ -0,0 +1 @@
+DROP TABLE cpe_dictionary;
@@ -0,0 +1,16 @@
+CREATE TABLE cpe_dictionary (
+    id SERIAL PRIMARY KEY,
+    cpe_name_id text NOT NULL UNIQUE,
+    cpe text NOT NULL,
+    part text NOT NULL,
+    vendor text NOT NULL,
+    product text NOT NULL,
+    version text NOT NULL,
+    title text NOT NULL,
+    deprecated boolean NOT NULL DEFAULT FALSE,
+    deprecated_by jsonb NOT NULL DEFAULT '[]',
+    refs jsonb NOT NULL DEFAULT '[]',
+    last_modified timestamp without time zone NOT NULL
+);
+
+CREATE INDEX cpe_dictionary_product ON cpe_dictionary USING btree (vendor, product, cpe);
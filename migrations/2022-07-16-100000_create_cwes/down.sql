@@ -0,0 +1,2 @@
+DROP TABLE cve_weaknesses;
+DROP TABLE cwes;
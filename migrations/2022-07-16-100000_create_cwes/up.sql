@@ -0,0 +1,20 @@
+CREATE TABLE cwes (
+    id SERIAL PRIMARY KEY,
+    created_at timestamp without time zone NOT NULL,
+    cwe text NOT NULL UNIQUE,
+    name text NOT NULL,
+    kind text NOT NULL,
+    abstraction text,
+    status text NOT NULL,
+    description text NOT NULL
+);
+
+CREATE TABLE cve_weaknesses (
+    id SERIAL PRIMARY KEY,
+    created_at timestamp without time zone NOT NULL,
+    cve text NOT NULL,
+    cwe text NOT NULL,
+    UNIQUE (cve, cwe)
+);
+
+CREATE INDEX cve_weaknesses_cwe_idx ON cve_weaknesses (cwe);
@@ -0,0 +1 @@
+DROP TABLE attack_patterns;
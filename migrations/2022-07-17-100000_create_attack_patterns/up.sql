@@ -0,0 +1,15 @@
+CREATE TABLE attack_patterns (
+    id SERIAL PRIMARY KEY,
+    created_at timestamp without time zone NOT NULL,
+    capec text NOT NULL,
+    cwe text NOT NULL,
+    name text NOT NULL,
+    abstraction text,
+    status text NOT NULL,
+    severity text,
+    likelihood text,
+    description text NOT NULL,
+    UNIQUE (capec, cwe)
+);
+
+CREATE INDEX attack_patterns_cwe_idx ON attack_patterns (cwe);
@@ -0,0 +1,10 @@
+CREATE TABLE display_names (
+    id SERIAL PRIMARY KEY,
+    created_at timestamp without time zone NOT NULL,
+    updated_at timestamp without time zone,
+    vendor text NOT NULL,
+    product text NOT NULL DEFAULT '',
+    name text NOT NULL,
+    localized jsonb NOT NULL DEFAULT '{}',
+    UNIQUE (vendor, product)
+);
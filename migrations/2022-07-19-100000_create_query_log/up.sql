@@ -0,0 +1,13 @@
+CREATE TABLE query_log (
+    id SERIAL PRIMARY KEY,
+    created_at timestamp without time zone NOT NULL,
+    updated_at timestamp without time zone,
+    day text NOT NULL,
+    vendor text NOT NULL,
+    product text NOT NULL,
+    version text NOT NULL,
+    count bigint NOT NULL,
+    latency_ms bigint NOT NULL,
+    max_latency_ms bigint NOT NULL,
+    UNIQUE (day, vendor, product, version)
+);
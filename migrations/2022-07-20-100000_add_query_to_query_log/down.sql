@@ -0,0 +1,7 @@
+DELETE FROM query_log a USING query_log b
+    WHERE a.id > b.id AND a.day = b.day AND a.vendor = b.vendor AND a.product = b.product
+    AND a.version = b.version;
+ALTER TABLE query_log DROP CONSTRAINT query_log_day_query_key;
+ALTER TABLE query_log ADD CONSTRAINT query_log_day_vendor_product_version_key
+    UNIQUE (day, vendor, product, version);
+ALTER TABLE query_log DROP COLUMN query;
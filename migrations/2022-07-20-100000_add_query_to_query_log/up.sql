@@ -0,0 +1,9 @@
+ALTER TABLE query_log ADD COLUMN query jsonb;
+UPDATE query_log SET query = jsonb_build_object(
+    'vendor', NULLIF(vendor, ''),
+    'product', product,
+    'version', NULLIF(version, '')
+);
+ALTER TABLE query_log ALTER COLUMN query SET NOT NULL;
+ALTER TABLE query_log DROP CONSTRAINT query_log_day_vendor_product_version_key;
+ALTER TABLE query_log ADD CONSTRAINT query_log_day_query_key UNIQUE (day, query);
@@ -0,0 +1,104 @@
+-- the timestamps were stored in UTC without their offset
+ALTER TABLE advisory_aliases
+    ALTER COLUMN created_at TYPE timestamptz USING created_at AT TIME ZONE 'UTC';
+ALTER TABLE assets
+    ALTER COLUMN created_at TYPE timestamptz USING created_at AT TIME ZONE 'UTC',
+    ALTER COLUMN updated_at TYPE timestamptz USING updated_at AT TIME ZONE 'UTC',
+    ALTER COLUMN deleted_at TYPE timestamptz USING deleted_at AT TIME ZONE 'UTC';
+ALTER TABLE attack_patterns
+    ALTER COLUMN created_at TYPE timestamptz USING created_at AT TIME ZONE 'UTC';
+ALTER TABLE cpe_dictionary
+    ALTER COLUMN last_modified TYPE timestamptz USING last_modified AT TIME ZONE 'UTC';
+ALTER TABLE cve_changes
+    ALTER COLUMN created_at TYPE timestamptz USING created_at AT TIME ZONE 'UTC';
+ALTER TABLE cve_weaknesses
+    ALTER COLUMN created_at TYPE timestamptz USING created_at AT TIME ZONE 'UTC';
+ALTER TABLE cves
+    ALTER COLUMN created_at TYPE timestamptz USING created_at AT TIME ZONE 'UTC',
+    ALTER COLUMN updated_at TYPE timestamptz USING updated_at AT TIME ZONE 'UTC';
+ALTER TABLE cwes
+    ALTER COLUMN created_at TYPE timestamptz USING created_at AT TIME ZONE 'UTC';
+ALTER TABLE display_names
+    ALTER COLUMN created_at TYPE timestamptz USING created_at AT TIME ZONE 'UTC',
+    ALTER COLUMN updated_at TYPE timestamptz USING updated_at AT TIME ZONE 'UTC';
+ALTER TABLE eol_products
+    ALTER COLUMN created_at TYPE timestamptz USING created_at AT TIME ZONE 'UTC',
+    ALTER COLUMN updated_at TYPE timestamptz USING updated_at AT TIME ZONE 'UTC';
+ALTER TABLE epss_scores
+    ALTER COLUMN created_at TYPE timestamptz USING created_at AT TIME ZONE 'UTC';
+ALTER TABLE exploits
+    ALTER COLUMN created_at TYPE timestamptz USING created_at AT TIME ZONE 'UTC';
+ALTER TABLE findings
+    ALTER COLUMN created_at TYPE timestamptz USING created_at AT TIME ZONE 'UTC';
+ALTER TABLE idempotency_keys
+    ALTER COLUMN created_at TYPE timestamptz USING created_at AT TIME ZONE 'UTC';
+ALTER TABLE import_filters
+    ALTER COLUMN created_at TYPE timestamptz USING created_at AT TIME ZONE 'UTC',
+    ALTER COLUMN updated_at TYPE timestamptz USING updated_at AT TIME ZONE 'UTC';
+ALTER TABLE import_runs
+    ALTER COLUMN started_at TYPE timestamptz USING started_at AT TIME ZONE 'UTC',
+    ALTER COLUMN finished_at TYPE timestamptz USING finished_at AT TIME ZONE 'UTC';
+ALTER TABLE known_exploited
+    ALTER COLUMN created_at TYPE timestamptz USING created_at AT TIME ZONE 'UTC',
+    ALTER COLUMN updated_at TYPE timestamptz USING updated_at AT TIME ZONE 'UTC';
+ALTER TABLE metasploit_modules
+    ALTER COLUMN created_at TYPE timestamptz USING created_at AT TIME ZONE 'UTC';
+ALTER TABLE objects
+    ALTER COLUMN created_at TYPE timestamptz USING created_at AT TIME ZONE 'UTC',
+    ALTER COLUMN updated_at TYPE timestamptz USING updated_at AT TIME ZONE 'UTC';
+ALTER TABLE pending_cves
+    ALTER COLUMN created_at TYPE timestamptz USING created_at AT TIME ZONE 'UTC',
+    ALTER COLUMN reconciled_at TYPE timestamptz USING reconciled_at AT TIME ZONE 'UTC';
+ALTER TABLE product_targets
+    ALTER COLUMN created_at TYPE timestamptz USING created_at AT TIME ZONE 'UTC';
+ALTER TABLE query_log
+    ALTER COLUMN created_at TYPE timestamptz USING created_at AT TIME ZONE 'UTC',
+    ALTER COLUMN updated_at TYPE timestamptz USING updated_at AT TIME ZONE 'UTC';
+ALTER TABLE related_cves
+    ALTER COLUMN created_at TYPE timestamptz USING created_at AT TIME ZONE 'UTC';
+ALTER TABLE releases
+    ALTER COLUMN created_at TYPE timestamptz USING created_at AT TIME ZONE 'UTC';
+ALTER TABLE sync_states
+    ALTER COLUMN created_at TYPE timestamptz USING created_at AT TIME ZONE 'UTC',
+    ALTER COLUMN updated_at TYPE timestamptz USING updated_at AT TIME ZONE 'UTC',
+    ALTER COLUMN synced_at TYPE timestamptz USING synced_at AT TIME ZONE 'UTC';
+
+-- the changes are logged at the current time, no longer at its UTC wall clock time
+CREATE OR REPLACE FUNCTION log_cve_change() RETURNS trigger AS $$
+DECLARE
+    changed cves%ROWTYPE;
+    delta integer;
+    num_records integer;
+    change_id integer;
+BEGIN
+    IF TG_OP = 'DELETE' THEN
+        changed := OLD;
+        delta := -1;
+    ELSIF TG_OP = 'INSERT' THEN
+        changed := NEW;
+        delta := 1;
+    ELSE
+        changed := NEW;
+        delta := 0;
+    END IF;
+
+    SELECT count(*) INTO num_records FROM cves WHERE cve = changed.cve;
+    INSERT INTO cve_changes (created_at, cve, records_before, records_after)
+        VALUES (now(), changed.cve, num_records, num_records + delta)
+        RETURNING id INTO change_id;
+
+    INSERT INTO cve_revisions (cve, revision, records)
+        VALUES (changed.cve, change_id, num_records + delta)
+        ON CONFLICT (cve) DO UPDATE
+        SET revision = EXCLUDED.revision, records = EXCLUDED.records;
+    INSERT INTO product_revisions (vendor, product, revision, records)
+        VALUES (changed.vendor, changed.product, change_id, greatest(delta, 0))
+        ON CONFLICT (vendor, product) DO UPDATE
+        SET revision = EXCLUDED.revision, records = product_revisions.records + delta;
+
+    IF TG_OP = 'DELETE' THEN
+        RETURN OLD;
+    END IF;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
@@ -0,0 +1,13 @@
+-- the Red Hat products are named like the ones of the other RPM based distributions,
+-- rhel-8-openssl rather than rhel8-openssl
+UPDATE cves SET product = regexp_replace(product, '^rhel([0-9]+)-', 'rhel-\1-')
+    WHERE vendor = '@redhat' AND product ~ '^rhel[0-9]+-';
+UPDATE findings SET product = regexp_replace(product, '^rhel([0-9]+)-', 'rhel-\1-')
+    WHERE vendor = '@redhat' AND product ~ '^rhel[0-9]+-';
+
+-- the revisions of the renamed products, the trigger only counting the inserted records
+DELETE FROM product_revisions WHERE vendor = '@redhat' AND product ~ '^rhel[0-9]+-';
+UPDATE product_revisions SET records = counted.records
+    FROM (SELECT vendor, product, count(*) AS records FROM cves
+          WHERE vendor = '@redhat' GROUP BY vendor, product) counted
+    WHERE product_revisions.vendor = counted.vendor AND product_revisions.product = counted.product;
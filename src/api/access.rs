@@ -0,0 +1,365 @@
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::env;
+use std::fs;
+use std::future::{ready, Future, Ready};
+use std::net::IpAddr;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::str::FromStr;
+
+use actix_web::{
+    dev::{Service, ServiceRequest, ServiceResponse, Transform},
+    error::ErrorForbidden,
+    Error,
+};
+use serde::Deserialize;
+
+/// IPv4 or IPv6 network in CIDR notation, a plain address is a network with a single host.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(try_from = "String")]
+pub struct Network {
+    addr: IpAddr,
+    prefix: u8,
+}
+
+impl FromStr for Network {
+    type Err = String;
+
+    fn from_str(val: &str) -> Result<Self, Self::Err> {
+        let (addr, prefix) = match val.split_once('/') {
+            Some((addr, prefix)) => (addr, Some(prefix)),
+            None => (val, None),
+        };
+
+        let addr = addr
+            .trim()
+            .parse::<IpAddr>()
+            .map_err(|e| format!("invalid network '{}': {}", val, e))?;
+        let max = if addr.is_ipv4() { 32 } else { 128 };
+        let prefix = match prefix {
+            Some(prefix) => prefix
+                .trim()
+                .parse::<u8>()
+                .ok()
+                .filter(|p| *p <= max)
+                .ok_or_else(|| format!("invalid network '{}': bad prefix length", val))?,
+            None => max,
+        };
+
+        Ok(Self { addr, prefix })
+    }
+}
+
+impl TryFrom<String> for Network {
+    type Error = String;
+
+    fn try_from(val: String) -> Result<Self, Self::Error> {
+        val.parse()
+    }
+}
+
+impl Network {
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        match (self.addr, ip) {
+            (IpAddr::V4(net), IpAddr::V4(ip)) => {
+                let mask = u32::MAX.checked_shl(32 - self.prefix as u32).unwrap_or(0);
+                u32::from(net) & mask == u32::from(ip) & mask
+            }
+            (IpAddr::V6(net), IpAddr::V6(ip)) => {
+                let mask = u128::MAX.checked_shl(128 - self.prefix as u32).unwrap_or(0);
+                u128::from(net) & mask == u128::from(ip) & mask
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Networks of the reverse proxies in front of kepler, listed in `KEPLER_TRUSTED_PROXIES`
+/// (comma separated), whose requests are attributed to the client address they forward.
+#[derive(Debug, Default, Clone)]
+pub struct TrustedProxies(Vec<Network>);
+
+impl TrustedProxies {
+    pub fn new(networks: Vec<Network>) -> Self {
+        Self(networks)
+    }
+
+    pub fn from_env() -> Result<Self, String> {
+        match env::var("KEPLER_TRUSTED_PROXIES") {
+            Ok(proxies) => proxies
+                .split(',')
+                .filter(|p| !p.trim().is_empty())
+                .map(|p| p.parse::<Network>())
+                .collect::<Result<Vec<_>, _>>()
+                .map(Self),
+            Err(_) => Ok(Self::default()),
+        }
+    }
+
+    fn is_trusted(&self, ip: IpAddr) -> bool {
+        self.0.iter().any(|n| n.contains(ip))
+    }
+
+    /// Address of the client, taken from the `X-Forwarded-For` header when the request comes
+    /// from a trusted proxy: the rightmost address not belonging to a trusted proxy is used,
+    /// since the ones on its left can be forged by the client.
+    pub fn client_ip(&self, peer: IpAddr, forwarded_for: Option<&str>) -> IpAddr {
+        if !self.is_trusted(peer) {
+            return peer;
+        }
+
+        forwarded_for
+            .into_iter()
+            .flat_map(|header| header.rsplit(','))
+            .filter_map(|addr| addr.trim().parse::<IpAddr>().ok())
+            .find(|addr| !self.is_trusted(*addr))
+            .unwrap_or(peer)
+    }
+
+    /// Address of the client of the request, unless the peer is unknown.
+    pub fn resolve(&self, req: &ServiceRequest) -> Option<IpAddr> {
+        let forwarded_for = req
+            .headers()
+            .get("X-Forwarded-For")
+            .and_then(|h| h.to_str().ok());
+        req.peer_addr()
+            .map(|peer| self.client_ip(peer.ip(), forwarded_for))
+    }
+}
+
+/// Networks allowed and denied for a route group. Denied networks take precedence, and when
+/// the allowed list is empty every network not denied is allowed.
+#[derive(Debug, Default, Clone, Deserialize)]
+pub struct Rule {
+    #[serde(default)]
+    pub allow: Vec<Network>,
+    #[serde(default)]
+    pub deny: Vec<Network>,
+}
+
+impl Rule {
+    /// Rule denying every network.
+    pub fn deny_all() -> Self {
+        Self {
+            allow: vec![],
+            deny: vec!["0.0.0.0/0".parse().unwrap(), "::/0".parse().unwrap()],
+        }
+    }
+
+    pub fn is_allowed(&self, ip: IpAddr) -> bool {
+        if self.deny.iter().any(|n| n.contains(ip)) {
+            return false;
+        }
+        self.allow.is_empty() || self.allow.iter().any(|n| n.contains(ip))
+    }
+}
+
+/// Groups of routes denied to every network unless a rule applies to them.
+const DENIED_BY_DEFAULT: &[&str] = &["admin"];
+
+/// Access rules by route group (`cve`, `scan`, `export`, `products`, `admin`, `mirror`), the
+/// `default` rule applies to the groups without their own. The `admin` group only follows its
+/// own rule, and is denied without one. The clients are identified like the rate limiter
+/// does, through the trusted proxies.
+#[derive(Debug, Default, Clone)]
+pub struct Rules {
+    groups: HashMap<String, Rule>,
+    proxies: TrustedProxies,
+}
+
+impl Rules {
+    /// Loads the rules from the JSON file pointed by the `KEPLER_ACCESS_RULES` environment
+    /// variable, everything but the `admin` group is allowed when it's not set.
+    pub fn from_env() -> Result<Self, String> {
+        let groups = match env::var("KEPLER_ACCESS_RULES") {
+            Ok(file_name) => fs::read_to_string(&file_name)
+                .map_err(|e| e.to_string())
+                .and_then(|json| serde_json::from_str(&json).map_err(|e| e.to_string()))
+                .map_err(|e| format!("could not load access rules from {}: {}", file_name, e))?,
+            Err(_) => HashMap::new(),
+        };
+
+        Ok(Self {
+            groups,
+            proxies: TrustedProxies::from_env()?,
+        })
+    }
+
+    fn rule(&self, group: &str) -> Option<Rule> {
+        // the default rule is meant for the public routes, it never opens the others
+        if DENIED_BY_DEFAULT.contains(&group) {
+            return Some(
+                self.groups
+                    .get(group)
+                    .cloned()
+                    .unwrap_or_else(Rule::deny_all),
+            );
+        }
+        self.groups
+            .get(group)
+            .or_else(|| self.groups.get("default"))
+            .cloned()
+    }
+
+    /// Middleware enforcing the rule of a route group.
+    pub fn guard(&self, group: &str) -> Access {
+        Access {
+            rule: self.rule(group).map(Rc::new),
+            proxies: self.proxies.clone(),
+        }
+    }
+}
+
+/// Middleware rejecting with `403 Forbidden` the requests from networks not allowed.
+pub struct Access {
+    rule: Option<Rc<Rule>>,
+    proxies: TrustedProxies,
+}
+
+impl<S, B> Transform<S, ServiceRequest> for Access
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Transform = AccessMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(AccessMiddleware {
+            service,
+            rule: self.rule.clone(),
+            proxies: self.proxies.clone(),
+        }))
+    }
+}
+
+pub struct AccessMiddleware<S> {
+    service: S,
+    rule: Option<Rc<Rule>>,
+    proxies: TrustedProxies,
+}
+
+impl<S, B> Service<ServiceRequest> for AccessMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    #[allow(clippy::type_complexity)]
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>>>>;
+
+    actix_web::dev::forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        if let Some(rule) = &self.rule {
+            let client = self.proxies.resolve(&req);
+            let allowed = client.map(|ip| rule.is_allowed(ip)).unwrap_or(false);
+
+            if !allowed {
+                log::warn!("access denied to {:?} for {}", client, req.path());
+                return Box::pin(ready(Err(ErrorForbidden("access denied"))));
+            }
+        }
+
+        Box::pin(self.service.call(req))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::{Network, Rule, Rules, TrustedProxies};
+
+    #[test]
+    fn can_match_networks() {
+        let network: Network = "10.1.0.0/16".parse().unwrap();
+        assert!(network.contains("10.1.2.3".parse().unwrap()));
+        assert!(!network.contains("10.2.0.1".parse().unwrap()));
+        assert!(!network.contains("::1".parse().unwrap()));
+
+        let network: Network = "fd00::/8".parse().unwrap();
+        assert!(network.contains("fd12:3456::1".parse().unwrap()));
+        assert!(!network.contains("fe80::1".parse().unwrap()));
+
+        let host: Network = "192.168.1.10".parse().unwrap();
+        assert!(host.contains("192.168.1.10".parse().unwrap()));
+        assert!(!host.contains("192.168.1.11".parse().unwrap()));
+
+        let any: Network = "0.0.0.0/0".parse().unwrap();
+        assert!(any.contains("8.8.8.8".parse().unwrap()));
+
+        assert!("10.0.0.0/33".parse::<Network>().is_err());
+        assert!("trollololol".parse::<Network>().is_err());
+    }
+
+    #[test]
+    fn deny_takes_precedence() {
+        let rule: Rule =
+            serde_json::from_str(r#"{"allow": ["10.0.0.0/8"], "deny": ["10.66.0.0/16"]}"#).unwrap();
+
+        assert!(rule.is_allowed("10.1.1.1".parse().unwrap()));
+        assert!(!rule.is_allowed("10.66.1.1".parse().unwrap()));
+        assert!(!rule.is_allowed("192.168.1.1".parse().unwrap()));
+
+        let rule: Rule = serde_json::from_str(r#"{"deny": ["192.168.0.0/16"]}"#).unwrap();
+        assert!(rule.is_allowed("10.1.1.1".parse().unwrap()));
+        assert!(!rule.is_allowed("192.168.1.1".parse().unwrap()));
+    }
+
+    #[test]
+    fn admin_is_denied_unless_configured() {
+        let ip = "10.1.1.1".parse().unwrap();
+
+        let rules = Rules::default();
+        assert!(rules.rule("cve").is_none());
+        assert!(!rules.rule("admin").unwrap().is_allowed(ip));
+        assert!(!rules
+            .rule("admin")
+            .unwrap()
+            .is_allowed("::1".parse().unwrap()));
+
+        // a permissive default rule doesn't open the admin routes
+        let mut groups = HashMap::new();
+        groups.insert(
+            "default".to_string(),
+            serde_json::from_str(r#"{"deny": ["192.168.0.0/16"]}"#).unwrap(),
+        );
+        let rules = Rules {
+            groups: groups.clone(),
+            ..Default::default()
+        };
+        assert!(rules.rule("cve").unwrap().is_allowed(ip));
+        assert!(!rules.rule("admin").unwrap().is_allowed(ip));
+
+        groups.insert(
+            "admin".to_string(),
+            serde_json::from_str(r#"{"allow": ["10.0.0.0/8"]}"#).unwrap(),
+        );
+        let rules = Rules {
+            groups,
+            ..Default::default()
+        };
+        assert!(rules.rule("admin").unwrap().is_allowed(ip));
+    }
+
+    #[test]
+    fn can_resolve_client_behind_trusted_proxy() {
+        let proxies = TrustedProxies::new(vec!["10.0.0.0/8".parse().unwrap()]);
+        let proxy = "10.0.0.1".parse().unwrap();
+        let client = "203.0.113.7".parse().unwrap();
+
+        assert_eq!(
+            client,
+            proxies.client_ip(proxy, Some("198.51.100.1, 203.0.113.7, 10.0.0.2"))
+        );
+        assert_eq!(proxy, proxies.client_ip(proxy, None));
+        // the header is ignored when the request doesn't come from a trusted proxy
+        assert_eq!(client, proxies.client_ip(client, Some("198.51.100.1")));
+    }
+}
@@ -0,0 +1,233 @@
+//! Operator endpoints, like the history of the import runs and the display names of the
+//! products.
+
+use std::collections::BTreeMap;
+
+use actix_web::{web, HttpResponse};
+use serde::{Deserialize, Serialize};
+
+use crate::db::models::{ImportRun, Name, NewDisplayName};
+
+use super::{
+    error::ApplicationError,
+    listing, timeout,
+    utils::{bad_request_body, handle_database_error, internal_server_error, ok_to_json},
+    ApplicationContext,
+};
+
+/// Number of import runs listed, unless requested otherwise.
+const DEFAULT_RUNS: i64 = 50;
+const MAX_RUNS: i64 = 500;
+/// Longest display name, in characters.
+const MAX_NAME_LENGTH: usize = 200;
+
+#[derive(Debug, Deserialize)]
+pub struct ImportsParams {
+    /// Only list the runs of this command, like `import_nist`.
+    pub command: Option<String>,
+    /// Id of the last run of the previous page.
+    pub before: Option<i32>,
+    pub limit: Option<i64>,
+}
+
+impl ImportsParams {
+    fn limit(&self) -> Result<i64, String> {
+        match self.limit {
+            None => Ok(DEFAULT_RUNS),
+            Some(limit) if limit > 0 && limit <= MAX_RUNS => Ok(limit),
+            Some(limit) => Err(format!(
+                "invalid limit {}, expected 1 to {}",
+                limit, MAX_RUNS
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct Imports {
+    runs: Vec<ImportRun>,
+    /// Id to request the next page before, unless this is the last one.
+    next: Option<i32>,
+}
+
+/// Lists the import runs, the latest first.
+pub async fn imports(
+    ctx: web::Data<ApplicationContext>,
+    params: web::Query<ImportsParams>,
+) -> Result<HttpResponse, ApplicationError> {
+    let limit = params.limit().map_err(bad_request_body)?;
+    let timeout = ctx.timeouts.default;
+
+    let runs = timeout::block(timeout, move || {
+        ctx.get_database(timeout)
+            .map_err(handle_database_error)?
+            .get_import_runs(params.command.as_deref(), params.before, limit)
+            .map_err(internal_server_error)
+    })
+    .await?;
+
+    let next = if runs.len() as i64 == limit {
+        runs.last().map(|run| run.id)
+    } else {
+        None
+    };
+    Ok(HttpResponse::Ok().json(Imports { runs, next }))
+}
+
+/// Vendor, or product of a vendor, named by an operator.
+#[derive(Debug, Deserialize)]
+pub struct NamePath {
+    vendor: String,
+    product: Option<String>,
+}
+
+impl NamePath {
+    fn id(&self) -> String {
+        match &self.product {
+            Some(product) => listing::product_id(&self.vendor, product),
+            None => self.vendor.clone(),
+        }
+    }
+}
+
+/// Checks the name and its translations, like `ja` or `pt-BR`, trimming them.
+fn validate(name: Name) -> Result<Name, String> {
+    let check = |name: &str| {
+        let name = name.trim();
+        if name.is_empty() || name.chars().count() > MAX_NAME_LENGTH {
+            return Err(format!(
+                "invalid name {:?}, expected 1 to {} characters",
+                name, MAX_NAME_LENGTH
+            ));
+        }
+        Ok(name.to_string())
+    };
+
+    let mut localized = BTreeMap::new();
+    for (tag, translation) in &name.localized {
+        let valid = (2..=35).contains(&tag.len())
+            && tag.split('-').all(|subtag| {
+                !subtag.is_empty() && subtag.chars().all(|c| c.is_ascii_alphanumeric())
+            });
+        if !valid {
+            return Err(format!(
+                "invalid language tag {:?}, expected like pt-BR",
+                tag
+            ));
+        }
+        localized.insert(tag.clone(), check(translation)?);
+    }
+
+    Ok(Name {
+        name: check(&name.name)?,
+        localized,
+    })
+}
+
+/// Lists the display names of the vendors and the products.
+pub async fn display_names(
+    ctx: web::Data<ApplicationContext>,
+) -> Result<HttpResponse, ApplicationError> {
+    let timeout = ctx.timeouts.default;
+
+    timeout::block(timeout, move || {
+        ctx.get_database(timeout)
+            .map_err(handle_database_error)?
+            .get_display_names()
+            .map_err(internal_server_error)
+    })
+    .await
+    .map(ok_to_json)
+}
+
+/// Sets the name shown for a vendor or a product, along with its translations.
+pub async fn set_display_name(
+    ctx: web::Data<ApplicationContext>,
+    path: web::Path<NamePath>,
+    body: web::Bytes,
+) -> Result<HttpResponse, ApplicationError> {
+    let name: Name = ctx.limits.parse_json(&body).map_err(bad_request_body)?;
+    let name = validate(name).map_err(bad_request_body)?;
+    let NamePath { vendor, product } = path.into_inner();
+    let timeout = ctx.timeouts.default;
+
+    timeout::block(timeout, move || {
+        ctx.get_database(timeout)
+            .map_err(handle_database_error)?
+            .set_display_name(NewDisplayName::with(vendor, product, name))
+            .map_err(internal_server_error)
+    })
+    .await
+    .map(ok_to_json)
+}
+
+/// Removes the name shown for a vendor or a product, which falls back to its CPE name.
+pub async fn delete_display_name(
+    ctx: web::Data<ApplicationContext>,
+    path: web::Path<NamePath>,
+) -> Result<HttpResponse, ApplicationError> {
+    let path = path.into_inner();
+    let id = path.id();
+    let timeout = ctx.timeouts.default;
+
+    let deleted = timeout::block(timeout, move || {
+        ctx.get_database(timeout)
+            .map_err(handle_database_error)?
+            .delete_display_name(&path.vendor, path.product.as_deref().unwrap_or_default())
+            .map_err(internal_server_error)
+    })
+    .await?;
+
+    if deleted {
+        Ok(HttpResponse::NoContent().finish())
+    } else {
+        Err(ApplicationError::NotFound(format!(
+            "no display name for {}",
+            id
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{validate, ImportsParams, Name};
+
+    #[test]
+    fn can_validate_limits() {
+        let params = |limit| ImportsParams {
+            command: None,
+            before: None,
+            limit,
+        };
+        assert_eq!(Ok(50), params(None).limit());
+        assert_eq!(Ok(500), params(Some(500)).limit());
+        assert!(params(Some(0)).limit().is_err());
+        assert!(params(Some(501)).limit().is_err());
+    }
+
+    #[test]
+    fn can_validate_names() {
+        let name = |name: &str, localized: &[(&str, &str)]| Name {
+            name: name.into(),
+            localized: localized
+                .iter()
+                .map(|(tag, name)| (tag.to_string(), name.to_string()))
+                .collect(),
+        };
+
+        assert_eq!(
+            Ok(name(
+                "Siemens SIMATIC S7-1200",
+                &[("pt-BR", "SIMATIC S7-1200")]
+            )),
+            validate(name(
+                " Siemens SIMATIC S7-1200 ",
+                &[("pt-BR", "SIMATIC S7-1200 ")]
+            ))
+        );
+        assert!(validate(name(" ", &[])).is_err());
+        assert!(validate(name(&"x".repeat(201), &[])).is_err());
+        assert!(validate(name("SIMATIC", &[("pt_BR", "SIMATIC")])).is_err());
+        assert!(validate(name("SIMATIC", &[("ja", "")])).is_err());
+    }
+}
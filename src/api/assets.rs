@@ -0,0 +1,553 @@
+use std::sync::Arc;
+
+use crate::bus::Event;
+use crate::db::models;
+use crate::manifest::{Manifest, Plan};
+use crate::monitor;
+use crate::scan::{self, lineage};
+use crate::search::{profile::Profile, Query};
+use actix_web::{http::header, web, HttpRequest, HttpResponse};
+use serde::Deserialize;
+
+use super::{
+    error::ApplicationError,
+    idempotency, scans, scoring, timeout,
+    utils::{bad_request_body, handle_database_error, internal_server_error, ok_to_json},
+    version::ApiVersion,
+    ApplicationContext,
+};
+
+#[derive(Debug, Deserialize)]
+pub struct NewAsset {
+    pub name: String,
+    pub kind: String,
+    #[serde(default)]
+    pub components: Vec<Query>,
+    #[serde(default)]
+    pub labels: models::Labels,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct NewRelease {
+    pub version: String,
+    pub components: Vec<Query>,
+}
+
+/// Number of days of the trend returned by default by the rollups.
+const DEFAULT_TREND_DAYS: u32 = 30;
+
+#[derive(Debug, Deserialize)]
+pub struct ApplyParams {
+    #[serde(default)]
+    pub prune: bool,
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RollupParams {
+    pub days: Option<u32>,
+}
+
+fn not_found(id: i32) -> ApplicationError {
+    ApplicationError::NotFound(format!("asset {} not found", id))
+}
+
+pub async fn create(
+    ctx: web::Data<ApplicationContext>,
+    req: HttpRequest,
+    body: web::Bytes,
+) -> Result<HttpResponse, ApplicationError> {
+    let asset: NewAsset = ctx.limits.parse_json(&body).map_err(bad_request_body)?;
+    if !models::ASSET_KINDS.contains(&asset.kind.as_str()) {
+        return Err(bad_request_body(format!(
+            "invalid asset kind '{}', expected one of {}",
+            asset.kind,
+            models::ASSET_KINDS.join(", ")
+        )));
+    }
+    let timeout = ctx.timeouts.default;
+    let key = idempotency::Key::from_request(&req, &body)?;
+
+    idempotency::once(ctx.clone(), key, async move {
+        timeout::block(timeout, move || {
+            ctx.get_database(timeout)
+                .map_err(handle_database_error)?
+                .create_asset(models::NewAsset::with(
+                    asset.name,
+                    asset.kind,
+                    scan::Format::Products.to_string(),
+                    asset.components,
+                    asset.labels,
+                ))
+                .map_err(internal_server_error)
+        })
+        .await
+        .map(ok_to_json)
+    })
+    .await
+}
+
+pub async fn all(ctx: web::Data<ApplicationContext>) -> Result<HttpResponse, ApplicationError> {
+    let timeout = ctx.timeouts.default;
+
+    timeout::block(timeout, move || {
+        ctx.get_database(timeout)
+            .map_err(handle_database_error)?
+            .get_assets()
+            .map_err(internal_server_error)
+    })
+    .await
+    .map(ok_to_json)
+}
+
+pub async fn get(
+    ctx: web::Data<ApplicationContext>,
+    id: web::Path<i32>,
+) -> Result<HttpResponse, ApplicationError> {
+    let id = id.into_inner();
+    let timeout = ctx.timeouts.default;
+
+    timeout::block(timeout, move || {
+        ctx.get_database(timeout)
+            .map_err(handle_database_error)?
+            .get_asset(id)
+            .map_err(internal_server_error)?
+            .ok_or_else(|| not_found(id))
+    })
+    .await
+    .map(ok_to_json)
+}
+
+/// Replaces the labels used to group the asset.
+pub async fn labels(
+    ctx: web::Data<ApplicationContext>,
+    id: web::Path<i32>,
+    body: web::Bytes,
+) -> Result<HttpResponse, ApplicationError> {
+    let id = id.into_inner();
+    let labels: models::Labels = ctx.limits.parse_json(&body).map_err(bad_request_body)?;
+    let timeout = ctx.timeouts.default;
+
+    timeout::block(timeout, move || {
+        ctx.get_database(timeout)
+            .map_err(handle_database_error)?
+            .update_asset_labels(id, labels)
+            .map_err(internal_server_error)?
+            .ok_or_else(|| not_found(id))
+    })
+    .await
+    .map(ok_to_json)
+}
+
+pub async fn delete(
+    ctx: web::Data<ApplicationContext>,
+    id: web::Path<i32>,
+) -> Result<HttpResponse, ApplicationError> {
+    let id = id.into_inner();
+    let timeout = ctx.timeouts.default;
+
+    let deleted = timeout::block(timeout, move || {
+        ctx.get_database(timeout)
+            .map_err(handle_database_error)?
+            .delete_asset(id)
+            .map_err(internal_server_error)
+    })
+    .await?;
+
+    if deleted {
+        Ok(HttpResponse::NoContent().finish())
+    } else {
+        Err(not_found(id))
+    }
+}
+
+/// Entity tag of the version of the assets manifest.
+fn manifest_etag(version: &str) -> String {
+    format!("\"{}\"", version)
+}
+
+/// Whether the request asks for a JSON manifest rather than a YAML one.
+fn accepts_json(req: &HttpRequest) -> bool {
+    req.headers()
+        .get_all(header::ACCEPT)
+        .filter_map(|value| value.to_str().ok())
+        .any(|value| {
+            value
+                .split(',')
+                .any(|t| t.trim().starts_with("application/json"))
+        })
+}
+
+/// Versions of the manifest the request was made against, from its `If-Match` header.
+fn if_match(req: &HttpRequest) -> Option<Vec<String>> {
+    let values: Vec<String> = req
+        .headers()
+        .get_all(header::IF_MATCH)
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(|tag| tag.trim().trim_start_matches("W/").to_string())
+        .filter(|tag| !tag.is_empty())
+        .collect();
+    if values.is_empty() {
+        None
+    } else {
+        Some(values)
+    }
+}
+
+/// Returns the assets as a YAML manifest, or a JSON one when asked with the `Accept`
+/// header, with its version as entity tag.
+pub async fn manifest(
+    ctx: web::Data<ApplicationContext>,
+    req: HttpRequest,
+) -> Result<HttpResponse, ApplicationError> {
+    let json = accepts_json(&req);
+    let timeout = ctx.timeouts.default;
+
+    let manifest = timeout::block(timeout, move || {
+        let assets = ctx
+            .get_database(timeout)
+            .map_err(handle_database_error)?
+            .get_assets()
+            .map_err(internal_server_error)?;
+
+        Ok(Manifest::with(&assets))
+    })
+    .await?;
+    let version = manifest.version().map_err(internal_server_error)?;
+
+    let mut res = HttpResponse::Ok();
+    res.insert_header((header::ETAG, manifest_etag(&version)));
+    if json {
+        return Ok(res.json(manifest));
+    }
+    let yaml = manifest.to_yaml().map_err(internal_server_error)?;
+    Ok(res.content_type("application/yaml").body(yaml))
+}
+
+/// Creates and updates the assets of a YAML or JSON manifest, deleting the ones it doesn't
+/// list with `prune`, and returns the names of the assets changed along with the version of
+/// the resulting manifest as entity tag. With `If-Match`, the manifest is applied only if
+/// the assets are still at one of the given versions.
+pub async fn apply(
+    ctx: web::Data<ApplicationContext>,
+    req: HttpRequest,
+    params: web::Query<ApplyParams>,
+    body: web::Bytes,
+) -> Result<HttpResponse, ApplicationError> {
+    let manifest = Manifest::parse(&body).map_err(bad_request_body)?;
+    let params = params.into_inner();
+    let expected = if_match(&req);
+    let timeout = ctx.timeouts.default;
+
+    let (summary, version) = timeout::block(timeout, move || {
+        let database = ctx.get_database(timeout).map_err(handle_database_error)?;
+        let assets = database.get_assets().map_err(internal_server_error)?;
+
+        let version = Manifest::with(&assets)
+            .version()
+            .map_err(internal_server_error)?;
+        if let Some(expected) = expected {
+            let etag = manifest_etag(&version);
+            if !expected.iter().any(|tag| *tag == etag || tag == "*") {
+                return Err(ApplicationError::PreconditionFailed(format!(
+                    "the assets changed, their manifest is now at version {}",
+                    etag
+                )));
+            }
+        }
+
+        let plan = Plan::new(manifest, &assets, params.prune).map_err(bad_request_body)?;
+        if params.dry_run {
+            return Ok((plan.summary(), version));
+        }
+        let summary = plan.apply(&database).map_err(internal_server_error)?;
+
+        let assets = database.get_assets().map_err(internal_server_error)?;
+        let version = Manifest::with(&assets)
+            .version()
+            .map_err(internal_server_error)?;
+        Ok((summary, version))
+    })
+    .await?;
+
+    Ok(HttpResponse::Ok()
+        .insert_header((header::ETAG, manifest_etag(&version)))
+        .json(summary))
+}
+
+/// Lists the deleted assets that can still be restored.
+pub async fn deleted(ctx: web::Data<ApplicationContext>) -> Result<HttpResponse, ApplicationError> {
+    let timeout = ctx.timeouts.default;
+
+    timeout::block(timeout, move || {
+        ctx.get_database(timeout)
+            .map_err(handle_database_error)?
+            .get_deleted_assets()
+            .map_err(internal_server_error)
+    })
+    .await
+    .map(ok_to_json)
+}
+
+/// Restores a deleted asset along with its findings and releases, unless already purged.
+pub async fn restore(
+    ctx: web::Data<ApplicationContext>,
+    id: web::Path<i32>,
+) -> Result<HttpResponse, ApplicationError> {
+    let id = id.into_inner();
+    let timeout = ctx.timeouts.default;
+
+    timeout::block(timeout, move || {
+        ctx.get_database(timeout)
+            .map_err(handle_database_error)?
+            .restore_asset(id)
+            .map_err(internal_server_error)?
+            .ok_or_else(|| ApplicationError::NotFound(format!("deleted asset {} not found", id)))
+    })
+    .await
+    .map(ok_to_json)
+}
+
+/// Replaces the components of the asset with the ones of the scan input, and returns the
+/// CVEs affecting them.
+async fn attach(
+    ctx: web::Data<ApplicationContext>,
+    profile: Option<Arc<Profile>>,
+    version: ApiVersion,
+    id: i32,
+    format: scan::Format,
+    body: String,
+) -> Result<HttpResponse, ApplicationError> {
+    let timeout = ctx.timeouts.scan;
+
+    timeout::block(timeout, move || {
+        let components = scan::parse(format, &body).map_err(bad_request_body)?;
+        let database = ctx.get_database(timeout).map_err(handle_database_error)?;
+
+        database
+            .update_asset_components(id, &format.to_string(), components.queries.clone())
+            .map_err(internal_server_error)?
+            .ok_or_else(|| not_found(id))?;
+
+        let num_components = components.queries.len();
+        let report = scan::run(&database, format, components).map_err(bad_request_body)?;
+
+        if let Some(bus) = &ctx.bus {
+            bus.spawn_publish(vec![Event::scan_completed(
+                Some(id),
+                format.to_string(),
+                num_components,
+                &report.cves,
+            )]);
+        }
+
+        Ok(report)
+    })
+    .await
+    .map(|report| scans::respond(version, profile, report))
+}
+
+pub async fn scan(
+    ctx: web::Data<ApplicationContext>,
+    req: HttpRequest,
+    version: ApiVersion,
+    path: web::Path<(i32, String)>,
+    body: String,
+) -> Result<HttpResponse, ApplicationError> {
+    let (id, format) = path.into_inner();
+    let format = format.parse::<scan::Format>().map_err(bad_request_body)?;
+    scans::check_depth(&ctx, &body)?;
+    let profile = scoring::profile(&ctx, &req)?;
+    let key = idempotency::Key::from_request(&req, body.as_bytes())?;
+
+    idempotency::once(
+        ctx.clone(),
+        key,
+        attach(ctx, profile, version, id, format, body),
+    )
+    .await
+}
+
+pub async fn detect(
+    ctx: web::Data<ApplicationContext>,
+    req: HttpRequest,
+    version: ApiVersion,
+    id: web::Path<i32>,
+    body: String,
+) -> Result<HttpResponse, ApplicationError> {
+    scans::check_depth(&ctx, &body)?;
+    let profile = scoring::profile(&ctx, &req)?;
+    let format = scan::detect(&body)
+        .ok_or_else(|| bad_request_body("could not detect the scan input format".into()))?;
+    let key = idempotency::Key::from_request(&req, body.as_bytes())?;
+
+    idempotency::once(
+        ctx.clone(),
+        key,
+        attach(ctx, profile, version, id.into_inner(), format, body),
+    )
+    .await
+}
+
+/// Matches the components of the asset against the current dataset.
+pub async fn cves(
+    ctx: web::Data<ApplicationContext>,
+    req: HttpRequest,
+    version: ApiVersion,
+    id: web::Path<i32>,
+) -> Result<HttpResponse, ApplicationError> {
+    let id = id.into_inner();
+    let profile = scoring::profile(&ctx, &req)?;
+    let timeout = ctx.timeouts.scan;
+
+    timeout::block(timeout, move || {
+        let database = ctx.get_database(timeout).map_err(handle_database_error)?;
+        let asset = database
+            .get_asset(id)
+            .map_err(internal_server_error)?
+            .ok_or_else(|| not_found(id))?;
+
+        let format = asset
+            .format
+            .parse::<scan::Format>()
+            .unwrap_or(scan::Format::Products);
+        let components = scan::Components {
+            queries: asset.components.0,
+            warnings: vec![],
+            licenses: vec![],
+        };
+
+        scan::run(&database, format, components).map_err(bad_request_body)
+    })
+    .await
+    .map(|report| scans::respond(version, profile, report))
+}
+
+/// Lists the CVEs that became applicable to the asset after the imports, newest first.
+pub async fn findings(
+    ctx: web::Data<ApplicationContext>,
+    id: web::Path<i32>,
+) -> Result<HttpResponse, ApplicationError> {
+    let id = id.into_inner();
+    let timeout = ctx.timeouts.default;
+
+    timeout::block(timeout, move || {
+        let database = ctx.get_database(timeout).map_err(handle_database_error)?;
+        database
+            .get_asset(id)
+            .map_err(internal_server_error)?
+            .ok_or_else(|| not_found(id))?;
+
+        database.get_findings(id).map_err(internal_server_error)
+    })
+    .await
+    .map(ok_to_json)
+}
+
+/// Aggregates the findings of the assets grouped by the value of a label.
+pub async fn rollups(
+    ctx: web::Data<ApplicationContext>,
+    label: web::Path<String>,
+    params: web::Query<RollupParams>,
+) -> Result<HttpResponse, ApplicationError> {
+    let label = label.into_inner();
+    let days = params.days.unwrap_or(DEFAULT_TREND_DAYS);
+    if days == 0 || days > 366 {
+        return Err(bad_request_body(format!(
+            "invalid number of days {}, expected 1 to 366",
+            days
+        )));
+    }
+    let timeout = ctx.timeouts.default;
+
+    timeout::block(timeout, move || {
+        let database = ctx.get_database(timeout).map_err(handle_database_error)?;
+        let assets = database.get_assets().map_err(internal_server_error)?;
+        let findings = database.get_all_findings().map_err(internal_server_error)?;
+        let today = chrono::Utc::now().naive_utc().date();
+
+        Ok(monitor::rollup(&assets, &findings, &label, today, days))
+    })
+    .await
+    .map(ok_to_json)
+}
+
+/// Records a release of the asset with its components, replacing the components of an
+/// existing release with the same version.
+pub async fn create_release(
+    ctx: web::Data<ApplicationContext>,
+    id: web::Path<i32>,
+    body: web::Bytes,
+) -> Result<HttpResponse, ApplicationError> {
+    let id = id.into_inner();
+    let release: NewRelease = ctx.limits.parse_json(&body).map_err(bad_request_body)?;
+    if release.version.trim().is_empty() {
+        return Err(bad_request_body("missing release version".into()));
+    }
+    let timeout = ctx.timeouts.default;
+
+    timeout::block(timeout, move || {
+        let database = ctx.get_database(timeout).map_err(handle_database_error)?;
+        database
+            .get_asset(id)
+            .map_err(internal_server_error)?
+            .ok_or_else(|| not_found(id))?;
+
+        database
+            .upsert_release(models::NewRelease::with(
+                id,
+                release.version,
+                release.components,
+            ))
+            .map_err(internal_server_error)
+    })
+    .await
+    .map(ok_to_json)
+}
+
+/// Lists the releases of the asset in version order.
+pub async fn releases(
+    ctx: web::Data<ApplicationContext>,
+    id: web::Path<i32>,
+) -> Result<HttpResponse, ApplicationError> {
+    let id = id.into_inner();
+    let timeout = ctx.timeouts.default;
+
+    timeout::block(timeout, move || {
+        let database = ctx.get_database(timeout).map_err(handle_database_error)?;
+        database
+            .get_asset(id)
+            .map_err(internal_server_error)?
+            .ok_or_else(|| not_found(id))?;
+
+        let mut releases = database.get_releases(id).map_err(internal_server_error)?;
+        releases.sort_by(|a, b| lineage::compare_versions(&a.version, &b.version));
+        Ok(releases)
+    })
+    .await
+    .map(ok_to_json)
+}
+
+/// Returns the releases of the asset affected by the CVE and the first one with the fix.
+pub async fn affected_releases(
+    ctx: web::Data<ApplicationContext>,
+    path: web::Path<(i32, String)>,
+) -> Result<HttpResponse, ApplicationError> {
+    let (id, cve) = path.into_inner();
+    let timeout = ctx.timeouts.scan;
+
+    timeout::block(timeout, move || {
+        let database = ctx.get_database(timeout).map_err(handle_database_error)?;
+        database
+            .get_asset(id)
+            .map_err(internal_server_error)?
+            .ok_or_else(|| not_found(id))?;
+
+        let releases = database.get_releases(id).map_err(internal_server_error)?;
+        lineage::run(&database, releases, &cve.to_uppercase()).map_err(bad_request_body)
+    })
+    .await
+    .map(ok_to_json)
+}
@@ -0,0 +1,68 @@
+use actix_web::{web, HttpResponse};
+use serde::Deserialize;
+
+use crate::search::suggest;
+
+use super::{
+    error::ApplicationError,
+    timeout,
+    utils::{bad_request_body, handle_database_error, internal_server_error, ok_to_json},
+    ApplicationContext,
+};
+
+/// Number of suggestions returned unless asked otherwise, and the most that can be asked.
+const DEFAULT_LIMIT: usize = 5;
+const MAX_LIMIT: usize = 20;
+
+/// Component described by its name as found in an inventory.
+#[derive(Debug, Deserialize)]
+pub struct Component {
+    pub name: String,
+    pub vendor: Option<String>,
+    pub version: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SuggestParams {
+    pub limit: Option<usize>,
+}
+
+/// Suggests the CPEs of a component, ranked from the most likely.
+pub async fn suggest(
+    ctx: web::Data<ApplicationContext>,
+    params: web::Query<SuggestParams>,
+    body: web::Bytes,
+) -> Result<HttpResponse, ApplicationError> {
+    let component: Component = ctx.limits.parse_json(&body).map_err(bad_request_body)?;
+    if component.name.trim().is_empty() {
+        return Err(bad_request_body("the component name is empty".into()));
+    }
+    if component.version.trim().is_empty() {
+        return Err(bad_request_body("the component version is empty".into()));
+    }
+    let limit = match params.limit {
+        Some(limit) if limit == 0 || limit > MAX_LIMIT => {
+            return Err(bad_request_body(format!(
+                "invalid limit {}, expected 1 to {}",
+                limit, MAX_LIMIT
+            )))
+        }
+        Some(limit) => limit,
+        None => DEFAULT_LIMIT,
+    };
+    let timeout = ctx.timeouts.default;
+
+    timeout::block(timeout, move || {
+        let database = ctx.get_database(timeout).map_err(handle_database_error)?;
+        suggest::suggest(
+            &database,
+            component.name.trim(),
+            component.vendor.as_deref().map(str::trim),
+            component.version.trim(),
+            limit,
+        )
+        .map_err(internal_server_error)
+    })
+    .await
+    .map(ok_to_json)
+}
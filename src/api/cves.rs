@@ -1,21 +1,372 @@
-use crate::search::{self, Query};
-use actix_web::{web, HttpResponse};
+use std::sync::Arc;
+use std::time::Instant;
+
+use crate::db::{changes::Changes, models};
+use crate::search::{
+    self, compose::Composition, dsl, enrichment::Enrichment, profile::Profile, Query,
+};
+use crate::sources::{capec, cwe};
+use actix_web::{web, HttpRequest, HttpResponse};
+use serde::{Deserialize, Serialize};
 
 use super::{
     error::ApplicationError,
-    utils::{bad_request_body, handle_blocking_error, handle_database_error, ok_to_json},
+    listing::{Entry, Page, PageParams},
+    scoring, timeout,
+    utils::{bad_request_body, handle_database_error, internal_server_error, ok_to_json},
+    v2,
+    version::ApiVersion,
     ApplicationContext,
 };
 
+fn respond(
+    version: ApiVersion,
+    profile: Option<Arc<Profile>>,
+    query: Query,
+    cves: Vec<models::CVE>,
+    enrichment: Enrichment,
+) -> HttpResponse {
+    match (version, profile) {
+        (ApiVersion::V1, profile) => {
+            version.json(scoring::scored(&cves, profile.as_deref(), &enrichment))
+        }
+        (ApiVersion::V2, profile) => version.json(v2::SearchResult {
+            query,
+            vulnerabilities: v2::vulnerabilities(cves, profile.as_deref(), &enrichment),
+        }),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SearchParams {
+    /// Minimum EPSS score of the CVEs, the ones not scored being left out.
+    pub epss_gte: Option<f64>,
+    /// Weakness of the CVEs, like `CWE-787`.
+    pub cwe: Option<String>,
+}
+
+impl SearchParams {
+    fn validate(&self) -> Result<(), String> {
+        if let Some(id) = &self.cwe {
+            if cwe::normalize(id).is_none() {
+                return Err(format!("invalid CWE {}, expected like CWE-787", id));
+            }
+        }
+        match self.epss_gte {
+            Some(epss) if !(0.0..=1.0).contains(&epss) => {
+                Err(format!("invalid EPSS score {}, expected 0 to 1", epss))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn filter(&self, cves: &mut Vec<models::CVE>, enrichment: &Enrichment) {
+        if let Some(epss) = self.epss_gte {
+            cves.retain(|cve| {
+                enrichment
+                    .epss
+                    .of(&cve.cve)
+                    .epss_score
+                    .map_or(false, |score| score >= epss)
+            });
+        }
+        if let Some(id) = self.cwe.as_deref().and_then(cwe::normalize) {
+            cves.retain(|cve| enrichment.weaknesses.has(&cve.cve, &id));
+        }
+    }
+}
+
 pub async fn search(
     ctx: web::Data<ApplicationContext>,
-    query: web::Json<Query>,
+    req: HttpRequest,
+    version: ApiVersion,
+    params: web::Query<SearchParams>,
+    body: web::Bytes,
+) -> Result<HttpResponse, ApplicationError> {
+    params.validate().map_err(bad_request_body)?;
+    let query: Query = ctx.limits.parse_json(&body).map_err(bad_request_body)?;
+    let profile = scoring::profile(&ctx, &req)?;
+    let timeout = ctx.timeouts.default;
+    ctx.usage.record_search();
+    let query_log = ctx.query_log.clone();
+    let keys = query_log.keys(vec![&query]);
+    let started = Instant::now();
+
+    let (cves, enrichment) = timeout::block(timeout, {
+        let query = query.clone();
+        move || {
+            let database = ctx.get_database(timeout).map_err(handle_database_error)?;
+            let mut cves = search::query(&database, &query).map_err(bad_request_body)?;
+            let enrichment = Enrichment::load(&database, &cves).map_err(internal_server_error)?;
+            params.filter(&mut cves, &enrichment);
+            Ok((cves, enrichment))
+        }
+    })
+    .await?;
+    query_log.record(keys, started.elapsed());
+
+    Ok(respond(version, profile, query, cves, enrichment))
+}
+
+pub async fn query(
+    ctx: web::Data<ApplicationContext>,
+    req: HttpRequest,
+    version: ApiVersion,
+    body: String,
+) -> Result<HttpResponse, ApplicationError> {
+    let expression = dsl::parse(&body).map_err(bad_request_body)?;
+    let profile = scoring::profile(&ctx, &req)?;
+    let timeout = ctx.timeouts.default;
+    ctx.usage.record_search();
+    let query_log = ctx.query_log.clone();
+    let keys = query_log.keys(vec![&expression.query]);
+    let started = Instant::now();
+
+    let (cves, enrichment) = timeout::block(timeout, {
+        let expression = expression.clone();
+        move || {
+            let database = ctx.get_database(timeout).map_err(handle_database_error)?;
+            dsl::query(&database, &expression).map_err(bad_request_body)
+        }
+    })
+    .await?;
+    query_log.record(keys, started.elapsed());
+
+    Ok(respond(
+        version,
+        profile,
+        expression.query,
+        cves,
+        enrichment,
+    ))
+}
+
+/// Searches the CVEs of a boolean composition of queries, like the CVEs of any of several
+/// products.
+pub async fn compose(
+    ctx: web::Data<ApplicationContext>,
+    req: HttpRequest,
+    version: ApiVersion,
+    body: web::Bytes,
+) -> Result<HttpResponse, ApplicationError> {
+    let composition: Composition = ctx.limits.parse_json(&body).map_err(bad_request_body)?;
+    composition.validate().map_err(bad_request_body)?;
+    let profile = scoring::profile(&ctx, &req)?;
+    let timeout = ctx.timeouts.default;
+    ctx.usage.record_search();
+    let query_log = ctx.query_log.clone();
+    let keys = query_log.keys(composition.queries());
+    let started = Instant::now();
+
+    let (cves, enrichment) = timeout::block(timeout, {
+        let composition = composition.clone();
+        move || {
+            let database = ctx.get_database(timeout).map_err(handle_database_error)?;
+            let cves = search::compose::query(&database, &composition).map_err(bad_request_body)?;
+            let enrichment = Enrichment::load(&database, &cves).map_err(internal_server_error)?;
+            Ok((cves, enrichment))
+        }
+    })
+    .await?;
+    query_log.record(keys, started.elapsed());
+
+    Ok(match version {
+        ApiVersion::V1 => version.json(scoring::scored(&cves, profile.as_deref(), &enrichment)),
+        ApiVersion::V2 => version.json(v2::CompositionResult {
+            composition,
+            vulnerabilities: v2::vulnerabilities(cves, profile.as_deref(), &enrichment),
+        }),
+    })
+}
+
+/// CVE similar to the requested one.
+#[derive(Debug, Serialize)]
+pub struct Related {
+    pub id: String,
+    pub similarity: f64,
+    pub summary: String,
+    pub score: f64,
+    pub severity: String,
+}
+
+/// Lists the CVEs imported while awaiting analysis, and not reconciled yet.
+pub async fn pending(ctx: web::Data<ApplicationContext>) -> Result<HttpResponse, ApplicationError> {
+    let timeout = ctx.timeouts.default;
+
+    timeout::block(timeout, move || {
+        let database = ctx.get_database(timeout).map_err(handle_database_error)?;
+        database.get_pending_cves().map_err(internal_server_error)
+    })
+    .await
+    .map(ok_to_json)
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ChangesParams {
+    pub since_revision: i32,
+}
+
+/// Lists the CVEs added, modified or removed since the given revision of the dataset, for
+/// mirrors to sync incrementally.
+pub async fn changes(
+    ctx: web::Data<ApplicationContext>,
+    params: web::Query<ChangesParams>,
 ) -> Result<HttpResponse, ApplicationError> {
-    web::block(move || {
-        let database = ctx.get_database().map_err(handle_database_error)?;
-        search::query(&database, &query.into_inner()).map_err(bad_request_body)
+    let since_revision = params.since_revision;
+    if since_revision < 0 {
+        return Err(bad_request_body(format!(
+            "invalid revision {}",
+            since_revision
+        )));
+    }
+    let timeout = ctx.timeouts.default;
+
+    timeout::block(timeout, move || {
+        let database = ctx.get_database(timeout).map_err(handle_database_error)?;
+
+        let log = database
+            .get_cve_changes_since(since_revision)
+            .map_err(internal_server_error)?;
+        let (oldest, latest) = database.get_revisions().map_err(internal_server_error)?;
+
+        // the changes right after the requested revision must still be logged
+        if let Some(oldest) = oldest {
+            if since_revision + 1 < oldest {
+                return Err(ApplicationError::Gone(format!(
+                    "changes before revision {} were pruned, a full sync is needed",
+                    oldest
+                )));
+            }
+        }
+
+        let revision = log
+            .last()
+            .map(|change| change.id)
+            .or(latest)
+            .unwrap_or(0)
+            .max(since_revision);
+        Ok(Changes::from_log(since_revision, revision, &log))
+    })
+    .await
+    .map(ok_to_json)
+}
+
+/// Lists the ids of the CVEs having records, with the revision that last modified them.
+pub async fn ids(
+    ctx: web::Data<ApplicationContext>,
+    params: web::Query<PageParams>,
+) -> Result<HttpResponse, ApplicationError> {
+    let limit = params.limit().map_err(bad_request_body)?;
+    let after = params.after.clone().unwrap_or_default();
+    let timeout = ctx.timeouts.default;
+
+    timeout::block(timeout, move || {
+        let database = ctx.get_database(timeout).map_err(handle_database_error)?;
+        let revisions = database
+            .get_cve_revisions(&after, limit)
+            .map_err(internal_server_error)?;
+
+        let items = revisions
+            .into_iter()
+            .map(|r| Entry {
+                id: r.cve,
+                revision: r.revision,
+            })
+            .collect();
+        Ok(Page::with(items, limit))
+    })
+    .await
+    .map(ok_to_json)
+}
+
+/// Lists the CVEs related to the given one, as found by the last similarity analysis.
+pub async fn related(
+    ctx: web::Data<ApplicationContext>,
+    id: web::Path<String>,
+) -> Result<HttpResponse, ApplicationError> {
+    let id = id.into_inner().to_uppercase();
+    let timeout = ctx.timeouts.default;
+
+    timeout::block(timeout, move || {
+        let database = ctx.get_database(timeout).map_err(handle_database_error)?;
+
+        let relations = database
+            .get_related_cves(&id)
+            .map_err(internal_server_error)?;
+        let ids: Vec<String> = relations.iter().map(|r| r.related.clone()).collect();
+        let records = database.get_cves(&ids).map_err(internal_server_error)?;
+
+        Ok(relations
+            .into_iter()
+            .filter_map(|relation| {
+                records
+                    .iter()
+                    .find(|record| record.cve == relation.related)
+                    .map(|record| Related {
+                        id: relation.related,
+                        similarity: relation.score,
+                        summary: record.summary.clone(),
+                        score: record.score,
+                        severity: record.severity.clone(),
+                    })
+            })
+            .collect::<Vec<_>>())
+    })
+    .await
+    .map(ok_to_json)
+}
+
+/// Lists the attack patterns of the CAPEC likely to exploit the CVE through its weaknesses,
+/// the most severe and likely first.
+pub async fn attack_patterns(
+    ctx: web::Data<ApplicationContext>,
+    id: web::Path<String>,
+) -> Result<HttpResponse, ApplicationError> {
+    let id = id.into_inner().to_uppercase();
+    let timeout = ctx.timeouts.default;
+
+    timeout::block(timeout, move || {
+        let database = ctx.get_database(timeout).map_err(handle_database_error)?;
+
+        let weaknesses = database
+            .get_cve_weaknesses(&[id.as_str()])
+            .map_err(internal_server_error)?;
+        let cwes: Vec<&str> = weaknesses.iter().map(|w| w.cwe.as_str()).collect();
+        if cwes.is_empty() {
+            return Ok(vec![]);
+        }
+        let patterns = database
+            .get_attack_patterns(&cwes)
+            .map_err(internal_server_error)?;
+
+        Ok(capec::link(patterns))
+    })
+    .await
+    .map(ok_to_json)
+}
+
+/// Lists the advisories cross-referenced to the CVE, or to the CVE of the advisory, with
+/// the given id.
+pub async fn aliases(
+    ctx: web::Data<ApplicationContext>,
+    id: web::Path<String>,
+) -> Result<HttpResponse, ApplicationError> {
+    let id = id.into_inner();
+    // the GitHub advisory ids are lowercase but for their prefix
+    let id = match id.get(..5) {
+        Some(prefix) if prefix.eq_ignore_ascii_case("GHSA-") => {
+            format!("GHSA-{}", id[5..].to_lowercase())
+        }
+        _ => id.to_uppercase(),
+    };
+    let timeout = ctx.timeouts.default;
+
+    timeout::block(timeout, move || {
+        ctx.get_database(timeout)
+            .map_err(handle_database_error)?
+            .get_advisory_aliases(&id)
+            .map_err(internal_server_error)
     })
     .await
-    .map_err(handle_blocking_error)?
     .map(ok_to_json)
 }
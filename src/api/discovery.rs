@@ -0,0 +1,142 @@
+//! Machine readable metadata of the instance, served under `/.well-known/kepler.json` like a
+//! `security.txt`, for the clients to discover what it serves before integrating.
+
+use std::env;
+
+use actix_web::{web, HttpResponse};
+use serde::Serialize;
+
+use super::{
+    error::ApplicationError,
+    timeout,
+    utils::{handle_database_error, internal_server_error},
+    version::{self, ApiVersion},
+    ApplicationContext,
+};
+
+/// Contact of the operators of the instance, as a `mailto:`, `https:` or `tel:` URI like the
+/// `Contact` field of a `security.txt`, unless not set.
+pub fn contact_from_env() -> Result<Option<String>, String> {
+    match env::var("KEPLER_CONTACT") {
+        Ok(value) if !value.trim().is_empty() => parse_contact(&value)
+            .map(Some)
+            .map_err(|e| format!("invalid KEPLER_CONTACT: {}", e)),
+        _ => Ok(None),
+    }
+}
+
+/// Contact as a URI, the bare email addresses becoming `mailto:` ones.
+fn parse_contact(value: &str) -> Result<String, String> {
+    let value = value.trim();
+    if value.contains(char::is_whitespace) {
+        return Err(format!("{:?} contains spaces", value));
+    }
+    if ["mailto:", "https://", "tel:"]
+        .iter()
+        .any(|scheme| value.starts_with(scheme) && value.len() > scheme.len())
+    {
+        return Ok(value.to_string());
+    }
+    match value.split_once('@') {
+        Some((user, domain)) if !user.is_empty() && domain.contains('.') => {
+            Ok(format!("mailto:{}", value))
+        }
+        _ => Err(format!(
+            "{:?} is not an email address nor a mailto:, https: or tel: URI",
+            value
+        )),
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct Metadata {
+    name: &'static str,
+    version: &'static str,
+    api_versions: Vec<&'static str>,
+    /// Media type to send in the `Accept` header to get the v2 responses.
+    v2_media_type: &'static str,
+    features: Vec<&'static str>,
+    /// Optional capabilities enabled by the configuration of the instance.
+    capabilities: Vec<&'static str>,
+    /// Sources having records, like `NIST` or `GHSA`.
+    sources: Vec<String>,
+    /// Current revision of the dataset, as listed by the changes API.
+    revision: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    contact: Option<String>,
+}
+
+/// Describes the versions of the API, the sources imported, the revision of the dataset and
+/// the contact of the instance.
+pub async fn metadata(
+    ctx: web::Data<ApplicationContext>,
+) -> Result<HttpResponse, ApplicationError> {
+    let timeout = ctx.timeouts.default;
+
+    let (sources, revision) = timeout::block(timeout, {
+        let ctx = ctx.clone();
+        move || {
+            let database = ctx.get_database(timeout).map_err(handle_database_error)?;
+            let sources: Vec<String> = database
+                .count_cves_by_source()
+                .map_err(internal_server_error)?
+                .into_iter()
+                .map(|(source, _)| source)
+                .collect();
+            let (_, revision) = database.get_revisions().map_err(internal_server_error)?;
+            Ok((sources, revision))
+        }
+    })
+    .await?;
+
+    let capabilities = [
+        ("attestation", ctx.signer.is_some()),
+        ("transparency_log", ctx.rekor.is_some()),
+        ("mirror", ctx.mirror.is_some()),
+        ("event_bus", ctx.bus.is_some()),
+    ]
+    .iter()
+    .filter(|(_, enabled)| *enabled)
+    .map(|(name, _)| *name)
+    .collect();
+
+    Ok(HttpResponse::Ok().json(Metadata {
+        name: "kepler",
+        version: crate::version(),
+        api_versions: ApiVersion::ALL
+            .iter()
+            .map(|version| version.name())
+            .collect(),
+        v2_media_type: version::V2_MEDIA_TYPE,
+        features: crate::features(),
+        capabilities,
+        sources,
+        revision,
+        contact: ctx.contact.clone(),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_contact;
+
+    #[test]
+    fn can_parse_contacts() {
+        assert_eq!(
+            Ok("mailto:security@example.com".to_string()),
+            parse_contact(" security@example.com ")
+        );
+        assert_eq!(
+            Ok("https://example.com/security".to_string()),
+            parse_contact("https://example.com/security")
+        );
+        assert_eq!(
+            Ok("tel:+1-201-555-0123".to_string()),
+            parse_contact("tel:+1-201-555-0123")
+        );
+        assert!(parse_contact("security").is_err());
+        assert!(parse_contact("http://example.com").is_err());
+        assert!(parse_contact("mailto:").is_err());
+        assert!(parse_contact("security team@example.com").is_err());
+    }
+}
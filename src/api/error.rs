@@ -1,12 +1,32 @@
 use std::fmt::Display;
+use std::time::Duration;
 
 use actix_web::{http::StatusCode, HttpResponse, HttpResponseBuilder};
+use serde::Serialize;
 
 #[derive(Debug)]
 pub enum ApplicationError {
     InternalServerError,
     BadRequest(String),
+    NotFound(String),
+    Gone(String),
+    Conflict(String),
+    PreconditionFailed(String),
+    UnprocessableEntity(String),
     ServiceUnavailable,
+    Timeout(Duration),
+    /// A panic, with the id of its crash report when known.
+    Crashed(Option<String>),
+}
+
+/// Error details as specified by RFC 7807.
+#[derive(Debug, Serialize)]
+struct Problem<'a> {
+    #[serde(rename = "type")]
+    kind: &'a str,
+    title: &'a str,
+    status: u16,
+    detail: String,
 }
 
 impl Display for ApplicationError {
@@ -19,18 +39,42 @@ impl actix_web::error::ResponseError for ApplicationError {
     fn error_response(&self) -> HttpResponse {
         let mut b = HttpResponseBuilder::new(self.status_code());
 
-        if let Self::BadRequest(err) = self {
-            b.body(err.to_owned())
-        } else {
-            b.finish()
+        match self {
+            Self::BadRequest(err)
+            | Self::NotFound(err)
+            | Self::Gone(err)
+            | Self::Conflict(err)
+            | Self::PreconditionFailed(err)
+            | Self::UnprocessableEntity(err) => b.body(err.to_owned()),
+            Self::Timeout(timeout) => b.content_type("application/problem+json").json(Problem {
+                kind: "about:blank",
+                title: "Gateway Timeout",
+                status: self.status_code().as_u16(),
+                detail: format!("request did not complete within {}s", timeout.as_secs()),
+            }),
+            Self::Crashed(report) => b.content_type("application/problem+json").json(Problem {
+                kind: "about:blank",
+                title: "Internal Server Error",
+                status: self.status_code().as_u16(),
+                detail: match report {
+                    Some(id) => format!("the request failed unexpectedly, see crash report {}", id),
+                    None => "the request failed unexpectedly".to_string(),
+                },
+            }),
+            _ => b.finish(),
         }
     }
 
     fn status_code(&self) -> StatusCode {
         match *self {
-            Self::InternalServerError => StatusCode::INTERNAL_SERVER_ERROR,
+            Self::InternalServerError | Self::Crashed(_) => StatusCode::INTERNAL_SERVER_ERROR,
             Self::BadRequest(_) => StatusCode::BAD_REQUEST,
-            Self::ServiceUnavailable => StatusCode::GATEWAY_TIMEOUT,
+            Self::NotFound(_) => StatusCode::NOT_FOUND,
+            Self::Gone(_) => StatusCode::GONE,
+            Self::Conflict(_) => StatusCode::CONFLICT,
+            Self::PreconditionFailed(_) => StatusCode::PRECONDITION_FAILED,
+            Self::UnprocessableEntity(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            Self::ServiceUnavailable | Self::Timeout(_) => StatusCode::GATEWAY_TIMEOUT,
         }
     }
 }
@@ -0,0 +1,44 @@
+use crate::export::{defectdojo, stix};
+use crate::search::{self, Query};
+use actix_web::{web, HttpResponse};
+
+use super::{
+    error::ApplicationError,
+    timeout,
+    utils::{bad_request_body, handle_database_error, ok_to_json},
+    ApplicationContext,
+};
+
+pub async fn defectdojo(
+    ctx: web::Data<ApplicationContext>,
+    body: web::Bytes,
+) -> Result<HttpResponse, ApplicationError> {
+    let queries: Vec<Query> = ctx.limits.parse_json(&body).map_err(bad_request_body)?;
+    let timeout = ctx.timeouts.export;
+
+    timeout::block(timeout, move || {
+        let database = ctx.get_database(timeout).map_err(handle_database_error)?;
+        search::query_all(&database, &queries)
+            .map(|results| defectdojo::findings(&results))
+            .map_err(bad_request_body)
+    })
+    .await
+    .map(ok_to_json)
+}
+
+pub async fn stix(
+    ctx: web::Data<ApplicationContext>,
+    body: web::Bytes,
+) -> Result<HttpResponse, ApplicationError> {
+    let queries: Vec<Query> = ctx.limits.parse_json(&body).map_err(bad_request_body)?;
+    let timeout = ctx.timeouts.export;
+
+    timeout::block(timeout, move || {
+        let database = ctx.get_database(timeout).map_err(handle_database_error)?;
+        search::query_all(&database, &queries)
+            .map(|results| stix::bundle(&results))
+            .map_err(bad_request_body)
+    })
+    .await
+    .map(ok_to_json)
+}
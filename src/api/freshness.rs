@@ -0,0 +1,277 @@
+use std::env;
+use std::fmt::Write;
+use std::sync::{Arc, RwLock};
+use std::thread;
+use std::time::Duration;
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::Serialize;
+
+use crate::db::{Database, Pool};
+use crate::notify::{Event, Notifier};
+use crate::sources::nist;
+
+const DEFAULT_SLO: Duration = Duration::from_secs(48 * 3600);
+const DEFAULT_INTERVAL: Duration = Duration::from_secs(300);
+
+/// Freshness of the records of a source.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct Status {
+    pub source: String,
+    pub latest_record: Option<String>,
+    pub age_secs: Option<u64>,
+    pub stale: bool,
+}
+
+impl Status {
+    pub fn with(
+        source: &str,
+        latest: Option<DateTime<Utc>>,
+        now: DateTime<Utc>,
+        slo: Duration,
+    ) -> Self {
+        let age = latest.map(|time| (now - time).to_std().unwrap_or_default());
+
+        Self {
+            source: source.to_string(),
+            latest_record: latest.map(|time| time.to_rfc3339_opts(SecondsFormat::Secs, true)),
+            age_secs: age.map(|age| age.as_secs()),
+            // a source without records is as stale as it gets
+            stale: age.map(|age| age > slo).unwrap_or(true),
+        }
+    }
+}
+
+/// Freshness objective of the imported data, the sources whose newest record is older than
+/// the SLO are stale. Configured with `KEPLER_FRESHNESS_SLO` (in hours, 48 by default),
+/// `KEPLER_FRESHNESS_SOURCES` (comma separated, NIST by default) and
+/// `KEPLER_FRESHNESS_INTERVAL` (how often it's checked, in seconds).
+#[derive(Debug)]
+pub struct Freshness {
+    slo: Duration,
+    interval: Duration,
+    sources: Vec<String>,
+    statuses: RwLock<Vec<Status>>,
+}
+
+impl Freshness {
+    pub fn new(slo: Duration, interval: Duration, sources: Vec<String>) -> Self {
+        Self {
+            slo,
+            interval,
+            sources,
+            statuses: RwLock::new(vec![]),
+        }
+    }
+
+    pub fn from_env() -> Result<Self, String> {
+        let slo = match env::var("KEPLER_FRESHNESS_SLO") {
+            Ok(value) => match value.parse::<u64>() {
+                Ok(hours) if hours > 0 => Duration::from_secs(hours * 3600),
+                _ => return Err(format!("invalid KEPLER_FRESHNESS_SLO: {}", value)),
+            },
+            Err(_) => DEFAULT_SLO,
+        };
+        let interval = match env::var("KEPLER_FRESHNESS_INTERVAL") {
+            Ok(value) => match value.parse::<u64>() {
+                Ok(secs) if secs > 0 => Duration::from_secs(secs),
+                _ => return Err(format!("invalid KEPLER_FRESHNESS_INTERVAL: {}", value)),
+            },
+            Err(_) => DEFAULT_INTERVAL,
+        };
+        let sources = match env::var("KEPLER_FRESHNESS_SOURCES") {
+            Ok(value) => value
+                .split(',')
+                .map(str::trim)
+                .filter(|source| !source.is_empty())
+                .map(str::to_string)
+                .collect(),
+            Err(_) => vec![nist::SOURCE_NAME.to_string()],
+        };
+
+        Ok(Self::new(slo, interval, sources))
+    }
+
+    pub fn statuses(&self) -> Vec<Status> {
+        self.statuses.read().unwrap().clone()
+    }
+
+    /// Ready as long as no source is stale, or before the first check.
+    pub fn is_ready(&self) -> bool {
+        self.statuses
+            .read()
+            .unwrap()
+            .iter()
+            .all(|status| !status.stale)
+    }
+
+    /// Updates the statuses, returning the ones that just became stale.
+    pub fn update(&self, statuses: Vec<Status>) -> Vec<Status> {
+        let mut current = self.statuses.write().unwrap();
+
+        let became_stale = statuses
+            .iter()
+            .filter(|status| status.stale)
+            .filter(|status| {
+                !current
+                    .iter()
+                    .any(|previous| previous.source == status.source && previous.stale)
+            })
+            .cloned()
+            .collect();
+
+        *current = statuses;
+        became_stale
+    }
+
+    fn check(&self, database: &Database) -> Result<Vec<Status>, String> {
+        let now = Utc::now();
+        let mut statuses = vec![];
+        for source in &self.sources {
+            let latest = database.get_latest_record_time(source)?;
+            statuses.push(Status::with(source, latest, now, self.slo));
+        }
+        Ok(statuses)
+    }
+
+    /// Checks the freshness of the sources periodically in the background, notifying the
+    /// ones becoming stale.
+    pub fn spawn(self: Arc<Self>, pool: Pool, notifier: Option<Notifier>) {
+        thread::spawn(move || loop {
+            let statuses = pool
+                .get()
+                .map_err(|e| e.to_string())
+                .and_then(|conn| self.check(&Database(conn)));
+
+            match statuses {
+                Ok(statuses) => {
+                    for status in self.update(statuses) {
+                        log::error!(
+                            "{} data is stale, the latest record is older than {}h",
+                            status.source,
+                            self.slo.as_secs() / 3600
+                        );
+                        if let Some(notifier) = &notifier {
+                            notifier.notify(&Event::DatasetStale {
+                                source: status.source,
+                                latest_record: status.latest_record,
+                                slo_hours: self.slo.as_secs() / 3600,
+                            });
+                        }
+                    }
+                }
+                Err(e) => log::warn!("could not check data freshness: {}", e),
+            }
+
+            thread::sleep(self.interval);
+        });
+    }
+
+    /// Metrics in the Prometheus text format, `kepler_dataset_stale` being the series to
+    /// alert on.
+    pub fn metrics(&self) -> String {
+        let statuses = self.statuses();
+        let mut metrics = String::new();
+
+        gauge(
+            &mut metrics,
+            "kepler_dataset_age_seconds",
+            "Age of the newest record of the source.",
+            statuses
+                .iter()
+                .filter_map(|status| status.age_secs.map(|age| (status.source.as_str(), age))),
+        );
+        gauge(
+            &mut metrics,
+            "kepler_dataset_stale",
+            "Whether the source breaches the freshness SLO.",
+            statuses
+                .iter()
+                .map(|status| (status.source.as_str(), status.stale as u64)),
+        );
+        gauge(
+            &mut metrics,
+            "kepler_dataset_freshness_slo_seconds",
+            "Freshness SLO of the sources.",
+            self.sources
+                .iter()
+                .map(|source| (source.as_str(), self.slo.as_secs())),
+        );
+
+        metrics
+    }
+}
+
+fn gauge<'a>(
+    metrics: &mut String,
+    name: &str,
+    help: &str,
+    values: impl Iterator<Item = (&'a str, u64)>,
+) {
+    let _ = writeln!(metrics, "# HELP {} {}", name, help);
+    let _ = writeln!(metrics, "# TYPE {} gauge", name);
+    for (source, value) in values {
+        let _ = writeln!(metrics, "{}{{source=\"{}\"}} {}", name, source, value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use chrono::{DateTime, Utc};
+
+    use super::{Freshness, Status};
+
+    const HOUR: Duration = Duration::from_secs(3600);
+
+    #[test]
+    fn can_detect_stale_sources() {
+        let now = DateTime::<Utc>::from(UNIX_EPOCH + Duration::from_secs(1_653_000_000));
+
+        let fresh = Status::with(
+            "NIST",
+            Some(now - chrono::Duration::hours(47)),
+            now,
+            48 * HOUR,
+        );
+        assert!(!fresh.stale);
+        assert_eq!(Some(47 * 3600), fresh.age_secs);
+
+        assert!(
+            Status::with(
+                "NIST",
+                Some(now - chrono::Duration::hours(49)),
+                now,
+                48 * HOUR
+            )
+            .stale
+        );
+        assert!(Status::with("NIST", None, now, 48 * HOUR).stale);
+    }
+
+    #[test]
+    fn can_flip_readiness() {
+        let now = Utc::now();
+        let freshness = Freshness::new(48 * HOUR, HOUR, vec!["NIST".into()]);
+        assert!(freshness.is_ready());
+
+        let stale = vec![Status::with(
+            "NIST",
+            Some(now - chrono::Duration::hours(72)),
+            now,
+            48 * HOUR,
+        )];
+        assert_eq!(1, freshness.update(stale.clone()).len());
+        assert!(!freshness.is_ready());
+        // only notified once
+        assert!(freshness.update(stale).is_empty());
+
+        freshness.update(vec![Status::with("NIST", Some(now), now, 48 * HOUR)]);
+        assert!(freshness.is_ready());
+
+        let metrics = freshness.metrics();
+        assert!(metrics.contains("kepler_dataset_stale{source=\"NIST\"} 0\n"));
+        assert!(metrics.contains("kepler_dataset_age_seconds{source=\"NIST\"} 0\n"));
+    }
+}
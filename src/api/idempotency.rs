@@ -0,0 +1,249 @@
+//! Idempotency keys of the write endpoints, so that the retries of a client after a network
+//! failure get the response of the first request instead of running it again.
+
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::time::{Duration, SystemTime};
+
+use actix_web::{
+    body, http::header, http::StatusCode, web, HttpRequest, HttpResponse, HttpResponseBuilder,
+};
+use ring::digest::{Context, SHA256};
+
+use crate::db::models;
+
+use super::{
+    error::ApplicationError,
+    scoring, timeout,
+    utils::{bad_request_body, handle_database_error, internal_server_error},
+    ApplicationContext,
+};
+
+pub const HEADER: &str = "Idempotency-Key";
+/// Header set on the responses replayed for a retry.
+pub const REPLAYED_HEADER: &str = "Idempotent-Replayed";
+
+const MAX_KEY_LENGTH: usize = 255;
+/// Time the responses are kept for the retries.
+const TTL: Duration = Duration::from_secs(24 * 3600);
+/// Time after which a request that never completed, likely because the server stopped,
+/// releases its key.
+const ABANDONED_TTL: Duration = Duration::from_secs(600);
+
+/// Idempotency key sent by the client, along with the fingerprint of its request.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Key {
+    pub key: String,
+    pub fingerprint: String,
+}
+
+impl Key {
+    /// Key of the request, if sent. The fingerprint covers what changes the response: the
+    /// route, the negotiated version, the scoring profile and the body.
+    pub fn from_request(req: &HttpRequest, body: &[u8]) -> Result<Option<Self>, ApplicationError> {
+        let value = match req.headers().get(HEADER) {
+            Some(value) => value,
+            None => return Ok(None),
+        };
+        let key = value
+            .to_str()
+            .ok()
+            .map(str::trim)
+            .filter(|key| !key.is_empty() && key.len() <= MAX_KEY_LENGTH)
+            .ok_or_else(|| bad_request_body(format!("invalid {} header", HEADER)))?;
+
+        let header_value = |name: &str| {
+            req.headers()
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .unwrap_or_default()
+        };
+        let fingerprint = fingerprint(&[
+            req.method().as_str().as_bytes(),
+            req.path().as_bytes(),
+            req.query_string().as_bytes(),
+            header_value(header::ACCEPT.as_str()).as_bytes(),
+            header_value(scoring::PROFILE_HEADER).as_bytes(),
+            body,
+        ]);
+
+        Ok(Some(Self {
+            key: key.to_string(),
+            fingerprint,
+        }))
+    }
+}
+
+fn fingerprint(parts: &[&[u8]]) -> String {
+    let mut context = Context::new(&SHA256);
+    for part in parts {
+        context.update(&(part.len() as u64).to_be_bytes());
+        context.update(part);
+    }
+    context
+        .finish()
+        .as_ref()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// Response of the request first sent with the key.
+fn replay(key: &Key, stored: models::IdempotencyKey) -> Result<HttpResponse, ApplicationError> {
+    if stored.fingerprint != key.fingerprint {
+        return Err(ApplicationError::UnprocessableEntity(format!(
+            "{} {} was already used for another request",
+            HEADER, key.key
+        )));
+    }
+    let status = match stored.status {
+        Some(status) => status,
+        None => {
+            return Err(ApplicationError::Conflict(format!(
+                "the request with {} {} is still running",
+                HEADER, key.key
+            )))
+        }
+    };
+
+    let status = StatusCode::from_u16(status as u16)
+        .map_err(|e| internal_server_error(format!("invalid stored status: {}", e)))?;
+    let mut response = HttpResponseBuilder::new(status);
+    if let Some(headers) = stored.headers {
+        for (name, value) in headers.0.iter() {
+            response.insert_header((name.as_str(), value.as_str()));
+        }
+    }
+    response.insert_header((REPLAYED_HEADER, "true"));
+
+    Ok(response.body(stored.body.unwrap_or_default()))
+}
+
+/// Runs the handler once per idempotency key: retries with the same key get the stored
+/// response, while the failed requests release their key so that they can be retried.
+pub async fn once<F>(
+    ctx: web::Data<ApplicationContext>,
+    key: Option<Key>,
+    handler: F,
+) -> Result<HttpResponse, ApplicationError>
+where
+    F: Future<Output = Result<HttpResponse, ApplicationError>>,
+{
+    let key = match key {
+        Some(key) => key,
+        None => return handler.await,
+    };
+    let timeout = ctx.timeouts.default;
+
+    let claimed = timeout::block(timeout, {
+        let (ctx, key) = (ctx.clone(), key.clone());
+        move || {
+            let database = ctx.get_database(timeout).map_err(handle_database_error)?;
+            let now = SystemTime::now();
+            database
+                .claim_idempotency_key(
+                    models::NewIdempotencyKey::with(key.key, key.fingerprint),
+                    (now - TTL).into(),
+                    (now - ABANDONED_TTL).into(),
+                )
+                .map_err(internal_server_error)
+        }
+    })
+    .await?;
+
+    if let Some(stored) = claimed {
+        return replay(&key, stored);
+    }
+
+    let completed = match handler.await {
+        Ok(response) if !response.status().is_server_error() => {
+            let status = response.status();
+            let headers: BTreeMap<String, String> = response
+                .headers()
+                .iter()
+                .filter_map(|(name, value)| {
+                    value
+                        .to_str()
+                        .ok()
+                        .map(|value| (name.to_string(), value.to_string()))
+                })
+                .collect();
+            match body::to_bytes(response.into_body()).await {
+                Ok(bytes) => {
+                    let mut response = HttpResponseBuilder::new(status);
+                    for (name, value) in &headers {
+                        response.insert_header((name.as_str(), value.as_str()));
+                    }
+                    Ok((response.body(bytes.clone()), Some((headers, bytes))))
+                }
+                Err(e) => Err(internal_server_error(e.to_string())),
+            }
+        }
+        Ok(response) => Ok((response, None)),
+        Err(err) => Err(err),
+    };
+
+    let stored = completed
+        .as_ref()
+        .ok()
+        .and_then(|(response, stored)| stored.clone().map(|s| (response.status().as_u16(), s)));
+    let stored = timeout::block(timeout, move || {
+        let database = ctx.get_database(timeout).map_err(handle_database_error)?;
+        match stored {
+            Some((status, (headers, bytes))) => database
+                .complete_idempotency_key(&key.key, status as i32, headers, &bytes)
+                .map_err(internal_server_error),
+            None => database
+                .release_idempotency_key(&key.key)
+                .map_err(internal_server_error),
+        }
+    })
+    .await;
+    // the response is still returned, the retries will run the request again
+    if let Err(err) = stored {
+        log::warn!("could not store the idempotent response: {}", err);
+    }
+
+    completed.map(|(response, _)| response)
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::test::TestRequest;
+
+    use super::{Key, HEADER};
+
+    #[test]
+    fn can_fingerprint_requests() {
+        let req = TestRequest::post()
+            .uri("/scan/syft")
+            .insert_header((HEADER, "4b1d3f1e"))
+            .to_http_request();
+
+        let key = Key::from_request(&req, b"{}").unwrap().unwrap();
+        assert_eq!("4b1d3f1e", key.key);
+        assert_eq!(key, Key::from_request(&req, b"{}").unwrap().unwrap());
+        assert_ne!(
+            key.fingerprint,
+            Key::from_request(&req, b"[]").unwrap().unwrap().fingerprint
+        );
+
+        let req = TestRequest::post()
+            .uri("/v2/scan/syft")
+            .insert_header((HEADER, "4b1d3f1e"))
+            .to_http_request();
+        assert_ne!(
+            key.fingerprint,
+            Key::from_request(&req, b"{}").unwrap().unwrap().fingerprint
+        );
+
+        let req = TestRequest::post().uri("/scan/syft").to_http_request();
+        assert_eq!(None, Key::from_request(&req, b"{}").unwrap());
+
+        let req = TestRequest::post()
+            .uri("/scan/syft")
+            .insert_header((HEADER, ""))
+            .to_http_request();
+        assert!(Key::from_request(&req, b"{}").is_err());
+    }
+}
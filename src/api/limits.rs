@@ -0,0 +1,91 @@
+use std::env;
+
+use serde::de::DeserializeOwned;
+
+const DEFAULT_BODY_SIZE: usize = 16 * 1024 * 1024;
+const DEFAULT_JSON_DEPTH: usize = 32;
+
+/// Limits on the request bodies, configured with the `KEPLER_MAX_BODY_SIZE` (in bytes) and
+/// `KEPLER_MAX_JSON_DEPTH` environment variables.
+#[derive(Debug, Clone, Copy)]
+pub struct Limits {
+    pub body_size: usize,
+    pub json_depth: usize,
+}
+
+impl Limits {
+    pub fn from_env() -> Self {
+        Self {
+            body_size: from_env("KEPLER_MAX_BODY_SIZE", DEFAULT_BODY_SIZE),
+            json_depth: from_env("KEPLER_MAX_JSON_DEPTH", DEFAULT_JSON_DEPTH),
+        }
+    }
+
+    /// Deserializes a JSON body, refusing documents nested deeper than the limit.
+    pub fn parse_json<T: DeserializeOwned>(&self, body: &[u8]) -> Result<T, String> {
+        check_depth(body, self.json_depth)?;
+        serde_json::from_slice(body).map_err(|e| format!("invalid JSON body: {}", e))
+    }
+}
+
+fn from_env(name: &str, default: usize) -> usize {
+    match env::var(name) {
+        Ok(value) => match value.parse::<usize>() {
+            Ok(limit) if limit > 0 => limit,
+            _ => {
+                log::warn!("Failed to parse {}. Using default {}", name, default);
+                default
+            }
+        },
+        Err(_) => default,
+    }
+}
+
+/// Checks the nesting of the arrays and objects of a JSON document without parsing it, so
+/// that deeply nested documents are refused before allocating anything for them.
+pub fn check_depth(input: &[u8], max: usize) -> Result<(), String> {
+    let mut depth = 0usize;
+    let mut in_string = false;
+    let mut escaped = false;
+
+    for &c in input {
+        if in_string {
+            if escaped {
+                escaped = false;
+            } else if c == b'\\' {
+                escaped = true;
+            } else if c == b'"' {
+                in_string = false;
+            }
+            continue;
+        }
+
+        match c {
+            b'"' => in_string = true,
+            b'{' | b'[' => {
+                depth += 1;
+                if depth > max {
+                    return Err(format!("JSON body nested deeper than {} levels", max));
+                }
+            }
+            b'}' | b']' => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::check_depth;
+
+    #[test]
+    fn can_check_json_depth() {
+        assert!(check_depth(br#"{"product": "libxml2"}"#, 1).is_ok());
+        assert!(check_depth(br#"[{"product": "libxml2"}]"#, 1).is_err());
+        assert!(check_depth(br#"[{"product": "[[[[libxml2"}]"#, 2).is_ok());
+        assert!(check_depth(br#"[{"product": "\"[[[["}]"#, 2).is_ok());
+        assert!(check_depth(&[b'['; 100], 32).is_err());
+    }
+}
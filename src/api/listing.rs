@@ -0,0 +1,96 @@
+//! Paginated listings of the CVE and product identifiers, for external indexers to mirror
+//! the content systematically.
+
+use serde::{Deserialize, Serialize};
+
+/// Number of identifiers per page, unless requested otherwise.
+pub const DEFAULT_PAGE_SIZE: i64 = 1000;
+pub const MAX_PAGE_SIZE: i64 = 10000;
+
+#[derive(Debug, Deserialize)]
+pub struct PageParams {
+    /// Last identifier of the previous page.
+    pub after: Option<String>,
+    pub limit: Option<i64>,
+}
+
+impl PageParams {
+    pub fn limit(&self) -> Result<i64, String> {
+        match self.limit {
+            None => Ok(DEFAULT_PAGE_SIZE),
+            Some(limit) if limit > 0 && limit <= MAX_PAGE_SIZE => Ok(limit),
+            Some(limit) => Err(format!(
+                "invalid limit {}, expected 1 to {}",
+                limit, MAX_PAGE_SIZE
+            )),
+        }
+    }
+}
+
+/// Identifier along with the revision of the dataset that last modified it.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct Entry {
+    pub id: String,
+    pub revision: i32,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct Page {
+    pub items: Vec<Entry>,
+    /// Identifier to request the next page after, unless this is the last one.
+    pub next: Option<String>,
+}
+
+impl Page {
+    pub fn with(items: Vec<Entry>, limit: i64) -> Self {
+        let next = if items.len() as i64 == limit {
+            items.last().map(|entry| entry.id.clone())
+        } else {
+            None
+        };
+        Self { items, next }
+    }
+}
+
+/// Identifier of a product, as `vendor:product` like in the CPE names.
+pub fn product_id(vendor: &str, product: &str) -> String {
+    format!("{}:{}", vendor, product)
+}
+
+pub fn parse_product_id(id: &str) -> Result<(&str, &str), String> {
+    id.split_once(':')
+        .ok_or_else(|| format!("invalid product {}, expected vendor:product", id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_product_id, product_id, Entry, Page};
+
+    fn entry(id: &str) -> Entry {
+        Entry {
+            id: id.to_string(),
+            revision: 1,
+        }
+    }
+
+    #[test]
+    fn can_paginate() {
+        let page = Page::with(vec![entry("CVE-2021-0001"), entry("CVE-2022-0001")], 2);
+        assert_eq!(Some("CVE-2022-0001".to_string()), page.next);
+
+        let page = Page::with(vec![entry("CVE-2022-0002")], 2);
+        assert_eq!(None, page.next);
+    }
+
+    #[test]
+    fn can_parse_product_ids() {
+        let id = product_id("busybox", "busybox");
+        assert_eq!("busybox:busybox", id);
+        assert_eq!(Ok(("busybox", "busybox")), parse_product_id(&id));
+        assert_eq!(
+            Ok(("npm", "@types/node")),
+            parse_product_id("npm:@types/node")
+        );
+        assert!(parse_product_id("busybox").is_err());
+    }
+}
@@ -0,0 +1,319 @@
+//! Mirror of the NIST data feeds downloaded by the imports, so that the other instances and
+//! tools can fetch them from kepler instead of each hitting the NVD.
+
+use std::collections::HashMap;
+use std::env;
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+use actix_web::{http::header, web, HttpRequest, HttpResponse};
+use chrono::{DateTime, Utc};
+use flate2::read::GzDecoder;
+use ring::digest::{digest, Context, SHA256};
+use serde::Serialize;
+
+use super::{error::ApplicationError, timeout, utils::internal_server_error, ApplicationContext};
+use crate::sources::nist::cve::{parse_meta_sha256, VERSION};
+use crate::utils::sha256_file;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Artifact {
+    /// Like `nvdcve-1.1-2024.json.gz`.
+    pub name: String,
+    pub size: u64,
+    pub modified: DateTime<Utc>,
+    /// Uppercase hex SHA-256 of the file.
+    pub sha256: String,
+    /// SHA-256 of the uncompressed feed, as published by its `.meta` file.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub uncompressed_sha256: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct Artifacts {
+    artifacts: Vec<Artifact>,
+}
+
+/// Checksums of a file, along with the modification time and the size they're computed for.
+#[derive(Debug, Clone)]
+struct Checksums {
+    modified: SystemTime,
+    size: u64,
+    sha256: String,
+    /// Only computed for the feeds.
+    uncompressed_sha256: Option<String>,
+}
+
+/// Whether the file is a feed or the `.meta` file of one, like `nvdcve-1.1-2024.json.gz`
+/// or `nvdcve-1.1-modified.meta`, returning the name of the feed.
+fn feed_name(name: &str) -> Option<(&str, bool)> {
+    let prefix = format!("nvdcve-{}-", VERSION);
+    let rest = name.strip_prefix(&prefix)?;
+    let (feed, compressed) = match rest.strip_suffix(".json.gz") {
+        Some(feed) => (feed, true),
+        None => (rest.strip_suffix(".meta")?, false),
+    };
+    if feed.is_empty() || !feed.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return None;
+    }
+    Some((&name[..prefix.len() + feed.len()], compressed))
+}
+
+/// Uppercase hex SHA-256 of the uncompressed content of the file.
+fn sha256_gunzip(file_name: &Path) -> Result<String, String> {
+    let file = File::open(file_name)
+        .map_err(|e| format!("could not open {}: {}", file_name.display(), e))?;
+    let mut decoder = GzDecoder::new(file);
+
+    let mut context = Context::new(&SHA256);
+    let mut buffer = [0u8; 64 * 1024];
+    loop {
+        let read = decoder
+            .read(&mut buffer)
+            .map_err(|e| format!("could not decompress {}: {}", file_name.display(), e))?;
+        if read == 0 {
+            break;
+        }
+        context.update(&buffer[..read]);
+    }
+
+    Ok(hex(context.finish().as_ref()))
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02X}", b)).collect()
+}
+
+/// Feeds of the data path of the NIST imports, only served once verified against their
+/// `.meta` file, so that the ones being downloaded or corrupted are left out.
+pub struct Mirror {
+    path: PathBuf,
+    checksums: Mutex<HashMap<String, Checksums>>,
+}
+
+impl Mirror {
+    /// Mirror of the `KEPLER_MIRROR_PATH` directory, if set.
+    pub fn from_env() -> Result<Option<Self>, String> {
+        let path = match env::var("KEPLER_MIRROR_PATH") {
+            Ok(path) => PathBuf::from(path),
+            Err(_) => return Ok(None),
+        };
+        if !path.is_dir() {
+            return Err(format!(
+                "invalid KEPLER_MIRROR_PATH: {} is not a directory",
+                path.display()
+            ));
+        }
+        Ok(Some(Self {
+            path,
+            checksums: Mutex::new(HashMap::new()),
+        }))
+    }
+
+    /// Checksums of the file, computed again only once it changed.
+    fn checksums(&self, name: &str, compressed: bool) -> Result<Option<Checksums>, String> {
+        let path = self.path.join(name);
+        let metadata = match fs::metadata(&path) {
+            Ok(metadata) if metadata.is_file() => metadata,
+            _ => return Ok(None),
+        };
+        let modified = metadata
+            .modified()
+            .map_err(|e| format!("could not read {}: {}", path.display(), e))?;
+        let size = metadata.len();
+
+        if let Some(checksums) = self.checksums.lock().unwrap().get(name) {
+            if checksums.modified == modified && checksums.size == size {
+                return Ok(Some(checksums.clone()));
+            }
+        }
+
+        let uncompressed_sha256 = if compressed {
+            // corrupted or still being downloaded
+            match sha256_gunzip(&path) {
+                Ok(sha256) => Some(sha256),
+                Err(e) => {
+                    log::warn!("{}", e);
+                    return Ok(None);
+                }
+            }
+        } else {
+            None
+        };
+        let checksums = Checksums {
+            modified,
+            size,
+            sha256: sha256_file(&path)?,
+            uncompressed_sha256,
+        };
+        self.checksums
+            .lock()
+            .unwrap()
+            .insert(name.to_string(), checksums.clone());
+        Ok(Some(checksums))
+    }
+
+    /// The feed or `.meta` file, unless missing, or a feed not matching its `.meta` file.
+    pub fn artifact(&self, name: &str) -> Result<Option<Artifact>, String> {
+        let (feed, compressed) = match feed_name(name) {
+            Some(feed) => feed,
+            None => return Ok(None),
+        };
+        let checksums = match self.checksums(name, compressed)? {
+            Some(checksums) => checksums,
+            None => return Ok(None),
+        };
+
+        if compressed {
+            let meta_file_name = self.path.join(format!("{}.meta", feed));
+            let expected = fs::read_to_string(&meta_file_name)
+                .ok()
+                .and_then(|meta| parse_meta_sha256(&meta));
+            if expected.is_none() || expected != checksums.uncompressed_sha256 {
+                return Ok(None);
+            }
+        }
+
+        Ok(Some(Artifact {
+            name: name.to_string(),
+            size: checksums.size,
+            modified: checksums.modified.into(),
+            sha256: checksums.sha256,
+            uncompressed_sha256: checksums.uncompressed_sha256,
+        }))
+    }
+
+    /// The feeds and `.meta` files served, by name.
+    pub fn artifacts(&self) -> Result<Vec<Artifact>, String> {
+        let entries = fs::read_dir(&self.path)
+            .map_err(|e| format!("could not list {}: {}", self.path.display(), e))?;
+        let mut names: Vec<String> = entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.file_name().to_string_lossy().into_owned())
+            .filter(|name| feed_name(name).is_some())
+            .collect();
+        names.sort();
+
+        let mut artifacts = vec![];
+        for name in names {
+            artifacts.extend(self.artifact(&name)?);
+        }
+        Ok(artifacts)
+    }
+
+    /// Content of the artifact, unless it changed since its checksum was computed.
+    fn read(&self, artifact: &Artifact) -> Result<Option<Vec<u8>>, String> {
+        let path = self.path.join(&artifact.name);
+        let content =
+            fs::read(&path).map_err(|e| format!("could not read {}: {}", path.display(), e))?;
+        if hex(digest(&SHA256, &content).as_ref()) != artifact.sha256 {
+            return Ok(None);
+        }
+        Ok(Some(content))
+    }
+}
+
+fn mirror(ctx: &ApplicationContext) -> Result<&Mirror, ApplicationError> {
+    ctx.mirror
+        .as_ref()
+        .ok_or_else(|| ApplicationError::NotFound("the mirror is not enabled".to_string()))
+}
+
+/// Whether the `If-None-Match` header of the request matches the entity tag.
+fn is_not_modified(req: &HttpRequest, etag: &str) -> bool {
+    req.headers()
+        .get(header::IF_NONE_MATCH)
+        .and_then(|value| value.to_str().ok())
+        .map_or(false, |value| {
+            value
+                .split(',')
+                .map(|tag| tag.trim().trim_start_matches("W/"))
+                .any(|tag| tag == "*" || tag == etag)
+        })
+}
+
+/// Lists the feeds and `.meta` files served with their checksums, for the consumers to only
+/// fetch the ones they don't have yet.
+pub async fn nist(ctx: web::Data<ApplicationContext>) -> Result<HttpResponse, ApplicationError> {
+    mirror(&ctx)?;
+    let timeout = ctx.timeouts.default;
+
+    let artifacts = timeout::block(timeout, move || {
+        mirror(&ctx)?.artifacts().map_err(internal_server_error)
+    })
+    .await?;
+
+    Ok(HttpResponse::Ok().json(Artifacts { artifacts }))
+}
+
+/// Serves a feed or a `.meta` file, tagged with its SHA-256 so that the consumers can
+/// verify it and fetch it again only once changed.
+pub async fn nist_file(
+    ctx: web::Data<ApplicationContext>,
+    req: HttpRequest,
+    name: web::Path<String>,
+) -> Result<HttpResponse, ApplicationError> {
+    mirror(&ctx)?;
+    let timeout = ctx.timeouts.default;
+    let name = name.into_inner();
+
+    let artifact = timeout::block(timeout, {
+        let ctx = ctx.clone();
+        let name = name.clone();
+        move || mirror(&ctx)?.artifact(&name).map_err(internal_server_error)
+    })
+    .await?
+    .ok_or_else(|| ApplicationError::NotFound(format!("{} is not mirrored", name)))?;
+
+    let etag = format!("\"{}\"", artifact.sha256);
+    if is_not_modified(&req, &etag) {
+        return Ok(HttpResponse::NotModified()
+            .insert_header((header::ETAG, etag))
+            .finish());
+    }
+
+    let (artifact, content) = timeout::block(timeout, move || {
+        let content = mirror(&ctx)?
+            .read(&artifact)
+            .map_err(internal_server_error)?;
+        Ok((artifact, content))
+    })
+    .await?;
+    // replaced by an import in the meantime
+    let content = content.ok_or(ApplicationError::ServiceUnavailable)?;
+
+    let content_type = if artifact.name.ends_with(".gz") {
+        "application/gzip"
+    } else {
+        "text/plain"
+    };
+    Ok(HttpResponse::Ok()
+        .content_type(content_type)
+        .insert_header((header::ETAG, etag))
+        .insert_header(("X-Checksum-Sha256", artifact.sha256))
+        .body(content))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::feed_name;
+
+    #[test]
+    fn can_name_feeds() {
+        assert_eq!(
+            Some(("nvdcve-1.1-2024", true)),
+            feed_name("nvdcve-1.1-2024.json.gz")
+        );
+        assert_eq!(
+            Some(("nvdcve-1.1-modified", false)),
+            feed_name("nvdcve-1.1-modified.meta")
+        );
+        assert_eq!(None, feed_name("nvdcve-1.1-2024.json"));
+        assert_eq!(None, feed_name("nvdcve-1.1-.meta"));
+        assert_eq!(None, feed_name("nvdcve-1.1-../secret.meta"));
+        assert_eq!(None, feed_name("epss_scores-current.csv.gz"));
+    }
+}
@@ -1,4 +1,7 @@
+use std::collections::BTreeMap;
 use std::env;
+use std::sync::Arc;
+use std::time::Duration;
 
 use actix_cors::Cors;
 use actix_web::{
@@ -9,13 +12,37 @@ use actix_web::{
 
 use serde::Serialize;
 
-use crate::db::{Database, Pool};
+use crate::bus::Bus;
+use crate::db::{models::ImportFilter, Database, Pool, TimedDatabase};
+use crate::notify::Notifier;
+use crate::scan::{attestation, rekor};
+use crate::search::{bloom, preload, profile, warmup};
 
+mod access;
+mod admin;
+mod assets;
+mod cpe;
+mod crash;
 mod cves;
+mod discovery;
 mod error;
+mod exports;
+mod freshness;
+mod idempotency;
+mod limits;
+mod listing;
+mod mirror;
 mod products;
+mod query_log;
+mod rate_limit;
+mod scans;
+mod scoring;
 mod telemetry;
+mod timeout;
+mod usage;
 mod utils;
+mod v2;
+mod version;
 
 pub use telemetry::init_logger;
 
@@ -34,22 +61,98 @@ pub fn run(pool: Pool) -> Result<Server, anyhow::Error> {
             8000
         });
 
-    let application_ctx = Data::new(ApplicationContext { pool });
+    let limits = limits::Limits::from_env();
+
+    if let Some(interval) = preload::interval_from_env().map_err(anyhow::Error::msg)? {
+        preload::start(pool.clone(), interval).map_err(anyhow::Error::msg)?;
+    }
+    if let Some(interval) = bloom::interval_from_env().map_err(anyhow::Error::msg)? {
+        bloom::start(pool.clone(), interval).map_err(anyhow::Error::msg)?;
+    }
+    if let Some((count, interval)) = warmup::from_env().map_err(anyhow::Error::msg)? {
+        warmup::start(pool.clone(), count, interval).map_err(anyhow::Error::msg)?;
+    }
+
+    let freshness = Arc::new(freshness::Freshness::from_env().map_err(anyhow::Error::msg)?);
+    freshness.clone().spawn(pool.clone(), Notifier::from_env());
+
+    let usage = Arc::new(usage::Usage::from_env().map_err(anyhow::Error::msg)?);
+    usage.clone().spawn(pool.clone());
+
+    let query_log = Arc::new(query_log::QueryLog::from_env().map_err(anyhow::Error::msg)?);
+    query_log.clone().spawn(pool.clone());
+
+    let application_ctx = Data::new(ApplicationContext {
+        pool,
+        timeouts: timeout::Timeouts::from_env(),
+        limits,
+        profiles: profile::load().map_err(anyhow::Error::msg)?,
+        signer: attestation::Signer::from_env().map_err(anyhow::Error::msg)?,
+        rekor: rekor::Rekor::from_env(),
+        freshness,
+        usage,
+        query_log,
+        bus: Bus::from_env().map_err(anyhow::Error::msg)?,
+        mirror: mirror::Mirror::from_env().map_err(anyhow::Error::msg)?,
+        contact: discovery::contact_from_env().map_err(anyhow::Error::msg)?,
+    });
+
+    let rules = access::Rules::from_env().map_err(anyhow::Error::msg)?;
+    let limiter = rate_limit::Limiter::from_env()
+        .map_err(anyhow::Error::msg)?
+        .map(Arc::new);
 
     let server = HttpServer::new(move || {
         App::new()
             .app_data(application_ctx.clone())
+            .app_data(web::PayloadConfig::new(limits.body_size))
             .route("/health_check", web::get().to(health_check))
+            .route("/version", web::get().to(build_info))
+            .route("/readiness", web::get().to(readiness))
+            .route("/metrics", web::get().to(metrics))
+            .route("/telemetry", web::get().to(telemetry))
+            .route("/stats", web::get().to(stats))
+            .route(
+                "/.well-known/kepler.json",
+                web::get().to(discovery::metadata),
+            )
             .service(
-                web::scope("/cve") //
-                    .route("/search", web::post().to(cves::search)), // List of connected agent
+                web::scope("/admin")
+                    .wrap(rules.guard("admin"))
+                    .route("/imports", web::get().to(admin::imports))
+                    .route("/query_log", web::get().to(query_log::export))
+                    .route("/display_names", web::get().to(admin::display_names))
+                    .route(
+                        "/display_names/{vendor}",
+                        web::put().to(admin::set_display_name),
+                    )
+                    .route(
+                        "/display_names/{vendor}",
+                        web::delete().to(admin::delete_display_name),
+                    )
+                    .route(
+                        "/display_names/{vendor}/{product}",
+                        web::put().to(admin::set_display_name),
+                    )
+                    .route(
+                        "/display_names/{vendor}/{product}",
+                        web::delete().to(admin::delete_display_name),
+                    ),
             )
             .service(
-                web::scope("/products") //
-                    .route("/", web::get().to(products::all)) // List of connected agent
-                    .route("/by_vendor", web::get().to(products::by_vendor)) // Agent detail
-                    .route("/search/{query}", web::get().to(products::search)), // Monitor agent
+                web::scope("/mirror")
+                    .wrap(rules.guard("mirror"))
+                    .route("/nist", web::get().to(mirror::nist))
+                    .route("/nist/{name}", web::get().to(mirror::nist_file)),
             )
+            .service(
+                web::scope("/v2")
+                    .app_data(version::ApiVersion::V2)
+                    .configure(|cfg| routes(cfg, &rules)),
+            )
+            .configure(|cfg| routes(cfg, &rules))
+            .wrap(crash::CatchPanic)
+            .wrap(rate_limit::RateLimit(limiter.clone()))
             .wrap(Cors::permissive())
             .wrap(tracing_actix_web::TracingLogger::default())
     })
@@ -58,14 +161,125 @@ pub fn run(pool: Pool) -> Result<Server, anyhow::Error> {
     Ok(server)
 }
 
+/// Loads the settings of the API server like it does when starting, returning the error of
+/// every invalid one by name.
+pub fn check_settings() -> Vec<(&'static str, String)> {
+    let port = match env::var("KEPLER_PORT") {
+        Ok(port) if port.parse::<u16>().is_err() => Err(format!("invalid KEPLER_PORT: {}", port)),
+        _ => Ok(()),
+    };
+
+    vec![
+        ("port", port),
+        ("freshness", freshness::Freshness::from_env().map(|_| ())),
+        ("access rules", access::Rules::from_env().map(|_| ())),
+        ("rate limit", rate_limit::Limiter::from_env().map(|_| ())),
+        ("preload", preload::interval_from_env().map(|_| ())),
+        ("bloom filter", bloom::interval_from_env().map(|_| ())),
+        ("usage statistics", usage::Usage::from_env().map(|_| ())),
+        ("mirror", mirror::Mirror::from_env().map(|_| ())),
+    ]
+    .into_iter()
+    .filter_map(|(name, result)| result.err().map(|e| (name, e)))
+    .collect()
+}
+
+/// Registers the API routes, shared by all the versions of the API.
+fn routes(cfg: &mut web::ServiceConfig, rules: &access::Rules) {
+    cfg.service(
+        web::scope("/cve")
+            .wrap(rules.guard("cve"))
+            .route("/search", web::post().to(cves::search)) // List of connected agent
+            .route("/query", web::post().to(cves::query))
+            .route("/compose", web::post().to(cves::compose))
+            .route("/pending", web::get().to(cves::pending))
+            .route("/changes", web::get().to(cves::changes))
+            .route("/ids", web::get().to(cves::ids))
+            .route("/{id}/related", web::get().to(cves::related))
+            .route("/{id}/aliases", web::get().to(cves::aliases))
+            .route(
+                "/{id}/attack_patterns",
+                web::get().to(cves::attack_patterns),
+            ),
+    )
+    .service(
+        web::scope("/cpe")
+            .wrap(rules.guard("cpe"))
+            .route("/suggest", web::post().to(cpe::suggest)),
+    )
+    .service(
+        web::scope("/export")
+            .wrap(rules.guard("export"))
+            .route("/defectdojo", web::post().to(exports::defectdojo))
+            .route("/stix", web::post().to(exports::stix)),
+    )
+    .service(
+        web::scope("/scan")
+            .wrap(rules.guard("scan"))
+            .route("", web::post().to(scans::detect))
+            .route("/{format}", web::post().to(scans::scan))
+            .route("/{format}/attestation", web::post().to(scans::attest)),
+    )
+    .service(
+        web::scope("/assets")
+            .wrap(rules.guard("assets"))
+            .route("", web::post().to(assets::create))
+            .route("", web::get().to(assets::all))
+            .route("/rollups/{label}", web::get().to(assets::rollups))
+            .route("/deleted", web::get().to(assets::deleted))
+            .route("/manifest", web::get().to(assets::manifest))
+            .route("/manifest", web::put().to(assets::apply))
+            .route("/{id}", web::get().to(assets::get))
+            .route("/{id}", web::delete().to(assets::delete))
+            .route("/{id}/restore", web::post().to(assets::restore))
+            .route("/{id}/cves", web::get().to(assets::cves))
+            .route("/{id}/findings", web::get().to(assets::findings))
+            .route("/{id}/labels", web::put().to(assets::labels))
+            .route("/{id}/releases", web::post().to(assets::create_release))
+            .route("/{id}/releases", web::get().to(assets::releases))
+            .route(
+                "/{id}/releases/affected/{cve}",
+                web::get().to(assets::affected_releases),
+            )
+            .route("/{id}/scan", web::post().to(assets::detect))
+            .route("/{id}/scan/{format}", web::post().to(assets::scan)),
+    )
+    .service(
+        web::scope("/products")
+            .wrap(rules.guard("products"))
+            .route("/", web::get().to(products::all)) // List of connected agent
+            .route("/by_vendor", web::get().to(products::by_vendor)) // Agent detail
+            .route("/ids", web::get().to(products::ids))
+            .route(
+                "/dictionary/{vendor}/{product}",
+                web::get().to(products::dictionary),
+            )
+            .route("/search/{query}", web::get().to(products::search)), // Monitor agent
+    );
+}
+
 pub struct ApplicationContext {
     pool: Pool,
+    timeouts: timeout::Timeouts,
+    limits: limits::Limits,
+    profiles: profile::Profiles,
+    signer: Option<attestation::Signer>,
+    rekor: Option<rekor::Rekor>,
+    freshness: Arc<freshness::Freshness>,
+    usage: Arc<usage::Usage>,
+    query_log: Arc<query_log::QueryLog>,
+    bus: Option<Bus>,
+    mirror: Option<mirror::Mirror>,
+    /// Contact of the operators, advertised by the instance metadata.
+    contact: Option<String>,
 }
 
 impl ApplicationContext {
-    pub fn get_database(&self) -> Result<Database, r2d2::Error> {
-        let pool = self.pool.get()?;
-        Ok(Database(pool))
+    /// Gets a connection from the pool, whose queries are cancelled after the timeout.
+    /// The timeout only applies while the connection is in use, it's reset once returned.
+    pub fn get_database(&self, timeout: Duration) -> Result<TimedDatabase, r2d2::Error> {
+        let database = Database(self.pool.get_timeout(timeout)?);
+        Ok(TimedDatabase::new(database, timeout))
     }
 }
 
@@ -79,3 +293,123 @@ async fn health_check() -> HttpResponse {
         version: crate::version(),
     })
 }
+
+#[derive(Debug, Serialize)]
+struct BuildInfo {
+    version: &'static str,
+    git_commit: Option<&'static str>,
+    build_date: &'static str,
+    features: Vec<&'static str>,
+    api_versions: Vec<&'static str>,
+}
+
+/// Reports the build of the server and the versions of the API it serves, for the clients
+/// to check their compatibility.
+async fn build_info() -> HttpResponse {
+    HttpResponse::Ok().json(BuildInfo {
+        version: crate::version(),
+        git_commit: crate::git_commit(),
+        build_date: crate::build_date(),
+        features: crate::features(),
+        api_versions: version::ApiVersion::ALL
+            .iter()
+            .map(|version| version.name())
+            .collect(),
+    })
+}
+
+#[derive(Debug, Serialize)]
+struct Readiness {
+    ready: bool,
+    datasets: Vec<freshness::Status>,
+}
+
+/// Not ready while the imported data breaches the freshness SLO.
+async fn readiness(ctx: web::Data<ApplicationContext>) -> HttpResponse {
+    let readiness = Readiness {
+        ready: ctx.freshness.is_ready(),
+        datasets: ctx.freshness.statuses(),
+    };
+
+    if readiness.ready {
+        HttpResponse::Ok().json(readiness)
+    } else {
+        HttpResponse::ServiceUnavailable().json(readiness)
+    }
+}
+
+async fn metrics(ctx: web::Data<ApplicationContext>) -> HttpResponse {
+    HttpResponse::Ok()
+        .content_type("text/plain; version=0.0.4")
+        .body(ctx.freshness.metrics())
+}
+
+#[derive(Debug, Serialize)]
+struct Telemetry<'a> {
+    enabled: bool,
+    endpoint: Option<&'a str>,
+    report: usage::Report,
+}
+
+/// Shows the anonymous usage statistics exactly as they would be sent, whether they're
+/// enabled or not.
+async fn telemetry(
+    ctx: web::Data<ApplicationContext>,
+) -> Result<HttpResponse, error::ApplicationError> {
+    let timeout = ctx.timeouts.default;
+
+    let report = timeout::block(timeout, {
+        let ctx = ctx.clone();
+        move || {
+            let database = ctx
+                .get_database(timeout)
+                .map_err(utils::handle_database_error)?;
+            ctx.usage
+                .report(&database)
+                .map_err(utils::internal_server_error)
+        }
+    })
+    .await?;
+
+    Ok(HttpResponse::Ok().json(Telemetry {
+        enabled: ctx.usage.endpoint().is_some(),
+        endpoint: ctx.usage.endpoint(),
+        report,
+    }))
+}
+
+#[derive(Debug, Serialize)]
+struct Stats {
+    records: i64,
+    sources: BTreeMap<String, i64>,
+    imports: Vec<ImportFilter>,
+}
+
+/// Shows the number of records of each source, along with the allowlists the imports were
+/// filtered by.
+async fn stats(
+    ctx: web::Data<ApplicationContext>,
+) -> Result<HttpResponse, error::ApplicationError> {
+    let timeout = ctx.timeouts.default;
+
+    let stats = timeout::block(timeout, move || {
+        let database = ctx
+            .get_database(timeout)
+            .map_err(utils::handle_database_error)?;
+        let sources = database
+            .count_cves_by_source()
+            .map_err(utils::internal_server_error)?;
+        let imports = database
+            .get_import_filters()
+            .map_err(utils::internal_server_error)?;
+
+        Ok(Stats {
+            records: sources.values().sum(),
+            sources,
+            imports,
+        })
+    })
+    .await?;
+
+    Ok(HttpResponse::Ok().json(stats))
+}
@@ -1,38 +1,43 @@
 use actix_web::{web, HttpResponse};
+use serde::Serialize;
 use std::collections::HashMap;
 
+use crate::db::models::CpeEntry;
+use crate::sources::nist::dictionary;
+
 use super::{
     error::ApplicationError,
-    utils::{
-        bad_request_body, handle_blocking_error, handle_database_error, internal_server_error,
-        ok_to_json,
-    },
+    listing::{self, Entry, Page, PageParams},
+    timeout,
+    utils::{bad_request_body, handle_database_error, internal_server_error, ok_to_json},
     ApplicationContext,
 };
 
 pub async fn all(ctx: web::Data<ApplicationContext>) -> Result<HttpResponse, ApplicationError> {
-    web::block(move || {
-        ctx.get_database()
+    let timeout = ctx.timeouts.default;
+
+    timeout::block(timeout, move || {
+        ctx.get_database(timeout)
             .map_err(handle_database_error)?
             .get_products()
             .map_err(internal_server_error)
     })
     .await
-    .map_err(handle_blocking_error)?
     .map(ok_to_json)
 }
 
 pub async fn by_vendor(
     ctx: web::Data<ApplicationContext>,
 ) -> Result<HttpResponse, ApplicationError> {
-    let products = web::block(move || {
-        ctx.get_database()
+    let timeout = ctx.timeouts.default;
+
+    let products = timeout::block(timeout, move || {
+        ctx.get_database(timeout)
             .map_err(handle_database_error)?
             .get_products()
             .map_err(internal_server_error)
     })
-    .await
-    .map_err(handle_blocking_error)??;
+    .await?;
 
     let mut grouped: HashMap<String, Vec<String>> = HashMap::new();
 
@@ -47,17 +52,115 @@ pub async fn by_vendor(
     Ok(HttpResponse::Ok().json(grouped))
 }
 
+/// Lists the `vendor:product` ids of the products having records, with the revision that
+/// last modified them.
+pub async fn ids(
+    ctx: web::Data<ApplicationContext>,
+    params: web::Query<PageParams>,
+) -> Result<HttpResponse, ApplicationError> {
+    let limit = params.limit().map_err(bad_request_body)?;
+    let after = match &params.after {
+        Some(after) => {
+            let (vendor, product) = listing::parse_product_id(after).map_err(bad_request_body)?;
+            (vendor.to_string(), product.to_string())
+        }
+        None => (String::new(), String::new()),
+    };
+    let timeout = ctx.timeouts.default;
+
+    timeout::block(timeout, move || {
+        let database = ctx.get_database(timeout).map_err(handle_database_error)?;
+        let revisions = database
+            .get_product_revisions((&after.0, &after.1), limit)
+            .map_err(internal_server_error)?;
+
+        let items = revisions
+            .into_iter()
+            .map(|r| Entry {
+                id: listing::product_id(&r.vendor, &r.product),
+                revision: r.revision,
+            })
+            .collect();
+        Ok(Page::with(items, limit))
+    })
+    .await
+    .map(ok_to_json)
+}
+
+/// Product as listed by the CPE dictionary, along with a page of its versions.
+#[derive(Debug, Serialize)]
+struct DictionaryProduct {
+    vendor: String,
+    product: String,
+    title: Option<String>,
+    /// Whether all its versions are deprecated.
+    deprecated: bool,
+    entries: Vec<CpeEntry>,
+    /// CPE to request the next page of versions after, unless this is the last one.
+    next: Option<String>,
+}
+
+/// Shows the canonical title of the product in the CPE dictionary, and the titles,
+/// references and deprecation of its versions, by CPE.
+pub async fn dictionary(
+    path: web::Path<(String, String)>,
+    ctx: web::Data<ApplicationContext>,
+    params: web::Query<PageParams>,
+) -> Result<HttpResponse, ApplicationError> {
+    let limit = params.limit().map_err(bad_request_body)?;
+    let (vendor, product) = path.into_inner();
+    let timeout = ctx.timeouts.default;
+
+    timeout::block(timeout, move || {
+        let database = ctx.get_database(timeout).map_err(handle_database_error)?;
+        let titles = database
+            .get_cpe_titles(&vendor, &product)
+            .map_err(internal_server_error)?;
+        if titles.is_empty() {
+            return Err(ApplicationError::NotFound(format!(
+                "{} is not in the CPE dictionary",
+                listing::product_id(&vendor, &product)
+            )));
+        }
+        let entries = database
+            .get_cpe_entries(
+                &vendor,
+                &product,
+                params.after.as_deref().unwrap_or_default(),
+                limit,
+            )
+            .map_err(internal_server_error)?;
+
+        let next = if entries.len() as i64 == limit {
+            entries.last().map(|entry| entry.cpe.clone())
+        } else {
+            None
+        };
+        Ok(DictionaryProduct {
+            title: dictionary::product_title(&titles),
+            deprecated: titles.iter().all(|(_, _, deprecated)| *deprecated),
+            vendor,
+            product,
+            entries,
+            next,
+        })
+    })
+    .await
+    .map(ok_to_json)
+}
+
 pub async fn search(
     query: web::Path<String>,
     ctx: web::Data<ApplicationContext>,
 ) -> Result<HttpResponse, ApplicationError> {
-    web::block(move || {
-        ctx.get_database()
+    let timeout = ctx.timeouts.default;
+
+    timeout::block(timeout, move || {
+        ctx.get_database(timeout)
             .map_err(handle_database_error)?
             .search_products(query.as_str())
             .map_err(bad_request_body)
     })
     .await
-    .map_err(handle_blocking_error)?
     .map(ok_to_json)
 }
@@ -0,0 +1,354 @@
+//! Opt-in log of the queries searched, counted by day along with how long the
+//! searches took, without anything identifying the clients, for the platform teams to see
+//! the components scanned the most and preload them.
+
+use std::collections::HashMap;
+use std::env;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use actix_web::{http::header, web, HttpResponse};
+use chrono::{NaiveDate, Utc};
+use serde::Deserialize;
+
+use crate::db::{models, Database, Pool};
+use crate::search::Query;
+
+use super::{
+    error::ApplicationError,
+    timeout,
+    utils::{bad_request_body, handle_database_error, internal_server_error},
+    ApplicationContext,
+};
+
+const DEFAULT_RETENTION_DAYS: i64 = 90;
+/// Interval between the writes of the searches counted meanwhile.
+const FLUSH_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Query searched, with all its fields as the clients sent them, like the search cache keys
+/// the results so that replaying it warms up the cache for the same searches.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Key(Query);
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+struct Stats {
+    count: i64,
+    latency_ms: i64,
+    max_latency_ms: i64,
+}
+
+impl Stats {
+    fn add(&mut self, other: Stats) {
+        self.count += other.count;
+        self.latency_ms += other.latency_ms;
+        self.max_latency_ms = self.max_latency_ms.max(other.max_latency_ms);
+    }
+}
+
+/// Searches counted by day and query, disabled unless `KEPLER_QUERY_LOG` is `on`.
+/// They're written to the database every minute, and the days older than
+/// `KEPLER_QUERY_LOG_RETENTION` days (90 by default) are forgotten.
+#[derive(Debug)]
+pub struct QueryLog {
+    enabled: bool,
+    retention_days: i64,
+    pending: Mutex<HashMap<(String, Key), Stats>>,
+}
+
+impl QueryLog {
+    pub fn new(enabled: bool, retention_days: i64) -> Self {
+        Self {
+            enabled,
+            retention_days,
+            pending: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn from_env() -> Result<Self, String> {
+        let enabled = match env::var("KEPLER_QUERY_LOG").as_deref() {
+            Ok("on") => true,
+            Ok("off") | Err(_) => false,
+            Ok(value) => {
+                return Err(format!(
+                    "invalid KEPLER_QUERY_LOG: {}, expected on or off",
+                    value
+                ))
+            }
+        };
+        let retention_days = match env::var("KEPLER_QUERY_LOG_RETENTION") {
+            Ok(value) => match value.parse::<i64>() {
+                Ok(days) if days > 0 => days,
+                _ => return Err(format!("invalid KEPLER_QUERY_LOG_RETENTION: {}", value)),
+            },
+            Err(_) => DEFAULT_RETENTION_DAYS,
+        };
+
+        Ok(Self::new(enabled, retention_days))
+    }
+
+    /// Queries to record once answered, none when disabled.
+    pub fn keys<'a>(&self, queries: impl IntoIterator<Item = &'a Query>) -> Vec<Key> {
+        if !self.enabled {
+            return vec![];
+        }
+        queries.into_iter().cloned().map(Key).collect()
+    }
+
+    /// Counts the searches of the queries, answered together in the given time.
+    pub fn record(&self, keys: Vec<Key>, latency: Duration) {
+        if keys.is_empty() {
+            return;
+        }
+        let latency_ms = latency.as_millis() as i64;
+        let stats = Stats {
+            count: 1,
+            latency_ms,
+            max_latency_ms: latency_ms,
+        };
+        let today = Utc::now().format("%Y-%m-%d").to_string();
+
+        let mut pending = self.pending.lock().unwrap();
+        for key in keys {
+            pending.entry((today.clone(), key)).or_default().add(stats);
+        }
+    }
+
+    /// Takes the searches counted since the last write.
+    fn take(&self) -> Vec<models::NewQueryLogEntry> {
+        let pending = std::mem::take(&mut *self.pending.lock().unwrap());
+        let now = Utc::now();
+        pending
+            .into_iter()
+            .map(|((day, Key(query)), stats)| models::NewQueryLogEntry {
+                created_at: now,
+                day,
+                vendor: query.vendor.clone().unwrap_or_default(),
+                product: query.product.clone(),
+                version: query.version.clone().unwrap_or_default(),
+                count: stats.count,
+                latency_ms: stats.latency_ms,
+                max_latency_ms: stats.max_latency_ms,
+                query: diesel_json::Json::new(query),
+            })
+            .collect()
+    }
+
+    /// Adds back the searches that could not be written, for the next write.
+    fn restore(&self, entries: Vec<models::NewQueryLogEntry>) {
+        let mut pending = self.pending.lock().unwrap();
+        for entry in entries {
+            let key = Key(entry.query.0);
+            pending.entry((entry.day, key)).or_default().add(Stats {
+                count: entry.count,
+                latency_ms: entry.latency_ms,
+                max_latency_ms: entry.max_latency_ms,
+            });
+        }
+    }
+
+    fn flush(&self, database: &Database) -> Result<(), String> {
+        let entries = self.take();
+        if !entries.is_empty() {
+            if let Err(e) = database.add_query_log(&entries) {
+                self.restore(entries);
+                return Err(e);
+            }
+        }
+
+        let before = Utc::now().naive_utc().date() - chrono::Duration::days(self.retention_days);
+        database.prune_query_log(&before.format("%Y-%m-%d").to_string())?;
+        Ok(())
+    }
+
+    /// Writes the searches counted periodically in the background, when enabled.
+    pub fn spawn(self: Arc<Self>, pool: Pool) {
+        if !self.enabled {
+            return;
+        }
+        log::info!(
+            "logging the searched queries for {} days",
+            self.retention_days
+        );
+
+        thread::spawn(move || loop {
+            thread::sleep(FLUSH_INTERVAL);
+
+            let result = pool
+                .get()
+                .map_err(|e| e.to_string())
+                .and_then(|conn| self.flush(&Database(conn)));
+            if let Err(e) = result {
+                log::warn!("could not write the query log: {}", e);
+            }
+        });
+    }
+}
+
+/// Writes the entries as CSV, with the average latency of the searches and the whole query as
+/// JSON.
+fn to_csv(entries: &[models::QueryLogEntry]) -> Result<Vec<u8>, String> {
+    let mut writer = csv::Writer::from_writer(vec![]);
+    writer
+        .write_record(&[
+            "day",
+            "vendor",
+            "product",
+            "version",
+            "count",
+            "avg_latency_ms",
+            "max_latency_ms",
+            "query",
+        ])
+        .map_err(|e| e.to_string())?;
+    for entry in entries {
+        writer
+            .write_record(&[
+                entry.day.clone(),
+                entry.vendor.clone(),
+                entry.product.clone(),
+                entry.version.clone(),
+                entry.count.to_string(),
+                (entry.latency_ms / entry.count.max(1)).to_string(),
+                entry.max_latency_ms.to_string(),
+                serde_json::to_string(&entry.query.0).map_err(|e| e.to_string())?,
+            ])
+            .map_err(|e| e.to_string())?;
+    }
+    writer.into_inner().map_err(|e| e.to_string())
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ExportParams {
+    /// First day to export, like `2022-07-19`, all the days kept otherwise.
+    pub since: Option<String>,
+}
+
+/// Exports the searches logged as CSV, by day and the most searched queries first.
+pub async fn export(
+    ctx: web::Data<ApplicationContext>,
+    params: web::Query<ExportParams>,
+) -> Result<HttpResponse, ApplicationError> {
+    let since = match &params.since {
+        Some(since) => NaiveDate::parse_from_str(since, "%Y-%m-%d")
+            .map_err(|_| {
+                bad_request_body(format!("invalid since {}, expected like 2022-07-19", since))
+            })?
+            .format("%Y-%m-%d")
+            .to_string(),
+        None => String::new(),
+    };
+    let timeout = ctx.timeouts.default;
+
+    let entries = timeout::block(timeout, move || {
+        ctx.get_database(timeout)
+            .map_err(handle_database_error)?
+            .get_query_log(&since)
+            .map_err(internal_server_error)
+    })
+    .await?;
+
+    let csv = to_csv(&entries).map_err(internal_server_error)?;
+    Ok(HttpResponse::Ok()
+        .content_type("text/csv; charset=utf-8")
+        .insert_header((
+            header::CONTENT_DISPOSITION,
+            "attachment; filename=\"query_log.csv\"",
+        ))
+        .body(csv))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use chrono::Utc;
+
+    use super::{to_csv, QueryLog};
+    use crate::db::models;
+    use crate::search::Query;
+
+    fn query(vendor: Option<&str>, product: &str, version: Option<&str>) -> Query {
+        Query {
+            vendor: vendor.map(str::to_string),
+            product: product.into(),
+            version: version.map(str::to_string),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn can_count_searches() {
+        let log = QueryLog::new(true, 90);
+        let openssl = query(None, "openssl", Some("1.1.1k"));
+        log.record(log.keys(vec![&openssl]), Duration::from_millis(30));
+        log.record(log.keys(vec![&openssl]), Duration::from_millis(10));
+        log.record(
+            log.keys(vec![&query(Some("gnu"), "glibc", None)]),
+            Duration::from_millis(5),
+        );
+        // the other fields make another search, cached apart
+        let on_debian = Query {
+            distro: Some("debian:11".into()),
+            ..openssl.clone()
+        };
+        log.record(log.keys(vec![&on_debian]), Duration::from_millis(20));
+
+        let mut entries = log.take();
+        entries.sort_by_key(|entry| (entry.product.clone(), entry.count));
+        assert_eq!(3, entries.len());
+        assert_eq!(
+            ("gnu", "glibc", ""),
+            (
+                entries[0].vendor.as_str(),
+                entries[0].product.as_str(),
+                entries[0].version.as_str()
+            )
+        );
+        assert_eq!(on_debian, entries[1].query.0);
+        assert_eq!(openssl, entries[2].query.0);
+        assert_eq!("openssl", entries[2].product);
+        assert_eq!("1.1.1k", entries[2].version);
+        assert_eq!(2, entries[2].count);
+        assert_eq!(40, entries[2].latency_ms);
+        assert_eq!(30, entries[2].max_latency_ms);
+        assert!(log.take().is_empty());
+
+        log.restore(entries);
+        assert_eq!(3, log.take().len());
+    }
+
+    #[test]
+    fn does_not_count_searches_unless_enabled() {
+        let log = QueryLog::new(false, 90);
+        log.record(
+            log.keys(vec![&query(None, "openssl", None)]),
+            Duration::from_millis(30),
+        );
+        assert!(log.take().is_empty());
+    }
+
+    #[test]
+    fn can_export_csv() {
+        let entry = models::QueryLogEntry {
+            id: 1,
+            created_at: Utc::now(),
+            updated_at: None,
+            day: "2022-07-19".into(),
+            vendor: String::new(),
+            product: "openssl".into(),
+            version: "1.1.1k".into(),
+            count: 4,
+            latency_ms: 100,
+            max_latency_ms: 40,
+            query: diesel_json::Json::new(query(None, "openssl", Some("1.1.1k"))),
+        };
+        assert_eq!(
+            "day,vendor,product,version,count,avg_latency_ms,max_latency_ms,query\n\
+             2022-07-19,,openssl,1.1.1k,4,25,40,\
+             \"{\"\"vendor\"\":null,\"\"product\"\":\"\"openssl\"\",\
+             \"\"version\"\":\"\"1.1.1k\"\"}\"\n",
+            String::from_utf8(to_csv(&[entry]).unwrap()).unwrap()
+        );
+    }
+}
@@ -0,0 +1,241 @@
+use std::env;
+use std::future::{ready, Future, Ready};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use actix_web::{
+    dev::{Service, ServiceRequest, ServiceResponse, Transform},
+    error::InternalError,
+    Error, HttpResponse,
+};
+use lru::LruCache;
+
+use super::access::TrustedProxies;
+
+/// Number of tracked clients, the least recently seen one being forgotten to track a new one.
+const MAX_BUCKETS: usize = 10_000;
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+/// Per client token bucket rate limiter.
+#[derive(Debug)]
+pub struct Limiter {
+    rate: f64,
+    burst: f64,
+    proxies: TrustedProxies,
+    buckets: Mutex<LruCache<IpAddr, Bucket>>,
+}
+
+/// Address the bucket of a client is kept for: the IPv6 clients are limited by /64 network,
+/// the smallest one usually assigned to a host, so that they can't get new buckets by
+/// rotating their addresses.
+fn bucket_key(ip: IpAddr) -> IpAddr {
+    match ip {
+        IpAddr::V4(_) => ip,
+        IpAddr::V6(ip) => match ip.segments() {
+            // IPv4 clients of a dual stack socket
+            [0, 0, 0, 0, 0, 0xffff, hi, lo] => {
+                IpAddr::V4(Ipv4Addr::from((u32::from(hi) << 16) | u32::from(lo)))
+            }
+            _ => IpAddr::V6(Ipv6Addr::from(u128::from(ip) & (u128::MAX << 64))),
+        },
+    }
+}
+
+impl Limiter {
+    pub fn new(rate: f64, burst: f64, proxies: TrustedProxies) -> Self {
+        Self {
+            rate,
+            burst,
+            proxies,
+            buckets: Mutex::new(LruCache::new(MAX_BUCKETS)),
+        }
+    }
+
+    /// Configures the limiter with the `KEPLER_RATE_LIMIT` (requests per second) and
+    /// `KEPLER_RATE_BURST` environment variables, along with the trusted proxies, rate
+    /// limiting is disabled when `KEPLER_RATE_LIMIT` is not set.
+    pub fn from_env() -> Result<Option<Self>, String> {
+        let rate = match env::var("KEPLER_RATE_LIMIT") {
+            Ok(rate) => rate
+                .parse::<f64>()
+                .ok()
+                .filter(|r| *r > 0.0)
+                .ok_or_else(|| format!("invalid KEPLER_RATE_LIMIT '{}'", rate))?,
+            Err(_) => return Ok(None),
+        };
+        let burst = match env::var("KEPLER_RATE_BURST") {
+            Ok(burst) => burst
+                .parse::<f64>()
+                .ok()
+                .filter(|b| *b >= 1.0)
+                .ok_or_else(|| format!("invalid KEPLER_RATE_BURST '{}'", burst))?,
+            Err(_) => rate.max(1.0),
+        };
+        let proxies = TrustedProxies::from_env()?;
+
+        log::info!("rate limiting clients to {}/s, burst {}", rate, burst);
+
+        Ok(Some(Self::new(rate, burst, proxies)))
+    }
+
+    /// Takes a token from the bucket of the client, returning how long it has to wait for
+    /// the next one if the bucket is empty.
+    pub fn check(&self, ip: IpAddr, now: Instant) -> Result<(), Duration> {
+        let key = bucket_key(ip);
+        let mut buckets = self.buckets.lock().unwrap();
+
+        if !buckets.contains(&key) {
+            buckets.put(
+                key,
+                Bucket {
+                    tokens: self.burst,
+                    updated: now,
+                },
+            );
+        }
+        let bucket = buckets.get_mut(&key).unwrap();
+
+        let elapsed = now.saturating_duration_since(bucket.updated).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * self.rate).min(self.burst);
+        bucket.updated = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64((1.0 - bucket.tokens) / self.rate))
+        }
+    }
+}
+
+/// Middleware rejecting with `429 Too Many Requests` the clients exceeding the rate limit.
+pub struct RateLimit(pub Option<Arc<Limiter>>);
+
+impl<S, B> Transform<S, ServiceRequest> for RateLimit
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Transform = RateLimitMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(RateLimitMiddleware {
+            service,
+            limiter: self.0.clone(),
+        }))
+    }
+}
+
+pub struct RateLimitMiddleware<S> {
+    service: S,
+    limiter: Option<Arc<Limiter>>,
+}
+
+impl<S, B> Service<ServiceRequest> for RateLimitMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    #[allow(clippy::type_complexity)]
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>>>>;
+
+    actix_web::dev::forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let client = self
+            .limiter
+            .as_ref()
+            .and_then(|limiter| limiter.proxies.resolve(&req));
+        if let (Some(limiter), Some(client)) = (&self.limiter, client) {
+            if let Err(wait) = limiter.check(client, Instant::now()) {
+                log::warn!("rate limit exceeded by {}", client);
+                let response = HttpResponse::TooManyRequests()
+                    .insert_header(("Retry-After", wait.as_secs().max(1).to_string()))
+                    .finish();
+                return Box::pin(ready(Err(InternalError::from_response(
+                    "rate limit exceeded",
+                    response,
+                )
+                .into())));
+            }
+        }
+
+        Box::pin(self.service.call(req))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::{IpAddr, Ipv4Addr};
+    use std::time::{Duration, Instant};
+
+    use super::{Limiter, TrustedProxies, MAX_BUCKETS};
+
+    #[test]
+    fn can_limit_rate() {
+        let limiter = Limiter::new(2.0, 3.0, TrustedProxies::default());
+        let client = "10.0.0.1".parse().unwrap();
+        let other = "10.0.0.2".parse().unwrap();
+        let now = Instant::now();
+
+        for _ in 0..3 {
+            assert!(limiter.check(client, now).is_ok());
+        }
+        assert_eq!(Err(Duration::from_millis(500)), limiter.check(client, now));
+        assert!(limiter.check(other, now).is_ok());
+
+        let later = now + Duration::from_millis(500);
+        assert!(limiter.check(client, later).is_ok());
+        assert!(limiter.check(client, later).is_err());
+    }
+
+    #[test]
+    fn can_limit_ipv6_clients_by_network() {
+        let limiter = Limiter::new(1.0, 1.0, TrustedProxies::default());
+        let now = Instant::now();
+
+        assert!(limiter
+            .check("2001:db8:1:2::1".parse().unwrap(), now)
+            .is_ok());
+        assert!(limiter
+            .check("2001:db8:1:2::2".parse().unwrap(), now)
+            .is_err());
+        assert!(limiter
+            .check("2001:db8:1:3::1".parse().unwrap(), now)
+            .is_ok());
+
+        // the IPv4 clients of a dual stack socket keep their own buckets
+        assert!(limiter
+            .check("::ffff:10.0.0.1".parse().unwrap(), now)
+            .is_ok());
+        assert!(limiter
+            .check("::ffff:10.0.0.2".parse().unwrap(), now)
+            .is_ok());
+        assert!(limiter.check("10.0.0.1".parse().unwrap(), now).is_err());
+    }
+
+    #[test]
+    fn can_bound_tracked_clients() {
+        let limiter = Limiter::new(1.0, 1.0, TrustedProxies::default());
+        let now = Instant::now();
+
+        for i in 0..MAX_BUCKETS as u32 + 10 {
+            let ip = IpAddr::V4(Ipv4Addr::from(i));
+            assert!(limiter.check(ip, now).is_ok());
+        }
+        assert_eq!(MAX_BUCKETS, limiter.buckets.lock().unwrap().len());
+    }
+}
@@ -0,0 +1,217 @@
+use std::sync::Arc;
+use std::time::Instant;
+
+use crate::bus::Event;
+use crate::scan::{self, attestation};
+use crate::search::profile::Profile;
+use crate::sources::eol;
+use actix_web::{web, HttpRequest, HttpResponse};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    error::ApplicationError,
+    idempotency, limits, scoring, timeout,
+    utils::{bad_request_body, handle_database_error, internal_server_error},
+    v2,
+    version::ApiVersion,
+    ApplicationContext,
+};
+
+/// Refuses JSON inputs nested too deep before they're parsed.
+pub(super) fn check_depth(ctx: &ApplicationContext, body: &str) -> Result<(), ApplicationError> {
+    let trimmed = body.trim_start();
+    if trimmed.starts_with('{') || trimmed.starts_with('[') {
+        limits::check_depth(body.as_bytes(), ctx.limits.json_depth).map_err(bad_request_body)?;
+    }
+    Ok(())
+}
+
+async fn run(
+    ctx: web::Data<ApplicationContext>,
+    format: scan::Format,
+    body: String,
+) -> Result<scan::Report, ApplicationError> {
+    let timeout = ctx.timeouts.scan;
+    ctx.usage.record_scan();
+    let started = Instant::now();
+
+    timeout::block(timeout, move || {
+        let components = scan::parse(format, &body).map_err(bad_request_body)?;
+        let num_components = components.queries.len();
+        let keys = ctx.query_log.keys(&components.queries);
+        let database = ctx.get_database(timeout).map_err(handle_database_error)?;
+        let report = scan::run(&database, format, components).map_err(bad_request_body)?;
+        ctx.query_log.record(keys, started.elapsed());
+
+        if let Some(bus) = &ctx.bus {
+            bus.spawn_publish(vec![Event::scan_completed(
+                None,
+                format.to_string(),
+                num_components,
+                &report.cves,
+            )]);
+        }
+
+        Ok(report)
+    })
+    .await
+}
+
+/// v1 scan report along with the recalibrated scores of the CVEs, when a profile is applied,
+/// and their exploitation data.
+#[derive(Serialize)]
+struct ScoredReport<'a> {
+    format: scan::Format,
+    warnings: &'a [String],
+    cves: Vec<scoring::Scored<'a>>,
+    #[serde(skip_serializing_if = "<[_]>::is_empty")]
+    eol: &'a [eol::EndOfLife],
+    #[serde(skip_serializing_if = "<[_]>::is_empty")]
+    licenses: &'a [scan::License],
+}
+
+pub(super) fn respond(
+    version: ApiVersion,
+    profile: Option<Arc<Profile>>,
+    report: scan::Report,
+) -> HttpResponse {
+    match (version, profile) {
+        (ApiVersion::V1, profile) => version.json(ScoredReport {
+            format: report.format,
+            warnings: &report.warnings,
+            cves: scoring::scored(&report.cves, profile.as_deref(), &report.enrichment),
+            eol: &report.eol,
+            licenses: &report.licenses,
+        }),
+        (ApiVersion::V2, profile) => version.json(v2::ScanReport::with(report, profile.as_deref())),
+    }
+}
+
+pub async fn scan(
+    ctx: web::Data<ApplicationContext>,
+    req: HttpRequest,
+    version: ApiVersion,
+    format: web::Path<String>,
+    body: String,
+) -> Result<HttpResponse, ApplicationError> {
+    let format = format.parse::<scan::Format>().map_err(bad_request_body)?;
+    check_depth(&ctx, &body)?;
+    let profile = scoring::profile(&ctx, &req)?;
+    let key = idempotency::Key::from_request(&req, body.as_bytes())?;
+
+    idempotency::once(ctx.clone(), key, async move {
+        run(ctx, format, body)
+            .await
+            .map(|report| respond(version, profile, report))
+    })
+    .await
+}
+
+/// Headers with the transparency log entry of the uploaded attestation.
+pub const REKOR_UUID_HEADER: &str = "X-Kepler-Rekor-Uuid";
+pub const REKOR_LOG_INDEX_HEADER: &str = "X-Kepler-Rekor-Log-Index";
+
+#[derive(Debug, Deserialize)]
+pub struct AttestationParams {
+    /// Name of the scanned artifact, the subject of the attestation.
+    pub name: Option<String>,
+    /// Revision of the scanned artifact, like a commit or a release.
+    pub revision: Option<String>,
+}
+
+/// Scans the input and returns the v2 report as a signed in-toto attestation, uploaded to
+/// the transparency log when one is configured.
+pub async fn attest(
+    ctx: web::Data<ApplicationContext>,
+    req: HttpRequest,
+    format: web::Path<String>,
+    params: web::Query<AttestationParams>,
+    body: String,
+) -> Result<HttpResponse, ApplicationError> {
+    if ctx.signer.is_none() {
+        return Err(bad_request_body(
+            "scan attestations are disabled, no signing key configured".into(),
+        ));
+    }
+    let format = format.parse::<scan::Format>().map_err(bad_request_body)?;
+    check_depth(&ctx, &body)?;
+    let profile = scoring::profile(&ctx, &req)?;
+    let key = idempotency::Key::from_request(&req, body.as_bytes())?;
+
+    idempotency::once(
+        ctx.clone(),
+        key,
+        sign(ctx, format, profile, params.into_inner(), body),
+    )
+    .await
+}
+
+/// Scans the input and signs the report, uploading the attestation to the transparency log.
+async fn sign(
+    ctx: web::Data<ApplicationContext>,
+    format: scan::Format,
+    profile: Option<Arc<Profile>>,
+    params: AttestationParams,
+    body: String,
+) -> Result<HttpResponse, ApplicationError> {
+    let input = body.clone().into_bytes();
+
+    let report = run(ctx.clone(), format, body).await?;
+    let statement = attestation::Statement::with(
+        params.name.unwrap_or_else(|| "input".into()),
+        &input,
+        params.revision,
+        v2::ScanReport::with(report, profile.as_deref()),
+    );
+
+    let signer = ctx.signer.as_ref().unwrap();
+    let envelope = statement
+        .sign(signer)
+        .and_then(|envelope| serde_json::to_string(&envelope).map_err(|e| e.to_string()))
+        .map_err(internal_server_error)?;
+
+    let mut response = HttpResponse::Ok();
+    response.content_type("application/vnd.dsse.envelope.v1+json");
+
+    if let Some(rekor) = ctx.rekor.clone() {
+        let timeout = ctx.timeouts.default;
+        let public_key = signer.public_key().to_vec();
+        let payload = envelope.clone();
+
+        let (entry, url) = timeout::block(timeout, move || {
+            let entry = rekor
+                .upload(&payload, &public_key)
+                .map_err(internal_server_error)?;
+            let url = rekor.entry_url(&entry.uuid);
+            Ok((entry, url))
+        })
+        .await?;
+
+        response
+            .insert_header((REKOR_UUID_HEADER, entry.uuid))
+            .insert_header((REKOR_LOG_INDEX_HEADER, entry.log_index.to_string()))
+            .insert_header(("Location", url));
+    }
+
+    Ok(response.body(envelope))
+}
+
+pub async fn detect(
+    ctx: web::Data<ApplicationContext>,
+    req: HttpRequest,
+    version: ApiVersion,
+    body: String,
+) -> Result<HttpResponse, ApplicationError> {
+    check_depth(&ctx, &body)?;
+    let profile = scoring::profile(&ctx, &req)?;
+    let format = scan::detect(&body)
+        .ok_or_else(|| bad_request_body("could not detect the scan input format".into()))?;
+    let key = idempotency::Key::from_request(&req, body.as_bytes())?;
+
+    idempotency::once(ctx.clone(), key, async move {
+        run(ctx, format, body)
+            .await
+            .map(|report| respond(version, profile, report))
+    })
+    .await
+}
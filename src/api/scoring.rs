@@ -0,0 +1,72 @@
+use std::sync::Arc;
+
+use actix_web::HttpRequest;
+use serde::Serialize;
+
+use super::{error::ApplicationError, utils::bad_request_body, ApplicationContext};
+use crate::db::models;
+use crate::search::enrichment::Enrichment;
+use crate::search::profile::{Profile, DEFAULT_PROFILE};
+use crate::sources::{cwe, epss, exploitdb, kev, metasploit};
+
+/// Header selecting the scoring profile of the request.
+pub const PROFILE_HEADER: &str = "X-Kepler-Profile";
+
+/// Returns the scoring profile requested with the header, or the default one if configured.
+pub fn profile(
+    ctx: &ApplicationContext,
+    req: &HttpRequest,
+) -> Result<Option<Arc<Profile>>, ApplicationError> {
+    match req.headers().get(PROFILE_HEADER) {
+        Some(name) => {
+            let name = name
+                .to_str()
+                .map_err(|_| bad_request_body("invalid scoring profile name".into()))?;
+            ctx.profiles
+                .get(name)
+                .cloned()
+                .map(Some)
+                .ok_or_else(|| bad_request_body(format!("unknown scoring profile '{}'", name)))
+        }
+        None => Ok(ctx.profiles.get(DEFAULT_PROFILE).cloned()),
+    }
+}
+
+/// v1 CVE record along with its recalibrated score, when a profile is applied, whether it's
+/// known to be exploited, its EPSS score, its public exploits, its Metasploit modules and its
+/// weaknesses.
+#[derive(Debug, Serialize)]
+pub struct Scored<'a> {
+    #[serde(flatten)]
+    pub cve: &'a models::CVE,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub adjusted_score: Option<f64>,
+    #[serde(flatten)]
+    pub exploitation: kev::Exploitation,
+    #[serde(flatten)]
+    pub epss: epss::Epss,
+    #[serde(flatten)]
+    pub exploits: exploitdb::Exploits,
+    #[serde(flatten)]
+    pub metasploit: metasploit::Metasploit,
+    #[serde(flatten)]
+    pub weaknesses: kepler_types::api::Weaknesses,
+}
+
+pub fn scored<'a>(
+    cves: &'a [models::CVE],
+    profile: Option<&Profile>,
+    enrichment: &Enrichment,
+) -> Vec<Scored<'a>> {
+    cves.iter()
+        .map(|cve| Scored {
+            cve,
+            adjusted_score: profile.map(|p| p.adjusted_score(cve, enrichment)),
+            exploitation: enrichment.exploited.of(&cve.cve),
+            epss: enrichment.epss.of(&cve.cve),
+            exploits: enrichment.exploits.of(&cve.cve),
+            metasploit: enrichment.metasploit.of(&cve.cve),
+            weaknesses: enrichment.weaknesses.of(&cve.cve),
+        })
+        .collect()
+}
@@ -0,0 +1,72 @@
+use std::env;
+use std::panic::{self, AssertUnwindSafe};
+use std::time::Duration;
+
+use actix_web::{rt::time, web};
+
+use super::{error::ApplicationError, utils::handle_blocking_error};
+use crate::crash;
+
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Maximum time the handlers can take, globally and for the route groups doing heavier
+/// work, configured in seconds with the `KEPLER_TIMEOUT`, `KEPLER_SCAN_TIMEOUT` and
+/// `KEPLER_EXPORT_TIMEOUT` environment variables.
+#[derive(Debug, Clone, Copy)]
+pub struct Timeouts {
+    pub default: Duration,
+    pub scan: Duration,
+    pub export: Duration,
+}
+
+impl Timeouts {
+    pub fn from_env() -> Self {
+        let default = from_env("KEPLER_TIMEOUT", DEFAULT_TIMEOUT);
+
+        Self {
+            default,
+            scan: from_env("KEPLER_SCAN_TIMEOUT", default),
+            export: from_env("KEPLER_EXPORT_TIMEOUT", default),
+        }
+    }
+}
+
+fn from_env(name: &str, default: Duration) -> Duration {
+    match env::var(name) {
+        Ok(value) => match value.parse::<u64>() {
+            Ok(secs) if secs > 0 => Duration::from_secs(secs),
+            _ => {
+                log::warn!(
+                    "Failed to parse {}. Using default {}s",
+                    name,
+                    default.as_secs()
+                );
+                default
+            }
+        },
+        Err(_) => default,
+    }
+}
+
+/// Runs the blocking function on the thread pool, giving up after the timeout. The
+/// function keeps running in the background, it's up to it to stop soon after, for instance
+/// by setting a statement timeout on the database connection. Panics of the function fail
+/// the request along with the id of their crash report.
+pub async fn block<F, T>(timeout: Duration, f: F) -> Result<T, ApplicationError>
+where
+    F: FnOnce() -> Result<T, ApplicationError> + Send + 'static,
+    T: Send + 'static,
+{
+    let f = move || {
+        panic::catch_unwind(AssertUnwindSafe(f))
+            .unwrap_or_else(|_| Err(ApplicationError::Crashed(crash::take_last())))
+    };
+
+    match time::timeout(timeout, web::block(f)).await {
+        Ok(result) => result.map_err(handle_blocking_error)?,
+        Err(_) => {
+            log::error!("request timed out after {}s", timeout.as_secs());
+            Err(ApplicationError::Timeout(timeout))
+        }
+    }
+}
@@ -0,0 +1,184 @@
+use std::env;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+
+use crate::db::{Database, Pool};
+
+const DEFAULT_INTERVAL: Duration = Duration::from_secs(24 * 3600);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Anonymous usage statistics, rounded so that they can't identify an installation.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct Report {
+    pub version: String,
+    pub os: &'static str,
+    pub arch: &'static str,
+    pub records: u64,
+    pub searches: u64,
+    pub scans: u64,
+    pub uptime_days: u64,
+}
+
+/// Opt-in reporting of the usage statistics, disabled unless `KEPLER_USAGE_STATS` is `on`.
+/// The report is sent to `KEPLER_USAGE_STATS_URL` every `KEPLER_USAGE_STATS_INTERVAL` hours
+/// (24 by default), and the counts of requests restart after every report.
+#[derive(Debug)]
+pub struct Usage {
+    endpoint: Option<String>,
+    interval: Duration,
+    started: Instant,
+    searches: AtomicU64,
+    scans: AtomicU64,
+}
+
+impl Usage {
+    pub fn new(endpoint: Option<String>, interval: Duration) -> Self {
+        Self {
+            endpoint,
+            interval,
+            started: Instant::now(),
+            searches: AtomicU64::new(0),
+            scans: AtomicU64::new(0),
+        }
+    }
+
+    pub fn from_env() -> Result<Self, String> {
+        let enabled = match env::var("KEPLER_USAGE_STATS").as_deref() {
+            Ok("on") => true,
+            Ok("off") | Err(_) => false,
+            Ok(value) => {
+                return Err(format!(
+                    "invalid KEPLER_USAGE_STATS: {}, expected on or off",
+                    value
+                ))
+            }
+        };
+        let endpoint = match (enabled, env::var("KEPLER_USAGE_STATS_URL")) {
+            (true, Ok(url)) if url.starts_with("http://") || url.starts_with("https://") => {
+                Some(url)
+            }
+            (true, Ok(url)) => return Err(format!("invalid KEPLER_USAGE_STATS_URL: {}", url)),
+            (true, Err(_)) => {
+                return Err("KEPLER_USAGE_STATS is on but KEPLER_USAGE_STATS_URL is not set".into())
+            }
+            (false, _) => None,
+        };
+        let interval = match env::var("KEPLER_USAGE_STATS_INTERVAL") {
+            Ok(value) => match value.parse::<u64>() {
+                Ok(hours) if hours > 0 => Duration::from_secs(hours * 3600),
+                _ => return Err(format!("invalid KEPLER_USAGE_STATS_INTERVAL: {}", value)),
+            },
+            Err(_) => DEFAULT_INTERVAL,
+        };
+
+        Ok(Self::new(endpoint, interval))
+    }
+
+    pub fn endpoint(&self) -> Option<&str> {
+        self.endpoint.as_deref()
+    }
+
+    pub fn record_search(&self) {
+        self.searches.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_scan(&self) {
+        self.scans.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Returns the report that would be sent now.
+    pub fn report(&self, database: &Database) -> Result<Report, String> {
+        let (records, _) = database.get_records_generation()?;
+
+        Ok(Report {
+            version: crate::version().to_string(),
+            os: env::consts::OS,
+            arch: env::consts::ARCH,
+            records: coarse(records as u64),
+            searches: coarse(self.searches.load(Ordering::Relaxed)),
+            scans: coarse(self.scans.load(Ordering::Relaxed)),
+            uptime_days: self.started.elapsed().as_secs() / (24 * 3600),
+        })
+    }
+
+    fn send(&self, endpoint: &str, report: &Report) -> Result<(), String> {
+        let response = reqwest::blocking::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .and_then(|client| client.post(endpoint).json(report).send())
+            .map_err(|e| e.to_string())?;
+
+        if !response.status().is_success() {
+            return Err(format!("status {}", response.status()));
+        }
+        Ok(())
+    }
+
+    /// Sends the reports periodically in the background, when enabled.
+    pub fn spawn(self: Arc<Self>, pool: Pool) {
+        let endpoint = match self.endpoint.clone() {
+            Some(endpoint) => endpoint,
+            None => return,
+        };
+        log::info!("sending anonymous usage statistics to {}", endpoint);
+
+        thread::spawn(move || loop {
+            thread::sleep(self.interval);
+
+            let result = pool
+                .get()
+                .map_err(|e| e.to_string())
+                .and_then(|conn| self.report(&Database(conn)))
+                .and_then(|report| self.send(&endpoint, &report));
+
+            match result {
+                Ok(()) => {
+                    self.searches.store(0, Ordering::Relaxed);
+                    self.scans.store(0, Ordering::Relaxed);
+                }
+                Err(e) => log::warn!("could not send the usage statistics: {}", e),
+            }
+        });
+    }
+}
+
+/// Rounds down to the first significant digit, 0 to 9 being kept as they are.
+pub fn coarse(count: u64) -> u64 {
+    let mut magnitude = 1;
+    while count / magnitude >= 10 {
+        magnitude *= 10;
+    }
+    count / magnitude * magnitude
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{coarse, Usage};
+
+    #[test]
+    fn can_round_counts() {
+        assert_eq!(0, coarse(0));
+        assert_eq!(7, coarse(7));
+        assert_eq!(10, coarse(19));
+        assert_eq!(200, coarse(234));
+        assert_eq!(90000, coarse(98765));
+    }
+
+    #[test]
+    fn can_count_requests() {
+        let usage = Usage::new(None, Duration::from_secs(3600));
+        usage.record_search();
+        usage.record_search();
+        usage.record_scan();
+
+        assert_eq!(None, usage.endpoint());
+        assert_eq!(2, usage.searches.load(std::sync::atomic::Ordering::Relaxed));
+        assert_eq!(1, usage.scans.load(std::sync::atomic::Ordering::Relaxed));
+    }
+}
@@ -0,0 +1,299 @@
+use serde::Serialize;
+
+use crate::db::models;
+use crate::scan;
+use crate::search::{
+    compose::Composition, dsl::severity_rank, enrichment::Enrichment, profile::Profile,
+};
+use crate::sources::eol;
+
+pub use kepler_types::api::{Provenance, SearchResult, Vulnerability};
+
+/// Number of vulnerabilities by severity, along with the highest one.
+#[derive(Debug, Default, PartialEq, Serialize)]
+pub struct Summary {
+    pub total: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_severity: Option<String>,
+    pub critical: usize,
+    pub high: usize,
+    pub medium: usize,
+    pub low: usize,
+    /// Vulnerabilities without a severity.
+    pub none: usize,
+}
+
+impl Summary {
+    fn of<'a>(vulnerabilities: impl Iterator<Item = &'a Vulnerability>) -> Self {
+        let mut summary = Self::default();
+        for vulnerability in vulnerabilities {
+            let rank = severity_rank(&vulnerability.severity);
+            summary.total += 1;
+            match rank {
+                Some(4) => summary.critical += 1,
+                Some(3) => summary.high += 1,
+                Some(2) => summary.medium += 1,
+                Some(1) => summary.low += 1,
+                _ => summary.none += 1,
+            }
+            if rank.is_some() && rank > summary.max_severity.as_deref().and_then(severity_rank) {
+                summary.max_severity = Some(vulnerability.severity.to_uppercase());
+            }
+        }
+        summary
+    }
+}
+
+/// Vulnerabilities found for a component of the scan input, listed by identifier.
+#[derive(Debug, Serialize)]
+pub struct ComponentReport {
+    pub vendor: Option<String>,
+    pub product: String,
+    pub version: Option<String>,
+    pub summary: Summary,
+    pub vulnerabilities: Vec<String>,
+}
+
+/// Summary of the vulnerabilities of all the components scanned.
+#[derive(Debug, Serialize)]
+pub struct Rollup {
+    pub components: usize,
+    pub vulnerable_components: usize,
+    #[serde(flatten)]
+    pub vulnerabilities: Summary,
+}
+
+/// v2 response of the scan API.
+#[derive(Debug, Serialize)]
+pub struct ScanReport {
+    pub format: scan::Format,
+    pub warnings: Vec<String>,
+    pub summary: Rollup,
+    /// Components with vulnerabilities, in the order of the scan input.
+    pub components: Vec<ComponentReport>,
+    pub vulnerabilities: Vec<Vulnerability>,
+    pub eol: Vec<eol::EndOfLife>,
+    pub licenses: Vec<scan::License>,
+}
+
+impl ScanReport {
+    pub fn with(report: scan::Report, profile: Option<&Profile>) -> Self {
+        let vulnerabilities = vulnerabilities(report.cves, profile, &report.enrichment);
+        let (components, summary) = group(&report.findings, &vulnerabilities);
+
+        Self {
+            format: report.format,
+            warnings: report.warnings,
+            summary,
+            components,
+            vulnerabilities,
+            eol: report.eol,
+            licenses: report.licenses,
+        }
+    }
+}
+
+/// Groups the vulnerabilities by the components they were found for, summing them up for
+/// every component and for the whole scan.
+pub fn group(
+    findings: &[scan::Finding],
+    vulnerabilities: &[Vulnerability],
+) -> (Vec<ComponentReport>, Rollup) {
+    let of = |ids: &[String]| {
+        Summary::of(
+            vulnerabilities
+                .iter()
+                .filter(|vulnerability| ids.contains(&vulnerability.id)),
+        )
+    };
+
+    let components: Vec<ComponentReport> = findings
+        .iter()
+        .filter(|finding| !finding.cves.is_empty())
+        .map(|finding| ComponentReport {
+            vendor: finding.query.vendor.clone(),
+            product: finding.query.product.clone(),
+            version: finding.query.version.clone(),
+            summary: of(&finding.cves),
+            vulnerabilities: finding.cves.clone(),
+        })
+        .collect();
+
+    let rollup = Rollup {
+        components: findings.len(),
+        vulnerable_components: components.len(),
+        vulnerabilities: Summary::of(vulnerabilities.iter()),
+    };
+    (components, rollup)
+}
+
+/// v2 response of the composed searches.
+#[derive(Debug, Serialize)]
+pub struct CompositionResult {
+    pub composition: Composition,
+    pub vulnerabilities: Vec<Vulnerability>,
+}
+
+/// Merges the CVE records with the same identifier, keeping the order in which they're
+/// first found and the highest score among the sources.
+pub fn vulnerabilities(
+    cves: Vec<models::CVE>,
+    profile: Option<&Profile>,
+    enrichment: &Enrichment,
+) -> Vec<Vulnerability> {
+    let mut vulnerabilities: Vec<Vulnerability> = vec![];
+
+    for cve in cves {
+        let adjusted_score = profile.map(|p| p.adjusted_score(&cve, enrichment));
+        let provenance = Provenance {
+            source: cve.source,
+            vendor: cve.vendor,
+            product: cve.product,
+        };
+
+        match vulnerabilities.iter_mut().find(|v| v.id == cve.cve) {
+            Some(vulnerability) => {
+                if cve.score > vulnerability.score {
+                    vulnerability.score = cve.score;
+                    vulnerability.severity = cve.severity;
+                    vulnerability.vector = cve.vector;
+                }
+                for reference in cve.references.0 {
+                    if !vulnerability.references.contains(&reference) {
+                        vulnerability.references.push(reference);
+                    }
+                }
+                if !vulnerability.provenance.contains(&provenance) {
+                    vulnerability.provenance.push(provenance);
+                }
+                if adjusted_score > vulnerability.adjusted_score {
+                    vulnerability.adjusted_score = adjusted_score;
+                }
+            }
+            None => vulnerabilities.push(Vulnerability {
+                exploitation: enrichment.exploited.of(&cve.cve),
+                epss: enrichment.epss.of(&cve.cve),
+                exploits: enrichment.exploits.of(&cve.cve),
+                metasploit: enrichment.metasploit.of(&cve.cve),
+                weaknesses: enrichment.weaknesses.of(&cve.cve),
+                id: cve.cve,
+                summary: cve.summary,
+                score: cve.score,
+                severity: cve.severity,
+                vector: cve.vector,
+                references: cve.references.0,
+                provenance: vec![provenance],
+                adjusted_score,
+            }),
+        }
+    }
+
+    vulnerabilities
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{group, vulnerabilities};
+    use crate::db::models::{Reference, CVE};
+    use crate::scan::Finding;
+    use crate::search::{enrichment::Enrichment, testing, Query};
+
+    fn cve(source: &str, id: &str, score: f64, url: &str) -> CVE {
+        CVE {
+            source: source.into(),
+            vendor: "xmlsoft".into(),
+            score,
+            severity: if score >= 7.0 { "HIGH" } else { "MEDIUM" }.into(),
+            references: diesel_json::Json::new(vec![Reference {
+                url: url.into(),
+                tags: vec![],
+            }]),
+            ..testing::cve(id, "libxml2")
+        }
+    }
+
+    #[test]
+    fn can_merge_cve_records() {
+        let merged = vulnerabilities(
+            vec![
+                cve("nist", "CVE-2021-3517", 5.5, "https://a"),
+                cve("nist", "CVE-2021-3518", 8.8, "https://b"),
+                cve("npm", "CVE-2021-3517", 8.6, "https://c"),
+            ],
+            None,
+            &Enrichment::default(),
+        );
+
+        assert_eq!(2, merged.len());
+        assert!(!merged[0].exploitation.known_exploited);
+        assert_eq!(None, merged[0].epss.epss_score);
+        assert!(!merged[0].exploits.exploit_available);
+        assert!(!merged[0].metasploit.metasploit_module);
+
+        assert_eq!("CVE-2021-3517", merged[0].id);
+        assert_eq!(8.6, merged[0].score);
+        assert_eq!("HIGH", merged[0].severity);
+        assert_eq!(2, merged[0].references.len());
+        assert_eq!(
+            vec!["nist", "npm"],
+            merged[0]
+                .provenance
+                .iter()
+                .map(|p| p.source.as_str())
+                .collect::<Vec<_>>()
+        );
+
+        assert_eq!("CVE-2021-3518", merged[1].id);
+        assert_eq!(1, merged[1].provenance.len());
+    }
+
+    #[test]
+    fn can_group_by_component() {
+        let merged = vulnerabilities(
+            vec![
+                cve("nist", "CVE-2021-3517", 8.6, "https://a"),
+                cve("nist", "CVE-2021-3518", 5.5, "https://b"),
+            ],
+            None,
+            &Enrichment::default(),
+        );
+        let finding = |product: &str, cves: &[&str]| Finding {
+            query: Query {
+                vendor: None,
+                product: product.into(),
+                version: Some("2.9.10".into()),
+                ..Default::default()
+            },
+            cves: cves.iter().map(|id| id.to_string()).collect(),
+        };
+
+        let (components, rollup) = group(
+            &[
+                finding("libxml2", &["CVE-2021-3517", "CVE-2021-3518"]),
+                finding("zlib", &[]),
+                finding("libxml2-utils", &["CVE-2021-3518"]),
+            ],
+            &merged,
+        );
+
+        assert_eq!(2, components.len());
+        assert_eq!("libxml2", components[0].product);
+        assert_eq!(2, components[0].summary.total);
+        assert_eq!(Some("HIGH".to_string()), components[0].summary.max_severity);
+        assert_eq!(1, components[0].summary.high);
+        assert_eq!(1, components[0].summary.medium);
+        assert_eq!(vec!["CVE-2021-3518"], components[1].vulnerabilities);
+        assert_eq!(
+            Some("MEDIUM".to_string()),
+            components[1].summary.max_severity
+        );
+
+        assert_eq!(3, rollup.components);
+        assert_eq!(2, rollup.vulnerable_components);
+        assert_eq!(2, rollup.vulnerabilities.total);
+        assert_eq!(
+            Some("HIGH".to_string()),
+            rollup.vulnerabilities.max_severity
+        );
+    }
+}
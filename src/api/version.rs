@@ -0,0 +1,66 @@
+use std::future::{ready, Ready};
+
+use actix_web::{dev::Payload, http::header, FromRequest, HttpRequest, HttpResponse};
+use serde::Serialize;
+
+/// Media type clients send in the `Accept` header to opt in the v2 responses.
+pub const V2_MEDIA_TYPE: &str = "application/vnd.kepler.v2+json";
+
+/// Version of the response schema, negotiated either with the `Accept` header or by
+/// prefixing the route with `/v2`. Clients not asking for anything get the v1 responses.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ApiVersion {
+    V1,
+    V2,
+}
+
+impl ApiVersion {
+    /// All the versions served, from the oldest.
+    pub const ALL: [ApiVersion; 2] = [Self::V1, Self::V2];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::V1 => "v1",
+            Self::V2 => "v2",
+        }
+    }
+
+    fn of(req: &HttpRequest) -> Self {
+        if let Some(version) = req.app_data::<ApiVersion>() {
+            return *version;
+        }
+
+        let accepts_v2 = req
+            .headers()
+            .get_all(header::ACCEPT)
+            .filter_map(|value| value.to_str().ok())
+            .any(|value| {
+                value
+                    .split(',')
+                    .any(|t| t.trim().starts_with(V2_MEDIA_TYPE))
+            });
+
+        if accepts_v2 {
+            Self::V2
+        } else {
+            Self::V1
+        }
+    }
+
+    /// Serializes the object with the content type of this version.
+    pub fn json<T: Serialize>(self, object: T) -> HttpResponse {
+        match self {
+            Self::V1 => HttpResponse::Ok().json(object),
+            Self::V2 => HttpResponse::Ok().content_type(V2_MEDIA_TYPE).json(object),
+        }
+    }
+}
+
+impl FromRequest for ApiVersion {
+    type Error = actix_web::Error;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        ready(Ok(Self::of(req)))
+    }
+}
@@ -0,0 +1,166 @@
+use std::fs;
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+
+/// A recorded request of the mix, by default a search.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Request {
+    #[serde(default = "default_path")]
+    pub path: String,
+    pub body: serde_json::Value,
+}
+
+fn default_path() -> String {
+    "/cve/search".into()
+}
+
+/// Latencies of the replayed requests.
+#[derive(Debug)]
+pub struct Report {
+    pub requests: usize,
+    pub errors: usize,
+    pub elapsed: Duration,
+    pub p50: Duration,
+    pub p95: Duration,
+    pub p99: Duration,
+    pub max: Duration,
+}
+
+impl Report {
+    fn with(mut latencies: Vec<Duration>, errors: usize, elapsed: Duration) -> Self {
+        latencies.sort();
+
+        Self {
+            requests: latencies.len(),
+            errors,
+            elapsed,
+            p50: percentile(&latencies, 50.0),
+            p95: percentile(&latencies, 95.0),
+            p99: percentile(&latencies, 99.0),
+            max: latencies.last().copied().unwrap_or_default(),
+        }
+    }
+
+    pub fn throughput(&self) -> f64 {
+        self.requests as f64 / self.elapsed.as_secs_f64().max(f64::EPSILON)
+    }
+}
+
+/// Nearest rank percentile of sorted latencies.
+fn percentile(sorted: &[Duration], percent: f64) -> Duration {
+    if sorted.is_empty() {
+        return Duration::default();
+    }
+    let rank = (percent / 100.0 * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+/// Replays the requests of the mix, cycling through them until the total number of requests
+/// is reached, from the given number of concurrent clients.
+pub fn run(url: &str, mix_path: &Path, total: usize, concurrency: usize) -> Result<Report> {
+    let json = fs::read_to_string(mix_path)
+        .with_context(|| format!("could not read {}", mix_path.display()))?;
+    let mix: Vec<Request> = serde_json::from_str(&json)
+        .with_context(|| format!("could not parse {}", mix_path.display()))?;
+    if mix.is_empty() {
+        bail!("no requests in {}", mix_path.display());
+    }
+
+    let mix = Arc::new(mix);
+    let next = Arc::new(AtomicUsize::new(0));
+    let url = url.trim_end_matches('/').to_string();
+
+    log::info!(
+        "replaying {} requests against {} from {} clients ...",
+        total,
+        url,
+        concurrency
+    );
+
+    let start = Instant::now();
+    let workers: Vec<_> = (0..concurrency.max(1))
+        .map(|_| {
+            let (mix, next, url) = (mix.clone(), next.clone(), url.clone());
+            thread::spawn(move || {
+                let client = reqwest::blocking::Client::new();
+                let mut latencies = vec![];
+                let mut errors = 0;
+
+                loop {
+                    let index = next.fetch_add(1, Ordering::Relaxed);
+                    if index >= total {
+                        break;
+                    }
+                    let request = &mix[index % mix.len()];
+
+                    let start = Instant::now();
+                    let response = client
+                        .post(format!("{}{}", url, request.path))
+                        .header("Content-Type", "application/json")
+                        .body(request.body.to_string())
+                        .send();
+                    latencies.push(start.elapsed());
+
+                    match response {
+                        Ok(response) if response.status().is_success() => {}
+                        Ok(response) => {
+                            log::debug!("{} returned {}", request.path, response.status());
+                            errors += 1;
+                        }
+                        Err(e) => {
+                            log::debug!("{} failed: {}", request.path, e);
+                            errors += 1;
+                        }
+                    }
+                }
+
+                (latencies, errors)
+            })
+        })
+        .collect();
+
+    let mut latencies = vec![];
+    let mut errors = 0;
+    for worker in workers {
+        let (worker_latencies, worker_errors) = worker
+            .join()
+            .map_err(|_| anyhow::anyhow!("benchmark client panicked"))?;
+        latencies.extend(worker_latencies);
+        errors += worker_errors;
+    }
+
+    Ok(Report::with(latencies, errors, start.elapsed()))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{percentile, Report};
+
+    #[test]
+    fn can_compute_percentiles() {
+        let latencies: Vec<Duration> = (1..=100).rev().map(Duration::from_millis).collect();
+        let report = Report::with(latencies, 2, Duration::from_secs(2));
+
+        assert_eq!(100, report.requests);
+        assert_eq!(2, report.errors);
+        assert_eq!(Duration::from_millis(50), report.p50);
+        assert_eq!(Duration::from_millis(95), report.p95);
+        assert_eq!(Duration::from_millis(99), report.p99);
+        assert_eq!(Duration::from_millis(100), report.max);
+        assert_eq!(50.0, report.throughput());
+
+        assert_eq!(Duration::default(), percentile(&[], 99.0));
+        assert_eq!(
+            Duration::from_millis(7),
+            percentile(&[Duration::from_millis(7)], 50.0)
+        );
+    }
+}
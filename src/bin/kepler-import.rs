@@ -0,0 +1,32 @@
+use anyhow::Result;
+use clap::Command;
+use dotenv::dotenv;
+
+use kepler::{cli, crash};
+
+#[cfg(feature = "jemalloc")]
+#[global_allocator]
+static GLOBAL: tikv_jemallocator::Jemalloc = tikv_jemallocator::Jemalloc;
+
+fn main() -> Result<()> {
+    let matches = Command::new("kepler-import")
+        .version(kepler::version())
+        .about("Kepler vulnerability database importer")
+        .disable_help_subcommand(true)
+        .subcommand_required(true)
+        .arg(cli::migrate_arg())
+        .subcommands(cli::import_commands())
+        .get_matches();
+
+    dotenv().ok();
+
+    cli::init_logger(false)?;
+    crash::install();
+
+    let pool = cli::connect(matches.is_present("migrate"))?;
+
+    match matches.subcommand() {
+        Some((exec_name, matches)) => cli::import(&pool, exec_name, matches),
+        None => unreachable!("A subcommand is required"),
+    }
+}
@@ -0,0 +1,26 @@
+use anyhow::Result;
+use clap::Command;
+use dotenv::dotenv;
+
+use kepler::{cli, crash};
+
+#[cfg(feature = "jemalloc")]
+#[global_allocator]
+static GLOBAL: tikv_jemallocator::Jemalloc = tikv_jemallocator::Jemalloc;
+
+fn main() -> Result<()> {
+    let matches = Command::new("kepler-server")
+        .version(kepler::version())
+        .about("Kepler vulnerability database search engine API server")
+        .arg(cli::migrate_arg())
+        .get_matches();
+
+    dotenv().ok();
+
+    cli::init_logger(true)?;
+    crash::install();
+
+    let pool = cli::connect(matches.is_present("migrate"))?;
+
+    cli::serve(pool)
+}
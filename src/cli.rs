@@ -0,0 +1,611 @@
+//! Setup and subcommands shared by the `kepler`, `kepler-server` and `kepler-import` binaries.
+
+use std::env;
+use std::time::Duration;
+#[cfg(feature = "import")]
+use std::{fs, path::PathBuf};
+
+#[cfg(feature = "import")]
+use anyhow::anyhow;
+use anyhow::{Context, Result};
+use chrono::Utc;
+use clap::Arg;
+#[cfg(feature = "import")]
+use clap::{ArgMatches, Command};
+use env_logger::Env;
+
+use crate::db::{self, Pool};
+use crate::utils::memory;
+#[cfg(feature = "import")]
+use crate::{
+    bus::{self, Bus},
+    db::changes::Changes,
+    monitor,
+    notify::{Event, Notifier},
+    sink,
+    sources::{
+        allowlist::{self, Allowlist},
+        almalinux, alpine, amazon, capec, cisco, cnnvd, cvelist, cwe, debian, eol, epss, exploitdb,
+        gentoo, ghsa, govulndb, jvn, kev, metasploit, msrc, nist, npm, oracle, osv, redhat, rocky,
+        suse, ubuntu,
+    },
+};
+
+pub fn migrate_arg() -> Arg<'static> {
+    Arg::new("migrate")
+        .short('m')
+        .long("migrate")
+        .takes_value(false)
+        .help("Migrate database")
+}
+
+/// Sets up the logger, the tracer when running the API server.
+pub fn init_logger(server: bool) -> Result<()> {
+    #[cfg(debug_assertions)]
+    let default_env_filter = "debug";
+    #[cfg(not(debug_assertions))]
+    let default_env_filter = "info";
+
+    #[cfg(feature = "server")]
+    if server {
+        return crate::api::init_logger(default_env_filter).context("Failed to setup logger");
+    }
+    #[cfg(not(feature = "server"))]
+    let _ = server;
+
+    let env = Env::default().default_filter_or(default_env_filter);
+    env_logger::Builder::from_env(env)
+        .try_init()
+        .context("Failed to setup logger")
+}
+
+/// Connects to the database pointed by `DATABASE_URL`, running the pending migrations if
+/// asked to, or exiting when there are any.
+pub fn connect(migrate: bool) -> Result<Pool> {
+    let pool = {
+        let database_url = env::var("DATABASE_URL")
+            .context("DATABASE_URL environment variable has not specified.")?;
+        db::setup(&database_url).context("Cannot connect to database")?
+    };
+
+    memory::log_settings(&pool);
+
+    // Setup database if needed and check for migrations
+    {
+        let conn = pool.get()?;
+
+        diesel_migrations::setup_database(&*conn)?;
+
+        if diesel_migrations::any_pending_migrations(&*conn)? {
+            if migrate {
+                diesel_migrations::run_pending_migrations(&*conn)?;
+                log::info!("Migration successfully")
+            } else {
+                log::error!("Migration needed");
+                std::process::exit(1)
+            }
+        }
+    }
+
+    Ok(pool)
+}
+
+#[cfg(feature = "import")]
+fn data_arg() -> Arg<'static> {
+    Arg::new("data")
+        .short('d')
+        .long("data")
+        .default_value("./data")
+        .help("Data path.")
+}
+
+#[cfg(feature = "import")]
+fn fresh_arg() -> Arg<'static> {
+    Arg::new("fresh")
+        .short('f')
+        .long("fresh")
+        .takes_value(false)
+        .help("Download fresh files")
+}
+
+/// The subcommands importing the data sources.
+#[cfg(feature = "import")]
+pub fn import_commands() -> Vec<Command<'static>> {
+    vec![
+        Command::new("import_nist")
+            .about("imports the specified year of CVE from the NVD API")
+            .arg(
+                Arg::new("year")
+                    .help("the year to import")
+                    .index(1)
+                    .required_unless_present("incremental"),
+            )
+            .arg(data_arg())
+            .arg(fresh_arg())
+            .arg(
+                Arg::new("feed")
+                    .long("feed")
+                    .takes_value(false)
+                    .help("Use the JSON 1.1 data feed instead of the NVD API"),
+            )
+            .arg(
+                Arg::new("incremental")
+                    .short('i')
+                    .long("incremental")
+                    .takes_value(false)
+                    .conflicts_with_all(&["year", "feed"])
+                    .help("Import the CVEs modified since the last sync"),
+            )
+            .arg(
+                Arg::new("since-year")
+                    .long("since-year")
+                    .takes_value(true)
+                    .help("Skip the CVEs of the years before this one, like KEPLER_NIST_SINCE_YEAR"),
+            ),
+        Command::new("import_cpe")
+            .about("imports the official CPE dictionary from the NVD CPE API")
+            .arg(data_arg())
+            .arg(
+                Arg::new("fresh")
+                    .short('f')
+                    .long("fresh")
+                    .takes_value(false)
+                    .help("Import the whole dictionary instead of the CPEs modified since the last import"),
+            ),
+        Command::new("import_cvelist")
+            .about("imports the CVE records of the CNAs from the CVEProject/cvelistV5 git repository")
+            .arg(data_arg())
+            .arg(
+                Arg::new("fresh")
+                    .short('f')
+                    .long("fresh")
+                    .takes_value(false)
+                    .help("Import all the records instead of the ones changed since the last import"),
+            ),
+        Command::new("import_npm")
+            .about("imports vulnerabilities from the registry.npmjs.org data feed")
+            .arg(
+                Arg::new("recent")
+                    .short('r')
+                    .long("recent")
+                    .takes_value(false)
+                    .help("only download recent records"),
+            )
+            .arg(data_arg()),
+        Command::new("import_osv")
+            .about("imports the vulnerabilities of the language ecosystems from OSV.dev")
+            .arg(
+                Arg::new("ecosystem")
+                    .short('e')
+                    .long("ecosystem")
+                    .takes_value(true)
+                    .multiple_occurrences(true)
+                    .help("Only import this ecosystem: PyPI, npm, crates.io or Go"),
+            )
+            .arg(data_arg())
+            .arg(fresh_arg()),
+        Command::new("import_govulndb")
+            .about("imports the vulnerabilities of the Go modules from the Go vulnerability database")
+            .arg(data_arg())
+            .arg(
+                Arg::new("fresh")
+                    .short('f')
+                    .long("fresh")
+                    .takes_value(false)
+                    .help("Download all the reports instead of the ones changed since the last import"),
+            ),
+        Command::new("import_ghsa")
+            .about("imports the security advisories from the GitHub Advisory Database")
+            .arg(data_arg())
+            .arg(
+                Arg::new("fresh")
+                    .short('f')
+                    .long("fresh")
+                    .takes_value(false)
+                    .help("Import all the advisories instead of the ones updated since the last import"),
+            ),
+        Command::new("import_redhat")
+            .about("imports the CVEs of the RHEL packages from the Red Hat Security Data API")
+            .arg(data_arg())
+            .arg(
+                Arg::new("fresh")
+                    .short('f')
+                    .long("fresh")
+                    .takes_value(false)
+                    .help("Import all the CVEs instead of the ones published since shortly before the last import"),
+            ),
+        Command::new("import_alpine")
+            .about("imports the CVEs of the Alpine packages from the Alpine security database")
+            .arg(data_arg())
+            .arg(fresh_arg()),
+        Command::new("import_amazon")
+            .about("imports the CVEs of the Amazon Linux packages from the Amazon Linux Security Advisories")
+            .arg(data_arg())
+            .arg(fresh_arg()),
+        Command::new("import_oracle")
+            .about("imports the CVEs of the Oracle Linux packages from the Oracle Linux errata")
+            .arg(data_arg())
+            .arg(fresh_arg()),
+        Command::new("import_rocky")
+            .about("imports the CVEs of the Rocky Linux packages from the Rocky Linux Security Advisories")
+            .arg(data_arg())
+            .arg(
+                Arg::new("fresh")
+                    .short('f')
+                    .long("fresh")
+                    .takes_value(false)
+                    .help("Import all the advisories instead of the ones published since shortly before the last import"),
+            ),
+        Command::new("import_almalinux")
+            .about("imports the CVEs of the AlmaLinux packages from the AlmaLinux Security Advisories")
+            .arg(data_arg())
+            .arg(fresh_arg()),
+        Command::new("import_debian")
+            .about("imports the CVEs of the Debian packages from the Debian Security Tracker")
+            .arg(data_arg())
+            .arg(fresh_arg()),
+        Command::new("import_suse")
+            .about("imports the CVEs of the SUSE and openSUSE packages from the SUSE OVAL definitions")
+            .arg(data_arg())
+            .arg(fresh_arg()),
+        Command::new("import_gentoo")
+            .about("imports the CVEs of the Gentoo packages from the Gentoo Linux Security Advisories")
+            .arg(data_arg())
+            .arg(fresh_arg()),
+        Command::new("import_ubuntu")
+            .about("imports the CVEs of the Ubuntu packages from the Ubuntu Security Notices")
+            .arg(data_arg())
+            .arg(
+                Arg::new("fresh")
+                    .short('f')
+                    .long("fresh")
+                    .takes_value(false)
+                    .help("Import all the notices instead of the ones published since shortly before the last import"),
+            ),
+        Command::new("import_msrc")
+            .about("imports the CVEs of the Microsoft products from the Microsoft Security Update Guide")
+            .arg(data_arg())
+            .arg(
+                Arg::new("fresh")
+                    .short('f')
+                    .long("fresh")
+                    .takes_value(false)
+                    .help("Import all the monthly updates instead of the ones revised since shortly before the last import"),
+            ),
+        Command::new("import_cisco")
+            .about("imports the CVEs of the Cisco IOS and IOS XE releases from the Cisco openVuln API")
+            .arg(data_arg()),
+        Command::new("import_jvn")
+            .about("imports the vulnerabilities of the Japanese products from JVN iPedia")
+            .arg(data_arg())
+            .arg(fresh_arg()),
+        Command::new("import_cnnvd")
+            .about("imports the vulnerabilities of the CNNVD XML dumps found in the data path")
+            .arg(data_arg())
+            .arg(
+                Arg::new("mapping")
+                    .short('m')
+                    .long("mapping")
+                    .takes_value(true)
+                    .help("CSV file mapping the CNNVD ids to CVE ids, like KEPLER_CNNVD_MAPPING"),
+            ),
+        Command::new("import_kev")
+            .about("imports the catalog of known exploited vulnerabilities from CISA")
+            .arg(data_arg())
+            .arg(fresh_arg()),
+        Command::new("import_epss")
+            .about("imports the EPSS scores of the CVEs from FIRST")
+            .arg(data_arg())
+            .arg(fresh_arg()),
+        Command::new("import_exploitdb")
+            .about("imports the public exploits of the CVEs archived by Exploit-DB")
+            .arg(data_arg())
+            .arg(fresh_arg()),
+        Command::new("import_metasploit")
+            .about("imports the Metasploit exploit modules of the CVEs")
+            .arg(data_arg())
+            .arg(fresh_arg()),
+        Command::new("import_cwe")
+            .about("imports the MITRE CWE catalog naming the weaknesses of the CVEs")
+            .arg(data_arg())
+            .arg(fresh_arg()),
+        Command::new("import_capec")
+            .about("imports the MITRE CAPEC attack patterns exploiting the weaknesses of the CVEs")
+            .arg(data_arg())
+            .arg(fresh_arg()),
+        Command::new("import_eol")
+            .about("imports the release cycles end of life dates from endoflife.date")
+            .arg(data_arg())
+            .arg(fresh_arg()),
+    ]
+}
+
+/// Days the changes of the records are kept for the mirrors to sync, set with
+/// `KEPLER_CHANGES_RETENTION`.
+#[cfg(feature = "import")]
+const DEFAULT_CHANGES_RETENTION_DAYS: u64 = 30;
+
+#[cfg(feature = "import")]
+fn changes_retention() -> Result<Duration> {
+    let days = match env::var("KEPLER_CHANGES_RETENTION") {
+        Ok(value) => match value.parse::<u64>() {
+            Ok(days) if days > 0 => days,
+            _ => return Err(anyhow!("invalid KEPLER_CHANGES_RETENTION: {}", value)),
+        },
+        Err(_) => DEFAULT_CHANGES_RETENTION_DAYS,
+    };
+    Ok(Duration::from_secs(days * 24 * 3600))
+}
+
+#[cfg(feature = "import")]
+fn report_message(num_records: u32) -> String {
+    if num_records == 0 {
+        "No new records created".to_string()
+    } else {
+        format!("{num_records} new records created")
+    }
+}
+
+/// Imports the data source of the subcommand, recording the run along with its outcome and
+/// the files it downloaded.
+#[cfg(feature = "import")]
+pub fn import(pool: &Pool, exec_name: &str, matches: &ArgMatches) -> Result<()> {
+    let run_id = db::Database(pool.get()?)
+        .create_import_run(db::models::NewImportRun::with(exec_name.to_string()))
+        .map_err(|e| anyhow!(e))?;
+
+    let result = run_import(pool, exec_name, matches);
+
+    let (outcome, num_records, error) = match &result {
+        Ok(num_records) => (db::models::IMPORT_SUCCEEDED, *num_records, None),
+        Err(e) => (db::models::IMPORT_FAILED, 0, Some(format!("{:#}", e))),
+    };
+    let recorded = pool.get().map_err(|e| e.to_string()).and_then(|conn| {
+        db::Database(conn).finish_import_run(
+            run_id,
+            outcome,
+            num_records as i64,
+            allowlist::num_skipped() as i64,
+            crate::utils::take_downloads(),
+            error,
+        )
+    });
+    // the outcome of the import matters more than its record
+    if let Err(e) = recorded {
+        log::warn!("could not record the import run: {}", e);
+    }
+
+    result.map(|_| ())
+}
+
+/// Imports the data source of the subcommand, then re-evaluates the assets against the new
+/// records, returning the number of records created.
+#[cfg(feature = "import")]
+fn run_import(pool: &Pool, exec_name: &str, matches: &ArgMatches) -> Result<u32> {
+    // Handle data directory creation
+    let data_path = PathBuf::from(matches.value_of("data").unwrap());
+    if !data_path.exists() {
+        log::info!("creating {}", data_path.display());
+        fs::create_dir_all(&data_path).expect("could not create data path");
+    }
+
+    // Import by command
+    let started = Utc::now();
+    let notifier = Notifier::from_env();
+    let bus = Bus::from_env().map_err(|e| anyhow!(e))?;
+    let (_, revision) = db::Database(pool.get()?)
+        .get_revisions()
+        .map_err(|e| anyhow!(e))?;
+    let filter = Allowlist::from_env().map_err(|e| anyhow!(e))?;
+    if let Some(filter) = &filter {
+        log::info!("only importing the records of {filter}");
+    }
+    allowlist::activate(filter.clone());
+    let since_year = match exec_name {
+        "import_nist" => nist::import::since_year(matches.value_of("since-year"))?,
+        _ => None,
+    };
+    let result = match exec_name {
+        "import_nist" if matches.is_present("incremental") => nist::import::sync(pool, since_year),
+
+        "import_nist" => nist::import::run(
+            pool,
+            matches.value_of("year").unwrap(),
+            &data_path,
+            matches.is_present("fresh"),
+            matches.is_present("feed"),
+            since_year,
+        ),
+
+        "import_cpe" => nist::dictionary::import::run(pool, matches.is_present("fresh")),
+
+        "import_cvelist" => cvelist::import::run(pool, &data_path, matches.is_present("fresh")),
+
+        "import_npm" => npm::import::run(pool, matches.is_present("recent"), &data_path),
+
+        "import_osv" => osv::import::run(
+            pool,
+            &matches
+                .values_of("ecosystem")
+                .map(|values| values.collect::<Vec<_>>())
+                .unwrap_or_default(),
+            &data_path,
+            matches.is_present("fresh"),
+        ),
+
+        "import_govulndb" => govulndb::import::run(pool, &data_path, matches.is_present("fresh")),
+
+        "import_ghsa" => ghsa::import::run(pool, matches.is_present("fresh")),
+
+        "import_redhat" => redhat::import::run(pool, matches.is_present("fresh")),
+
+        "import_alpine" => alpine::import::run(pool, &data_path, matches.is_present("fresh")),
+
+        "import_amazon" => amazon::import::run(pool, &data_path, matches.is_present("fresh")),
+
+        "import_oracle" => oracle::import::run(pool, &data_path, matches.is_present("fresh")),
+
+        "import_rocky" => rocky::import::run(pool, matches.is_present("fresh")),
+
+        "import_almalinux" => almalinux::import::run(pool, &data_path, matches.is_present("fresh")),
+
+        "import_debian" => debian::import::run(pool, &data_path, matches.is_present("fresh")),
+
+        "import_suse" => suse::import::run(pool, &data_path, matches.is_present("fresh")),
+
+        "import_gentoo" => gentoo::import::run(pool, &data_path, matches.is_present("fresh")),
+
+        "import_ubuntu" => ubuntu::import::run(pool, matches.is_present("fresh")),
+
+        "import_msrc" => msrc::import::run(pool, matches.is_present("fresh")),
+
+        "import_cisco" => cisco::import::run(pool),
+
+        "import_jvn" => jvn::import::run(pool, &data_path, matches.is_present("fresh")),
+
+        "import_cnnvd" => cnnvd::import::run(
+            pool,
+            &data_path,
+            cnnvd::import::mapping_path(matches.value_of("mapping")).as_deref(),
+        ),
+
+        "import_kev" => kev::import::run(pool, &data_path, matches.is_present("fresh")),
+
+        "import_epss" => epss::import::run(pool, &data_path, matches.is_present("fresh")),
+
+        "import_exploitdb" => exploitdb::import::run(pool, &data_path, matches.is_present("fresh")),
+
+        "import_metasploit" => {
+            metasploit::import::run(pool, &data_path, matches.is_present("fresh"))
+        }
+
+        "import_cwe" => cwe::import::run(pool, &data_path, matches.is_present("fresh")),
+
+        "import_capec" => capec::import::run(pool, &data_path, matches.is_present("fresh")),
+
+        "import_eol" => eol::import::run(pool, &data_path, matches.is_present("fresh")),
+
+        _ => unreachable!("Trying to launch a not existent subcommand"),
+    };
+
+    let num_records = match result {
+        Ok(num_records) => num_records,
+        Err(err) => {
+            if let Some(notifier) = &notifier {
+                notifier.notify(&Event::import_failed(exec_name, &err));
+            }
+            return Err(err);
+        }
+    };
+
+    memory::log_usage(exec_name);
+
+    let report = report_message(num_records);
+
+    log::info!("{report}");
+
+    if num_records > 0 {
+        let num_findings =
+            monitor::run(pool, started, notifier.as_ref()).map_err(|e| anyhow!(e))?;
+        log::info!("{num_findings} new asset findings recorded");
+    }
+
+    let database = db::Database(pool.get()?);
+
+    let num_skipped = allowlist::num_skipped();
+    if filter.is_some() {
+        let num_deleted = database
+            .delete_unreferenced_objects(&allowlist::skipped_objects())
+            .map_err(|e| anyhow!(e))?;
+        log::info!("{num_skipped} records skipped by the allowlist, {num_deleted} objects deleted");
+    }
+    database
+        .set_import_filter(db::models::NewImportFilter::with(
+            exec_name.to_string(),
+            filter.map(|filter| filter.to_string()),
+            num_skipped as i64,
+            since_year,
+        ))
+        .map_err(|e| anyhow!(e))?;
+
+    if num_records > 0 {
+        if let Some(writer) = sink::from_env().map_err(|e| anyhow!(e))? {
+            let num_rows = sink::stream_since(writer.as_ref(), &database, started)
+                .map_err(|e| anyhow!("could not stream the new records: {}", e))?;
+            log::info!("{num_rows} records streamed to {}", writer.name());
+        }
+    }
+
+    if let Some(bus) = &bus {
+        publish_changes(bus, &database, revision.unwrap_or_default());
+    }
+
+    let num_pruned = database
+        .prune_cve_changes(started - chrono::Duration::from_std(changes_retention()?)?)
+        .map_err(|e| anyhow!(e))?;
+    log::info!("{num_pruned} old record changes pruned");
+
+    collect_garbage(&database)?;
+
+    Ok(num_records)
+}
+
+/// Objects written more recently are kept by the garbage collection, as an import running
+/// meanwhile creates them before their records.
+const GC_GRACE_PERIOD: Duration = Duration::from_secs(3600);
+
+/// Deletes the objects, the product targets and the revisions no record refers to anymore,
+/// left behind by the imports deleting or rewriting records.
+pub fn collect_garbage(database: &db::Database) -> Result<()> {
+    let num_objects = database
+        .delete_orphaned_objects(Utc::now() - chrono::Duration::from_std(GC_GRACE_PERIOD)?)
+        .map_err(anyhow::Error::msg)?;
+    let num_targets = database
+        .delete_orphaned_product_targets()
+        .map_err(anyhow::Error::msg)?;
+    let num_revisions = database
+        .delete_empty_revisions()
+        .map_err(anyhow::Error::msg)?;
+    log::info!(
+        "{num_objects} orphaned objects, {num_targets} product targets and {num_revisions} revisions deleted"
+    );
+
+    Ok(())
+}
+
+/// Publishes the events of the CVEs changed by the import, only logging failures as the
+/// publication is best effort.
+#[cfg(feature = "import")]
+fn publish_changes(bus: &Bus, database: &db::Database, since_revision: i32) {
+    let result = database
+        .get_cve_changes_since(since_revision)
+        .and_then(|log| {
+            let revision = log.last().map_or(since_revision, |change| change.id);
+            let changes = Changes::from_log(since_revision, revision, &log);
+
+            let ids: Vec<String> = changes
+                .added
+                .iter()
+                .chain(&changes.modified)
+                .cloned()
+                .collect();
+            let records = database.get_cves(&ids)?;
+            let events = bus::cve_events(&changes, &records);
+
+            bus.publish(&events).map(|_| events.len())
+        });
+
+    match result {
+        Ok(num_events) => log::info!("{num_events} CVE events published"),
+        Err(e) => log::warn!("{e}"),
+    }
+}
+
+/// Runs the API server until it's stopped.
+#[cfg(feature = "server")]
+pub fn serve(pool: Pool) -> Result<()> {
+    actix_web::rt::System::new().block_on(async move {
+        crate::api::run(pool)?.await?;
+        Ok::<(), anyhow::Error>(())
+    })
+}
@@ -0,0 +1,145 @@
+//! Crash reports of the panics, logged along with what's needed to investigate them and,
+//! when `KEPLER_CRASH_REPORT_URL` is set, posted to it.
+
+use std::any::Any;
+use std::cell::RefCell;
+use std::env;
+use std::panic::{self, PanicInfo};
+use std::thread;
+
+use chrono::{SecondsFormat, Utc};
+use ring::digest::{digest, SHA256};
+use serde::Serialize;
+
+thread_local! {
+    /// Id of the last crash report of the thread, for the panics caught to refer to it.
+    static LAST_REPORT: RefCell<Option<String>> = RefCell::new(None);
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Report {
+    pub id: String,
+    pub time: String,
+    pub version: String,
+    /// Hash of the settings, telling apart the installations and their changes without
+    /// disclosing the settings.
+    pub config_hash: String,
+    pub thread: String,
+    pub message: String,
+    pub location: Option<String>,
+    pub backtrace: String,
+}
+
+impl Report {
+    pub fn new(info: &PanicInfo) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            time: Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true),
+            version: crate::version().to_string(),
+            config_hash: config_hash(env::vars()),
+            thread: thread::current().name().unwrap_or("unnamed").to_string(),
+            message: message(info.payload()),
+            location: info
+                .location()
+                .map(|location| format!("{}:{}", location.file(), location.line())),
+            backtrace: format!("{:?}", backtrace::Backtrace::new()),
+        }
+    }
+}
+
+/// Hash of the `KEPLER_` settings among the environment variables.
+fn config_hash<I: Iterator<Item = (String, String)>>(vars: I) -> String {
+    let mut settings: Vec<String> = vars
+        .filter(|(name, _)| name.starts_with("KEPLER_"))
+        .map(|(name, value)| format!("{}={}", name, value))
+        .collect();
+    settings.sort();
+
+    digest(&SHA256, settings.join("\n").as_bytes())
+        .as_ref()
+        .iter()
+        .take(8)
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+fn message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic".to_string()
+    }
+}
+
+/// Takes the id of the last crash report of the current thread, if any.
+pub fn take_last() -> Option<String> {
+    LAST_REPORT.with(|last| last.borrow_mut().take())
+}
+
+/// Replaces the default panic hook by one logging a crash report, and posting it to
+/// `KEPLER_CRASH_REPORT_URL` when set.
+pub fn install() {
+    #[cfg(feature = "http")]
+    let notifier = env::var("KEPLER_CRASH_REPORT_URL").ok().map(|url| {
+        log::info!("posting crash reports to {}", url);
+        crate::notify::Notifier::new(&url)
+    });
+
+    panic::set_hook(Box::new(move |info| {
+        let report = Report::new(info);
+        LAST_REPORT.with(|last| *last.borrow_mut() = Some(report.id.clone()));
+
+        match serde_json::to_string(&report) {
+            Ok(json) => log::error!("crash report: {}", json),
+            Err(_) => log::error!("crash report {}: {}", report.id, report.message),
+        }
+
+        // the blocking client can't run on the threads of the async runtime
+        #[cfg(feature = "http")]
+        if let Some(notifier) = notifier.clone() {
+            let event = crate::notify::Event::Crashed(report);
+            let _ = thread::spawn(move || notifier.notify(&event)).join();
+        }
+    }));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{config_hash, message};
+
+    fn vars(vars: &[(&str, &str)]) -> impl Iterator<Item = (String, String)> {
+        vars.iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+
+    #[test]
+    fn can_hash_settings() {
+        let hash = config_hash(vars(&[("KEPLER_PORT", "8000"), ("KEPLER_TIMEOUT", "30")]));
+        assert_eq!(16, hash.len());
+
+        // neither the order nor the other variables matter
+        assert_eq!(
+            hash,
+            config_hash(vars(&[
+                ("KEPLER_TIMEOUT", "30"),
+                ("HOME", "/root"),
+                ("KEPLER_PORT", "8000")
+            ]))
+        );
+        assert_ne!(
+            hash,
+            config_hash(vars(&[("KEPLER_PORT", "8080"), ("KEPLER_TIMEOUT", "30")]))
+        );
+    }
+
+    #[test]
+    fn can_get_panic_messages() {
+        assert_eq!("static", message(&"static"));
+        assert_eq!("formatted 1", message(&format!("formatted {}", 1)));
+        assert_eq!("unknown panic", message(&1));
+    }
+}
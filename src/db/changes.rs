@@ -0,0 +1,99 @@
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+use super::models;
+
+/// CVEs whose records changed between two revisions of the dataset.
+#[derive(Debug, Default, PartialEq, Serialize)]
+pub struct Changes {
+    pub since_revision: i32,
+    pub revision: i32,
+    /// CVEs without records at the first revision.
+    pub added: Vec<String>,
+    /// CVEs with records at both revisions, some of them added or removed.
+    pub modified: Vec<String>,
+    /// CVEs without records anymore.
+    pub removed: Vec<String>,
+}
+
+impl Changes {
+    /// Sums up the changes logged after the first revision, in order.
+    pub fn from_log(since_revision: i32, revision: i32, log: &[models::CveChange]) -> Self {
+        // records of each CVE before its first change and after its last one
+        let mut records: BTreeMap<&str, (i32, i32)> = BTreeMap::new();
+        for change in log {
+            records
+                .entry(change.cve.as_str())
+                .or_insert((change.records_before, change.records_after))
+                .1 = change.records_after;
+        }
+
+        let mut changes = Self {
+            since_revision,
+            revision,
+            ..Default::default()
+        };
+        for (cve, (before, after)) in records {
+            match (before > 0, after > 0) {
+                (false, true) => changes.added.push(cve.to_string()),
+                (true, true) => changes.modified.push(cve.to_string()),
+                (true, false) => changes.removed.push(cve.to_string()),
+                // added then removed in between
+                (false, false) => {}
+            }
+        }
+        changes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::Changes;
+    use crate::db::models::CveChange;
+
+    fn change(id: i32, cve: &str, records_before: i32, records_after: i32) -> CveChange {
+        CveChange {
+            id,
+            created_at: Utc::now(),
+            cve: cve.to_string(),
+            records_before,
+            records_after,
+        }
+    }
+
+    #[test]
+    fn can_sum_up_changes() {
+        let log = vec![
+            change(11, "CVE-2022-0001", 0, 1),
+            change(12, "CVE-2022-0001", 1, 2),
+            change(13, "CVE-2021-0001", 2, 3),
+            change(14, "CVE-2020-0001", 1, 0),
+            change(15, "CVE-2022-0002", 0, 1),
+            change(16, "CVE-2022-0002", 1, 0),
+            change(17, "CVE-2019-0001", 1, 0),
+            change(18, "CVE-2019-0001", 0, 1),
+        ];
+
+        assert_eq!(
+            Changes {
+                since_revision: 10,
+                revision: 18,
+                added: vec!["CVE-2022-0001".into()],
+                modified: vec!["CVE-2019-0001".into(), "CVE-2021-0001".into()],
+                removed: vec!["CVE-2020-0001".into()],
+            },
+            Changes::from_log(10, 18, &log)
+        );
+        assert_eq!(
+            Changes {
+                since_revision: 18,
+                revision: 18,
+                ..Default::default()
+            },
+            Changes::from_log(18, 18, &[])
+        );
+    }
+}
@@ -1,13 +1,21 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::ops::Deref;
+use std::time::Duration;
 
+use chrono::{DateTime, Utc};
 use diesel::insert_into;
 use diesel::pg::PgConnection;
 use diesel::prelude::*;
 use r2d2_diesel::ConnectionManager;
 
+pub mod changes;
 pub mod models;
 pub mod schema;
 
+use crate::search::Query;
+use crate::sources::allowlist;
+use crate::utils::Download;
+
 pub struct Database(pub r2d2::PooledConnection<ConnectionManager<PgConnection>>);
 
 impl Deref for Database {
@@ -18,6 +26,35 @@ impl Deref for Database {
     }
 }
 
+/// Connection whose queries are cancelled after a timeout, getting back the default timeout
+/// of the server once dropped so that the next users of the pooled connection don't inherit it.
+pub struct TimedDatabase(Database);
+
+impl TimedDatabase {
+    pub fn new(database: Database, timeout: Duration) -> Self {
+        if let Err(err) = database.set_statement_timeout(timeout) {
+            log::warn!("{}", err);
+        }
+        Self(database)
+    }
+}
+
+impl Deref for TimedDatabase {
+    type Target = Database;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl Drop for TimedDatabase {
+    fn drop(&mut self) {
+        if let Err(err) = self.0.reset_statement_timeout() {
+            log::warn!("{}", err);
+        }
+    }
+}
+
 pub type Pool = r2d2::Pool<ConnectionManager<PgConnection>>;
 
 pub fn setup(database_url: &str) -> Result<Pool, anyhow::Error> {
@@ -51,6 +88,10 @@ impl Database {
     pub fn create_cve_if_not_exist(&self, values: models::NewCVE) -> Result<bool, String> {
         use schema::cves::dsl::*;
 
+        if !allowlist::admits(&values.vendor, &values.product, values.object_id) {
+            return Ok(false);
+        }
+
         // check if we have it already by (vendor, product, cve)
         let found: i64 = cves
             .filter(
@@ -76,6 +117,117 @@ impl Database {
         Ok(true)
     }
 
+    /// Creates the object of the CVE, or replaces the data of the existing one.
+    pub fn upsert_object(&self, values: models::NewObject) -> Result<i32, String> {
+        use schema::objects::dsl::*;
+
+        let found = objects
+            .filter(cve.eq(&values.cve))
+            .first::<models::Object>(self.deref())
+            .optional()
+            .map_err(|e| format!("error getting record: {}", e))?;
+
+        match found {
+            Some(obj) if obj.data == values.data => Ok(obj.id),
+            Some(obj) => diesel::update(objects.filter(id.eq(obj.id)))
+                .set((updated_at.eq(Some(Utc::now())), data.eq(&values.data)))
+                .execute(self.deref())
+                .map(|_| obj.id)
+                .map_err(|e| format!("error updating record: {}", e)),
+            None => insert_into(objects)
+                .values(values)
+                .get_result::<models::Object>(self.deref())
+                .map(|obj| obj.id)
+                .map_err(|e| format!("error creating record: {}", e)),
+        }
+    }
+
+    /// Creates the record, or updates the existing one with the same (vendor, product, cve),
+    /// returns false if it didn't change.
+    pub fn upsert_cve(&self, values: models::NewCVE) -> Result<bool, String> {
+        use schema::cves::dsl::*;
+
+        if !allowlist::admits(&values.vendor, &values.product, values.object_id) {
+            return Ok(false);
+        }
+
+        let found = cves
+            .filter(
+                vendor
+                    .eq(&values.vendor)
+                    .and(product.eq(&values.product))
+                    .and(cve.eq(&values.cve)),
+            )
+            .first::<models::CVE>(self.deref())
+            .optional()
+            .map_err(|e| format!("error getting record: {}", e))?;
+
+        let found = match found {
+            Some(found) => found,
+            None => {
+                return insert_into(cves)
+                    .values(values)
+                    .execute(self.deref())
+                    .map(|_| true)
+                    .map_err(|e| format!("error creating record: {}", e))
+            }
+        };
+
+        if found.summary == values.summary
+            && found.score == values.score
+            && found.severity == values.severity
+            && found.vector == values.vector
+            && found.references.0 == values.references.0
+            && found.object_id == values.object_id
+        {
+            return Ok(false);
+        }
+
+        diesel::update(cves.filter(id.eq(found.id)))
+            .set((
+                updated_at.eq(Some(Utc::now())),
+                summary.eq(&values.summary),
+                score.eq(values.score),
+                severity.eq(&values.severity),
+                vector.eq(&values.vector),
+                references.eq(&values.references),
+                object_id.eq(values.object_id),
+            ))
+            .execute(self.deref())
+            .map(|_| true)
+            .map_err(|e| format!("error updating record: {}", e))
+    }
+
+    /// Deletes the records of the CVE from the source about other products than the given
+    /// (vendor, product) pairs, no longer affected since.
+    pub fn delete_other_products(
+        &self,
+        the_source: &str,
+        the_cve: &str,
+        products: &[(String, String)],
+    ) -> Result<usize, String> {
+        use schema::cves::dsl::*;
+
+        let found: Vec<(i32, String, String)> = cves
+            .filter(source.eq(the_source).and(cve.eq(the_cve)))
+            .select((id, vendor, product))
+            .load(self.deref())
+            .map_err(|e| format!("error listing records: {}", e))?;
+
+        let stale: Vec<i32> = found
+            .into_iter()
+            .filter(|(_, v, p)| !products.iter().any(|(pv, pp)| pv == v && pp == p))
+            .map(|(record, _, _)| record)
+            .collect();
+        if stale.is_empty() {
+            return Ok(0);
+        }
+
+        diesel::delete(cves.filter(id.eq_any(stale)))
+            .execute(self.deref())
+            .map_err(|e| format!("error deleting records: {}", e))
+    }
+
     pub fn delete_cve(
         &self,
         the_vendor: &str,
@@ -96,6 +248,15 @@ impl Database {
         .map_err(|e| format!("error deleting record: {}", e))
     }
 
+    /// Deletes the records of the given CVEs from the source.
+    pub fn delete_source_cves(&self, the_source: &str, ids: &[&str]) -> Result<usize, String> {
+        use schema::cves::dsl::*;
+
+        diesel::delete(cves.filter(source.eq(the_source).and(cve.eq_any(ids))))
+            .execute(self.deref())
+            .map_err(|e| format!("error deleting records: {}", e))
+    }
+
     pub fn search(
         &self,
         by_vendor: Option<&String>,
@@ -118,6 +279,48 @@ impl Database {
         })
     }
 
+    /// Any record of the product imported from the source, along with its object.
+    pub fn get_product_sample(
+        &self,
+        by_vendor: &str,
+        by_product: &str,
+        by_source: &str,
+    ) -> Result<Option<(models::CVE, models::Object)>, String> {
+        use schema::cves::dsl::*;
+        use schema::objects::dsl::objects;
+
+        cves.filter(
+            vendor
+                .eq(by_vendor)
+                .and(product.eq(by_product))
+                .and(source.eq(by_source)),
+        )
+        .inner_join(objects)
+        .first(self.deref())
+        .optional()
+        .map_err(|e| format!("error getting a record of the product: {}", e))
+    }
+
+    /// All the records along with their object, to preload them in memory.
+    pub fn get_all_records(&self) -> Result<Vec<(models::CVE, models::Object)>, String> {
+        use schema::cves::dsl::*;
+        use schema::objects::dsl::*;
+
+        cves.inner_join(objects)
+            .load(self.deref())
+            .map_err(|e| format!("error loading records: {}", e))
+    }
+
+    /// Number of records and highest record id, changing whenever records are imported or
+    /// deleted.
+    pub fn get_records_generation(&self) -> Result<(i64, Option<i32>), String> {
+        use schema::cves::dsl::*;
+
+        cves.select((diesel::dsl::count_star(), diesel::dsl::max(id)))
+            .first(self.deref())
+            .map_err(|e| format!("error counting records: {}", e))
+    }
+
     pub fn get_products(&self) -> Result<Vec<models::Product>, String> {
         use schema::cves::dsl::*;
 
@@ -127,31 +330,1460 @@ impl Database {
             .get_results::<(String, String)>(self.deref())
             .map_err(|e| format!("error fetching products: {}", e))?;
 
-        Ok(prods
-            .iter()
-            .map(|(v, p)| models::Product {
-                vendor: v.into(),
-                product: p.into(),
-            })
-            .collect())
+        self.with_targets(prods)
     }
 
+    /// Products having records matching the query, along with the ones of the CPE dictionary
+    /// without any.
     pub fn search_products(&self, query: &str) -> Result<Vec<models::Product>, String> {
         use schema::cves::dsl::*;
 
-        let prods: Vec<(String, String)> = cves
+        let mut prods: Vec<(String, String)> = cves
             .select((vendor, product))
             .distinct()
             .filter(product.like(format!("%{}%", query)))
             .get_results::<(String, String)>(self.deref())
             .map_err(|e| format!("error searching products: {}", e))?;
 
+        let found: HashSet<(String, String)> = prods.iter().cloned().collect();
+        prods.extend(
+            self.get_dictionary_products(Some(query))?
+                .into_iter()
+                .filter(|prod| !found.contains(prod)),
+        );
+
+        self.with_targets(prods)
+    }
+
+    /// Lists the (vendor, product) pairs as products, along with the software they run on and
+    /// their display names.
+    fn with_targets(&self, prods: Vec<(String, String)>) -> Result<Vec<models::Product>, String> {
+        use schema::product_targets::dsl::*;
+
+        let found: Vec<(String, String, String)> = product_targets
+            .select((vendor, product, target_sw))
+            .order(target_sw)
+            .load(self.deref())
+            .map_err(|e| format!("error fetching product targets: {}", e))?;
+
+        let mut targets: HashMap<(String, String), Vec<String>> = HashMap::new();
+        for (v, p, target) in found {
+            targets.entry((v, p)).or_default().push(target);
+        }
+        let names: HashMap<(String, String), models::Name> = self
+            .get_display_names()?
+            .into_iter()
+            .map(|entry| ((entry.vendor.clone(), entry.product.clone()), entry.name()))
+            .collect();
+
         Ok(prods
-            .iter()
-            .map(|(v, p)| models::Product {
-                vendor: v.into(),
-                product: p.into(),
+            .into_iter()
+            .map(|(v, p)| {
+                let target = targets.remove(&(v.clone(), p.clone())).unwrap_or_default();
+                models::Product {
+                    display_name: names.get(&(v.clone(), p.clone())).cloned(),
+                    vendor_display_name: names.get(&(v.clone(), String::new())).cloned(),
+                    vendor: v,
+                    product: p,
+                    target_sw: target,
+                }
             })
             .collect())
     }
+
+    /// Display names of the vendors and the products, the ones of the vendors first.
+    pub fn get_display_names(&self) -> Result<Vec<models::DisplayName>, String> {
+        use schema::display_names::dsl::*;
+
+        display_names
+            .order((vendor, product))
+            .load(self.deref())
+            .map_err(|e| format!("error fetching display names: {}", e))
+    }
+
+    /// Sets the display name of a vendor or a product, replacing the previous one.
+    pub fn set_display_name(
+        &self,
+        values: models::NewDisplayName,
+    ) -> Result<models::DisplayName, String> {
+        use diesel::pg::upsert::excluded;
+        use schema::display_names::dsl::*;
+
+        insert_into(display_names)
+            .values(&values)
+            .on_conflict((vendor, product))
+            .do_update()
+            .set((
+                updated_at.eq(Some(Utc::now())),
+                name.eq(excluded(name)),
+                localized.eq(excluded(localized)),
+            ))
+            .get_result(self.deref())
+            .map_err(|e| format!("error storing display name: {}", e))
+    }
+
+    /// Removes the display name of a vendor, or of a product when given, returning whether
+    /// there was one.
+    pub fn delete_display_name(&self, the_vendor: &str, the_product: &str) -> Result<bool, String> {
+        use schema::display_names::dsl::*;
+
+        diesel::delete(
+            display_names
+                .filter(vendor.eq(the_vendor))
+                .filter(product.eq(the_product)),
+        )
+        .execute(self.deref())
+        .map(|num_deleted| num_deleted > 0)
+        .map_err(|e| format!("error deleting display name: {}", e))
+    }
+
+    /// Records the software the products run on, ignoring the ones already known.
+    pub fn create_product_targets(
+        &self,
+        values: &[models::NewProductTarget],
+    ) -> Result<usize, String> {
+        use schema::product_targets::dsl::*;
+
+        insert_into(product_targets)
+            .values(values)
+            .on_conflict_do_nothing()
+            .execute(self.deref())
+            .map_err(|e| format!("error creating product targets: {}", e))
+    }
+
+    pub fn create_asset(&self, values: models::NewAsset) -> Result<models::Asset, String> {
+        use schema::assets::dsl::*;
+
+        insert_into(assets)
+            .values(values)
+            .get_result(self.deref())
+            .map_err(|e| format!("error creating asset: {}", e))
+    }
+
+    pub fn get_assets(&self) -> Result<Vec<models::Asset>, String> {
+        use schema::assets::dsl::*;
+
+        assets
+            .filter(deleted_at.is_null())
+            .order(id)
+            .load(self.deref())
+            .map_err(|e| format!("error listing assets: {}", e))
+    }
+
+    pub fn get_asset(&self, asset_id: i32) -> Result<Option<models::Asset>, String> {
+        use schema::assets::dsl::*;
+
+        assets
+            .find(asset_id)
+            .filter(deleted_at.is_null())
+            .first(self.deref())
+            .optional()
+            .map_err(|e| format!("error getting asset: {}", e))
+    }
+
+    pub fn update_asset_components(
+        &self,
+        asset_id: i32,
+        new_format: &str,
+        new_components: Vec<Query>,
+    ) -> Result<Option<models::Asset>, String> {
+        use schema::assets::dsl::*;
+
+        diesel::update(assets.find(asset_id).filter(deleted_at.is_null()))
+            .set((
+                updated_at.eq(Some(Utc::now())),
+                format.eq(new_format),
+                components.eq(diesel_json::Json::new(new_components)),
+            ))
+            .get_result(self.deref())
+            .optional()
+            .map_err(|e| format!("error updating asset: {}", e))
+    }
+
+    pub fn update_asset_labels(
+        &self,
+        asset_id: i32,
+        new_labels: models::Labels,
+    ) -> Result<Option<models::Asset>, String> {
+        use schema::assets::dsl::*;
+
+        diesel::update(assets.find(asset_id).filter(deleted_at.is_null()))
+            .set((
+                updated_at.eq(Some(Utc::now())),
+                labels.eq(diesel_json::Json::new(new_labels)),
+            ))
+            .get_result(self.deref())
+            .optional()
+            .map_err(|e| format!("error updating asset: {}", e))
+    }
+
+    /// Soft deletes the asset, which can be restored until purged.
+    pub fn delete_asset(&self, asset_id: i32) -> Result<bool, String> {
+        use schema::assets::dsl::*;
+
+        diesel::update(assets.find(asset_id).filter(deleted_at.is_null()))
+            .set(deleted_at.eq(Some(Utc::now())))
+            .execute(self.deref())
+            .map(|deleted| deleted > 0)
+            .map_err(|e| format!("error deleting asset: {}", e))
+    }
+
+    /// Creates, updates and soft deletes the assets all at once, or not at all.
+    pub fn apply_assets(
+        &self,
+        created: Vec<models::NewAsset>,
+        updated: Vec<(i32, models::NewAsset)>,
+        deleted: Vec<i32>,
+    ) -> Result<(), String> {
+        use schema::assets::dsl::*;
+
+        self.transaction::<_, diesel::result::Error, _>(|| {
+            if !created.is_empty() {
+                insert_into(assets).values(&created).execute(self.deref())?;
+            }
+            for (asset_id, values) in &updated {
+                diesel::update(assets.find(*asset_id).filter(deleted_at.is_null()))
+                    .set((
+                        updated_at.eq(Some(Utc::now())),
+                        kind.eq(&values.kind),
+                        format.eq(&values.format),
+                        components.eq(&values.components),
+                        labels.eq(&values.labels),
+                    ))
+                    .execute(self.deref())?;
+            }
+            diesel::update(assets.filter(id.eq_any(deleted).and(deleted_at.is_null())))
+                .set(deleted_at.eq(Some(Utc::now())))
+                .execute(self.deref())?;
+            Ok(())
+        })
+        .map_err(|e| format!("error applying assets: {}", e))
+    }
+
+    /// Lists the deleted assets not purged yet, most recently deleted first.
+    pub fn get_deleted_assets(&self) -> Result<Vec<models::Asset>, String> {
+        use schema::assets::dsl::*;
+
+        assets
+            .filter(deleted_at.is_not_null())
+            .order(deleted_at.desc())
+            .load(self.deref())
+            .map_err(|e| format!("error listing deleted assets: {}", e))
+    }
+
+    pub fn restore_asset(&self, asset_id: i32) -> Result<Option<models::Asset>, String> {
+        use schema::assets::dsl::*;
+
+        diesel::update(assets.find(asset_id).filter(deleted_at.is_not_null()))
+            .set(deleted_at.eq(None::<DateTime<Utc>>))
+            .get_result(self.deref())
+            .optional()
+            .map_err(|e| format!("error restoring asset: {}", e))
+    }
+
+    /// Permanently deletes the assets deleted before the given time, along with their
+    /// findings and releases.
+    pub fn purge_assets(&self, deleted_before: DateTime<Utc>) -> Result<usize, String> {
+        use schema::assets::dsl::*;
+
+        diesel::delete(assets.filter(deleted_at.lt(deleted_before)))
+            .execute(self.deref())
+            .map_err(|e| format!("error purging assets: {}", e))
+    }
+
+    /// Returns the records created since the given time.
+    pub fn get_cves_since(&self, since: DateTime<Utc>) -> Result<Vec<models::CVE>, String> {
+        use schema::cves::dsl::*;
+
+        cves.filter(created_at.ge(since))
+            .load(self.deref())
+            .map_err(|e| format!("error listing records: {}", e))
+    }
+
+    /// All the records, sorted by CVE.
+    pub fn get_all_cves(&self) -> Result<Vec<models::CVE>, String> {
+        use schema::cves::dsl::*;
+
+        cves.order((cve.asc(), id.asc()))
+            .load(self.deref())
+            .map_err(|e| format!("error listing records: {}", e))
+    }
+
+    /// Claims the idempotency key for a new request, unless it's already claimed, in which
+    /// case the request holding it is returned. The keys of the requests completed before
+    /// `expired`, or still running since before `abandoned`, are released first.
+    pub fn claim_idempotency_key(
+        &self,
+        values: models::NewIdempotencyKey,
+        expired: DateTime<Utc>,
+        abandoned: DateTime<Utc>,
+    ) -> Result<Option<models::IdempotencyKey>, String> {
+        use schema::idempotency_keys::dsl::*;
+
+        diesel::delete(
+            idempotency_keys.filter(
+                created_at
+                    .lt(expired)
+                    .or(status.is_null().and(created_at.lt(abandoned))),
+            ),
+        )
+        .execute(self.deref())
+        .map_err(|e| format!("error releasing idempotency keys: {}", e))?;
+
+        let claimed = insert_into(idempotency_keys)
+            .values(&values)
+            .on_conflict_do_nothing()
+            .execute(self.deref())
+            .map_err(|e| format!("error claiming idempotency key: {}", e))?;
+        if claimed > 0 {
+            return Ok(None);
+        }
+
+        idempotency_keys
+            .filter(key.eq(&values.key))
+            .first(self.deref())
+            .map(Some)
+            .map_err(|e| format!("error fetching idempotency key: {}", e))
+    }
+
+    /// Stores the response of the request holding the idempotency key.
+    pub fn complete_idempotency_key(
+        &self,
+        the_key: &str,
+        the_status: i32,
+        the_headers: BTreeMap<String, String>,
+        the_body: &[u8],
+    ) -> Result<(), String> {
+        use schema::idempotency_keys::dsl::*;
+
+        diesel::update(idempotency_keys.filter(key.eq(the_key)))
+            .set((
+                status.eq(Some(the_status)),
+                headers.eq(Some(diesel_json::Json::new(the_headers))),
+                body.eq(Some(the_body)),
+            ))
+            .execute(self.deref())
+            .map(|_| ())
+            .map_err(|e| format!("error storing idempotent response: {}", e))
+    }
+
+    /// Releases the idempotency key of a failed request, so that it can be retried.
+    pub fn release_idempotency_key(&self, the_key: &str) -> Result<(), String> {
+        use schema::idempotency_keys::dsl::*;
+
+        diesel::delete(idempotency_keys.filter(key.eq(the_key)))
+            .execute(self.deref())
+            .map(|_| ())
+            .map_err(|e| format!("error releasing idempotency key: {}", e))
+    }
+
+    /// Time up to which the records of the source were last synced, if ever.
+    pub fn get_synced_at(&self, the_source: &str) -> Result<Option<DateTime<Utc>>, String> {
+        use schema::sync_states::dsl::*;
+
+        sync_states
+            .filter(source.eq(the_source))
+            .select(synced_at)
+            .first(self.deref())
+            .optional()
+            .map_err(|e| format!("error getting sync state: {}", e))
+    }
+
+    pub fn set_synced_at(&self, values: models::NewSyncState) -> Result<(), String> {
+        use schema::sync_states::dsl::*;
+
+        insert_into(sync_states)
+            .values(&values)
+            .on_conflict(source)
+            .do_update()
+            .set((
+                updated_at.eq(Some(Utc::now())),
+                synced_at.eq(values.synced_at),
+            ))
+            .execute(self.deref())
+            .map(|_| ())
+            .map_err(|e| format!("error storing sync state: {}", e))
+    }
+
+    /// Creates the entries of the CPE dictionary, or replaces the ones already known by their
+    /// NVD id.
+    pub fn upsert_cpe_entries(&self, values: &[models::NewCpeEntry]) -> Result<usize, String> {
+        use diesel::pg::upsert::excluded;
+        use schema::cpe_dictionary::dsl::*;
+
+        let mut num_upserted = 0;
+        // 11 parameters a row, staying below the limit of a single statement
+        for chunk in values.chunks(5000) {
+            num_upserted += insert_into(cpe_dictionary)
+                .values(chunk)
+                .on_conflict(cpe_name_id)
+                .do_update()
+                .set((
+                    cpe.eq(excluded(cpe)),
+                    part.eq(excluded(part)),
+                    vendor.eq(excluded(vendor)),
+                    product.eq(excluded(product)),
+                    version.eq(excluded(version)),
+                    title.eq(excluded(title)),
+                    deprecated.eq(excluded(deprecated)),
+                    deprecated_by.eq(excluded(deprecated_by)),
+                    refs.eq(excluded(refs)),
+                    last_modified.eq(excluded(last_modified)),
+                ))
+                .execute(self.deref())
+                .map_err(|e| format!("error storing cpe dictionary: {}", e))?;
+        }
+        Ok(num_upserted)
+    }
+
+    /// Entries of the CPE dictionary for the versions of the product, by CPE after the given
+    /// one.
+    pub fn get_cpe_entries(
+        &self,
+        the_vendor: &str,
+        the_product: &str,
+        after: &str,
+        limit: i64,
+    ) -> Result<Vec<models::CpeEntry>, String> {
+        use schema::cpe_dictionary::dsl::*;
+
+        cpe_dictionary
+            .filter(vendor.eq(the_vendor))
+            .filter(product.eq(the_product))
+            .filter(cpe.gt(after))
+            .order(cpe)
+            .limit(limit)
+            .load(self.deref())
+            .map_err(|e| format!("error fetching cpe dictionary: {}", e))
+    }
+
+    /// Version, title and deprecation of every entry of the CPE dictionary for the product.
+    pub fn get_cpe_titles(
+        &self,
+        the_vendor: &str,
+        the_product: &str,
+    ) -> Result<Vec<(String, String, bool)>, String> {
+        use schema::cpe_dictionary::dsl::*;
+
+        cpe_dictionary
+            .select((version, title, deprecated))
+            .filter(vendor.eq(the_vendor))
+            .filter(product.eq(the_product))
+            .load(self.deref())
+            .map_err(|e| format!("error fetching cpe dictionary: {}", e))
+    }
+
+    /// Distinct versions of the product in the CPE dictionary, of any vendor unless given.
+    pub fn get_dictionary_versions(
+        &self,
+        the_vendor: Option<&str>,
+        the_product: &str,
+    ) -> Result<Vec<String>, String> {
+        use schema::cpe_dictionary::dsl::*;
+
+        let mut select = cpe_dictionary
+            .select(version)
+            .distinct()
+            .filter(product.eq(the_product))
+            .into_boxed();
+        if let Some(the_vendor) = the_vendor {
+            select = select.filter(vendor.eq(the_vendor));
+        }
+        select
+            .load(self.deref())
+            .map_err(|e| format!("error fetching cpe dictionary: {}", e))
+    }
+
+    /// Part of the CPEs of the product in the CPE dictionary, if known.
+    pub fn get_dictionary_part(
+        &self,
+        the_vendor: &str,
+        the_product: &str,
+    ) -> Result<Option<String>, String> {
+        use schema::cpe_dictionary::dsl::*;
+
+        cpe_dictionary
+            .select(part)
+            .filter(vendor.eq(the_vendor))
+            .filter(product.eq(the_product))
+            .first(self.deref())
+            .optional()
+            .map_err(|e| format!("error fetching cpe dictionary: {}", e))
+    }
+
+    /// Products of the CPE dictionary having versions not deprecated, matching the query if
+    /// any.
+    pub fn get_dictionary_products(
+        &self,
+        query: Option<&str>,
+    ) -> Result<Vec<(String, String)>, String> {
+        use schema::cpe_dictionary::dsl::*;
+
+        let mut select = cpe_dictionary
+            .select((vendor, product))
+            .distinct()
+            .filter(deprecated.eq(false))
+            .into_boxed();
+        if let Some(query) = query {
+            select = select.filter(product.like(format!("%{}%", query)));
+        }
+        select
+            .load(self.deref())
+            .map_err(|e| format!("error fetching dictionary products: {}", e))
+    }
+
+    /// Records the allowlist the import command was last run with.
+    pub fn set_import_filter(&self, values: models::NewImportFilter) -> Result<(), String> {
+        use schema::import_filters::dsl::*;
+
+        insert_into(import_filters)
+            .values(&values)
+            .on_conflict(command)
+            .do_update()
+            .set((
+                updated_at.eq(values.updated_at),
+                allowlist.eq(&values.allowlist),
+                skipped.eq(values.skipped),
+                since_year.eq(values.since_year),
+            ))
+            .execute(self.deref())
+            .map(|_| ())
+            .map_err(|e| format!("error storing import filter: {}", e))
+    }
+
+    pub fn get_import_filters(&self) -> Result<Vec<models::ImportFilter>, String> {
+        use schema::import_filters::dsl::*;
+
+        import_filters
+            .order(command.asc())
+            .load(self.deref())
+            .map_err(|e| format!("error listing import filters: {}", e))
+    }
+
+    /// Records the start of an import run, returning its id.
+    pub fn create_import_run(&self, values: models::NewImportRun) -> Result<i32, String> {
+        use schema::import_runs::dsl::*;
+
+        insert_into(import_runs)
+            .values(&values)
+            .returning(id)
+            .get_result(self.deref())
+            .map_err(|e| format!("error creating import run: {}", e))
+    }
+
+    /// Records the end of an import run, along with the files it downloaded.
+    pub fn finish_import_run(
+        &self,
+        run_id: i32,
+        the_outcome: &str,
+        num_records: i64,
+        num_skipped: i64,
+        the_downloads: Vec<Download>,
+        the_error: Option<String>,
+    ) -> Result<(), String> {
+        use schema::import_runs::dsl::*;
+
+        diesel::update(import_runs.filter(id.eq(run_id)))
+            .set((
+                finished_at.eq(Some(Utc::now())),
+                outcome.eq(the_outcome),
+                records.eq(num_records),
+                skipped.eq(num_skipped),
+                downloads.eq(diesel_json::Json::new(the_downloads)),
+                error.eq(the_error),
+            ))
+            .execute(self.deref())
+            .map(|_| ())
+            .map_err(|e| format!("error finishing import run: {}", e))
+    }
+
+    /// Import runs before the given one, the latest first, optionally of a single command.
+    pub fn get_import_runs(
+        &self,
+        the_command: Option<&str>,
+        before: Option<i32>,
+        limit: i64,
+    ) -> Result<Vec<models::ImportRun>, String> {
+        use schema::import_runs::dsl::*;
+
+        let mut query = import_runs.into_boxed();
+        if let Some(the_command) = the_command {
+            query = query.filter(command.eq(the_command));
+        }
+        if let Some(before) = before {
+            query = query.filter(id.lt(before));
+        }
+        query
+            .order(id.desc())
+            .limit(limit)
+            .load(self.deref())
+            .map_err(|e| format!("error listing import runs: {}", e))
+    }
+
+    /// Adds the searches to the identical ones already logged that day.
+    pub fn add_query_log(&self, values: &[models::NewQueryLogEntry]) -> Result<usize, String> {
+        use diesel::dsl::sql;
+        use diesel::pg::upsert::excluded;
+        use diesel::sql_types::BigInt;
+        use schema::query_log::dsl::*;
+
+        let mut num_upserted = 0;
+        // 9 parameters a row, staying below the limit of a single statement
+        for chunk in values.chunks(5000) {
+            num_upserted += insert_into(query_log)
+                .values(chunk)
+                .on_conflict((day, query))
+                .do_update()
+                .set((
+                    updated_at.eq(Some(Utc::now())),
+                    count.eq(count + excluded(count)),
+                    latency_ms.eq(latency_ms + excluded(latency_ms)),
+                    max_latency_ms.eq(sql::<BigInt>(
+                        "GREATEST(query_log.max_latency_ms, excluded.max_latency_ms)",
+                    )),
+                ))
+                .execute(self.deref())
+                .map_err(|e| format!("error storing query log: {}", e))?;
+        }
+        Ok(num_upserted)
+    }
+
+    /// Searches logged since the given day, like `2022-07-19`, by day and the most searched
+    /// product versions first.
+    pub fn get_query_log(&self, since: &str) -> Result<Vec<models::QueryLogEntry>, String> {
+        use schema::query_log::dsl::*;
+
+        query_log
+            .filter(day.ge(since))
+            .order((day, count.desc(), vendor, product, version))
+            .load(self.deref())
+            .map_err(|e| format!("error fetching query log: {}", e))
+    }
+
+    /// The queries searched the most over the days logged.
+    pub fn get_top_queries(&self, limit: i64) -> Result<Vec<Query>, String> {
+        use diesel::dsl::sql;
+        use diesel::sql_types::BigInt;
+        use schema::query_log::dsl::*;
+
+        query_log
+            .group_by(query)
+            .select(query)
+            .order(sql::<BigInt>("sum(count) DESC"))
+            .limit(limit)
+            .load::<diesel_json::Json<Query>>(self.deref())
+            .map(|queries| queries.into_iter().map(|q| q.0).collect())
+            .map_err(|e| format!("error fetching top queries: {}", e))
+    }
+
+    /// Forgets the searches logged before the given day.
+    pub fn prune_query_log(&self, before: &str) -> Result<usize, String> {
+        use schema::query_log::dsl::*;
+
+        diesel::delete(query_log.filter(day.lt(before)))
+            .execute(self.deref())
+            .map_err(|e| format!("error pruning query log: {}", e))
+    }
+
+    /// Number of records stored by each source.
+    pub fn count_cves_by_source(&self) -> Result<BTreeMap<String, i64>, String> {
+        use diesel::dsl::count_star;
+        use schema::cves::dsl::*;
+
+        cves.group_by(source)
+            .select((source, count_star()))
+            .load::<(String, i64)>(self.deref())
+            .map(|counts| counts.into_iter().collect())
+            .map_err(|e| format!("error counting records: {}", e))
+    }
+
+    /// Deletes the given objects unless records still refer to them, like the ones of the
+    /// records skipped by an import allowlist.
+    pub fn delete_unreferenced_objects(&self, ids: &[i32]) -> Result<usize, String> {
+        use schema::cves::dsl as c;
+        use schema::objects::dsl::*;
+
+        if ids.is_empty() {
+            return Ok(0);
+        }
+
+        let referenced: Vec<Option<i32>> = c::cves
+            .filter(c::object_id.eq_any(ids))
+            .select(c::object_id)
+            .distinct()
+            .load(self.deref())
+            .map_err(|e| format!("error listing records: {}", e))?;
+
+        let unreferenced: Vec<i32> = ids
+            .iter()
+            .copied()
+            .filter(|obj| !referenced.contains(&Some(*obj)))
+            .collect();
+        if unreferenced.is_empty() {
+            return Ok(0);
+        }
+
+        diesel::delete(objects.filter(id.eq_any(unreferenced)))
+            .execute(self.deref())
+            .map_err(|e| format!("error deleting objects: {}", e))
+    }
+
+    /// Tracks a CVE awaiting analysis, unless already tracked.
+    pub fn track_pending_cve(&self, values: models::NewPendingCve) -> Result<bool, String> {
+        use schema::pending_cves::dsl::*;
+
+        insert_into(pending_cves)
+            .values(&values)
+            .on_conflict_do_nothing()
+            .execute(self.deref())
+            .map(|created| created > 0)
+            .map_err(|e| format!("error tracking pending record: {}", e))
+    }
+
+    /// Marks the tracked CVEs among the given ones as reconciled, returning them.
+    pub fn reconcile_pending_cves(&self, ids: &[&str]) -> Result<Vec<String>, String> {
+        use schema::pending_cves::dsl::*;
+
+        diesel::update(pending_cves.filter(cve.eq_any(ids).and(reconciled_at.is_null())))
+            .set(reconciled_at.eq(Utc::now()))
+            .returning(cve)
+            .get_results(self.deref())
+            .map_err(|e| format!("error reconciling pending records: {}", e))
+    }
+
+    pub fn get_pending_cves(&self) -> Result<Vec<models::PendingCve>, String> {
+        use schema::pending_cves::dsl::*;
+
+        pending_cves
+            .filter(reconciled_at.is_null())
+            .order(created_at.desc())
+            .load(self.deref())
+            .map_err(|e| format!("error listing pending records: {}", e))
+    }
+
+    /// CVEs reconciled since the given time.
+    pub fn get_reconciled_cves_since(&self, since: DateTime<Utc>) -> Result<Vec<String>, String> {
+        use schema::pending_cves::dsl::*;
+
+        pending_cves
+            .select(cve)
+            .filter(reconciled_at.ge(since))
+            .load(self.deref())
+            .map_err(|e| format!("error listing reconciled records: {}", e))
+    }
+
+    /// Creation time of the most recent record of the source, if any.
+    pub fn get_latest_record_time(
+        &self,
+        the_source: &str,
+    ) -> Result<Option<DateTime<Utc>>, String> {
+        use schema::cves::dsl::*;
+
+        cves.filter(source.eq(the_source))
+            .select(diesel::dsl::max(created_at))
+            .first(self.deref())
+            .map_err(|e| format!("error fetching latest record: {}", e))
+    }
+
+    /// Changes of the records made after the given revision, in order.
+    pub fn get_cve_changes_since(&self, revision: i32) -> Result<Vec<models::CveChange>, String> {
+        use schema::cve_changes::dsl::*;
+
+        cve_changes
+            .filter(id.gt(revision))
+            .order(id.asc())
+            .load(self.deref())
+            .map_err(|e| format!("error listing changes: {}", e))
+    }
+
+    /// Oldest revision still logged and current revision of the dataset.
+    pub fn get_revisions(&self) -> Result<(Option<i32>, Option<i32>), String> {
+        use schema::cve_changes::dsl::*;
+
+        cve_changes
+            .select((diesel::dsl::min(id), diesel::dsl::max(id)))
+            .first(self.deref())
+            .map_err(|e| format!("error fetching revisions: {}", e))
+    }
+
+    /// Forgets the changes made before the given time, except the last one telling the
+    /// current revision.
+    pub fn prune_cve_changes(&self, before: DateTime<Utc>) -> Result<usize, String> {
+        use schema::cve_changes::dsl::*;
+
+        let (_, latest) = self.get_revisions()?;
+        let latest = match latest {
+            Some(latest) => latest,
+            None => return Ok(0),
+        };
+
+        diesel::delete(cve_changes.filter(created_at.lt(before).and(id.lt(latest))))
+            .execute(self.deref())
+            .map_err(|e| format!("error pruning changes: {}", e))
+    }
+
+    /// CVEs having records, sorted by id after the given one, with the revision that last
+    /// changed them.
+    pub fn get_cve_revisions(
+        &self,
+        after: &str,
+        limit: i64,
+    ) -> Result<Vec<models::CveRevision>, String> {
+        use schema::cve_revisions::dsl::*;
+
+        cve_revisions
+            .filter(records.gt(0).and(cve.gt(after)))
+            .order(cve.asc())
+            .limit(limit)
+            .load(self.deref())
+            .map_err(|e| format!("error listing cve revisions: {}", e))
+    }
+
+    /// Products having records, sorted by vendor and product after the given ones, with the
+    /// revision that last changed them.
+    pub fn get_product_revisions(
+        &self,
+        after: (&str, &str),
+        limit: i64,
+    ) -> Result<Vec<models::ProductRevision>, String> {
+        use schema::product_revisions::dsl::*;
+
+        let (after_vendor, after_product) = after;
+        product_revisions
+            .filter(records.gt(0))
+            .filter(
+                vendor
+                    .gt(after_vendor)
+                    .or(vendor.eq(after_vendor).and(product.gt(after_product))),
+            )
+            .order((vendor.asc(), product.asc()))
+            .limit(limit)
+            .load(self.deref())
+            .map_err(|e| format!("error listing product revisions: {}", e))
+    }
+
+    pub fn create_finding_if_not_exist(&self, values: models::NewFinding) -> Result<bool, String> {
+        use schema::findings::dsl::*;
+
+        insert_into(findings)
+            .values(&values)
+            .on_conflict_do_nothing()
+            .execute(self.deref())
+            .map(|created| created > 0)
+            .map_err(|e| format!("error creating finding: {}", e))
+    }
+
+    pub fn get_all_findings(&self) -> Result<Vec<models::Finding>, String> {
+        use schema::findings::dsl::*;
+
+        findings
+            .order(id)
+            .load(self.deref())
+            .map_err(|e| format!("error listing findings: {}", e))
+    }
+
+    pub fn get_findings(&self, the_asset_id: i32) -> Result<Vec<models::Finding>, String> {
+        use schema::findings::dsl::*;
+
+        findings
+            .filter(asset_id.eq(the_asset_id))
+            .order((created_at.desc(), id.desc()))
+            .load(self.deref())
+            .map_err(|e| format!("error listing findings: {}", e))
+    }
+
+    /// Creates the release of the asset, or replaces the components of an existing one with
+    /// the same version.
+    pub fn upsert_release(&self, values: models::NewRelease) -> Result<models::Release, String> {
+        use schema::releases::dsl::*;
+
+        insert_into(releases)
+            .values(&values)
+            .on_conflict((asset_id, version))
+            .do_update()
+            .set(components.eq(&values.components))
+            .get_result(self.deref())
+            .map_err(|e| format!("error creating release: {}", e))
+    }
+
+    pub fn get_releases(&self, the_asset_id: i32) -> Result<Vec<models::Release>, String> {
+        use schema::releases::dsl::*;
+
+        releases
+            .filter(asset_id.eq(the_asset_id))
+            .load(self.deref())
+            .map_err(|e| format!("error listing releases: {}", e))
+    }
+
+    /// Creates or updates the release cycles of the product, returns false if they didn't
+    /// change.
+    pub fn upsert_eol_product(&self, values: models::NewEolProduct) -> Result<bool, String> {
+        use schema::eol_products::dsl::*;
+
+        let found = eol_products
+            .filter(product.eq(&values.product))
+            .first::<models::EolProduct>(self.deref())
+            .optional()
+            .map_err(|e| format!("error getting product: {}", e))?;
+
+        if let Some(found) = found {
+            if found.cycles.0 == values.cycles.0 {
+                return Ok(false);
+            }
+        }
+
+        insert_into(eol_products)
+            .values(&values)
+            .on_conflict(product)
+            .do_update()
+            .set((updated_at.eq(Some(Utc::now())), cycles.eq(&values.cycles)))
+            .execute(self.deref())
+            .map(|_| true)
+            .map_err(|e| format!("error creating product: {}", e))
+    }
+
+    pub fn get_eol_products(&self, names: &[String]) -> Result<Vec<models::EolProduct>, String> {
+        use schema::eol_products::dsl::*;
+
+        eol_products
+            .filter(product.eq_any(names))
+            .load(self.deref())
+            .map_err(|e| format!("error getting products: {}", e))
+    }
+
+    /// Returns the CVE identifier, product and summary of all the records.
+    pub fn get_summaries(&self) -> Result<Vec<(String, String, String)>, String> {
+        use schema::cves::dsl::*;
+
+        cves.select((cve, product, summary))
+            .order(id)
+            .load(self.deref())
+            .map_err(|e| format!("error listing records: {}", e))
+    }
+
+    /// Replaces all the related CVEs with the new ones.
+    pub fn replace_related_cves(&self, values: Vec<models::NewRelatedCve>) -> Result<(), String> {
+        use schema::related_cves::dsl::*;
+
+        self.transaction::<_, diesel::result::Error, _>(|| {
+            diesel::delete(related_cves).execute(self.deref())?;
+            // stay below the limit of parameters of a single statement
+            for chunk in values.chunks(10000) {
+                insert_into(related_cves)
+                    .values(chunk)
+                    .execute(self.deref())?;
+            }
+            Ok(())
+        })
+        .map_err(|e| format!("error replacing related records: {}", e))
+    }
+
+    /// Cross-references the advisory to its CVEs, replacing the previous ones if any.
+    pub fn replace_advisory_aliases(
+        &self,
+        the_advisory: &str,
+        cves: &[&str],
+    ) -> Result<(), String> {
+        use schema::advisory_aliases::dsl::*;
+
+        let values: Vec<models::NewAdvisoryAlias> = cves
+            .iter()
+            .map(|the_cve| models::NewAdvisoryAlias::with(the_advisory.into(), the_cve.to_string()))
+            .collect();
+
+        self.transaction::<_, diesel::result::Error, _>(|| {
+            diesel::delete(
+                advisory_aliases.filter(advisory.eq(the_advisory).and(cve.ne_all(cves))),
+            )
+            .execute(self.deref())?;
+            if !values.is_empty() {
+                insert_into(advisory_aliases)
+                    .values(&values)
+                    .on_conflict_do_nothing()
+                    .execute(self.deref())?;
+            }
+            Ok(())
+        })
+        .map_err(|e| format!("error replacing advisory aliases: {}", e))
+    }
+
+    /// Cross-references of the advisory or the CVE with the given id.
+    pub fn get_advisory_aliases(&self, the_id: &str) -> Result<Vec<models::AdvisoryAlias>, String> {
+        use schema::advisory_aliases::dsl::*;
+
+        let found: Vec<String> = advisory_aliases
+            .filter(advisory.eq(the_id))
+            .select(cve)
+            .load(self.deref())
+            .map_err(|e| format!("error listing advisory aliases: {}", e))?;
+
+        // the other advisories about the same CVE too
+        advisory_aliases
+            .filter(cve.eq(the_id).or(cve.eq_any(found)))
+            .order(advisory)
+            .load(self.deref())
+            .map_err(|e| format!("error listing advisory aliases: {}", e))
+    }
+
+    /// Replaces the catalog of known exploited CVEs, updating only the changed entries, and
+    /// returns the number of CVEs added to it.
+    pub fn replace_known_exploited(
+        &self,
+        values: Vec<models::NewKnownExploited>,
+    ) -> Result<usize, String> {
+        use schema::known_exploited::dsl::*;
+
+        self.transaction::<_, diesel::result::Error, _>(|| {
+            let found: BTreeMap<String, models::KnownExploited> = known_exploited
+                .load::<models::KnownExploited>(self.deref())?
+                .into_iter()
+                .map(|entry| (entry.cve.clone(), entry))
+                .collect();
+
+            let ids: Vec<&str> = values.iter().map(|value| value.cve.as_str()).collect();
+            diesel::delete(known_exploited.filter(cve.ne_all(ids))).execute(self.deref())?;
+
+            let mut num_added = 0;
+            for value in &values {
+                match found.get(&value.cve) {
+                    None => {
+                        insert_into(known_exploited)
+                            .values(value)
+                            .execute(self.deref())?;
+                        num_added += 1;
+                    }
+                    Some(entry)
+                        if entry.vendor != value.vendor
+                            || entry.product != value.product
+                            || entry.name != value.name
+                            || entry.date_added != value.date_added
+                            || entry.due_date != value.due_date
+                            || entry.required_action != value.required_action
+                            || entry.ransomware != value.ransomware =>
+                    {
+                        diesel::update(known_exploited.filter(id.eq(entry.id)))
+                            .set((
+                                updated_at.eq(Some(Utc::now())),
+                                vendor.eq(&value.vendor),
+                                product.eq(&value.product),
+                                name.eq(&value.name),
+                                date_added.eq(&value.date_added),
+                                due_date.eq(&value.due_date),
+                                required_action.eq(&value.required_action),
+                                ransomware.eq(value.ransomware),
+                            ))
+                            .execute(self.deref())?;
+                    }
+                    Some(_) => {}
+                }
+            }
+            Ok(num_added)
+        })
+        .map_err(|e| format!("error replacing known exploited records: {}", e))
+    }
+
+    pub fn get_known_exploited(&self, ids: &[&str]) -> Result<Vec<models::KnownExploited>, String> {
+        use schema::known_exploited::dsl::*;
+
+        known_exploited
+            .filter(cve.eq_any(ids))
+            .load(self.deref())
+            .map_err(|e| format!("error getting known exploited records: {}", e))
+    }
+
+    /// Replaces the EPSS scores by the ones of the latest model run, and returns the number of
+    /// CVEs scored for the first time.
+    pub fn replace_epss_scores(&self, values: Vec<models::NewEpssScore>) -> Result<usize, String> {
+        use schema::epss_scores::dsl::*;
+
+        self.transaction::<_, diesel::result::Error, _>(|| {
+            let found: std::collections::HashSet<String> = epss_scores
+                .select(cve)
+                .load(self.deref())?
+                .into_iter()
+                .collect();
+            let num_added = values
+                .iter()
+                .filter(|value| !found.contains(&value.cve))
+                .count();
+
+            diesel::delete(epss_scores).execute(self.deref())?;
+            // stay below the limit of parameters of a single statement
+            for chunk in values.chunks(10000) {
+                insert_into(epss_scores)
+                    .values(chunk)
+                    .execute(self.deref())?;
+            }
+            Ok(num_added)
+        })
+        .map_err(|e| format!("error replacing EPSS scores: {}", e))
+    }
+
+    pub fn get_epss_scores(&self, ids: &[&str]) -> Result<Vec<models::EpssScore>, String> {
+        use schema::epss_scores::dsl::*;
+
+        epss_scores
+            .filter(cve.eq_any(ids))
+            .load(self.deref())
+            .map_err(|e| format!("error getting EPSS scores: {}", e))
+    }
+
+    /// Replaces the exploits by the ones of the latest archive, and returns the number of
+    /// exploits of CVEs not archived before.
+    pub fn replace_exploits(&self, values: Vec<models::NewExploit>) -> Result<usize, String> {
+        use schema::exploits::dsl::*;
+
+        self.transaction::<_, diesel::result::Error, _>(|| {
+            let found: std::collections::HashSet<(i32, String)> = exploits
+                .select((edb_id, cve))
+                .load(self.deref())?
+                .into_iter()
+                .collect();
+            let num_added = values
+                .iter()
+                .filter(|value| !found.contains(&(value.edb_id, value.cve.clone())))
+                .count();
+
+            diesel::delete(exploits).execute(self.deref())?;
+            // stay below the limit of parameters of a single statement
+            for chunk in values.chunks(10000) {
+                insert_into(exploits)
+                    .values(chunk)
+                    .on_conflict_do_nothing()
+                    .execute(self.deref())?;
+            }
+            Ok(num_added)
+        })
+        .map_err(|e| format!("error replacing exploits: {}", e))
+    }
+
+    pub fn get_exploits(&self, ids: &[&str]) -> Result<Vec<models::Exploit>, String> {
+        use schema::exploits::dsl::*;
+
+        exploits
+            .filter(cve.eq_any(ids))
+            .order(edb_id.asc())
+            .load(self.deref())
+            .map_err(|e| format!("error getting exploits: {}", e))
+    }
+
+    /// Replaces the Metasploit modules by the ones of the latest metadata, and returns the
+    /// number of modules of CVEs not referenced before.
+    pub fn replace_metasploit_modules(
+        &self,
+        values: Vec<models::NewMetasploitModule>,
+    ) -> Result<usize, String> {
+        use schema::metasploit_modules::dsl::*;
+
+        self.transaction::<_, diesel::result::Error, _>(|| {
+            let found: std::collections::HashSet<(String, String)> = metasploit_modules
+                .select((fullname, cve))
+                .load(self.deref())?
+                .into_iter()
+                .collect();
+            let num_added = values
+                .iter()
+                .filter(|value| !found.contains(&(value.fullname.clone(), value.cve.clone())))
+                .count();
+
+            diesel::delete(metasploit_modules).execute(self.deref())?;
+            // stay below the limit of parameters of a single statement
+            for chunk in values.chunks(10000) {
+                insert_into(metasploit_modules)
+                    .values(chunk)
+                    .on_conflict_do_nothing()
+                    .execute(self.deref())?;
+            }
+            Ok(num_added)
+        })
+        .map_err(|e| format!("error replacing Metasploit modules: {}", e))
+    }
+
+    pub fn get_metasploit_modules(
+        &self,
+        ids: &[&str],
+    ) -> Result<Vec<models::MetasploitModule>, String> {
+        use schema::metasploit_modules::dsl::*;
+
+        metasploit_modules
+            .filter(cve.eq_any(ids))
+            .order((rank.desc(), fullname.asc()))
+            .load(self.deref())
+            .map_err(|e| format!("error getting Metasploit modules: {}", e))
+    }
+
+    /// Replaces the CWE catalog by the latest one, and returns the number of weaknesses and
+    /// categories not listed before.
+    pub fn replace_cwes(&self, values: Vec<models::NewCwe>) -> Result<usize, String> {
+        use schema::cwes::dsl::*;
+
+        self.transaction::<_, diesel::result::Error, _>(|| {
+            let found: std::collections::HashSet<String> =
+                cwes.select(cwe).load(self.deref())?.into_iter().collect();
+            let num_added = values
+                .iter()
+                .filter(|value| !found.contains(&value.cwe))
+                .count();
+
+            diesel::delete(cwes).execute(self.deref())?;
+            for chunk in values.chunks(5000) {
+                insert_into(cwes)
+                    .values(chunk)
+                    .on_conflict_do_nothing()
+                    .execute(self.deref())?;
+            }
+            Ok(num_added)
+        })
+        .map_err(|e| format!("error replacing the CWE catalog: {}", e))
+    }
+
+    pub fn get_cwes(&self, ids: &[&str]) -> Result<Vec<models::Cwe>, String> {
+        use schema::cwes::dsl::*;
+
+        cwes.filter(cwe.eq_any(ids))
+            .load(self.deref())
+            .map_err(|e| format!("error getting CWEs: {}", e))
+    }
+
+    /// Replaces the weaknesses of the CVEs by the ones of their latest records.
+    pub fn replace_cve_weaknesses(
+        &self,
+        ids: &[String],
+        values: Vec<models::NewCveWeakness>,
+    ) -> Result<(), String> {
+        use schema::cve_weaknesses::dsl::*;
+
+        self.transaction::<_, diesel::result::Error, _>(|| {
+            for chunk in ids.chunks(10000) {
+                diesel::delete(cve_weaknesses.filter(cve.eq_any(chunk))).execute(self.deref())?;
+            }
+            for chunk in values.chunks(10000) {
+                insert_into(cve_weaknesses)
+                    .values(chunk)
+                    .on_conflict_do_nothing()
+                    .execute(self.deref())?;
+            }
+            Ok(())
+        })
+        .map_err(|e| format!("error replacing CVE weaknesses: {}", e))
+    }
+
+    pub fn get_cve_weaknesses(&self, ids: &[&str]) -> Result<Vec<models::CveWeakness>, String> {
+        use schema::cve_weaknesses::dsl::*;
+
+        cve_weaknesses
+            .filter(cve.eq_any(ids))
+            .order((cve.asc(), cwe.asc()))
+            .load(self.deref())
+            .map_err(|e| format!("error getting CVE weaknesses: {}", e))
+    }
+
+    /// Replaces the attack patterns by the ones of the latest CAPEC catalog, and returns the
+    /// number of patterns not listed before.
+    pub fn replace_attack_patterns(
+        &self,
+        values: Vec<models::NewAttackPattern>,
+    ) -> Result<usize, String> {
+        use schema::attack_patterns::dsl::*;
+
+        self.transaction::<_, diesel::result::Error, _>(|| {
+            let found: std::collections::HashSet<String> = attack_patterns
+                .select(capec)
+                .load(self.deref())?
+                .into_iter()
+                .collect();
+            let added: std::collections::HashSet<&str> = values
+                .iter()
+                .filter(|value| !found.contains(&value.capec))
+                .map(|value| value.capec.as_str())
+                .collect();
+
+            diesel::delete(attack_patterns).execute(self.deref())?;
+            for chunk in values.chunks(5000) {
+                insert_into(attack_patterns)
+                    .values(chunk)
+                    .on_conflict_do_nothing()
+                    .execute(self.deref())?;
+            }
+            Ok(added.len())
+        })
+        .map_err(|e| format!("error replacing attack patterns: {}", e))
+    }
+
+    /// Attack patterns exploiting any of the weaknesses.
+    pub fn get_attack_patterns(&self, cwes: &[&str]) -> Result<Vec<models::AttackPattern>, String> {
+        use schema::attack_patterns::dsl::*;
+
+        attack_patterns
+            .filter(cwe.eq_any(cwes))
+            .order((capec.asc(), cwe.asc()))
+            .load(self.deref())
+            .map_err(|e| format!("error getting attack patterns: {}", e))
+    }
+
+    pub fn get_related_cves(&self, the_cve: &str) -> Result<Vec<models::RelatedCve>, String> {
+        use schema::related_cves::dsl::*;
+
+        related_cves
+            .filter(cve.eq(the_cve))
+            .order((score.desc(), related))
+            .load(self.deref())
+            .map_err(|e| format!("error listing related records: {}", e))
+    }
+
+    pub fn get_cves(&self, ids: &[String]) -> Result<Vec<models::CVE>, String> {
+        use schema::cves::dsl::*;
+
+        cves.filter(cve.eq_any(ids))
+            .order(id)
+            .load(self.deref())
+            .map_err(|e| format!("error listing records: {}", e))
+    }
+
+    /// Deletes the objects last written before the given time that no record refers to, like
+    /// the configurations of the CVEs rejected or rewritten since. The newer ones are kept, as
+    /// an import running meanwhile creates the objects before their records.
+    pub fn delete_orphaned_objects(&self, written_before: DateTime<Utc>) -> Result<usize, String> {
+        diesel::sql_query(
+            "DELETE FROM objects WHERE coalesce(updated_at, created_at) < $1 \
+             AND NOT EXISTS (SELECT 1 FROM cves WHERE cves.object_id = objects.id)",
+        )
+        .bind::<diesel::sql_types::Timestamptz, _>(written_before)
+        .execute(self.deref())
+        .map_err(|e| format!("error deleting orphaned objects: {}", e))
+    }
+
+    /// Deletes the software recorded for the products without records anymore.
+    pub fn delete_orphaned_product_targets(&self) -> Result<usize, String> {
+        diesel::sql_query(
+            "DELETE FROM product_targets WHERE NOT EXISTS (SELECT 1 FROM cves \
+             WHERE cves.vendor = product_targets.vendor AND cves.product = product_targets.product)",
+        )
+        .execute(self.deref())
+        .map_err(|e| format!("error deleting orphaned product targets: {}", e))
+    }
+
+    /// Deletes the revisions of the CVEs and of the products without records anymore, which
+    /// the listings leave out.
+    pub fn delete_empty_revisions(&self) -> Result<usize, String> {
+        let num_cves = {
+            use schema::cve_revisions::dsl::*;
+
+            diesel::delete(cve_revisions.filter(records.le(0)))
+                .execute(self.deref())
+                .map_err(|e| format!("error deleting cve revisions: {}", e))?
+        };
+        let num_products = {
+            use schema::product_revisions::dsl::*;
+
+            diesel::delete(product_revisions.filter(records.le(0)))
+                .execute(self.deref())
+                .map_err(|e| format!("error deleting product revisions: {}", e))?
+        };
+        Ok(num_cves + num_products)
+    }
+
+    /// Ids of the records without an object, which the searches leave out.
+    pub fn get_records_without_object(&self) -> Result<Vec<i32>, String> {
+        use schema::cves::dsl::*;
+
+        cves.select(id)
+            .filter(object_id.is_null())
+            .load(self.deref())
+            .map_err(|e| format!("error listing records without object: {}", e))
+    }
+
+    pub fn delete_records(&self, ids: &[i32]) -> Result<usize, String> {
+        use schema::cves::dsl::*;
+
+        diesel::delete(cves.filter(id.eq_any(ids)))
+            .execute(self.deref())
+            .map_err(|e| format!("error deleting records: {}", e))
+    }
+
+    /// All the software recorded for the products, as (vendor, product, target_sw).
+    pub fn get_product_targets(&self) -> Result<Vec<(String, String, String)>, String> {
+        use schema::product_targets::dsl::*;
+
+        product_targets
+            .select((vendor, product, target_sw))
+            .load(self.deref())
+            .map_err(|e| format!("error fetching product targets: {}", e))
+    }
+
+    /// Number of CVE revisions and of product revisions whose number of records differs from
+    /// the records stored, counting the missing revisions of the CVEs and the products having
+    /// records.
+    pub fn count_divergent_revisions(&self) -> Result<(i64, i64), String> {
+        use diesel::dsl::sql;
+        use diesel::sql_types::BigInt;
+
+        let num_cves = diesel::select(sql::<BigInt>(
+            "(SELECT count(*) FROM cve_revisions r \
+             FULL JOIN (SELECT cve, count(*) AS records FROM cves GROUP BY cve) c \
+             ON c.cve = r.cve WHERE coalesce(r.records, 0) <> coalesce(c.records, 0))",
+        ))
+        .get_result(self.deref())
+        .map_err(|e| format!("error counting cve revisions: {}", e))?;
+        let num_products = diesel::select(sql::<BigInt>(
+            "(SELECT count(*) FROM product_revisions r \
+             FULL JOIN (SELECT vendor, product, count(*) AS records FROM cves \
+             GROUP BY vendor, product) c ON c.vendor = r.vendor AND c.product = r.product \
+             WHERE coalesce(r.records, 0) <> coalesce(c.records, 0))",
+        ))
+        .get_result(self.deref())
+        .map_err(|e| format!("error counting product revisions: {}", e))?;
+        Ok((num_cves, num_products))
+    }
+
+    /// Sets the number of records of the divergent CVE and product revisions to the records
+    /// stored, bumping them to the latest change so that the clients listing them by revision
+    /// fetch them again.
+    pub fn recount_revisions(&self) -> Result<usize, String> {
+        self.transaction::<_, diesel::result::Error, _>(|| {
+            let num_cves = diesel::sql_query(
+                "INSERT INTO cve_revisions (cve, revision, records) \
+                 SELECT coalesce(c.cve, r.cve), \
+                 (SELECT coalesce(max(id), 0) FROM cve_changes), coalesce(c.records, 0) \
+                 FROM cve_revisions r \
+                 FULL JOIN (SELECT cve, count(*)::integer AS records FROM cves GROUP BY cve) c \
+                 ON c.cve = r.cve WHERE coalesce(r.records, 0) <> coalesce(c.records, 0) \
+                 ON CONFLICT (cve) DO UPDATE \
+                 SET revision = EXCLUDED.revision, records = EXCLUDED.records",
+            )
+            .execute(self.deref())?;
+            let num_products = diesel::sql_query(
+                "INSERT INTO product_revisions (vendor, product, revision, records) \
+                 SELECT coalesce(c.vendor, r.vendor), coalesce(c.product, r.product), \
+                 (SELECT coalesce(max(id), 0) FROM cve_changes), coalesce(c.records, 0) \
+                 FROM product_revisions r \
+                 FULL JOIN (SELECT vendor, product, count(*)::integer AS records FROM cves \
+                 GROUP BY vendor, product) c ON c.vendor = r.vendor AND c.product = r.product \
+                 WHERE coalesce(r.records, 0) <> coalesce(c.records, 0) \
+                 ON CONFLICT (vendor, product) DO UPDATE \
+                 SET revision = EXCLUDED.revision, records = EXCLUDED.records",
+            )
+            .execute(self.deref())?;
+            Ok(num_cves + num_products)
+        })
+        .map_err(|e| format!("error recounting revisions: {}", e))
+    }
+
+    /// Makes the database server cancel the queries of this connection running longer than
+    /// the timeout.
+    pub fn set_statement_timeout(&self, timeout: Duration) -> Result<(), String> {
+        diesel::sql_query(format!("SET statement_timeout = {}", timeout.as_millis()))
+            .execute(self.deref())
+            .map(|_| ())
+            .map_err(|e| format!("error setting statement timeout: {}", e))
+    }
+
+    /// Makes the database server use its default timeout again for the queries of this
+    /// connection.
+    pub fn reset_statement_timeout(&self) -> Result<(), String> {
+        diesel::sql_query("RESET statement_timeout")
+            .execute(self.deref())
+            .map(|_| ())
+            .map_err(|e| format!("error resetting statement timeout: {}", e))
+    }
 }
@@ -1,20 +1,28 @@
-use std::time::SystemTime;
+use std::collections::BTreeMap;
 
-use serde::{Deserialize, Serialize};
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::{Serialize, Serializer};
 
-use super::schema::{cves, objects};
+use super::schema::{
+    advisory_aliases, assets, attack_patterns, cpe_dictionary, cve_weaknesses, cves, cwes,
+    display_names, eol_products, epss_scores, exploits, findings, idempotency_keys, import_filters,
+    import_runs, known_exploited, metasploit_modules, objects, pending_cves, product_targets,
+    query_log, related_cves, releases, sync_states,
+};
+use crate::search::Query;
+use crate::sources::{
+    capec, cwe, eol, exploitdb, kev, metasploit,
+    nist::{cpe, dictionary},
+};
+use crate::utils::Download;
 
-#[derive(Debug, Serialize)]
-pub struct Product {
-    pub vendor: String,
-    pub product: String,
-}
+pub use kepler_types::api::{DisplayName as Name, Product, Reference, References};
 
 #[derive(Queryable, Debug)]
 pub struct Object {
     pub id: i32,
-    pub created_at: SystemTime,
-    pub updated_at: Option<SystemTime>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: Option<DateTime<Utc>>,
     pub cve: String,
     pub data: String,
 }
@@ -22,7 +30,7 @@ pub struct Object {
 #[derive(Debug, Insertable)]
 #[table_name = "objects"]
 pub struct NewObject {
-    pub created_at: SystemTime,
+    pub created_at: DateTime<Utc>,
     pub cve: String,
     pub data: String,
 }
@@ -30,30 +38,22 @@ pub struct NewObject {
 impl NewObject {
     pub fn with(cve: String, data: String) -> Self {
         Self {
-            created_at: SystemTime::now(),
+            created_at: Utc::now(),
             cve,
             data,
         }
     }
 }
 
-#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Default)]
-pub struct Reference {
-    pub url: String,
-    pub tags: Vec<String>,
-}
-
-pub type References = Vec<Reference>;
-
 #[derive(Queryable, Debug, Clone, Serialize)]
 #[allow(clippy::upper_case_acronyms)]
 pub struct CVE {
     #[serde(skip_serializing)]
     pub id: i32,
     #[serde(skip_serializing)]
-    pub created_at: SystemTime,
+    pub created_at: DateTime<Utc>,
     #[serde(skip_serializing)]
-    pub updated_at: Option<SystemTime>,
+    pub updated_at: Option<DateTime<Utc>>,
     pub source: String,
     pub vendor: String,
     pub product: String,
@@ -70,7 +70,7 @@ pub struct CVE {
 #[derive(Debug, Insertable)]
 #[table_name = "cves"]
 pub struct NewCVE {
-    pub created_at: SystemTime,
+    pub created_at: DateTime<Utc>,
     pub source: String,
     pub vendor: String,
     pub product: String,
@@ -100,7 +100,7 @@ impl NewCVE {
     ) -> Self {
         let references = diesel_json::Json::new(references);
         Self {
-            created_at: SystemTime::now(),
+            created_at: Utc::now(),
             source,
             vendor,
             product,
@@ -114,3 +114,932 @@ impl NewCVE {
         }
     }
 }
+
+/// Device, firmware or image whose components are tracked.
+#[derive(Queryable, Debug, Clone, Serialize)]
+pub struct Asset {
+    pub id: i32,
+    #[serde(skip_serializing)]
+    pub created_at: DateTime<Utc>,
+    #[serde(skip_serializing)]
+    pub updated_at: Option<DateTime<Utc>>,
+    pub name: String,
+    pub kind: String,
+    pub format: String,
+    pub components: diesel_json::Json<Vec<Query>>,
+    pub labels: diesel_json::Json<Labels>,
+    /// Set while the asset is deleted and can still be restored, until purged.
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "optional_timestamp"
+    )]
+    pub deleted_at: Option<DateTime<Utc>>,
+}
+
+/// Kinds of the assets.
+pub const ASSET_KINDS: &[&str] = &["device", "firmware", "image"];
+
+/// Grouping dimensions of an asset, like its product line, customer or site.
+pub type Labels = BTreeMap<String, String>;
+
+#[derive(Debug, Insertable)]
+#[table_name = "assets"]
+pub struct NewAsset {
+    pub created_at: DateTime<Utc>,
+    pub name: String,
+    pub kind: String,
+    pub format: String,
+    pub components: diesel_json::Json<Vec<Query>>,
+    pub labels: diesel_json::Json<Labels>,
+}
+
+impl NewAsset {
+    pub fn with(
+        name: String,
+        kind: String,
+        format: String,
+        components: Vec<Query>,
+        labels: Labels,
+    ) -> Self {
+        Self {
+            created_at: Utc::now(),
+            name,
+            kind,
+            format,
+            components: diesel_json::Json::new(components),
+            labels: diesel_json::Json::new(labels),
+        }
+    }
+}
+
+/// Shipped release of a firmware asset, with its components.
+#[derive(Queryable, Debug, Clone, Serialize)]
+pub struct Release {
+    #[serde(skip_serializing)]
+    pub id: i32,
+    #[serde(serialize_with = "timestamp")]
+    pub created_at: DateTime<Utc>,
+    #[serde(skip_serializing)]
+    pub asset_id: i32,
+    pub version: String,
+    pub components: diesel_json::Json<Vec<Query>>,
+}
+
+#[derive(Debug, Insertable)]
+#[table_name = "releases"]
+pub struct NewRelease {
+    pub created_at: DateTime<Utc>,
+    pub asset_id: i32,
+    pub version: String,
+    pub components: diesel_json::Json<Vec<Query>>,
+}
+
+impl NewRelease {
+    pub fn with(asset_id: i32, version: String, components: Vec<Query>) -> Self {
+        Self {
+            created_at: Utc::now(),
+            asset_id,
+            version,
+            components: diesel_json::Json::new(components),
+        }
+    }
+}
+
+/// Release cycles of a product from endoflife.date.
+#[derive(Queryable, Debug, Clone)]
+pub struct EolProduct {
+    pub id: i32,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: Option<DateTime<Utc>>,
+    pub product: String,
+    pub cycles: diesel_json::Json<Vec<eol::Cycle>>,
+}
+
+#[derive(Debug, Insertable)]
+#[table_name = "eol_products"]
+pub struct NewEolProduct {
+    pub created_at: DateTime<Utc>,
+    pub product: String,
+    pub cycles: diesel_json::Json<Vec<eol::Cycle>>,
+}
+
+impl NewEolProduct {
+    pub fn with(product: String, cycles: Vec<eol::Cycle>) -> Self {
+        Self {
+            created_at: Utc::now(),
+            product,
+            cycles: diesel_json::Json::new(cycles),
+        }
+    }
+}
+
+/// CVE imported while still awaiting analysis, so without the configurations telling the
+/// affected products, until a later import reconciles it.
+#[derive(Queryable, Debug, Clone, Serialize)]
+pub struct PendingCve {
+    #[serde(skip)]
+    pub id: i32,
+    #[serde(serialize_with = "timestamp")]
+    pub created_at: DateTime<Utc>,
+    #[serde(skip)]
+    pub reconciled_at: Option<DateTime<Utc>>,
+    pub cve: String,
+    pub summary: String,
+}
+
+#[derive(Debug, Insertable)]
+#[table_name = "pending_cves"]
+pub struct NewPendingCve {
+    pub created_at: DateTime<Utc>,
+    pub cve: String,
+    pub summary: String,
+}
+
+impl NewPendingCve {
+    pub fn with(cve: String, summary: String) -> Self {
+        Self {
+            created_at: Utc::now(),
+            cve,
+            summary,
+        }
+    }
+}
+
+/// Change of the records of a CVE, whose id is the revision of the dataset it made.
+#[derive(Queryable, Debug, Clone)]
+pub struct CveChange {
+    pub id: i32,
+    pub created_at: DateTime<Utc>,
+    pub cve: String,
+    pub records_before: i32,
+    pub records_after: i32,
+}
+
+/// Revision of the dataset that last changed the records of a CVE.
+#[derive(Queryable, Debug, Clone)]
+pub struct CveRevision {
+    pub cve: String,
+    pub revision: i32,
+    pub records: i32,
+}
+
+/// Revision of the dataset that last changed the records of a product.
+#[derive(Queryable, Debug, Clone)]
+pub struct ProductRevision {
+    pub vendor: String,
+    pub product: String,
+    pub revision: i32,
+    pub records: i32,
+}
+
+/// Idempotency key of a write request, with its response once completed.
+#[derive(Queryable, Debug, Clone)]
+pub struct IdempotencyKey {
+    pub id: i32,
+    pub created_at: DateTime<Utc>,
+    pub key: String,
+    /// Hash of the request, so that the key isn't reused for another one.
+    pub fingerprint: String,
+    pub status: Option<i32>,
+    pub headers: Option<diesel_json::Json<BTreeMap<String, String>>>,
+    pub body: Option<Vec<u8>>,
+}
+
+#[derive(Debug, Insertable)]
+#[table_name = "idempotency_keys"]
+pub struct NewIdempotencyKey {
+    pub created_at: DateTime<Utc>,
+    pub key: String,
+    pub fingerprint: String,
+}
+
+impl NewIdempotencyKey {
+    pub fn with(key: String, fingerprint: String) -> Self {
+        Self {
+            created_at: Utc::now(),
+            key,
+            fingerprint,
+        }
+    }
+}
+
+/// Time up to which the records of a source were last synced.
+#[derive(Queryable, Debug)]
+pub struct SyncState {
+    pub id: i32,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: Option<DateTime<Utc>>,
+    pub source: String,
+    pub synced_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Insertable)]
+#[table_name = "sync_states"]
+pub struct NewSyncState {
+    pub created_at: DateTime<Utc>,
+    pub source: String,
+    pub synced_at: DateTime<Utc>,
+}
+
+impl NewSyncState {
+    pub fn with(source: String, synced_at: DateTime<Utc>) -> Self {
+        Self {
+            created_at: Utc::now(),
+            source,
+            synced_at,
+        }
+    }
+}
+
+/// Entry of the official CPE dictionary, for a version of a product.
+#[derive(Queryable, Debug, Clone, Serialize)]
+pub struct CpeEntry {
+    #[serde(skip)]
+    pub id: i32,
+    #[serde(skip)]
+    pub cpe_name_id: String,
+    pub cpe: String,
+    #[serde(skip)]
+    pub part: String,
+    #[serde(skip)]
+    pub vendor: String,
+    #[serde(skip)]
+    pub product: String,
+    pub version: String,
+    pub title: String,
+    pub deprecated: bool,
+    /// CPEs replacing the deprecated one.
+    #[serde(skip_serializing_if = "is_empty")]
+    pub deprecated_by: diesel_json::Json<Vec<String>>,
+    pub references: diesel_json::Json<Vec<dictionary::Reference>>,
+    #[serde(serialize_with = "timestamp")]
+    pub last_modified: DateTime<Utc>,
+}
+
+#[derive(Debug, Insertable)]
+#[table_name = "cpe_dictionary"]
+pub struct NewCpeEntry {
+    pub cpe_name_id: String,
+    pub cpe: String,
+    pub part: String,
+    pub vendor: String,
+    pub product: String,
+    pub version: String,
+    pub title: String,
+    pub deprecated: bool,
+    pub deprecated_by: diesel_json::Json<Vec<String>>,
+    pub refs: diesel_json::Json<Vec<dictionary::Reference>>,
+    pub last_modified: DateTime<Utc>,
+}
+
+fn is_empty(cpes: &diesel_json::Json<Vec<String>>) -> bool {
+    cpes.0.is_empty()
+}
+
+/// Number of searches of a query in a day, and how long they took.
+#[derive(Queryable, Debug, Clone, PartialEq)]
+pub struct QueryLogEntry {
+    pub id: i32,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: Option<DateTime<Utc>>,
+    /// Like `2022-07-19`, in UTC.
+    pub day: String,
+    pub vendor: String,
+    pub product: String,
+    pub version: String,
+    pub count: i64,
+    /// Total latency of the searches, in milliseconds.
+    pub latency_ms: i64,
+    pub max_latency_ms: i64,
+    /// Query searched, as sent by the clients, its vendor, product and version being copied
+    /// to their own columns for the exports.
+    pub query: diesel_json::Json<Query>,
+}
+
+#[derive(Debug, Insertable)]
+#[table_name = "query_log"]
+pub struct NewQueryLogEntry {
+    pub created_at: DateTime<Utc>,
+    pub day: String,
+    pub vendor: String,
+    pub product: String,
+    pub version: String,
+    pub count: i64,
+    pub latency_ms: i64,
+    pub max_latency_ms: i64,
+    pub query: diesel_json::Json<Query>,
+}
+
+/// Name to show for a vendor, or for one of its products, set by an operator.
+#[derive(Queryable, Debug, Clone, Serialize)]
+pub struct DisplayName {
+    #[serde(skip)]
+    pub id: i32,
+    #[serde(skip)]
+    pub created_at: DateTime<Utc>,
+    #[serde(serialize_with = "optional_timestamp")]
+    pub updated_at: Option<DateTime<Utc>>,
+    pub vendor: String,
+    /// Empty for the name of the vendor.
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub product: String,
+    pub name: String,
+    pub localized: diesel_json::Json<BTreeMap<String, String>>,
+}
+
+impl DisplayName {
+    pub fn name(&self) -> Name {
+        Name {
+            name: self.name.clone(),
+            localized: self.localized.0.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Insertable)]
+#[table_name = "display_names"]
+pub struct NewDisplayName {
+    pub created_at: DateTime<Utc>,
+    pub vendor: String,
+    pub product: String,
+    pub name: String,
+    pub localized: diesel_json::Json<BTreeMap<String, String>>,
+}
+
+impl NewDisplayName {
+    /// Name of the product of the vendor, or of the vendor itself without a product.
+    pub fn with(vendor: String, product: Option<String>, name: Name) -> Self {
+        Self {
+            created_at: Utc::now(),
+            vendor,
+            product: product.unwrap_or_default(),
+            name: name.name,
+            localized: diesel_json::Json(name.localized),
+        }
+    }
+}
+
+/// Allowlist of the products the last run of an import command was filtered by, if any,
+/// along with the first year of the CVEs it imported.
+#[derive(Queryable, Debug, Clone, Serialize)]
+pub struct ImportFilter {
+    #[serde(skip_serializing)]
+    pub id: i32,
+    #[serde(skip_serializing)]
+    pub created_at: DateTime<Utc>,
+    #[serde(rename = "imported_at", serialize_with = "optional_timestamp")]
+    pub updated_at: Option<DateTime<Utc>>,
+    pub command: String,
+    pub allowlist: Option<String>,
+    pub skipped: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub since_year: Option<i32>,
+}
+
+#[derive(Debug, Insertable)]
+#[table_name = "import_filters"]
+pub struct NewImportFilter {
+    pub created_at: DateTime<Utc>,
+    pub updated_at: Option<DateTime<Utc>>,
+    pub command: String,
+    pub allowlist: Option<String>,
+    pub skipped: i64,
+    pub since_year: Option<i32>,
+}
+
+impl NewImportFilter {
+    pub fn with(
+        command: String,
+        allowlist: Option<String>,
+        skipped: i64,
+        since_year: Option<i32>,
+    ) -> Self {
+        let now = Utc::now();
+        Self {
+            created_at: now,
+            updated_at: Some(now),
+            command,
+            allowlist,
+            skipped,
+            since_year,
+        }
+    }
+}
+
+/// Outcome of an import run, `running` until it ends.
+pub const IMPORT_RUNNING: &str = "running";
+pub const IMPORT_SUCCEEDED: &str = "succeeded";
+pub const IMPORT_FAILED: &str = "failed";
+
+/// Run of an import command, with the files it downloaded and the records it imported.
+#[derive(Queryable, Debug, Clone, Serialize)]
+pub struct ImportRun {
+    pub id: i32,
+    #[serde(serialize_with = "timestamp")]
+    pub started_at: DateTime<Utc>,
+    #[serde(serialize_with = "optional_timestamp")]
+    pub finished_at: Option<DateTime<Utc>>,
+    pub command: String,
+    pub outcome: String,
+    pub records: i64,
+    pub skipped: i64,
+    pub downloads: diesel_json::Json<Vec<Download>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Insertable)]
+#[table_name = "import_runs"]
+pub struct NewImportRun {
+    pub started_at: DateTime<Utc>,
+    pub command: String,
+    pub outcome: String,
+}
+
+impl NewImportRun {
+    pub fn with(command: String) -> Self {
+        Self {
+            started_at: Utc::now(),
+            command,
+            outcome: IMPORT_RUNNING.into(),
+        }
+    }
+}
+
+/// Id of a security advisory, like a GitHub one, cross-referenced to the CVE it's about.
+#[derive(Queryable, Debug, Clone, Serialize)]
+pub struct AdvisoryAlias {
+    #[serde(skip_serializing)]
+    pub id: i32,
+    #[serde(skip_serializing)]
+    pub created_at: DateTime<Utc>,
+    pub advisory: String,
+    pub cve: String,
+}
+
+#[derive(Debug, Insertable)]
+#[table_name = "advisory_aliases"]
+pub struct NewAdvisoryAlias {
+    pub created_at: DateTime<Utc>,
+    pub advisory: String,
+    pub cve: String,
+}
+
+impl NewAdvisoryAlias {
+    pub fn with(advisory: String, cve: String) -> Self {
+        Self {
+            created_at: Utc::now(),
+            advisory,
+            cve,
+        }
+    }
+}
+
+/// CVE of the CISA catalog of known exploited vulnerabilities.
+#[derive(Queryable, Debug, Clone)]
+pub struct KnownExploited {
+    pub id: i32,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: Option<DateTime<Utc>>,
+    pub cve: String,
+    pub vendor: String,
+    pub product: String,
+    pub name: String,
+    pub date_added: String,
+    pub due_date: String,
+    pub required_action: String,
+    pub ransomware: bool,
+}
+
+#[derive(Debug, Insertable)]
+#[table_name = "known_exploited"]
+pub struct NewKnownExploited {
+    pub created_at: DateTime<Utc>,
+    pub cve: String,
+    pub vendor: String,
+    pub product: String,
+    pub name: String,
+    pub date_added: String,
+    pub due_date: String,
+    pub required_action: String,
+    pub ransomware: bool,
+}
+
+impl NewKnownExploited {
+    pub fn with(vulnerability: kev::Vulnerability) -> Self {
+        Self {
+            created_at: Utc::now(),
+            ransomware: vulnerability.is_used_by_ransomware(),
+            cve: vulnerability.cve_id,
+            vendor: vulnerability.vendor_project,
+            product: vulnerability.product,
+            name: vulnerability.vulnerability_name,
+            date_added: vulnerability.date_added,
+            due_date: vulnerability.due_date,
+            required_action: vulnerability.required_action,
+        }
+    }
+}
+
+/// Probability of a CVE being exploited according to the FIRST EPSS model.
+#[derive(Queryable, Debug, Clone)]
+pub struct EpssScore {
+    pub id: i32,
+    pub created_at: DateTime<Utc>,
+    pub cve: String,
+    pub score: f64,
+    pub percentile: f64,
+    pub score_date: String,
+}
+
+#[derive(Debug, Insertable)]
+#[table_name = "epss_scores"]
+pub struct NewEpssScore {
+    pub created_at: DateTime<Utc>,
+    pub cve: String,
+    pub score: f64,
+    pub percentile: f64,
+    pub score_date: String,
+}
+
+impl NewEpssScore {
+    pub fn with(cve: String, score: f64, percentile: f64, score_date: String) -> Self {
+        Self {
+            created_at: Utc::now(),
+            cve,
+            score,
+            percentile,
+            score_date,
+        }
+    }
+}
+
+/// Public exploit of a CVE archived by Exploit-DB, one for every CVE the exploit targets.
+#[derive(Queryable, Debug, Clone)]
+pub struct Exploit {
+    pub id: i32,
+    pub created_at: DateTime<Utc>,
+    pub edb_id: i32,
+    pub cve: String,
+    pub description: String,
+    pub published: String,
+}
+
+impl Exploit {
+    pub fn url(&self) -> String {
+        exploitdb::url(self.edb_id)
+    }
+}
+
+#[derive(Debug, Insertable)]
+#[table_name = "exploits"]
+pub struct NewExploit {
+    pub created_at: DateTime<Utc>,
+    pub edb_id: i32,
+    pub cve: String,
+    pub description: String,
+    pub published: String,
+}
+
+impl NewExploit {
+    /// Returns the entries of the exploit, one for every CVE it targets.
+    pub fn with(exploit: &exploitdb::Exploit) -> Vec<Self> {
+        exploit
+            .cves()
+            .into_iter()
+            .map(|cve| Self {
+                created_at: Utc::now(),
+                edb_id: exploit.id,
+                cve,
+                description: exploit.description.clone(),
+                published: exploit.date_published.clone(),
+            })
+            .collect()
+    }
+}
+
+/// Software a product runs on, like `wordpress` for its plugins, from the CPEs of its records.
+#[derive(Debug, Insertable)]
+#[table_name = "product_targets"]
+pub struct NewProductTarget {
+    pub created_at: DateTime<Utc>,
+    pub vendor: String,
+    pub product: String,
+    pub target_sw: String,
+}
+
+impl NewProductTarget {
+    pub fn with(target: cpe::Target) -> Self {
+        Self {
+            created_at: Utc::now(),
+            vendor: target.vendor,
+            product: target.product,
+            target_sw: target.target_sw,
+        }
+    }
+}
+
+/// Metasploit module exploiting a CVE, one for every CVE the module references.
+#[derive(Queryable, Debug, Clone)]
+pub struct MetasploitModule {
+    pub id: i32,
+    pub created_at: DateTime<Utc>,
+    pub fullname: String,
+    pub cve: String,
+    pub name: String,
+    pub rank: i32,
+    pub disclosure_date: Option<String>,
+}
+
+#[derive(Debug, Insertable)]
+#[table_name = "metasploit_modules"]
+pub struct NewMetasploitModule {
+    pub created_at: DateTime<Utc>,
+    pub fullname: String,
+    pub cve: String,
+    pub name: String,
+    pub rank: i32,
+    pub disclosure_date: Option<String>,
+}
+
+impl NewMetasploitModule {
+    /// Returns the entries of the module, one for every CVE it references.
+    pub fn with(module: &metasploit::Module) -> Vec<Self> {
+        module
+            .cves()
+            .into_iter()
+            .map(|cve| Self {
+                created_at: Utc::now(),
+                fullname: module.fullname.clone(),
+                cve,
+                name: module.name.clone(),
+                rank: module.rank,
+                disclosure_date: module.disclosure_date.clone(),
+            })
+            .collect()
+    }
+}
+
+/// Weakness or category of the CWE catalog.
+#[derive(Queryable, Debug, Clone)]
+pub struct Cwe {
+    pub id: i32,
+    pub created_at: DateTime<Utc>,
+    pub cwe: String,
+    pub name: String,
+    pub kind: String,
+    pub abstraction: Option<String>,
+    pub status: String,
+    pub description: String,
+}
+
+#[derive(Debug, Insertable)]
+#[table_name = "cwes"]
+pub struct NewCwe {
+    pub created_at: DateTime<Utc>,
+    pub cwe: String,
+    pub name: String,
+    pub kind: String,
+    pub abstraction: Option<String>,
+    pub status: String,
+    pub description: String,
+}
+
+impl NewCwe {
+    pub fn with(entry: &cwe::Entry) -> Self {
+        Self {
+            created_at: Utc::now(),
+            cwe: entry.id.clone(),
+            name: entry.name.clone(),
+            kind: entry.kind.clone(),
+            abstraction: entry.abstraction.clone(),
+            status: entry.status.clone(),
+            description: entry.description.clone(),
+        }
+    }
+}
+
+/// Weakness of a CVE according to the NVD, by its CWE id.
+#[derive(Queryable, Debug, Clone)]
+pub struct CveWeakness {
+    pub id: i32,
+    pub created_at: DateTime<Utc>,
+    pub cve: String,
+    pub cwe: String,
+}
+
+#[derive(Debug, Insertable)]
+#[table_name = "cve_weaknesses"]
+pub struct NewCveWeakness {
+    pub created_at: DateTime<Utc>,
+    pub cve: String,
+    pub cwe: String,
+}
+
+impl NewCveWeakness {
+    pub fn with(cve: String, cwe: String) -> Self {
+        Self {
+            created_at: Utc::now(),
+            cve,
+            cwe,
+        }
+    }
+}
+
+/// Attack pattern of the CAPEC catalog exploiting a weakness, one for every weakness the
+/// pattern relates to.
+#[derive(Queryable, Debug, Clone)]
+pub struct AttackPattern {
+    pub id: i32,
+    pub created_at: DateTime<Utc>,
+    pub capec: String,
+    pub cwe: String,
+    pub name: String,
+    pub abstraction: Option<String>,
+    pub status: String,
+    pub severity: Option<String>,
+    pub likelihood: Option<String>,
+    pub description: String,
+}
+
+#[derive(Debug, Insertable)]
+#[table_name = "attack_patterns"]
+pub struct NewAttackPattern {
+    pub created_at: DateTime<Utc>,
+    pub capec: String,
+    pub cwe: String,
+    pub name: String,
+    pub abstraction: Option<String>,
+    pub status: String,
+    pub severity: Option<String>,
+    pub likelihood: Option<String>,
+    pub description: String,
+}
+
+impl NewAttackPattern {
+    /// Returns the entries of the pattern, one for every weakness it relates to.
+    pub fn with(pattern: &capec::AttackPattern) -> Vec<Self> {
+        pattern
+            .weaknesses
+            .iter()
+            .map(|cwe| Self {
+                created_at: Utc::now(),
+                capec: pattern.id.clone(),
+                cwe: cwe.clone(),
+                name: pattern.name.clone(),
+                abstraction: pattern.abstraction.clone(),
+                status: pattern.status.clone(),
+                severity: pattern.severity.clone(),
+                likelihood: pattern.likelihood.clone(),
+                description: pattern.description.clone(),
+            })
+            .collect()
+    }
+}
+
+/// CVE with a similar summary and affected products, found by the similarity analysis.
+#[derive(Queryable, Debug, Clone)]
+pub struct RelatedCve {
+    pub id: i32,
+    pub created_at: DateTime<Utc>,
+    pub cve: String,
+    pub related: String,
+    pub score: f64,
+}
+
+#[derive(Debug, Insertable)]
+#[table_name = "related_cves"]
+pub struct NewRelatedCve {
+    pub created_at: DateTime<Utc>,
+    pub cve: String,
+    pub related: String,
+    pub score: f64,
+}
+
+impl NewRelatedCve {
+    pub fn with(cve: String, related: String, score: f64) -> Self {
+        Self {
+            created_at: Utc::now(),
+            cve,
+            related,
+            score,
+        }
+    }
+}
+
+fn timestamp<S: Serializer>(time: &DateTime<Utc>, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&time.to_rfc3339_opts(SecondsFormat::Secs, true))
+}
+
+fn optional_timestamp<S: Serializer>(
+    time: &Option<DateTime<Utc>>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    match time {
+        Some(time) => timestamp(time, serializer),
+        None => serializer.serialize_none(),
+    }
+}
+
+/// CVE that became applicable to an asset after an import.
+#[derive(Queryable, Debug, Clone, Serialize)]
+pub struct Finding {
+    pub id: i32,
+    #[serde(serialize_with = "timestamp")]
+    pub created_at: DateTime<Utc>,
+    pub asset_id: i32,
+    pub source: String,
+    pub vendor: String,
+    pub product: String,
+    pub cve: String,
+    pub score: f64,
+    pub severity: String,
+}
+
+#[derive(Debug, Insertable)]
+#[table_name = "findings"]
+pub struct NewFinding {
+    pub created_at: DateTime<Utc>,
+    pub asset_id: i32,
+    pub source: String,
+    pub vendor: String,
+    pub product: String,
+    pub cve: String,
+    pub score: f64,
+    pub severity: String,
+}
+
+impl NewFinding {
+    pub fn with(asset_id: i32, cve: &CVE) -> Self {
+        Self {
+            created_at: Utc::now(),
+            asset_id,
+            source: cve.source.clone(),
+            vendor: cve.vendor.clone(),
+            product: cve.product.clone(),
+            cve: cve.cve.clone(),
+            score: cve.score,
+            severity: cve.severity.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::time::UNIX_EPOCH;
+
+    use chrono::{DateTime, Utc};
+
+    use super::{Asset, Labels, Reference, CVE};
+
+    #[test]
+    fn cve_serializes_as_the_api_model() {
+        let cve = CVE {
+            id: 1,
+            created_at: Utc::now(),
+            updated_at: None,
+            source: "nist".into(),
+            vendor: "xmlsoft".into(),
+            product: "libxml2".into(),
+            cve: "CVE-2021-3517".into(),
+            summary: "There is a flaw in libxml2.".into(),
+            score: 8.6,
+            severity: "HIGH".into(),
+            vector: Some("NETWORK".into()),
+            references: diesel_json::Json::new(vec![Reference {
+                url: "https://bugzilla.redhat.com".into(),
+                tags: vec![],
+            }]),
+            object_id: None,
+        };
+
+        let json = serde_json::to_string(&cve).unwrap();
+        let api: kepler_types::api::Cve = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(cve.cve, api.cve);
+        assert_eq!(cve.score, api.score);
+        assert_eq!(cve.references.0, api.references);
+    }
+
+    #[test]
+    fn asset_serializes_deleted_at_once_deleted() {
+        let mut asset = Asset {
+            id: 1,
+            created_at: Utc::now(),
+            updated_at: None,
+            name: "gateway-fw-1.2.0".into(),
+            kind: "firmware".into(),
+            format: "products".into(),
+            components: diesel_json::Json::new(vec![]),
+            labels: diesel_json::Json::new(Labels::new()),
+            deleted_at: None,
+        };
+        let json = serde_json::to_value(&asset).unwrap();
+        assert!(json.get("deleted_at").is_none());
+
+        asset.deleted_at = Some(DateTime::<Utc>::from(UNIX_EPOCH));
+        let json = serde_json::to_value(&asset).unwrap();
+        assert_eq!("1970-01-01T00:00:00Z", json["deleted_at"]);
+    }
+}
@@ -1,8 +1,90 @@
+table! {
+    advisory_aliases (id) {
+        id -> Int4,
+        created_at -> Timestamptz,
+        advisory -> Text,
+        cve -> Text,
+    }
+}
+
+table! {
+    assets (id) {
+        id -> Int4,
+        created_at -> Timestamptz,
+        updated_at -> Nullable<Timestamptz>,
+        name -> Text,
+        kind -> Text,
+        format -> Text,
+        components -> Jsonb,
+        labels -> Jsonb,
+        deleted_at -> Nullable<Timestamptz>,
+    }
+}
+
+table! {
+    attack_patterns (id) {
+        id -> Int4,
+        created_at -> Timestamptz,
+        capec -> Text,
+        cwe -> Text,
+        name -> Text,
+        abstraction -> Nullable<Text>,
+        status -> Text,
+        severity -> Nullable<Text>,
+        likelihood -> Nullable<Text>,
+        description -> Text,
+    }
+}
+
+table! {
+    cpe_dictionary (id) {
+        id -> Int4,
+        cpe_name_id -> Text,
+        cpe -> Text,
+        part -> Text,
+        vendor -> Text,
+        product -> Text,
+        version -> Text,
+        title -> Text,
+        deprecated -> Bool,
+        deprecated_by -> Jsonb,
+        refs -> Jsonb,
+        last_modified -> Timestamptz,
+    }
+}
+
+table! {
+    cve_changes (id) {
+        id -> Int4,
+        created_at -> Timestamptz,
+        cve -> Text,
+        records_before -> Int4,
+        records_after -> Int4,
+    }
+}
+
+table! {
+    cve_revisions (cve) {
+        cve -> Text,
+        revision -> Int4,
+        records -> Int4,
+    }
+}
+
+table! {
+    cve_weaknesses (id) {
+        id -> Int4,
+        created_at -> Timestamptz,
+        cve -> Text,
+        cwe -> Text,
+    }
+}
+
 table! {
     cves (id) {
         id -> Int4,
-        created_at -> Timestamp,
-        updated_at -> Nullable<Timestamp>,
+        created_at -> Timestamptz,
+        updated_at -> Nullable<Timestamptz>,
         source -> Text,
         vendor -> Text,
         product -> Text,
@@ -16,16 +98,258 @@ table! {
     }
 }
 
+table! {
+    cwes (id) {
+        id -> Int4,
+        created_at -> Timestamptz,
+        cwe -> Text,
+        name -> Text,
+        kind -> Text,
+        abstraction -> Nullable<Text>,
+        status -> Text,
+        description -> Text,
+    }
+}
+
+table! {
+    display_names (id) {
+        id -> Int4,
+        created_at -> Timestamptz,
+        updated_at -> Nullable<Timestamptz>,
+        vendor -> Text,
+        product -> Text,
+        name -> Text,
+        localized -> Jsonb,
+    }
+}
+
+table! {
+    eol_products (id) {
+        id -> Int4,
+        created_at -> Timestamptz,
+        updated_at -> Nullable<Timestamptz>,
+        product -> Text,
+        cycles -> Jsonb,
+    }
+}
+
+table! {
+    epss_scores (id) {
+        id -> Int4,
+        created_at -> Timestamptz,
+        cve -> Text,
+        score -> Float8,
+        percentile -> Float8,
+        score_date -> Text,
+    }
+}
+
+table! {
+    exploits (id) {
+        id -> Int4,
+        created_at -> Timestamptz,
+        edb_id -> Int4,
+        cve -> Text,
+        description -> Text,
+        published -> Text,
+    }
+}
+
+table! {
+    findings (id) {
+        id -> Int4,
+        created_at -> Timestamptz,
+        asset_id -> Int4,
+        source -> Text,
+        vendor -> Text,
+        product -> Text,
+        cve -> Text,
+        score -> Float8,
+        severity -> Text,
+    }
+}
+
+table! {
+    idempotency_keys (id) {
+        id -> Int4,
+        created_at -> Timestamptz,
+        key -> Text,
+        fingerprint -> Text,
+        status -> Nullable<Int4>,
+        headers -> Nullable<Jsonb>,
+        body -> Nullable<Bytea>,
+    }
+}
+
+table! {
+    import_filters (id) {
+        id -> Int4,
+        created_at -> Timestamptz,
+        updated_at -> Nullable<Timestamptz>,
+        command -> Text,
+        allowlist -> Nullable<Text>,
+        skipped -> Int8,
+        since_year -> Nullable<Int4>,
+    }
+}
+
+table! {
+    import_runs (id) {
+        id -> Int4,
+        started_at -> Timestamptz,
+        finished_at -> Nullable<Timestamptz>,
+        command -> Text,
+        outcome -> Text,
+        records -> Int8,
+        skipped -> Int8,
+        downloads -> Jsonb,
+        error -> Nullable<Text>,
+    }
+}
+
+table! {
+    known_exploited (id) {
+        id -> Int4,
+        created_at -> Timestamptz,
+        updated_at -> Nullable<Timestamptz>,
+        cve -> Text,
+        vendor -> Text,
+        product -> Text,
+        name -> Text,
+        date_added -> Text,
+        due_date -> Text,
+        required_action -> Text,
+        ransomware -> Bool,
+    }
+}
+
+table! {
+    metasploit_modules (id) {
+        id -> Int4,
+        created_at -> Timestamptz,
+        fullname -> Text,
+        cve -> Text,
+        name -> Text,
+        rank -> Int4,
+        disclosure_date -> Nullable<Text>,
+    }
+}
+
 table! {
     objects (id) {
         id -> Int4,
-        created_at -> Timestamp,
-        updated_at -> Nullable<Timestamp>,
+        created_at -> Timestamptz,
+        updated_at -> Nullable<Timestamptz>,
         cve -> Text,
         data -> Text,
     }
 }
 
+table! {
+    pending_cves (id) {
+        id -> Int4,
+        created_at -> Timestamptz,
+        reconciled_at -> Nullable<Timestamptz>,
+        cve -> Text,
+        summary -> Text,
+    }
+}
+
+table! {
+    product_targets (id) {
+        id -> Int4,
+        created_at -> Timestamptz,
+        vendor -> Text,
+        product -> Text,
+        target_sw -> Text,
+    }
+}
+
+table! {
+    product_revisions (vendor, product) {
+        vendor -> Text,
+        product -> Text,
+        revision -> Int4,
+        records -> Int4,
+    }
+}
+
+table! {
+    query_log (id) {
+        id -> Int4,
+        created_at -> Timestamptz,
+        updated_at -> Nullable<Timestamptz>,
+        day -> Text,
+        vendor -> Text,
+        product -> Text,
+        version -> Text,
+        count -> Int8,
+        latency_ms -> Int8,
+        max_latency_ms -> Int8,
+        query -> Jsonb,
+    }
+}
+
+table! {
+    related_cves (id) {
+        id -> Int4,
+        created_at -> Timestamptz,
+        cve -> Text,
+        related -> Text,
+        score -> Float8,
+    }
+}
+
+table! {
+    releases (id) {
+        id -> Int4,
+        created_at -> Timestamptz,
+        asset_id -> Int4,
+        version -> Text,
+        components -> Jsonb,
+    }
+}
+
+table! {
+    sync_states (id) {
+        id -> Int4,
+        created_at -> Timestamptz,
+        updated_at -> Nullable<Timestamptz>,
+        source -> Text,
+        synced_at -> Timestamptz,
+    }
+}
+
 joinable!(cves -> objects (object_id));
+joinable!(findings -> assets (asset_id));
+joinable!(releases -> assets (asset_id));
 
-allow_tables_to_appear_in_same_query!(cves, objects,);
+allow_tables_to_appear_in_same_query!(
+    advisory_aliases,
+    assets,
+    attack_patterns,
+    cpe_dictionary,
+    cve_changes,
+    cve_revisions,
+    cve_weaknesses,
+    cves,
+    cwes,
+    display_names,
+    eol_products,
+    epss_scores,
+    exploits,
+    findings,
+    idempotency_keys,
+    import_filters,
+    import_runs,
+    known_exploited,
+    metasploit_modules,
+    objects,
+    pending_cves,
+    product_revisions,
+    product_targets,
+    query_log,
+    related_cves,
+    releases,
+    sync_states,
+);
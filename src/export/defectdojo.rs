@@ -0,0 +1,148 @@
+use serde::Serialize;
+
+use crate::db::models;
+use crate::search::Query;
+
+#[derive(Debug, Serialize, PartialEq)]
+pub struct VulnerabilityId {
+    pub vulnerability_id: String,
+}
+
+/// A single finding in the DefectDojo generic findings import format.
+#[derive(Debug, Serialize)]
+pub struct Finding {
+    pub title: String,
+    pub description: String,
+    pub severity: String,
+    pub date: String,
+    pub references: String,
+    pub component_name: String,
+    pub component_version: Option<String>,
+    pub vulnerability_ids: Vec<VulnerabilityId>,
+    pub unique_id_from_tool: String,
+    pub vuln_id_from_tool: String,
+    pub static_finding: bool,
+    pub dynamic_finding: bool,
+}
+
+#[derive(Debug, Serialize, Default)]
+pub struct Findings {
+    pub findings: Vec<Finding>,
+}
+
+/// DefectDojo only accepts capitalized severities, anything we can't map becomes informational.
+fn severity(cve: &models::CVE) -> String {
+    match cve.severity.to_ascii_uppercase().as_str() {
+        "CRITICAL" => "Critical",
+        "HIGH" => "High",
+        "MEDIUM" => "Medium",
+        "LOW" => "Low",
+        _ => "Info",
+    }
+    .to_owned()
+}
+
+impl Finding {
+    pub fn with(query: &Query, cve: &models::CVE) -> Self {
+        let date = cve.created_at;
+        let references = cve
+            .references
+            .iter()
+            .map(|r| r.url.as_str())
+            .collect::<Vec<_>>()
+            .join("\n");
+        let component_version = query.version.clone();
+
+        Self {
+            title: format!("{} in {}", cve.cve, cve.product),
+            description: cve.summary.clone(),
+            severity: severity(cve),
+            date: date.format("%Y-%m-%d").to_string(),
+            references,
+            component_name: cve.product.clone(),
+            unique_id_from_tool: format!(
+                "{}:{}:{}:{}",
+                cve.cve,
+                cve.vendor,
+                cve.product,
+                component_version.as_deref().unwrap_or("*")
+            ),
+            component_version,
+            vulnerability_ids: vec![VulnerabilityId {
+                vulnerability_id: cve.cve.clone(),
+            }],
+            vuln_id_from_tool: cve.cve.clone(),
+            static_finding: true,
+            dynamic_finding: false,
+        }
+    }
+}
+
+pub fn findings(results: &[(Query, Vec<models::CVE>)]) -> Findings {
+    let mut findings = Findings::default();
+
+    for (query, cves) in results {
+        for cve in cves {
+            findings.findings.push(Finding::with(query, cve));
+        }
+    }
+
+    findings
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::UNIX_EPOCH;
+
+    use chrono::{DateTime, Utc};
+
+    use super::{findings, VulnerabilityId};
+    use crate::db::models;
+    use crate::search::{testing, Query};
+
+    fn cve(id: &str, severity: &str) -> models::CVE {
+        models::CVE {
+            created_at: DateTime::<Utc>::from(UNIX_EPOCH),
+            vendor: "xmlsoft".into(),
+            severity: severity.into(),
+            references: diesel_json::Json::new(vec![models::Reference {
+                url: "https://example.com".into(),
+                tags: vec![],
+            }]),
+            ..testing::cve(id, "libxml2")
+        }
+    }
+
+    #[test]
+    fn can_convert_results_to_findings() {
+        let query = Query {
+            vendor: None,
+            product: "libxml2".into(),
+            version: Some("2.9.10".into()),
+            ..Default::default()
+        };
+        let results = vec![(
+            query,
+            vec![cve("CVE-2021-3517", "HIGH"), cve("CVE-2021-3541", "")],
+        )];
+
+        let findings = findings(&results).findings;
+
+        assert_eq!(2, findings.len());
+        assert_eq!("High", findings[0].severity);
+        assert_eq!("Info", findings[1].severity);
+        assert_eq!("1970-01-01", findings[0].date);
+        assert_eq!("https://example.com", findings[0].references);
+        assert_eq!(Some("2.9.10".to_owned()), findings[0].component_version);
+        assert_eq!(
+            vec![VulnerabilityId {
+                vulnerability_id: "CVE-2021-3517".into()
+            }],
+            findings[0].vulnerability_ids
+        );
+        assert_eq!(
+            "CVE-2021-3517:xmlsoft:libxml2:2.9.10",
+            findings[0].unique_id_from_tool
+        );
+    }
+}
@@ -0,0 +1,397 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+use anyhow::{anyhow, bail, Context, Result};
+use chrono::{DateTime, Utc};
+use lazy_static::lazy_static;
+use log::info;
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::db::{self, models, Pool};
+use crate::search::{self, Query};
+use crate::sources::kev;
+
+pub const ORGANIZATION: &str = "Kepler";
+
+lazy_static! {
+    /// Root namespace for the deterministic UUIDs of the generated events and attributes, this
+    /// way regenerating the feed updates the existing events in MISP instead of duplicating them.
+    static ref NAMESPACE: Uuid = Uuid::new_v5(&Uuid::NAMESPACE_URL, b"https://github.com/Exein-io/kepler");
+}
+
+fn uuid(name: &str) -> String {
+    Uuid::new_v5(&NAMESPACE, name.as_bytes()).to_string()
+}
+
+fn epoch(time: DateTime<Utc>) -> String {
+    time.timestamp().max(0).to_string()
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct Organization {
+    pub name: String,
+    pub uuid: String,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct Tag {
+    pub name: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Attribute {
+    pub uuid: String,
+    #[serde(rename = "type")]
+    pub kind: &'static str,
+    pub category: &'static str,
+    pub value: String,
+    pub to_ids: bool,
+    pub timestamp: String,
+    pub comment: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Event {
+    pub uuid: String,
+    pub info: String,
+    pub date: String,
+    pub threat_level_id: String,
+    pub analysis: String,
+    pub timestamp: String,
+    pub published: bool,
+    #[serde(rename = "Orgc")]
+    pub orgc: Organization,
+    #[serde(rename = "Tag")]
+    pub tags: Vec<Tag>,
+    #[serde(rename = "Attribute")]
+    pub attributes: Vec<Attribute>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct EventFile {
+    #[serde(rename = "Event")]
+    pub event: Event,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ManifestEntry {
+    pub info: String,
+    pub date: String,
+    pub threat_level_id: String,
+    pub analysis: String,
+    pub timestamp: String,
+    #[serde(rename = "Orgc")]
+    pub orgc: Organization,
+    #[serde(rename = "Tag")]
+    pub tags: Vec<Tag>,
+}
+
+#[derive(Debug, Default)]
+pub struct Feed {
+    pub manifest: HashMap<String, ManifestEntry>,
+    pub events: Vec<EventFile>,
+}
+
+/// Only CVEs at or above this severity, or known to be exploited, are published to the feed.
+pub fn is_relevant(cve: &models::CVE, exploited: &kev::Exploited) -> bool {
+    matches!(
+        cve.severity.to_ascii_uppercase().as_str(),
+        "HIGH" | "CRITICAL"
+    ) || exploited.of(&cve.cve).known_exploited
+}
+
+fn threat_level(cve: &models::CVE) -> String {
+    match cve.severity.to_ascii_uppercase().as_str() {
+        "CRITICAL" | "HIGH" => "1",
+        "MEDIUM" => "2",
+        "LOW" => "3",
+        _ => "4",
+    }
+    .to_owned()
+}
+
+impl Feed {
+    pub fn with(
+        results: &[(Query, Vec<models::CVE>)],
+        exploited: &kev::Exploited,
+        since: DateTime<Utc>,
+    ) -> Self {
+        let orgc = Organization {
+            name: ORGANIZATION.into(),
+            uuid: uuid(ORGANIZATION),
+        };
+
+        // group matches by CVE, a single event lists all the watched products it affects
+        let mut grouped: HashMap<&str, Vec<(&Query, &models::CVE)>> = HashMap::new();
+        for (query, cves) in results {
+            for cve in cves {
+                if is_relevant(cve, exploited) && cve.created_at >= since {
+                    grouped
+                        .entry(cve.cve.as_str())
+                        .or_default()
+                        .push((query, cve));
+                }
+            }
+        }
+
+        let mut feed = Self::default();
+
+        for (id, matches) in grouped {
+            let cve = matches[0].1;
+            let date = cve.created_at;
+            let timestamp = epoch(cve.updated_at.unwrap_or(cve.created_at));
+            let mut tags = vec![
+                Tag {
+                    name: format!("kepler:severity=\"{}\"", cve.severity.to_lowercase()),
+                },
+                Tag {
+                    name: format!("kepler:source=\"{}\"", cve.source.to_lowercase()),
+                },
+            ];
+            let known_exploited = exploited.of(id).known_exploited;
+            if known_exploited {
+                tags.push(Tag {
+                    name: "kepler:known-exploited=\"true\"".into(),
+                });
+            }
+
+            let mut attributes = vec![Attribute {
+                uuid: uuid(&format!("{}:vulnerability", id)),
+                kind: "vulnerability",
+                category: "External analysis",
+                value: id.to_owned(),
+                to_ids: false,
+                timestamp: timestamp.clone(),
+                comment: cve.summary.clone(),
+            }];
+
+            for (query, cve) in &matches {
+                let product = format!(
+                    "{}:{}:{}",
+                    cve.vendor,
+                    cve.product,
+                    query.version.as_deref().unwrap_or("*")
+                );
+                attributes.push(Attribute {
+                    uuid: uuid(&format!("{}:{}", id, product)),
+                    kind: "text",
+                    category: "External analysis",
+                    value: product,
+                    to_ids: false,
+                    timestamp: timestamp.clone(),
+                    comment: "affected product".into(),
+                });
+            }
+
+            for reference in cve.references.iter() {
+                attributes.push(Attribute {
+                    uuid: uuid(&format!("{}:{}", id, reference.url)),
+                    kind: "link",
+                    category: "External analysis",
+                    value: reference.url.clone(),
+                    to_ids: false,
+                    timestamp: timestamp.clone(),
+                    comment: reference.tags.join(", "),
+                });
+            }
+
+            let event = Event {
+                uuid: uuid(id),
+                info: format!("{} ({})", id, cve.product),
+                date: date.format("%Y-%m-%d").to_string(),
+                // exploited vulnerabilities are a high threat whatever their severity
+                threat_level_id: if known_exploited {
+                    "1".into()
+                } else {
+                    threat_level(cve)
+                },
+                analysis: "2".into(),
+                timestamp,
+                published: true,
+                orgc: orgc.clone(),
+                tags,
+                attributes,
+            };
+
+            feed.manifest.insert(
+                event.uuid.clone(),
+                ManifestEntry {
+                    info: event.info.clone(),
+                    date: event.date.clone(),
+                    threat_level_id: event.threat_level_id.clone(),
+                    analysis: event.analysis.clone(),
+                    timestamp: event.timestamp.clone(),
+                    orgc: event.orgc.clone(),
+                    tags: event.tags.clone(),
+                },
+            );
+            feed.events.push(EventFile { event });
+        }
+
+        feed
+    }
+
+    pub fn write(&self, output_path: &Path) -> Result<()> {
+        for file in &self.events {
+            let mut file_path = output_path.to_path_buf();
+            file_path.push(format!("{}.json", file.event.uuid));
+            fs::write(&file_path, serde_json::to_string(file)?)
+                .with_context(|| format!("could not write {}", file_path.display()))?;
+        }
+
+        let mut file_path = output_path.to_path_buf();
+        file_path.push("manifest.json");
+        fs::write(&file_path, serde_json::to_string(&self.manifest)?)
+            .with_context(|| format!("could not write {}", file_path.display()))?;
+
+        Ok(())
+    }
+}
+
+/// Time the given number of days before now, the CVEs published since being the ones of the feed.
+fn since(now: DateTime<Utc>, days: u64) -> Result<DateTime<Utc>> {
+    match days
+        .checked_mul(24 * 60 * 60)
+        .and_then(|secs| chrono::Duration::from_std(Duration::from_secs(secs)).ok())
+        .and_then(|duration| now.checked_sub_signed(duration))
+    {
+        Some(since) => Ok(since),
+        None => bail!("invalid number of days {}, too large", days),
+    }
+}
+
+pub fn run(pool: &Pool, watchlist_path: &Path, output_path: &Path, days: u64) -> Result<u32> {
+    let watchlist: Vec<Query> = serde_yaml::from_str(
+        &fs::read_to_string(watchlist_path)
+            .with_context(|| format!("could not read {}", watchlist_path.display()))?,
+    )
+    .with_context(|| format!("could not parse {}", watchlist_path.display()))?;
+
+    info!(
+        "generating MISP feed for {} watched products ...",
+        watchlist.len()
+    );
+
+    let since = since(Utc::now(), days)?;
+
+    let database = db::Database(pool.get()?);
+    let results = search::query_all(&database, &watchlist).map_err(|err| anyhow!(err))?;
+    let cves: Vec<models::CVE> = results
+        .iter()
+        .flat_map(|(_, cves)| cves.iter().cloned())
+        .collect();
+    let exploited = kev::Exploited::load(&database, &cves).map_err(|err| anyhow!(err))?;
+
+    let feed = Feed::with(&results, &exploited, since);
+
+    fs::create_dir_all(output_path)
+        .with_context(|| format!("could not create {}", output_path.display()))?;
+    feed.write(output_path)?;
+
+    Ok(feed.events.len() as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, Utc};
+
+    use super::{since, Feed};
+    use crate::db::models;
+    use crate::search::{testing, Query};
+    use crate::sources::kev;
+
+    fn cve(id: &str, product: &str, severity: &str) -> models::CVE {
+        models::CVE {
+            vendor: "vendor".into(),
+            severity: severity.into(),
+            ..testing::cve(id, product)
+        }
+    }
+
+    fn query(product: &str) -> Query {
+        Query {
+            vendor: None,
+            product: product.into(),
+            version: Some("1.0.0".into()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn can_build_feed_of_relevant_cves() {
+        let results = vec![
+            (
+                query("a"),
+                vec![cve("CVE-1", "a", "HIGH"), cve("CVE-2", "a", "LOW")],
+            ),
+            (query("b"), vec![cve("CVE-1", "b", "HIGH")]),
+        ];
+
+        let feed = Feed::with(
+            &results,
+            &kev::Exploited::default(),
+            Utc::now() - Duration::seconds(60),
+        );
+
+        assert_eq!(1, feed.events.len());
+        assert_eq!(1, feed.manifest.len());
+
+        let event = &feed.events[0].event;
+        assert!(feed.manifest.contains_key(&event.uuid));
+        assert_eq!("1", event.threat_level_id);
+        // the vulnerability itself plus the two affected products
+        assert_eq!(3, event.attributes.len());
+    }
+
+    #[test]
+    fn can_skip_old_cves() {
+        let results = vec![(query("a"), vec![cve("CVE-1", "a", "CRITICAL")])];
+
+        let feed = Feed::with(
+            &results,
+            &kev::Exploited::default(),
+            Utc::now() + Duration::seconds(60),
+        );
+
+        assert!(feed.events.is_empty());
+    }
+
+    #[test]
+    fn can_include_known_exploited_cves() {
+        let results = vec![(query("a"), vec![cve("CVE-1", "a", "LOW")])];
+        let exploited = kev::Exploited::with(vec![models::KnownExploited {
+            id: 1,
+            created_at: Utc::now(),
+            updated_at: None,
+            cve: "CVE-1".into(),
+            vendor: "vendor".into(),
+            product: "a".into(),
+            name: "name".into(),
+            date_added: "2022-04-12".into(),
+            due_date: "2022-05-03".into(),
+            required_action: "Apply updates per vendor instructions.".into(),
+            ransomware: false,
+        }]);
+
+        let feed = Feed::with(&results, &exploited, Utc::now() - Duration::seconds(60));
+
+        assert_eq!(1, feed.events.len());
+        let event = &feed.events[0].event;
+        assert_eq!("1", event.threat_level_id);
+        assert!(event
+            .tags
+            .iter()
+            .any(|tag| tag.name == "kepler:known-exploited=\"true\""));
+    }
+
+    #[test]
+    fn can_reject_too_many_days() {
+        let now = Utc::now();
+        assert_eq!(now - Duration::days(2), since(now, 2).unwrap());
+        assert!(since(now, u64::MAX).is_err());
+        assert!(since(now, 1 << 50).is_err());
+    }
+}
@@ -0,0 +1,5 @@
+pub mod defectdojo;
+pub mod misp;
+#[cfg(feature = "parquet")]
+pub mod parquet;
+pub mod stix;
@@ -0,0 +1,245 @@
+//! Columnar export of the CVEs and of the products they affect, as Parquet files for
+//! analytical engines like DuckDB or Spark.
+
+use std::collections::HashSet;
+use std::fs::{self, File};
+use std::path::Path;
+use std::sync::Arc;
+
+use ::parquet::basic::Compression;
+use ::parquet::data_type::{ByteArray, ByteArrayType, DoubleType, Int64Type};
+use ::parquet::file::properties::WriterProperties;
+use ::parquet::file::writer::SerializedFileWriter;
+use ::parquet::schema::parser::parse_message_type;
+use anyhow::{anyhow, Context, Result};
+use log::info;
+
+use crate::db::{self, models, Pool};
+
+/// Rows per row group, so that the engines can read the files in parallel.
+const ROW_GROUP_SIZE: usize = 128 * 1024;
+
+const CVES_SCHEMA: &str = "
+message cve {
+    REQUIRED BYTE_ARRAY cve (UTF8);
+    REQUIRED BYTE_ARRAY source (UTF8);
+    REQUIRED BYTE_ARRAY summary (UTF8);
+    REQUIRED DOUBLE score;
+    REQUIRED BYTE_ARRAY severity (UTF8);
+    OPTIONAL BYTE_ARRAY vector (UTF8);
+    REQUIRED INT64 imported_at (TIMESTAMP_MILLIS);
+}
+";
+
+const PRODUCTS_SCHEMA: &str = "
+message product {
+    REQUIRED BYTE_ARRAY cve (UTF8);
+    REQUIRED BYTE_ARRAY source (UTF8);
+    REQUIRED BYTE_ARRAY vendor (UTF8);
+    REQUIRED BYTE_ARRAY product (UTF8);
+}
+";
+
+/// Values of a column, in the order of the fields of the schema.
+#[derive(Debug, PartialEq)]
+pub enum Column {
+    Text(Vec<String>),
+    OptionalText(Vec<Option<String>>),
+    Double(Vec<f64>),
+    Timestamp(Vec<i64>),
+}
+
+impl Column {
+    fn len(&self) -> usize {
+        match self {
+            Self::Text(values) => values.len(),
+            Self::OptionalText(values) => values.len(),
+            Self::Double(values) => values.len(),
+            Self::Timestamp(values) => values.len(),
+        }
+    }
+}
+
+fn text(values: &[String]) -> Vec<ByteArray> {
+    values.iter().map(|v| ByteArray::from(v.as_str())).collect()
+}
+
+/// Columns of the CVEs, once per source as their scores may differ.
+pub fn cve_columns(records: &[models::CVE]) -> Vec<Column> {
+    let mut seen = HashSet::new();
+    let records: Vec<&models::CVE> = records
+        .iter()
+        .filter(|r| seen.insert((r.source.as_str(), r.cve.as_str())))
+        .collect();
+
+    vec![
+        Column::Text(records.iter().map(|r| r.cve.clone()).collect()),
+        Column::Text(records.iter().map(|r| r.source.clone()).collect()),
+        Column::Text(records.iter().map(|r| r.summary.clone()).collect()),
+        Column::Double(records.iter().map(|r| r.score).collect()),
+        Column::Text(records.iter().map(|r| r.severity.clone()).collect()),
+        Column::OptionalText(records.iter().map(|r| r.vector.clone()).collect()),
+        Column::Timestamp(
+            records
+                .iter()
+                .map(|r| r.created_at.timestamp_millis())
+                .collect(),
+        ),
+    ]
+}
+
+/// Columns of the mappings between the CVEs and the products they affect.
+pub fn product_columns(records: &[models::CVE]) -> Vec<Column> {
+    vec![
+        Column::Text(records.iter().map(|r| r.cve.clone()).collect()),
+        Column::Text(records.iter().map(|r| r.source.clone()).collect()),
+        Column::Text(records.iter().map(|r| r.vendor.clone()).collect()),
+        Column::Text(records.iter().map(|r| r.product.clone()).collect()),
+    ]
+}
+
+/// Writes the columns as a Parquet file with the given schema, returning the number of rows.
+pub fn write(path: &Path, schema: &str, columns: &[Column]) -> Result<usize> {
+    let num_rows = columns.first().map(Column::len).unwrap_or_default();
+    let schema = Arc::new(parse_message_type(schema)?);
+    let properties = Arc::new(
+        WriterProperties::builder()
+            .set_compression(Compression::SNAPPY)
+            .build(),
+    );
+
+    let file =
+        File::create(path).with_context(|| format!("could not create {}", path.display()))?;
+    let mut writer = SerializedFileWriter::new(file, schema, properties)?;
+
+    let mut start = 0;
+    while start < num_rows {
+        let end = (start + ROW_GROUP_SIZE).min(num_rows);
+        let mut row_group = writer.next_row_group()?;
+
+        for column in columns {
+            let mut writer = row_group
+                .next_column()?
+                .ok_or_else(|| anyhow!("more columns than fields in the schema"))?;
+
+            match column {
+                Column::Text(values) => {
+                    writer.typed::<ByteArrayType>().write_batch(
+                        &text(&values[start..end]),
+                        None,
+                        None,
+                    )?;
+                }
+                Column::OptionalText(values) => {
+                    let values = &values[start..end];
+                    let levels: Vec<i16> = values.iter().map(|v| v.is_some() as i16).collect();
+                    let present: Vec<String> = values.iter().flatten().cloned().collect();
+                    writer.typed::<ByteArrayType>().write_batch(
+                        &text(&present),
+                        Some(&levels),
+                        None,
+                    )?;
+                }
+                Column::Double(values) => {
+                    writer
+                        .typed::<DoubleType>()
+                        .write_batch(&values[start..end], None, None)?;
+                }
+                Column::Timestamp(values) => {
+                    writer
+                        .typed::<Int64Type>()
+                        .write_batch(&values[start..end], None, None)?;
+                }
+            }
+
+            writer.close()?;
+        }
+
+        row_group.close()?;
+        start = end;
+    }
+
+    writer.close()?;
+
+    Ok(num_rows)
+}
+
+/// Exports all the records as `cves.parquet` and `products.parquet` in the output path,
+/// returning the number of CVEs and of product mappings written.
+pub fn run(pool: &Pool, output: &Path) -> Result<(usize, usize)> {
+    fs::create_dir_all(output).with_context(|| format!("could not create {}", output.display()))?;
+
+    let database = db::Database(pool.get()?);
+    let records = database.get_all_cves().map_err(|e| anyhow!(e))?;
+    info!("exporting {} records", records.len());
+
+    let num_cves = write(
+        &output.join("cves.parquet"),
+        CVES_SCHEMA,
+        &cve_columns(&records),
+    )?;
+    let num_products = write(
+        &output.join("products.parquet"),
+        PRODUCTS_SCHEMA,
+        &product_columns(&records),
+    )?;
+
+    Ok((num_cves, num_products))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+    use std::fs::{self, File};
+
+    use ::parquet::file::reader::{FileReader, SerializedFileReader};
+
+    use super::{cve_columns, product_columns, write, Column, CVES_SCHEMA, PRODUCTS_SCHEMA};
+    use crate::db::models::CVE;
+    use crate::search::testing;
+
+    fn record(cve: &str, product: &str, vector: Option<&str>) -> CVE {
+        CVE {
+            vendor: "busybox".into(),
+            vector: vector.map(String::from),
+            ..testing::cve(cve, product)
+        }
+    }
+
+    #[test]
+    fn can_export_cves_and_products() {
+        let records = vec![
+            record("CVE-2021-42373", "busybox", Some("AV:N")),
+            record("CVE-2021-42373", "udhcpd", Some("AV:N")),
+            record("CVE-2022-28391", "busybox", None),
+        ];
+
+        let cves = cve_columns(&records);
+        assert_eq!(
+            Column::Text(vec!["CVE-2021-42373".into(), "CVE-2022-28391".into()]),
+            cves[0]
+        );
+        assert_eq!(
+            Column::OptionalText(vec![Some("AV:N".into()), None]),
+            cves[5]
+        );
+
+        let dir = env::temp_dir().join(format!("kepler-parquet-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+
+        let path = dir.join("cves.parquet");
+        assert_eq!(2, write(&path, CVES_SCHEMA, &cves).unwrap());
+        let reader = SerializedFileReader::new(File::open(&path).unwrap()).unwrap();
+        assert_eq!(2, reader.metadata().file_metadata().num_rows());
+
+        let path = dir.join("products.parquet");
+        assert_eq!(
+            3,
+            write(&path, PRODUCTS_SCHEMA, &product_columns(&records)).unwrap()
+        );
+        let reader = SerializedFileReader::new(File::open(&path).unwrap()).unwrap();
+        assert_eq!(3, reader.metadata().file_metadata().num_rows());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,252 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use lazy_static::lazy_static;
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::db::models;
+use crate::search::Query;
+
+pub const SPEC_VERSION: &str = "2.1";
+
+lazy_static! {
+    /// Namespace mandated by the STIX 2.1 specification for deterministic SCO identifiers.
+    static ref NAMESPACE: Uuid = Uuid::parse_str("00abedb4-aa42-466c-9c01-fed23315a9b7").unwrap();
+}
+
+/// Builds a deterministic `<type>--<uuid>` identifier so that exporting the same data twice
+/// results in the same objects and platforms can deduplicate them.
+fn identifier(kind: &str, name: &str) -> String {
+    format!(
+        "{}--{}",
+        kind,
+        Uuid::new_v5(&NAMESPACE, format!("{}:{}", kind, name).as_bytes())
+    )
+}
+
+fn timestamp(time: DateTime<Utc>) -> String {
+    time.to_rfc3339_opts(SecondsFormat::Millis, true)
+}
+
+#[derive(Debug, Serialize, PartialEq)]
+pub struct ExternalReference {
+    pub source_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub external_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum Object {
+    Vulnerability {
+        spec_version: &'static str,
+        id: String,
+        created: String,
+        modified: String,
+        name: String,
+        description: String,
+        external_references: Vec<ExternalReference>,
+    },
+    Software {
+        spec_version: &'static str,
+        id: String,
+        name: String,
+        vendor: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        version: Option<String>,
+    },
+    Relationship {
+        spec_version: &'static str,
+        id: String,
+        created: String,
+        modified: String,
+        relationship_type: &'static str,
+        source_ref: String,
+        target_ref: String,
+    },
+}
+
+impl Object {
+    pub fn id(&self) -> &str {
+        match self {
+            Self::Vulnerability { id, .. } => id,
+            Self::Software { id, .. } => id,
+            Self::Relationship { id, .. } => id,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct Bundle {
+    #[serde(rename = "type")]
+    pub kind: &'static str,
+    pub id: String,
+    pub objects: Vec<Object>,
+}
+
+fn vulnerability(cve: &models::CVE) -> Object {
+    let mut external_references = vec![ExternalReference {
+        source_name: "cve".into(),
+        external_id: Some(cve.cve.clone()),
+        url: None,
+        description: None,
+    }];
+
+    for reference in cve.references.iter() {
+        // exploit references are tagged as such by NVD, keep the tags so consumers can tell them apart
+        let description = if reference.tags.is_empty() {
+            None
+        } else {
+            Some(reference.tags.join(", "))
+        };
+        external_references.push(ExternalReference {
+            source_name: cve.source.to_lowercase(),
+            external_id: None,
+            url: Some(reference.url.clone()),
+            description,
+        });
+    }
+
+    Object::Vulnerability {
+        spec_version: SPEC_VERSION,
+        id: identifier("vulnerability", &cve.cve),
+        created: timestamp(cve.created_at),
+        modified: timestamp(cve.updated_at.unwrap_or(cve.created_at)),
+        name: cve.cve.clone(),
+        description: cve.summary.clone(),
+        external_references,
+    }
+}
+
+fn software(query: &Query, cve: &models::CVE) -> Object {
+    let version = query.version.clone();
+    Object::Software {
+        spec_version: SPEC_VERSION,
+        id: identifier(
+            "software",
+            &format!(
+                "{}:{}:{}",
+                cve.vendor,
+                cve.product,
+                version.as_deref().unwrap_or("*")
+            ),
+        ),
+        name: cve.product.clone(),
+        vendor: cve.vendor.clone(),
+        version,
+    }
+}
+
+fn relationship(software: &Object, vulnerability: &Object, cve: &models::CVE) -> Object {
+    Object::Relationship {
+        spec_version: SPEC_VERSION,
+        id: identifier(
+            "relationship",
+            &format!("{}:{}", software.id(), vulnerability.id()),
+        ),
+        created: timestamp(cve.created_at),
+        modified: timestamp(cve.updated_at.unwrap_or(cve.created_at)),
+        relationship_type: "has",
+        source_ref: software.id().to_owned(),
+        target_ref: vulnerability.id().to_owned(),
+    }
+}
+
+pub fn bundle(results: &[(Query, Vec<models::CVE>)]) -> Bundle {
+    // the same CVE or product can show up for multiple queries, only emit them once
+    let mut objects: HashMap<String, Object> = HashMap::new();
+
+    for (query, cves) in results {
+        for cve in cves {
+            let vulnerability = vulnerability(cve);
+            let software = software(query, cve);
+            let relationship = relationship(&software, &vulnerability, cve);
+
+            for object in [vulnerability, software, relationship] {
+                objects.entry(object.id().to_owned()).or_insert(object);
+            }
+        }
+    }
+
+    let mut objects: Vec<Object> = objects.into_values().collect();
+    objects.sort_by(|a, b| a.id().cmp(b.id()));
+
+    Bundle {
+        kind: "bundle",
+        id: format!("bundle--{}", Uuid::new_v4()),
+        objects,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::UNIX_EPOCH;
+
+    use chrono::{DateTime, Utc};
+
+    use super::{bundle, Object};
+    use crate::db::models;
+    use crate::search::{testing, Query};
+
+    fn cve(id: &str) -> models::CVE {
+        models::CVE {
+            created_at: DateTime::<Utc>::from(UNIX_EPOCH),
+            vendor: "xmlsoft".into(),
+            references: diesel_json::Json::new(vec![models::Reference {
+                url: "https://www.exploit-db.com/exploits/1".into(),
+                tags: vec!["Exploit".into()],
+            }]),
+            ..testing::cve(id, "libxml2")
+        }
+    }
+
+    #[test]
+    fn can_build_deduplicated_bundle() {
+        let query = Query {
+            vendor: None,
+            product: "libxml2".into(),
+            version: Some("2.9.10".into()),
+            ..Default::default()
+        };
+        let results = vec![
+            (
+                query.clone(),
+                vec![cve("CVE-2021-3517"), cve("CVE-2021-3518")],
+            ),
+            (query, vec![cve("CVE-2021-3517")]),
+        ];
+
+        let bundle = bundle(&results);
+
+        let count = |kind: &str| {
+            bundle
+                .objects
+                .iter()
+                .filter(|o| o.id().starts_with(kind))
+                .count()
+        };
+        assert_eq!(2, count("vulnerability--"));
+        assert_eq!(1, count("software--"));
+        assert_eq!(2, count("relationship--"));
+
+        let exploit_tagged = bundle.objects.iter().any(|o| match o {
+            Object::Vulnerability {
+                external_references,
+                created,
+                ..
+            } => {
+                created == "1970-01-01T00:00:00.000Z"
+                    && external_references
+                        .iter()
+                        .any(|r| r.description.as_deref() == Some("Exploit"))
+            }
+            _ => false,
+        });
+        assert!(exploit_tagged);
+    }
+}
@@ -0,0 +1,255 @@
+//! Consistency checks of the data derived from the stored source records, like the records of
+//! the products of the NIST configurations, the software they run on and the revisions, which
+//! an import interrupted halfway can leave diverging.
+
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+
+use serde::Serialize;
+
+use crate::db::{models, Database};
+use crate::search;
+use crate::sources::allowlist::Allowlist;
+use crate::sources::{nist, Source};
+
+#[derive(Debug, Serialize)]
+pub struct Divergence {
+    pub check: &'static str,
+    pub detail: String,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct Report {
+    pub divergences: Vec<Divergence>,
+    /// Number of rows written or deleted to repair the divergences.
+    pub repaired: usize,
+}
+
+impl Report {
+    fn push<D: Into<String>>(&mut self, check: &'static str, detail: D) {
+        self.divergences.push(Divergence {
+            check,
+            detail: detail.into(),
+        })
+    }
+
+    pub fn is_consistent(&self) -> bool {
+        self.divergences.is_empty()
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for divergence in &self.divergences {
+            writeln!(f, "{:<24} {}", divergence.check, divergence.detail)?;
+        }
+        write!(
+            f,
+            "{} divergences found, {} rows repaired",
+            self.divergences.len(),
+            self.repaired
+        )
+    }
+}
+
+/// Products expected but without a record, and products having a record but not expected.
+fn compare<'a>(
+    expected: &'a [(String, String)],
+    present: &'a [(String, String)],
+) -> (Vec<&'a (String, String)>, Vec<&'a (String, String)>) {
+    let missing = expected.iter().filter(|p| !present.contains(p)).collect();
+    let stale = present.iter().filter(|p| !expected.contains(p)).collect();
+    (missing, stale)
+}
+
+/// Checks the records against their object, rebuilding the NIST records and product targets
+/// from the configurations of the CVEs with `repair`.
+fn check_records(database: &Database, repair: bool, report: &mut Report) -> Result<(), String> {
+    let orphans = database.get_records_without_object()?;
+    for id in &orphans {
+        report.push("record without object", format!("record {}", id));
+    }
+    if repair && !orphans.is_empty() {
+        report.repaired += database.delete_records(&orphans)?;
+    }
+
+    // the records skipped by the import allowlist are not missing
+    let allowlist = Allowlist::from_env()?;
+    let admitted = |vendor: &str, product: &str| {
+        allowlist
+            .as_ref()
+            .map_or(true, |allowlist| allowlist.allows(vendor, product))
+    };
+
+    let all_records = database.get_all_records()?;
+    let mut objects: BTreeMap<i32, (&models::Object, Vec<&models::CVE>)> = BTreeMap::new();
+    for (record, object) in &all_records {
+        objects
+            .entry(object.id)
+            .or_insert_with(|| (object, vec![]))
+            .1
+            .push(record);
+    }
+
+    let mut targets: HashSet<(String, String, String)> =
+        database.get_product_targets()?.into_iter().collect();
+    let mut missing_records = vec![];
+    let mut stale_records = vec![];
+    let mut missing_targets = vec![];
+    for (object, records) in objects.values() {
+        let mut item = match search::deserialize(records[0], object) {
+            Ok(Source::Nist(item)) => item,
+            Ok(_) => continue,
+            Err(e) => {
+                report.push("unreadable object", e);
+                continue;
+            }
+        };
+
+        let nist_records: Vec<&models::CVE> = records
+            .iter()
+            .filter(|record| record.source == nist::SOURCE_NAME)
+            .copied()
+            .collect();
+        let template = match nist_records.first() {
+            Some(record) => *record,
+            None => continue,
+        };
+
+        let expected: Vec<(String, String)> = item
+            .collect_unique_products()
+            .into_iter()
+            .map(|product| (product.vendor, product.product))
+            .collect();
+        let present: Vec<(String, String)> = nist_records
+            .iter()
+            .map(|record| (record.vendor.clone(), record.product.clone()))
+            .collect();
+        let (missing, stale) = compare(&expected, &present);
+
+        for (vendor, product) in missing {
+            if !admitted(vendor, product) {
+                continue;
+            }
+            report.push(
+                "missing record",
+                format!("{} of {}:{}", object.cve, vendor, product),
+            );
+            missing_records.push(models::NewCVE::with(
+                nist::SOURCE_NAME.into(),
+                vendor.clone(),
+                product.clone(),
+                template.cve.clone(),
+                template.summary.clone(),
+                template.score,
+                template.severity.clone(),
+                template.vector.clone(),
+                template.references.0.clone(),
+                Some(object.id),
+            ));
+        }
+        for (vendor, product) in stale {
+            report.push(
+                "stale record",
+                format!("{} of {}:{}", object.cve, vendor, product),
+            );
+            stale_records.extend(
+                nist_records
+                    .iter()
+                    .filter(|record| &record.vendor == vendor && &record.product == product)
+                    .map(|record| record.id),
+            );
+        }
+
+        for target in item.collect_unique_targets() {
+            let key = (
+                target.vendor.clone(),
+                target.product.clone(),
+                target.target_sw.clone(),
+            );
+            if targets.insert(key) {
+                report.push(
+                    "missing product target",
+                    format!(
+                        "{}:{} on {}",
+                        target.vendor, target.product, target.target_sw
+                    ),
+                );
+                missing_targets.push(models::NewProductTarget::with(target));
+            }
+        }
+    }
+
+    if repair {
+        if !stale_records.is_empty() {
+            report.repaired += database.delete_records(&stale_records)?;
+        }
+        for record in missing_records {
+            if database.create_cve_if_not_exist(record)? {
+                report.repaired += 1;
+            }
+        }
+        if !missing_targets.is_empty() {
+            report.repaired += database.create_product_targets(&missing_targets)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Checks the number of records of the revisions, once the records are repaired as their
+/// changes are counted.
+fn check_revisions(database: &Database, repair: bool, report: &mut Report) -> Result<(), String> {
+    let (num_cves, num_products) = database.count_divergent_revisions()?;
+    if num_cves > 0 {
+        report.push(
+            "wrong cve revisions",
+            format!("{} CVEs with a wrong number of records", num_cves),
+        );
+    }
+    if num_products > 0 {
+        report.push(
+            "wrong product revisions",
+            format!("{} products with a wrong number of records", num_products),
+        );
+    }
+    if repair && num_cves + num_products > 0 {
+        report.repaired += database.recount_revisions()?;
+    }
+    Ok(())
+}
+
+/// Cross-checks the records, the product targets and the revisions against the stored
+/// objects, and rebuilds the diverging ones with `repair`.
+pub fn run(database: &Database, repair: bool) -> Result<Report, String> {
+    let mut report = Report::default();
+    check_records(database, repair, &mut report)?;
+    check_revisions(database, repair, &mut report)?;
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::compare;
+
+    fn products(names: &[(&str, &str)]) -> Vec<(String, String)> {
+        names
+            .iter()
+            .map(|(vendor, product)| (vendor.to_string(), product.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn can_compare_products() {
+        let expected = products(&[("apache", "log4j"), ("apache", "struts")]);
+        let present = products(&[("apache", "struts"), ("apache", "tomcat")]);
+
+        let (missing, stale) = compare(&expected, &present);
+        assert_eq!(vec![&expected[0]], missing);
+        assert_eq!(vec![&present[1]], stale);
+
+        let (missing, stale) = compare(&expected, &expected);
+        assert!(missing.is_empty());
+        assert!(stale.is_empty());
+    }
+}
@@ -5,9 +5,27 @@ extern crate r2d2_diesel;
 
 use lazy_static::lazy_static;
 
+#[cfg(feature = "server")]
 pub mod api;
+#[cfg(feature = "http")]
+pub mod bench;
+#[cfg(feature = "http")]
+pub mod bus;
+pub mod cli;
+pub mod crash;
 pub mod db;
+pub mod doctor;
+pub mod export;
+pub mod fsck;
+pub mod manifest;
+#[cfg(feature = "http")]
+pub mod monitor;
+#[cfg(feature = "http")]
+pub mod notify;
+pub mod scan;
 pub mod search;
+#[cfg(feature = "http")]
+pub mod sink;
 pub mod sources;
 pub mod utils;
 
@@ -23,3 +41,32 @@ pub fn version() -> &'static str {
     }
     &VERSION
 }
+
+/// Commit the binary was built from, when known at build time.
+pub fn git_commit() -> Option<&'static str> {
+    option_env!("KEPLER_GIT_COMMIT")
+}
+
+/// Date of the build, formatted as `YYYY-MM-DD`.
+pub fn build_date() -> &'static str {
+    env!("KEPLER_BUILD_DATE")
+}
+
+/// Cargo features the binary was built with.
+pub fn features() -> Vec<&'static str> {
+    let features = [
+        ("server", cfg!(feature = "server")),
+        ("import", cfg!(feature = "import")),
+        ("http", cfg!(feature = "http")),
+        ("native-tls", cfg!(feature = "native-tls")),
+        ("rustls", cfg!(feature = "rustls")),
+        ("parquet", cfg!(feature = "parquet")),
+        ("jemalloc", cfg!(feature = "jemalloc")),
+    ];
+
+    features
+        .iter()
+        .filter(|(_, enabled)| *enabled)
+        .map(|(name, _)| *name)
+        .collect()
+}
@@ -1,36 +1,79 @@
-use anyhow::{Context, Result};
-use clap::{Arg, Command};
+use anyhow::{anyhow, bail, Context, Result};
+use chrono::{Duration, Utc};
+use clap::{Arg, ArgMatches, Command};
 use dotenv::dotenv;
-use env_logger::Env;
-use std::{env, fs, path::PathBuf};
+use std::{fs, path::PathBuf};
 
-use kepler::{
-    api, db,
-    sources::{nist, npm},
-};
+use kepler::search::{self, enrichment::Enrichment, similarity, Query};
+#[cfg(feature = "http")]
+use kepler::{bench, sink};
+use kepler::{cli, crash, db, doctor, export::misp, fsck, manifest, sources::nist};
 
-#[actix_web::main]
-async fn main() -> Result<()> {
-    let matches = Command::new("nvdio")
+#[cfg(feature = "jemalloc")]
+#[global_allocator]
+static GLOBAL: tikv_jemallocator::Jemalloc = tikv_jemallocator::Jemalloc;
+
+fn main() -> Result<()> {
+    let command = Command::new("nvdio")
         .version(kepler::version())
         .about("Kepler vulnerability database search engine")
         .disable_help_subcommand(true)
-        .arg(
-            Arg::new("migrate")
-                .short('m')
-                .long("migrate")
-                .takes_value(false)
-                .help("Migrate database"),
+        .arg(cli::migrate_arg())
+        .subcommand(
+            Command::new("analyze_related")
+                .about("finds the related CVEs of every CVE by the similarity of their summaries and products")
+                .arg(
+                    Arg::new("limit")
+                        .long("limit")
+                        .default_value("10")
+                        .help("Maximum number of related CVEs of every CVE"),
+                )
+                .arg(
+                    Arg::new("min-score")
+                        .long("min-score")
+                        .default_value("0.3")
+                        .help("Minimum similarity, from 0 to 1"),
+                ),
         )
         .subcommand(
-            Command::new("import_nist")
-                .about("imports the specified year of CVE from the NIST data feed")
+            Command::new("bench_http")
+                .about("replays a mix of requests against a running instance and reports the latencies")
                 .arg(
-                    Arg::new("year")
-                        .help("the year to import")
+                    Arg::new("mix")
+                        .help("JSON file with the list of requests to replay")
                         .index(1)
                         .required(true),
                 )
+                .arg(
+                    Arg::new("url")
+                        .long("url")
+                        .default_value("http://localhost:8000")
+                        .help("Base URL of the instance"),
+                )
+                .arg(
+                    Arg::new("requests")
+                        .short('n')
+                        .long("requests")
+                        .default_value("1000")
+                        .help("Total number of requests"),
+                )
+                .arg(
+                    Arg::new("concurrency")
+                        .short('c')
+                        .long("concurrency")
+                        .default_value("4")
+                        .help("Number of concurrent clients"),
+                )
+                .arg(
+                    Arg::new("max-p99")
+                        .long("max-p99")
+                        .takes_value(true)
+                        .help("Fail if the 99th percentile latency exceeds this number of milliseconds"),
+                ),
+        )
+        .subcommand(
+            Command::new("doctor")
+                .about("checks the database, the settings, the data path and the data sources, and prints a report")
                 .arg(
                     Arg::new("data")
                         .short('d')
@@ -39,22 +82,40 @@ async fn main() -> Result<()> {
                         .help("Data path."),
                 )
                 .arg(
-                    Arg::new("fresh")
-                        .short('f')
-                        .long("fresh")
+                    Arg::new("offline")
+                        .long("offline")
                         .takes_value(false)
-                        .help("Download fresh files"),
+                        .help("Don't probe the data sources"),
+                )
+                .arg(
+                    Arg::new("json")
+                        .long("json")
+                        .takes_value(false)
+                        .help("Print the report as JSON"),
                 ),
         )
         .subcommand(
-            Command::new("import_npm")
-                .about("imports vulnerabilities from the registry.npmjs.org data feed")
+            Command::new("gen_fixture")
+                .about(
+                    "generates a feed of synthetic CVEs for testing, importable with import_nist --feed",
+                )
                 .arg(
-                    Arg::new("recent")
-                        .short('r')
-                        .long("recent")
-                        .takes_value(false)
-                        .help("only download recent records"),
+                    Arg::new("cves")
+                        .long("cves")
+                        .default_value("1000")
+                        .help("Number of CVEs to generate"),
+                )
+                .arg(
+                    Arg::new("seed")
+                        .long("seed")
+                        .default_value("42")
+                        .help("Seed of the generator, the same seed generates the same CVEs"),
+                )
+                .arg(
+                    Arg::new("year")
+                        .long("year")
+                        .default_value("2099")
+                        .help("Year of the CVE identifiers and of the feed file name"),
                 )
                 .arg(
                     Arg::new("data")
@@ -64,92 +125,523 @@ async fn main() -> Result<()> {
                         .help("Data path."),
                 ),
         )
-        .get_matches();
+        .subcommand(
+            Command::new("export_misp")
+                .about(
+                    "generates a MISP feed of the recent high severity CVEs affecting a watchlist",
+                )
+                .arg(
+                    Arg::new("watchlist")
+                        .help("JSON file with the list of queries to watch")
+                        .index(1)
+                        .required(true),
+                )
+                .arg(
+                    Arg::new("output")
+                        .short('o')
+                        .long("output")
+                        .default_value("./misp")
+                        .help("Feed output path."),
+                )
+                .arg(
+                    Arg::new("days")
+                        .long("days")
+                        .default_value("30")
+                        .help("Only include CVEs imported in the last number of days"),
+                ),
+        )
+        .subcommand(
+            Command::new("export_assets")
+                .about("writes the assets as a YAML manifest")
+                .arg(
+                    Arg::new("output")
+                        .short('o')
+                        .long("output")
+                        .default_value("./assets.yaml")
+                        .help("Manifest output file."),
+                ),
+        )
+        .subcommand(
+            Command::new("apply_assets")
+                .about("creates and updates the assets of a YAML manifest")
+                .arg(
+                    Arg::new("manifest")
+                        .help("YAML manifest of the assets")
+                        .index(1)
+                        .required(true),
+                )
+                .arg(
+                    Arg::new("prune")
+                        .long("prune")
+                        .takes_value(false)
+                        .help("Delete the assets missing from the manifest"),
+                )
+                .arg(
+                    Arg::new("dry-run")
+                        .long("dry-run")
+                        .takes_value(false)
+                        .help("Only report the changes without applying them"),
+                ),
+        )
+        .subcommand(
+            Command::new("search_cves")
+                .about("prints the CVEs affecting a product, flagging the exploited ones")
+                .arg(
+                    Arg::new("product")
+                        .help("Product name")
+                        .index(1)
+                        .required(true),
+                )
+                .arg(
+                    Arg::new("product-version")
+                        .help("Product version, all the versions if missing")
+                        .index(2),
+                )
+                .arg(
+                    Arg::new("vendor")
+                        .long("vendor")
+                        .takes_value(true)
+                        .help("Vendor name"),
+                )
+                .arg(
+                    Arg::new("target-sw")
+                        .long("target-sw")
+                        .takes_value(true)
+                        .help("Software the product runs on, like wordpress for its plugins"),
+                )
+                .arg(
+                    Arg::new("part")
+                        .long("part")
+                        .takes_value(true)
+                        .help("Only match the applications, the operating systems or the hardware"),
+                )
+                .arg(
+                    Arg::new("distro")
+                        .long("distro")
+                        .takes_value(true)
+                        .help("Distribution release of the package, like alpine:3.19"),
+                )
+                .arg(
+                    Arg::new("os")
+                        .long("os")
+                        .takes_value(true)
+                        .help("Operating system the product runs on, like o:linux:linux_kernel:5.10"),
+                )
+                .arg(
+                    Arg::new("update")
+                        .long("update")
+                        .takes_value(true)
+                        .help("Update of the version, like p1"),
+                )
+                .arg(
+                    Arg::new("edition")
+                        .long("edition")
+                        .takes_value(true)
+                        .help("Edition of the product, like enterprise"),
+                )
+                .arg(
+                    Arg::new("strictness")
+                        .long("strictness")
+                        .takes_value(true)
+                        .help("Matching strictness, one of strict, default or paranoid"),
+                )
+                .arg(
+                    Arg::new("json")
+                        .long("json")
+                        .takes_value(false)
+                        .help("Print the CVEs as JSON"),
+                ),
+        )
+        .subcommand(
+            Command::new("gc")
+                .about("deletes the objects, product targets and revisions no record refers to anymore"),
+        )
+        .subcommand(
+            Command::new("fsck")
+                .about("cross-checks the records, product targets and revisions against the stored source records")
+                .arg(
+                    Arg::new("repair")
+                        .long("repair")
+                        .takes_value(false)
+                        .help("Rebuild the diverging records, product targets and revisions"),
+                )
+                .arg(
+                    Arg::new("json")
+                        .long("json")
+                        .takes_value(false)
+                        .help("Print the report as JSON"),
+                ),
+        )
+        .subcommand(
+            Command::new("purge_assets")
+                .about("permanently deletes the assets deleted before the restore window")
+                .arg(
+                    Arg::new("days")
+                        .long("days")
+                        .default_value("30")
+                        .help("Purge the assets deleted more than this number of days ago"),
+                ),
+        );
+    #[cfg(feature = "parquet")]
+    let command = command.subcommand(
+        Command::new("export_parquet")
+            .about("exports the CVEs and the products they affect as Parquet files")
+            .arg(
+                Arg::new("output")
+                    .short('o')
+                    .long("output")
+                    .default_value("./parquet")
+                    .help("Output path."),
+            ),
+    );
+    #[cfg(feature = "http")]
+    let command = command.subcommand(
+        Command::new("sink_records")
+            .about("streams the records imported in the last days to the warehouse sink")
+            .arg(
+                Arg::new("days")
+                    .long("days")
+                    .default_value("1")
+                    .help("Only stream the records imported in the last number of days"),
+            ),
+    );
+    #[cfg(feature = "import")]
+    let command = command.subcommands(cli::import_commands());
+    let matches = command.get_matches();
 
     dotenv().ok();
 
-    // Database pool connection
-    let pool = {
-        let database_url = env::var("DATABASE_URL")
-            .context("DATABASE_URL environment variable has not specified.")?;
-        db::setup(&database_url).context("Cannot connect to database")?
-    };
-
-    // Setup logger
-    {
-        #[cfg(debug_assertions)]
-        let default_env_filter = "debug";
-        #[cfg(not(debug_assertions))]
-        let default_env_filter = "info";
-
-        match matches.subcommand() {
-            Some(_) => {
-                // Init logger for non web application
-                let env = Env::default().default_filter_or(default_env_filter);
-                env_logger::Builder::from_env(env).try_init()
-            }
-            None => {
-                // Init tracer for web application
-                api::init_logger(default_env_filter)
-            }
+    cli::init_logger(matches.subcommand().is_none())?;
+    crash::install();
+
+    // Benchmarks run against an instance, without a database
+    if let Some(("bench_http", matches)) = matches.subcommand() {
+        return bench_http(matches);
+    }
+
+    // Diagnostics run even when the database is unreachable or not migrated
+    if let Some(("doctor", matches)) = matches.subcommand() {
+        let report = doctor::run(
+            &PathBuf::from(matches.value_of("data").unwrap()),
+            matches.is_present("offline"),
+        );
+
+        if matches.is_present("json") {
+            println!("{}", serde_json::to_string_pretty(&report)?);
+        } else {
+            println!("{}", report);
+        }
+
+        if report.status() == doctor::Status::Error {
+            bail!("some checks failed");
         }
-        .context("Failed to setup logger")?;
+        return Ok(());
     }
 
-    // Setup database if needed and check for migrations
-    {
-        let conn = pool.get()?;
+    // Fixtures are generated without a database
+    if let Some(("gen_fixture", matches)) = matches.subcommand() {
+        let num_cves = matches
+            .value_of("cves")
+            .unwrap()
+            .parse::<u32>()
+            .context("Invalid number of CVEs")?;
+        let seed = matches
+            .value_of("seed")
+            .unwrap()
+            .parse::<u64>()
+            .context("Invalid seed")?;
+
+        let data_path = PathBuf::from(matches.value_of("data").unwrap());
+        fs::create_dir_all(&data_path).context("Could not create data path")?;
+
+        let file_name = nist::fixture::run(
+            &data_path,
+            matches.value_of("year").unwrap(),
+            num_cves,
+            seed,
+        )
+        .map_err(anyhow::Error::msg)?;
+
+        log::info!("{num_cves} CVEs written to {}", file_name.display());
+
+        return Ok(());
+    }
+
+    let pool = cli::connect(matches.is_present("migrate"))?;
+
+    match matches.subcommand() {
+        Some(("export_misp", matches)) => {
+            let days = matches
+                .value_of("days")
+                .unwrap()
+                .parse::<u64>()
+                .context("Invalid number of days")?;
 
-        diesel_migrations::setup_database(&*conn)?;
+            let num_events = misp::run(
+                &pool,
+                &PathBuf::from(matches.value_of("watchlist").unwrap()),
+                &PathBuf::from(matches.value_of("output").unwrap()),
+                days,
+            )?;
 
-        if diesel_migrations::any_pending_migrations(&*conn)? {
-            if matches.is_present("migrate") {
-                diesel_migrations::run_pending_migrations(&*conn)?;
-                log::info!("Migration successfully")
+            log::info!("{num_events} MISP events written");
+        }
+        Some(("analyze_related", matches)) => {
+            let limit = matches
+                .value_of("limit")
+                .unwrap()
+                .parse::<usize>()
+                .context("Invalid limit")?;
+            let min_score = matches
+                .value_of("min-score")
+                .unwrap()
+                .parse::<f64>()
+                .context("Invalid minimum similarity")?;
+
+            let num_relations = similarity::run(&pool, limit, min_score).map_err(|e| anyhow!(e))?;
+
+            log::info!("{num_relations} related CVEs stored");
+        }
+        Some(("export_assets", matches)) => {
+            let output = PathBuf::from(matches.value_of("output").unwrap());
+            let assets = db::Database(pool.get()?)
+                .get_assets()
+                .map_err(|e| anyhow!(e))?;
+
+            let yaml = manifest::Manifest::with(&assets)
+                .to_yaml()
+                .map_err(|e| anyhow!(e))?;
+            fs::write(&output, yaml)
+                .with_context(|| format!("could not write {}", output.display()))?;
+
+            log::info!("{} assets written to {}", assets.len(), output.display());
+        }
+        Some(("apply_assets", matches)) => {
+            let input = PathBuf::from(matches.value_of("manifest").unwrap());
+            let yaml =
+                fs::read(&input).with_context(|| format!("could not read {}", input.display()))?;
+            let manifest = manifest::Manifest::parse(&yaml).map_err(|e| anyhow!(e))?;
+
+            let database = db::Database(pool.get()?);
+            let assets = database.get_assets().map_err(|e| anyhow!(e))?;
+            let plan = manifest::Plan::new(manifest, &assets, matches.is_present("prune"))
+                .map_err(|e| anyhow!(e))?;
+
+            let summary = if matches.is_present("dry-run") {
+                plan.summary()
             } else {
-                log::error!("Migration needed");
-                std::process::exit(1)
+                plan.apply(&database).map_err(|e| anyhow!(e))?
+            };
+
+            for name in &summary.created {
+                log::info!("created {name}");
+            }
+            for name in &summary.updated {
+                log::info!("updated {name}");
+            }
+            for name in &summary.deleted {
+                log::info!("deleted {name}");
             }
+            log::info!(
+                "{} assets created, {} updated, {} unchanged, {} deleted",
+                summary.created.len(),
+                summary.updated.len(),
+                summary.unchanged.len(),
+                summary.deleted.len()
+            );
         }
-    }
+        Some(("search_cves", matches)) => {
+            let query = Query {
+                vendor: matches.value_of("vendor").map(str::to_string),
+                product: matches.value_of("product").unwrap().to_string(),
+                version: matches.value_of("product-version").map(str::to_string),
+                target_sw: matches.value_of("target-sw").map(str::to_string),
+                part: matches.value_of("part").map(str::to_string),
+                distro: matches.value_of("distro").map(str::to_string),
+                os: matches.value_of("os").map(str::to_string),
+                update: matches.value_of("update").map(str::to_string),
+                edition: matches.value_of("edition").map(str::to_string),
+                strictness: matches
+                    .value_of("strictness")
+                    .map(str::parse)
+                    .transpose()
+                    .map_err(|e: String| anyhow!(e))?,
+            };
 
-    match matches.subcommand() {
-        Some((exec_name, matches)) => {
-            // Handle data directory creation
-            let data_path = PathBuf::from(matches.value_of("data").unwrap());
-            if !data_path.exists() {
-                log::info!("creating {}", data_path.display());
-                fs::create_dir_all(&data_path).expect("could not create data path");
+            let database = db::Database(pool.get()?);
+            let records = search::query(&database, &query).map_err(|e| anyhow!(e))?;
+            let enrichment = Enrichment::load(&database, &records).map_err(|e| anyhow!(e))?;
+            let cves: Vec<_> = records
+                .into_iter()
+                .map(|record| enrichment.cve(record))
+                .collect();
+
+            if matches.is_present("json") {
+                println!("{}", serde_json::to_string_pretty(&cves)?);
+            } else {
+                for cve in &cves {
+                    let mut flags = vec![];
+                    if cve.exploitation.known_exploited {
+                        flags.push("known exploited".to_string());
+                    }
+                    if let Some(score) = cve.epss.epss_score {
+                        flags.push(format!("epss {:.3}", score));
+                    }
+                    if cve.metasploit.metasploit_module {
+                        flags.push("metasploit module".to_string());
+                    }
+                    println!(
+                        "{} {:>4.1} {:<8} {}/{} {}",
+                        cve.cve,
+                        cve.score,
+                        cve.severity,
+                        cve.vendor,
+                        cve.product,
+                        flags.join(", ")
+                    );
+                    for url in &cve.exploits.exploits {
+                        println!("    exploit: {}", url);
+                    }
+                    for module in &cve.metasploit.metasploit_modules {
+                        println!("    metasploit: {}", module);
+                    }
+                    for weakness in &cve.weaknesses.weaknesses {
+                        match &weakness.name {
+                            Some(name) => println!("    weakness: {} {}", weakness.id, name),
+                            None => println!("    weakness: {}", weakness.id),
+                        }
+                    }
+                }
             }
+        }
+        Some(("gc", _)) => cli::collect_garbage(&db::Database(pool.get()?))?,
+        Some(("fsck", matches)) => {
+            let repair = matches.is_present("repair");
+            let report = fsck::run(&db::Database(pool.get()?), repair).map_err(|e| anyhow!(e))?;
 
-            // Import by command
-            let num_records = match exec_name {
-                "import_nist" => nist::import::run(
-                    &pool,
-                    matches.value_of("year").unwrap(),
-                    &data_path,
-                    matches.is_present("fresh"),
-                ),
+            if matches.is_present("json") {
+                println!("{}", serde_json::to_string_pretty(&report)?);
+            } else {
+                println!("{}", report);
+            }
 
-                "import_npm" => npm::import::run(&pool, matches.is_present("recent"), &data_path),
+            if !repair && !report.is_consistent() {
+                bail!("some derived data diverges from the stored records, run with --repair");
+            }
+        }
+        Some(("purge_assets", matches)) => {
+            let days = matches
+                .value_of("days")
+                .unwrap()
+                .parse::<u32>()
+                .context("Invalid number of days")?;
 
-                _ => unreachable!("Trying to launch a not existent subcommand"),
-            }?;
+            let before = Utc::now() - Duration::days(days.into());
+            let num_purged = db::Database(pool.get()?)
+                .purge_assets(before)
+                .map_err(|e| anyhow!(e))?;
 
-            let report = report_message(num_records);
+            log::info!("{num_purged} deleted assets purged");
+        }
+        #[cfg(feature = "parquet")]
+        Some(("export_parquet", matches)) => {
+            let (num_cves, num_products) = kepler::export::parquet::run(
+                &pool,
+                &PathBuf::from(matches.value_of("output").unwrap()),
+            )?;
 
-            log::info!("{report}");
+            log::info!("{num_cves} CVEs and {num_products} product mappings exported");
+        }
+        #[cfg(feature = "http")]
+        Some(("sink_records", matches)) => {
+            let days = matches
+                .value_of("days")
+                .unwrap()
+                .parse::<u32>()
+                .context("Invalid number of days")?;
+            let writer = sink::from_env()
+                .map_err(|e| anyhow!(e))?
+                .context("No sink configured, KEPLER_SINK is not set")?;
+
+            let since = Utc::now() - Duration::days(days.into());
+            let database = db::Database(pool.get()?);
+            let num_rows =
+                sink::stream_since(writer.as_ref(), &database, since).map_err(|e| anyhow!(e))?;
+
+            log::info!("{num_rows} records streamed to {}", writer.name());
+        }
+        #[cfg(feature = "import")]
+        Some((exec_name, matches)) => cli::import(&pool, exec_name, matches)?,
+        #[cfg(not(feature = "import"))]
+        Some(_) => unreachable!("Trying to launch a not existent subcommand"),
+        #[cfg(feature = "server")]
+        None => cli::serve(pool)?,
+        #[cfg(not(feature = "server"))]
+        None => {
+            bail!("the API server is not available, kepler was built without the server feature")
         }
-        None => api::run(pool)?.await?,
     }
 
     Ok(())
 }
 
-fn report_message(num_records: u32) -> String {
-    if num_records == 0 {
-        "No new records created".to_string()
-    } else {
-        format!("{num_records} new records created")
+/// Replays a mix of requests against a running instance and reports the latencies.
+#[cfg(feature = "http")]
+fn bench_http(matches: &ArgMatches) -> Result<()> {
+    let total = matches
+        .value_of("requests")
+        .unwrap()
+        .parse::<usize>()
+        .context("Invalid number of requests")?;
+    let concurrency = matches
+        .value_of("concurrency")
+        .unwrap()
+        .parse::<usize>()
+        .context("Invalid concurrency")?;
+    let max_p99 = matches
+        .value_of("max-p99")
+        .map(|ms| ms.parse::<u64>().context("Invalid 99th percentile limit"))
+        .transpose()?;
+
+    let report = bench::run(
+        matches.value_of("url").unwrap(),
+        &PathBuf::from(matches.value_of("mix").unwrap()),
+        total,
+        concurrency,
+    )?;
+
+    log::info!(
+        "{} requests ({} errors) in {:?}, {:.1} req/s",
+        report.requests,
+        report.errors,
+        report.elapsed,
+        report.throughput()
+    );
+    log::info!(
+        "latencies: p50 {:?}, p95 {:?}, p99 {:?}, max {:?}",
+        report.p50,
+        report.p95,
+        report.p99,
+        report.max
+    );
+
+    if let Some(max_p99) = max_p99 {
+        if report.p99.as_millis() > max_p99 as u128 {
+            bail!(
+                "99th percentile latency {:?} exceeds {}ms",
+                report.p99,
+                max_p99
+            );
+        }
+    }
+    if report.errors > 0 {
+        bail!("{} requests failed", report.errors);
     }
+
+    Ok(())
+}
+
+#[cfg(not(feature = "http"))]
+fn bench_http(_matches: &ArgMatches) -> Result<()> {
+    bail!("bench_http is not available, kepler was built without the http feature")
 }
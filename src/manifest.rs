@@ -0,0 +1,294 @@
+//! Manifest of the assets as a YAML file, so that they can be reviewed and versioned as
+//! code, then applied to any number of instances.
+
+use std::collections::{HashMap, HashSet};
+
+use ring::digest::{digest, SHA256};
+use serde::{Deserialize, Serialize};
+
+use crate::db::{models, Database};
+use crate::search::Query;
+
+/// Format of the components of the assets created or updated by a manifest.
+const FORMAT: &str = "products";
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Manifest {
+    #[serde(default)]
+    pub assets: Vec<AssetSpec>,
+}
+
+/// Asset as described in the manifest, identified by its name.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AssetSpec {
+    pub name: String,
+    pub kind: String,
+    #[serde(default, skip_serializing_if = "models::Labels::is_empty")]
+    pub labels: models::Labels,
+    #[serde(default)]
+    pub components: Vec<Query>,
+}
+
+impl From<&models::Asset> for AssetSpec {
+    fn from(asset: &models::Asset) -> Self {
+        Self {
+            name: asset.name.clone(),
+            kind: asset.kind.clone(),
+            labels: asset.labels.0.clone(),
+            components: asset.components.0.clone(),
+        }
+    }
+}
+
+impl Manifest {
+    /// Manifest of the assets in name order.
+    pub fn with(assets: &[models::Asset]) -> Self {
+        let mut assets: Vec<AssetSpec> = assets.iter().map(AssetSpec::from).collect();
+        assets.sort_by(|a, b| a.name.cmp(&b.name));
+        Self { assets }
+    }
+
+    pub fn parse(yaml: &[u8]) -> Result<Self, String> {
+        serde_yaml::from_slice(yaml).map_err(|e| format!("could not parse manifest: {}", e))
+    }
+
+    pub fn to_yaml(&self) -> Result<String, String> {
+        serde_yaml::to_string(self).map_err(|e| format!("could not write manifest: {}", e))
+    }
+
+    /// Version of the assets of the manifest, the SHA-256 of its YAML, which doesn't depend
+    /// on the order of the assets nor of their labels. Declarative tools compare it to
+    /// detect drift, and send it back to apply their changes only if nothing else changed
+    /// the assets in the meantime.
+    pub fn version(&self) -> Result<String, String> {
+        let mut sorted = self.clone();
+        sorted.assets.sort_by(|a, b| a.name.cmp(&b.name));
+
+        Ok(digest(&SHA256, sorted.to_yaml()?.as_bytes())
+            .as_ref()
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect())
+    }
+}
+
+/// Changes needed for the assets to match the manifest.
+#[derive(Debug, Default)]
+pub struct Plan {
+    pub create: Vec<AssetSpec>,
+    pub update: Vec<(i32, AssetSpec)>,
+    pub unchanged: Vec<String>,
+    /// The assets missing from the manifest, deleted when pruning.
+    pub delete: Vec<(i32, String)>,
+}
+
+/// Names of the assets changed, or to be changed with a dry run.
+#[derive(Debug, Default, PartialEq, Serialize)]
+pub struct Summary {
+    pub created: Vec<String>,
+    pub updated: Vec<String>,
+    pub unchanged: Vec<String>,
+    pub deleted: Vec<String>,
+}
+
+impl Plan {
+    /// Compares the manifest with the current assets, deleting the ones it doesn't list
+    /// with `prune`. The assets are matched by name, which must identify a single asset.
+    pub fn new(manifest: Manifest, assets: &[models::Asset], prune: bool) -> Result<Self, String> {
+        let mut names = HashSet::new();
+        for spec in &manifest.assets {
+            if spec.name.trim().is_empty() {
+                return Err("asset without name in manifest".into());
+            }
+            if !models::ASSET_KINDS.contains(&spec.kind.as_str()) {
+                return Err(format!(
+                    "invalid kind '{}' of asset {}, expected one of {}",
+                    spec.kind,
+                    spec.name,
+                    models::ASSET_KINDS.join(", ")
+                ));
+            }
+            if !names.insert(spec.name.as_str()) {
+                return Err(format!("asset {} listed twice in manifest", spec.name));
+            }
+        }
+
+        let mut by_name: HashMap<&str, Vec<&models::Asset>> = HashMap::new();
+        for asset in assets {
+            by_name.entry(asset.name.as_str()).or_default().push(asset);
+        }
+
+        let mut plan = Self::default();
+        for spec in manifest.assets {
+            match by_name.get(spec.name.as_str()).map(Vec::as_slice) {
+                None => plan.create.push(spec),
+                Some([asset]) if AssetSpec::from(*asset) == spec => plan.unchanged.push(spec.name),
+                Some([asset]) => plan.update.push((asset.id, spec)),
+                Some(_) => {
+                    return Err(format!(
+                        "several assets named {}, the manifest can't tell them apart",
+                        spec.name
+                    ))
+                }
+            }
+        }
+
+        if prune {
+            plan.delete = assets
+                .iter()
+                .filter(|asset| !names.contains(asset.name.as_str()))
+                .map(|asset| (asset.id, asset.name.clone()))
+                .collect();
+        }
+
+        Ok(plan)
+    }
+
+    pub fn summary(&self) -> Summary {
+        Summary {
+            created: self.create.iter().map(|spec| spec.name.clone()).collect(),
+            updated: self
+                .update
+                .iter()
+                .map(|(_, spec)| spec.name.clone())
+                .collect(),
+            unchanged: self.unchanged.clone(),
+            deleted: self.delete.iter().map(|(_, name)| name.clone()).collect(),
+        }
+    }
+
+    /// Applies the changes, the pruned assets being soft deleted so that they can still be
+    /// restored.
+    pub fn apply(self, database: &Database) -> Result<Summary, String> {
+        let summary = self.summary();
+
+        database.apply_assets(
+            self.create.into_iter().map(new_asset).collect(),
+            self.update
+                .into_iter()
+                .map(|(id, spec)| (id, new_asset(spec)))
+                .collect(),
+            self.delete.into_iter().map(|(id, _)| id).collect(),
+        )?;
+
+        Ok(summary)
+    }
+}
+
+fn new_asset(spec: AssetSpec) -> models::NewAsset {
+    models::NewAsset::with(
+        spec.name,
+        spec.kind,
+        FORMAT.into(),
+        spec.components,
+        spec.labels,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::{Manifest, Plan};
+    use crate::db::models;
+
+    fn asset(id: i32, name: &str, site: &str) -> models::Asset {
+        let mut labels = models::Labels::new();
+        labels.insert("site".into(), site.into());
+
+        models::Asset {
+            id,
+            created_at: Utc::now(),
+            updated_at: None,
+            name: name.into(),
+            kind: "firmware".into(),
+            format: "products".into(),
+            components: diesel_json::Json::new(vec![]),
+            labels: diesel_json::Json::new(labels),
+            deleted_at: None,
+        }
+    }
+
+    const MANIFEST: &str = r#"
+assets:
+  - name: gateway
+    kind: firmware
+    labels:
+      site: berlin
+  - name: router
+    kind: firmware
+    labels:
+      site: munich
+  - name: camera
+    kind: device
+    components:
+      - product: busybox
+        version: 1.33.1
+"#;
+
+    #[test]
+    fn can_round_trip_yaml() {
+        let manifest =
+            Manifest::with(&[asset(2, "router", "munich"), asset(1, "gateway", "berlin")]);
+        assert_eq!("gateway", manifest.assets[0].name);
+
+        let yaml = manifest.to_yaml().unwrap();
+        assert_eq!(manifest, Manifest::parse(yaml.as_bytes()).unwrap());
+    }
+
+    #[test]
+    fn can_version_manifests() {
+        let manifest =
+            Manifest::with(&[asset(1, "gateway", "berlin"), asset(2, "router", "munich")]);
+        let version = manifest.version().unwrap();
+        assert_eq!(64, version.len());
+
+        let mut reordered = manifest.clone();
+        reordered.assets.reverse();
+        assert_eq!(version, reordered.version().unwrap());
+
+        let changed =
+            Manifest::with(&[asset(1, "gateway", "berlin"), asset(2, "router", "berlin")]);
+        assert_ne!(version, changed.version().unwrap());
+    }
+
+    #[test]
+    fn can_plan_changes() {
+        let assets = vec![
+            asset(1, "gateway", "berlin"),
+            asset(2, "router", "berlin"),
+            asset(3, "switch", "berlin"),
+        ];
+
+        let summary = Plan::new(
+            Manifest::parse(MANIFEST.as_bytes()).unwrap(),
+            &assets,
+            false,
+        )
+        .unwrap()
+        .summary();
+        assert_eq!(vec!["camera"], summary.created);
+        assert_eq!(vec!["router"], summary.updated);
+        assert_eq!(vec!["gateway"], summary.unchanged);
+        assert!(summary.deleted.is_empty());
+
+        let plan = Plan::new(Manifest::parse(MANIFEST.as_bytes()).unwrap(), &assets, true).unwrap();
+        assert_eq!(vec![(3, "switch".to_string())], plan.delete);
+    }
+
+    #[test]
+    fn rejects_ambiguous_manifests() {
+        let manifest = Manifest::parse(MANIFEST.as_bytes()).unwrap();
+
+        let twice = vec![asset(1, "gateway", "berlin"), asset(2, "gateway", "munich")];
+        assert!(Plan::new(manifest.clone(), &twice, false).is_err());
+
+        let mut duplicated = manifest.clone();
+        duplicated.assets.push(manifest.assets[0].clone());
+        assert!(Plan::new(duplicated, &[], false).is_err());
+
+        let mut invalid = manifest;
+        invalid.assets[0].kind = "server".into();
+        assert!(Plan::new(invalid, &[], false).is_err());
+    }
+}
@@ -0,0 +1,302 @@
+use std::collections::{BTreeMap, HashSet};
+
+use chrono::{DateTime, NaiveDate, Utc};
+use log::{info, warn};
+use serde::Serialize;
+
+use crate::db::{models, Database, Pool};
+use crate::notify::{Event, Notifier};
+use crate::search::{self, dsl::severity_rank, Query};
+
+/// Components of the asset whose product got new records in the delta, the only ones that
+/// need to be queried again.
+fn affected(components: &[Query], delta: &[models::CVE]) -> Vec<Query> {
+    let products: HashSet<&str> = delta.iter().map(|cve| cve.product.as_str()).collect();
+
+    components
+        .iter()
+        .filter(|query| products.contains(query.product.as_str()))
+        .cloned()
+        .collect()
+}
+
+/// Re-evaluates the stored assets against the records imported since the given time, and
+/// records the CVEs that became applicable to each of them. The CVEs that were awaiting
+/// analysis when first imported are notified, as no search could have found them before.
+/// Returns the number of new findings.
+pub fn run(pool: &Pool, since: DateTime<Utc>, notifier: Option<&Notifier>) -> Result<u32, String> {
+    let database = Database(pool.get().map_err(|e| e.to_string())?);
+
+    let delta = database.get_cves_since(since)?;
+    if delta.is_empty() {
+        return Ok(0);
+    }
+
+    let keys: HashSet<(&str, &str, &str)> = delta
+        .iter()
+        .map(|cve| (cve.cve.as_str(), cve.vendor.as_str(), cve.product.as_str()))
+        .collect();
+
+    let reconciled: HashSet<String> = database
+        .get_reconciled_cves_since(since)?
+        .into_iter()
+        .collect();
+
+    let assets = database.get_assets()?;
+    info!(
+        "re-evaluating {} assets against {} new records ...",
+        assets.len(),
+        delta.len()
+    );
+
+    let mut num_findings = 0;
+    for asset in assets {
+        let queries = affected(&asset.components.0, &delta);
+        if queries.is_empty() {
+            continue;
+        }
+
+        let mut found = vec![];
+        for query in &queries {
+            // one component that can't be searched doesn't hold back the others
+            let matches = match search::query(&database, query) {
+                Ok(matches) => matches,
+                Err(err) => {
+                    warn!(
+                        "asset {} ({}): skipping {}: {}",
+                        asset.id, asset.name, query.product, err
+                    );
+                    continue;
+                }
+            };
+            for cve in matches {
+                let key = (cve.cve.as_str(), cve.vendor.as_str(), cve.product.as_str());
+                if keys.contains(&key)
+                    && database
+                        .create_finding_if_not_exist(models::NewFinding::with(asset.id, &cve))?
+                {
+                    found.push(cve.cve);
+                }
+            }
+        }
+
+        if !found.is_empty() {
+            info!(
+                "asset {} ({}): {} new applicable CVEs: {}",
+                asset.id,
+                asset.name,
+                found.len(),
+                found.join(", ")
+            );
+            num_findings += found.len() as u32;
+
+            let mut cves: Vec<String> = found
+                .into_iter()
+                .filter(|cve| reconciled.contains(cve))
+                .collect();
+            cves.sort();
+            cves.dedup();
+            if let (Some(notifier), false) = (notifier, cves.is_empty()) {
+                notifier.notify(&Event::PendingCvesReconciled {
+                    asset_id: asset.id,
+                    asset_name: asset.name.clone(),
+                    cves,
+                });
+            }
+        }
+    }
+
+    Ok(num_findings)
+}
+
+/// Number of findings recorded in a day.
+#[derive(Debug, Serialize, PartialEq)]
+pub struct TrendPoint {
+    pub date: NaiveDate,
+    pub new_findings: usize,
+}
+
+/// Security posture of a group of assets sharing the same value of a label, `group` is
+/// `None` for the assets without the label.
+#[derive(Debug, Serialize, PartialEq)]
+pub struct Rollup {
+    pub group: Option<String>,
+    pub assets: usize,
+    pub open_findings: usize,
+    pub worst_severity: Option<String>,
+    pub trend: Vec<TrendPoint>,
+}
+
+fn date(time: DateTime<Utc>) -> NaiveDate {
+    time.naive_utc().date()
+}
+
+/// A finding is open as long as the asset still contains the affected product.
+fn is_open(asset: &models::Asset, finding: &models::Finding) -> bool {
+    asset
+        .components
+        .0
+        .iter()
+        .any(|query| query.product == finding.product)
+}
+
+/// Aggregates the findings of the assets grouped by the given label, with the daily number
+/// of new findings of the last `days` days up to `today`.
+pub fn rollup(
+    assets: &[models::Asset],
+    findings: &[models::Finding],
+    label: &str,
+    today: NaiveDate,
+    days: u32,
+) -> Vec<Rollup> {
+    let mut groups: BTreeMap<Option<&str>, Vec<&models::Asset>> = BTreeMap::new();
+    for asset in assets {
+        groups
+            .entry(asset.labels.0.get(label).map(String::as_str))
+            .or_default()
+            .push(asset);
+    }
+
+    groups
+        .into_iter()
+        .map(|(group, members)| {
+            let mut open_findings = 0;
+            let mut worst_severity: Option<&str> = None;
+            let mut daily: BTreeMap<NaiveDate, usize> = BTreeMap::new();
+
+            for asset in &members {
+                for finding in findings.iter().filter(|f| f.asset_id == asset.id) {
+                    *daily.entry(date(finding.created_at)).or_default() += 1;
+
+                    if is_open(asset, finding) {
+                        open_findings += 1;
+                        if worst_severity.and_then(severity_rank) < severity_rank(&finding.severity)
+                        {
+                            worst_severity = Some(&finding.severity);
+                        }
+                    }
+                }
+            }
+
+            let trend = (0..days)
+                .rev()
+                .map(|ago| today - chrono::Duration::days(ago as i64))
+                .map(|date| TrendPoint {
+                    date,
+                    new_findings: daily.get(&date).copied().unwrap_or_default(),
+                })
+                .collect();
+
+            Rollup {
+                group: group.map(str::to_string),
+                assets: members.len(),
+                open_findings,
+                worst_severity: worst_severity.map(str::to_string),
+                trend,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, Utc};
+
+    use super::{affected, date, rollup};
+    use crate::db::models;
+    use crate::search::{testing::cve, Query};
+
+    fn query(product: &str) -> Query {
+        Query {
+            vendor: None,
+            product: product.into(),
+            version: Some("1.0".into()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn only_components_in_the_delta_are_affected() {
+        let components = vec![query("busybox"), query("openssl"), query("zlib")];
+        let delta = vec![
+            cve("CVE-2022-0001", "openssl"),
+            cve("CVE-2022-0002", "curl"),
+        ];
+
+        assert_eq!(vec![query("openssl")], affected(&components, &delta));
+        assert!(affected(&components, &[]).is_empty());
+    }
+
+    fn asset(id: i32, site: Option<&str>, products: &[&str]) -> models::Asset {
+        let mut labels = models::Labels::new();
+        if let Some(site) = site {
+            labels.insert("site".into(), site.into());
+        }
+
+        models::Asset {
+            id,
+            created_at: Utc::now(),
+            updated_at: None,
+            name: format!("asset-{}", id),
+            kind: "device".into(),
+            format: "products".into(),
+            components: diesel_json::Json::new(products.iter().map(|p| query(p)).collect()),
+            labels: diesel_json::Json::new(labels),
+            deleted_at: None,
+        }
+    }
+
+    fn finding(asset_id: i32, product: &str, severity: &str, days_ago: i64) -> models::Finding {
+        models::Finding {
+            id: 0,
+            created_at: Utc::now() - Duration::days(days_ago),
+            asset_id,
+            source: "NIST".into(),
+            vendor: "vendor".into(),
+            product: product.into(),
+            cve: "CVE-2022-0001".into(),
+            score: 7.5,
+            severity: severity.into(),
+        }
+    }
+
+    #[test]
+    fn can_rollup_findings_by_label() {
+        let assets = vec![
+            asset(1, Some("berlin"), &["openssl", "busybox"]),
+            asset(2, Some("berlin"), &["busybox"]),
+            asset(3, None, &["zlib"]),
+        ];
+        let findings = vec![
+            finding(1, "openssl", "HIGH", 0),
+            finding(1, "busybox", "MEDIUM", 1),
+            finding(2, "busybox", "MEDIUM", 1),
+            // no longer open, the component was removed
+            finding(2, "openssl", "CRITICAL", 2),
+        ];
+        let today = date(Utc::now());
+
+        let rollups = rollup(&assets, &findings, "site", today, 3);
+
+        assert_eq!(2, rollups.len());
+
+        assert_eq!(None, rollups[0].group);
+        assert_eq!(1, rollups[0].assets);
+        assert_eq!(0, rollups[0].open_findings);
+        assert_eq!(None, rollups[0].worst_severity);
+
+        assert_eq!(Some("berlin".to_owned()), rollups[1].group);
+        assert_eq!(2, rollups[1].assets);
+        assert_eq!(3, rollups[1].open_findings);
+        assert_eq!(Some("HIGH".to_owned()), rollups[1].worst_severity);
+        assert_eq!(
+            vec![1, 2, 1],
+            rollups[1]
+                .trend
+                .iter()
+                .map(|point| point.new_findings)
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(today, rollups[1].trend[2].date);
+    }
+}
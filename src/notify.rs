@@ -0,0 +1,159 @@
+use std::env;
+use std::time::Duration;
+
+use log::{info, warn};
+use serde::Serialize;
+
+use crate::utils::ImportError;
+
+/// Event notified to the webhook.
+#[derive(Debug, Serialize, PartialEq)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum Event {
+    /// CVEs imported while awaiting analysis that, once their configurations became
+    /// available, turned out to affect an asset.
+    PendingCvesReconciled {
+        asset_id: i32,
+        asset_name: String,
+        cves: Vec<String>,
+    },
+    /// The newest record of a source is older than the freshness SLO, so the imports are
+    /// likely failing.
+    DatasetStale {
+        source: String,
+        latest_record: Option<String>,
+        slo_hours: u64,
+    },
+    /// An import failed, with the diagnostics of the download or validation failures.
+    ImportFailed {
+        import: String,
+        #[serde(flatten)]
+        failure: ImportError,
+    },
+    /// A panic, posted to `KEPLER_CRASH_REPORT_URL` rather than to the webhook.
+    Crashed(crate::crash::Report),
+}
+
+impl Event {
+    /// Failure of the import, with its diagnostics when it failed downloading or validating
+    /// its data.
+    pub fn import_failed(import: &str, err: &anyhow::Error) -> Self {
+        Self::ImportFailed {
+            import: import.to_string(),
+            failure: match err.downcast_ref::<ImportError>() {
+                Some(failure) => failure.clone(),
+                None => ImportError::from(format!("{:#}", err)),
+            },
+        }
+    }
+}
+
+/// Webhook receiving the events as JSON.
+#[derive(Debug, Clone)]
+pub struct Notifier {
+    url: String,
+}
+
+impl Notifier {
+    pub fn new(url: &str) -> Self {
+        Self {
+            url: url.to_string(),
+        }
+    }
+
+    /// Webhook pointed by `KEPLER_WEBHOOK_URL`, if set.
+    pub fn from_env() -> Option<Self> {
+        env::var("KEPLER_WEBHOOK_URL").ok().map(|url| {
+            info!("notifying events to {}", url);
+            Self::new(&url)
+        })
+    }
+
+    pub fn send(&self, event: &Event) -> Result<(), String> {
+        let body = serde_json::to_string(event)
+            .map_err(|e| format!("could not serialize event: {}", e))?;
+
+        let client = reqwest::blocking::Client::builder()
+            .timeout(Some(Duration::from_secs(30)))
+            .build()
+            .map_err(|e| format!("could not create http client: {}", e))?;
+
+        let res = client
+            .post(&self.url)
+            .header("Content-Type", "application/json")
+            .body(body)
+            .send()
+            .map_err(|e| format!("error notifying {}: {}", self.url, e))?;
+
+        if !res.status().is_success() {
+            return Err(format!("{} refused the event ({})", self.url, res.status()));
+        }
+
+        Ok(())
+    }
+
+    /// Sends the event, only logging failures as notifications are best effort.
+    pub fn notify(&self, event: &Event) {
+        if let Err(e) = self.send(event) {
+            warn!("{}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Event;
+    use crate::utils::ImportError;
+
+    #[test]
+    fn can_serialize_events() {
+        let event = Event::PendingCvesReconciled {
+            asset_id: 1,
+            asset_name: "router".into(),
+            cves: vec!["CVE-2022-0001".into()],
+        };
+
+        assert_eq!(
+            r#"{"event":"pending_cves_reconciled","asset_id":1,"asset_name":"router","cves":["CVE-2022-0001"]}"#,
+            serde_json::to_string(&event).unwrap()
+        );
+    }
+
+    #[test]
+    fn can_serialize_import_failures() {
+        let err = anyhow::Error::new(ImportError {
+            error: "error downloading https://nvd.nist.gov/feed.gz: 503".into(),
+            url: Some("https://nvd.nist.gov/feed.gz".into()),
+            status: Some(503),
+            ..Default::default()
+        });
+        let json = serde_json::to_value(Event::import_failed("import_nist", &err)).unwrap();
+        assert_eq!("import_failed", json["event"]);
+        assert_eq!("import_nist", json["import"]);
+        assert_eq!(503, json["status"]);
+        assert_eq!("https://nvd.nist.gov/feed.gz", json["url"]);
+
+        let err = anyhow::anyhow!("database is down");
+        let json = serde_json::to_value(Event::import_failed("import_npm", &err)).unwrap();
+        assert_eq!("database is down", json["error"]);
+        assert!(json["status"].is_null());
+    }
+
+    #[test]
+    fn can_serialize_crash_reports() {
+        let event = Event::Crashed(crate::crash::Report {
+            id: "6f1c1b8e-6b1a-4d3e-9b8f-0c2d4e6f8a0b".into(),
+            time: "2022-07-06T10:00:00Z".into(),
+            version: "1.0.0".into(),
+            config_hash: "9f86d081884c7d65".into(),
+            thread: "actix-rt|system:0|arbiter:0".into(),
+            message: "index out of bounds".into(),
+            location: Some("src/scan/mod.rs:42".into()),
+            backtrace: String::new(),
+        });
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!("crashed", json["event"]);
+        assert_eq!("index out of bounds", json["message"]);
+        assert_eq!("9f86d081884c7d65", json["config_hash"]);
+    }
+}
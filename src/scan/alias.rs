@@ -0,0 +1,242 @@
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+
+use lazy_static::lazy_static;
+use log::{info, warn};
+use serde::Deserialize;
+
+/// CPE vendor and product a build system package name maps to.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Alias {
+    pub vendor: Option<String>,
+    pub product: String,
+}
+
+/// Package names used by embedded build systems (Yocto, Buildroot and OpenWrt) that differ
+/// from their CPE product.
+const BUILTIN: &[(&str, &str, &str)] = &[
+    ("bind", "isc", "bind"),
+    ("bluez5", "bluez", "bluez"),
+    ("busybox", "busybox", "busybox"),
+    ("curl", "haxx", "curl"),
+    ("dbus", "freedesktop", "dbus"),
+    ("dnsmasq", "thekelleys", "dnsmasq"),
+    ("dropbear", "dropbear_ssh_project", "dropbear_ssh"),
+    ("expat", "libexpat_project", "libexpat"),
+    ("glib-2.0", "gnome", "glib"),
+    ("glibc", "gnu", "glibc"),
+    ("gnutls", "gnu", "gnutls"),
+    ("hostapd", "w1.fi", "hostapd"),
+    ("kernel", "linux", "linux_kernel"),
+    ("libcurl", "haxx", "curl"),
+    ("libglib2", "gnome", "glib"),
+    ("libopenssl", "openssl", "openssl"),
+    ("libpcap", "tcpdump", "libpcap"),
+    ("libpng", "libpng", "libpng"),
+    ("libxml2", "xmlsoft", "libxml2"),
+    ("linux", "linux", "linux_kernel"),
+    ("linux-yocto", "linux", "linux_kernel"),
+    ("linux-headers", "linux", "linux_kernel"),
+    ("linux-libc-headers", "linux", "linux_kernel"),
+    ("lighttpd", "lighttpd", "lighttpd"),
+    ("mbedtls", "arm", "mbed_tls"),
+    ("ncurses", "gnu", "ncurses"),
+    ("openssh", "openbsd", "openssh"),
+    ("openssl", "openssl", "openssl"),
+    ("openvpn", "openvpn", "openvpn"),
+    ("python3", "python", "python"),
+    ("sqlite3", "sqlite", "sqlite"),
+    ("sudo", "sudo_project", "sudo"),
+    ("systemd", "systemd_project", "systemd"),
+    ("tcpdump", "tcpdump", "tcpdump"),
+    ("u-boot", "denx", "u-boot"),
+    ("uboot", "denx", "u-boot"),
+    ("util-linux", "kernel", "util-linux"),
+    ("wpa-supplicant", "w1.fi", "wpa_supplicant"),
+    ("wpad", "w1.fi", "hostapd"),
+    ("wpad-basic", "w1.fi", "hostapd"),
+    ("wpad-basic-wolfssl", "w1.fi", "hostapd"),
+    ("xz", "tukaani", "xz"),
+    ("zlib", "zlib", "zlib"),
+];
+
+/// Maven `group:artifact` coordinates whose CPE vendor and product can't be guessed from the
+/// group and the artifact.
+const MAVEN: &[(&str, &str, &str)] = &[
+    ("org.apache.logging.log4j:log4j-api", "apache", "log4j"),
+    ("org.apache.logging.log4j:log4j-core", "apache", "log4j"),
+    ("org.apache.struts:struts2-core", "apache", "struts"),
+    (
+        "org.apache.tomcat.embed:tomcat-embed-core",
+        "apache",
+        "tomcat",
+    ),
+    (
+        "org.springframework:spring-core",
+        "vmware",
+        "spring_framework",
+    ),
+    (
+        "org.springframework:spring-webmvc",
+        "vmware",
+        "spring_framework",
+    ),
+    ("org.yaml:snakeyaml", "snakeyaml_project", "snakeyaml"),
+];
+
+lazy_static! {
+    static ref ALIASES: HashMap<String, Alias> = load();
+}
+
+/// Loads the builtin aliases of the build systems and of Maven, extended or overridden by the JSON or YAML file pointed by the
+/// `KEPLER_ALIASES` environment variable if present.
+fn load() -> HashMap<String, Alias> {
+    let mut aliases: HashMap<String, Alias> = BUILTIN
+        .iter()
+        .chain(MAVEN)
+        .map(|(name, vendor, product)| {
+            (
+                name.to_string(),
+                Alias {
+                    vendor: Some(vendor.to_string()),
+                    product: product.to_string(),
+                },
+            )
+        })
+        .collect();
+
+    match load_custom() {
+        Ok(custom) => {
+            if !custom.is_empty() {
+                info!("loaded {} custom product aliases", custom.len());
+            }
+            aliases.extend(custom);
+        }
+        Err(e) => warn!("{}", e),
+    }
+
+    aliases
+}
+
+/// Loads the aliases of the JSON or YAML file pointed by the `KEPLER_ALIASES` environment
+/// variable, none when it's not set.
+pub fn load_custom() -> Result<HashMap<String, Alias>, String> {
+    match env::var("KEPLER_ALIASES") {
+        Ok(file_name) => fs::read_to_string(&file_name)
+            .map_err(|e| e.to_string())
+            .and_then(|yaml| {
+                // YAML being a superset of JSON, both are parsed alike
+                serde_yaml::from_str::<HashMap<String, Alias>>(&yaml).map_err(|e| e.to_string())
+            })
+            .map_err(|e| format!("could not load product aliases from {}: {}", file_name, e)),
+        Err(_) => Ok(HashMap::new()),
+    }
+}
+
+/// Strips the build system specific decorations from a package name, for instance the
+/// `-native` suffix of the Yocto host tools or the `lib32-` multilib prefix.
+fn normalize_name(name: &str) -> &str {
+    let name = name.strip_prefix("lib32-").unwrap_or(name);
+    let name = name.strip_prefix("lib64-").unwrap_or(name);
+    let name = name.strip_prefix("nativesdk-").unwrap_or(name);
+    name.strip_suffix("-native").unwrap_or(name)
+}
+
+/// Strips revisions and source control suffixes from a package version, for instance
+/// `5.10.78+gitAUTOINC+1234-r0` becomes `5.10.78`.
+pub fn normalize_version(version: &str) -> &str {
+    let version = version.split('+').next().unwrap_or(version);
+    match version.rfind("-r") {
+        Some(index) if version[index + 2..].chars().all(|c| c.is_ascii_digit()) => {
+            &version[..index]
+        }
+        _ => version,
+    }
+}
+
+/// Strips the ABI version distributions append to library package names, for instance
+/// `libopenssl1.1` becomes `libopenssl`.
+fn strip_abi_version(name: &str) -> &str {
+    name.trim_end_matches(|c: char| c.is_ascii_digit() || c == '.')
+}
+
+/// Returns the CPE vendor and product for a package name, falling back to the package name
+/// itself as product when no alias is known.
+pub fn resolve(name: &str) -> Alias {
+    let name = normalize_name(name);
+    let alias = ALIASES.get(name).or_else(|| {
+        if name.starts_with("lib") {
+            ALIASES.get(strip_abi_version(name))
+        } else {
+            None
+        }
+    });
+
+    match alias {
+        Some(alias) => alias.clone(),
+        None => Alias {
+            vendor: None,
+            product: name.to_string(),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{normalize_version, resolve, Alias};
+
+    #[test]
+    fn can_resolve_aliases() {
+        assert_eq!(
+            Alias {
+                vendor: Some("linux".into()),
+                product: "linux_kernel".into()
+            },
+            resolve("linux-yocto")
+        );
+        assert_eq!(
+            Alias {
+                vendor: Some("openssl".into()),
+                product: "openssl".into()
+            },
+            resolve("openssl-native")
+        );
+        assert_eq!(
+            Alias {
+                vendor: None,
+                product: "unknown".into()
+            },
+            resolve("lib32-unknown")
+        );
+        assert_eq!(
+            Alias {
+                vendor: Some("openssl".into()),
+                product: "openssl".into()
+            },
+            resolve("libopenssl1.1")
+        );
+        assert_eq!(
+            Alias {
+                vendor: Some("xmlsoft".into()),
+                product: "libxml2".into()
+            },
+            resolve("libxml2")
+        );
+        assert_eq!(
+            Alias {
+                vendor: Some("apache".into()),
+                product: "log4j".into()
+            },
+            resolve("org.apache.logging.log4j:log4j-core")
+        );
+    }
+
+    #[test]
+    fn can_normalize_versions() {
+        assert_eq!("5.10.78", normalize_version("5.10.78+gitAUTOINC+1234-r0"));
+        assert_eq!("1.1.1k", normalize_version("1.1.1k-r0"));
+        assert_eq!("1.2.3-rc1", normalize_version("1.2.3-rc1"));
+        assert_eq!("1.33.1", normalize_version("1.33.1"));
+    }
+}
@@ -0,0 +1,136 @@
+use super::{alias, Components};
+use crate::search::Query;
+
+/// Splits a line of the CSV files generated by buildroot, where every field is quoted.
+fn split_fields(line: &str) -> Vec<String> {
+    let mut fields = vec![];
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                // escaped quote
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(std::mem::take(&mut field)),
+            _ => field.push(c),
+        }
+    }
+    fields.push(field);
+
+    fields
+}
+
+/// Parses the `manifest.csv` generated by `make legal-info`.
+pub fn parse(input: &str) -> Result<Components, String> {
+    let mut lines = input.lines().filter(|l| !l.trim().is_empty());
+
+    let header = split_fields(lines.next().ok_or("empty buildroot manifest")?);
+    let column = |name: &str| {
+        header
+            .iter()
+            .position(|h| h.trim() == name)
+            .ok_or_else(|| format!("missing {} column in buildroot manifest", name))
+    };
+    let package_column = column("PACKAGE")?;
+    let version_column = column("VERSION")?;
+    let license_column = column("LICENSE").ok();
+
+    let mut components = Components::default();
+    for line in lines {
+        let fields = split_fields(line);
+        let (package, version) = match (fields.get(package_column), fields.get(version_column)) {
+            (Some(package), Some(version)) if !version.is_empty() => (package, version),
+            (Some(package), _) => {
+                components.warn(format!("skipping {}: missing version", package));
+                continue;
+            }
+            _ => {
+                components.warn(format!("skipping invalid manifest line '{}'", line));
+                continue;
+            }
+        };
+
+        let alias = alias::resolve(package);
+        let query = Query {
+            vendor: alias.vendor,
+            product: alias.product,
+            version: Some(alias::normalize_version(version).to_string()),
+            ..Default::default()
+        };
+        if let Some(license) = license_column.and_then(|column| fields.get(column)) {
+            components.license(&query, license);
+        }
+        components.push(query);
+    }
+
+    Ok(components)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse, split_fields};
+    use crate::search::Query;
+
+    #[test]
+    fn can_split_quoted_fields() {
+        assert_eq!(
+            vec!["a", "b, c", "d \"e\"", ""],
+            split_fields(r#""a","b, c","d ""e""","""#)
+        );
+    }
+
+    #[test]
+    fn can_parse_legal_info_manifest() {
+        let manifest = r#""PACKAGE","VERSION","LICENSE","LICENSE FILES","SOURCE ARCHIVE","SOURCE SITE","DEPENDENCIES WITH LICENSES"
+"busybox","1.33.1","GPL-2.0, bzip2-1.0.4","LICENSE archival/libarchive/bz/LICENSE","busybox-1.33.1.tar.bz2","https://www.busybox.net/downloads","skeleton-init-sysv: [unknown]"
+"libopenssl","1.1.1k","OpenSSL or SSLeay","LICENSE","openssl-1.1.1k.tar.gz","https://www.openssl.org/source","zlib: [Zlib]"
+"skeleton-init-sysv","","unknown","","","",""
+"#;
+
+        let components = parse(manifest).unwrap();
+
+        assert_eq!(
+            vec![
+                Query {
+                    vendor: Some("busybox".into()),
+                    product: "busybox".into(),
+                    version: Some("1.33.1".into()),
+                    ..Default::default()
+                },
+                Query {
+                    vendor: Some("openssl".into()),
+                    product: "openssl".into(),
+                    version: Some("1.1.1k".into()),
+                    ..Default::default()
+                },
+            ],
+            components.queries
+        );
+        assert_eq!(
+            vec!["skipping skeleton-init-sysv: missing version".to_owned()],
+            components.warnings
+        );
+        assert_eq!(
+            vec![
+                ("busybox", "GPL-2.0, bzip2-1.0.4"),
+                ("openssl", "OpenSSL or SSLeay")
+            ],
+            components
+                .licenses
+                .iter()
+                .map(|l| (l.product.as_str(), l.license.as_str()))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn can_detect_invalid_manifests() {
+        assert!(parse("").is_err());
+        assert!(parse("\"NAME\",\"LICENSE\"").is_err());
+    }
+}
@@ -0,0 +1,70 @@
+use super::{ecosystem_product, lock_packages, Components};
+use crate::search::Query;
+
+/// Parses a `Cargo.lock` file, workspace members (packages without a source) are skipped.
+pub fn parse(input: &str) -> Result<Components, String> {
+    let packages = lock_packages(input);
+    if packages.is_empty() {
+        return Err("no packages found in Cargo.lock".into());
+    }
+
+    let mut components = Components::default();
+    for package in &packages {
+        if let (Some(name), Some(version), Some(_)) = (
+            package.get("name"),
+            package.get("version"),
+            package.get("source"),
+        ) {
+            components.push(Query {
+                vendor: None,
+                product: ecosystem_product("rust", name),
+                version: Some(version.clone()),
+                ..Default::default()
+            });
+        }
+    }
+
+    Ok(components)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse;
+    use crate::search::Query;
+
+    #[test]
+    fn can_parse_cargo_lock() {
+        let lock = r#"# This file is automatically @generated by Cargo.
+# It is not intended for manual editing.
+version = 3
+
+[[package]]
+name = "kepler"
+version = "1.0.0"
+dependencies = [
+ "regex",
+]
+
+[[package]]
+name = "regex"
+version = "1.5.5"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+checksum = "1a11647b6b25ff05a515cb92c365cec08801e83423a235b51e231e1808747286"
+"#;
+
+        assert_eq!(
+            vec![Query {
+                vendor: None,
+                product: "rust-regex".into(),
+                version: Some("1.5.5".into()),
+                ..Default::default()
+            }],
+            parse(lock).unwrap().queries
+        );
+    }
+
+    #[test]
+    fn can_detect_invalid_lock_files() {
+        assert!(parse("{}").is_err());
+    }
+}
@@ -0,0 +1,171 @@
+use serde::Deserialize;
+
+use super::{cpe_query, purl_query, Components};
+
+#[derive(Debug, Deserialize)]
+pub struct LicenseId {
+    pub id: Option<String>,
+    pub name: Option<String>,
+}
+
+/// A license is either an SPDX identifier, a free form name or an SPDX expression.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum LicenseChoice {
+    License { license: LicenseId },
+    Expression { expression: String },
+}
+
+impl LicenseChoice {
+    fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::License { license } => license.id.as_deref().or(license.name.as_deref()),
+            Self::Expression { expression } => Some(expression),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Component {
+    pub name: String,
+    pub version: Option<String>,
+    pub cpe: Option<String>,
+    pub purl: Option<String>,
+    #[serde(default)]
+    pub licenses: Vec<LicenseChoice>,
+    #[serde(default)]
+    pub components: Vec<Component>,
+}
+
+impl Component {
+    fn license(&self) -> String {
+        self.licenses
+            .iter()
+            .filter_map(LicenseChoice::as_str)
+            .collect::<Vec<_>>()
+            .join(" AND ")
+    }
+}
+
+/// CycloneDX BOM in its JSON representation.
+#[derive(Debug, Deserialize)]
+pub struct Bom {
+    #[serde(default)]
+    pub components: Vec<Component>,
+}
+
+fn collect(components: &mut Components, list: &[Component]) {
+    for component in list {
+        match component.version.as_deref() {
+            None | Some("") => {
+                components.warn(format!("skipping {}: missing version", component.name))
+            }
+            Some(version) => {
+                let query = component
+                    .cpe
+                    .as_deref()
+                    .and_then(|cpe| cpe_query(cpe, version))
+                    .unwrap_or_else(|| {
+                        purl_query(
+                            component.purl.as_deref().unwrap_or_default(),
+                            &component.name,
+                            version,
+                        )
+                    });
+                components.license(&query, &component.license());
+                components.push(query);
+            }
+        }
+        // assemblies can nest their own components
+        collect(components, &component.components);
+    }
+}
+
+pub fn parse(input: &str) -> Result<Components, String> {
+    let bom: Bom = serde_json::from_str(input)
+        .map_err(|e| format!("could not parse CycloneDX document: {}", e))?;
+
+    let mut components = Components::default();
+    collect(&mut components, &bom.components);
+
+    Ok(components)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse;
+    use crate::search::Query;
+
+    #[test]
+    fn can_parse_cyclonedx_documents() {
+        let bom = r#"{
+            "bomFormat": "CycloneDX",
+            "specVersion": "1.4",
+            "components": [
+                {
+                    "type": "library",
+                    "name": "libxml2",
+                    "version": "2.9.10",
+                    "cpe": "cpe:2.3:a:xmlsoft:libxml2:2.9.10:*:*:*:*:*:*:*",
+                    "licenses": [{"license": {"id": "MIT"}}]
+                },
+                {
+                    "type": "library",
+                    "name": "lodash",
+                    "version": "4.17.20",
+                    "purl": "pkg:npm/lodash@4.17.20",
+                    "licenses": [{"expression": "MIT OR CC0-1.0"}]
+                },
+                {
+                    "type": "firmware",
+                    "name": "firmware",
+                    "components": [
+                        {
+                            "type": "library",
+                            "name": "busybox",
+                            "version": "1.33.1"
+                        }
+                    ]
+                }
+            ]
+        }"#;
+
+        let components = parse(bom).unwrap();
+
+        assert_eq!(
+            vec![
+                Query {
+                    vendor: Some("xmlsoft".into()),
+                    product: "libxml2".into(),
+                    version: Some("2.9.10".into()),
+                    ..Default::default()
+                },
+                Query {
+                    vendor: None,
+                    product: "node-lodash".into(),
+                    version: Some("4.17.20".into()),
+                    ..Default::default()
+                },
+                Query {
+                    vendor: Some("busybox".into()),
+                    product: "busybox".into(),
+                    version: Some("1.33.1".into()),
+                    ..Default::default()
+                },
+            ],
+            components.queries
+        );
+        assert_eq!(
+            vec!["skipping firmware: missing version".to_owned()],
+            components.warnings
+        );
+        assert_eq!(
+            vec![("libxml2", "MIT"), ("node-lodash", "MIT OR CC0-1.0")],
+            components
+                .licenses
+                .iter()
+                .map(|l| (l.product.as_str(), l.license.as_str()))
+                .collect::<Vec<_>>()
+        );
+    }
+}
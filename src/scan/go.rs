@@ -0,0 +1,95 @@
+use super::{ecosystem_product, Components};
+use crate::search::Query;
+
+/// Go modules are identified by their path, the product is its last element, skipping the
+/// major version suffix (`github.com/go-redis/redis/v8` is the `redis` product).
+pub fn module_product(path: &str) -> &str {
+    let mut elements = path.rsplit('/');
+    let last = elements.next().unwrap_or(path);
+    let is_major_version =
+        last.len() > 1 && last.starts_with('v') && last[1..].chars().all(|c| c.is_ascii_digit());
+    if is_major_version {
+        elements.next().unwrap_or(last)
+    } else {
+        last
+    }
+}
+
+/// Parses a `go.sum` file, made of `module version hash` lines.
+pub fn parse(input: &str) -> Result<Components, String> {
+    let mut components = Components::default();
+
+    for line in input.lines().map(str::trim).filter(|l| !l.is_empty()) {
+        let mut fields = line.split_whitespace();
+        let (module, version) = match (fields.next(), fields.next(), fields.next()) {
+            (Some(module), Some(version), Some(_)) => (module, version),
+            _ => {
+                components.warn(format!("skipping invalid go.sum line '{}'", line));
+                continue;
+            }
+        };
+
+        let version = version.trim_end_matches("/go.mod");
+        let version = version.trim_end_matches("+incompatible");
+        let version = version.strip_prefix('v').unwrap_or(version);
+
+        components.push(Query {
+            vendor: None,
+            product: ecosystem_product("go", module_product(module)),
+            version: Some(version.to_string()),
+            ..Default::default()
+        });
+    }
+
+    Ok(components)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse;
+    use crate::search::Query;
+
+    #[test]
+    fn can_parse_go_sum() {
+        let sum = "github.com/gin-gonic/gin v1.7.0 h1:jGB9xAJQ12AIGNB4HguylppmDK1Am9ppF7XnGXXJuoU=
+github.com/gin-gonic/gin v1.7.0/go.mod h1:jD2toBW3GZUr5UMcdrwQA10I7RuaFOl/SGeDjXkfUtY=
+github.com/go-redis/redis/v8 v8.11.4 h1:kHoYkfZP6+pe04aFTnhDH6GDROa5yJdHJVNQV3F8ncQ=
+github.com/docker/docker v20.10.7+incompatible h1:Z6O9Nhsjv+ayUEeI1IojKbYcsGdgYSNqxe1s2MYzUhQ=
+";
+
+        assert_eq!(
+            vec![
+                Query {
+                    vendor: None,
+                    product: "go-gin".into(),
+                    version: Some("1.7.0".into()),
+                    ..Default::default()
+                },
+                Query {
+                    vendor: None,
+                    product: "go-redis".into(),
+                    version: Some("8.11.4".into()),
+                    ..Default::default()
+                },
+                Query {
+                    vendor: None,
+                    product: "go-docker".into(),
+                    version: Some("20.10.7".into()),
+                    ..Default::default()
+                },
+            ],
+            parse(sum).unwrap().queries
+        );
+    }
+
+    #[test]
+    fn can_detect_invalid_lines() {
+        let components = parse("github.com/gin-gonic/gin").unwrap();
+
+        assert!(components.queries.is_empty());
+        assert_eq!(
+            vec!["skipping invalid go.sum line 'github.com/gin-gonic/gin'".to_owned()],
+            components.warnings
+        );
+    }
+}
@@ -0,0 +1,80 @@
+use std::env;
+
+use lazy_static::lazy_static;
+use log::info;
+
+use super::License;
+
+lazy_static! {
+    /// SPDX identifiers of the licenses flagged in the scan reports.
+    static ref DISALLOWED: Vec<String> = load();
+}
+
+fn load() -> Vec<String> {
+    let disallowed: Vec<String> = env::var("KEPLER_DISALLOWED_LICENSES")
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|id| !id.is_empty())
+        .map(str::to_string)
+        .collect();
+
+    if !disallowed.is_empty() {
+        info!("flagging licenses: {}", disallowed.join(", "));
+    }
+
+    disallowed
+}
+
+/// Values used by the SBOM formats when the license is unknown.
+pub fn is_unknown(license: &str) -> bool {
+    matches!(
+        license.trim().to_uppercase().as_str(),
+        "" | "NOASSERTION" | "NONE" | "UNKNOWN"
+    )
+}
+
+/// Whether the license expression mentions any of the disallowed licenses. Alternatives
+/// are not taken into account, so that `MIT OR GPL-3.0` is flagged too and gets reviewed.
+pub fn is_disallowed(expression: &str, disallowed: &[String]) -> bool {
+    expression
+        .split(|c: char| c.is_whitespace() || "()&|,".contains(c))
+        .filter(|token| !token.is_empty())
+        .any(|token| disallowed.iter().any(|id| id.eq_ignore_ascii_case(token)))
+}
+
+/// Flags the licenses disallowed by the configuration.
+pub fn flag(licenses: &mut [License]) {
+    for license in licenses {
+        license.disallowed = is_disallowed(&license.license, &DISALLOWED);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_disallowed, is_unknown};
+
+    #[test]
+    fn can_flag_disallowed_licenses() {
+        let disallowed = vec!["GPL-3.0-only".to_owned(), "AGPL-3.0".to_owned()];
+
+        assert!(is_disallowed("GPL-3.0-only", &disallowed));
+        assert!(is_disallowed(
+            "MIT OR (agpl-3.0 AND BSD-3-Clause)",
+            &disallowed
+        ));
+        assert!(is_disallowed("GPLv2 & AGPL-3.0", &disallowed));
+        assert!(is_disallowed("GPL-3.0-only, Zlib", &disallowed));
+        assert!(!is_disallowed("GPL-3.0-or-later", &disallowed));
+        assert!(!is_disallowed("MIT", &disallowed));
+        assert!(!is_disallowed("MIT", &[]));
+    }
+
+    #[test]
+    fn can_detect_unknown_licenses() {
+        assert!(is_unknown("NOASSERTION"));
+        assert!(is_unknown(" "));
+        assert!(is_unknown("unknown"));
+        assert!(!is_unknown("MIT"));
+    }
+}
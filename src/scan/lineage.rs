@@ -0,0 +1,116 @@
+use std::cmp::Ordering;
+
+use serde::Serialize;
+use version_compare::Cmp;
+
+use crate::db::{models, Database};
+use crate::search;
+
+/// Releases of an asset affected by a CVE, in version order, and the first release that
+/// shipped the fix.
+#[derive(Debug, Serialize, PartialEq)]
+pub struct Lineage {
+    pub cve: String,
+    pub affected: Vec<String>,
+    pub fixed_in: Option<String>,
+}
+
+/// Orders the release versions, the ones that can't be compared keep their relative order.
+pub fn compare_versions(a: &str, b: &str) -> Ordering {
+    match version_compare::compare(a, b) {
+        Ok(Cmp::Lt) => Ordering::Less,
+        Ok(Cmp::Gt) => Ordering::Greater,
+        _ => Ordering::Equal,
+    }
+}
+
+/// Builds the lineage from the sorted versions of the releases, each one with whether it's
+/// affected. The fix is the first release following the last affected one, so that a CVE
+/// reintroduced by a later release is not reported as fixed.
+fn trace(cve: &str, releases: &[(String, bool)]) -> Lineage {
+    let affected: Vec<String> = releases
+        .iter()
+        .filter(|(_, affected)| *affected)
+        .map(|(version, _)| version.clone())
+        .collect();
+
+    let fixed_in = releases
+        .iter()
+        .rposition(|(_, affected)| *affected)
+        .and_then(|last| releases.get(last + 1))
+        .map(|(version, _)| version.clone());
+
+    Lineage {
+        cve: cve.to_string(),
+        affected,
+        fixed_in,
+    }
+}
+
+/// Matches the components of every release against the CVE.
+pub fn run(
+    db: &Database,
+    mut releases: Vec<models::Release>,
+    cve: &str,
+) -> Result<Lineage, String> {
+    releases.sort_by(|a, b| compare_versions(&a.version, &b.version));
+
+    let mut matched = vec![];
+    for release in releases {
+        let affected = search::query_all(db, &release.components.0)?
+            .iter()
+            .any(|(_, matches)| matches.iter().any(|m| m.cve == cve));
+        matched.push((release.version, affected));
+    }
+
+    Ok(trace(cve, &matched))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cmp::Ordering;
+
+    use super::{compare_versions, trace};
+
+    fn releases(table: &[(&str, bool)]) -> Vec<(String, bool)> {
+        table
+            .iter()
+            .map(|(version, affected)| (version.to_string(), *affected))
+            .collect()
+    }
+
+    #[test]
+    fn can_compare_versions() {
+        assert_eq!(Ordering::Less, compare_versions("1.2.0", "1.10.0"));
+        assert_eq!(Ordering::Greater, compare_versions("2.0", "1.9.9"));
+        assert_eq!(Ordering::Equal, compare_versions("1.0", "1.0"));
+    }
+
+    #[test]
+    fn can_trace_affected_releases() {
+        let lineage = trace(
+            "CVE-2022-0001",
+            &releases(&[
+                ("1.0", false),
+                ("1.1", true),
+                ("1.2", true),
+                ("1.3", false),
+                ("1.4", false),
+            ]),
+        );
+        assert_eq!(vec!["1.1".to_owned(), "1.2".to_owned()], lineage.affected);
+        assert_eq!(Some("1.3".to_owned()), lineage.fixed_in);
+
+        // reintroduced and not fixed yet
+        let lineage = trace(
+            "CVE-2022-0001",
+            &releases(&[("1.0", true), ("1.1", false), ("1.2", true)]),
+        );
+        assert_eq!(None, lineage.fixed_in);
+
+        // never affected
+        let lineage = trace("CVE-2022-0001", &releases(&[("1.0", false)]));
+        assert!(lineage.affected.is_empty());
+        assert_eq!(None, lineage.fixed_in);
+    }
+}
@@ -0,0 +1,136 @@
+use super::{alias, Components};
+use crate::search::Query;
+
+/// Maps Maven coordinates to a query, using the product aliases keyed by `group:artifact`
+/// when available and otherwise guessing the vendor from the reverse domain of the group.
+fn query(group: &str, artifact: &str, version: &str) -> Query {
+    let alias = alias::resolve(&format!("{}:{}", group, artifact));
+    let (vendor, product) = if alias.vendor.is_some() {
+        (alias.vendor, alias.product)
+    } else {
+        let mut elements = group.split('.');
+        let vendor = match (elements.next(), elements.next()) {
+            (Some("org" | "com" | "io" | "net"), Some(vendor)) => Some(vendor.to_string()),
+            _ => None,
+        };
+        (vendor, artifact.to_string())
+    };
+
+    Query {
+        vendor,
+        product,
+        version: Some(version.to_string()),
+        ..Default::default()
+    }
+}
+
+/// Parses the output of `mvn dependency:list`, where dependencies are listed as
+/// `group:artifact:type[:classifier]:version:scope`.
+pub fn parse_dependency_list(input: &str) -> Result<Components, String> {
+    let mut components = Components::default();
+
+    for line in input.lines() {
+        let line = line.trim_start_matches("[INFO]").trim();
+        // module information is appended by recent versions of the plugin
+        let line = line.split(" -- ").next().unwrap_or_default();
+        if line.contains(' ') {
+            continue;
+        }
+
+        let fields: Vec<&str> = line.split(':').collect();
+        let (group, artifact, version) = match fields.len() {
+            5 => (fields[0], fields[1], fields[3]),
+            6 => (fields[0], fields[1], fields[4]),
+            _ => continue,
+        };
+        components.push(query(group, artifact, version));
+    }
+
+    if components.queries.is_empty() {
+        return Err("no dependencies found in maven dependency list".into());
+    }
+
+    Ok(components)
+}
+
+/// Parses a `gradle.lockfile`, one `group:artifact:version=configurations` per line.
+pub fn parse_gradle_lockfile(input: &str) -> Result<Components, String> {
+    let mut components = Components::default();
+
+    for line in input.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') || line.starts_with("empty=") {
+            continue;
+        }
+
+        let coordinates = line.split('=').next().unwrap_or_default();
+        let fields: Vec<&str> = coordinates.split(':').collect();
+        if fields.len() != 3 {
+            components.warn(format!("skipping invalid gradle lockfile line '{}'", line));
+            continue;
+        }
+        components.push(query(fields[0], fields[1], fields[2]));
+    }
+
+    Ok(components)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_dependency_list, parse_gradle_lockfile};
+    use crate::search::Query;
+
+    fn query(vendor: Option<&str>, product: &str, version: &str) -> Query {
+        Query {
+            vendor: vendor.map(|v| v.into()),
+            product: product.into(),
+            version: Some(version.into()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn can_parse_dependency_list() {
+        let list = "[INFO] --- maven-dependency-plugin:2.8:list (default-cli) @ app ---
+[INFO]
+[INFO] The following files have been resolved:
+[INFO]    org.apache.logging.log4j:log4j-core:jar:2.14.1:compile
+[INFO]    com.fasterxml.jackson.core:jackson-databind:jar:2.12.3:compile -- module com.fasterxml.jackson.databind
+[INFO]    io.netty:netty-transport-native-epoll:jar:linux-x86_64:4.1.65.Final:runtime
+[INFO]    junit:junit:jar:4.13.2:test
+[INFO]
+[INFO] BUILD SUCCESS";
+
+        assert_eq!(
+            vec![
+                query(Some("apache"), "log4j", "2.14.1"),
+                query(Some("fasterxml"), "jackson-databind", "2.12.3"),
+                query(
+                    Some("netty"),
+                    "netty-transport-native-epoll",
+                    "4.1.65.Final"
+                ),
+                query(None, "junit", "4.13.2"),
+            ],
+            parse_dependency_list(list).unwrap().queries
+        );
+    }
+
+    #[test]
+    fn can_parse_gradle_lockfile() {
+        let lock = "# This is a Gradle generated file for dependency locking.
+# Manual edits can break the build and are not advised.
+# This file is expected to be part of source control.
+com.google.guava:guava:30.1-jre=compileClasspath,runtimeClasspath
+org.yaml:snakeyaml:1.28=runtimeClasspath
+empty=annotationProcessor
+";
+
+        assert_eq!(
+            vec![
+                query(Some("google"), "guava", "30.1-jre"),
+                query(Some("snakeyaml_project"), "snakeyaml", "1.28"),
+            ],
+            parse_gradle_lockfile(lock).unwrap().queries
+        );
+    }
+}
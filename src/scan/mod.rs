@@ -0,0 +1,849 @@
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::fmt;
+use std::str::FromStr;
+
+use serde::Serialize;
+
+use crate::db::{models, Database};
+use crate::search::{self, enrichment::Enrichment, Query};
+use crate::sources::{
+    almalinux, alpine, amazon, debian, eol, gentoo, nist::cpe, oracle, redhat, rocky, suse, ubuntu,
+};
+
+pub mod alias;
+pub mod attestation;
+pub mod buildroot;
+pub mod cargo;
+pub mod cyclonedx;
+pub mod go;
+pub mod license;
+pub mod lineage;
+pub mod maven;
+pub mod npm;
+pub mod openwrt;
+pub mod python;
+#[cfg(feature = "http")]
+pub mod rekor;
+pub mod spdx;
+pub mod syft;
+pub mod yocto;
+
+/// Supported formats of the component listings accepted as scan input.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Format {
+    Syft,
+    Yocto,
+    Buildroot,
+    OpenWrt,
+    Cargo,
+    Npm,
+    Go,
+    Pip,
+    Poetry,
+    Maven,
+    Gradle,
+    CycloneDx,
+    Spdx,
+    Products,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(val: &str) -> Result<Self, Self::Err> {
+        match val {
+            "syft" => Ok(Self::Syft),
+            "yocto" => Ok(Self::Yocto),
+            "buildroot" => Ok(Self::Buildroot),
+            "openwrt" => Ok(Self::OpenWrt),
+            "cargo" => Ok(Self::Cargo),
+            "npm" => Ok(Self::Npm),
+            "go" => Ok(Self::Go),
+            "pip" => Ok(Self::Pip),
+            "poetry" => Ok(Self::Poetry),
+            "maven" => Ok(Self::Maven),
+            "gradle" => Ok(Self::Gradle),
+            "cyclonedx" => Ok(Self::CycloneDx),
+            "spdx" => Ok(Self::Spdx),
+            "products" => Ok(Self::Products),
+            _ => Err(format!("unsupported scan input format '{}'", val)),
+        }
+    }
+}
+
+impl fmt::Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Syft => "syft",
+            Self::Yocto => "yocto",
+            Self::Buildroot => "buildroot",
+            Self::OpenWrt => "openwrt",
+            Self::Cargo => "cargo",
+            Self::Npm => "npm",
+            Self::Go => "go",
+            Self::Pip => "pip",
+            Self::Poetry => "poetry",
+            Self::Maven => "maven",
+            Self::Gradle => "gradle",
+            Self::CycloneDx => "cyclonedx",
+            Self::Spdx => "spdx",
+            Self::Products => "products",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// License declared by the scan input for a component.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct License {
+    pub product: String,
+    pub version: String,
+    pub license: String,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub disallowed: bool,
+}
+
+/// Components parsed from a scan input, along with the warnings about the entries that had
+/// to be skipped and the licenses declared by the input, if any.
+#[derive(Debug, Default, PartialEq)]
+pub struct Components {
+    pub queries: Vec<Query>,
+    pub warnings: Vec<String>,
+    pub licenses: Vec<License>,
+}
+
+impl Components {
+    pub fn push(&mut self, query: Query) {
+        if !self.queries.contains(&query) {
+            self.queries.push(query);
+        }
+    }
+
+    /// Records the license of the component, unknown licenses are ignored.
+    pub fn license(&mut self, query: &Query, license: &str) {
+        if license::is_unknown(license) {
+            return;
+        }
+
+        let license = License {
+            product: query.product.clone(),
+            version: query.version.clone().unwrap_or_default(),
+            license: license.trim().to_string(),
+            disallowed: false,
+        };
+        if !self.licenses.contains(&license) {
+            self.licenses.push(license);
+        }
+    }
+
+    pub fn warn(&mut self, warning: String) {
+        self.warnings.push(warning);
+    }
+}
+
+/// CVEs found for a component of the scan input.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Finding {
+    pub query: Query,
+    /// Identifiers of the CVEs, without duplicates.
+    pub cves: Vec<String>,
+}
+
+/// Result of a scan.
+#[derive(Debug, Serialize)]
+pub struct Report {
+    pub format: Format,
+    pub warnings: Vec<String>,
+    pub cves: Vec<models::CVE>,
+    /// Components whose release cycle reached its end of life.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub eol: Vec<eol::EndOfLife>,
+    /// Licenses declared by the scan input.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub licenses: Vec<License>,
+    /// Exploitation data of the CVEs, included in the responses.
+    #[serde(skip)]
+    pub enrichment: Enrichment,
+    /// CVEs of every component, grouped in the v2 responses.
+    #[serde(skip)]
+    pub findings: Vec<Finding>,
+}
+
+/// Parses the input into the list of queries, one for every versioned component.
+pub fn parse(format: Format, input: &str) -> Result<Components, String> {
+    match format {
+        Format::Syft => syft::parse(input),
+        Format::Yocto => yocto::parse(input),
+        Format::Buildroot => buildroot::parse(input),
+        Format::OpenWrt => openwrt::parse(input),
+        Format::Cargo => cargo::parse(input),
+        Format::Npm => npm::parse(input),
+        Format::Go => go::parse(input),
+        Format::Pip => python::parse_requirements(input),
+        Format::Poetry => python::parse_poetry_lock(input),
+        Format::Maven => maven::parse_dependency_list(input),
+        Format::Gradle => maven::parse_gradle_lockfile(input),
+        Format::CycloneDx => cyclonedx::parse(input),
+        Format::Spdx => spdx::parse(input),
+        Format::Products => parse_products(input),
+    }
+}
+
+/// Parses a plain JSON list of queries.
+fn parse_products(input: &str) -> Result<Components, String> {
+    let queries: Vec<Query> =
+        serde_json::from_str(input).map_err(|e| format!("could not parse products list: {}", e))?;
+
+    let mut components = Components::default();
+    for query in queries {
+        if query.version.is_none() {
+            components.warn(format!("skipping {}: missing version", query.product));
+        } else {
+            components.push(query);
+        }
+    }
+
+    Ok(components)
+}
+
+/// Detects the format of a scan input by sniffing its content.
+pub fn detect(input: &str) -> Option<Format> {
+    let trimmed = input.trim_start();
+
+    if trimmed.starts_with('{') || trimmed.starts_with('[') {
+        if let Ok(json) = serde_json::from_str::<serde_json::Value>(input) {
+            if json.is_array() {
+                return Some(Format::Products);
+            }
+            return if json.get("bomFormat").and_then(|f| f.as_str()) == Some("CycloneDX") {
+                Some(Format::CycloneDx)
+            } else if json.get("spdxVersion").is_some() {
+                Some(Format::Spdx)
+            } else if json.get("artifacts").is_some() {
+                Some(Format::Syft)
+            } else if json.get("lockfileVersion").is_some() {
+                Some(Format::Npm)
+            } else if json.get("package").map(|p| p.is_array()) == Some(true) {
+                Some(Format::Yocto)
+            } else {
+                None
+            };
+        }
+    }
+
+    let lines: Vec<&str> = input
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .collect();
+    let first = lines.first()?;
+
+    if input.contains("[[package]]") {
+        if input.contains("python-versions") || input.contains("content-hash") {
+            Some(Format::Poetry)
+        } else {
+            Some(Format::Cargo)
+        }
+    } else if input.contains("PACKAGE NAME:") {
+        Some(Format::Yocto)
+    } else if first.starts_with("\"PACKAGE\"") {
+        Some(Format::Buildroot)
+    } else if input.contains("[INFO]") {
+        Some(Format::Maven)
+    } else if input.contains("Gradle generated file") {
+        Some(Format::Gradle)
+    } else if lines.iter().all(|l| l.contains(" h1:")) {
+        Some(Format::Go)
+    } else if lines.iter().all(|l| l.contains(" - ")) {
+        Some(Format::OpenWrt)
+    } else if lines
+        .iter()
+        .any(|l| !l.starts_with('#') && !l.starts_with('-') && l.contains("=="))
+    {
+        Some(Format::Pip)
+    } else {
+        None
+    }
+}
+
+/// Products of language ecosystem packages are prefixed with the ecosystem, like the
+/// NIST target software and the NPM importer do, so that they don't collide with homonymous
+/// system packages.
+pub fn ecosystem_product(ecosystem: &str, name: &str) -> String {
+    let prefix = format!("{}-", ecosystem);
+    if name.starts_with(&prefix) {
+        name.to_string()
+    } else {
+        prefix + name
+    }
+}
+
+/// Builds a query from the vendor and product of a CPE 2.3 string, if they're set.
+pub(crate) fn cpe_query(cpe: &str, version: &str) -> Option<Query> {
+    let cpe = cpe::CPE23::try_from(cpe).ok()?;
+    if !cpe.product.is_value() {
+        return None;
+    }
+
+    Some(Query {
+        vendor: if cpe.vendor.is_value() {
+            Some(cpe.vendor.to_string())
+        } else {
+            None
+        },
+        product: cpe.product.to_string(),
+        version: Some(version.to_string()),
+        target_sw: if cpe.target_sw.is_value() {
+            Some(cpe.target_sw.to_string())
+        } else {
+            None
+        },
+        // the part of the CPEs generated by the SBOM tools is often a guess
+        ..Default::default()
+    })
+}
+
+/// Value of a qualifier of a package URL, like the `distro` of
+/// `pkg:deb/debian/openssl@1.1.1n-0+deb11u3?arch=amd64&distro=debian-11`.
+fn purl_qualifier<'a>(purl: &'a str, key: &str) -> Option<&'a str> {
+    purl.split('#')
+        .next()?
+        .split_once('?')?
+        .1
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(k, _)| *k == key)
+        .map(|(_, value)| value)
+}
+
+/// Version of an RPM package from its URL, prefixed with the `epoch` qualifier unless it
+/// already has one, like `1:1.1.1k-5.el8_5`.
+fn rpm_version(purl: &str, version: &str) -> String {
+    match purl_qualifier(purl, "epoch") {
+        Some(epoch) if !version.contains(':') => format!("{}:{}", epoch, version),
+        _ => version.to_string(),
+    }
+}
+
+/// Builds a query for a RHEL package from its URL, like
+/// `pkg:rpm/redhat/openssl@1.1.1k-5.el8_5?arch=x86_64&distro=rhel-8.5&epoch=1`, to be matched
+/// against the fixes backported by Red Hat.
+fn redhat_query(purl: &str, name: &str, version: &str) -> Option<Query> {
+    if !purl.starts_with("pkg:rpm/redhat/") {
+        return None;
+    }
+    let qualifier = |key: &str| purl_qualifier(purl, key);
+
+    let release = redhat::release(qualifier("distro")?.strip_prefix("rhel-")?)?;
+
+    Some(Query {
+        vendor: None,
+        product: redhat::product(&release, name),
+        version: Some(rpm_version(purl, version)),
+        ..Default::default()
+    })
+}
+
+/// Builds a query for a SUSE Linux Enterprise or openSUSE package from its URL, like
+/// `pkg:rpm/suse/libopenssl1_1@1.1.1l-150400.7.3.1?arch=x86_64&distro=sles-15.4`, to be
+/// matched against the fixes of the package in the release.
+fn suse_query(purl: &str, name: &str, version: &str) -> Option<Query> {
+    if !purl.starts_with("pkg:rpm/suse/") && !purl.starts_with("pkg:rpm/opensuse/") {
+        return None;
+    }
+    let qualifier = |key: &str| purl_qualifier(purl, key);
+
+    let distro = qualifier("distro")?;
+    let release = ["sles", "sled", "opensuse-leap", "opensuse-tumbleweed"]
+        .iter()
+        .find_map(|id| {
+            let version = distro.strip_prefix(id)?;
+            suse::release(id, version.trim_start_matches('-'))
+        })?;
+
+    Some(Query {
+        vendor: None,
+        product: suse::product(&release, name),
+        version: Some(rpm_version(purl, version)),
+        ..Default::default()
+    })
+}
+
+/// Builds a query for an Amazon Linux package from its URL, like
+/// `pkg:rpm/amazon/openssl@1.0.2k-24.amzn2.0.7?arch=x86_64&distro=amzn-2&epoch=1`, to be
+/// matched against the fixes of the package in the release.
+fn amazon_query(purl: &str, name: &str, version: &str) -> Option<Query> {
+    if !purl.starts_with("pkg:rpm/amazon/") {
+        return None;
+    }
+    let qualifier = |key: &str| purl_qualifier(purl, key);
+
+    let release = amazon::release(qualifier("distro")?.strip_prefix("amzn-")?)?;
+
+    Some(Query {
+        vendor: None,
+        product: amazon::product(&release, name),
+        version: Some(rpm_version(purl, version)),
+        ..Default::default()
+    })
+}
+
+/// Builds a query for an Oracle Linux package from its URL, like
+/// `pkg:rpm/oracle/openssl@1.1.1k-7.el8_6?arch=x86_64&distro=ol-8.7&epoch=1`, to be matched
+/// against the fixes of the package in the release.
+fn oracle_query(purl: &str, name: &str, version: &str) -> Option<Query> {
+    if !purl.starts_with("pkg:rpm/oracle/") {
+        return None;
+    }
+    let qualifier = |key: &str| purl_qualifier(purl, key);
+
+    let release = oracle::release(qualifier("distro")?.strip_prefix("ol-")?)?;
+
+    Some(Query {
+        vendor: None,
+        product: oracle::product(&release, name),
+        version: Some(rpm_version(purl, version)),
+        ..Default::default()
+    })
+}
+
+/// Builds a query for a Rocky Linux or AlmaLinux package from its URL, like
+/// `pkg:rpm/rocky/openssl@1.1.1k-9.el8_7?arch=x86_64&distro=rocky-8.7&epoch=1`, to be matched
+/// against the fixes of the package in the release.
+fn rebuild_query(purl: &str, name: &str, version: &str) -> Option<Query> {
+    let qualifier = |key: &str| purl_qualifier(purl, key);
+
+    let product = if purl.starts_with("pkg:rpm/rocky/") {
+        let release = rocky::release(qualifier("distro")?.strip_prefix("rocky-")?)?;
+        rocky::product(&release, name)
+    } else if purl.starts_with("pkg:rpm/almalinux/") {
+        let release = almalinux::release(qualifier("distro")?.strip_prefix("almalinux-")?)?;
+        almalinux::product(&release, name)
+    } else {
+        return None;
+    };
+
+    Some(Query {
+        vendor: None,
+        product,
+        version: Some(rpm_version(purl, version)),
+        ..Default::default()
+    })
+}
+
+/// Builds a query for a Debian, Ubuntu or Alpine package from its URL, like
+/// `pkg:deb/debian/libssl1.1@1.1.1n-0+deb11u3?arch=amd64&distro=debian-11&upstream=openssl`,
+/// to be matched against the fixes of its source package in the release.
+fn source_package_query(purl: &str, name: &str, version: &str) -> Option<Query> {
+    let (kind, rest) = purl.strip_prefix("pkg:")?.split_once('/')?;
+    let namespace = rest.split('/').next()?;
+    let distro = purl_qualifier(purl, "distro")?;
+    let release = distro
+        .strip_prefix(namespace)
+        .and_then(|release| release.strip_prefix('-'))
+        .unwrap_or(distro);
+    // the source package, along with its version when it differs, like `gcc-10%4010.2.1-6`
+    let package = purl_qualifier(purl, "upstream")
+        .and_then(|upstream| {
+            upstream
+                .replace("%40", "@")
+                .split('@')
+                .next()
+                .map(str::to_string)
+        })
+        .filter(|package| !package.is_empty())
+        .unwrap_or_else(|| name.to_string());
+
+    let product = match (kind, namespace) {
+        ("deb", "debian") => debian::product(debian::codename(release)?, &package),
+        ("deb", "ubuntu") => ubuntu::product(ubuntu::codename(release)?, &package),
+        ("apk", "alpine") => alpine::product(&alpine::branch(release)?, &package),
+        _ => return None,
+    };
+
+    Some(Query {
+        vendor: None,
+        product,
+        version: Some(version.to_string()),
+        ..Default::default()
+    })
+}
+
+/// Builds a query from a package URL, using the same product naming of the lock file parsers
+/// for the language ecosystems.
+pub(crate) fn purl_query(purl: &str, name: &str, version: &str) -> Query {
+    if let Some(query) = redhat_query(purl, name, version)
+        .or_else(|| suse_query(purl, name, version))
+        .or_else(|| amazon_query(purl, name, version))
+        .or_else(|| oracle_query(purl, name, version))
+        .or_else(|| rebuild_query(purl, name, version))
+        .or_else(|| source_package_query(purl, name, version))
+    {
+        return query;
+    }
+
+    let ecosystem = purl
+        .strip_prefix("pkg:")
+        .and_then(|p| p.split('/').next())
+        .unwrap_or_default();
+
+    let (vendor, product) = match ecosystem {
+        "npm" => (None, ecosystem_product("node", name)),
+        "cargo" => (None, ecosystem_product("rust", name)),
+        "pypi" => (
+            None,
+            ecosystem_product("python", &python::normalize_name(name)),
+        ),
+        "golang" => (
+            None,
+            ecosystem_product("go", name.rsplit('/').next().unwrap_or(name)),
+        ),
+        // the category is part of the package atom, like `pkg:ebuild/dev-libs/openssl@3.0.11`
+        "ebuild" => (
+            None,
+            gentoo::product(
+                purl.strip_prefix("pkg:ebuild/")
+                    .and_then(|atom| atom.split(|c| c == '@' || c == '?').next())
+                    .filter(|atom| atom.contains('/'))
+                    .unwrap_or(name),
+            ),
+        ),
+        _ => {
+            let alias = alias::resolve(name);
+            (alias.vendor, alias.product)
+        }
+    };
+
+    Query {
+        vendor,
+        product,
+        version: Some(version.to_string()),
+        ..Default::default()
+    }
+}
+
+/// Collects the string fields of the `[[package]]` tables of TOML lock files, which is all
+/// we need from them.
+pub(crate) fn lock_packages(input: &str) -> Vec<HashMap<String, String>> {
+    let mut packages = vec![];
+    let mut current: Option<HashMap<String, String>> = None;
+
+    for line in input.lines().map(str::trim) {
+        if line.starts_with('[') {
+            if let Some(package) = current.take() {
+                packages.push(package);
+            }
+            if line == "[[package]]" {
+                current = Some(HashMap::new());
+            }
+        } else if let (Some(package), Some((key, value))) = (&mut current, line.split_once('=')) {
+            let value = value.trim();
+            if value.len() >= 2 && value.starts_with('"') && value.ends_with('"') {
+                package.insert(
+                    key.trim().to_string(),
+                    value[1..value.len() - 1].to_string(),
+                );
+            }
+        }
+    }
+    if let Some(package) = current.take() {
+        packages.push(package);
+    }
+
+    packages
+}
+
+/// Runs the queries of the parsed components and reports the matching CVEs, without
+/// duplicates. The components that can't be searched, like those of an invalid version, are
+/// skipped with a warning.
+pub fn run(db: &Database, format: Format, mut components: Components) -> Result<Report, String> {
+    let mut cves: Vec<models::CVE> = vec![];
+    let mut findings = vec![];
+
+    for query in &components.queries {
+        let matches = match search::query(db, query) {
+            Ok(matches) => matches,
+            Err(err) => {
+                let component = match &query.version {
+                    Some(version) => format!("{} {}", query.product, version),
+                    None => query.product.clone(),
+                };
+                components
+                    .warnings
+                    .push(format!("skipping {}: {}", component, err));
+                continue;
+            }
+        };
+        let mut ids: Vec<String> = vec![];
+        for cve in matches {
+            if !ids.contains(&cve.cve) {
+                ids.push(cve.cve.clone());
+            }
+            let found = cves
+                .iter()
+                .any(|c| c.cve == cve.cve && c.vendor == cve.vendor && c.product == cve.product);
+            if !found {
+                cves.push(cve);
+            }
+        }
+        findings.push(Finding {
+            query: query.clone(),
+            cves: ids,
+        });
+    }
+
+    let today = chrono::Utc::now().naive_utc().date();
+    let eol = eol::check(db, &components.queries, today)?;
+
+    let mut licenses = components.licenses;
+    license::flag(&mut licenses);
+
+    let enrichment = Enrichment::load(db, &cves)?;
+
+    Ok(Report {
+        format,
+        warnings: components.warnings,
+        cves,
+        eol,
+        licenses,
+        enrichment,
+        findings,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{detect, purl_query, rpm_version, Format};
+    use crate::search::Query;
+
+    #[test]
+    fn can_detect_formats() {
+        let table = vec![
+            (
+                r#"{"bomFormat": "CycloneDX", "components": []}"#,
+                Format::CycloneDx,
+            ),
+            (r#"{"spdxVersion": "SPDX-2.2", "packages": []}"#, Format::Spdx),
+            (r#"{"artifacts": [], "source": {}}"#, Format::Syft),
+            (r#"{"lockfileVersion": 2, "packages": {}}"#, Format::Npm),
+            (r#"{"version": "1", "package": []}"#, Format::Yocto),
+            (
+                r#"[{"product": "libxml2", "version": "2.9.10"}]"#,
+                Format::Products,
+            ),
+            (
+                "[[package]]\nname = \"regex\"\nversion = \"1.5.5\"",
+                Format::Cargo,
+            ),
+            (
+                "[[package]]\nname = \"django\"\npython-versions = \">=3.6\"",
+                Format::Poetry,
+            ),
+            (
+                "PACKAGE NAME: busybox\nPACKAGE VERSION: 1.33.1",
+                Format::Yocto,
+            ),
+            ("\"PACKAGE\",\"VERSION\",\"LICENSE\"", Format::Buildroot),
+            ("[INFO]    junit:junit:jar:4.13.2:test", Format::Maven),
+            (
+                "# This is a Gradle generated file for dependency locking.\na:b:1.0=runtimeClasspath",
+                Format::Gradle,
+            ),
+            (
+                "github.com/gin-gonic/gin v1.7.0 h1:jGB9xAJQ12AIGNB4HguylppmDK1Am9ppF7XnGXXJuoU=",
+                Format::Go,
+            ),
+            ("busybox - 1.33.2-1\nkernel - 5.4.188-1", Format::OpenWrt),
+            ("# requirements\nDjango==3.2.12", Format::Pip),
+        ];
+
+        for (input, format) in table {
+            assert_eq!(Some(format), detect(input), "{}", input);
+            assert_eq!(Ok(format), format.to_string().parse::<Format>());
+        }
+
+        assert_eq!(None, detect(""));
+        assert_eq!(None, detect("{}"));
+        assert_eq!(None, detect("trollololol"));
+    }
+
+    #[test]
+    fn can_query_redhat_packages() {
+        assert_eq!(
+            Query {
+                vendor: None,
+                product: "rhel-8-openssl-libs".into(),
+                version: Some("1:1.1.1k-5.el8_5".into()),
+                ..Default::default()
+            },
+            purl_query(
+                "pkg:rpm/redhat/openssl-libs@1.1.1k-5.el8_5?arch=x86_64&distro=rhel-8.5&epoch=1",
+                "openssl-libs",
+                "1.1.1k-5.el8_5"
+            )
+        );
+        // without the release of the distribution the upstream CPE is searched
+        assert_eq!(
+            Some("openssl".to_string()),
+            purl_query(
+                "pkg:rpm/redhat/openssl@1.1.1k-5.el8_5?arch=x86_64",
+                "openssl",
+                "1.1.1k-5.el8_5"
+            )
+            .vendor
+        );
+    }
+
+    #[test]
+    fn can_prefix_rpm_epoch() {
+        let purl = "pkg:rpm/redhat/openssl@1.1.1k-5.el8_5?distro=rhel-8.5&epoch=1";
+        assert_eq!("1:1.1.1k-5.el8_5", rpm_version(purl, "1.1.1k-5.el8_5"));
+        // the version already has an epoch
+        assert_eq!("2:1.1.1k-5.el8_5", rpm_version(purl, "2:1.1.1k-5.el8_5"));
+        assert_eq!(
+            "1.1.1k-5.el8_5",
+            rpm_version("pkg:rpm/redhat/openssl@1.1.1k-5.el8_5", "1.1.1k-5.el8_5")
+        );
+    }
+
+    #[test]
+    fn can_query_debian_packages() {
+        assert_eq!(
+            Query {
+                vendor: None,
+                product: "debian-bullseye-openssl".into(),
+                version: Some("1.1.1n-0+deb11u3".into()),
+                ..Default::default()
+            },
+            purl_query(
+                "pkg:deb/debian/libssl1.1@1.1.1n-0+deb11u3?arch=amd64&distro=debian-11&upstream=openssl",
+                "libssl1.1",
+                "1.1.1n-0+deb11u3"
+            )
+        );
+        assert_eq!(
+            "debian-bullseye-gcc-10",
+            purl_query(
+                "pkg:deb/debian/libgcc-s1@10.2.1-6?arch=amd64&distro=debian-11.6&upstream=gcc-10%4010.2.1-6",
+                "libgcc-s1",
+                "10.2.1-6"
+            )
+            .product
+        );
+        assert_eq!(
+            "debian-bookworm-bash",
+            purl_query(
+                "pkg:deb/debian/bash@5.2.15-2?distro=bookworm",
+                "bash",
+                "5.2.15-2"
+            )
+            .product
+        );
+        assert_eq!(
+            "ubuntu-focal-openssl",
+            purl_query(
+                "pkg:deb/ubuntu/libssl1.1@1.1.1f-1ubuntu2.13?arch=amd64&distro=ubuntu-20.04&upstream=openssl",
+                "libssl1.1",
+                "1.1.1f-1ubuntu2.13"
+            )
+            .product
+        );
+    }
+
+    #[test]
+    fn can_query_suse_packages() {
+        assert_eq!(
+            "sles-15-sp4-libopenssl1_1",
+            purl_query(
+                "pkg:rpm/suse/libopenssl1_1@1.1.1l-150400.7.3.1?arch=x86_64&distro=sles-15.4",
+                "libopenssl1_1",
+                "1.1.1l-150400.7.3.1"
+            )
+            .product
+        );
+        assert_eq!(
+            "opensuse-leap-15.5-curl",
+            purl_query(
+                "pkg:rpm/opensuse/curl@8.0.1-150400.5.26.1?arch=x86_64&distro=opensuse-leap-15.5",
+                "curl",
+                "8.0.1-150400.5.26.1"
+            )
+            .product
+        );
+    }
+
+    #[test]
+    fn can_query_alpine_packages() {
+        assert_eq!(
+            "alpine-3.19-openssl",
+            purl_query(
+                "pkg:apk/alpine/libcrypto3@3.1.4-r1?arch=x86_64&upstream=openssl&distro=alpine-3.19.1",
+                "libcrypto3",
+                "3.1.4-r1"
+            )
+            .product
+        );
+        assert_eq!(
+            "alpine-edge-busybox",
+            purl_query(
+                "pkg:apk/alpine/busybox@1.36.1-r15?arch=x86_64&distro=alpine-edge",
+                "busybox",
+                "1.36.1-r15"
+            )
+            .product
+        );
+    }
+
+    #[test]
+    fn can_query_amazon_packages() {
+        let query = purl_query(
+            "pkg:rpm/amazon/openssl@1.0.2k-24.amzn2.0.7?arch=x86_64&distro=amzn-2&epoch=1",
+            "openssl",
+            "1.0.2k-24.amzn2.0.7",
+        );
+        assert_eq!("amzn-2-openssl", query.product);
+        assert_eq!(Some("1:1.0.2k-24.amzn2.0.7".to_string()), query.version);
+    }
+
+    #[test]
+    fn can_query_oracle_packages() {
+        let query = purl_query(
+            "pkg:rpm/oracle/openssl@1.1.1k-7.el8_6?arch=x86_64&distro=ol-8.7&epoch=1",
+            "openssl",
+            "1.1.1k-7.el8_6",
+        );
+        assert_eq!("ol-8-openssl", query.product);
+        assert_eq!(Some("1:1.1.1k-7.el8_6".to_string()), query.version);
+    }
+
+    #[test]
+    fn can_query_rebuild_packages() {
+        let query = purl_query(
+            "pkg:rpm/rocky/openssl@1.1.1k-9.el8_7?arch=x86_64&distro=rocky-8.7&epoch=1",
+            "openssl",
+            "1.1.1k-9.el8_7",
+        );
+        assert_eq!("rocky-8-openssl", query.product);
+        assert_eq!(Some("1:1.1.1k-9.el8_7".to_string()), query.version);
+
+        assert_eq!(
+            "almalinux-9-openssl",
+            purl_query(
+                "pkg:rpm/almalinux/openssl@3.0.7-6.el9_2?arch=x86_64&distro=almalinux-9.2&epoch=1",
+                "openssl",
+                "3.0.7-6.el9_2"
+            )
+            .product
+        );
+    }
+
+    #[test]
+    fn can_query_gentoo_packages() {
+        assert_eq!(
+            "gentoo-dev-libs/openssl",
+            purl_query("pkg:ebuild/dev-libs/openssl@3.0.11", "openssl", "3.0.11").product
+        );
+    }
+}
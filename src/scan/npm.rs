@@ -0,0 +1,145 @@
+use std::collections::HashMap;
+
+use serde::Deserialize;
+
+use super::{ecosystem_product, Components};
+use crate::search::Query;
+
+#[derive(Debug, Deserialize)]
+pub struct Dependency {
+    pub version: Option<String>,
+    #[serde(default)]
+    pub dependencies: HashMap<String, Dependency>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Package {
+    pub name: Option<String>,
+    pub version: Option<String>,
+}
+
+/// `package-lock.json`, lockfile version 1 only has the nested `dependencies` while
+/// versions 2 and 3 list every installed module in `packages`.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PackageLock {
+    #[serde(default)]
+    pub packages: HashMap<String, Package>,
+    #[serde(default)]
+    pub dependencies: HashMap<String, Dependency>,
+}
+
+fn push_query(components: &mut Components, name: &str, version: &str) {
+    // links, tarballs and git dependencies can't be matched
+    if version.is_empty() || version.contains(':') || version.contains('/') {
+        components.warn(format!(
+            "skipping {}: unsupported version {}",
+            name, version
+        ));
+        return;
+    }
+    components.push(Query {
+        vendor: None,
+        product: ecosystem_product("node", name),
+        version: Some(version.to_string()),
+        ..Default::default()
+    });
+}
+
+fn collect_dependencies(components: &mut Components, dependencies: &HashMap<String, Dependency>) {
+    for (name, dependency) in dependencies {
+        if let Some(version) = &dependency.version {
+            push_query(components, name, version);
+        }
+        collect_dependencies(components, &dependency.dependencies);
+    }
+}
+
+pub fn parse(input: &str) -> Result<Components, String> {
+    let lock: PackageLock = serde_json::from_str(input)
+        .map_err(|e| format!("could not parse package-lock.json: {}", e))?;
+
+    let mut components = Components::default();
+
+    if !lock.packages.is_empty() {
+        for (path, package) in &lock.packages {
+            // the root package (the project itself) has an empty path
+            if path.is_empty() {
+                continue;
+            }
+            let name = match (&package.name, path.rsplit_once("node_modules/")) {
+                (Some(name), _) => name.as_str(),
+                (None, Some((_, name))) => name,
+                _ => continue,
+            };
+            if let Some(version) = &package.version {
+                push_query(&mut components, name, version);
+            }
+        }
+    } else {
+        collect_dependencies(&mut components, &lock.dependencies);
+    }
+
+    components
+        .queries
+        .sort_by(|a, b| a.product.cmp(&b.product).then(a.version.cmp(&b.version)));
+
+    Ok(components)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse;
+    use crate::search::testing::query;
+
+    #[test]
+    fn can_parse_lockfile_v1() {
+        let lock = r#"{
+            "name": "app",
+            "lockfileVersion": 1,
+            "dependencies": {
+                "tar": {
+                    "version": "6.1.0",
+                    "dependencies": {
+                        "minimist": { "version": "1.2.5" }
+                    }
+                },
+                "local": { "version": "file:../local" }
+            }
+        }"#;
+
+        let components = parse(lock).unwrap();
+
+        assert_eq!(
+            vec![query("node-minimist", "1.2.5"), query("node-tar", "6.1.0")],
+            components.queries
+        );
+        assert_eq!(
+            vec!["skipping local: unsupported version file:../local".to_owned()],
+            components.warnings
+        );
+    }
+
+    #[test]
+    fn can_parse_lockfile_v2() {
+        let lock = r#"{
+            "name": "app",
+            "lockfileVersion": 2,
+            "packages": {
+                "": { "name": "app", "version": "1.0.0" },
+                "node_modules/tar": { "version": "6.1.0" },
+                "node_modules/tar/node_modules/minimist": { "version": "1.2.5" },
+                "node_modules/@babel/core": { "version": "7.17.5" }
+            }
+        }"#;
+
+        assert_eq!(
+            vec![
+                query("node-@babel/core", "7.17.5"),
+                query("node-minimist", "1.2.5"),
+                query("node-tar", "6.1.0"),
+            ],
+            parse(lock).unwrap().queries
+        );
+    }
+}
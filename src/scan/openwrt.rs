@@ -0,0 +1,98 @@
+use super::{alias, Components};
+use crate::search::Query;
+
+/// Strips the OpenWrt package release (and commit hash for kernel builds) from the version,
+/// for instance `1.1.1n-1` becomes `1.1.1n` and `5.4.188-1-89d6e0e8dd` becomes `5.4.188`.
+/// Date based versions of packages built from git snapshots are left untouched.
+fn normalize_version(version: &str) -> &str {
+    match version.split_once('-') {
+        Some((upstream, _)) if upstream.contains('.') => upstream,
+        _ => version,
+    }
+}
+
+/// Parses the output of `opkg list-installed`, one `package - version` per line.
+pub fn parse(input: &str) -> Result<Components, String> {
+    let mut components = Components::default();
+
+    for line in input.lines().map(str::trim).filter(|l| !l.is_empty()) {
+        let (package, version) = match line.split_once(" - ") {
+            Some(entry) => entry,
+            None => {
+                components.warn(format!("skipping invalid opkg package line '{}'", line));
+                continue;
+            }
+        };
+
+        let alias = alias::resolve(package.trim());
+        components.push(Query {
+            vendor: alias.vendor,
+            product: alias.product,
+            version: Some(normalize_version(version.trim()).to_string()),
+            ..Default::default()
+        });
+    }
+
+    if components.queries.is_empty() {
+        return Err("no packages found in opkg list".into());
+    }
+
+    Ok(components)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse;
+    use crate::search::Query;
+
+    #[test]
+    fn can_parse_opkg_list() {
+        let list = "busybox - 1.33.2-1
+kernel - 5.4.188-1-89d6e0e8dd8d1d1f3d49d7d4e4f5d0e1
+libopenssl1.1 - 1.1.1n-1
+libustream-wolfssl20201210 - 2022-01-16-868fd881-1
+";
+
+        assert_eq!(
+            vec![
+                Query {
+                    vendor: Some("busybox".into()),
+                    product: "busybox".into(),
+                    version: Some("1.33.2".into()),
+                    ..Default::default()
+                },
+                Query {
+                    vendor: Some("linux".into()),
+                    product: "linux_kernel".into(),
+                    version: Some("5.4.188".into()),
+                    ..Default::default()
+                },
+                Query {
+                    vendor: Some("openssl".into()),
+                    product: "openssl".into(),
+                    version: Some("1.1.1n".into()),
+                    ..Default::default()
+                },
+                Query {
+                    vendor: None,
+                    product: "libustream-wolfssl20201210".into(),
+                    version: Some("2022-01-16-868fd881-1".into()),
+                    ..Default::default()
+                },
+            ],
+            parse(list).unwrap().queries
+        );
+    }
+
+    #[test]
+    fn can_detect_invalid_lines() {
+        assert!(parse("not a package line").is_err());
+
+        let components = parse("busybox - 1.33.2-1\nnot a package line").unwrap();
+        assert_eq!(1, components.queries.len());
+        assert_eq!(
+            vec!["skipping invalid opkg package line 'not a package line'".to_owned()],
+            components.warnings
+        );
+    }
+}
@@ -0,0 +1,333 @@
+use std::cmp::Ordering;
+
+use lazy_static::lazy_static;
+use regex::Regex;
+use version_compare::Cmp;
+
+use super::{ecosystem_product, lock_packages, Components};
+use crate::search::Query;
+use crate::utils;
+
+lazy_static! {
+    static ref PRE_RELEASE: Regex =
+        Regex::new(r"[-_\.]?(?P<kind>alpha|beta|preview|pre|rc|a|b|c)[-_\.]?(?P<number>\d*)")
+            .unwrap();
+    static ref POST_RELEASE: Regex =
+        Regex::new(r"(?:[-_\.]?(?:post|rev|r)[-_\.]?|-)(?P<number>\d+)$").unwrap();
+    static ref DEV_RELEASE: Regex = Regex::new(r"[-_\.]?dev[-_\.]?(?P<number>\d*)").unwrap();
+    static ref SEPARATORS: Regex = Regex::new(r"[-_\.]+").unwrap();
+    static ref CANONICAL: Regex = Regex::new(concat!(
+        r"^(?:(?P<epoch>\d+)!)?(?P<release>\d+(?:\.\d+)*)",
+        r"(?:(?P<pre>a|b|rc)(?P<pre_number>\d+))?(?:\.post(?P<post>\d+))?(?:\.dev(?P<dev>\d+))?$"
+    ))
+    .unwrap();
+}
+
+/// Normalizes a package name as specified by PEP 503.
+pub fn normalize_name(name: &str) -> String {
+    SEPARATORS
+        .replace_all(&name.to_lowercase(), "-")
+        .to_string()
+}
+
+/// Normalizes a version to its canonical PEP 440 form, so that for instance `1.0-RC.1`,
+/// `v1.0rc1` and `1.0c1` are all compared as `1.0rc1`. Local version labels are dropped and
+/// so is the default epoch.
+pub fn normalize_version(version: &str) -> String {
+    let version = version.trim().to_lowercase();
+    let version = version.strip_prefix('v').unwrap_or(&version);
+    let version = version.split('+').next().unwrap_or(version);
+    let version = version.strip_prefix("0!").unwrap_or(version);
+
+    let version = PRE_RELEASE.replace(version, |caps: &regex::Captures| {
+        let kind = match &caps["kind"] {
+            "alpha" | "a" => "a",
+            "beta" | "b" => "b",
+            _ => "rc",
+        };
+        let number = if caps["number"].is_empty() {
+            "0"
+        } else {
+            &caps["number"]
+        };
+        format!("{}{}", kind, number)
+    });
+    let version = POST_RELEASE.replace(&version, ".post$number");
+    let version = DEV_RELEASE.replace(&version, |caps: &regex::Captures| {
+        let number = if caps["number"].is_empty() {
+            "0"
+        } else {
+            &caps["number"]
+        };
+        format!(".dev{}", number)
+    });
+
+    version.to_string()
+}
+
+/// Version in its canonical PEP 440 form, whose fields are ordered like PEP 440 orders the
+/// versions: development releases come before the pre-releases, before the final release,
+/// before the post-releases.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct Pep440 {
+    epoch: u64,
+    /// Without its trailing zeros, `1.0` being the same as `1`.
+    release: Vec<u64>,
+    /// Kind and number of the pre-release: 0 for the development releases of a final
+    /// release, 1 to 3 for the alpha, beta and release candidates, 4 for no pre-release.
+    pre: (u8, u64),
+    post: Option<u64>,
+    /// 0 and the number of the development release, 1 for none.
+    dev: (u8, u64),
+}
+
+impl Pep440 {
+    /// Parses a version normalized by `normalize_version`.
+    fn parse(version: &str) -> Option<Self> {
+        let caps = CANONICAL.captures(version)?;
+        let number = |name: &str| caps.name(name).and_then(|m| m.as_str().parse::<u64>().ok());
+
+        let mut release = caps["release"]
+            .split('.')
+            .map(|n| n.parse::<u64>().ok())
+            .collect::<Option<Vec<_>>>()?;
+        while release.len() > 1 && release.last() == Some(&0) {
+            release.pop();
+        }
+        let post = number("post");
+        let dev = number("dev");
+        let pre = match caps.name("pre").map(|m| m.as_str()) {
+            Some("a") => (1, number("pre_number")?),
+            Some("b") => (2, number("pre_number")?),
+            Some(_) => (3, number("pre_number")?),
+            None if dev.is_some() && post.is_none() => (0, 0),
+            None => (4, 0),
+        };
+
+        Some(Self {
+            epoch: number("epoch").unwrap_or(0),
+            release,
+            pre,
+            post,
+            dev: dev.map_or((1, 0), |dev| (0, dev)),
+        })
+    }
+}
+
+/// Compares two versions normalized by `normalize_version` according to PEP 440, falling
+/// back to the generic comparison for the versions that don't follow it.
+pub fn version_cmp(a: &str, b: &str, operator: Cmp) -> bool {
+    let ordering = match (Pep440::parse(a), Pep440::parse(b)) {
+        (Some(a), Some(b)) => a.cmp(&b),
+        _ => return utils::version_cmp(a, b, operator),
+    };
+
+    match operator {
+        Cmp::Eq => ordering == Ordering::Equal,
+        Cmp::Ne => ordering != Ordering::Equal,
+        Cmp::Lt => ordering == Ordering::Less,
+        Cmp::Le => ordering != Ordering::Greater,
+        Cmp::Ge => ordering != Ordering::Less,
+        Cmp::Gt => ordering == Ordering::Greater,
+    }
+}
+
+fn query(name: &str, version: &str) -> Query {
+    Query {
+        vendor: None,
+        product: ecosystem_product("python", &normalize_name(name)),
+        version: Some(normalize_version(version)),
+        ..Default::default()
+    }
+}
+
+/// Parses a pip requirements file, only pinned (`==` or `===`) requirements can be scanned.
+pub fn parse_requirements(input: &str) -> Result<Components, String> {
+    let mut components = Components::default();
+
+    for line in input.lines() {
+        // strip comments, environment markers and hashes
+        let line = line.split('#').next().unwrap_or_default();
+        let line = line.split(';').next().unwrap_or_default();
+        let line = line.split(" --").next().unwrap_or_default().trim();
+
+        // options (-r, -e, --index-url, ...) and continuation lines
+        if line.is_empty() || line.starts_with('-') || line == "\\" {
+            continue;
+        }
+
+        match line.split_once("==") {
+            Some((name, version)) => {
+                let name = name.split('[').next().unwrap_or(name).trim();
+                let version = version
+                    .trim_start_matches('=')
+                    .trim_end_matches('\\')
+                    .trim();
+                if !name.is_empty() && !version.is_empty() && !version.contains('*') {
+                    components.push(query(name, version));
+                } else {
+                    components.warn(format!("skipping '{}': not pinned", line));
+                }
+            }
+            None => components.warn(format!("skipping '{}': not pinned", line)),
+        }
+    }
+
+    Ok(components)
+}
+
+/// Parses a `poetry.lock` file.
+pub fn parse_poetry_lock(input: &str) -> Result<Components, String> {
+    let packages = lock_packages(input);
+    if packages.is_empty() {
+        return Err("no packages found in poetry.lock".into());
+    }
+
+    let mut components = Components::default();
+    for package in &packages {
+        if let (Some(name), Some(version)) = (package.get("name"), package.get("version")) {
+            components.push(query(name, version));
+        }
+    }
+
+    Ok(components)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use version_compare::Cmp;
+
+    use super::{
+        normalize_name, normalize_version, parse_poetry_lock, parse_requirements, version_cmp,
+    };
+    use crate::search::testing::query;
+
+    #[test]
+    fn can_normalize_names() {
+        assert_eq!("zope-interface", normalize_name("Zope.Interface"));
+        assert_eq!("pyyaml", normalize_name("PyYAML"));
+        assert_eq!("typing-extensions", normalize_name("typing__extensions"));
+    }
+
+    #[test]
+    fn can_normalize_versions() {
+        let mut table = HashMap::new();
+
+        table.insert("1.0", "1.0");
+        table.insert("v1.0", "1.0");
+        table.insert("0!1.0", "1.0");
+        table.insert("1.0-RC.1", "1.0rc1");
+        table.insert("1.0c1", "1.0rc1");
+        table.insert("1.0alpha", "1.0a0");
+        table.insert("1.0.beta2", "1.0b2");
+        table.insert("1.0-1", "1.0.post1");
+        table.insert("1.0-post2", "1.0.post2");
+        table.insert("1.0dev", "1.0.dev0");
+        table.insert("1.0+ubuntu1", "1.0");
+
+        for (version, normalized) in table {
+            assert_eq!(normalized, normalize_version(version), "{}", version);
+        }
+    }
+
+    #[test]
+    fn can_order_versions() {
+        let ordered = [
+            "1.0.dev0",
+            "1.0a1.dev1",
+            "1.0a1",
+            "1.0a2",
+            "1.0b1",
+            "1.0rc1",
+            "1.0",
+            "1.0.post1.dev0",
+            "1.0.post1",
+            "1.0.1",
+            "1.1",
+            "1!0.5",
+        ];
+
+        for pair in ordered.windows(2) {
+            assert!(
+                version_cmp(pair[0], pair[1], Cmp::Lt),
+                "{} < {}",
+                pair[0],
+                pair[1]
+            );
+            assert!(
+                version_cmp(pair[1], pair[0], Cmp::Gt),
+                "{} > {}",
+                pair[1],
+                pair[0]
+            );
+        }
+        assert!(version_cmp("1.0", "1.0.0", Cmp::Eq));
+        assert!(version_cmp("1.0rc1", "1.0", Cmp::Le));
+        assert!(version_cmp(&normalize_version("2.0-RC.1"), "2.0", Cmp::Lt));
+    }
+
+    #[test]
+    fn can_parse_requirements() {
+        let requirements = "# production dependencies
+-r base.txt
+--index-url https://pypi.org/simple
+Django==3.2.12
+requests[security]==2.27.1 ; python_version >= \"3.6\"
+PyYAML==5.4.1 \\
+    --hash=sha256:abcdef
+urllib3>=1.26
+numpy
+";
+
+        let components = parse_requirements(requirements).unwrap();
+
+        assert_eq!(
+            vec![
+                query("python-django", "3.2.12"),
+                query("python-requests", "2.27.1"),
+                query("python-pyyaml", "5.4.1"),
+            ],
+            components.queries
+        );
+        assert_eq!(
+            vec![
+                "skipping 'urllib3>=1.26': not pinned".to_owned(),
+                "skipping 'numpy': not pinned".to_owned(),
+            ],
+            components.warnings
+        );
+    }
+
+    #[test]
+    fn can_parse_poetry_lock() {
+        let lock = r#"[[package]]
+name = "django"
+version = "3.2.12"
+description = "A high-level Python Web framework."
+category = "main"
+optional = false
+python-versions = ">=3.6"
+
+[package.dependencies]
+asgiref = ">=3.3.2,<4"
+
+[[package]]
+name = "asgiref"
+version = "3.5.0"
+description = "ASGI specs, helper code, and adapters"
+
+[metadata]
+lock-version = "1.1"
+"#;
+
+        assert_eq!(
+            vec![
+                query("python-django", "3.2.12"),
+                query("python-asgiref", "3.5.0")
+            ],
+            parse_poetry_lock(lock).unwrap().queries
+        );
+    }
+}
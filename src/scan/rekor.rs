@@ -0,0 +1,163 @@
+use std::collections::HashMap;
+use std::env;
+use std::time::Duration;
+
+use log::info;
+use serde::Deserialize;
+use serde_json::json;
+
+/// DER prefix of the SubjectPublicKeyInfo of an Ed25519 public key.
+const ED25519_SPKI_PREFIX: &[u8] = &[
+    0x30, 0x2a, 0x30, 0x05, 0x06, 0x03, 0x2b, 0x65, 0x70, 0x03, 0x21, 0x00,
+];
+
+/// Entry of the transparency log.
+#[derive(Debug, PartialEq)]
+pub struct LogEntry {
+    pub uuid: String,
+    pub log_index: u64,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct EntryBody {
+    log_index: u64,
+}
+
+/// Client of a Rekor transparency log.
+#[derive(Debug, Clone)]
+pub struct Rekor {
+    url: String,
+}
+
+impl Rekor {
+    pub fn new(url: &str) -> Self {
+        Self {
+            url: url.trim_end_matches('/').to_string(),
+        }
+    }
+
+    /// Client of the log pointed by `KEPLER_REKOR_URL`, if set.
+    pub fn from_env() -> Option<Self> {
+        env::var("KEPLER_REKOR_URL").ok().map(|url| {
+            info!("uploading scan attestations to {}", url);
+            Self::new(&url)
+        })
+    }
+
+    /// URL of the entry in the log.
+    pub fn entry_url(&self, uuid: &str) -> String {
+        format!("{}/api/v1/log/entries/{}", self.url, uuid)
+    }
+
+    /// Uploads the signed DSSE envelope along with the public key verifying it.
+    pub fn upload(&self, envelope: &str, public_key: &[u8]) -> Result<LogEntry, String> {
+        let client = reqwest::blocking::Client::builder()
+            .timeout(Some(Duration::from_secs(30)))
+            .build()
+            .map_err(|e| format!("could not create http client: {}", e))?;
+
+        let res = client
+            .post(format!("{}/api/v1/log/entries", self.url))
+            .header("Content-Type", "application/json")
+            .body(proposed_entry(envelope, public_key).to_string())
+            .send()
+            .map_err(|e| format!("error uploading to {}: {}", self.url, e))?;
+
+        let status = res.status();
+        let body = res
+            .text()
+            .map_err(|e| format!("error reading response of {}: {}", self.url, e))?;
+        if !status.is_success() {
+            return Err(format!(
+                "{} refused the entry ({}): {}",
+                self.url, status, body
+            ));
+        }
+
+        parse_response(&body)
+    }
+}
+
+/// PEM encoding of an Ed25519 public key.
+fn public_key_pem(public_key: &[u8]) -> String {
+    let mut der = ED25519_SPKI_PREFIX.to_vec();
+    der.extend_from_slice(public_key);
+    format!(
+        "-----BEGIN PUBLIC KEY-----\n{}\n-----END PUBLIC KEY-----\n",
+        base64::encode(der)
+    )
+}
+
+/// Proposed entry of the `dsse` kind.
+fn proposed_entry(envelope: &str, public_key: &[u8]) -> serde_json::Value {
+    json!({
+        "apiVersion": "0.0.1",
+        "kind": "dsse",
+        "spec": {
+            "proposedContent": {
+                "envelope": envelope,
+                "verifiers": [base64::encode(public_key_pem(public_key))],
+            }
+        }
+    })
+}
+
+/// The response maps the UUID of the created entry to its content.
+fn parse_response(body: &str) -> Result<LogEntry, String> {
+    let entries: HashMap<String, EntryBody> =
+        serde_json::from_str(body).map_err(|e| format!("invalid log entry: {}", e))?;
+
+    entries
+        .into_iter()
+        .next()
+        .map(|(uuid, entry)| LogEntry {
+            uuid,
+            log_index: entry.log_index,
+        })
+        .ok_or_else(|| "no log entry created".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_response, proposed_entry, public_key_pem, LogEntry, Rekor};
+
+    #[test]
+    fn can_encode_public_keys() {
+        let pem = public_key_pem(&[0u8; 32]);
+        assert_eq!(
+            "-----BEGIN PUBLIC KEY-----\nMCowBQYDK2VwAyEAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA=\n-----END PUBLIC KEY-----\n",
+            pem
+        );
+
+        let entry = proposed_entry("{}", &[0u8; 32]);
+        assert_eq!("dsse", entry["kind"]);
+        assert_eq!("{}", entry["spec"]["proposedContent"]["envelope"]);
+    }
+
+    #[test]
+    fn can_parse_log_entries() {
+        let body = r#"{
+            "24296fb24b8ad77a1ad7edcd612f1e4a2c12b8c9a0d3aa8c5d1f3e5a6b7c8d9e": {
+                "body": "eyJhcGlWZXJzaW9uIjoiMC4wLjEifQ==",
+                "integratedTime": 1652871234,
+                "logID": "c0d23d6ad406973f9559f3ba2d1ca01f84147d8ffc5b8445c224f98b9591801d",
+                "logIndex": 2348211
+            }
+        }"#;
+
+        assert_eq!(
+            Ok(LogEntry {
+                uuid: "24296fb24b8ad77a1ad7edcd612f1e4a2c12b8c9a0d3aa8c5d1f3e5a6b7c8d9e".into(),
+                log_index: 2348211,
+            }),
+            parse_response(body)
+        );
+        assert!(parse_response("{}").is_err());
+
+        assert_eq!(
+            "https://rekor.sigstore.dev/api/v1/log/entries/abc",
+            Rekor::new("https://rekor.sigstore.dev/").entry_url("abc")
+        );
+    }
+}
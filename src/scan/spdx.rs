@@ -0,0 +1,157 @@
+use serde::Deserialize;
+
+use super::{cpe_query, license, purl_query, Components};
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExternalRef {
+    pub reference_type: String,
+    pub reference_locator: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Package {
+    pub name: String,
+    pub version_info: Option<String>,
+    pub license_concluded: Option<String>,
+    pub license_declared: Option<String>,
+    #[serde(default)]
+    pub external_refs: Vec<ExternalRef>,
+}
+
+impl Package {
+    fn reference(&self, kind: &str) -> Option<&str> {
+        self.external_refs
+            .iter()
+            .find(|r| r.reference_type == kind)
+            .map(|r| r.reference_locator.as_str())
+    }
+
+    /// The concluded license, falling back on the declared one.
+    fn license(&self) -> &str {
+        self.license_concluded
+            .as_deref()
+            .filter(|license| !license::is_unknown(license))
+            .or_else(|| self.license_declared.as_deref())
+            .unwrap_or_default()
+    }
+}
+
+/// SPDX document in its JSON representation.
+#[derive(Debug, Deserialize)]
+pub struct Document {
+    #[serde(default)]
+    pub packages: Vec<Package>,
+}
+
+pub fn parse(input: &str) -> Result<Components, String> {
+    let document: Document =
+        serde_json::from_str(input).map_err(|e| format!("could not parse SPDX document: {}", e))?;
+
+    let mut components = Components::default();
+    for package in &document.packages {
+        let version = match package.version_info.as_deref() {
+            None | Some("") | Some("NOASSERTION") => {
+                components.warn(format!("skipping {}: missing version", package.name));
+                continue;
+            }
+            Some(version) => version,
+        };
+
+        let query = package
+            .reference("cpe23Type")
+            .and_then(|cpe| cpe_query(cpe, version))
+            .unwrap_or_else(|| {
+                purl_query(
+                    package.reference("purl").unwrap_or_default(),
+                    &package.name,
+                    version,
+                )
+            });
+        components.license(&query, package.license());
+        components.push(query);
+    }
+
+    Ok(components)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse;
+    use crate::search::Query;
+
+    #[test]
+    fn can_parse_spdx_documents() {
+        let document = r#"{
+            "spdxVersion": "SPDX-2.2",
+            "SPDXID": "SPDXRef-DOCUMENT",
+            "packages": [
+                {
+                    "SPDXID": "SPDXRef-Package-openssl",
+                    "name": "openssl",
+                    "versionInfo": "1.1.1k",
+                    "licenseConcluded": "NOASSERTION",
+                    "licenseDeclared": "OpenSSL",
+                    "externalRefs": [
+                        {
+                            "referenceCategory": "SECURITY",
+                            "referenceType": "cpe23Type",
+                            "referenceLocator": "cpe:2.3:a:openssl:openssl:1.1.1k:*:*:*:*:*:*:*"
+                        }
+                    ]
+                },
+                {
+                    "SPDXID": "SPDXRef-Package-django",
+                    "name": "Django",
+                    "versionInfo": "3.2.12",
+                    "licenseConcluded": "BSD-3-Clause",
+                    "externalRefs": [
+                        {
+                            "referenceCategory": "PACKAGE-MANAGER",
+                            "referenceType": "purl",
+                            "referenceLocator": "pkg:pypi/django@3.2.12"
+                        }
+                    ]
+                },
+                {
+                    "SPDXID": "SPDXRef-Package-unknown",
+                    "name": "unknown",
+                    "versionInfo": "NOASSERTION"
+                }
+            ]
+        }"#;
+
+        let components = parse(document).unwrap();
+
+        assert_eq!(
+            vec![
+                Query {
+                    vendor: Some("openssl".into()),
+                    product: "openssl".into(),
+                    version: Some("1.1.1k".into()),
+                    ..Default::default()
+                },
+                Query {
+                    vendor: None,
+                    product: "python-django".into(),
+                    version: Some("3.2.12".into()),
+                    ..Default::default()
+                },
+            ],
+            components.queries
+        );
+        assert_eq!(
+            vec!["skipping unknown: missing version".to_owned()],
+            components.warnings
+        );
+        assert_eq!(
+            vec![("openssl", "OpenSSL"), ("python-django", "BSD-3-Clause")],
+            components
+                .licenses
+                .iter()
+                .map(|l| (l.product.as_str(), l.license.as_str()))
+                .collect::<Vec<_>>()
+        );
+    }
+}
@@ -0,0 +1,189 @@
+use serde::Deserialize;
+
+use super::{cpe_query, ecosystem_product, Components};
+use crate::search::Query;
+
+/// Older syft releases list CPEs as plain strings, newer ones as objects.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum Cpe {
+    Plain(String),
+    Detailed { cpe: String },
+}
+
+impl Cpe {
+    fn as_str(&self) -> &str {
+        match self {
+            Self::Plain(cpe) => cpe,
+            Self::Detailed { cpe } => cpe,
+        }
+    }
+}
+
+/// Like CPEs, licenses are plain strings in older syft releases and objects in newer ones.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum License {
+    Plain(String),
+    Detailed { value: String },
+}
+
+impl License {
+    fn as_str(&self) -> &str {
+        match self {
+            Self::Plain(license) => license,
+            Self::Detailed { value } => value,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Artifact {
+    pub name: String,
+    #[serde(default)]
+    pub version: String,
+    #[serde(rename = "type", default)]
+    pub kind: String,
+    #[serde(default)]
+    pub cpes: Vec<Cpe>,
+    #[serde(default)]
+    pub licenses: Vec<License>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Document {
+    pub artifacts: Vec<Artifact>,
+}
+
+impl Artifact {
+    fn query(&self) -> Query {
+        // the first CPE generated by syft is the most specific one
+        if let Some(query) = self
+            .cpes
+            .first()
+            .and_then(|c| cpe_query(c.as_str(), &self.version))
+        {
+            return query;
+        }
+
+        let product = match self.kind.as_str() {
+            "npm" => ecosystem_product("node", &self.name),
+            "rust-crate" => ecosystem_product("rust", &self.name),
+            _ => self.name.clone(),
+        };
+
+        Query {
+            vendor: None,
+            product,
+            version: Some(self.version.clone()),
+            ..Default::default()
+        }
+    }
+}
+
+pub fn parse(input: &str) -> Result<Components, String> {
+    let document: Document =
+        serde_json::from_str(input).map_err(|e| format!("could not parse syft document: {}", e))?;
+
+    let mut components = Components::default();
+    for artifact in &document.artifacts {
+        if artifact.version.is_empty() {
+            components.warn(format!("skipping {}: missing version", artifact.name));
+            continue;
+        }
+        let query = artifact.query();
+        let license = artifact
+            .licenses
+            .iter()
+            .map(License::as_str)
+            .collect::<Vec<_>>()
+            .join(" AND ");
+        components.license(&query, &license);
+        components.push(query);
+    }
+
+    Ok(components)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse;
+    use crate::search::Query;
+
+    #[test]
+    fn can_parse_syft_documents() {
+        let json = r#"{
+            "artifacts": [
+                {
+                    "name": "openssl",
+                    "version": "1.1.1k",
+                    "type": "apk",
+                    "licenses": ["OpenSSL"],
+                    "cpes": ["cpe:2.3:a:openssl:openssl:1.1.1k:*:*:*:*:*:*:*"]
+                },
+                {
+                    "name": "busybox",
+                    "version": "1.33.1",
+                    "type": "apk",
+                    "cpes": [{"cpe": "cpe:2.3:a:busybox:busybox:1.33.1:*:*:*:*:*:*:*", "source": "syft-generated"}]
+                },
+                {
+                    "name": "tar",
+                    "version": "6.1.0",
+                    "type": "npm",
+                    "licenses": [{"value": "ISC", "spdxExpression": "ISC", "type": "declared"}],
+                    "cpes": []
+                },
+                {
+                    "name": "unversioned",
+                    "version": "",
+                    "type": "binary"
+                }
+            ]
+        }"#;
+
+        let components = parse(json).unwrap();
+
+        assert_eq!(
+            vec![
+                Query {
+                    vendor: Some("openssl".into()),
+                    product: "openssl".into(),
+                    version: Some("1.1.1k".into()),
+                    ..Default::default()
+                },
+                Query {
+                    vendor: Some("busybox".into()),
+                    product: "busybox".into(),
+                    version: Some("1.33.1".into()),
+                    ..Default::default()
+                },
+                Query {
+                    vendor: None,
+                    product: "node-tar".into(),
+                    version: Some("6.1.0".into()),
+                    ..Default::default()
+                },
+            ],
+            components.queries
+        );
+        assert_eq!(
+            vec!["skipping unversioned: missing version".to_owned()],
+            components.warnings
+        );
+        assert_eq!(
+            vec![("openssl", "OpenSSL"), ("node-tar", "ISC")],
+            components
+                .licenses
+                .iter()
+                .map(|l| (l.product.as_str(), l.license.as_str()))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn can_detect_invalid_documents() {
+        assert!(parse("{}").is_err());
+        assert!(parse("troll").is_err());
+    }
+}
@@ -0,0 +1,209 @@
+use serde::Deserialize;
+
+use super::{alias, Components};
+use crate::search::Query;
+
+#[derive(Debug, Deserialize)]
+pub struct CveCheckProduct {
+    pub product: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CveCheckPackage {
+    pub name: String,
+    pub version: String,
+    #[serde(default)]
+    pub products: Vec<CveCheckProduct>,
+}
+
+/// JSON summary written by the `cve-check` class (`cve-summary.json`).
+#[derive(Debug, Deserialize)]
+pub struct CveCheck {
+    pub package: Vec<CveCheckPackage>,
+}
+
+fn query(name: &str, version: &str) -> Query {
+    let alias = alias::resolve(name);
+    Query {
+        vendor: alias.vendor,
+        product: alias.product,
+        version: Some(alias::normalize_version(version).to_string()),
+        ..Default::default()
+    }
+}
+
+fn parse_cve_check(input: &str) -> Result<Components, String> {
+    let summary: CveCheck = serde_json::from_str(input)
+        .map_err(|e| format!("could not parse cve-check summary: {}", e))?;
+
+    let mut components = Components::default();
+    for package in &summary.package {
+        if package.products.is_empty() {
+            components.push(query(&package.name, &package.version));
+        }
+        // recipes declare their CPE products explicitly with CVE_PRODUCT, prefer those
+        for product in &package.products {
+            components.push(query(&product.product, &package.version));
+        }
+    }
+
+    Ok(components)
+}
+
+/// Parses the `license.manifest` of an image, made of blocks of `KEY: value` lines.
+fn parse_license_manifest(input: &str) -> Result<Components, String> {
+    let mut components = Components::default();
+    let mut recipe: Option<&str> = None;
+    let mut package: Option<&str> = None;
+    let mut version: Option<&str> = None;
+    let mut license: Option<&str> = None;
+
+    // an empty line terminates a block, add one to flush the last of them
+    for line in input.lines().chain(std::iter::once("")) {
+        let line = line.trim();
+        if line.is_empty() {
+            match (recipe.or(package), version) {
+                (Some(name), Some(version)) => {
+                    let query = query(name, version);
+                    components.license(&query, license.unwrap_or_default());
+                    components.push(query);
+                }
+                (Some(name), None) => {
+                    components.warn(format!("skipping {}: missing version", name))
+                }
+                _ => {}
+            }
+            recipe = None;
+            package = None;
+            version = None;
+            license = None;
+            continue;
+        }
+
+        if let Some((key, value)) = line.split_once(':') {
+            let value = value.trim();
+            match key.trim() {
+                "RECIPE NAME" => recipe = Some(value),
+                "PACKAGE NAME" => package = Some(value),
+                "PACKAGE VERSION" => version = Some(value),
+                "LICENSE" => license = Some(value),
+                _ => {}
+            }
+        }
+    }
+
+    if components.queries.is_empty() {
+        return Err("no packages found in license manifest".into());
+    }
+
+    Ok(components)
+}
+
+pub fn parse(input: &str) -> Result<Components, String> {
+    if input.trim_start().starts_with('{') {
+        parse_cve_check(input)
+    } else {
+        parse_license_manifest(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse;
+    use crate::search::Query;
+
+    #[test]
+    fn can_parse_license_manifest() {
+        let manifest = "PACKAGE NAME: busybox-syslog
+PACKAGE VERSION: 1.33.1
+RECIPE NAME: busybox
+LICENSE: GPLv2 & bzip2-1.0.6
+
+PACKAGE NAME: kernel-5.10.78-yocto-standard
+PACKAGE VERSION: 5.10.78+gitAUTOINC+0c3d8a1e33
+RECIPE NAME: linux-yocto
+LICENSE: GPLv2
+
+PACKAGE NAME: busybox
+PACKAGE VERSION: 1.33.1
+RECIPE NAME: busybox
+LICENSE: GPLv2 & bzip2-1.0.6";
+
+        let components = parse(manifest).unwrap();
+
+        assert_eq!(
+            vec![
+                Query {
+                    vendor: Some("busybox".into()),
+                    product: "busybox".into(),
+                    version: Some("1.33.1".into()),
+                    ..Default::default()
+                },
+                Query {
+                    vendor: Some("linux".into()),
+                    product: "linux_kernel".into(),
+                    version: Some("5.10.78".into()),
+                    ..Default::default()
+                },
+            ],
+            components.queries
+        );
+        assert_eq!(
+            vec![
+                ("busybox", "GPLv2 & bzip2-1.0.6"),
+                ("linux_kernel", "GPLv2")
+            ],
+            components
+                .licenses
+                .iter()
+                .map(|l| (l.product.as_str(), l.license.as_str()))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn can_parse_cve_check_summary() {
+        let summary = r#"{
+            "version": "1",
+            "package": [
+                {
+                    "name": "libxml2",
+                    "layer": "meta",
+                    "version": "2.9.10",
+                    "products": [{"product": "libxml2", "cvesInRecord": "Yes"}],
+                    "issue": []
+                },
+                {
+                    "name": "unknown-recipe",
+                    "layer": "meta-custom",
+                    "version": "1.0",
+                    "products": [],
+                    "issue": []
+                }
+            ]
+        }"#;
+
+        assert_eq!(
+            vec![
+                Query {
+                    vendor: Some("xmlsoft".into()),
+                    product: "libxml2".into(),
+                    version: Some("2.9.10".into()),
+                    ..Default::default()
+                },
+                Query {
+                    vendor: None,
+                    product: "unknown-recipe".into(),
+                    version: Some("1.0".into()),
+                    ..Default::default()
+                },
+            ],
+            parse(summary).unwrap().queries
+        );
+    }
+
+    #[test]
+    fn can_detect_empty_manifests() {
+        assert!(parse("").is_err());
+    }
+}
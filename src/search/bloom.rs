@@ -0,0 +1,214 @@
+use std::collections::hash_map::DefaultHasher;
+use std::env;
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, RwLock};
+use std::thread;
+use std::time::Duration;
+
+use lazy_static::lazy_static;
+use log::{info, warn};
+
+use crate::db::{models, Database, Pool};
+
+use super::Query;
+
+const DEFAULT_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Rate of the lookups of missing products answered as possibly present.
+const FALSE_POSITIVE_RATE: f64 = 0.01;
+
+lazy_static! {
+    static ref FILTER: RwLock<Option<Arc<ProductFilter>>> = RwLock::new(None);
+}
+
+/// Bloom filter of strings, with the hashes derived from two SipHash ones.
+#[derive(Debug)]
+pub struct Bloom {
+    bits: Vec<u64>,
+    num_bits: u64,
+    num_hashes: u64,
+}
+
+impl Bloom {
+    /// Filter sized for the number of items at the false positives rate.
+    pub fn with_capacity(items: usize, false_positive_rate: f64) -> Self {
+        let items = items.max(1) as f64;
+        let ln2 = std::f64::consts::LN_2;
+        let num_bits = (-items * false_positive_rate.ln() / (ln2 * ln2))
+            .ceil()
+            .max(64.0) as u64;
+        let num_hashes = ((num_bits as f64 / items) * ln2).round().max(1.0) as u64;
+
+        Self {
+            bits: vec![0; ((num_bits + 63) / 64) as usize],
+            num_bits,
+            num_hashes,
+        }
+    }
+
+    fn positions(&self, key: &str) -> impl Iterator<Item = u64> {
+        let hash = |seed: u8| {
+            let mut hasher = DefaultHasher::new();
+            seed.hash(&mut hasher);
+            key.hash(&mut hasher);
+            hasher.finish()
+        };
+        let (h1, h2) = (hash(0), hash(1));
+        let num_bits = self.num_bits;
+
+        (0..self.num_hashes).map(move |i| h1.wrapping_add(i.wrapping_mul(h2)) % num_bits)
+    }
+
+    pub fn insert(&mut self, key: &str) {
+        for position in self.positions(key).collect::<Vec<_>>() {
+            self.bits[(position / 64) as usize] |= 1 << (position % 64);
+        }
+    }
+
+    pub fn contains(&self, key: &str) -> bool {
+        self.positions(key)
+            .all(|position| self.bits[(position / 64) as usize] & (1 << (position % 64)) != 0)
+    }
+}
+
+fn key(vendor: Option<&str>, product: &str) -> String {
+    match vendor {
+        Some(vendor) => format!("{}\0{}", vendor, product),
+        None => product.to_string(),
+    }
+}
+
+/// The products, and the vendors and products, having records.
+pub struct ProductFilter {
+    generation: (i64, Option<i32>),
+    bloom: Bloom,
+}
+
+impl ProductFilter {
+    pub fn build(generation: (i64, Option<i32>), products: &[models::Product]) -> Self {
+        // every product is inserted with and without its vendor
+        let mut bloom = Bloom::with_capacity(products.len() * 2, FALSE_POSITIVE_RATE);
+        for product in products {
+            bloom.insert(&key(None, &product.product));
+            bloom.insert(&key(Some(&product.vendor), &product.product));
+        }
+
+        Self { generation, bloom }
+    }
+
+    /// False when there are surely no records of the product of the query.
+    pub fn may_match(&self, query: &Query) -> bool {
+        self.bloom
+            .contains(&key(query.vendor.as_deref(), &query.product))
+    }
+}
+
+/// False when the filter is loaded and there are surely no records of the product.
+pub fn may_match(query: &Query) -> bool {
+    match FILTER.read().unwrap().as_ref() {
+        Some(filter) => filter.may_match(query),
+        None => true,
+    }
+}
+
+/// Builds the filter, unless the records didn't change since the last build.
+pub fn load(database: &Database) -> Result<bool, String> {
+    let generation = database.get_records_generation()?;
+    if let Some(filter) = FILTER.read().unwrap().as_ref() {
+        if filter.generation == generation {
+            return Ok(false);
+        }
+    }
+
+    let products = database.get_products()?;
+    info!("built the filter of {} products", products.len());
+    *FILTER.write().unwrap() = Some(Arc::new(ProductFilter::build(generation, &products)));
+
+    Ok(true)
+}
+
+/// How often the filter is checked for changes of the records, if it's enabled by setting
+/// `KEPLER_BLOOM_FILTER` to `true`. `KEPLER_BLOOM_FILTER_INTERVAL` sets it in seconds.
+pub fn interval_from_env() -> Result<Option<Duration>, String> {
+    match env::var("KEPLER_BLOOM_FILTER") {
+        Ok(value) if value == "true" || value == "1" => {}
+        Ok(value) if value == "false" || value == "0" => return Ok(None),
+        Ok(value) => return Err(format!("invalid KEPLER_BLOOM_FILTER: {}", value)),
+        Err(_) => return Ok(None),
+    }
+
+    match env::var("KEPLER_BLOOM_FILTER_INTERVAL") {
+        Ok(value) => match value.parse::<u64>() {
+            Ok(secs) if secs > 0 => Ok(Some(Duration::from_secs(secs))),
+            _ => Err(format!("invalid KEPLER_BLOOM_FILTER_INTERVAL: {}", value)),
+        },
+        Err(_) => Ok(Some(DEFAULT_INTERVAL)),
+    }
+}
+
+/// Builds the filter now, then rebuilds it in the background whenever an import changes
+/// the records.
+pub fn start(pool: Pool, interval: Duration) -> Result<(), String> {
+    load(&Database(pool.get().map_err(|e| e.to_string())?))?;
+
+    thread::spawn(move || loop {
+        thread::sleep(interval);
+
+        let loaded = pool
+            .get()
+            .map_err(|e| e.to_string())
+            .and_then(|conn| load(&Database(conn)));
+        if let Err(e) = loaded {
+            warn!("could not rebuild the products filter: {}", e);
+        }
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Bloom, ProductFilter};
+    use crate::db::models::Product;
+    use crate::search::Query;
+
+    #[test]
+    fn can_filter_missing_keys() {
+        let mut bloom = Bloom::with_capacity(1000, 0.01);
+        for i in 0..1000 {
+            bloom.insert(&format!("product-{}", i));
+        }
+
+        assert!((0..1000).all(|i| bloom.contains(&format!("product-{}", i))));
+
+        let false_positives = (0..10000)
+            .filter(|i| bloom.contains(&format!("missing-{}", i)))
+            .count();
+        assert!(false_positives < 300, "{} false positives", false_positives);
+    }
+
+    #[test]
+    fn can_filter_products() {
+        let filter = ProductFilter::build(
+            (1, Some(1)),
+            &[Product {
+                vendor: "xmlsoft".into(),
+                product: "libxml2".into(),
+                target_sw: vec![],
+                display_name: None,
+                vendor_display_name: None,
+            }],
+        );
+        let query = |vendor: Option<&str>, product: &str| Query {
+            vendor: vendor.map(str::to_string),
+            product: product.into(),
+            version: Some("2.9.10".into()),
+            ..Default::default()
+        };
+
+        assert!(filter.may_match(&query(None, "libxml2")));
+        assert!(filter.may_match(&query(Some("xmlsoft"), "libxml2")));
+        assert!(!filter.may_match(&query(Some("gnome"), "libxml2")));
+        assert!(!filter.may_match(&query(None, "busybox")));
+    }
+}
@@ -0,0 +1,154 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::{Arc, Condvar, Mutex};
+
+enum State<V> {
+    Running,
+    Done(V),
+    /// The call panicked, the waiting ones run it themselves.
+    Failed,
+}
+
+struct Call<V> {
+    state: Mutex<State<V>>,
+    done: Condvar,
+}
+
+/// Coalesces the identical calls running at the same time, so that only the first one
+/// actually runs and the others get a copy of its result.
+pub struct Group<K, V> {
+    calls: Mutex<HashMap<K, Arc<Call<V>>>>,
+}
+
+/// Marks the call as failed if it didn't complete, so that the waiting ones don't hang.
+struct Guard<'a, K: Eq + Hash, V> {
+    group: &'a Group<K, V>,
+    key: &'a K,
+    call: Arc<Call<V>>,
+}
+
+impl<'a, K: Eq + Hash, V> Drop for Guard<'a, K, V> {
+    fn drop(&mut self) {
+        self.group.calls.lock().unwrap().remove(self.key);
+
+        let mut state = match self.call.state.lock() {
+            Ok(state) => state,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        if let State::Running = *state {
+            *state = State::Failed;
+        }
+        self.call.done.notify_all();
+    }
+}
+
+impl<K: Eq + Hash + Clone, V: Clone> Default for Group<K, V> {
+    fn default() -> Self {
+        Self {
+            calls: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+impl<K: Eq + Hash + Clone, V: Clone> Group<K, V> {
+    /// Runs the function unless an identical call is already running, in which case it
+    /// waits for its result instead. Returns the result and whether it was shared.
+    pub fn run<F: FnOnce() -> V>(&self, key: K, f: F) -> (V, bool) {
+        let (call, leader) = {
+            let mut calls = self.calls.lock().unwrap();
+            match calls.get(&key) {
+                Some(call) => (call.clone(), false),
+                None => {
+                    let call = Arc::new(Call {
+                        state: Mutex::new(State::Running),
+                        done: Condvar::new(),
+                    });
+                    calls.insert(key.clone(), call.clone());
+                    (call, true)
+                }
+            }
+        };
+
+        if !leader {
+            let mut state = call.state.lock().unwrap();
+            loop {
+                match &*state {
+                    State::Running => state = call.done.wait(state).unwrap(),
+                    State::Done(value) => return (value.clone(), true),
+                    State::Failed => break,
+                }
+            }
+            drop(state);
+            return (f(), false);
+        }
+
+        let guard = Guard {
+            group: self,
+            key: &key,
+            call,
+        };
+        let value = f();
+        *guard.call.state.lock().unwrap() = State::Done(value.clone());
+        drop(guard);
+
+        (value, false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Arc, Barrier};
+    use std::thread;
+    use std::time::Duration;
+
+    use super::Group;
+
+    #[test]
+    fn can_coalesce_identical_calls() {
+        let group: Arc<Group<String, u32>> = Arc::new(Group::default());
+        let runs = Arc::new(AtomicUsize::new(0));
+        let barrier = Arc::new(Barrier::new(8));
+
+        let handles: Vec<_> = (0..8)
+            .map(|_| {
+                let (group, runs, barrier) = (group.clone(), runs.clone(), barrier.clone());
+                thread::spawn(move || {
+                    barrier.wait();
+                    group.run("libxml2".into(), || {
+                        runs.fetch_add(1, Ordering::SeqCst);
+                        thread::sleep(Duration::from_millis(200));
+                        42
+                    })
+                })
+            })
+            .collect();
+
+        let results: Vec<(u32, bool)> = handles.into_iter().map(|h| h.join().unwrap()).collect();
+        assert!(results.iter().all(|(value, _)| *value == 42));
+        assert_eq!(1, runs.load(Ordering::SeqCst));
+        assert_eq!(7, results.iter().filter(|(_, shared)| *shared).count());
+
+        // once done the calls run again
+        assert_eq!((43, false), group.run("libxml2".into(), || 43));
+    }
+
+    #[test]
+    fn can_recover_from_panics() {
+        let group: Arc<Group<String, u32>> = Arc::new(Group::default());
+
+        let leader = {
+            let group = group.clone();
+            thread::spawn(move || {
+                group.run("busybox".into(), || {
+                    thread::sleep(Duration::from_millis(200));
+                    panic!("database is down")
+                })
+            })
+        };
+        thread::sleep(Duration::from_millis(50));
+
+        assert_eq!((1, false), group.run("busybox".into(), || 1));
+        assert!(leader.join().is_err());
+    }
+}
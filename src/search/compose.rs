@@ -0,0 +1,206 @@
+//! Boolean compositions of search queries, like the CVEs of any of several CPEs, evaluated in
+//! a single request.
+
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+
+use super::Query;
+use crate::db::{models, Database};
+
+/// Maximum number of queries of a composition.
+pub const MAX_QUERIES: usize = 32;
+
+/// Composition of queries, given as a query, as `{"any": [...]}` for the CVEs of any of the
+/// compositions listed, or as `{"all": [...]}` for the CVEs of all of them.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Composition {
+    Any { any: Vec<Composition> },
+    All { all: Vec<Composition> },
+    Query(Query),
+}
+
+impl Composition {
+    /// Queries of the composition, in order.
+    pub fn queries(&self) -> Vec<&Query> {
+        match self {
+            Composition::Any { any: items } | Composition::All { all: items } => {
+                items.iter().flat_map(Composition::queries).collect()
+            }
+            Composition::Query(query) => vec![query],
+        }
+    }
+
+    fn num_queries(&self) -> usize {
+        match self {
+            Composition::Any { any: items } | Composition::All { all: items } => {
+                items.iter().map(Composition::num_queries).sum()
+            }
+            Composition::Query(_) => 1,
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.num_queries() > MAX_QUERIES {
+            return Err(format!("too many queries, the maximum is {}", MAX_QUERIES));
+        }
+        self.validate_items()
+    }
+
+    fn validate_items(&self) -> Result<(), String> {
+        match self {
+            Composition::Any { any: items } | Composition::All { all: items } => {
+                if items.is_empty() {
+                    return Err("the compositions can't be empty".into());
+                }
+                items.iter().try_for_each(Composition::validate_items)
+            }
+            Composition::Query(_) => Ok(()),
+        }
+    }
+}
+
+fn push_unique(cves: &mut Vec<models::CVE>, cve: models::CVE) {
+    let found = cves
+        .iter()
+        .any(|c| c.cve == cve.cve && c.vendor == cve.vendor && c.product == cve.product);
+    if !found {
+        cves.push(cve);
+    }
+}
+
+/// Evaluates the composition with the search given: `any` is the union of the records found,
+/// and `all` the records of the CVEs found by every composition listed.
+pub fn evaluate<F>(composition: &Composition, search: &mut F) -> Result<Vec<models::CVE>, String>
+where
+    F: FnMut(&Query) -> Result<Vec<models::CVE>, String>,
+{
+    match composition {
+        Composition::Query(query) => search(query),
+        Composition::Any { any } => {
+            let mut cves = vec![];
+            for item in any {
+                for cve in evaluate(item, search)? {
+                    push_unique(&mut cves, cve);
+                }
+            }
+            Ok(cves)
+        }
+        Composition::All { all } => {
+            let mut results = vec![];
+            for item in all {
+                let cves = evaluate(item, search)?;
+                // nothing can be found by all of them anymore
+                if cves.is_empty() {
+                    return Ok(vec![]);
+                }
+                results.push(cves);
+            }
+
+            let ids: Vec<HashSet<&str>> = results
+                .iter()
+                .map(|cves| cves.iter().map(|cve| cve.cve.as_str()).collect())
+                .collect();
+            let is_common = |id: &str| ids.iter().all(|ids| ids.contains(id));
+
+            let mut cves = vec![];
+            for cve in results.iter().flatten() {
+                if is_common(&cve.cve) {
+                    push_unique(&mut cves, cve.clone());
+                }
+            }
+            Ok(cves)
+        }
+    }
+}
+
+/// Runs the queries of the composition and combines the CVEs found.
+pub fn query(db: &Database, composition: &Composition) -> Result<Vec<models::CVE>, String> {
+    composition.validate()?;
+    evaluate(composition, &mut |query| super::query(db, query))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{evaluate, Composition, MAX_QUERIES};
+    use crate::db::models;
+    use crate::search::{testing::cve, Query};
+
+    /// Records of the CVEs of the products, whatever the version.
+    fn search(query: &Query) -> Result<Vec<models::CVE>, String> {
+        Ok(match query.product.as_str() {
+            "openssl" => vec![
+                cve("CVE-2021-3711", "openssl"),
+                cve("CVE-2021-3449", "openssl"),
+            ],
+            "nginx" => vec![
+                cve("CVE-2021-3449", "nginx"),
+                cve("CVE-2021-23017", "nginx"),
+            ],
+            "curl" => vec![cve("CVE-2021-22945", "curl")],
+            _ => vec![],
+        })
+    }
+
+    fn ids(cves: Vec<models::CVE>) -> Vec<String> {
+        cves.into_iter()
+            .map(|cve| format!("{} {}", cve.cve, cve.product))
+            .collect()
+    }
+
+    #[test]
+    fn can_compose_queries() {
+        let composition: Composition = serde_json::from_str(
+            r#"{"any": [
+                {"product": "openssl", "version": "1.1.1k"},
+                {"product": "curl", "version": "7.78.0"},
+                {"product": "unknown", "version": "1.0"}
+            ]}"#,
+        )
+        .unwrap();
+        assert!(composition.validate().is_ok());
+        assert_eq!(
+            vec![
+                "CVE-2021-3711 openssl",
+                "CVE-2021-3449 openssl",
+                "CVE-2021-22945 curl"
+            ],
+            ids(evaluate(&composition, &mut search).unwrap())
+        );
+
+        let composition: Composition = serde_json::from_str(
+            r#"{"all": [
+                {"product": "openssl", "version": "1.1.1k"},
+                {"any": [{"product": "nginx", "version": "1.20.0"}, {"product": "curl", "version": "7.78.0"}]}
+            ]}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            vec!["openssl", "nginx", "curl"],
+            composition
+                .queries()
+                .iter()
+                .map(|query| query.product.as_str())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec!["CVE-2021-3449 openssl", "CVE-2021-3449 nginx"],
+            ids(evaluate(&composition, &mut search).unwrap())
+        );
+    }
+
+    #[test]
+    fn can_reject_invalid_compositions() {
+        let empty: Composition = serde_json::from_str(r#"{"any": [{"all": []}]}"#).unwrap();
+        assert!(empty.validate().is_err());
+
+        let query = r#"{"product": "openssl", "version": "1.1.1k"}"#;
+        let many: Composition = serde_json::from_str(&format!(
+            r#"{{"any": [{}]}}"#,
+            vec![query; MAX_QUERIES + 1].join(",")
+        ))
+        .unwrap();
+        assert!(many.validate().is_err());
+    }
+}
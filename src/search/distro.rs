@@ -0,0 +1,135 @@
+//! Searches of the packages of a distribution release, matched against the fixes backported
+//! by the distribution rather than the upstream versions.
+
+use super::Query;
+use crate::sources::{almalinux, alpine, amazon, debian, oracle, redhat, rocky, suse, ubuntu};
+
+/// Distributions supported by the `distro` field of the queries.
+pub const DISTROS: &[&str] = &[
+    "alpine",
+    "debian",
+    "ubuntu",
+    "rhel",
+    "sles",
+    "opensuse-leap",
+    "opensuse-tumbleweed",
+    "amzn",
+    "ol",
+    "rocky",
+    "almalinux",
+];
+
+/// Rewrites the query of a package of a distribution release, like `openssl` with
+/// `alpine:3.19`, into the query of its product in the records of the distribution, like
+/// `alpine-3.19-openssl`. The queries without a distribution are left as they are.
+pub fn resolve(query: &Query) -> Result<Query, String> {
+    let distro = match &query.distro {
+        Some(distro) => distro,
+        None => return Ok(query.clone()),
+    };
+
+    let (name, release) = distro
+        .split_once(':')
+        .ok_or("invalid distro, must be like alpine:3.19")?;
+    let product = match name {
+        "alpine" => alpine::branch(release).map(|branch| alpine::product(&branch, &query.product)),
+        "debian" => {
+            debian::codename(release).map(|codename| debian::product(codename, &query.product))
+        }
+        "ubuntu" => {
+            ubuntu::codename(release).map(|codename| ubuntu::product(codename, &query.product))
+        }
+        "rhel" => redhat::release(release).map(|release| redhat::product(&release, &query.product)),
+        "sles" | "opensuse-leap" | "opensuse-tumbleweed" => {
+            suse::release(name, release).map(|release| suse::product(&release, &query.product))
+        }
+        "amzn" => amazon::release(release).map(|release| amazon::product(&release, &query.product)),
+        "ol" => oracle::release(release).map(|release| oracle::product(&release, &query.product)),
+        "rocky" => rocky::release(release).map(|release| rocky::product(&release, &query.product)),
+        "almalinux" => {
+            almalinux::release(release).map(|release| almalinux::product(&release, &query.product))
+        }
+        _ => {
+            return Err(format!(
+                "unsupported distro {}, must be one of {}",
+                name,
+                DISTROS.join(", ")
+            ))
+        }
+    }
+    .ok_or_else(|| format!("unknown release {} of {}", release, name))?;
+
+    Ok(Query {
+        vendor: None,
+        product,
+        version: query.version.clone(),
+        strictness: query.strictness,
+        ..Default::default()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::resolve;
+    use crate::search::Query;
+
+    fn query(distro: &str) -> Query {
+        Query {
+            vendor: None,
+            product: "openssl".into(),
+            version: Some("3.1.4-r0".into()),
+            distro: Some(distro.into()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn can_resolve_distro_packages() {
+        let resolved = resolve(&query("alpine:3.19")).unwrap();
+        assert_eq!("alpine-3.19-openssl", resolved.product);
+        assert_eq!(Some("3.1.4-r0".to_string()), resolved.version);
+        assert_eq!(None, resolved.distro);
+
+        assert_eq!(
+            "alpine-3.19-openssl",
+            resolve(&query("alpine:3.19.1")).unwrap().product
+        );
+        assert_eq!(
+            "debian-bullseye-openssl",
+            resolve(&query("debian:11")).unwrap().product
+        );
+        assert_eq!(
+            "ubuntu-focal-openssl",
+            resolve(&query("ubuntu:20.04")).unwrap().product
+        );
+        assert_eq!(
+            "rhel-8-openssl",
+            resolve(&query("rhel:8.5")).unwrap().product
+        );
+        assert_eq!(
+            "sles-15-sp4-openssl",
+            resolve(&query("sles:15.4")).unwrap().product
+        );
+        assert_eq!(
+            "amzn-2023-openssl",
+            resolve(&query("amzn:2023")).unwrap().product
+        );
+        assert_eq!("ol-8-openssl", resolve(&query("ol:8.7")).unwrap().product);
+        assert_eq!(
+            "rocky-9-openssl",
+            resolve(&query("rocky:9.2")).unwrap().product
+        );
+        assert_eq!(
+            "almalinux-8-openssl",
+            resolve(&query("almalinux:8")).unwrap().product
+        );
+    }
+
+    #[test]
+    fn can_reject_unknown_distros() {
+        assert!(resolve(&query("alpine")).is_err());
+        assert!(resolve(&query("gentoo:2.14")).is_err());
+        assert!(resolve(&query("alpine:latest")).is_err());
+        assert!(resolve(&query("ubuntu:19.04")).is_err());
+    }
+}
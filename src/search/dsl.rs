@@ -0,0 +1,723 @@
+use std::cmp::Ordering;
+use std::collections::HashSet;
+use std::iter::Peekable;
+use std::ops::Bound;
+use std::str::Chars;
+
+use version_compare::Cmp;
+
+use super::{enrichment::Enrichment, Query};
+use crate::db::{models, Database};
+
+/// Comparison operator of a term.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Op {
+    Eq,
+    Gt,
+    Ge,
+    Lt,
+    Le,
+}
+
+impl Op {
+    fn holds(self, ordering: Ordering) -> bool {
+        match self {
+            Self::Eq => ordering == Ordering::Equal,
+            Self::Gt => ordering == Ordering::Greater,
+            Self::Ge => ordering != Ordering::Less,
+            Self::Lt => ordering == Ordering::Less,
+            Self::Le => ordering != Ordering::Greater,
+        }
+    }
+}
+
+/// Boolean expression over the fields of the CVEs found by the search.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Filter {
+    And(Box<Filter>, Box<Filter>),
+    Or(Box<Filter>, Box<Filter>),
+    Not(Box<Filter>),
+    Severity(Op, u8),
+    Score(Op, f64),
+    Source(String),
+    Cve(String),
+    Vector(String),
+    Kev(bool),
+}
+
+const SEVERITIES: &[&str] = &["NONE", "LOW", "MEDIUM", "HIGH", "CRITICAL"];
+
+pub(crate) fn severity_rank(severity: &str) -> Option<u8> {
+    SEVERITIES
+        .iter()
+        .position(|s| s.eq_ignore_ascii_case(severity))
+        .map(|p| p as u8)
+}
+
+impl Filter {
+    /// Whether the CVE matches, looking up the known exploited ones in the enrichment.
+    pub fn matches(&self, cve: &models::CVE, enrichment: &Enrichment) -> bool {
+        match self {
+            Self::And(a, b) => a.matches(cve, enrichment) && b.matches(cve, enrichment),
+            Self::Or(a, b) => a.matches(cve, enrichment) || b.matches(cve, enrichment),
+            Self::Not(a) => !a.matches(cve, enrichment),
+            Self::Severity(op, rank) => severity_rank(&cve.severity)
+                .map(|r| op.holds(r.cmp(rank)))
+                .unwrap_or(false),
+            Self::Score(op, score) => cve
+                .score
+                .partial_cmp(score)
+                .map(|o| op.holds(o))
+                .unwrap_or(false),
+            Self::Source(source) => cve.source.eq_ignore_ascii_case(source),
+            Self::Cve(pattern) => match pattern.strip_suffix('*') {
+                Some(prefix) => cve.cve.to_uppercase().starts_with(&prefix.to_uppercase()),
+                None => cve.cve.eq_ignore_ascii_case(pattern),
+            },
+            Self::Vector(vector) => cve
+                .vector
+                .as_deref()
+                .map(|v| v.eq_ignore_ascii_case(vector))
+                .unwrap_or(false),
+            Self::Kev(flag) => enrichment.exploited.of(&cve.cve).known_exploited == *flag,
+        }
+    }
+}
+
+/// Maximum number of versions searched for a version range.
+pub const MAX_RANGE_VERSIONS: usize = 256;
+
+/// Range of versions like `[1.1.1,1.1.1n)`, with `[`/`]` including the bound, `(`/`)`
+/// excluding it and either bound left empty to leave the range open on that side.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VersionRange {
+    pub start: Bound<String>,
+    pub end: Bound<String>,
+}
+
+fn is_valid_version(version: &str) -> bool {
+    version_compare::compare_to(version, "1.0.0", Cmp::Ne).is_ok()
+}
+
+impl VersionRange {
+    pub fn parse(value: &str) -> Result<Self, String> {
+        let invalid = || format!("invalid version range '{}'", value);
+
+        let inner = value
+            .get(1..value.len().saturating_sub(1))
+            .ok_or_else(invalid)?;
+        let (start, end) = inner.split_once(',').ok_or_else(invalid)?;
+        let bound = |version: &str, inclusive: bool| -> Result<Bound<String>, String> {
+            let version = version.trim();
+            if version.is_empty() {
+                Ok(Bound::Unbounded)
+            } else if !is_valid_version(version) {
+                Err(format!("invalid version '{}' in range", version))
+            } else if inclusive {
+                Ok(Bound::Included(version.into()))
+            } else {
+                Ok(Bound::Excluded(version.into()))
+            }
+        };
+
+        let start = match value.chars().next() {
+            Some('[') => bound(start, true)?,
+            Some('(') => bound(start, false)?,
+            _ => return Err(invalid()),
+        };
+        let end = match value.chars().last() {
+            Some(']') => bound(end, true)?,
+            Some(')') => bound(end, false)?,
+            _ => return Err(invalid()),
+        };
+        if start == Bound::Unbounded && end == Bound::Unbounded {
+            return Err(format!("the version range '{}' must have a bound", value));
+        }
+
+        Ok(Self { start, end })
+    }
+
+    pub fn contains(&self, version: &str) -> bool {
+        let holds = |bound: &str, op| version_compare::compare_to(version, bound, op) == Ok(true);
+
+        (match &self.start {
+            Bound::Included(start) => holds(start, Cmp::Ge),
+            Bound::Excluded(start) => holds(start, Cmp::Gt),
+            Bound::Unbounded => true,
+        }) && (match &self.end {
+            Bound::Included(end) => holds(end, Cmp::Le),
+            Bound::Excluded(end) => holds(end, Cmp::Lt),
+            Bound::Unbounded => true,
+        })
+    }
+
+    /// Versions of the product to search: the ones of the CPE dictionary within the range,
+    /// along with the bounds included.
+    pub fn versions(&self, db: &Database, query: &Query) -> Result<Vec<String>, String> {
+        let mut versions: Vec<String> = db
+            .get_dictionary_versions(query.vendor.as_deref(), &query.product)?
+            .into_iter()
+            .filter(|version| self.contains(version))
+            .collect();
+        for bound in [&self.start, &self.end] {
+            if let Bound::Included(version) = bound {
+                versions.push(version.clone());
+            }
+        }
+        versions.sort_unstable();
+        versions.dedup();
+
+        if versions.len() > MAX_RANGE_VERSIONS {
+            return Err(format!(
+                "the version range matches more than {} versions, narrow it down",
+                MAX_RANGE_VERSIONS
+            ));
+        }
+        Ok(versions)
+    }
+}
+
+/// A parsed search expression: the query selecting the product, or its versions within a
+/// range, along with the filter applied to its CVEs.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Expression {
+    pub query: Query,
+    pub versions: Option<VersionRange>,
+    pub filter: Option<Filter>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    And,
+    Or,
+    Not,
+    Open,
+    Close,
+    Term(String, Op, String),
+}
+
+fn read_value(chars: &mut Peekable<Chars>) -> Result<String, String> {
+    let mut value = String::new();
+
+    // version ranges, like [1.1.1,1.1.1n), are read up to their closing bracket
+    if chars.peek() == Some(&'[') || chars.peek() == Some(&'(') {
+        for c in chars {
+            value.push(c);
+            if c == ']' || c == ')' {
+                return Ok(value);
+            }
+        }
+        return Err("unterminated version range".into());
+    }
+
+    if chars.peek() == Some(&'"') {
+        chars.next();
+        loop {
+            match chars.next() {
+                Some('"') => return Ok(value),
+                Some('\\') => value.extend(chars.next()),
+                Some(c) => value.push(c),
+                None => return Err("unterminated quoted value".into()),
+            }
+        }
+    }
+
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() || c == '(' || c == ')' {
+            break;
+        }
+        value.push(c);
+        chars.next();
+    }
+
+    Ok(value)
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = vec![];
+    let mut chars = input.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+            continue;
+        }
+        if c == '(' || c == ')' {
+            chars.next();
+            tokens.push(if c == '(' { Token::Open } else { Token::Close });
+            continue;
+        }
+
+        let mut field = String::new();
+        while let Some(&c) = chars.peek() {
+            if c.is_ascii_alphanumeric() || c == '_' {
+                field.push(c);
+                chars.next();
+            } else {
+                break;
+            }
+        }
+
+        let op = match chars.peek() {
+            Some(':') | Some('=') => {
+                chars.next();
+                Some(Op::Eq)
+            }
+            Some('>') | Some('<') => {
+                let greater = chars.next() == Some('>');
+                let inclusive = chars.peek() == Some(&'=');
+                if inclusive {
+                    chars.next();
+                }
+                Some(match (greater, inclusive) {
+                    (true, true) => Op::Ge,
+                    (true, false) => Op::Gt,
+                    (false, true) => Op::Le,
+                    (false, false) => Op::Lt,
+                })
+            }
+            _ => None,
+        };
+
+        match (field.as_str(), op) {
+            ("AND", None) => tokens.push(Token::And),
+            ("OR", None) => tokens.push(Token::Or),
+            ("NOT", None) => tokens.push(Token::Not),
+            ("", _) => {
+                return Err(format!(
+                    "unexpected '{}'",
+                    chars.next().map(String::from).unwrap_or_default()
+                ))
+            }
+            (_, None) => return Err(format!("expected an operator after '{}'", field)),
+            (_, Some(op)) => {
+                let value = read_value(&mut chars)?;
+                if value.is_empty() {
+                    return Err(format!("missing value for '{}'", field));
+                }
+                tokens.push(Token::Term(field.to_lowercase(), op, value));
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+/// Syntax tree of the expression before the query terms are separated from the filters.
+#[derive(Debug, Clone, PartialEq)]
+enum Node {
+    And(Box<Node>, Box<Node>),
+    Or(Box<Node>, Box<Node>),
+    Not(Box<Node>),
+    Term(String, Op, String),
+}
+
+/// Recursive descent parser, `NOT` binding tighter than `AND`, binding tighter than `OR`.
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn or(&mut self) -> Result<Node, String> {
+        let mut node = self.and()?;
+        while self.peek() == Some(&Token::Or) {
+            self.next();
+            node = Node::Or(Box::new(node), Box::new(self.and()?));
+        }
+        Ok(node)
+    }
+
+    fn and(&mut self) -> Result<Node, String> {
+        let mut node = self.not()?;
+        loop {
+            match self.peek() {
+                Some(Token::And) => {
+                    self.next();
+                }
+                // terms next to each other are implicitly in AND
+                Some(Token::Not) | Some(Token::Open) | Some(Token::Term(..)) => {}
+                _ => return Ok(node),
+            }
+            node = Node::And(Box::new(node), Box::new(self.not()?));
+        }
+    }
+
+    fn not(&mut self) -> Result<Node, String> {
+        match self.next() {
+            Some(Token::Not) => Ok(Node::Not(Box::new(self.not()?))),
+            Some(Token::Open) => {
+                let node = self.or()?;
+                match self.next() {
+                    Some(Token::Close) => Ok(node),
+                    _ => Err("missing closing parenthesis".into()),
+                }
+            }
+            Some(Token::Term(field, op, value)) => Ok(Node::Term(field, op, value)),
+            Some(token) => Err(format!("unexpected {:?}", token)),
+            None => Err("unexpected end of expression".into()),
+        }
+    }
+}
+
+fn is_query_field(field: &str) -> bool {
+    matches!(
+        field,
+        "product"
+            | "vendor"
+            | "version"
+            | "target_sw"
+            | "part"
+            | "distro"
+            | "os"
+            | "update"
+            | "edition"
+            | "strictness"
+    )
+}
+
+/// Splits the conjuncts at the top level of the tree.
+fn conjuncts(node: Node, into: &mut Vec<Node>) {
+    match node {
+        Node::And(a, b) => {
+            conjuncts(*a, into);
+            conjuncts(*b, into);
+        }
+        node => into.push(node),
+    }
+}
+
+fn filter(node: Node) -> Result<Filter, String> {
+    Ok(match node {
+        Node::And(a, b) => Filter::And(Box::new(filter(*a)?), Box::new(filter(*b)?)),
+        Node::Or(a, b) => Filter::Or(Box::new(filter(*a)?), Box::new(filter(*b)?)),
+        Node::Not(a) => Filter::Not(Box::new(filter(*a)?)),
+        Node::Term(field, op, value) => match (field.as_str(), op) {
+            ("severity", op) => Filter::Severity(
+                op,
+                severity_rank(&value).ok_or_else(|| format!("invalid severity '{}'", value))?,
+            ),
+            ("score", op) => Filter::Score(
+                op,
+                value
+                    .parse::<f64>()
+                    .map_err(|_| format!("invalid score '{}'", value))?,
+            ),
+            ("source", Op::Eq) => Filter::Source(value),
+            ("cve", Op::Eq) => Filter::Cve(value),
+            ("vector", Op::Eq) => Filter::Vector(value),
+            ("kev", Op::Eq) => Filter::Kev(
+                value
+                    .parse::<bool>()
+                    .map_err(|_| format!("invalid kev '{}', must be true or false", value))?,
+            ),
+            (field, _) if is_query_field(field) => {
+                return Err(format!(
+                    "'{}' can't be used in filters, only combined with AND at the top level of a search",
+                    field
+                ))
+            }
+            ("source", _) | ("cve", _) | ("vector", _) | ("kev", _) => {
+                return Err(format!("'{}' can only be compared with ':'", field))
+            }
+            (field, _) => return Err(format!("unknown field '{}'", field)),
+        },
+    })
+}
+
+fn parse_tree(input: &str) -> Result<Node, String> {
+    let mut parser = Parser {
+        tokens: tokenize(input)?,
+        pos: 0,
+    };
+    let tree = parser.or()?;
+    if let Some(token) = parser.peek() {
+        return Err(format!("unexpected {:?}", token));
+    }
+    Ok(tree)
+}
+
+/// Parses an expression made of filter terms only, like `vector:NETWORK AND severity>=HIGH`.
+pub fn parse_filter(input: &str) -> Result<Filter, String> {
+    filter(parse_tree(input)?)
+}
+
+/// Parses a search expression such as
+/// `product:openssl AND version:1.1.1k AND (severity>=HIGH OR score>7.5)`.
+///
+/// The `product`, `vendor`, `version`, `target_sw`, `part`, `distro`, `os`, `update`,
+/// `edition` and `strictness` terms select the CVEs like the fields of the search API, so they
+/// must be combined with `AND` at the top level, the `version` being either exact or a range
+/// like `[1.1.1,1.1.1n)`; the other terms (`severity`, `score`, `source`, `cve`, `vector` and
+/// `kev`) filter the CVEs found and can be freely combined with `AND`, `OR`, `NOT` and
+/// parentheses.
+pub fn parse(input: &str) -> Result<Expression, String> {
+    let mut terms = vec![];
+    conjuncts(parse_tree(input)?, &mut terms);
+
+    let (mut vendor, mut product, mut version) = (None, None, None);
+    let (mut target_sw, mut part, mut distro, mut os) = (None, None, None, None);
+    let (mut update, mut edition, mut strictness) = (None, None, None);
+    let mut versions = None;
+    let mut filters = vec![];
+
+    for term in terms {
+        match term {
+            Node::Term(field, op, value) if is_query_field(&field) => {
+                if op != Op::Eq {
+                    return Err(format!("'{}' can only be compared with ':'", field));
+                }
+                if value.starts_with('[') || value.starts_with('(') {
+                    if field != "version" {
+                        return Err(format!("'{}' can't be a range", field));
+                    }
+                    if versions.replace(VersionRange::parse(&value)?).is_some() {
+                        return Err("'version' can only be specified once".into());
+                    }
+                    continue;
+                }
+                let slot = match field.as_str() {
+                    "vendor" => &mut vendor,
+                    "product" => &mut product,
+                    "target_sw" => &mut target_sw,
+                    "part" => &mut part,
+                    "distro" => &mut distro,
+                    "os" => &mut os,
+                    "update" => &mut update,
+                    "edition" => &mut edition,
+                    "strictness" => &mut strictness,
+                    _ => &mut version,
+                };
+                if slot.replace(value).is_some() {
+                    return Err(format!("'{}' can only be specified once", field));
+                }
+            }
+            node => filters.push(filter(node)?),
+        }
+    }
+
+    if version.is_some() && versions.is_some() {
+        return Err("'version' can only be specified once".into());
+    }
+
+    let filter = filters
+        .into_iter()
+        .reduce(|a, b| Filter::And(Box::new(a), Box::new(b)));
+
+    Ok(Expression {
+        query: Query {
+            vendor,
+            product: product.ok_or("the expression must specify a product")?,
+            version,
+            target_sw,
+            part,
+            distro,
+            os,
+            update,
+            edition,
+            strictness: strictness.map(|value| value.parse()).transpose()?,
+        },
+        versions,
+        filter,
+    })
+}
+
+/// Runs the query of the expression, for each version of its range if any, and filters the
+/// CVEs found, returned along with their enrichment.
+pub fn query(
+    db: &Database,
+    expression: &Expression,
+) -> Result<(Vec<models::CVE>, Enrichment), String> {
+    let mut cves = match &expression.versions {
+        Some(range) => {
+            let mut found = HashSet::new();
+            let mut cves = vec![];
+            for version in range.versions(db, &expression.query)? {
+                let query = Query {
+                    version: Some(version),
+                    ..expression.query.clone()
+                };
+                for cve in super::query(db, &query)? {
+                    if found.insert(cve.id) {
+                        cves.push(cve);
+                    }
+                }
+            }
+            cves
+        }
+        None => super::query(db, &expression.query)?,
+    };
+
+    let enrichment = Enrichment::load(db, &cves)?;
+    if let Some(filter) = &expression.filter {
+        cves.retain(|cve| filter.matches(cve, &enrichment));
+    }
+    Ok((cves, enrichment))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::Bound;
+
+    use chrono::Utc;
+
+    use super::{parse, Filter, Op, VersionRange};
+    use crate::db::models;
+    use crate::search::{enrichment::Enrichment, testing, Query};
+    use crate::sources::kev;
+
+    fn cve(id: &str, source: &str, score: f64, severity: &str) -> models::CVE {
+        models::CVE {
+            source: source.into(),
+            score,
+            severity: severity.into(),
+            vector: Some("NETWORK".into()),
+            ..testing::cve(id, "openssl")
+        }
+    }
+
+    #[test]
+    fn can_parse_expressions() {
+        let expression =
+            parse("product:openssl AND vendor:openssl version:1.1.1k AND severity>=HIGH").unwrap();
+
+        assert_eq!(
+            Query {
+                vendor: Some("openssl".into()),
+                product: "openssl".into(),
+                version: Some("1.1.1k".into()),
+                ..Default::default()
+            },
+            expression.query
+        );
+        assert_eq!(Some(Filter::Severity(Op::Ge, 3)), expression.filter);
+
+        let expression = parse("product:woocommerce AND target_sw:wordpress").unwrap();
+        assert_eq!(Some("wordpress".into()), expression.query.target_sw);
+
+        let expression = parse("product:h410c AND part:h AND version:1.2").unwrap();
+        assert_eq!(Some("h".into()), expression.query.part);
+
+        let expression = parse("product:openssl AND distro:alpine:3.19").unwrap();
+        assert_eq!(Some("alpine:3.19".into()), expression.query.distro);
+
+        let expression = parse("product:meetings AND os:o:microsoft:windows:10").unwrap();
+        assert_eq!(Some("o:microsoft:windows:10".into()), expression.query.os);
+
+        let expression = parse("product:openssh AND version:7.4 AND update:p1").unwrap();
+        assert_eq!(Some("p1".into()), expression.query.update);
+
+        let expression = parse("product:\"my product\"").unwrap();
+        assert_eq!("my product", expression.query.product);
+        assert_eq!(None, expression.filter);
+    }
+
+    #[test]
+    fn can_filter_cves() {
+        let filter = parse(
+            "product:openssl AND (severity:CRITICAL OR score>=7.5) AND NOT source:npm AND cve:CVE-2021-*",
+        )
+        .unwrap()
+        .filter
+        .unwrap();
+
+        let enrichment = Enrichment::default();
+        assert!(filter.matches(&cve("CVE-2021-3711", "nist", 9.8, "CRITICAL"), &enrichment));
+        assert!(filter.matches(&cve("CVE-2021-3449", "nist", 7.5, "HIGH"), &enrichment));
+        assert!(!filter.matches(&cve("CVE-2021-3712", "nist", 7.4, "HIGH"), &enrichment));
+        assert!(!filter.matches(&cve("CVE-2021-3711", "npm", 9.8, "CRITICAL"), &enrichment));
+        assert!(!filter.matches(&cve("CVE-2020-1967", "nist", 9.8, "CRITICAL"), &enrichment));
+    }
+
+    #[test]
+    fn can_filter_known_exploited_cves() {
+        let enrichment = Enrichment {
+            exploited: kev::Exploited::with(vec![models::KnownExploited {
+                id: 0,
+                created_at: Utc::now(),
+                updated_at: None,
+                cve: "CVE-2022-0778".into(),
+                vendor: "OpenSSL".into(),
+                product: "OpenSSL".into(),
+                name: "OpenSSL Infinite Loop Vulnerability".into(),
+                date_added: "2022-03-25".into(),
+                due_date: "2022-04-15".into(),
+                required_action: "Apply updates per vendor instructions.".into(),
+                ransomware: false,
+            }]),
+            ..Default::default()
+        };
+
+        let filter = parse("product:openssl AND kev:true")
+            .unwrap()
+            .filter
+            .unwrap();
+        assert_eq!(Filter::Kev(true), filter);
+        assert!(filter.matches(&cve("CVE-2022-0778", "nist", 7.5, "HIGH"), &enrichment));
+        assert!(!filter.matches(&cve("CVE-2021-3711", "nist", 9.8, "CRITICAL"), &enrichment));
+
+        let filter = parse("product:openssl AND NOT kev:true")
+            .unwrap()
+            .filter
+            .unwrap();
+        assert!(!filter.matches(&cve("CVE-2022-0778", "nist", 7.5, "HIGH"), &enrichment));
+        assert!(filter.matches(&cve("CVE-2021-3711", "nist", 9.8, "CRITICAL"), &enrichment));
+    }
+
+    #[test]
+    fn can_parse_version_ranges() {
+        let expression = parse("product:openssl AND version:[1.1.1,1.1.1n) AND kev:false").unwrap();
+        assert_eq!(None, expression.query.version);
+        assert_eq!(Some(Filter::Kev(false)), expression.filter);
+
+        let range = expression.versions.unwrap();
+        assert_eq!(Bound::Included("1.1.1".into()), range.start);
+        assert_eq!(Bound::Excluded("1.1.1n".into()), range.end);
+        assert!(range.contains("1.1.1"));
+        assert!(range.contains("1.1.1k"));
+        assert!(!range.contains("1.1.1n"));
+        assert!(!range.contains("1.1.0l"));
+
+        let range = VersionRange::parse("(2.0,]").unwrap();
+        assert_eq!(Bound::Excluded("2.0".into()), range.start);
+        assert_eq!(Bound::Unbounded, range.end);
+        assert!(!range.contains("2.0"));
+        assert!(range.contains("2.4.1"));
+
+        let range = VersionRange::parse("[,1.2]").unwrap();
+        assert!(range.contains("1.2"));
+        assert!(range.contains("0.9"));
+        assert!(!range.contains("1.3"));
+
+        assert!(VersionRange::parse("[,)").is_err());
+        assert!(VersionRange::parse("[1.0]").is_err());
+        assert!(VersionRange::parse("[1.0,2.0").is_err());
+    }
+
+    #[test]
+    fn can_report_errors() {
+        assert!(parse("").is_err());
+        assert!(parse("severity>=HIGH").is_err());
+        assert!(parse("product:openssl OR product:libressl").is_err());
+        assert!(parse("product:openssl product:libressl").is_err());
+        assert!(parse("product:openssl AND version:1.1.1k version:[1.1.1,1.1.1n)").is_err());
+        assert!(parse("product:openssl AND version:[1.1.1,1.1.1n").is_err());
+        assert!(parse("product:openssl AND vendor:[a,b]").is_err());
+        assert!(parse("product:openssl AND kev:maybe").is_err());
+        assert!(parse("product:openssl AND kev>true").is_err());
+        assert!(parse("product:openssl AND (severity:HIGH").is_err());
+        assert!(parse("product:openssl AND severity:URGENT").is_err());
+        assert!(parse("product:openssl AND").is_err());
+        assert!(parse("product:").is_err());
+    }
+}
@@ -0,0 +1,47 @@
+use kepler_types::api::Cve;
+
+use crate::db::{models, Database};
+use crate::sources::{cwe, epss, exploitdb, kev, metasploit};
+
+/// Data from the sources not tied to products, looked up for the CVEs of a response.
+#[derive(Debug, Default)]
+pub struct Enrichment {
+    pub exploited: kev::Exploited,
+    pub epss: epss::Scores,
+    pub exploits: exploitdb::Archive,
+    pub metasploit: metasploit::Modules,
+    pub weaknesses: cwe::Weaknesses,
+}
+
+impl Enrichment {
+    pub fn load(db: &Database, cves: &[models::CVE]) -> Result<Self, String> {
+        Ok(Self {
+            exploited: kev::Exploited::load(db, cves)?,
+            epss: epss::Scores::load(db, cves)?,
+            exploits: exploitdb::Archive::load(db, cves)?,
+            metasploit: metasploit::Modules::load(db, cves)?,
+            weaknesses: cwe::Weaknesses::load(db, cves)?,
+        })
+    }
+
+    /// Returns the v1 representation of the record along with its enrichment.
+    pub fn cve(&self, record: models::CVE) -> Cve {
+        Cve {
+            exploitation: self.exploited.of(&record.cve),
+            epss: self.epss.of(&record.cve),
+            exploits: self.exploits.of(&record.cve),
+            metasploit: self.metasploit.of(&record.cve),
+            weaknesses: self.weaknesses.of(&record.cve),
+            source: record.source,
+            vendor: record.vendor,
+            product: record.product,
+            cve: record.cve,
+            summary: record.summary,
+            score: record.score,
+            severity: record.severity,
+            vector: record.vector,
+            references: record.references.0,
+            adjusted_score: None,
+        }
+    }
+}
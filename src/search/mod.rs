@@ -4,91 +4,190 @@ use std::time::Instant;
 use lazy_static::lazy_static;
 use log::info;
 use lru::LruCache;
-use serde::Deserialize;
 use version_compare::Cmp;
 
 use crate::db::{models, Database};
-use crate::sources::{nist, npm, Source};
+use crate::sources::{
+    almalinux, alpine, amazon, cisco, cnnvd, cvelist, debian, gentoo, ghsa, govulndb, jvn, msrc,
+    nist, npm, oracle, osv, redhat, rocky, suse, ubuntu, Source,
+};
+
+pub mod bloom;
+pub mod coalesce;
+pub mod compose;
+pub mod distro;
+pub mod dsl;
+pub mod enrichment;
+pub mod preload;
+pub mod profile;
+pub mod similarity;
+pub mod suggest;
+#[cfg(test)]
+pub mod testing;
+pub mod warmup;
+
+pub use kepler_types::api::{Query, Strictness};
+
+/// Number of search results kept in memory.
+pub const CACHE_SIZE: usize = 4096;
 
 lazy_static! {
-    static ref CACHE: Mutex<LruCache<Query, Vec<models::CVE>>> = Mutex::new(LruCache::new(4096));
+    static ref CACHE: Mutex<LruCache<Query, Vec<models::CVE>>> =
+        Mutex::new(LruCache::new(CACHE_SIZE));
+    static ref IN_FLIGHT: coalesce::Group<Query, Result<Vec<models::CVE>, String>> =
+        coalesce::Group::default();
 }
 
-#[derive(Debug, Deserialize, PartialEq, Eq, Hash, Clone)]
-pub struct Query {
-    pub vendor: Option<String>,
-    pub product: String,
-    pub version: Option<String>,
+/// Deserializes the object of a record as its data source.
+pub(crate) fn deserialize(cve: &models::CVE, obj: &models::Object) -> Result<Source, String> {
+    // matches the name of the source of the record, deserializing its object as the variant
+    macro_rules! sources {
+        ($($name:path => $variant:ident,)*) => {
+            match cve.source.as_str() {
+                $($name => serde_json::from_str(&obj.data).map(Source::$variant),)*
+                _ => return Err(format!("unsupported data source {}", cve.source)),
+            }
+        };
+    }
+
+    sources! {
+        nist::SOURCE_NAME => Nist,
+        npm::SOURCE_NAME => Npm,
+        nist::heuristic::SOURCE_NAME => Heuristic,
+        osv::SOURCE_NAME => Osv,
+        govulndb::SOURCE_NAME => GoVulnDb,
+        ghsa::SOURCE_NAME => Ghsa,
+        redhat::SOURCE_NAME => RedHat,
+        debian::SOURCE_NAME => Debian,
+        ubuntu::SOURCE_NAME => Ubuntu,
+        alpine::SOURCE_NAME => Alpine,
+        suse::SOURCE_NAME => Suse,
+        gentoo::SOURCE_NAME => Gentoo,
+        amazon::SOURCE_NAME => Amazon,
+        oracle::SOURCE_NAME => Oracle,
+        rocky::SOURCE_NAME => Rocky,
+        almalinux::SOURCE_NAME => AlmaLinux,
+        msrc::SOURCE_NAME => Msrc,
+        cisco::SOURCE_NAME => Cisco,
+        jvn::SOURCE_NAME => Jvn,
+        cnnvd::SOURCE_NAME => Cnnvd,
+        cvelist::SOURCE_NAME => CveList,
+    }
+    .map_err(|e| format!("could not deserialize {}: {}", obj.cve, e))
 }
 
 pub fn query(db: &Database, query: &Query) -> Result<Vec<models::CVE>, String> {
     info!("searching query: {:?} ...", query);
 
-    // validate version string
+    // the packages of a distribution release are searched among the records of the distribution
+    let query = &distro::resolve(query)?;
+
+    // validate version string, without its update
     if let Some(ver) = &query.version {
+        let (ver, _) = nist::cpe::split_update(ver);
         if version_compare::compare_to(ver, "1.0.0", Cmp::Ne).is_err() {
             return Err("invalid version string".to_owned());
         }
     }
 
-    let mut cache = CACHE.lock().unwrap();
-    Ok(if let Some(cached) = cache.get(query) {
+    if let Some(part) = &query.part {
+        if !matches!(part.as_str(), "a" | "o" | "h") {
+            return Err("invalid part, must be one of a, o or h".to_owned());
+        }
+    }
+
+    if let Some(os) = &query.os {
+        os.parse::<nist::cpe::Platform>()
+            .map_err(|e| format!("invalid os: {}", e))?;
+    }
+
+    if !bloom::may_match(query) {
+        info!("no records of the product");
+        return Ok(vec![]);
+    }
+
+    if let Some(cached) = CACHE.lock().unwrap().get(query) {
         info!("cache hit");
-        cached.to_vec()
-    } else {
-        info!("cache miss");
+        return Ok(cached.to_vec());
+    }
+    info!("cache miss");
 
-        // fetch potential candidates for this query
-        let start = Instant::now();
-        let candidates = db.search(query.vendor.as_ref(), &query.product)?;
+    // identical searches arriving together, like scans of the same image, run only once
+    let (matches, shared) = IN_FLIGHT.run(query.clone(), || {
+        let matches = search(db, query)?;
+        CACHE.lock().unwrap().put(query.clone(), matches.clone());
+        Ok(matches)
+    });
+    if shared {
+        info!("coalesced with an identical search in flight");
+    }
+
+    matches
+}
 
+/// Searches the records matching the query, in the preloaded index or in the database.
+fn search(db: &Database, query: &Query) -> Result<Vec<models::CVE>, String> {
+    // the preloaded index, when enabled, answers without the database
+    if let Some(index) = preload::get() {
+        let start = Instant::now();
+        let matches = index.query(query);
         info!(
-            "found {} candidates in {:?}",
-            candidates.len(),
+            "found {} matches in the preloaded index in {:?}",
+            matches.len(),
             start.elapsed()
         );
+        return Ok(matches);
+    }
 
-        // deserialize all objects belonging to the potential CVEs
-        let start = Instant::now();
-        let mut matches = vec![];
-        let mut sources = vec![];
+    // fetch potential candidates for this query
+    let start = Instant::now();
+    let candidates = db.search(query.vendor.as_ref(), &query.product)?;
 
-        for (cve, obj) in &candidates {
-            match cve.source.as_str() {
-                nist::SOURCE_NAME => {
-                    if let Ok(cve) = serde_json::from_str(&obj.data) {
-                        sources.push(Source::Nist(cve));
-                    } else {
-                        return Err(format!("could not deserialize {}", obj.cve));
-                    }
-                }
-                npm::SOURCE_NAME => {
-                    if let Ok(adv) = serde_json::from_str(&obj.data) {
-                        sources.push(Source::Npm(adv));
-                    } else {
-                        return Err(format!("could not deserialize {}:\n{}", obj.cve, obj.data));
-                    }
-                }
-                _ => return Err(format!("unsupported data source {}", cve.source)),
-            }
-        }
+    info!(
+        "found {} candidates in {:?}",
+        candidates.len(),
+        start.elapsed()
+    );
 
-        info!(
-            "deserialized the {} candidates in {:?}",
-            sources.len(),
-            start.elapsed()
-        );
+    // deserialize all objects belonging to the potential CVEs
+    let start = Instant::now();
+    let mut matches = vec![];
+    let mut sources = vec![];
 
-        let start = Instant::now();
-        for (index, object) in sources.iter_mut().enumerate() {
-            if object.is_match(query) {
-                matches.push(candidates[index].0.clone());
-            }
+    for (cve, obj) in &candidates {
+        sources.push(deserialize(cve, obj)?);
+    }
+
+    info!(
+        "deserialized the {} candidates in {:?}",
+        sources.len(),
+        start.elapsed()
+    );
+
+    let start = Instant::now();
+    for (index, object) in sources.iter_mut().enumerate() {
+        if object.is_match(query) {
+            matches.push(candidates[index].0.clone());
         }
+    }
 
-        info!("found {} matches in {:?}", matches.len(), start.elapsed());
+    info!("found {} matches in {:?}", matches.len(), start.elapsed());
 
-        cache.put(query.clone(), matches.clone());
-        matches
-    })
+    Ok(matches)
+}
+
+/// Forgets the cached search results, stale once the records change.
+pub fn clear_cache() {
+    CACHE.lock().unwrap().clear();
+}
+
+pub fn query_all(
+    db: &Database,
+    queries: &[Query],
+) -> Result<Vec<(Query, Vec<models::CVE>)>, String> {
+    let mut results = vec![];
+    for q in queries {
+        results.push((q.clone(), query(db, q)?));
+    }
+    Ok(results)
 }
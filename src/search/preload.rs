@@ -0,0 +1,241 @@
+use std::collections::HashMap;
+use std::env;
+use std::sync::{Arc, Mutex, RwLock};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use lazy_static::lazy_static;
+use log::{info, warn};
+
+use crate::db::{models, Database, Pool};
+use crate::sources::Source;
+
+use super::Query;
+
+const DEFAULT_INTERVAL: Duration = Duration::from_secs(60);
+
+lazy_static! {
+    static ref INDEX: RwLock<Option<Arc<Index>>> = RwLock::new(None);
+}
+
+/// Candidate record of a product, its data source being parsed lazily when matched.
+struct Entry {
+    cve: models::CVE,
+    source: Mutex<Source>,
+}
+
+/// All the records in memory by product, along with their data sources to match the
+/// versions against.
+pub struct Index {
+    generation: (i64, Option<i32>),
+    products: HashMap<String, Vec<Entry>>,
+}
+
+impl Index {
+    pub fn build(
+        generation: (i64, Option<i32>),
+        records: Vec<(models::CVE, models::Object)>,
+    ) -> Result<Self, String> {
+        let mut products: HashMap<String, Vec<Entry>> = HashMap::new();
+        for (cve, obj) in records {
+            let source = super::deserialize(&cve, &obj)?;
+            products
+                .entry(cve.product.clone())
+                .or_default()
+                .push(Entry {
+                    cve,
+                    source: Mutex::new(source),
+                });
+        }
+
+        Ok(Self {
+            generation,
+            products,
+        })
+    }
+
+    pub fn num_products(&self) -> usize {
+        self.products.len()
+    }
+
+    /// The records matching the query, like a search without the cache would find.
+    pub fn query(&self, query: &Query) -> Vec<models::CVE> {
+        let entries = match self.products.get(&query.product) {
+            Some(entries) => entries,
+            None => return vec![],
+        };
+
+        entries
+            .iter()
+            .filter(|entry| match &query.vendor {
+                Some(vendor) => entry.cve.vendor == *vendor,
+                None => true,
+            })
+            .filter(|entry| entry.source.lock().unwrap().is_match(query))
+            .map(|entry| entry.cve.clone())
+            .collect()
+    }
+}
+
+/// The preloaded index, if any.
+pub fn get() -> Option<Arc<Index>> {
+    INDEX.read().unwrap().clone()
+}
+
+/// Loads the records in memory, unless they didn't change since the last load. Returns
+/// whether they were loaded.
+pub fn load(database: &Database) -> Result<bool, String> {
+    let generation = database.get_records_generation()?;
+    if let Some(index) = get() {
+        if index.generation == generation {
+            return Ok(false);
+        }
+    }
+
+    let start = Instant::now();
+    let index = Index::build(generation, database.get_all_records()?)?;
+    info!(
+        "preloaded {} records of {} products in {:?}",
+        generation.0,
+        index.num_products(),
+        start.elapsed()
+    );
+
+    *INDEX.write().unwrap() = Some(Arc::new(index));
+    // the cached results may predate the new records
+    super::clear_cache();
+
+    Ok(true)
+}
+
+/// How often the preloaded records are checked for changes, if preloading is enabled by
+/// setting `KEPLER_PRELOAD` to `true`. `KEPLER_PRELOAD_INTERVAL` sets it in seconds.
+pub fn interval_from_env() -> Result<Option<Duration>, String> {
+    match env::var("KEPLER_PRELOAD") {
+        Ok(value) if value == "true" || value == "1" => {}
+        Ok(value) if value == "false" || value == "0" => return Ok(None),
+        Ok(value) => return Err(format!("invalid KEPLER_PRELOAD: {}", value)),
+        Err(_) => return Ok(None),
+    }
+
+    match env::var("KEPLER_PRELOAD_INTERVAL") {
+        Ok(value) => match value.parse::<u64>() {
+            Ok(secs) if secs > 0 => Ok(Some(Duration::from_secs(secs))),
+            _ => Err(format!("invalid KEPLER_PRELOAD_INTERVAL: {}", value)),
+        },
+        Err(_) => Ok(Some(DEFAULT_INTERVAL)),
+    }
+}
+
+/// Loads the records now, then reloads them in the background whenever an import changes
+/// them.
+pub fn start(pool: Pool, interval: Duration) -> Result<(), String> {
+    load(&Database(pool.get().map_err(|e| e.to_string())?))?;
+
+    thread::spawn(move || loop {
+        thread::sleep(interval);
+
+        let loaded = pool
+            .get()
+            .map_err(|e| e.to_string())
+            .and_then(|conn| load(&Database(conn)));
+        if let Err(e) = loaded {
+            warn!("could not reload the preloaded records: {}", e);
+        }
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::Index;
+    use crate::db::models;
+    use crate::search::{testing, Query};
+    use crate::sources::nist::heuristic::{self, Guess};
+
+    fn record(
+        id: i32,
+        vendor: &str,
+        product: &str,
+        summary: &str,
+    ) -> (models::CVE, models::Object) {
+        let cve_id = format!("CVE-2022-000{}", id);
+        let guess = Guess {
+            id: cve_id.clone(),
+            mentions: heuristic::extract(summary),
+        };
+
+        (
+            models::CVE {
+                id,
+                source: heuristic::SOURCE_NAME.into(),
+                vendor: vendor.into(),
+                summary: summary.into(),
+                object_id: Some(id),
+                ..testing::cve(&cve_id, product)
+            },
+            models::Object {
+                id,
+                created_at: Utc::now(),
+                updated_at: None,
+                cve: cve_id,
+                data: serde_json::to_string(&guess).unwrap(),
+            },
+        )
+    }
+
+    fn query(vendor: Option<&str>, product: &str, version: &str) -> Query {
+        Query {
+            vendor: vendor.map(str::to_string),
+            product: product.into(),
+            version: Some(version.into()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn can_query_preloaded_records() {
+        let index = Index::build(
+            (3, Some(3)),
+            vec![
+                record(
+                    1,
+                    "@heuristic",
+                    "libxml2",
+                    "Crash in libxml2 before 2.9.11.",
+                ),
+                record(
+                    2,
+                    "@heuristic",
+                    "libxml2",
+                    "Overflow in libxml2 through 2.9.14.",
+                ),
+                record(3, "@heuristic", "busybox", "Injection in busybox 1.33.1."),
+            ],
+        )
+        .unwrap();
+        assert_eq!(2, index.num_products());
+
+        let cves = |query: &Query| -> Vec<String> {
+            index.query(query).into_iter().map(|cve| cve.cve).collect()
+        };
+
+        assert_eq!(
+            vec!["CVE-2022-0001", "CVE-2022-0002"],
+            cves(&query(None, "libxml2", "2.9.10"))
+        );
+        assert_eq!(
+            vec!["CVE-2022-0002"],
+            cves(&query(None, "libxml2", "2.9.12"))
+        );
+        assert!(cves(&query(Some("xmlsoft"), "libxml2", "2.9.10")).is_empty());
+        assert_eq!(
+            vec!["CVE-2022-0003"],
+            cves(&query(Some("@heuristic"), "busybox", "1.33.1"))
+        );
+        assert!(cves(&query(None, "openssl", "1.1.1k")).is_empty());
+    }
+}
@@ -0,0 +1,132 @@
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::env;
+use std::fs;
+use std::sync::Arc;
+
+use serde::Deserialize;
+
+use super::dsl::{self, Filter};
+use super::enrichment::Enrichment;
+use crate::db::models;
+
+/// Condition of a rule, written in the filter syntax of the query language.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(try_from = "String")]
+pub struct Condition(Filter);
+
+impl TryFrom<String> for Condition {
+    type Error = String;
+
+    fn try_from(val: String) -> Result<Self, Self::Error> {
+        dsl::parse_filter(&val).map(Self)
+    }
+}
+
+/// Adjustment of the score of the CVEs matching the condition.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Rule {
+    pub when: Condition,
+    pub adjust: f64,
+}
+
+/// Scoring profile recalibrating the CVSS scores according to an internal risk model.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct Profile {
+    pub rules: Vec<Rule>,
+}
+
+impl Profile {
+    /// Applies the adjustments of all the matching rules, in order, to the CVSS score.
+    pub fn adjusted_score(&self, cve: &models::CVE, enrichment: &Enrichment) -> f64 {
+        let score = self
+            .rules
+            .iter()
+            .filter(|rule| rule.when.0.matches(cve, enrichment))
+            .fold(cve.score, |score, rule| score + rule.adjust);
+
+        (score.clamp(0.0, 10.0) * 10.0).round() / 10.0
+    }
+}
+
+pub type Profiles = HashMap<String, Arc<Profile>>;
+
+/// Name of the profile applied when none is requested.
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// Loads the scoring profiles from the JSON file pointed by the `KEPLER_SCORING_PROFILES`
+/// environment variable, if set.
+pub fn load() -> Result<Profiles, String> {
+    match env::var("KEPLER_SCORING_PROFILES") {
+        Ok(file_name) => fs::read_to_string(&file_name)
+            .map_err(|e| e.to_string())
+            .and_then(|json| {
+                serde_json::from_str::<HashMap<String, Profile>>(&json).map_err(|e| e.to_string())
+            })
+            .map(|profiles| {
+                profiles
+                    .into_iter()
+                    .map(|(name, profile)| (name, Arc::new(profile)))
+                    .collect()
+            })
+            .map_err(|e| format!("could not load scoring profiles from {}: {}", file_name, e)),
+        Err(_) => Ok(Profiles::new()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Profile;
+    use crate::db::models;
+    use crate::search::{enrichment::Enrichment, testing};
+
+    fn cve(score: f64, vector: &str) -> models::CVE {
+        models::CVE {
+            score,
+            vector: Some(vector.into()),
+            ..testing::cve("CVE-2021-3711", "openssl")
+        }
+    }
+
+    #[test]
+    fn can_adjust_scores() {
+        let profile: Profile = serde_json::from_str(
+            r#"{
+                "rules": [
+                    { "when": "vector:NETWORK", "adjust": 1.5 },
+                    { "when": "vector:NETWORK AND severity>=HIGH", "adjust": 0.5 },
+                    { "when": "vector:LOCAL", "adjust": -2 }
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let enrichment = Enrichment::default();
+        assert_eq!(
+            9.5,
+            profile.adjusted_score(&cve(7.5, "NETWORK"), &enrichment)
+        );
+        assert_eq!(
+            10.0,
+            profile.adjusted_score(&cve(9.8, "NETWORK"), &enrichment)
+        );
+        assert_eq!(5.8, profile.adjusted_score(&cve(7.8, "LOCAL"), &enrichment));
+        assert_eq!(0.0, profile.adjusted_score(&cve(1.0, "LOCAL"), &enrichment));
+        assert_eq!(
+            6.1,
+            profile.adjusted_score(&cve(6.1, "ADJACENT_NETWORK"), &enrichment)
+        );
+    }
+
+    #[test]
+    fn rules_must_be_valid_filters() {
+        assert!(serde_json::from_str::<Profile>(
+            r#"{"rules": [{"when": "product:openssl", "adjust": 1}]}"#
+        )
+        .is_err());
+        assert!(serde_json::from_str::<Profile>(
+            r#"{"rules": [{"when": "vector:", "adjust": 1}]}"#
+        )
+        .is_err());
+    }
+}
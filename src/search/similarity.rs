@@ -0,0 +1,320 @@
+use std::collections::{BTreeSet, HashMap};
+
+use log::info;
+
+use crate::db::{models, Database, Pool};
+
+/// Words too common in CVE summaries to tell them apart.
+const STOP_WORDS: &[&str] = &[
+    "a",
+    "an",
+    "and",
+    "are",
+    "as",
+    "at",
+    "be",
+    "before",
+    "by",
+    "can",
+    "could",
+    "for",
+    "from",
+    "has",
+    "have",
+    "in",
+    "is",
+    "it",
+    "its",
+    "may",
+    "of",
+    "on",
+    "or",
+    "that",
+    "the",
+    "this",
+    "to",
+    "via",
+    "when",
+    "which",
+    "with",
+    "allows",
+    "attacker",
+    "attackers",
+    "remote",
+    "vulnerability",
+    "issue",
+    "discovered",
+    "version",
+    "versions",
+    "affected",
+    "user",
+    "users",
+];
+
+/// Number of most relevant terms of every summary taken into account.
+const TERMS_PER_DOCUMENT: usize = 24;
+
+/// Terms found in more than this fraction of the summaries are ignored.
+const MAX_DOCUMENT_FREQUENCY: f64 = 0.05;
+
+/// Weight of the affected products overlap in the similarity, the rest is the summaries
+/// similarity.
+const PRODUCTS_WEIGHT: f64 = 0.2;
+
+/// Splits a summary in lowercase terms, skipping stop words and numbers.
+pub fn tokenize(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric() && c != '_' && c != '-')
+        .map(|token| token.trim_matches('-').to_lowercase())
+        .filter(|token| token.len() > 2)
+        .filter(|token| {
+            !token
+                .chars()
+                .all(|c| c.is_ascii_digit() || c == '.' || c == '-')
+        })
+        .filter(|token| !STOP_WORDS.contains(&token.as_str()))
+        .collect()
+}
+
+/// A CVE as seen by the similarity analysis: its summary and the products it affects.
+#[derive(Debug, Clone)]
+pub struct Document {
+    pub cve: String,
+    pub summary: String,
+    pub products: BTreeSet<String>,
+}
+
+/// Groups the records of the same CVE in a single document.
+pub fn documents(records: Vec<(String, String, String)>) -> Vec<Document> {
+    let mut documents: Vec<Document> = vec![];
+    let mut positions: HashMap<String, usize> = HashMap::new();
+
+    for (cve, product, summary) in records {
+        match positions.get(&cve) {
+            Some(&position) => {
+                documents[position].products.insert(product);
+            }
+            None => {
+                positions.insert(cve.clone(), documents.len());
+                documents.push(Document {
+                    cve,
+                    summary,
+                    products: std::iter::once(product).collect(),
+                });
+            }
+        }
+    }
+
+    documents
+}
+
+/// TF-IDF vectors of the summaries, with an inverted index of their terms to only compare
+/// the documents sharing at least one of them.
+pub struct Index {
+    documents: Vec<Document>,
+    vectors: Vec<Vec<(usize, f64)>>,
+    postings: HashMap<usize, Vec<usize>>,
+}
+
+impl Index {
+    pub fn build(documents: Vec<Document>) -> Self {
+        let mut terms: HashMap<String, usize> = HashMap::new();
+        let mut frequencies: Vec<HashMap<usize, f64>> = vec![];
+        let mut document_frequency: HashMap<usize, usize> = HashMap::new();
+
+        for document in &documents {
+            let mut tf: HashMap<usize, f64> = HashMap::new();
+            for token in tokenize(&document.summary) {
+                let next = terms.len();
+                let term = *terms.entry(token).or_insert(next);
+                *tf.entry(term).or_default() += 1.0;
+            }
+            for term in tf.keys() {
+                *document_frequency.entry(*term).or_default() += 1;
+            }
+            frequencies.push(tf);
+        }
+
+        let num_documents = documents.len() as f64;
+        // with few documents every term would be too common
+        let max_df = ((num_documents * MAX_DOCUMENT_FREQUENCY) as usize).max(2);
+
+        let mut vectors = vec![];
+        let mut postings: HashMap<usize, Vec<usize>> = HashMap::new();
+
+        for (position, tf) in frequencies.into_iter().enumerate() {
+            let mut vector: Vec<(usize, f64)> = tf
+                .into_iter()
+                .filter(|(term, _)| document_frequency[term] <= max_df)
+                .map(|(term, count)| {
+                    let idf = (num_documents / document_frequency[&term] as f64).ln() + 1.0;
+                    (term, (1.0 + count.ln()) * idf)
+                })
+                .collect();
+
+            vector.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+            vector.truncate(TERMS_PER_DOCUMENT);
+
+            let norm = vector.iter().map(|(_, w)| w * w).sum::<f64>().sqrt();
+            if norm > 0.0 {
+                for (_, weight) in vector.iter_mut() {
+                    *weight /= norm;
+                }
+            }
+            vector.sort_by_key(|(term, _)| *term);
+
+            for (term, _) in &vector {
+                postings.entry(*term).or_default().push(position);
+            }
+            vectors.push(vector);
+        }
+
+        Self {
+            documents,
+            vectors,
+            postings,
+        }
+    }
+
+    pub fn documents(&self) -> &[Document] {
+        &self.documents
+    }
+
+    fn products_overlap(&self, a: usize, b: usize) -> f64 {
+        let (a, b) = (&self.documents[a].products, &self.documents[b].products);
+        let union = a.union(b).count();
+        if union == 0 {
+            0.0
+        } else {
+            a.intersection(b).count() as f64 / union as f64
+        }
+    }
+
+    /// The documents most similar to the one at the given position, with their similarity.
+    pub fn related(&self, position: usize, limit: usize, min_score: f64) -> Vec<(usize, f64)> {
+        let mut dot: HashMap<usize, f64> = HashMap::new();
+        for (term, weight) in &self.vectors[position] {
+            for other in &self.postings[term] {
+                if *other == position {
+                    continue;
+                }
+                let other_weight = self.vectors[*other]
+                    .binary_search_by_key(term, |(t, _)| *t)
+                    .map(|index| self.vectors[*other][index].1)
+                    .unwrap_or_default();
+                *dot.entry(*other).or_default() += weight * other_weight;
+            }
+        }
+
+        let mut related: Vec<(usize, f64)> = dot
+            .into_iter()
+            .map(|(other, cosine)| {
+                let score = (1.0 - PRODUCTS_WEIGHT) * cosine
+                    + PRODUCTS_WEIGHT * self.products_overlap(position, other);
+                (other, (score * 1000.0).round() / 1000.0)
+            })
+            .filter(|(_, score)| *score >= min_score)
+            .collect();
+
+        related.sort_by(|a, b| {
+            b.1.partial_cmp(&a.1)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| self.documents[a.0].cve.cmp(&self.documents[b.0].cve))
+        });
+        related.truncate(limit);
+        related
+    }
+}
+
+/// Computes the related CVEs of every CVE and replaces the stored ones. Returns the number
+/// of relations stored.
+pub fn run(pool: &Pool, limit: usize, min_score: f64) -> Result<u32, String> {
+    let database = Database(pool.get().map_err(|e| e.to_string())?);
+
+    let index = Index::build(documents(database.get_summaries()?));
+    info!(
+        "finding the related CVEs of {} CVEs ...",
+        index.documents().len()
+    );
+
+    let mut relations = vec![];
+    for (position, document) in index.documents().iter().enumerate() {
+        for (other, score) in index.related(position, limit, min_score) {
+            relations.push(models::NewRelatedCve::with(
+                document.cve.clone(),
+                index.documents()[other].cve.clone(),
+                score,
+            ));
+        }
+    }
+
+    let num_relations = relations.len() as u32;
+    database.replace_related_cves(relations)?;
+
+    Ok(num_relations)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{documents, tokenize, Index};
+
+    fn record(cve: &str, product: &str, summary: &str) -> (String, String, String) {
+        (cve.into(), product.into(), summary.into())
+    }
+
+    #[test]
+    fn can_tokenize_summaries() {
+        assert_eq!(
+            vec!["heap-based", "buffer", "overflow", "xmlparsecharref", "libxml2"],
+            tokenize("A heap-based buffer overflow in xmlParseCharRef of libxml2 2.9.10 allows attackers")
+        );
+    }
+
+    #[test]
+    fn can_find_related_cves() {
+        let records = vec![
+            record(
+                "CVE-1",
+                "libxml2",
+                "Heap-based buffer overflow in xmlParseCharRef of libxml2.",
+            ),
+            record(
+                "CVE-1",
+                "libxml",
+                "Heap-based buffer overflow in xmlParseCharRef of libxml2.",
+            ),
+            record(
+                "CVE-2",
+                "libxml2",
+                "Use after free in xmlParseCharRef when parsing entities.",
+            ),
+            record(
+                "CVE-3",
+                "openssl",
+                "NULL pointer dereference in the X509 certificate verification.",
+            ),
+            record(
+                "CVE-4",
+                "gnutls",
+                "NULL pointer dereference in certificate verification of gnutls.",
+            ),
+            record(
+                "CVE-5",
+                "busybox",
+                "Command injection through crafted hostnames in udhcpc.",
+            ),
+        ];
+        let documents = documents(records);
+        assert_eq!(5, documents.len());
+        assert_eq!(2, documents[0].products.len());
+
+        let index = Index::build(documents);
+
+        let related = index.related(0, 10, 0.1);
+        assert_eq!(vec![1], related.iter().map(|r| r.0).collect::<Vec<_>>());
+
+        let related = index.related(2, 10, 0.1);
+        assert_eq!(vec![3], related.iter().map(|r| r.0).collect::<Vec<_>>());
+
+        assert!(index.related(4, 10, 0.1).is_empty());
+    }
+}
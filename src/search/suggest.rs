@@ -0,0 +1,250 @@
+//! Suggestion of the CPEs of a component described by its name, from the products having
+//! records or listed by the CPE dictionary, and the aliases of the package names.
+
+use std::collections::HashSet;
+
+use serde::Serialize;
+
+use crate::db::{models, Database};
+use crate::scan::alias;
+use crate::sources::nist;
+
+/// Candidates scoring less than this are not suggested.
+pub const MIN_SCORE: f64 = 0.5;
+
+/// Candidate CPE of a component, the best ones scoring 1.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct Suggestion {
+    pub cpe: String,
+    pub vendor: String,
+    pub product: String,
+    pub score: f64,
+}
+
+/// Lowercases the name and joins its words with `_`, the separator used by most CPEs.
+fn key(name: &str) -> String {
+    name.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join("_")
+}
+
+fn bigrams(key: &str) -> HashSet<(char, char)> {
+    let chars: Vec<char> = key.chars().collect();
+    chars.windows(2).map(|pair| (pair[0], pair[1])).collect()
+}
+
+/// Dice coefficient of the character bigrams of the keys, from 0 to 1.
+fn similarity(a: &str, b: &str) -> f64 {
+    if a == b {
+        return 1.0;
+    }
+    let (a, b) = (bigrams(a), bigrams(b));
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    2.0 * a.intersection(&b).count() as f64 / (a.len() + b.len()) as f64
+}
+
+/// Scores how likely the product is the component: 1 for an alias of the package name, 0.9
+/// for the same name, less for similar names. The vendor, when known, weights a fifth.
+pub fn score(name: &str, vendor: Option<&str>, product: &models::Product) -> f64 {
+    let alias = alias::resolve(name);
+    let product_score = if alias.vendor.as_deref() == Some(product.vendor.as_str())
+        && alias.product == product.product
+    {
+        1.0
+    } else if key(name) == product.product {
+        0.9
+    } else {
+        0.8 * similarity(&key(name), &key(&product.product))
+    };
+
+    match vendor {
+        Some(vendor) => 0.8 * product_score + 0.2 * similarity(&key(vendor), &key(&product.vendor)),
+        None => product_score,
+    }
+}
+
+/// Escapes the characters of a value that are special in the CPE 2.3 formatted strings.
+pub fn escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        if c.is_whitespace() {
+            escaped.push('_');
+        } else {
+            if !(c.is_alphanumeric() || c == '_' || c == '-' || c == '.') {
+                escaped.push('\\');
+            }
+            escaped.push(c);
+        }
+    }
+    escaped
+}
+
+/// Formats the CPE 2.3 string of a version of the product.
+pub fn cpe(part: &str, vendor: &str, product: &str, version: &str) -> String {
+    format!(
+        "cpe:2.3:{}:{}:{}:{}:*:*:*:*:*:*:*",
+        part,
+        escape(vendor),
+        escape(product),
+        escape(version)
+    )
+}
+
+/// Ranks the products by how likely they are the component, the best first.
+pub fn rank(
+    products: &[models::Product],
+    name: &str,
+    vendor: Option<&str>,
+    limit: usize,
+) -> Vec<(models::Product, f64)> {
+    let mut ranked: Vec<(models::Product, f64)> = products
+        .iter()
+        .map(|product| (product.clone(), score(name, vendor, product)))
+        .filter(|(_, score)| *score >= MIN_SCORE)
+        .collect();
+
+    ranked.sort_by(|a, b| {
+        b.1.partial_cmp(&a.1)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| a.0.vendor.cmp(&b.0.vendor))
+            .then_with(|| a.0.product.cmp(&b.0.product))
+    });
+    ranked.truncate(limit);
+    ranked
+}
+
+/// Products having records, along with the ones of the CPE dictionary without any.
+fn candidates(db: &Database) -> Result<Vec<models::Product>, String> {
+    let mut products = db.get_products()?;
+    let known: HashSet<(String, String)> = products
+        .iter()
+        .map(|product| (product.vendor.clone(), product.product.clone()))
+        .collect();
+    for (vendor, product) in db.get_dictionary_products(None)? {
+        if !known.contains(&(vendor.clone(), product.clone())) {
+            products.push(models::Product {
+                vendor,
+                product,
+                target_sw: vec![],
+                display_name: None,
+                vendor_display_name: None,
+            });
+        }
+    }
+    Ok(products)
+}
+
+/// Part of the CPEs of the product in its NIST records, or else in the CPE dictionary,
+/// applications when unknown.
+fn part(db: &Database, product: &models::Product) -> Result<String, String> {
+    let sample = db.get_product_sample(&product.vendor, &product.product, nist::SOURCE_NAME)?;
+    if sample.is_none() {
+        let part = db.get_dictionary_part(&product.vendor, &product.product)?;
+        return Ok(part.unwrap_or_else(|| "a".to_string()));
+    }
+
+    let part = sample.and_then(|(_, object)| {
+        let item: nist::cve::item::CVE = serde_json::from_str(&object.data).ok()?;
+        item.configurations
+            .nodes
+            .iter()
+            .find_map(|node| node.part_of(&product.vendor, &product.product))
+            .map(str::to_string)
+    });
+    Ok(part.unwrap_or_else(|| "a".to_string()))
+}
+
+/// Suggests the CPEs of a version of the component, the best first.
+pub fn suggest(
+    db: &Database,
+    name: &str,
+    vendor: Option<&str>,
+    version: &str,
+    limit: usize,
+) -> Result<Vec<Suggestion>, String> {
+    let products = candidates(db)?;
+    let version = alias::normalize_version(version);
+
+    let mut suggestions = vec![];
+    for (product, score) in rank(&products, name, vendor, limit) {
+        suggestions.push(Suggestion {
+            cpe: cpe(
+                &part(db, &product)?,
+                &product.vendor,
+                &product.product,
+                version,
+            ),
+            vendor: product.vendor,
+            product: product.product,
+            score: (score * 100.0).round() / 100.0,
+        });
+    }
+    Ok(suggestions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{cpe, rank, score};
+    use crate::db::models::Product;
+
+    fn product(vendor: &str, product: &str) -> Product {
+        Product {
+            vendor: vendor.into(),
+            product: product.into(),
+            target_sw: vec![],
+            display_name: None,
+            vendor_display_name: None,
+        }
+    }
+
+    #[test]
+    fn can_score_products() {
+        // alias of the package name
+        assert_eq!(
+            1.0,
+            score("linux-yocto", None, &product("linux", "linux_kernel"))
+        );
+        // same name once normalized
+        assert_eq!(
+            0.9,
+            score("Spring Boot", None, &product("vmware", "spring_boot"))
+        );
+        // the vendor weights a fifth
+        assert!(
+            score("openssl", Some("openssl"), &product("openssl", "openssl"))
+                > score("openssl", Some("openssl"), &product("fedora", "openssl"))
+        );
+        assert!(score("nginx", None, &product("apache", "http_server")) < 0.5);
+    }
+
+    #[test]
+    fn can_rank_products() {
+        let products = vec![
+            product("f5", "nginx"),
+            product("nginx", "nginx"),
+            product("nginx", "nginx_unit"),
+            product("apache", "http_server"),
+        ];
+
+        let ranked = rank(&products, "nginx", Some("f5"), 2);
+        assert_eq!(2, ranked.len());
+        assert_eq!(product("f5", "nginx"), ranked[0].0);
+        assert_eq!(product("nginx", "nginx"), ranked[1].0);
+    }
+
+    #[test]
+    fn can_format_cpes() {
+        assert_eq!(
+            "cpe:2.3:a:haxx:curl:7.83.1:*:*:*:*:*:*:*",
+            cpe("a", "haxx", "curl", "7.83.1")
+        );
+        assert_eq!(
+            "cpe:2.3:a:vendor:c\\+\\+_lib:1.0\\:beta:*:*:*:*:*:*:*",
+            cpe("a", "vendor", "c++ lib", "1.0:beta")
+        );
+    }
+}
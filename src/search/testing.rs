@@ -0,0 +1,36 @@
+//! Queries and records for the tests of the sources, the scans and the exports.
+
+use chrono::Utc;
+
+use super::Query;
+use crate::db::models;
+
+/// Query of a product, without a vendor, at the version if given.
+pub fn query<'a>(product: &str, version: impl Into<Option<&'a str>>) -> Query {
+    Query {
+        vendor: None,
+        product: product.into(),
+        version: version.into().map(str::to_string),
+        ..Default::default()
+    }
+}
+
+/// High severity NIST record of the CVE affecting the product, of the vendor of the same name.
+/// The tests override the fields they check, like `CVE { score: 9.8, ..cve(id, product) }`.
+pub fn cve(id: &str, product: &str) -> models::CVE {
+    models::CVE {
+        id: 0,
+        created_at: Utc::now(),
+        updated_at: None,
+        source: "NIST".into(),
+        vendor: product.into(),
+        product: product.into(),
+        cve: id.into(),
+        summary: "summary".into(),
+        score: 7.5,
+        severity: "HIGH".into(),
+        vector: None,
+        references: diesel_json::Json::new(vec![]),
+        object_id: None,
+    }
+}
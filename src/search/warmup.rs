@@ -0,0 +1,117 @@
+//! Searches of the queries searched the most according to the query log, run on
+//! startup and after the imports to fill the caches before the clients ask for them.
+
+use std::env;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use log::{info, warn};
+
+use crate::db::{Database, Pool};
+
+use super::{preload, CACHE_SIZE};
+
+const DEFAULT_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Number of queries to run, if the warm-up is enabled by setting `KEPLER_WARMUP`, up to the
+/// size of the cache.
+fn parse_count(value: &str) -> Result<Option<usize>, String> {
+    match value.parse::<usize>() {
+        Ok(0) => Ok(None),
+        Ok(count) if count <= CACHE_SIZE => Ok(Some(count)),
+        _ => Err(format!(
+            "invalid KEPLER_WARMUP: {}, expected 0 to {}",
+            value, CACHE_SIZE
+        )),
+    }
+}
+
+/// Number of queries to run and how often the records are checked for changes, if the
+/// warm-up is enabled by setting `KEPLER_WARMUP` to the number of queries.
+/// `KEPLER_WARMUP_INTERVAL` sets the interval in seconds.
+pub fn from_env() -> Result<Option<(usize, Duration)>, String> {
+    let count = match env::var("KEPLER_WARMUP") {
+        Ok(value) => match parse_count(&value)? {
+            Some(count) => count,
+            None => return Ok(None),
+        },
+        Err(_) => return Ok(None),
+    };
+
+    let interval = match env::var("KEPLER_WARMUP_INTERVAL") {
+        Ok(value) => match value.parse::<u64>() {
+            Ok(secs) if secs > 0 => Duration::from_secs(secs),
+            _ => return Err(format!("invalid KEPLER_WARMUP_INTERVAL: {}", value)),
+        },
+        Err(_) => DEFAULT_INTERVAL,
+    };
+
+    Ok(Some((count, interval)))
+}
+
+/// Runs the searches queried the most, as the clients sent them so that they hit the cache,
+/// returning how many succeeded.
+pub fn run(database: &Database, count: usize) -> Result<usize, String> {
+    // the preloaded records, when enabled, are the ones the searches should be cached from
+    if preload::get().is_some() {
+        preload::load(database)?;
+    }
+
+    let start = Instant::now();
+    let mut num_warmed = 0;
+    for query in database.get_top_queries(count as i64)? {
+        match super::query(database, &query) {
+            Ok(_) => num_warmed += 1,
+            Err(e) => warn!("could not warm up {:?}: {}", query, e),
+        }
+    }
+    info!(
+        "warmed up the cache with {} searches in {:?}",
+        num_warmed,
+        start.elapsed()
+    );
+
+    Ok(num_warmed)
+}
+
+/// Runs the searches now, then again in the background whenever an import changes the
+/// records, with the cache emptied of the results predating them.
+pub fn start(pool: Pool, count: usize, interval: Duration) -> Result<(), String> {
+    let database = Database(pool.get().map_err(|e| e.to_string())?);
+    let mut generation = database.get_records_generation()?;
+    run(&database, count)?;
+
+    thread::spawn(move || loop {
+        thread::sleep(interval);
+
+        let warmed = pool.get().map_err(|e| e.to_string()).and_then(|conn| {
+            let database = Database(conn);
+            let latest = database.get_records_generation()?;
+            if latest == generation {
+                return Ok(());
+            }
+            super::clear_cache();
+            run(&database, count)?;
+            generation = latest;
+            Ok(())
+        });
+        if let Err(e) = warmed {
+            warn!("could not warm up the cache: {}", e);
+        }
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_count;
+
+    #[test]
+    fn can_parse_counts() {
+        assert_eq!(Ok(None), parse_count("0"));
+        assert_eq!(Ok(Some(100)), parse_count("100"));
+        assert!(parse_count("4097").is_err());
+        assert!(parse_count("all").is_err());
+    }
+}
@@ -0,0 +1,261 @@
+//! Streams the imported records to an analytics warehouse, so that analytics teams don't
+//! query the operational database.
+
+use std::env;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use log::info;
+use serde::Serialize;
+
+use crate::db::{models, Database};
+
+/// Rows written per request.
+pub const BATCH_SIZE: usize = 5000;
+
+/// Row of a record as written to the warehouse.
+#[derive(Debug, Serialize, PartialEq)]
+pub struct Row {
+    pub cve: String,
+    pub source: String,
+    pub vendor: String,
+    pub product: String,
+    pub summary: String,
+    pub score: f64,
+    pub severity: String,
+    pub vector: Option<String>,
+    /// As `YYYY-MM-DD hh:mm:ss` in UTC, parsed by most warehouses.
+    pub imported_at: String,
+}
+
+impl From<&models::CVE> for Row {
+    fn from(record: &models::CVE) -> Self {
+        Self {
+            cve: record.cve.clone(),
+            source: record.source.clone(),
+            vendor: record.vendor.clone(),
+            product: record.product.clone(),
+            summary: record.summary.clone(),
+            score: record.score,
+            severity: record.severity.clone(),
+            vector: record.vector.clone(),
+            imported_at: record.created_at.format("%Y-%m-%d %H:%M:%S").to_string(),
+        }
+    }
+}
+
+/// Rows as newline delimited JSON.
+pub fn ndjson(rows: &[Row]) -> Result<String, String> {
+    let mut body = String::new();
+    for row in rows {
+        body.push_str(
+            &serde_json::to_string(row).map_err(|e| format!("could not serialize row: {}", e))?,
+        );
+        body.push('\n');
+    }
+    Ok(body)
+}
+
+/// Writer of the rows to a warehouse.
+pub trait Writer {
+    fn name(&self) -> String;
+
+    fn write(&self, rows: &[Row]) -> Result<(), String>;
+}
+
+fn client() -> Result<reqwest::blocking::Client, String> {
+    reqwest::blocking::Client::builder()
+        .timeout(Some(Duration::from_secs(60)))
+        .build()
+        .map_err(|e| format!("could not create http client: {}", e))
+}
+
+/// ClickHouse table written through its HTTP interface.
+#[derive(Debug, Clone)]
+pub struct ClickHouse {
+    pub url: String,
+    pub table: String,
+    pub user: Option<String>,
+    pub password: Option<String>,
+}
+
+impl Writer for ClickHouse {
+    fn name(&self) -> String {
+        format!("clickhouse table {}", self.table)
+    }
+
+    fn write(&self, rows: &[Row]) -> Result<(), String> {
+        let mut req = client()?
+            .post(&self.url)
+            .query(&[(
+                "query",
+                format!("INSERT INTO {} FORMAT JSONEachRow", self.table),
+            )])
+            .body(ndjson(rows)?);
+        if let Some(user) = &self.user {
+            req = req.header("X-ClickHouse-User", user);
+        }
+        if let Some(password) = &self.password {
+            req = req.header("X-ClickHouse-Key", password);
+        }
+
+        let res = req
+            .send()
+            .map_err(|e| format!("error writing to {}: {}", self.url, e))?;
+        if !res.status().is_success() {
+            return Err(format!(
+                "{} refused the rows ({}): {}",
+                self.url,
+                res.status(),
+                res.text().unwrap_or_default().trim()
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+/// Any endpoint accepting newline delimited JSON, like the HTTP sources of the log shippers
+/// forwarding to other warehouses.
+#[derive(Debug, Clone)]
+pub struct JsonLines {
+    pub url: String,
+}
+
+impl Writer for JsonLines {
+    fn name(&self) -> String {
+        self.url.clone()
+    }
+
+    fn write(&self, rows: &[Row]) -> Result<(), String> {
+        let res = client()?
+            .post(&self.url)
+            .header("Content-Type", "application/x-ndjson")
+            .body(ndjson(rows)?)
+            .send()
+            .map_err(|e| format!("error writing to {}: {}", self.url, e))?;
+        if !res.status().is_success() {
+            return Err(format!("{} refused the rows ({})", self.url, res.status()));
+        }
+
+        Ok(())
+    }
+}
+
+fn is_identifier(table: &str) -> bool {
+    !table.is_empty()
+        && table.split('.').all(|part| {
+            !part.is_empty() && part.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        })
+}
+
+/// Writer configured with `KEPLER_SINK` (`clickhouse` or `http`) and `KEPLER_SINK_URL`, if
+/// any. ClickHouse writes to the `KEPLER_SINK_TABLE` table, `cves` by default, as the
+/// `KEPLER_SINK_USER` user with the `KEPLER_SINK_PASSWORD` password.
+pub fn from_env() -> Result<Option<Box<dyn Writer>>, String> {
+    let kind = match env::var("KEPLER_SINK") {
+        Ok(kind) => kind,
+        Err(_) => return Ok(None),
+    };
+    let url = env::var("KEPLER_SINK_URL").map_err(|_| "missing KEPLER_SINK_URL".to_string())?;
+
+    let writer: Box<dyn Writer> = match kind.as_str() {
+        "clickhouse" => {
+            let table = env::var("KEPLER_SINK_TABLE").unwrap_or_else(|_| "cves".to_string());
+            if !is_identifier(&table) {
+                return Err(format!("invalid KEPLER_SINK_TABLE: {}", table));
+            }
+            Box::new(ClickHouse {
+                url,
+                table,
+                user: env::var("KEPLER_SINK_USER").ok(),
+                password: env::var("KEPLER_SINK_PASSWORD").ok(),
+            })
+        }
+        "http" => Box::new(JsonLines { url }),
+        _ => return Err(format!("invalid KEPLER_SINK: {}", kind)),
+    };
+
+    info!("streaming the imported records to {}", writer.name());
+    Ok(Some(writer))
+}
+
+/// Writes the records in batches, returning the number of rows written.
+pub fn stream(writer: &dyn Writer, records: &[models::CVE]) -> Result<usize, String> {
+    for batch in records.chunks(BATCH_SIZE) {
+        let rows: Vec<Row> = batch.iter().map(Row::from).collect();
+        writer.write(&rows)?;
+    }
+    Ok(records.len())
+}
+
+/// Writes the records created since the given time.
+pub fn stream_since(
+    writer: &dyn Writer,
+    database: &Database,
+    since: DateTime<Utc>,
+) -> Result<usize, String> {
+    let records = database.get_cves_since(since)?;
+    stream(writer, &records)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use chrono::{DateTime, Utc};
+
+    use super::{is_identifier, ndjson, stream, Row, Writer};
+    use crate::db::models::CVE;
+    use crate::search::testing;
+
+    struct Recorder(RefCell<Vec<usize>>);
+
+    impl Writer for Recorder {
+        fn name(&self) -> String {
+            "recorder".into()
+        }
+
+        fn write(&self, rows: &[Row]) -> Result<(), String> {
+            self.0.borrow_mut().push(rows.len());
+            Ok(())
+        }
+    }
+
+    fn record(cve: &str) -> CVE {
+        CVE {
+            created_at: DateTime::<Utc>::from(UNIX_EPOCH + Duration::from_secs(1_654_000_000)),
+            summary: "...".into(),
+            ..testing::cve(cve, "busybox")
+        }
+    }
+
+    #[test]
+    fn can_serialize_rows() {
+        let rows = vec![Row::from(&record("CVE-2022-28391"))];
+        assert_eq!(
+            "{\"cve\":\"CVE-2022-28391\",\"source\":\"NIST\",\"vendor\":\"busybox\",\"product\":\"busybox\",\"summary\":\"...\",\"score\":7.5,\"severity\":\"HIGH\",\"vector\":null,\"imported_at\":\"2022-05-31 12:26:40\"}\n",
+            ndjson(&rows).unwrap()
+        );
+    }
+
+    #[test]
+    fn can_stream_in_batches() {
+        let records: Vec<CVE> = (0..12001)
+            .map(|i| record(&format!("CVE-2022-{i}")))
+            .collect();
+        let recorder = Recorder(RefCell::new(vec![]));
+
+        assert_eq!(12001, stream(&recorder, &records).unwrap());
+        assert_eq!(vec![5000, 5000, 2001], *recorder.0.borrow());
+    }
+
+    #[test]
+    fn can_validate_tables() {
+        assert!(is_identifier("cves"));
+        assert!(is_identifier("kepler.cves_v2"));
+        assert!(!is_identifier("cves; DROP TABLE cves"));
+        assert!(!is_identifier("kepler."));
+    }
+}
@@ -0,0 +1,207 @@
+//! Allowlist of the products imported, so that the deployments caring about a few products
+//! only store their records. It's set with `KEPLER_IMPORT_ALLOWLIST` as a comma separated
+//! list of `vendor:product` or `product` patterns, where `*` matches any sequence of
+//! characters, like `openssl:openssl,*:curl,@alpine:*-openssl`.
+
+use std::collections::HashSet;
+use std::env;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::RwLock;
+
+use lazy_static::lazy_static;
+
+pub const ENV_VAR: &str = "KEPLER_IMPORT_ALLOWLIST";
+
+lazy_static! {
+    static ref ACTIVE: RwLock<Option<Allowlist>> = RwLock::new(None);
+    static ref SKIPPED: RwLock<Skipped> = RwLock::new(Skipped::default());
+}
+
+/// Records skipped since the allowlist was activated, along with their objects.
+#[derive(Debug, Default)]
+struct Skipped {
+    records: u64,
+    objects: HashSet<i32>,
+}
+
+/// Whether the value matches the pattern, `*` matching any sequence of characters.
+fn is_glob_match(pattern: &str, value: &str) -> bool {
+    let parts: Vec<&str> = pattern.split('*').collect();
+    if parts.len() == 1 {
+        return pattern == value;
+    }
+
+    let (first, last) = (parts[0], parts[parts.len() - 1]);
+    if value.len() < first.len() + last.len() || !value.starts_with(first) || !value.ends_with(last)
+    {
+        return false;
+    }
+
+    let mut rest = &value[first.len()..value.len() - last.len()];
+    for part in &parts[1..parts.len() - 1] {
+        match rest.find(part) {
+            Some(index) => rest = &rest[index + part.len()..],
+            None => return false,
+        }
+    }
+    true
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Pattern {
+    vendor: Option<String>,
+    product: String,
+}
+
+impl Pattern {
+    fn matches(&self, vendor: &str, product: &str) -> bool {
+        self.vendor
+            .as_deref()
+            .map_or(true, |pattern| is_glob_match(pattern, vendor))
+            && is_glob_match(&self.product, product)
+    }
+}
+
+impl fmt::Display for Pattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.vendor {
+            Some(vendor) => write!(f, "{}:{}", vendor, self.product),
+            None => write!(f, "{}", self.product),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Allowlist {
+    patterns: Vec<Pattern>,
+}
+
+impl FromStr for Allowlist {
+    type Err = String;
+
+    fn from_str(val: &str) -> Result<Self, Self::Err> {
+        let mut patterns = vec![];
+        for entry in val
+            .split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+        {
+            let entry = entry.to_lowercase();
+            let (vendor, product) = match entry.split_once(':') {
+                Some((vendor, product)) => (Some(vendor.to_string()), product.to_string()),
+                None => (None, entry.clone()),
+            };
+            if product.is_empty() || vendor.as_deref() == Some("") || product.contains(':') {
+                return Err(format!(
+                    "invalid allowlist entry {}, must be like vendor:product",
+                    entry
+                ));
+            }
+            patterns.push(Pattern { vendor, product });
+        }
+
+        if patterns.is_empty() {
+            return Err("the allowlist is empty".into());
+        }
+        Ok(Self { patterns })
+    }
+}
+
+impl fmt::Display for Allowlist {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let patterns: Vec<String> = self.patterns.iter().map(Pattern::to_string).collect();
+        write!(f, "{}", patterns.join(","))
+    }
+}
+
+impl Allowlist {
+    /// Allowlist of `KEPLER_IMPORT_ALLOWLIST`, if set.
+    pub fn from_env() -> Result<Option<Self>, String> {
+        match env::var(ENV_VAR) {
+            Ok(value) => value
+                .parse()
+                .map(Some)
+                .map_err(|e| format!("invalid {}: {}", ENV_VAR, e)),
+            Err(_) => Ok(None),
+        }
+    }
+
+    pub fn allows(&self, vendor: &str, product: &str) -> bool {
+        let (vendor, product) = (vendor.to_lowercase(), product.to_lowercase());
+        self.patterns
+            .iter()
+            .any(|pattern| pattern.matches(&vendor, &product))
+    }
+}
+
+/// Activates the allowlist for the records stored from now on by this process, starting the
+/// count of the skipped records over.
+pub fn activate(allowlist: Option<Allowlist>) {
+    *ACTIVE.write().unwrap() = allowlist;
+    *SKIPPED.write().unwrap() = Skipped::default();
+}
+
+/// Whether the record of the product can be stored, counting the ones that can't.
+pub fn admits(vendor: &str, product: &str, object_id: Option<i32>) -> bool {
+    let allowed = match &*ACTIVE.read().unwrap() {
+        Some(allowlist) => allowlist.allows(vendor, product),
+        None => true,
+    };
+
+    if !allowed {
+        let mut skipped = SKIPPED.write().unwrap();
+        skipped.records += 1;
+        skipped.objects.extend(object_id);
+    }
+    allowed
+}
+
+/// Number of records skipped since the allowlist was activated.
+pub fn num_skipped() -> u64 {
+    SKIPPED.read().unwrap().records
+}
+
+/// Objects of the records skipped since the allowlist was activated, to be deleted unless
+/// other records need them.
+pub fn skipped_objects() -> Vec<i32> {
+    SKIPPED.read().unwrap().objects.iter().copied().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_glob_match, Allowlist};
+
+    #[test]
+    fn can_match_globs() {
+        assert!(is_glob_match("openssl", "openssl"));
+        assert!(!is_glob_match("openssl", "openssl3"));
+        assert!(is_glob_match("*-openssl", "alpine-3.19-openssl"));
+        assert!(is_glob_match("lib*", "libxml2"));
+        assert!(is_glob_match("*ssl*", "openssl-libs"));
+        assert!(is_glob_match("a*b*c", "a-b-c"));
+        assert!(!is_glob_match("a*b*c", "a-c-b"));
+        assert!(!is_glob_match("ab*ba", "aba"));
+        assert!(is_glob_match("*", "anything"));
+    }
+
+    #[test]
+    fn can_allow_products() {
+        let allowlist: Allowlist = "openssl:openssl, curl ,@alpine:*-busybox".parse().unwrap();
+        assert_eq!(
+            "openssl:openssl,curl,@alpine:*-busybox",
+            allowlist.to_string()
+        );
+
+        assert!(allowlist.allows("openssl", "openssl"));
+        assert!(allowlist.allows("haxx", "curl"));
+        assert!(allowlist.allows("@alpine", "alpine-3.19-busybox"));
+        assert!(allowlist.allows("OpenSSL", "OpenSSL"));
+        assert!(!allowlist.allows("@debian", "debian-bullseye-busybox"));
+        assert!(!allowlist.allows("gnu", "glibc"));
+
+        assert!("".parse::<Allowlist>().is_err());
+        assert!(":openssl".parse::<Allowlist>().is_err());
+        assert!("a:b:c".parse::<Allowlist>().is_err());
+    }
+}
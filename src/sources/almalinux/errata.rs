@@ -0,0 +1,186 @@
+//! Parser of the errata of the AlmaLinux releases, listing the advisories along with the
+//! packages fixing them.
+
+use std::io::Read;
+
+use chrono::NaiveDateTime;
+use serde::Deserialize;
+
+use super::{Advisory, Fix};
+
+#[derive(Debug, Deserialize)]
+struct Reference {
+    #[serde(default)]
+    id: String,
+    #[serde(rename = "type", default)]
+    kind: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Package {
+    name: String,
+    #[serde(default)]
+    epoch: String,
+    version: String,
+    release: String,
+    #[serde(default)]
+    arch: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct PackageList {
+    #[serde(default)]
+    packages: Vec<Package>,
+}
+
+/// Date of an erratum, either a MongoDB date like `{"$date": 1686614400000}` or a string.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum Date {
+    Millis {
+        #[serde(rename = "$date")]
+        date: i64,
+    },
+    Text(String),
+}
+
+impl Date {
+    fn format(&self) -> String {
+        match self {
+            Date::Millis { date } => NaiveDateTime::from_timestamp_opt(date / 1000, 0)
+                .map(|date| date.format("%Y-%m-%d").to_string())
+                .unwrap_or_default(),
+            Date::Text(date) => date.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct Erratum {
+    #[serde(alias = "updateinfo_id")]
+    id: String,
+    #[serde(rename = "type", default)]
+    kind: String,
+    #[serde(default)]
+    title: String,
+    #[serde(default)]
+    description: String,
+    #[serde(default)]
+    severity: Option<String>,
+    #[serde(default)]
+    issued_date: Option<Date>,
+    #[serde(default)]
+    references: Vec<Reference>,
+    #[serde(default)]
+    pkglist: PackageList,
+}
+
+/// Parses the security advisories of the errata of a release, like `almalinux-9`.
+pub fn parse<R: Read>(source: R, release: &str) -> Result<Vec<Advisory>, String> {
+    let errata: Vec<Erratum> = serde_json::from_reader(source)
+        .map_err(|e| format!("could not parse the errata: {}", e))?;
+
+    let mut advisories = vec![];
+    // the bug fixes and the enhancements fix no CVEs
+    for erratum in errata
+        .into_iter()
+        .filter(|erratum| erratum.kind == "security")
+    {
+        let mut cves: Vec<String> = vec![];
+        for reference in &erratum.references {
+            if reference.kind == "cve" && !cves.contains(&reference.id) {
+                cves.push(reference.id.clone());
+            }
+        }
+
+        let mut fixes = vec![];
+        for package in &erratum.pkglist.packages {
+            if package.arch == "src" {
+                continue;
+            }
+            let version = match package.epoch.as_str() {
+                "" | "0" => format!("{}-{}", package.version, package.release),
+                epoch => format!("{}:{}-{}", epoch, package.version, package.release),
+            };
+            // the same fix for every architecture
+            let fix = Fix {
+                release: release.to_string(),
+                package: package.name.clone(),
+                version,
+            };
+            if !fixes.contains(&fix) {
+                fixes.push(fix);
+            }
+        }
+
+        advisories.push(Advisory {
+            id: erratum.id,
+            title: erratum.title,
+            description: erratum.description,
+            severity: erratum.severity,
+            issued: erratum
+                .issued_date
+                .as_ref()
+                .map(Date::format)
+                .unwrap_or_default(),
+            cves,
+            fixes,
+        });
+    }
+
+    Ok(advisories)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse;
+
+    const ERRATA: &str = r#"[
+        {
+            "id": "ALSA-2023:3722",
+            "type": "security",
+            "title": "Moderate: openssl security and bug fix update",
+            "description": "OpenSSL is a toolkit that implements the SSL and TLS protocols.",
+            "severity": "Moderate",
+            "issued_date": { "$date": 1686614400000 },
+            "references": [
+                { "href": "https://access.redhat.com/errata/RHSA-2023:3722", "id": "RHSA-2023:3722", "title": "RHSA-2023:3722", "type": "rhsa" },
+                { "href": "https://www.cve.org/CVERecord?id=CVE-2023-0464", "id": "CVE-2023-0464", "title": "CVE-2023-0464", "type": "cve" }
+            ],
+            "pkglist": {
+                "name": "almalinux-9-for-x86_64-appstream-rpms__9_2_default",
+                "packages": [
+                    { "name": "openssl", "epoch": "1", "version": "3.0.7", "release": "6.el9_2", "arch": "src", "filename": "openssl-3.0.7-6.el9_2.src.rpm" },
+                    { "name": "openssl", "epoch": "1", "version": "3.0.7", "release": "6.el9_2", "arch": "x86_64", "filename": "openssl-3.0.7-6.el9_2.x86_64.rpm" },
+                    { "name": "openssl", "epoch": "1", "version": "3.0.7", "release": "6.el9_2", "arch": "aarch64", "filename": "openssl-3.0.7-6.el9_2.aarch64.rpm" },
+                    { "name": "openssl-perl", "epoch": "0", "version": "3.0.7", "release": "6.el9_2", "arch": "x86_64", "filename": "openssl-perl-3.0.7-6.el9_2.x86_64.rpm" }
+                ]
+            }
+        },
+        {
+            "id": "ALBA-2023:3000",
+            "type": "bugfix",
+            "title": "tzdata bug fix update",
+            "references": [],
+            "pkglist": { "packages": [] }
+        }
+    ]"#;
+
+    #[test]
+    fn can_parse_security_advisories() {
+        let advisories = parse(ERRATA.as_bytes(), "almalinux-9").unwrap();
+        assert_eq!(1, advisories.len());
+
+        let advisory = &advisories[0];
+        assert_eq!("ALSA-2023:3722", advisory.id);
+        assert_eq!("MEDIUM", advisory.severity());
+        assert_eq!("2023-06-13", advisory.issued);
+        assert_eq!(vec!["CVE-2023-0464"], advisory.cves);
+        assert_eq!(
+            vec!["almalinux-9-openssl", "almalinux-9-openssl-perl"],
+            advisory.products()
+        );
+        assert_eq!("1:3.0.7-6.el9_2", advisory.fixes[0].version);
+        assert_eq!("3.0.7-6.el9_2", advisory.fixes[1].version);
+    }
+}
@@ -0,0 +1,80 @@
+use std::fs::{self, File};
+use std::io::BufReader;
+use std::path::Path;
+
+use anyhow::{anyhow, Context, Result};
+use log::info;
+
+use super::{errata, object_key, release, url, Advisory, SOURCE_NAME, VENDOR};
+
+use crate::db::{self, Pool};
+use crate::sources::redhat;
+use crate::utils::{download_to_file, ImportError};
+
+pub const FEED_URL: &str = "https://errata.almalinux.org";
+
+/// Major versions of the releases imported.
+pub const RELEASES: &[&str] = &["8", "9"];
+
+fn store(database: &db::Database, advisory: &Advisory) -> Result<u32> {
+    redhat::advisory::import::store(
+        database,
+        SOURCE_NAME,
+        VENDOR,
+        object_key(&advisory.id),
+        url(advisory),
+        advisory,
+    )
+}
+
+/// Imports the CVEs of the AlmaLinux packages from the errata of AlmaLinux 8 and 9,
+/// downloaded again with `fresh`, creating the new records and updating the changed ones.
+pub fn run(pool: &Pool, data_path: &Path, fresh: bool) -> Result<u32> {
+    let mut almalinux_path = data_path.to_path_buf();
+    almalinux_path.push("almalinux");
+    fs::create_dir_all(&almalinux_path)
+        .with_context(|| format!("could not create {}", almalinux_path.display()))?;
+
+    let mut advisories = vec![];
+    for major in RELEASES {
+        let mut file_path = almalinux_path.clone();
+        file_path.push(format!("{}.errata.full.json", major));
+
+        if fresh || !file_path.exists() {
+            download_to_file(
+                &format!("{}/{}/errata.full.json", FEED_URL, major),
+                &file_path,
+            )?;
+        }
+
+        info!("parsing {} ...", file_path.display());
+        let file = File::open(&file_path)
+            .with_context(|| format!("could not open {}", file_path.display()))?;
+        let release = release(major).ok_or_else(|| anyhow!("invalid release {}", major))?;
+        let parsed = errata::parse(BufReader::new(file), &release)
+            .map_err(|e| ImportError::from(e).quarantine(&file_path))?;
+        // empty errata are a broken feed rather than no advisories ever published
+        if parsed.is_empty() {
+            return Err(
+                ImportError::from(format!("no advisories of {} found", release))
+                    .quarantine(&file_path)
+                    .into(),
+            );
+        }
+        advisories.extend(parsed);
+    }
+
+    info!("importing {} AlmaLinux advisories ...", advisories.len());
+
+    let database = db::Database(pool.get()?);
+    let mut num_imported = 0;
+    for (index, advisory) in advisories.iter().enumerate() {
+        num_imported += store(&database, advisory)?;
+
+        if index > 0 && index % 1000 == 0 {
+            info!("processed {} advisories ...", index);
+        }
+    }
+
+    Ok(num_imported)
+}
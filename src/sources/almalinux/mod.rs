@@ -0,0 +1,73 @@
+//! CVEs of the AlmaLinux packages fixed by the AlmaLinux Security Advisories (ALSA), rebuilt
+//! from the Red Hat ones and published in the errata of every release with the versions of
+//! the AlmaLinux packages.
+
+pub use super::redhat::advisory::{product, Advisory, Fix};
+
+#[cfg(feature = "import")]
+pub mod errata;
+#[cfg(feature = "import")]
+pub mod import;
+
+pub const SOURCE_NAME: &str = "AlmaLinux";
+/// No vendors for the packages, the AlmaLinux release is part of the product name.
+pub const VENDOR: &str = "@almalinux";
+
+/// Release of an AlmaLinux version, like `almalinux-9` for `9.2`.
+pub fn release(version: &str) -> Option<String> {
+    version
+        .split('.')
+        .next()
+        .filter(|major| !major.is_empty() && major.chars().all(|c| c.is_ascii_digit()))
+        .map(|major| format!("almalinux-{}", major))
+}
+
+pub fn object_key(id: &str) -> String {
+    format!("{} (almalinux)", id)
+}
+
+/// Page of the advisory in the AlmaLinux errata of its release, like
+/// `https://errata.almalinux.org/9/ALSA-2023-3722.html`.
+pub fn url(advisory: &Advisory) -> String {
+    format!(
+        "https://errata.almalinux.org/{}/{}.html",
+        advisory
+            .release()
+            .unwrap_or_default()
+            .trim_start_matches("almalinux-"),
+        advisory.id.replace(':', "-")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{release, url, Advisory, Fix};
+    use crate::search::testing::query;
+
+    #[test]
+    fn can_match_fixed_versions() {
+        assert_eq!(Some("almalinux-9".to_string()), release("9.2"));
+        assert_eq!(None, release("kitten"));
+
+        let advisory = Advisory {
+            id: "ALSA-2023:3722".into(),
+            severity: Some("Moderate".into()),
+            fixes: vec![Fix {
+                release: "almalinux-9".into(),
+                package: "openssl".into(),
+                version: "1:3.0.7-6.el9_2".into(),
+            }],
+            ..Default::default()
+        };
+
+        assert_eq!("MEDIUM", advisory.severity());
+        assert_eq!(
+            "https://errata.almalinux.org/9/ALSA-2023-3722.html",
+            url(&advisory)
+        );
+        assert_eq!(vec!["almalinux-9-openssl"], advisory.products());
+        assert!(advisory.is_match(&query("almalinux-9-openssl", "1:3.0.7-5.el9")));
+        assert!(!advisory.is_match(&query("almalinux-9-openssl", "1:3.0.7-6.el9_2")));
+        assert!(!advisory.is_match(&query("almalinux-8-openssl", "1:1.1.1k-1.el8")));
+    }
+}
@@ -0,0 +1,163 @@
+//! Comparison of the Alpine package versions, like `apk version -t` does.
+
+use std::cmp::Ordering;
+
+/// Version and package release of a package, like `3.1.4-r1`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Version<'a> {
+    pub version: &'a str,
+    pub release: Option<u64>,
+}
+
+impl<'a> Version<'a> {
+    pub fn parse(version: &'a str) -> Self {
+        match version.rsplit_once("-r") {
+            Some((rest, release)) => match release.parse::<u64>() {
+                Ok(release) => Self {
+                    version: rest,
+                    release: Some(release),
+                },
+                Err(_) => Self {
+                    version,
+                    release: None,
+                },
+            },
+            None => Self {
+                version,
+                release: None,
+            },
+        }
+    }
+
+    /// Compares with another version of the same package, the package releases only if both
+    /// are set.
+    pub fn compare(&self, other: &Version) -> Ordering {
+        match compare(self.version, other.version) {
+            Ordering::Equal => match (self.release, other.release) {
+                (Some(release), Some(other_release)) => release.cmp(&other_release),
+                _ => Ordering::Equal,
+            },
+            ordering => ordering,
+        }
+    }
+}
+
+/// Rank of a suffix like `_rc2`, the pre-releases sorting before the version without a
+/// suffix and the patches after it.
+fn suffix_rank(name: &str) -> i32 {
+    match name {
+        "alpha" => 0,
+        "beta" => 1,
+        "pre" => 2,
+        "rc" => 3,
+        "" => 4,
+        "cvs" => 5,
+        "svn" => 6,
+        "git" => 7,
+        "hg" => 8,
+        "p" => 9,
+        _ => 10,
+    }
+}
+
+/// Number and letters of a dotted component like `1a`, or of a suffix like `rc2`.
+fn split_number(part: &str) -> (&str, u64) {
+    let digits = part
+        .find(|c: char| c.is_ascii_digit())
+        .unwrap_or_else(|| part.len());
+    let (name, number) = part.split_at(digits);
+    (name, number.parse().unwrap_or_default())
+}
+
+fn compare_component(a: &str, b: &str) -> Ordering {
+    let split = |part: &str| {
+        let letters = part
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or_else(|| part.len());
+        let (number, letter) = part.split_at(letters);
+        (
+            number.parse::<u64>().unwrap_or_default(),
+            letter.to_string(),
+        )
+    };
+    split(a).cmp(&split(b))
+}
+
+/// Compares two versions without their package release, like `1.2.3a_rc1`, made of dotted
+/// numbers with an optional trailing letter followed by the suffixes.
+pub fn compare(a: &str, b: &str) -> Ordering {
+    let mut a_parts = a.split('_');
+    let mut b_parts = b.split('_');
+    let (a_base, b_base) = (
+        a_parts.next().unwrap_or_default(),
+        b_parts.next().unwrap_or_default(),
+    );
+
+    let a_components: Vec<&str> = a_base.split('.').collect();
+    let b_components: Vec<&str> = b_base.split('.').collect();
+    for (a, b) in a_components.iter().zip(&b_components) {
+        match compare_component(a, b) {
+            Ordering::Equal => {}
+            ordering => return ordering,
+        }
+    }
+    // `1.2` is older than `1.2.1`
+    match a_components.len().cmp(&b_components.len()) {
+        Ordering::Equal => {}
+        ordering => return ordering,
+    }
+
+    let a_suffixes: Vec<(&str, u64)> = a_parts.map(split_number).collect();
+    let b_suffixes: Vec<(&str, u64)> = b_parts.map(split_number).collect();
+    for index in 0..a_suffixes.len().max(b_suffixes.len()) {
+        let (a_name, a_number) = a_suffixes.get(index).copied().unwrap_or(("", 0));
+        let (b_name, b_number) = b_suffixes.get(index).copied().unwrap_or(("", 0));
+        let ordering = suffix_rank(a_name)
+            .cmp(&suffix_rank(b_name))
+            .then(a_number.cmp(&b_number));
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+
+    Ordering::Equal
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cmp::Ordering;
+
+    use super::{compare, Version};
+
+    #[test]
+    fn can_compare_versions() {
+        assert_eq!(Ordering::Equal, compare("1.0", "1.0"));
+        assert_eq!(Ordering::Less, compare("1.0", "1.0.1"));
+        assert_eq!(Ordering::Greater, compare("2.10", "2.9"));
+        assert_eq!(Ordering::Less, compare("1.1.1k", "1.1.1n"));
+        assert_eq!(Ordering::Less, compare("1.0_rc1", "1.0"));
+        assert_eq!(Ordering::Less, compare("1.0_alpha2", "1.0_beta1"));
+        assert_eq!(Ordering::Less, compare("1.0_rc1", "1.0_rc2"));
+        assert_eq!(Ordering::Greater, compare("1.0_p1", "1.0"));
+        assert_eq!(Ordering::Greater, compare("1.0_git20230101", "1.0"));
+    }
+
+    #[test]
+    fn can_compare_package_versions() {
+        let fixed = Version::parse("3.1.4-r1");
+        assert_eq!("3.1.4", fixed.version);
+        assert_eq!(Some(1), fixed.release);
+
+        assert_eq!(Ordering::Less, Version::parse("3.1.4-r0").compare(&fixed));
+        assert_eq!(Ordering::Equal, Version::parse("3.1.4-r1").compare(&fixed));
+        assert_eq!(
+            Ordering::Greater,
+            Version::parse("3.1.4-r10").compare(&fixed)
+        );
+        assert_eq!(Ordering::Less, Version::parse("3.1.3").compare(&fixed));
+        assert_eq!(
+            Ordering::Greater,
+            Version::parse("3.2.0-r0").compare(&fixed)
+        );
+    }
+}
@@ -0,0 +1,122 @@
+use std::fs;
+use std::path::Path;
+
+use anyhow::{anyhow, Context, Result};
+use log::info;
+
+use super::{cves, Cve, SecDb, BRANCHES, REPOSITORIES, SOURCE_NAME, VENDOR};
+
+use crate::db::{self, Pool};
+use crate::utils::{download_to_file, ImportError};
+
+pub const FEED_URL: &str = "https://secdb.alpinelinux.org";
+
+/// Path of the security database of a repository of a branch, like `v3.19/main.json`.
+pub fn secdb_path(branch: &str, repository: &str) -> String {
+    if branch == "edge" {
+        format!("edge/{}.json", repository)
+    } else {
+        format!("v{}/{}.json", branch, repository)
+    }
+}
+
+fn store(database: &db::Database, cve: &Cve) -> Result<u32> {
+    let products = cve.products();
+    let affected: Vec<(String, String)> = products
+        .iter()
+        .map(|product| (VENDOR.to_string(), product.clone()))
+        .collect();
+    // the fixes withdrawn from the database
+    database
+        .delete_other_products(SOURCE_NAME, &cve.name, &affected)
+        .map_err(|e| anyhow!(e))?;
+    if products.is_empty() {
+        return Ok(0);
+    }
+
+    let object_id = database
+        .upsert_object(db::models::NewObject::with(
+            Cve::object_key(&cve.name),
+            serde_json::to_string(cve)?,
+        ))
+        .map_err(|e| anyhow!(e))?;
+
+    let mut refs = db::models::References::default();
+    refs.push(db::models::Reference {
+        url: format!("https://security.alpinelinux.org/vuln/{}", cve.name),
+        tags: vec!["Alpine".into()],
+    });
+
+    let mut num_imported = 0;
+    for product in products {
+        let new_cve = db::models::NewCVE::with(
+            SOURCE_NAME.into(),
+            VENDOR.into(),
+            product,
+            cve.name.clone(),
+            String::new(),
+            0.0,
+            "NONE".into(),
+            None,
+            refs.clone(),
+            Some(object_id),
+        );
+        if database.upsert_cve(new_cve).map_err(|e| anyhow!(e))? {
+            num_imported += 1;
+        }
+    }
+
+    Ok(num_imported)
+}
+
+/// Imports the CVEs of the Alpine packages from the security databases of the supported
+/// branches, downloaded again with `fresh`, creating the new records, updating the changed
+/// ones and deleting the ones of the fixes withdrawn.
+pub fn run(pool: &Pool, data_path: &Path, fresh: bool) -> Result<u32> {
+    let mut alpine_path = data_path.to_path_buf();
+    alpine_path.push("alpine");
+    fs::create_dir_all(&alpine_path)
+        .with_context(|| format!("could not create {}", alpine_path.display()))?;
+
+    let mut secdbs = vec![];
+    for branch in BRANCHES {
+        for repository in REPOSITORIES {
+            let mut file_path = alpine_path.clone();
+            file_path.push(format!("{}-{}.json", branch, repository));
+            if fresh || !file_path.exists() {
+                let url = format!("{}/{}", FEED_URL, secdb_path(branch, repository));
+                download_to_file(&url, &file_path)?;
+            }
+
+            let json = fs::read_to_string(&file_path)
+                .with_context(|| format!("could not read {}", file_path.display()))?;
+            let secdb: SecDb = serde_json::from_str(&json).map_err(|e| {
+                ImportError::from(format!("could not parse the security database: {}", e))
+                    .quarantine(&file_path)
+            })?;
+            secdbs.push(secdb);
+        }
+    }
+    // an empty database is a broken feed rather than no CVEs ever fixed
+    if secdbs.iter().all(|secdb| secdb.packages.is_empty()) {
+        return Err(anyhow!("the security databases are empty"));
+    }
+
+    let cves = cves(secdbs);
+    info!(
+        "importing the {} CVEs of the Alpine packages ...",
+        cves.len()
+    );
+
+    let database = db::Database(pool.get()?);
+    let mut num_imported = 0;
+    for (index, cve) in cves.iter().enumerate() {
+        num_imported += store(&database, cve)?;
+
+        if index > 0 && index % 10000 == 0 {
+            info!("processed {} CVEs ...", index);
+        }
+    }
+
+    Ok(num_imported)
+}
@@ -0,0 +1,221 @@
+//! CVEs of the Alpine packages fixed according to the Alpine security database (secdb), with
+//! the versions of every branch fixing them rather than the upstream version ranges.
+
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::scan::ecosystem_product;
+use crate::search::Query;
+
+pub mod apk;
+#[cfg(feature = "import")]
+pub mod import;
+
+pub const SOURCE_NAME: &str = "Alpine";
+/// No vendors for the packages, the Alpine branch is part of the product name.
+pub const VENDOR: &str = "@alpine";
+
+/// Branches imported, the older ones being out of support.
+pub const BRANCHES: &[&str] = &[
+    "3.14", "3.15", "3.16", "3.17", "3.18", "3.19", "3.20", "edge",
+];
+/// Repositories of every branch.
+pub const REPOSITORIES: &[&str] = &["main", "community"];
+
+/// Product of a package of an Alpine branch, like `alpine-3.19-openssl`.
+pub fn product(branch: &str, package: &str) -> String {
+    ecosystem_product(&format!("alpine-{}", branch), package)
+}
+
+/// Branch of a release, like `3.19` for `3.19.1` or `v3.19`, or `edge`.
+pub fn branch(release: &str) -> Option<String> {
+    let release = release.trim_start_matches('v');
+    if release == "edge" {
+        return Some(release.to_string());
+    }
+
+    let mut numbers = release.split('.');
+    let (major, minor) = (numbers.next()?, numbers.next()?);
+    let is_number = |n: &str| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit());
+    if !is_number(major) || !is_number(minor) {
+        return None;
+    }
+    Some(format!("{}.{}", major, minor))
+}
+
+/// Fixes of the packages of a repository of a branch, as published by the security database.
+#[derive(Debug, Default, Clone, Deserialize)]
+pub struct SecDb {
+    /// Like `v3.19`.
+    pub distroversion: String,
+    #[serde(default)]
+    pub packages: Vec<Entry>,
+}
+
+#[derive(Debug, Default, Clone, Deserialize)]
+pub struct Entry {
+    pub pkg: Package,
+}
+
+#[derive(Debug, Default, Clone, Deserialize)]
+pub struct Package {
+    pub name: String,
+    /// CVEs fixed by every version, `0` for the ones never affecting the package of the
+    /// branch. The CVE ids may be followed by other ids, like `CVE-2019-1234 XSA-293`.
+    #[serde(default)]
+    pub secfixes: BTreeMap<String, Vec<String>>,
+}
+
+/// Version of a package of a branch fixing a CVE.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Fix {
+    pub branch: String,
+    pub package: String,
+    pub version: String,
+}
+
+#[derive(Debug, Default, Clone, Deserialize, Serialize)]
+pub struct Cve {
+    pub name: String,
+    pub fixes: Vec<Fix>,
+}
+
+/// Groups the fixes of the security databases by CVE.
+pub fn cves(secdbs: Vec<SecDb>) -> Vec<Cve> {
+    let mut cves: BTreeMap<String, Cve> = BTreeMap::new();
+
+    for secdb in secdbs {
+        let branch = match branch(&secdb.distroversion) {
+            Some(branch) => branch,
+            None => continue,
+        };
+        for entry in secdb.packages {
+            for (version, ids) in entry.pkg.secfixes {
+                if version == "0" {
+                    continue;
+                }
+                let ids = ids
+                    .iter()
+                    .flat_map(|ids| ids.split_whitespace())
+                    .filter(|id| id.starts_with("CVE-"));
+                for id in ids {
+                    let cve = cves.entry(id.to_string()).or_insert_with(|| Cve {
+                        name: id.to_string(),
+                        ..Default::default()
+                    });
+                    let fix = Fix {
+                        branch: branch.clone(),
+                        package: entry.pkg.name.clone(),
+                        version: version.clone(),
+                    };
+                    if !cve.fixes.contains(&fix) {
+                        cve.fixes.push(fix);
+                    }
+                }
+            }
+        }
+    }
+
+    cves.into_iter().map(|(_, cve)| cve).collect()
+}
+
+impl Cve {
+    pub fn object_key(id: &str) -> String {
+        format!("{} (alpine)", id)
+    }
+
+    /// Unique products of the packages fixed.
+    pub fn products(&self) -> Vec<String> {
+        let mut products = vec![];
+        for fix in &self.fixes {
+            let product = product(&fix.branch, &fix.package);
+            if !products.contains(&product) {
+                products.push(product);
+            }
+        }
+        products
+    }
+
+    /// Matches the versions older than the fixed one.
+    pub fn is_match(&self, query: &Query) -> bool {
+        // we need a version
+        let version = match &query.version {
+            Some(version) => apk::Version::parse(version),
+            None => return false,
+        };
+
+        self.fixes.iter().any(|fix| {
+            product(&fix.branch, &fix.package) == query.product
+                && version.compare(&apk::Version::parse(&fix.version)) == Ordering::Less
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{branch, cves, SecDb};
+    use crate::search::testing::query;
+
+    const SECDB: &str = r#"{
+        "apkurl": "{{urlprefix}}/{{distroversion}}/{{reponame}}/{{arch}}/{{pkg.name}}-{{pkg.ver}}.apk",
+        "archs": ["aarch64", "x86_64"],
+        "reponame": "main",
+        "urlprefix": "https://dl-cdn.alpinelinux.org/alpine",
+        "distroversion": "v3.19",
+        "packages": [
+            {"pkg": {"name": "openssl", "secfixes": {
+                "3.1.4-r1": ["CVE-2023-5678"],
+                "3.1.4-r3": ["CVE-2023-6129", "CVE-2023-6237"],
+                "0": ["CVE-2022-1343"]
+            }}},
+            {"pkg": {"name": "xen", "secfixes": {
+                "4.18.0-r2": ["CVE-2023-46837 XSA-449"]
+            }}}
+        ]
+    }"#;
+
+    #[test]
+    fn can_group_cves() {
+        let secdb: SecDb = serde_json::from_str(SECDB).unwrap();
+        let cves = cves(vec![secdb]);
+
+        let names: Vec<&str> = cves.iter().map(|cve| cve.name.as_str()).collect();
+        // the packages never affected are left out
+        assert_eq!(
+            vec![
+                "CVE-2023-46837",
+                "CVE-2023-5678",
+                "CVE-2023-6129",
+                "CVE-2023-6237"
+            ],
+            names
+        );
+        assert_eq!(vec!["alpine-3.19-xen"], cves[0].products());
+        assert_eq!("4.18.0-r2", cves[0].fixes[0].version);
+    }
+
+    #[test]
+    fn can_match_fixed_versions() {
+        let secdb: SecDb = serde_json::from_str(SECDB).unwrap();
+        let cves = cves(vec![secdb]);
+        let cve = cves.iter().find(|cve| cve.name == "CVE-2023-5678").unwrap();
+
+        assert!(cve.is_match(&query("alpine-3.19-openssl", "3.1.4-r0")));
+        assert!(cve.is_match(&query("alpine-3.19-openssl", "3.1.3-r5")));
+        assert!(!cve.is_match(&query("alpine-3.19-openssl", "3.1.4-r1")));
+        assert!(!cve.is_match(&query("alpine-3.19-openssl", "3.1.5-r0")));
+        assert!(!cve.is_match(&query("alpine-3.18-openssl", "3.1.4-r0")));
+    }
+
+    #[test]
+    fn can_name_branches() {
+        assert_eq!(Some("3.19".to_string()), branch("3.19"));
+        assert_eq!(Some("3.19".to_string()), branch("3.19.1"));
+        assert_eq!(Some("3.19".to_string()), branch("v3.19"));
+        assert_eq!(Some("edge".to_string()), branch("edge"));
+        assert_eq!(None, branch("3"));
+        assert_eq!(None, branch("bullseye"));
+    }
+}
@@ -0,0 +1,127 @@
+use std::fs::{self, File};
+use std::io::BufReader;
+use std::path::Path;
+
+use anyhow::{anyhow, Context, Result};
+use log::info;
+
+use super::{object_key, updateinfo, url, Advisory, SOURCE_NAME, VENDOR};
+
+use crate::db::{self, Pool};
+use crate::sources::redhat;
+use crate::utils::{download_to_file, gunzip, ImportError};
+
+/// Mirror lists of the repositories of the releases imported, giving the URL of the
+/// repository of the nearest region first.
+pub const MIRRORS: &[(&str, &str)] = &[
+    (
+        "amzn-1",
+        "http://repo.us-east-1.amazonaws.com/latest/updates/mirror.list",
+    ),
+    (
+        "amzn-2",
+        "https://cdn.amazonlinux.com/2/core/latest/x86_64/mirror.list",
+    ),
+    (
+        "amzn-2023",
+        "https://cdn.amazonlinux.com/al2023/core/mirrors/latest/x86_64/mirror.list",
+    ),
+];
+
+/// Downloads the `updateinfo` metadata of the repository of a release, going through its
+/// mirror list and its `repomd.xml` index.
+fn download_updateinfo(release_path: &Path, mirror_list: &str, file_path: &Path) -> Result<()> {
+    let mut list_path = release_path.to_path_buf();
+    list_path.set_extension("mirror.list");
+    download_to_file(mirror_list, &list_path)?;
+    let mirrors = fs::read_to_string(&list_path)
+        .with_context(|| format!("could not read {}", list_path.display()))?;
+    let mirror = mirrors
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .map(|line| line.trim_end_matches('/').to_string())
+        .ok_or_else(|| {
+            ImportError::from("the mirror list is empty".to_string()).quarantine(&list_path)
+        })?;
+
+    let mut repomd_path = release_path.to_path_buf();
+    repomd_path.set_extension("repomd.xml");
+    download_to_file(&format!("{}/repodata/repomd.xml", mirror), &repomd_path)?;
+    let repomd = fs::read_to_string(&repomd_path)
+        .with_context(|| format!("could not read {}", repomd_path.display()))?;
+    let location =
+        updateinfo::location(&repomd).map_err(|e| ImportError::from(e).quarantine(&repomd_path))?;
+
+    let mut gz_path = release_path.to_path_buf();
+    gz_path.set_extension("updateinfo.xml.gz");
+    download_to_file(&format!("{}/{}", mirror, location), &gz_path)?;
+    gunzip(&gz_path, file_path).map_err(|e| anyhow!(e))?;
+
+    for path in &[list_path, repomd_path, gz_path] {
+        fs::remove_file(path).with_context(|| format!("could not remove {}", path.display()))?;
+    }
+    Ok(())
+}
+
+fn store(database: &db::Database, advisory: &Advisory) -> Result<u32> {
+    redhat::advisory::import::store(
+        database,
+        SOURCE_NAME,
+        VENDOR,
+        object_key(&advisory.id),
+        url(advisory),
+        advisory,
+    )
+}
+
+/// Imports the CVEs of the Amazon Linux packages from the `updateinfo` metadata of the
+/// repositories of Amazon Linux 1, 2 and 2023, downloaded again with `fresh`, creating the
+/// new records and updating the changed ones.
+pub fn run(pool: &Pool, data_path: &Path, fresh: bool) -> Result<u32> {
+    let mut amazon_path = data_path.to_path_buf();
+    amazon_path.push("amazon");
+    fs::create_dir_all(&amazon_path)
+        .with_context(|| format!("could not create {}", amazon_path.display()))?;
+
+    let mut advisories = vec![];
+    for (release, mirror_list) in MIRRORS {
+        let mut release_path = amazon_path.clone();
+        release_path.push(release);
+        let mut file_path = release_path.clone();
+        file_path.set_extension("updateinfo.xml");
+
+        if fresh || !file_path.exists() {
+            download_updateinfo(&release_path, mirror_list, &file_path)?;
+        }
+
+        info!("parsing {} ...", file_path.display());
+        let file = File::open(&file_path)
+            .with_context(|| format!("could not open {}", file_path.display()))?;
+        let parsed = updateinfo::parse(BufReader::new(file), release)
+            .map_err(|e| ImportError::from(e).quarantine(&file_path))?;
+        // an empty updateinfo is a broken feed rather than no advisories ever published
+        if parsed.is_empty() {
+            return Err(
+                ImportError::from(format!("no advisories of {} found", release))
+                    .quarantine(&file_path)
+                    .into(),
+            );
+        }
+        advisories.extend(parsed);
+    }
+
+    info!("importing {} Amazon Linux advisories ...", advisories.len());
+
+    let database = db::Database(pool.get()?);
+    let mut num_imported = 0;
+    for (index, advisory) in advisories.iter().enumerate() {
+        num_imported += store(&database, advisory)?;
+
+        if index > 0 && index % 1000 == 0 {
+            info!("processed {} advisories ...", index);
+        }
+    }
+
+    Ok(num_imported)
+}
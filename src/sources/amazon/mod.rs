@@ -0,0 +1,74 @@
+//! CVEs of the Amazon Linux packages fixed by the Amazon Linux Security Advisories (ALAS),
+//! published in the `updateinfo` metadata of the package repositories of every release.
+
+pub use super::redhat::advisory::{product, Advisory, Fix};
+
+#[cfg(feature = "import")]
+pub mod import;
+#[cfg(feature = "import")]
+pub mod updateinfo;
+
+pub const SOURCE_NAME: &str = "Amazon";
+/// No vendors for the packages, the Amazon Linux release is part of the product name.
+pub const VENDOR: &str = "@amazon";
+
+/// Release of an Amazon Linux version, like `amzn-2` for `2` or `amzn-1` for `2018.03`.
+pub fn release(version: &str) -> Option<String> {
+    match version.split('.').next()? {
+        "1" | "2017" | "2018" => Some("amzn-1".to_string()),
+        "2" => Some("amzn-2".to_string()),
+        "2023" => Some("amzn-2023".to_string()),
+        _ => None,
+    }
+}
+
+pub fn object_key(id: &str) -> String {
+    format!("{} (amazon)", id)
+}
+
+/// Page of the advisory in the Amazon Linux Security Center.
+pub fn url(advisory: &Advisory) -> String {
+    match advisory.release() {
+        Some("amzn-2") => format!("https://alas.aws.amazon.com/AL2/{}.html", advisory.id),
+        Some("amzn-2023") => format!("https://alas.aws.amazon.com/AL2023/{}.html", advisory.id),
+        _ => format!("https://alas.aws.amazon.com/{}.html", advisory.id),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{release, url, Advisory, Fix};
+    use crate::search::testing::query;
+
+    #[test]
+    fn can_name_releases() {
+        assert_eq!(Some("amzn-2".to_string()), release("2"));
+        assert_eq!(Some("amzn-2023".to_string()), release("2023"));
+        assert_eq!(Some("amzn-1".to_string()), release("2018.03"));
+        assert_eq!(None, release("2022"));
+    }
+
+    #[test]
+    fn can_match_fixed_versions() {
+        let advisory = Advisory {
+            id: "ALAS2-2023-2000".into(),
+            severity: Some("important".into()),
+            fixes: vec![Fix {
+                release: "amzn-2".into(),
+                package: "openssl".into(),
+                version: "1:1.0.2k-24.amzn2.0.7".into(),
+            }],
+            ..Default::default()
+        };
+
+        assert_eq!("HIGH", advisory.severity());
+        assert_eq!(
+            "https://alas.aws.amazon.com/AL2/ALAS2-2023-2000.html",
+            url(&advisory)
+        );
+        assert_eq!(vec!["amzn-2-openssl"], advisory.products());
+        assert!(advisory.is_match(&query("amzn-2-openssl", "1.0.2k-24.amzn2.0.6")));
+        assert!(!advisory.is_match(&query("amzn-2-openssl", "1:1.0.2k-24.amzn2.0.7")));
+        assert!(!advisory.is_match(&query("amzn-2023-openssl", "1.0.2k-1")));
+    }
+}
@@ -0,0 +1,250 @@
+//! Parser of the `updateinfo` metadata of the Amazon Linux repositories, listing the
+//! advisories along with the packages fixing them.
+
+use std::io::BufRead;
+
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+
+use super::{Advisory, Fix};
+use crate::utils::normalize_utc;
+
+/// Element whose text is being read.
+enum Text {
+    None,
+    Id,
+    Title,
+    Severity,
+    Description,
+}
+
+fn attribute(element: &BytesStart, name: &[u8]) -> Option<String> {
+    element
+        .attributes()
+        .flatten()
+        .find(|attribute| attribute.key == name)
+        .and_then(|attribute| {
+            attribute
+                .unescaped_value()
+                .ok()
+                .map(|value| String::from_utf8_lossy(&value).into_owned())
+        })
+}
+
+/// Location of the `updateinfo` metadata given by the `repomd.xml` index of a repository, like
+/// `repodata/updateinfo.xml.gz`.
+pub fn location(repomd: &str) -> Result<String, String> {
+    let mut reader = Reader::from_str(repomd);
+    reader.trim_text(true);
+
+    let mut is_updateinfo = false;
+    let mut buf = vec![];
+    loop {
+        match reader.read_event(&mut buf) {
+            Ok(Event::Start(element)) | Ok(Event::Empty(element)) => match element.local_name() {
+                b"data" => {
+                    is_updateinfo = attribute(&element, b"type").as_deref() == Some("updateinfo")
+                }
+                b"location" if is_updateinfo => {
+                    if let Some(href) = attribute(&element, b"href") {
+                        return Ok(href);
+                    }
+                }
+                _ => {}
+            },
+            Ok(Event::End(element)) if element.local_name() == b"data" => is_updateinfo = false,
+            Ok(Event::Eof) => return Err("no updateinfo in the repository index".into()),
+            Err(e) => return Err(format!("could not parse the repository index: {}", e)),
+            _ => {}
+        }
+        buf.clear();
+    }
+}
+
+/// Parses the security advisories of the `updateinfo` metadata of a release, like `amzn-2`.
+pub fn parse<R: BufRead>(source: R, release: &str) -> Result<Vec<Advisory>, String> {
+    let mut reader = Reader::from_reader(source);
+    reader.trim_text(true);
+
+    let mut advisories = vec![];
+    let mut advisory: Option<Advisory> = None;
+    let mut text = Text::None;
+
+    let mut buf = vec![];
+    loop {
+        let event = reader.read_event(&mut buf).map_err(|e| {
+            format!(
+                "could not parse the updateinfo at {}: {}",
+                reader.buffer_position(),
+                e
+            )
+        })?;
+
+        match &event {
+            Event::Start(element) | Event::Empty(element) => match element.local_name() {
+                b"update" if matches!(event, Event::Start(_)) => {
+                    // the bug fixes and the enhancements fix no CVEs
+                    if attribute(element, b"type").as_deref() == Some("security") {
+                        advisory = Some(Advisory::default());
+                    }
+                }
+                b"id" => text = Text::Id,
+                b"title" => text = Text::Title,
+                b"severity" => text = Text::Severity,
+                b"description" => text = Text::Description,
+                b"issued" => {
+                    if let Some(advisory) = &mut advisory {
+                        advisory.issued =
+                            normalize_utc(&attribute(element, b"date").unwrap_or_default());
+                    }
+                }
+                b"reference" => {
+                    if let (Some(advisory), Some("cve")) =
+                        (&mut advisory, attribute(element, b"type").as_deref())
+                    {
+                        if let Some(id) = attribute(element, b"id") {
+                            if !advisory.cves.contains(&id) {
+                                advisory.cves.push(id);
+                            }
+                        }
+                    }
+                }
+                b"package" => {
+                    if let (Some(advisory), Some(package)) =
+                        (&mut advisory, attribute(element, b"name"))
+                    {
+                        let version = attribute(element, b"version").unwrap_or_default();
+                        let rpm_release = attribute(element, b"release").unwrap_or_default();
+                        let version = match attribute(element, b"epoch").as_deref() {
+                            Some("0") | None => format!("{}-{}", version, rpm_release),
+                            Some(epoch) => format!("{}:{}-{}", epoch, version, rpm_release),
+                        };
+                        // the same fix for every architecture
+                        let fix = Fix {
+                            release: release.to_string(),
+                            package,
+                            version,
+                        };
+                        if !advisory.fixes.contains(&fix) {
+                            advisory.fixes.push(fix);
+                        }
+                    }
+                }
+                _ => {}
+            },
+            Event::Text(value) => {
+                let value = value
+                    .unescape_and_decode(&reader)
+                    .map_err(|e| format!("could not decode the updateinfo: {}", e))?;
+                if let Some(advisory) = &mut advisory {
+                    match text {
+                        Text::Id => advisory.id = value,
+                        Text::Title => advisory.title = value,
+                        Text::Severity => advisory.severity = Some(value),
+                        Text::Description => advisory.description = value,
+                        Text::None => {}
+                    }
+                }
+            }
+            Event::End(element) => {
+                text = Text::None;
+                if element.local_name() == b"update" {
+                    advisories.extend(advisory.take().filter(|advisory| !advisory.id.is_empty()));
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+
+        buf.clear();
+    }
+
+    Ok(advisories)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{location, parse};
+
+    const REPOMD: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<repomd xmlns="http://linux.duke.edu/metadata/repo">
+  <data type="primary">
+    <location href="repodata/primary.sqlite.bz2"/>
+  </data>
+  <data type="updateinfo">
+    <checksum type="sha256">5e4a</checksum>
+    <location href="repodata/updateinfo.xml.gz"/>
+  </data>
+</repomd>"#;
+
+    const UPDATEINFO: &str = r#"<?xml version="1.0" ?>
+<updates>
+  <update author="linux-security@amazon.com" from="linux-security@amazon.com" status="final" type="security" version="2.0">
+    <id>ALAS2-2023-2000</id>
+    <title>Amazon Linux 2 - ALAS2-2023-2000: important priority package update for openssl</title>
+    <issued date="2023-03-09 21:32" />
+    <updated date="2023-03-13 19:26" />
+    <severity>important</severity>
+    <description>Package updates are available for Amazon Linux 2 that fix the following vulnerabilities:
+CVE-2023-0286:
+	A type confusion vulnerability &amp; more.</description>
+    <references>
+      <reference href="https://cve.mitre.org/cgi-bin/cvename.cgi?name=CVE-2023-0286" id="CVE-2023-0286" title="" type="cve" />
+      <reference href="https://cve.mitre.org/cgi-bin/cvename.cgi?name=CVE-2023-0215" id="CVE-2023-0215" title="" type="cve" />
+    </references>
+    <pkglist>
+      <collection short="amazon-linux-2">
+        <name>Amazon Linux 2</name>
+        <package arch="x86_64" epoch="1" name="openssl" release="24.amzn2.0.7" version="1.0.2k">
+          <filename>Packages/openssl-1.0.2k-24.amzn2.0.7.x86_64.rpm</filename>
+        </package>
+        <package arch="aarch64" epoch="1" name="openssl" release="24.amzn2.0.7" version="1.0.2k">
+          <filename>Packages/openssl-1.0.2k-24.amzn2.0.7.aarch64.rpm</filename>
+        </package>
+        <package arch="x86_64" epoch="0" name="openssl-perl" release="24.amzn2.0.7" version="1.0.2k">
+          <filename>Packages/openssl-perl-1.0.2k-24.amzn2.0.7.x86_64.rpm</filename>
+        </package>
+      </collection>
+    </pkglist>
+  </update>
+  <update status="final" type="bugfix" version="2.0">
+    <id>ALAS2-2023-2001</id>
+    <pkglist>
+      <collection>
+        <package arch="x86_64" epoch="0" name="tzdata" release="1.amzn2" version="2023c" />
+      </collection>
+    </pkglist>
+  </update>
+</updates>"#;
+
+    #[test]
+    fn can_locate_the_updateinfo() {
+        assert_eq!(
+            Ok("repodata/updateinfo.xml.gz".to_string()),
+            location(REPOMD)
+        );
+        assert!(location("<repomd></repomd>").is_err());
+    }
+
+    #[test]
+    fn can_parse_security_advisories() {
+        let advisories = parse(UPDATEINFO.as_bytes(), "amzn-2").unwrap();
+        assert_eq!(1, advisories.len());
+
+        let advisory = &advisories[0];
+        assert_eq!("ALAS2-2023-2000", advisory.id);
+        assert_eq!(Some("amzn-2"), advisory.release());
+        assert_eq!("HIGH", advisory.severity());
+        assert_eq!("2023-03-09T21:32:00Z", advisory.issued);
+        assert!(advisory.description.ends_with("& more."));
+        assert_eq!(vec!["CVE-2023-0286", "CVE-2023-0215"], advisory.cves);
+        assert_eq!(2, advisory.fixes.len());
+        assert_eq!("1:1.0.2k-24.amzn2.0.7", advisory.fixes[0].version);
+        assert_eq!("openssl-perl", advisory.fixes[1].package);
+        assert_eq!("1.0.2k-24.amzn2.0.7", advisory.fixes[1].version);
+        assert_eq!(
+            vec!["amzn-2-openssl", "amzn-2-openssl-perl"],
+            advisory.products()
+        );
+    }
+}
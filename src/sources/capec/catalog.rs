@@ -0,0 +1,177 @@
+//! Parser of the XML catalog of the CAPEC, like `capec_v3.9.xml`.
+
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+
+use super::AttackPattern;
+use crate::sources::cwe;
+
+/// Element whose text is being read.
+enum Text {
+    None,
+    Description,
+    Likelihood,
+    Severity,
+}
+
+fn attribute(element: &BytesStart, name: &[u8]) -> Option<String> {
+    element
+        .attributes()
+        .flatten()
+        .find(|attribute| attribute.key == name)
+        .and_then(|attribute| {
+            attribute
+                .unescaped_value()
+                .ok()
+                .map(|value| String::from_utf8_lossy(&value).into_owned())
+        })
+}
+
+/// Parses the attack patterns of the catalog relating to a weakness, leaving out the
+/// deprecated ones.
+pub fn parse(xml: &str) -> Result<Vec<AttackPattern>, String> {
+    let mut reader = Reader::from_str(xml);
+    reader.trim_text(true);
+
+    let mut patterns = vec![];
+    let mut pattern: Option<AttackPattern> = None;
+    // the description of a pattern is a direct child of it, and can hold XHTML paragraphs
+    let mut depth = 0;
+    let mut pattern_depth = 0;
+    let mut text_depth = 0;
+    let mut text = Text::None;
+
+    let mut buf = vec![];
+    loop {
+        let event = reader
+            .read_event(&mut buf)
+            .map_err(|e| format!("could not parse at {}: {}", reader.buffer_position(), e))?;
+
+        match &event {
+            Event::Start(element) => {
+                depth += 1;
+                let child = pattern.is_some() && depth == pattern_depth + 1;
+                match element.local_name() {
+                    b"Attack_Pattern" => {
+                        pattern = Some(AttackPattern {
+                            id: format!("CAPEC-{}", attribute(element, b"ID").unwrap_or_default()),
+                            name: attribute(element, b"Name").unwrap_or_default(),
+                            abstraction: attribute(element, b"Abstraction"),
+                            status: attribute(element, b"Status").unwrap_or_default(),
+                            ..Default::default()
+                        });
+                        pattern_depth = depth;
+                    }
+                    b"Description" if child => text = Text::Description,
+                    b"Likelihood_Of_Attack" if child => text = Text::Likelihood,
+                    b"Typical_Severity" if child => text = Text::Severity,
+                    _ => {}
+                }
+                if child && !matches!(text, Text::None) {
+                    text_depth = depth;
+                }
+            }
+            Event::Empty(element) => {
+                if element.local_name() == b"Related_Weakness" {
+                    if let Some(pattern) = &mut pattern {
+                        let id = attribute(element, b"CWE_ID").unwrap_or_default();
+                        if let Some(id) = cwe::normalize(&format!("CWE-{}", id)) {
+                            if !pattern.weaknesses.contains(&id) {
+                                pattern.weaknesses.push(id);
+                            }
+                        }
+                    }
+                }
+            }
+            Event::Text(value) => {
+                let value = value
+                    .unescape_and_decode(&reader)
+                    .map_err(|e| format!("could not decode: {}", e))?;
+                if let Some(pattern) = &mut pattern {
+                    match text {
+                        Text::Description => {
+                            if !pattern.description.is_empty() {
+                                pattern.description.push(' ');
+                            }
+                            pattern.description.push_str(&value);
+                        }
+                        Text::Likelihood => pattern.likelihood = Some(value),
+                        Text::Severity => pattern.severity = Some(value),
+                        Text::None => {}
+                    }
+                }
+            }
+            Event::End(element) => {
+                if depth == text_depth {
+                    text = Text::None;
+                }
+                depth -= 1;
+                if element.local_name() == b"Attack_Pattern" {
+                    if let Some(pattern) = pattern.take() {
+                        if pattern.status != "Deprecated" && !pattern.weaknesses.is_empty() {
+                            patterns.push(pattern);
+                        }
+                    }
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+
+        buf.clear();
+    }
+
+    Ok(patterns)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse;
+
+    const CATALOG: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<Attack_Pattern_Catalog Name="CAPEC" Version="3.9" Date="2023-01-24" xmlns="http://capec.mitre.org/capec-3" xmlns:xhtml="http://www.w3.org/1999/xhtml">
+  <Attack_Patterns>
+    <Attack_Pattern ID="586" Name="Object Injection" Abstraction="Meta" Status="Draft">
+      <Description>An adversary attempts to exploit an application that injects <xhtml:i>untrusted</xhtml:i> data.</Description>
+      <Likelihood_Of_Attack>Medium</Likelihood_Of_Attack>
+      <Typical_Severity>High</Typical_Severity>
+      <Execution_Flow>
+        <Attack_Step>
+          <Description>Not the description of the pattern.</Description>
+        </Attack_Step>
+      </Execution_Flow>
+      <Related_Weaknesses>
+        <Related_Weakness CWE_ID="502"/>
+        <Related_Weakness CWE_ID="502"/>
+      </Related_Weaknesses>
+    </Attack_Pattern>
+    <Attack_Pattern ID="13" Name="Subverting Environment Variable Values" Abstraction="Standard" Status="Deprecated">
+      <Description>Deprecated.</Description>
+      <Related_Weaknesses>
+        <Related_Weakness CWE_ID="353"/>
+      </Related_Weaknesses>
+    </Attack_Pattern>
+    <Attack_Pattern ID="1000" Name="Without Weakness" Abstraction="Standard" Status="Draft">
+      <Description>No weakness.</Description>
+    </Attack_Pattern>
+  </Attack_Patterns>
+</Attack_Pattern_Catalog>"#;
+
+    #[test]
+    fn can_parse_catalogs() {
+        let patterns = parse(CATALOG).unwrap();
+        assert_eq!(1, patterns.len());
+
+        let pattern = &patterns[0];
+        assert_eq!("CAPEC-586", pattern.id);
+        assert_eq!("Object Injection", pattern.name);
+        assert_eq!(Some("Meta".to_string()), pattern.abstraction);
+        assert_eq!(Some("Medium".to_string()), pattern.likelihood);
+        assert_eq!(Some("High".to_string()), pattern.severity);
+        assert_eq!(
+            "An adversary attempts to exploit an application that injects untrusted data.",
+            pattern.description
+        );
+        assert_eq!(vec!["CWE-502"], pattern.weaknesses);
+    }
+}
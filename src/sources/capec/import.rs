@@ -0,0 +1,51 @@
+use std::fs;
+use std::path::Path;
+
+use anyhow::{anyhow, Context, Result};
+use log::info;
+
+use super::catalog;
+use crate::db::{self, Pool};
+use crate::utils::{download_to_file, ImportError};
+
+pub const FEED_URL: &str = "https://capec.mitre.org/data/xml/capec_latest.xml";
+
+/// Imports the attack patterns of the CAPEC catalog relating to a weakness, replacing the
+/// previous ones, and returns the number of patterns added.
+pub fn run(pool: &Pool, data_path: &Path, fresh: bool) -> Result<u32> {
+    let mut capec_path = data_path.to_path_buf();
+    capec_path.push("capec");
+    fs::create_dir_all(&capec_path)
+        .with_context(|| format!("could not create {}", capec_path.display()))?;
+
+    let mut file_path = capec_path;
+    file_path.push("capec_latest.xml");
+    if fresh || !file_path.exists() {
+        download_to_file(FEED_URL, &file_path)?;
+    }
+
+    let xml = fs::read_to_string(&file_path)
+        .with_context(|| format!("could not read {}", file_path.display()))?;
+    let patterns = catalog::parse(&xml).map_err(|e| {
+        ImportError::from(format!("could not parse the catalog: {}", e)).quarantine(&file_path)
+    })?;
+    // an empty catalog is a broken download rather than all the patterns being removed
+    if patterns.is_empty() {
+        return Err(ImportError::from("no attack pattern found".to_string())
+            .quarantine(&file_path)
+            .into());
+    }
+
+    info!("importing {} attack patterns ...", patterns.len());
+
+    let num_added = db::Database(pool.get()?)
+        .replace_attack_patterns(
+            patterns
+                .iter()
+                .flat_map(db::models::NewAttackPattern::with)
+                .collect(),
+        )
+        .map_err(|e| anyhow!(e))?;
+
+    Ok(num_added as u32)
+}
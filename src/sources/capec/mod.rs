@@ -0,0 +1,143 @@
+//! Catalog of the Common Attack Pattern Enumeration and Classification (CAPEC) of MITRE,
+//! giving the attack patterns likely to exploit a CVE through its weaknesses.
+
+use serde::Serialize;
+
+use crate::db::models;
+
+#[cfg(feature = "import")]
+pub mod catalog;
+#[cfg(feature = "import")]
+pub mod import;
+
+pub const SOURCE_NAME: &str = "CAPEC";
+
+/// Attack pattern of the catalog.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct AttackPattern {
+    /// Like `CAPEC-586`.
+    pub id: String,
+    pub name: String,
+    /// `Meta`, `Standard` or `Detailed`.
+    pub abstraction: Option<String>,
+    /// Like `Stable`, `Draft` or `Deprecated`.
+    pub status: String,
+    /// Typical severity, from `Very Low` to `Very High`.
+    pub severity: Option<String>,
+    /// Likelihood of attack, from `Very Low` to `Very High`.
+    pub likelihood: Option<String>,
+    pub description: String,
+    /// CWE ids of the weaknesses exploited, like `CWE-502`.
+    pub weaknesses: Vec<String>,
+}
+
+/// Attack pattern likely to exploit a CVE, through the weaknesses of the CVE it relates to.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Linked {
+    pub id: String,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub abstraction: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub severity: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub likelihood: Option<String>,
+    pub description: String,
+    /// Weaknesses of the CVE exploited by the pattern.
+    pub weaknesses: Vec<String>,
+    pub url: String,
+}
+
+/// Rank of a severity or a likelihood, the unknown ones last.
+fn rank(level: Option<&str>) -> u8 {
+    match level {
+        Some("Very High") => 5,
+        Some("High") => 4,
+        Some("Medium") => 3,
+        Some("Low") => 2,
+        Some("Very Low") => 1,
+        _ => 0,
+    }
+}
+
+/// Groups the entries of the patterns, one for every weakness, ranking the most severe and
+/// likely patterns first.
+pub fn link(entries: Vec<models::AttackPattern>) -> Vec<Linked> {
+    let mut patterns: Vec<Linked> = vec![];
+    for entry in entries {
+        match patterns
+            .iter_mut()
+            .find(|pattern| pattern.id == entry.capec)
+        {
+            Some(pattern) => {
+                if !pattern.weaknesses.contains(&entry.cwe) {
+                    pattern.weaknesses.push(entry.cwe);
+                }
+            }
+            None => patterns.push(Linked {
+                url: format!(
+                    "https://capec.mitre.org/data/definitions/{}.html",
+                    entry.capec.trim_start_matches("CAPEC-")
+                ),
+                id: entry.capec,
+                name: entry.name,
+                abstraction: entry.abstraction,
+                severity: entry.severity,
+                likelihood: entry.likelihood,
+                description: entry.description,
+                weaknesses: vec![entry.cwe],
+            }),
+        }
+    }
+
+    patterns.sort_by(|a, b| {
+        rank(b.severity.as_deref())
+            .cmp(&rank(a.severity.as_deref()))
+            .then_with(|| rank(b.likelihood.as_deref()).cmp(&rank(a.likelihood.as_deref())))
+            .then_with(|| a.id.cmp(&b.id))
+    });
+    patterns
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::link;
+    use crate::db::models;
+
+    fn entry(capec: &str, cwe: &str, severity: Option<&str>) -> models::AttackPattern {
+        models::AttackPattern {
+            id: 0,
+            created_at: Utc::now(),
+            capec: capec.into(),
+            cwe: cwe.into(),
+            name: format!("{} pattern", capec),
+            abstraction: Some("Standard".into()),
+            status: "Draft".into(),
+            severity: severity.map(str::to_string),
+            likelihood: Some("Medium".into()),
+            description: String::new(),
+        }
+    }
+
+    #[test]
+    fn can_link_patterns() {
+        let patterns = link(vec![
+            entry("CAPEC-153", "CWE-20", Some("Medium")),
+            entry("CAPEC-586", "CWE-502", Some("High")),
+            entry("CAPEC-586", "CWE-20", Some("High")),
+            entry("CAPEC-9", "CWE-20", None),
+        ]);
+
+        assert_eq!(3, patterns.len());
+        assert_eq!("CAPEC-586", patterns[0].id);
+        assert_eq!(vec!["CWE-502", "CWE-20"], patterns[0].weaknesses);
+        assert_eq!(
+            "https://capec.mitre.org/data/definitions/586.html",
+            patterns[0].url
+        );
+        assert_eq!("CAPEC-153", patterns[1].id);
+        assert_eq!("CAPEC-9", patterns[2].id);
+    }
+}
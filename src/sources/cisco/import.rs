@@ -0,0 +1,206 @@
+use std::collections::BTreeMap;
+use std::env;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, bail, Context, Result};
+use log::info;
+use serde::de::DeserializeOwned;
+
+use super::{openvuln, Advisory, OS_TYPES, SOURCE_NAME, VENDOR};
+
+use crate::db::{self, Pool};
+use crate::utils::{parse_json, send_with_retries};
+
+pub const TOKEN_URL: &str = "https://id.cisco.com/oauth2/default/v1/token";
+pub const API_URL: &str = "https://apix.cisco.com/security/advisories/v2";
+/// Pause between the requests, as the API allows a few of them per second.
+const REQUEST_INTERVAL: Duration = Duration::from_millis(200);
+/// The token is renewed shortly before it expires.
+const TOKEN_MARGIN: Duration = Duration::from_secs(60);
+
+/// Client of the openVuln API, authenticated with the OAuth2 client credentials set in
+/// `CISCO_CLIENT_ID` and `CISCO_CLIENT_SECRET`.
+struct Client {
+    http: reqwest::blocking::Client,
+    client_id: String,
+    client_secret: String,
+    token: Option<(String, Instant)>,
+}
+
+impl Client {
+    fn from_env() -> Result<Self> {
+        let var = |name: &str| {
+            env::var(name)
+                .ok()
+                .filter(|value| !value.is_empty())
+                .with_context(|| {
+                    format!(
+                        "{} is not set, the openVuln API requires the client credentials",
+                        name
+                    )
+                })
+        };
+
+        Ok(Self {
+            http: reqwest::blocking::Client::builder()
+                .timeout(Some(Duration::from_secs(300)))
+                .user_agent(format!("kepler/{}", crate::version()))
+                .build()?,
+            client_id: var("CISCO_CLIENT_ID")?,
+            client_secret: var("CISCO_CLIENT_SECRET")?,
+            token: None,
+        })
+    }
+
+    /// Access token, requested again once expired.
+    fn token(&mut self) -> Result<String> {
+        if let Some((token, expires)) = &self.token {
+            if Instant::now() < *expires {
+                return Ok(token.clone());
+            }
+        }
+
+        let res = self
+            .http
+            .post(TOKEN_URL)
+            .form(&[
+                ("grant_type", "client_credentials"),
+                ("client_id", &self.client_id),
+                ("client_secret", &self.client_secret),
+            ])
+            .send()
+            .with_context(|| format!("error requesting {}", TOKEN_URL))?;
+        let status = res.status();
+        if !status.is_success() {
+            bail!("error requesting {}: {}", TOKEN_URL, status);
+        }
+
+        let token: openvuln::Token = res
+            .json()
+            .with_context(|| format!("could not parse the response of {}", TOKEN_URL))?;
+        let valid = Duration::from_secs(token.expires_in).saturating_sub(TOKEN_MARGIN);
+        self.token = Some((token.access_token.clone(), Instant::now() + valid));
+        Ok(token.access_token)
+    }
+
+    /// Gets a resource of the API, none when not found.
+    fn get<T: DeserializeOwned>(&mut self, url: &str) -> Result<Option<T>> {
+        let mut res = self.send(url)?;
+        // the token can be revoked before it expires
+        if res.status().as_u16() == 401 {
+            self.token = None;
+            res = self.send(url)?;
+        }
+        // the releases without advisories are not found
+        if res.status().as_u16() == 404 {
+            return Ok(None);
+        }
+
+        Ok(Some(parse_json(url, res)?))
+    }
+
+    fn send(&mut self, url: &str) -> Result<reqwest::blocking::Response> {
+        let token = self.token()?;
+        let http = &self.http;
+        Ok(send_with_retries(url, || {
+            thread::sleep(REQUEST_INTERVAL);
+            http.get(url)
+                .bearer_auth(&token)
+                .header(reqwest::header::ACCEPT, "application/json")
+        })?)
+    }
+}
+
+fn store(database: &db::Database, advisory: &Advisory) -> Result<u32> {
+    let products = advisory.products();
+    if products.is_empty() || advisory.cves.is_empty() {
+        return Ok(0);
+    }
+
+    let object_id = database
+        .upsert_object(db::models::NewObject::with(
+            Advisory::object_key(&advisory.id),
+            serde_json::to_string(advisory)?,
+        ))
+        .map_err(|e| anyhow!(e))?;
+
+    let mut refs = db::models::References::default();
+    if !advisory.url.is_empty() {
+        refs.push(db::models::Reference {
+            url: advisory.url.clone(),
+            tags: vec!["Vendor Advisory".into()],
+        });
+    }
+
+    let summary = if advisory.summary.is_empty() {
+        advisory.title.clone()
+    } else {
+        advisory.summary.clone()
+    };
+
+    let mut num_imported = 0;
+    for cve in &advisory.cves {
+        for product in &products {
+            let new_cve = db::models::NewCVE::with(
+                SOURCE_NAME.into(),
+                VENDOR.into(),
+                product.clone(),
+                cve.clone(),
+                summary.clone(),
+                advisory.score.unwrap_or_default(),
+                advisory.severity(),
+                None,
+                refs.clone(),
+                Some(object_id),
+            );
+            if database.upsert_cve(new_cve).map_err(|e| anyhow!(e))? {
+                num_imported += 1;
+            }
+        }
+    }
+
+    Ok(num_imported)
+}
+
+/// Imports the advisories affecting every IOS and IOS XE release known to the openVuln API,
+/// creating the new records and updating the changed ones.
+pub fn run(pool: &Pool) -> Result<u32> {
+    let mut client = Client::from_env()?;
+
+    let mut advisories = BTreeMap::new();
+    for (os_type, product) in OS_TYPES {
+        let url = format!("{}/OS_version/OS_data?OSType={}", API_URL, os_type);
+        let data: Vec<openvuln::OsData> = client.get(&url)?.unwrap_or_default();
+        let versions = openvuln::versions(&data);
+        info!(
+            "querying the advisories of {} {} releases ...",
+            versions.len(),
+            os_type
+        );
+
+        for (index, version) in versions.iter().enumerate() {
+            let url = reqwest::Url::parse_with_params(
+                &format!("{}/OSType/{}", API_URL, os_type),
+                &[("version", version)],
+            )?;
+            if let Some(response) = client.get::<openvuln::Response>(url.as_str())? {
+                openvuln::merge(&mut advisories, product, version, &response);
+            }
+
+            if index > 0 && index % 100 == 0 {
+                info!("queried {} releases ...", index);
+            }
+        }
+    }
+
+    info!("importing {} advisories ...", advisories.len());
+
+    let database = db::Database(pool.get()?);
+    let mut num_imported = 0;
+    for advisory in advisories.values() {
+        num_imported += store(&database, advisory)?;
+    }
+
+    Ok(num_imported)
+}
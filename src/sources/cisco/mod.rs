@@ -0,0 +1,159 @@
+//! CVEs of the Cisco IOS and IOS XE releases published in the Cisco Security Advisories, with
+//! the releases affected by every advisory as reported by the Cisco openVuln API.
+
+use serde::{Deserialize, Serialize};
+
+use crate::search::Query;
+
+#[cfg(feature = "import")]
+pub mod import;
+#[cfg(feature = "import")]
+pub mod openvuln;
+
+pub const SOURCE_NAME: &str = "Cisco";
+/// Kept apart from the `cisco` vendor of the NIST records.
+pub const VENDOR: &str = "@cisco";
+
+/// Operating systems imported, with the product they're named after like the NIST products.
+pub const OS_TYPES: &[(&str, &str)] = &[("ios", "ios"), ("iosxe", "ios_xe")];
+
+/// Releases of a product affected by an advisory, which Cisco names in ways the version
+/// ranges can't capture, like `15.2(4)M11` or `17.3.4a`.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Affected {
+    /// Like `ios_xe`.
+    pub product: String,
+    pub versions: Vec<String>,
+    /// Like `17.3.5`.
+    #[serde(default)]
+    pub first_fixed: Vec<String>,
+}
+
+#[derive(Debug, Default, Clone, Deserialize, Serialize)]
+pub struct Advisory {
+    /// Like `cisco-sa-iosxe-webui-privesc-j22SaA4z`.
+    pub id: String,
+    #[serde(default)]
+    pub title: String,
+    #[serde(default)]
+    pub summary: String,
+    /// Security impact rating, `Critical`, `High`, `Medium`, `Low` or `Informational`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sir: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub score: Option<f64>,
+    #[serde(default)]
+    pub url: String,
+    #[serde(default)]
+    pub published: String,
+    #[serde(default)]
+    pub cves: Vec<String>,
+    pub affected: Vec<Affected>,
+}
+
+impl Advisory {
+    pub fn object_key(id: &str) -> String {
+        format!("{} (cisco)", id)
+    }
+
+    /// Severity rated by Cisco, named like the NIST severities.
+    pub fn severity(&self) -> String {
+        match self.sir.as_deref().map(str::to_lowercase).as_deref() {
+            Some("critical") => "CRITICAL",
+            Some("high") => "HIGH",
+            Some("medium") => "MEDIUM",
+            Some("low") => "LOW",
+            _ => "NONE",
+        }
+        .to_string()
+    }
+
+    /// Unique products affected.
+    pub fn products(&self) -> Vec<String> {
+        let mut products: Vec<String> = vec![];
+        for affected in &self.affected {
+            if !products.contains(&affected.product) {
+                products.push(affected.product.clone());
+            }
+        }
+        products
+    }
+
+    /// Records a release of a product affected by the advisory.
+    pub fn add_affected(&mut self, product: &str, version: &str, first_fixed: &[String]) {
+        let index = match self
+            .affected
+            .iter()
+            .position(|affected| affected.product == product)
+        {
+            Some(index) => index,
+            None => {
+                self.affected.push(Affected {
+                    product: product.to_string(),
+                    ..Default::default()
+                });
+                self.affected.len() - 1
+            }
+        };
+
+        let affected = &mut self.affected[index];
+        if !affected.versions.iter().any(|v| v == version) {
+            affected.versions.push(version.to_string());
+        }
+        for fixed in first_fixed {
+            if !affected.first_fixed.contains(fixed) {
+                affected.first_fixed.push(fixed.clone());
+            }
+        }
+    }
+
+    /// Matches the releases affected, compared without case.
+    pub fn is_match(&self, query: &Query) -> bool {
+        // we need a version
+        let version = match &query.version {
+            Some(version) => version.trim(),
+            None => return false,
+        };
+
+        self.affected.iter().any(|affected| {
+            affected.product == query.product
+                && affected
+                    .versions
+                    .iter()
+                    .any(|affected| affected.eq_ignore_ascii_case(version))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Advisory;
+    use crate::search::testing::query;
+
+    #[test]
+    fn can_match_affected_releases() {
+        let mut advisory = Advisory {
+            id: "cisco-sa-iosxe-webui-privesc-j22SaA4z".into(),
+            sir: Some("Critical".into()),
+            cves: vec!["CVE-2023-20198".into()],
+            ..Default::default()
+        };
+        advisory.add_affected("ios_xe", "17.3.1", &["17.3.8a".into()]);
+        advisory.add_affected("ios_xe", "17.3.1", &["17.3.8a".into()]);
+        advisory.add_affected("ios_xe", "16.12.4", &["16.12.10a".into()]);
+        advisory.add_affected("ios", "15.2(4)M11", &[]);
+
+        assert_eq!("CRITICAL", advisory.severity());
+        assert_eq!(vec!["ios_xe", "ios"], advisory.products());
+        assert_eq!(vec!["17.3.1", "16.12.4"], advisory.affected[0].versions);
+        assert_eq!(
+            vec!["17.3.8a", "16.12.10a"],
+            advisory.affected[0].first_fixed
+        );
+
+        assert!(advisory.is_match(&query("ios_xe", "17.3.1")));
+        assert!(advisory.is_match(&query("ios", "15.2(4)m11")));
+        assert!(!advisory.is_match(&query("ios_xe", "17.3.8a")));
+        assert!(!advisory.is_match(&query("ios", "17.3.1")));
+    }
+}
@@ -0,0 +1,169 @@
+//! Responses of the Cisco openVuln API, merged into the advisories of the releases affected.
+
+use std::collections::BTreeMap;
+
+use serde::Deserialize;
+
+use super::Advisory;
+use crate::utils::{html_text, normalize_utc};
+
+/// Access token of the OAuth2 client credentials.
+#[derive(Debug, Deserialize)]
+pub struct Token {
+    pub access_token: String,
+    /// Seconds the token is valid for.
+    #[serde(default)]
+    pub expires_in: u64,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum Versions {
+    One(String),
+    Many(Vec<String>),
+}
+
+/// Releases of an operating system known to the API.
+#[derive(Debug, Deserialize)]
+pub struct OsData {
+    #[serde(alias = "versions")]
+    pub version: Versions,
+}
+
+/// Lists the releases of the OS data, without duplicates.
+pub fn versions(data: &[OsData]) -> Vec<String> {
+    let mut versions: Vec<String> = vec![];
+    for data in data {
+        let listed = match &data.version {
+            Versions::One(version) => vec![version.clone()],
+            Versions::Many(listed) => listed.clone(),
+        };
+        for version in listed {
+            let version = version.trim().to_string();
+            if !version.is_empty() && !versions.contains(&version) {
+                versions.push(version);
+            }
+        }
+    }
+    versions
+}
+
+/// Advisories affecting a release.
+#[derive(Debug, Default, Deserialize)]
+pub struct Response {
+    #[serde(default)]
+    pub advisories: Vec<OpenVulnAdvisory>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpenVulnAdvisory {
+    pub advisory_id: String,
+    #[serde(default)]
+    pub advisory_title: String,
+    /// In HTML.
+    #[serde(default)]
+    pub summary: String,
+    #[serde(default)]
+    pub sir: Option<String>,
+    /// Like `8.6`, or `NA`.
+    #[serde(default)]
+    pub cvss_base_score: Option<String>,
+    #[serde(default)]
+    pub cves: Vec<String>,
+    #[serde(default)]
+    pub publication_url: String,
+    #[serde(default)]
+    pub first_published: String,
+    #[serde(default)]
+    pub first_fixed: Vec<String>,
+}
+
+/// Merges the advisories affecting a release of a product into the ones found so far, by id.
+pub fn merge(
+    advisories: &mut BTreeMap<String, Advisory>,
+    product: &str,
+    version: &str,
+    response: &Response,
+) {
+    for found in &response.advisories {
+        let advisory = advisories
+            .entry(found.advisory_id.clone())
+            .or_insert_with(|| Advisory {
+                id: found.advisory_id.clone(),
+                title: found.advisory_title.trim().to_string(),
+                summary: html_text(&found.summary),
+                sir: found.sir.clone(),
+                score: found
+                    .cvss_base_score
+                    .as_deref()
+                    .and_then(|score| score.trim().parse().ok()),
+                url: found.publication_url.clone(),
+                published: normalize_utc(&found.first_published),
+                cves: found
+                    .cves
+                    .iter()
+                    .filter(|cve| cve.starts_with("CVE-"))
+                    .cloned()
+                    .collect(),
+                affected: vec![],
+            });
+        advisory.add_affected(product, version, &found.first_fixed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::{merge, versions, OsData, Response};
+
+    const RESPONSE: &str = r#"{
+        "advisories": [
+            {
+                "advisoryId": "cisco-sa-iosxe-webui-privesc-j22SaA4z",
+                "advisoryTitle": "Cisco IOS XE Software Web UI Privilege Escalation Vulnerability",
+                "bugIDs": ["CSCwh87343"],
+                "cves": ["CVE-2023-20198", "CVE-2023-20273"],
+                "cvrfUrl": "https://sec.cloudapps.cisco.com/security/center/contentxml/CiscoSecurityAdvisory/cisco-sa-iosxe-webui-privesc-j22SaA4z/cvrf/cisco-sa-iosxe-webui-privesc-j22SaA4z_cvrf.xml",
+                "cvssBaseScore": "10.0",
+                "cwe": ["CWE-420"],
+                "firstFixed": ["17.3.8a"],
+                "firstPublished": "2023-10-16T15:00:00",
+                "iosRelease": ["17.3.1"],
+                "lastUpdated": "2023-11-01T20:22:17",
+                "productNames": ["Cisco IOS XE Software 17.3.1"],
+                "publicationUrl": "https://sec.cloudapps.cisco.com/security/center/content/CiscoSecurityAdvisory/cisco-sa-iosxe-webui-privesc-j22SaA4z",
+                "sir": "Critical",
+                "summary": "<p>Cisco is aware of <strong>active exploitation</strong> of the web UI feature.</p>"
+            }
+        ]
+    }"#;
+
+    #[test]
+    fn can_list_versions() {
+        let data: Vec<OsData> = serde_json::from_str(
+            r#"[{"OSType": "iosxe", "version": ["17.3.1", "16.12.4"]}, {"version": "17.3.1"}, {"versions": " 17.9.1 "}]"#,
+        )
+        .unwrap();
+        assert_eq!(vec!["17.3.1", "16.12.4", "17.9.1"], versions(&data));
+    }
+
+    #[test]
+    fn can_merge_advisories() {
+        let response: Response = serde_json::from_str(RESPONSE).unwrap();
+        let mut advisories = BTreeMap::new();
+        merge(&mut advisories, "ios_xe", "17.3.1", &response);
+        merge(&mut advisories, "ios_xe", "17.3.2", &response);
+
+        let advisory = &advisories["cisco-sa-iosxe-webui-privesc-j22SaA4z"];
+        assert_eq!(
+            "Cisco is aware of active exploitation of the web UI feature.",
+            advisory.summary
+        );
+        assert_eq!(Some(10.0), advisory.score);
+        assert_eq!(vec!["CVE-2023-20198", "CVE-2023-20273"], advisory.cves);
+        assert_eq!(vec!["17.3.1", "17.3.2"], advisory.affected[0].versions);
+        assert_eq!(vec!["17.3.8a"], advisory.affected[0].first_fixed);
+    }
+}
@@ -0,0 +1,144 @@
+use std::env;
+use std::fs::{self, File};
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, bail, Context, Result};
+use log::info;
+
+use super::{url, xml, Entry, Mapping, SOURCE_NAME, VENDOR};
+
+use crate::db::{self, Pool};
+use crate::utils::ImportError;
+
+/// Mapping file of the CNNVD ids to the CVE ids, from the `--mapping` argument or else from
+/// `KEPLER_CNNVD_MAPPING`.
+pub fn mapping_path(arg: Option<&str>) -> Option<PathBuf> {
+    arg.map(str::to_string)
+        .or_else(|| env::var("KEPLER_CNNVD_MAPPING").ok())
+        .filter(|path| !path.is_empty())
+        .map(PathBuf::from)
+}
+
+fn read_mapping(path: &Path) -> Result<Mapping> {
+    let data = fs::read(path).with_context(|| format!("could not read {}", path.display()))?;
+    let mapping = Mapping::parse(&data).map_err(|e| {
+        anyhow!(
+            "could not parse the CNNVD mapping {}: {}",
+            path.display(),
+            e
+        )
+    })?;
+    info!(
+        "mapping the CVEs of {} CNNVD ids from {}",
+        mapping.len(),
+        path.display()
+    );
+    Ok(mapping)
+}
+
+fn store(database: &db::Database, entry: &Entry) -> Result<u32> {
+    // the CNNVD ids stand for the CVEs
+    let cves: Vec<&str> = entry.cves.iter().map(String::as_str).collect();
+    database
+        .replace_advisory_aliases(&entry.id, &cves)
+        .map_err(|e| anyhow!(e))?;
+
+    let products = entry.product_names();
+    if products.is_empty() {
+        return Ok(0);
+    }
+
+    let object_id = database
+        .upsert_object(db::models::NewObject::with(
+            Entry::object_key(&entry.id),
+            serde_json::to_string(entry)?,
+        ))
+        .map_err(|e| anyhow!(e))?;
+
+    let mut refs = db::models::References::default();
+    refs.push(db::models::Reference {
+        url: url(&entry.id),
+        tags: vec!["Third Party Advisory".into()],
+    });
+
+    let mut num_imported = 0;
+    for id in entry.record_ids() {
+        for product in &products {
+            let new_cve = db::models::NewCVE::with(
+                SOURCE_NAME.into(),
+                VENDOR.into(),
+                product.clone(),
+                id.clone(),
+                entry.summary(),
+                0.0,
+                entry.severity(),
+                None,
+                refs.clone(),
+                Some(object_id),
+            );
+            if database.upsert_cve(new_cve).map_err(|e| anyhow!(e))? {
+                num_imported += 1;
+            }
+        }
+    }
+
+    Ok(num_imported)
+}
+
+/// Imports the entries of the XML dumps found in the `cnnvd` directory of the data path, their
+/// CVEs being replaced by the ones of the mapping file if any, creating the new records and
+/// updating the changed ones.
+pub fn run(pool: &Pool, data_path: &Path, mapping: Option<&Path>) -> Result<u32> {
+    let mut cnnvd_path = data_path.to_path_buf();
+    cnnvd_path.push("cnnvd");
+    fs::create_dir_all(&cnnvd_path)
+        .with_context(|| format!("could not create {}", cnnvd_path.display()))?;
+
+    // the dumps are only available to the registered users, so they're not downloaded
+    let mut dumps: Vec<PathBuf> = fs::read_dir(&cnnvd_path)
+        .with_context(|| format!("could not list {}", cnnvd_path.display()))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.is_file()
+                && path
+                    .extension()
+                    .map_or(false, |extension| extension.eq_ignore_ascii_case("xml"))
+        })
+        .collect();
+    if dumps.is_empty() {
+        bail!("no CNNVD XML dump found in {}", cnnvd_path.display());
+    }
+    dumps.sort();
+
+    let mapping = match mapping {
+        Some(path) => read_mapping(path)?,
+        None => Mapping::default(),
+    };
+
+    let database = db::Database(pool.get()?);
+    let mut num_imported = 0;
+    for file_path in &dumps {
+        info!("parsing {} ...", file_path.display());
+        let file = File::open(file_path)
+            .with_context(|| format!("could not open {}", file_path.display()))?;
+        let mut entries = xml::parse(BufReader::new(file))
+            .map_err(|e| ImportError::from(e).quarantine(file_path))?;
+
+        let num_mapped = entries
+            .iter_mut()
+            .map(|entry| mapping.apply(entry))
+            .filter(|mapped| *mapped)
+            .count();
+        info!(
+            "importing {} entries, {} of them mapped ...",
+            entries.len(),
+            num_mapped
+        );
+        for entry in &entries {
+            num_imported += store(&database, entry)?;
+        }
+    }
+
+    Ok(num_imported)
+}
@@ -0,0 +1,239 @@
+//! Vulnerabilities of CNNVD, the China National Vulnerability Database of Information Security,
+//! read from the XML dumps of its entries, published with their CVE ids when assigned but
+//! without the versions affected.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::search::Query;
+
+#[cfg(feature = "import")]
+pub mod import;
+#[cfg(feature = "import")]
+pub mod xml;
+
+pub const SOURCE_NAME: &str = "CNNVD";
+/// Kept apart from the NIST vendors, the products being named after the software listed.
+pub const VENDOR: &str = "@cnnvd";
+
+pub fn url(id: &str) -> String {
+    format!("http://www.cnnvd.org.cn/web/xxk/ldxqById.tag?CNNVD={}", id)
+}
+
+/// Product of a software listed by an entry, its name before the version lowercased with its
+/// words joined by `_`, like `cybozu_garoon` for `Cybozu Garoon 5.0.0`.
+pub fn product(software: &str) -> Option<String> {
+    let words: Vec<String> = software
+        .split_whitespace()
+        .take_while(|word| !word.starts_with(|c: char| c.is_ascii_digit() || "<>=".contains(c)))
+        .map(str::to_lowercase)
+        .collect();
+    if words.is_empty() {
+        return None;
+    }
+    Some(words.join("_"))
+}
+
+#[derive(Debug, Default, Clone, Deserialize, Serialize)]
+pub struct Entry {
+    /// Like `CNNVD-201901-001`.
+    pub id: String,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub cves: Vec<String>,
+    /// Software affected, like `Cybozu Garoon 5.0.0`.
+    #[serde(default)]
+    pub software: Vec<String>,
+    /// `超危`, `高危`, `中危` or `低危`, or their English names in the English dumps.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub severity: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vuln_type: Option<String>,
+    #[serde(default)]
+    pub published: String,
+    #[serde(default)]
+    pub modified: String,
+}
+
+impl Entry {
+    pub fn object_key(id: &str) -> String {
+        format!("{} (cnnvd)", id)
+    }
+
+    /// CVEs of the records, or else the CNNVD id for the vulnerabilities assigned no CVE.
+    pub fn record_ids(&self) -> Vec<String> {
+        if self.cves.is_empty() {
+            return vec![self.id.clone()];
+        }
+        self.cves.clone()
+    }
+
+    pub fn summary(&self) -> String {
+        if self.description.trim().is_empty() {
+            return self.name.trim().to_string();
+        }
+        self.description.trim().to_string()
+    }
+
+    /// Severity rated by CNNVD, named like the NIST severities.
+    pub fn severity(&self) -> String {
+        match self.severity.as_deref().map(str::trim) {
+            Some("超危") => "CRITICAL",
+            Some("高危") => "HIGH",
+            Some("中危") => "MEDIUM",
+            Some("低危") => "LOW",
+            Some(severity) => match severity.to_lowercase().as_str() {
+                "critical" => "CRITICAL",
+                "high" => "HIGH",
+                "medium" => "MEDIUM",
+                "low" => "LOW",
+                _ => "NONE",
+            },
+            None => "NONE",
+        }
+        .to_string()
+    }
+
+    /// Unique products of the software listed.
+    pub fn product_names(&self) -> Vec<String> {
+        let mut products: Vec<String> = vec![];
+        for product in self.software.iter().map(String::as_str).filter_map(product) {
+            if !products.contains(&product) {
+                products.push(product);
+            }
+        }
+        products
+    }
+
+    /// Matches the searches of the product without a version only, the versions affected
+    /// being left out of the dumps.
+    pub fn is_match(&self, query: &Query) -> bool {
+        query.version.is_none() && self.product_names().contains(&query.product)
+    }
+}
+
+/// Row of a mapping file.
+#[derive(Debug, Deserialize)]
+struct MappingRow {
+    cnnvd: String,
+    #[serde(default)]
+    cve: String,
+}
+
+/// CVEs of the CNNVD ids, overriding the ones of the dumps, from a CSV file with a `cnnvd` and
+/// a `cve` column and a row for every CVE of an id, or a single row without a CVE for the ids
+/// not to be linked to any.
+#[derive(Debug, Default)]
+pub struct Mapping(HashMap<String, Vec<String>>);
+
+impl Mapping {
+    pub fn parse(data: &[u8]) -> Result<Self, String> {
+        let mut cves: HashMap<String, Vec<String>> = HashMap::new();
+        for (i, row) in csv::Reader::from_reader(data).deserialize().enumerate() {
+            let row: MappingRow = row.map_err(|e| format!("row {}: {}", i + 1, e))?;
+            let id = row.cnnvd.trim().to_uppercase();
+            if !id.starts_with("CNNVD-") {
+                return Err(format!("row {}: invalid CNNVD id {}", i + 1, row.cnnvd));
+            }
+            let ids = cves.entry(id).or_default();
+            let cve = row.cve.trim().to_uppercase();
+            if cve.is_empty() {
+                continue;
+            }
+            if !cve.starts_with("CVE-") {
+                return Err(format!("row {}: invalid CVE id {}", i + 1, row.cve));
+            }
+            if !ids.contains(&cve) {
+                ids.push(cve);
+            }
+        }
+        Ok(Self(cves))
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Replaces the CVEs of the entry with the mapped ones, if its id is mapped.
+    pub fn apply(&self, entry: &mut Entry) -> bool {
+        match self.0.get(&entry.id) {
+            Some(cves) => {
+                entry.cves = cves.clone();
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{product, Entry, Mapping};
+    use crate::search::testing::query;
+
+    #[test]
+    fn can_name_products() {
+        assert_eq!(Some("cybozu_garoon".into()), product("Cybozu Garoon 5.0.0"));
+        assert_eq!(Some("openssl".into()), product(" OpenSSL <1.1.1t"));
+        assert_eq!(None, product("2.0"));
+    }
+
+    #[test]
+    fn can_match_products() {
+        let entry = Entry {
+            id: "CNNVD-202301-001".into(),
+            severity: Some("高危".into()),
+            software: vec!["Cybozu Garoon 5.0.0".into(), "Cybozu Garoon 5.5.1".into()],
+            ..Default::default()
+        };
+
+        assert_eq!("HIGH", entry.severity());
+        assert_eq!(vec!["cybozu_garoon"], entry.product_names());
+        assert_eq!(vec!["CNNVD-202301-001"], entry.record_ids());
+        assert!(entry.is_match(&query("cybozu_garoon", None)));
+        assert!(!entry.is_match(&query("cybozu_garoon", Some("5.0.0"))));
+        assert!(!entry.is_match(&query("garoon", None)));
+    }
+
+    #[test]
+    fn can_map_cves() {
+        let mapping = Mapping::parse(
+            b"cnnvd,cve\nCNNVD-202301-001,CVE-2023-0001\ncnnvd-202301-001,cve-2023-0002\nCNNVD-202301-002,\n",
+        )
+        .unwrap();
+        assert_eq!(2, mapping.len());
+
+        let mut entry = Entry {
+            id: "CNNVD-202301-001".into(),
+            ..Default::default()
+        };
+        assert!(mapping.apply(&mut entry));
+        assert_eq!(vec!["CVE-2023-0001", "CVE-2023-0002"], entry.cves);
+
+        let mut entry = Entry {
+            id: "CNNVD-202301-002".into(),
+            cves: vec!["CVE-2023-0003".into()],
+            ..Default::default()
+        };
+        assert!(mapping.apply(&mut entry));
+        assert!(entry.cves.is_empty());
+
+        let mut entry = Entry {
+            id: "CNNVD-202301-003".into(),
+            cves: vec!["CVE-2023-0004".into()],
+            ..Default::default()
+        };
+        assert!(!mapping.apply(&mut entry));
+        assert_eq!(vec!["CVE-2023-0004"], entry.cves);
+
+        assert!(Mapping::parse(b"cnnvd,cve\nCVE-2023-0001,CVE-2023-0001\n").is_err());
+    }
+}
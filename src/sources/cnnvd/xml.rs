@@ -0,0 +1,166 @@
+//! Streaming parser of the XML dumps of CNNVD, listing the entries with their other ids and
+//! the software they affect.
+
+use std::io::BufRead;
+
+use quick_xml::events::Event;
+use quick_xml::Reader;
+
+use super::Entry;
+
+/// Element whose text is being read.
+enum Text {
+    None,
+    Name,
+    Id,
+    Published,
+    Modified,
+    Severity,
+    Type,
+    Description,
+    Cve,
+    Product,
+}
+
+/// Parses the entries of the dump.
+pub fn parse<R: BufRead>(source: R) -> Result<Vec<Entry>, String> {
+    let mut reader = Reader::from_reader(source);
+    reader.trim_text(true);
+
+    let mut entries = vec![];
+    let mut entry: Option<Entry> = None;
+    let mut text = Text::None;
+
+    let mut buf = vec![];
+    loop {
+        let event = reader.read_event(&mut buf).map_err(|e| {
+            format!(
+                "could not parse the CNNVD dump at {}: {}",
+                reader.buffer_position(),
+                e
+            )
+        })?;
+
+        match &event {
+            Event::Start(element) => {
+                text = match element.local_name() {
+                    b"entry" => {
+                        entry = Some(Entry::default());
+                        Text::None
+                    }
+                    b"name" => Text::Name,
+                    b"vuln-id" => Text::Id,
+                    b"published" => Text::Published,
+                    b"modified" => Text::Modified,
+                    b"severity" => Text::Severity,
+                    b"vuln-type" => Text::Type,
+                    b"vuln-descript" => Text::Description,
+                    b"cve-id" => Text::Cve,
+                    b"product" => Text::Product,
+                    _ => Text::None,
+                };
+            }
+            Event::Text(_) | Event::CData(_) => {
+                let value = match &event {
+                    Event::Text(value) => value
+                        .unescape_and_decode(&reader)
+                        .map_err(|e| format!("could not decode the CNNVD dump: {}", e))?,
+                    Event::CData(value) => String::from_utf8_lossy(value).into_owned(),
+                    _ => unreachable!(),
+                };
+                if let Some(entry) = &mut entry {
+                    let value = value.trim().to_string();
+                    match text {
+                        Text::Name => entry.name = value,
+                        Text::Id => entry.id = value.to_uppercase(),
+                        Text::Published => entry.published = value,
+                        Text::Modified => entry.modified = value,
+                        Text::Severity => entry.severity = Some(value),
+                        Text::Type => entry.vuln_type = Some(value),
+                        Text::Description => entry.description = value,
+                        Text::Cve => {
+                            let cve = value.to_uppercase();
+                            if cve.starts_with("CVE-") && !entry.cves.contains(&cve) {
+                                entry.cves.push(cve);
+                            }
+                        }
+                        Text::Product => entry.software.push(value),
+                        Text::None => {}
+                    }
+                }
+            }
+            Event::End(element) => {
+                text = Text::None;
+                if element.local_name() == b"entry" {
+                    entries.extend(entry.take().filter(|entry| !entry.id.is_empty()));
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+
+        buf.clear();
+    }
+
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse;
+
+    const DUMP: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<cnnvd cnnvd_xml_version="*.*" pub_date="2023-01-31" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance">
+  <entry>
+    <name>Cybozu Garoon 安全漏洞</name>
+    <vuln-id>CNNVD-202301-001</vuln-id>
+    <published>2023-01-11</published>
+    <modified>2023-01-12</modified>
+    <source>Cybozu</source>
+    <severity>中危</severity>
+    <vuln-type>其他</vuln-type>
+    <vuln-descript><![CDATA[Cybozu Garoon是日本Cybozu公司的一套协同办公软件。]]></vuln-descript>
+    <other-id>
+      <cve-id>CVE-2023-22282</cve-id>
+      <bugtraq-id></bugtraq-id>
+    </other-id>
+    <vuln-solution>目前厂商已发布升级补丁以修复漏洞。</vuln-solution>
+    <vuln-software-list>
+      <product>Cybozu Garoon 5.0.0</product>
+      <product>Cybozu Garoon 5.15.0</product>
+    </vuln-software-list>
+  </entry>
+  <entry>
+    <name>Example &amp; Co 信息泄露漏洞</name>
+    <vuln-id>CNNVD-202301-002</vuln-id>
+    <severity>低危</severity>
+    <other-id>
+      <cve-id></cve-id>
+    </other-id>
+  </entry>
+</cnnvd>"#;
+
+    #[test]
+    fn can_parse_entries() {
+        let entries = parse(DUMP.as_bytes()).unwrap();
+        assert_eq!(2, entries.len());
+
+        let entry = &entries[0];
+        assert_eq!("CNNVD-202301-001", entry.id);
+        assert_eq!("Cybozu Garoon 安全漏洞", entry.name);
+        assert_eq!(
+            "Cybozu Garoon是日本Cybozu公司的一套协同办公软件。",
+            entry.summary()
+        );
+        assert_eq!(vec!["CVE-2023-22282"], entry.cves);
+        assert_eq!(vec!["cybozu_garoon"], entry.product_names());
+        assert_eq!("MEDIUM", entry.severity());
+        assert_eq!("2023-01-11", entry.published);
+
+        let entry = &entries[1];
+        assert_eq!("Example & Co 信息泄露漏洞", entry.summary());
+        assert!(entry.cves.is_empty());
+        assert_eq!(vec!["CNNVD-202301-002"], entry.record_ids());
+        assert_eq!("LOW", entry.severity());
+    }
+}
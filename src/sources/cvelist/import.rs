@@ -0,0 +1,254 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use anyhow::{anyhow, bail, Context, Result};
+use log::{info, warn};
+
+use super::{record, Vulnerability, SOURCE_NAME, VENDOR};
+
+use crate::db::{self, Pool};
+
+pub const REPOSITORY_URL: &str = "https://github.com/CVEProject/cvelistV5.git";
+/// Branch the CVE records are published to.
+const BRANCH: &str = "main";
+/// File next to the clone holding the last commit imported.
+const IMPORTED_COMMIT: &str = "imported_commit";
+
+/// Runs git in the directory, returning its output.
+fn git(dir: &Path, args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .output()
+        .context("could not run git, is it installed?")?;
+    if !output.status.success() {
+        bail!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Clones the repository, or brings the clone to the latest commit, without its history.
+fn update(cvelist_path: &Path, repository_path: &Path) -> Result<()> {
+    if !repository_path.join(".git").exists() {
+        info!("cloning {} ...", REPOSITORY_URL);
+        git(
+            cvelist_path,
+            &[
+                "clone",
+                "--depth",
+                "1",
+                "--branch",
+                BRANCH,
+                REPOSITORY_URL,
+                &repository_path.to_string_lossy(),
+            ],
+        )?;
+        return Ok(());
+    }
+
+    info!("pulling {} ...", REPOSITORY_URL);
+    git(
+        repository_path,
+        &["fetch", "--depth", "1", "origin", BRANCH],
+    )?;
+    git(repository_path, &["reset", "--hard", "FETCH_HEAD"])?;
+    Ok(())
+}
+
+/// Collects the files of the CVE records in the directory and its subdirectories.
+fn collect_records(dir: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
+    for entry in fs::read_dir(dir).with_context(|| format!("could not list {}", dir.display()))? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_records(&path, files)?;
+        } else if path
+            .file_name()
+            .map(|name| name.to_string_lossy())
+            .map_or(false, |name| {
+                name.starts_with("CVE-") && name.ends_with(".json")
+            })
+        {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Files of the CVE records changed since the commit, or `None` if the commit isn't known
+/// to the clone anymore.
+fn changed_records(repository_path: &Path, since_commit: &str) -> Option<Vec<PathBuf>> {
+    let diff = git(
+        repository_path,
+        &["diff", "--name-only", since_commit, "HEAD", "--", "cves"],
+    );
+    match diff {
+        Ok(names) => Some(
+            names
+                .lines()
+                .filter(|name| name.ends_with(".json") && name.contains("CVE-"))
+                .map(|name| repository_path.join(name))
+                .collect(),
+        ),
+        Err(e) => {
+            warn!(
+                "could not list the records changed since {}: {}",
+                since_commit, e
+            );
+            None
+        }
+    }
+}
+
+fn store(
+    database: &db::Database,
+    vulnerability: &Vulnerability,
+    references: &[record::Reference],
+) -> Result<u32> {
+    let products = vulnerability.products();
+    let affected: Vec<(String, String)> = products
+        .iter()
+        .map(|product| (VENDOR.to_string(), product.clone()))
+        .collect();
+    database
+        .delete_other_products(SOURCE_NAME, &vulnerability.id, &affected)
+        .map_err(|e| anyhow!(e))?;
+    if products.is_empty() {
+        return Ok(0);
+    }
+
+    let object_id = database
+        .upsert_object(db::models::NewObject::with(
+            Vulnerability::object_key(&vulnerability.id),
+            serde_json::to_string(vulnerability)?,
+        ))
+        .map_err(|e| anyhow!(e))?;
+
+    let mut refs = db::models::References::default();
+    for reference in references {
+        refs.push(db::models::Reference {
+            url: reference.url.clone(),
+            tags: reference.tags.clone(),
+        });
+    }
+
+    let mut num_imported = 0;
+    for product in products {
+        let new_cve = db::models::NewCVE::with(
+            SOURCE_NAME.into(),
+            VENDOR.into(),
+            product,
+            vulnerability.id.clone(),
+            vulnerability.description.clone(),
+            vulnerability.score.unwrap_or_default(),
+            vulnerability.severity(),
+            vulnerability.vector.clone(),
+            refs.clone(),
+            Some(object_id),
+        );
+        if database.upsert_cve(new_cve).map_err(|e| anyhow!(e))? {
+            num_imported += 1;
+        }
+    }
+
+    Ok(num_imported)
+}
+
+/// Imports the CVE records of the repository, cloned to the data path or pulled, only going
+/// through the records changed since the last commit imported unless `fresh`. The records of
+/// the rejected CVEs are deleted.
+pub fn run(pool: &Pool, data_path: &Path, fresh: bool) -> Result<u32> {
+    let mut cvelist_path = data_path.to_path_buf();
+    cvelist_path.push("cvelist");
+    fs::create_dir_all(&cvelist_path)
+        .with_context(|| format!("could not create {}", cvelist_path.display()))?;
+    let repository_path = cvelist_path.join("cvelistV5");
+    let commit_path = cvelist_path.join(IMPORTED_COMMIT);
+
+    update(&cvelist_path, &repository_path)?;
+    let head = git(&repository_path, &["rev-parse", "HEAD"])?;
+
+    let imported = if fresh {
+        None
+    } else {
+        fs::read_to_string(&commit_path)
+            .ok()
+            .map(|commit| commit.trim().to_string())
+    };
+    let files = match imported {
+        Some(commit) if commit == head => {
+            info!("commit {} already imported", head);
+            return Ok(0);
+        }
+        Some(commit) => changed_records(&repository_path, &commit),
+        None => None,
+    };
+    let files = match files {
+        Some(files) => files,
+        None => {
+            let mut files = vec![];
+            collect_records(&repository_path.join("cves"), &mut files)?;
+            files
+        }
+    };
+
+    info!(
+        "importing {} CVE records of commit {} ...",
+        files.len(),
+        head
+    );
+
+    let database = db::Database(pool.get()?);
+    let mut num_imported = 0;
+    let mut num_failed = 0;
+    let mut rejected = vec![];
+    for (i, file_path) in files.iter().enumerate() {
+        // deleted since, like the records moved to another directory
+        if !file_path.exists() {
+            continue;
+        }
+        let data = fs::read(file_path)
+            .with_context(|| format!("could not read {}", file_path.display()))?;
+        match record::parse(&data) {
+            Ok(Some((vulnerability, references))) => {
+                num_imported += store(&database, &vulnerability, &references)?;
+            }
+            Ok(None) => rejected.push(
+                file_path
+                    .file_stem()
+                    .map(|stem| stem.to_string_lossy().into_owned())
+                    .unwrap_or_default(),
+            ),
+            Err(e) => {
+                // a single malformed record shouldn't hold back the others
+                warn!("{}: {}", file_path.display(), e);
+                num_failed += 1;
+            }
+        }
+
+        if i > 0 && i % 10000 == 0 {
+            info!("went through {} records ...", i);
+        }
+    }
+
+    let rejected: Vec<&str> = rejected.iter().map(String::as_str).collect();
+    let num_deleted = database
+        .delete_source_cves(SOURCE_NAME, &rejected)
+        .map_err(|e| anyhow!(e))?;
+    info!(
+        "{} rejected CVEs, {} records deleted, {} malformed records skipped",
+        rejected.len(),
+        num_deleted,
+        num_failed
+    );
+
+    fs::write(&commit_path, &head)
+        .with_context(|| format!("could not write {}", commit_path.display()))?;
+
+    Ok(num_imported)
+}
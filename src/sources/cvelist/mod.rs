@@ -0,0 +1,269 @@
+//! CVE records published by the CNAs in the `CVEProject/cvelistV5` repository of the CVE
+//! Program, with their own CVSS scores and affected versions, available days before the NVD
+//! analysis.
+
+use serde::{Deserialize, Serialize};
+use version_compare::Cmp;
+
+use crate::search::Query;
+use crate::utils::version_cmp;
+
+#[cfg(feature = "import")]
+pub mod import;
+#[cfg(feature = "import")]
+pub mod record;
+
+pub const SOURCE_NAME: &str = "CVEList";
+/// Kept apart from the NIST vendors, the vendors being named freely by the CNAs.
+pub const VENDOR: &str = "@cvelist";
+
+/// Versions standing for any version, or for an unknown one.
+const ANY_VERSION: &[&str] = &["", "*", "0", "n/a", "unspecified", "all"];
+
+/// Product of a CPE, like `log4j` for `cpe:2.3:a:apache:log4j:*:*:*:*:*:*:*:*`.
+pub fn cpe_product(cpe: &str) -> Option<String> {
+    let mut parts = cpe
+        .strip_prefix("cpe:2.3:")
+        .or_else(|| cpe.strip_prefix("cpe:/"))?
+        .split(':');
+    let (_part, _vendor) = (parts.next()?, parts.next()?);
+    parts
+        .next()
+        .map(str::to_lowercase)
+        .filter(|product| !product.is_empty() && product != "*")
+}
+
+/// Product of a name given by a CNA, lowercased with its words joined by `_`, like
+/// `apache_log4j2` for `Apache Log4j2`.
+pub fn product(name: &str) -> Option<String> {
+    let product = name
+        .split_whitespace()
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join("_");
+    if ANY_VERSION.contains(&product.as_str()) {
+        return None;
+    }
+    Some(product)
+}
+
+/// Version or range of versions of an affected product, as given by the CNA.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Version {
+    /// Exact version, or first version of the range.
+    pub version: String,
+    /// `affected`, `unaffected` or `unknown`.
+    pub status: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub less_than: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub less_than_or_equal: Option<String>,
+    /// Like `semver` or `git`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version_type: Option<String>,
+}
+
+impl Version {
+    /// Whether the version is the exact one or within the range, the commit hashes never
+    /// matching.
+    pub fn contains(&self, version: &str) -> bool {
+        if self.version_type.as_deref() == Some("git") {
+            return false;
+        }
+
+        let unbounded = |bound: &str| ANY_VERSION.contains(&bound.trim());
+        let after_start =
+            || unbounded(&self.version) || version_cmp(version, &self.version, Cmp::Ge);
+        match (&self.less_than, &self.less_than_or_equal) {
+            (Some(end), _) => {
+                after_start() && (unbounded(end) || version_cmp(version, end, Cmp::Lt))
+            }
+            (None, Some(end)) => {
+                after_start() && (unbounded(end) || version_cmp(version, end, Cmp::Le))
+            }
+            (None, None) => {
+                !unbounded(&self.version) && version_cmp(version, &self.version, Cmp::Eq)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Affected {
+    /// Like `Apache Software Foundation`.
+    #[serde(default)]
+    pub vendor: String,
+    /// Like `Apache Log4j2`.
+    #[serde(default)]
+    pub name: String,
+    /// Like `log4j`, from the CPE of the product if given.
+    pub product: String,
+    #[serde(default)]
+    pub versions: Vec<Version>,
+    /// Status of the versions not listed, `unaffected` unless given.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_status: Option<String>,
+}
+
+impl Affected {
+    /// Whether the version is affected, according to the first version listed containing it
+    /// or else to the default status.
+    pub fn affects(&self, version: &str) -> bool {
+        match self.versions.iter().find(|v| v.contains(version)) {
+            Some(v) => v.status == "affected",
+            None => self.default_status.as_deref() == Some("affected"),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Deserialize, Serialize)]
+pub struct Vulnerability {
+    /// Like `CVE-2021-44228`.
+    pub id: String,
+    /// Short name of the CNA, like `apache`.
+    #[serde(default)]
+    pub assigner: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub affected: Vec<Affected>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub score: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub severity: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vector: Option<String>,
+    #[serde(default)]
+    pub published: String,
+    #[serde(default)]
+    pub updated: String,
+}
+
+impl Vulnerability {
+    pub fn object_key(id: &str) -> String {
+        format!("{} (cvelist)", id)
+    }
+
+    /// Severity rated by the CNA, named like the NIST severities.
+    pub fn severity(&self) -> String {
+        match self.severity.as_deref().map(str::to_uppercase) {
+            Some(severity)
+                if ["CRITICAL", "HIGH", "MEDIUM", "LOW"].contains(&severity.as_str()) =>
+            {
+                severity
+            }
+            _ => "NONE".to_string(),
+        }
+    }
+
+    /// Unique products affected.
+    pub fn products(&self) -> Vec<String> {
+        let mut products: Vec<String> = vec![];
+        for affected in &self.affected {
+            if !products.contains(&affected.product) {
+                products.push(affected.product.clone());
+            }
+        }
+        products
+    }
+
+    /// Matches the versions affected of the product according to the CNA, or any version
+    /// when the query has none.
+    pub fn is_match(&self, query: &Query) -> bool {
+        self.affected
+            .iter()
+            .filter(|affected| affected.product == query.product)
+            .any(|affected| match &query.version {
+                Some(version) => affected.affects(version),
+                None => true,
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{cpe_product, product, Affected, Version, Vulnerability};
+    use crate::search::testing::query;
+
+    fn version(version: &str, status: &str, less_than: Option<&str>) -> Version {
+        Version {
+            version: version.into(),
+            status: status.into(),
+            less_than: less_than.map(str::to_string),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn can_name_products() {
+        assert_eq!(
+            Some("log4j".to_string()),
+            cpe_product("cpe:2.3:a:apache:log4j:*:*:*:*:*:*:*:*")
+        );
+        assert_eq!(
+            Some("garoon".to_string()),
+            cpe_product("cpe:/a:cybozu:garoon")
+        );
+        assert_eq!(None, cpe_product("log4j"));
+        assert_eq!(Some("apache_log4j2".to_string()), product("Apache  Log4j2"));
+        assert_eq!(None, product("n/a"));
+    }
+
+    #[test]
+    fn can_match_versions() {
+        let vulnerability = Vulnerability {
+            id: "CVE-2021-44228".into(),
+            severity: Some("critical".into()),
+            affected: vec![Affected {
+                vendor: "Apache Software Foundation".into(),
+                name: "Apache Log4j2".into(),
+                product: "log4j".into(),
+                versions: vec![
+                    version("2.0-beta9", "affected", Some("2.3.1")),
+                    version("2.4", "affected", Some("2.12.2")),
+                    version("2.13.0", "affected", Some("2.15.0")),
+                ],
+                default_status: None,
+            }],
+            ..Default::default()
+        };
+
+        assert_eq!("CRITICAL", vulnerability.severity());
+        assert_eq!(vec!["log4j"], vulnerability.products());
+        assert!(vulnerability.is_match(&query("log4j", Some("2.14.1"))));
+        assert!(vulnerability.is_match(&query("log4j", Some("2.13.0"))));
+        assert!(vulnerability.is_match(&query("log4j", None)));
+        assert!(!vulnerability.is_match(&query("log4j", Some("2.15.0"))));
+        assert!(!vulnerability.is_match(&query("log4j", Some("2.12.2"))));
+        assert!(!vulnerability.is_match(&query("log4j_core", Some("2.14.1"))));
+    }
+
+    #[test]
+    fn can_match_default_status() {
+        let affected = Affected {
+            product: "example".into(),
+            versions: vec![
+                version("1.2.3", "unaffected", None),
+                Version {
+                    version: "0123abcd".into(),
+                    status: "affected".into(),
+                    version_type: Some("git".into()),
+                    ..Default::default()
+                },
+            ],
+            default_status: Some("affected".into()),
+            ..Default::default()
+        };
+
+        assert!(affected.affects("1.0.0"));
+        assert!(!affected.affects("1.2.3"));
+
+        let unknown = Affected {
+            product: "example".into(),
+            versions: vec![version("n/a", "affected", None)],
+            ..Default::default()
+        };
+        assert!(!unknown.affects("1.0.0"));
+    }
+}
@@ -0,0 +1,281 @@
+//! CVE records in the CVE JSON 5 format, as published in the `cves` directory of the
+//! repository, keeping the affected products, the metrics and the references.
+
+use serde::Deserialize;
+
+use super::{cpe_product, product, Affected, Version, Vulnerability};
+use crate::utils::normalize_utc;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Record {
+    cve_metadata: Metadata,
+    #[serde(default)]
+    containers: Containers,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Metadata {
+    cve_id: String,
+    /// `PUBLISHED` or `REJECTED`.
+    state: String,
+    #[serde(default)]
+    assigner_short_name: Option<String>,
+    #[serde(default)]
+    date_published: Option<String>,
+    #[serde(default)]
+    date_updated: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct Containers {
+    #[serde(default)]
+    cna: Option<Container>,
+    /// Containers of the authorized data publishers, like CISA.
+    #[serde(default)]
+    adp: Vec<Container>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct Container {
+    #[serde(default)]
+    affected: Vec<Product>,
+    #[serde(default)]
+    descriptions: Vec<Description>,
+    #[serde(default)]
+    metrics: Vec<Metric>,
+    #[serde(default)]
+    references: Vec<Reference>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Product {
+    #[serde(default)]
+    vendor: Option<String>,
+    #[serde(default)]
+    product: Option<String>,
+    #[serde(default)]
+    package_name: Option<String>,
+    #[serde(default)]
+    cpes: Vec<String>,
+    #[serde(default)]
+    versions: Vec<Version>,
+    #[serde(default)]
+    default_status: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Description {
+    lang: String,
+    value: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Metric {
+    #[serde(default, rename = "cvssV3_1")]
+    cvss_v3_1: Option<Cvss>,
+    #[serde(default, rename = "cvssV3_0")]
+    cvss_v3_0: Option<Cvss>,
+    #[serde(default, rename = "cvssV4_0")]
+    cvss_v4_0: Option<Cvss>,
+    #[serde(default, rename = "cvssV2_0")]
+    cvss_v2_0: Option<Cvss>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Cvss {
+    base_score: f64,
+    #[serde(default)]
+    base_severity: Option<String>,
+    vector_string: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Reference {
+    pub url: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+impl Metric {
+    /// CVSS of the versions the NIST records are scored with first.
+    fn cvss(&self) -> Option<&Cvss> {
+        self.cvss_v3_1
+            .as_ref()
+            .or_else(|| self.cvss_v3_0.as_ref())
+            .or_else(|| self.cvss_v4_0.as_ref())
+            .or_else(|| self.cvss_v2_0.as_ref())
+    }
+}
+
+impl Product {
+    /// Product of the first CPE, or else of the name given by the CNA.
+    fn product(&self) -> Option<String> {
+        self.cpes
+            .iter()
+            .find_map(|cpe| cpe_product(cpe))
+            .or_else(|| {
+                self.product
+                    .as_deref()
+                    .or_else(|| self.package_name.as_deref())
+                    .and_then(product)
+            })
+    }
+}
+
+/// Parses a CVE record, returning its vulnerability and references unless it was rejected.
+/// The products affected are the ones given by the CNA, along with the ones added by the
+/// authorized data publishers, and the score is the one of the CNA if any.
+pub fn parse(data: &[u8]) -> Result<Option<(Vulnerability, Vec<Reference>)>, String> {
+    let record: Record =
+        serde_json::from_slice(data).map_err(|e| format!("could not parse record: {}", e))?;
+    if record.cve_metadata.state != "PUBLISHED" {
+        return Ok(None);
+    }
+
+    let mut containers: Vec<Container> = vec![];
+    containers.extend(record.containers.cna);
+    containers.extend(record.containers.adp);
+
+    let mut vulnerability = Vulnerability {
+        id: record.cve_metadata.cve_id,
+        assigner: record.cve_metadata.assigner_short_name.unwrap_or_default(),
+        published: normalize_utc(&record.cve_metadata.date_published.unwrap_or_default()),
+        updated: normalize_utc(&record.cve_metadata.date_updated.unwrap_or_default()),
+        ..Default::default()
+    };
+
+    let descriptions = containers
+        .iter()
+        .flat_map(|container| &container.descriptions);
+    vulnerability.description = descriptions
+        .clone()
+        .find(|description| description.lang.starts_with("en"))
+        .or_else(|| descriptions.clone().next())
+        .map(|description| description.value.trim().to_string())
+        .unwrap_or_default();
+
+    if let Some(cvss) = containers
+        .iter()
+        .flat_map(|container| &container.metrics)
+        .find_map(Metric::cvss)
+    {
+        vulnerability.score = Some(cvss.base_score);
+        vulnerability.severity = cvss.base_severity.clone();
+        vulnerability.vector = Some(cvss.vector_string.clone());
+    }
+
+    let mut references = vec![];
+    for container in containers {
+        for affected in container.affected {
+            if let Some(product) = affected.product() {
+                vulnerability.affected.push(Affected {
+                    vendor: affected.vendor.unwrap_or_default(),
+                    name: affected
+                        .product
+                        .or(affected.package_name)
+                        .unwrap_or_default(),
+                    product,
+                    versions: affected.versions,
+                    default_status: affected.default_status,
+                });
+            }
+        }
+        for reference in container.references {
+            if !references
+                .iter()
+                .any(|r: &Reference| r.url == reference.url)
+            {
+                references.push(reference);
+            }
+        }
+    }
+
+    Ok(Some((vulnerability, references)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse;
+
+    const RECORD: &str = r#"{
+  "dataType": "CVE_RECORD",
+  "dataVersion": "5.0",
+  "cveMetadata": {
+    "cveId": "CVE-2021-44228",
+    "assignerOrgId": "f0158376-9dc2-43b6-827c-5f631a4d8d09",
+    "assignerShortName": "apache",
+    "state": "PUBLISHED",
+    "datePublished": "2021-12-10T00:00:00",
+    "dateUpdated": "2023-11-06T18:06:43.052Z"
+  },
+  "containers": {
+    "cna": {
+      "affected": [
+        {
+          "vendor": "Apache Software Foundation",
+          "product": "Apache Log4j2",
+          "versions": [
+            { "version": "2.0-beta9", "status": "affected", "lessThan": "2.3.1", "versionType": "custom" },
+            { "version": "2.13.0", "status": "affected", "lessThan": "2.15.0", "versionType": "custom" }
+          ]
+        },
+        { "vendor": "n/a", "product": "n/a", "versions": [{ "version": "n/a", "status": "affected" }] }
+      ],
+      "descriptions": [
+        { "lang": "en", "value": "Apache Log4j2 2.0-beta9 through 2.15.0 JNDI features do not protect against attacker controlled LDAP endpoints." }
+      ],
+      "metrics": [
+        { "other": { "type": "unknown", "content": {} } },
+        { "cvssV3_1": { "version": "3.1", "baseScore": 10.0, "baseSeverity": "CRITICAL", "vectorString": "CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:C/C:H/I:H/A:H" } }
+      ],
+      "references": [
+        { "url": "https://logging.apache.org/log4j/2.x/security.html", "tags": ["vendor-advisory"] }
+      ]
+    },
+    "adp": [
+      {
+        "affected": [
+          {
+            "vendor": "apache",
+            "product": "log4j",
+            "cpes": ["cpe:2.3:a:apache:log4j:*:*:*:*:*:*:*:*"],
+            "defaultStatus": "unknown",
+            "versions": [{ "version": "2.0", "status": "affected", "lessThan": "2.15.0", "versionType": "custom" }]
+          }
+        ],
+        "references": [
+          { "url": "https://logging.apache.org/log4j/2.x/security.html" },
+          { "url": "http://www.openwall.com/lists/oss-security/2021/12/10/1" }
+        ]
+      }
+    ]
+  }
+}"#;
+
+    #[test]
+    fn can_parse_records() {
+        let (vulnerability, references) = parse(RECORD.as_bytes()).unwrap().unwrap();
+        assert_eq!("CVE-2021-44228", vulnerability.id);
+        assert_eq!("apache", vulnerability.assigner);
+        assert_eq!("2021-12-10T00:00:00Z", vulnerability.published);
+        assert_eq!("2023-11-06T18:06:43Z", vulnerability.updated);
+        assert!(vulnerability.description.starts_with("Apache Log4j2"));
+        assert_eq!(Some(10.0), vulnerability.score);
+        assert_eq!("CRITICAL", vulnerability.severity());
+        assert_eq!(vec!["apache_log4j2", "log4j"], vulnerability.products());
+        assert_eq!("Apache Log4j2", vulnerability.affected[0].name);
+        assert!(vulnerability.affected[0].affects("2.14.1"));
+        assert_eq!(2, references.len());
+        assert_eq!(vec!["vendor-advisory"], references[0].tags);
+    }
+
+    #[test]
+    fn can_skip_rejected_records() {
+        let record = r#"{ "cveMetadata": { "cveId": "CVE-2021-0001", "state": "REJECTED" } }"#;
+        assert!(parse(record.as_bytes()).unwrap().is_none());
+    }
+}
@@ -0,0 +1,151 @@
+//! Parser of the XML catalog of the CWE, like `cwec_v4.14.xml`.
+
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+
+use super::Entry;
+
+fn attribute(element: &BytesStart, name: &[u8]) -> Option<String> {
+    element
+        .attributes()
+        .flatten()
+        .find(|attribute| attribute.key == name)
+        .and_then(|attribute| {
+            attribute
+                .unescaped_value()
+                .ok()
+                .map(|value| String::from_utf8_lossy(&value).into_owned())
+        })
+}
+
+/// Parses the weaknesses and the categories of the catalog, leaving out the views.
+pub fn parse(xml: &str) -> Result<Vec<Entry>, String> {
+    let mut reader = Reader::from_str(xml);
+    reader.trim_text(true);
+
+    let mut entries = vec![];
+    let mut entry: Option<Entry> = None;
+    // depth of the elements, the descriptions being the direct children of the entries only,
+    // unlike the ones of the observed examples
+    let mut depth = 0;
+    let mut entry_depth = 0;
+    let mut in_description = false;
+
+    let mut buf = vec![];
+    loop {
+        let event = reader
+            .read_event(&mut buf)
+            .map_err(|e| format!("could not parse at {}: {}", reader.buffer_position(), e))?;
+
+        match &event {
+            Event::Start(element) => {
+                depth += 1;
+                match element.local_name() {
+                    kind @ (b"Weakness" | b"Category") => {
+                        let id = attribute(element, b"ID").unwrap_or_default();
+                        entry = Some(Entry {
+                            id: format!("CWE-{}", id),
+                            name: attribute(element, b"Name").unwrap_or_default(),
+                            kind: String::from_utf8_lossy(kind).into_owned(),
+                            abstraction: attribute(element, b"Abstraction"),
+                            status: attribute(element, b"Status").unwrap_or_default(),
+                            description: String::new(),
+                        });
+                        entry_depth = depth;
+                    }
+                    b"Description" | b"Summary" => {
+                        in_description = entry.is_some() && depth == entry_depth + 1
+                    }
+                    _ => {}
+                }
+            }
+            Event::Text(value) if in_description => {
+                let value = value
+                    .unescape_and_decode(&reader)
+                    .map_err(|e| format!("could not decode: {}", e))?;
+                if let Some(entry) = &mut entry {
+                    entry.description.push_str(&value);
+                }
+            }
+            Event::End(element) => {
+                depth -= 1;
+                in_description = false;
+                if matches!(element.local_name(), b"Weakness" | b"Category") {
+                    if let Some(entry) = entry.take() {
+                        if super::normalize(&entry.id).is_some() && !entry.name.is_empty() {
+                            entries.push(entry);
+                        }
+                    }
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+
+        buf.clear();
+    }
+
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse;
+
+    const CATALOG: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<Weakness_Catalog Name="CWE" Version="4.14" Date="2024-02-29" xmlns="http://cwe.mitre.org/cwe-7">
+  <Weaknesses>
+    <Weakness ID="787" Name="Out-of-bounds Write" Abstraction="Base" Structure="Simple" Status="Stable">
+      <Description>The product writes data past the end, or before the beginning, of the intended buffer.</Description>
+      <Extended_Description><xhtml:p>Typically, this can result in corruption of data.</xhtml:p></Extended_Description>
+      <Observed_Examples>
+        <Observed_Example>
+          <Reference>CVE-2021-21220</Reference>
+          <Description>Chain: insufficient input validation leads to an out-of-bounds write.</Description>
+        </Observed_Example>
+      </Observed_Examples>
+    </Weakness>
+    <Weakness ID="1321" Name="Improperly Controlled Modification of Object Prototype Attributes (&apos;Prototype Pollution&apos;)" Abstraction="Variant" Structure="Simple" Status="Incomplete">
+      <Description>The product receives input from an upstream component that specifies attributes.</Description>
+    </Weakness>
+  </Weaknesses>
+  <Categories>
+    <Category ID="1218" Name="Memory Buffer Errors" Status="Draft">
+      <Summary>Weaknesses in this category are related to the handling of memory buffers within a software system.</Summary>
+      <Relationships>
+        <Has_Member CWE_ID="787" View_ID="1194"/>
+      </Relationships>
+    </Category>
+  </Categories>
+  <Views>
+    <View ID="1003" Name="Weaknesses for Simplified Mapping of Published Vulnerabilities" Type="Graph" Status="Incomplete">
+      <Objective>CWE entries in this view are listed in the NVD.</Objective>
+    </View>
+  </Views>
+</Weakness_Catalog>"#;
+
+    #[test]
+    fn can_parse_catalogs() {
+        let entries = parse(CATALOG).unwrap();
+        assert_eq!(3, entries.len());
+
+        assert_eq!("CWE-787", entries[0].id);
+        assert_eq!("Out-of-bounds Write", entries[0].name);
+        assert_eq!("Weakness", entries[0].kind);
+        assert_eq!(Some("Base".to_string()), entries[0].abstraction);
+        assert_eq!("Stable", entries[0].status);
+        assert!(entries[0]
+            .description
+            .starts_with("The product writes data"));
+        assert!(!entries[0].description.contains("Chain"));
+
+        assert!(entries[1].name.ends_with("('Prototype Pollution')"));
+
+        assert_eq!("CWE-1218", entries[2].id);
+        assert_eq!("Category", entries[2].kind);
+        assert_eq!(None, entries[2].abstraction);
+        assert!(entries[2]
+            .description
+            .starts_with("Weaknesses in this category"));
+    }
+}
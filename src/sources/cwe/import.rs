@@ -0,0 +1,75 @@
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::Path;
+
+use anyhow::{anyhow, Context, Result};
+use log::info;
+
+use super::catalog;
+use crate::db::{self, Pool};
+use crate::utils::{download_to_file, ImportError};
+
+pub const FEED_URL: &str = "https://cwe.mitre.org/data/xml/cwec_latest.xml.zip";
+
+/// Reads the catalog of the archive, the single `cwec_v*.xml` file it holds.
+fn read_archive(file_path: &Path) -> Result<String> {
+    let file =
+        File::open(file_path).with_context(|| format!("could not open {}", file_path.display()))?;
+    let mut archive = zip::ZipArchive::new(file).map_err(|e| {
+        ImportError::from(format!("could not read {}: {}", file_path.display(), e))
+            .quarantine(file_path)
+    })?;
+
+    for index in 0..archive.len() {
+        let mut entry = archive.by_index(index)?;
+        if !entry.name().ends_with(".xml") {
+            continue;
+        }
+
+        let mut xml = String::new();
+        entry
+            .read_to_string(&mut xml)
+            .with_context(|| format!("could not read {}", entry.name()))?;
+        return Ok(xml);
+    }
+
+    Err(
+        ImportError::from(format!("no catalog in {}", file_path.display()))
+            .quarantine(file_path)
+            .into(),
+    )
+}
+
+/// Imports the CWE catalog, replacing the previous one, and returns the number of weaknesses
+/// and categories added.
+pub fn run(pool: &Pool, data_path: &Path, fresh: bool) -> Result<u32> {
+    let mut cwe_path = data_path.to_path_buf();
+    cwe_path.push("cwe");
+    fs::create_dir_all(&cwe_path)
+        .with_context(|| format!("could not create {}", cwe_path.display()))?;
+
+    let mut file_path = cwe_path;
+    file_path.push("cwec_latest.xml.zip");
+    if fresh || !file_path.exists() {
+        download_to_file(FEED_URL, &file_path)?;
+    }
+
+    let xml = read_archive(&file_path)?;
+    let entries = catalog::parse(&xml).map_err(|e| {
+        ImportError::from(format!("could not parse the catalog: {}", e)).quarantine(&file_path)
+    })?;
+    // an empty catalog is a broken download rather than all the weaknesses being removed
+    if entries.is_empty() {
+        return Err(ImportError::from("no weakness found".to_string())
+            .quarantine(&file_path)
+            .into());
+    }
+
+    info!("importing {} weaknesses and categories ...", entries.len());
+
+    let num_added = db::Database(pool.get()?)
+        .replace_cwes(entries.iter().map(db::models::NewCwe::with).collect())
+        .map_err(|e| anyhow!(e))?;
+
+    Ok(num_added as u32)
+}
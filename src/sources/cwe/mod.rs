@@ -0,0 +1,146 @@
+//! Catalog of the Common Weakness Enumeration (CWE) of MITRE, naming the weaknesses the NVD
+//! assigns to the CVEs, like `CWE-787` for `Out-of-bounds Write`.
+
+use std::collections::HashMap;
+
+use crate::db::{models, Database};
+
+#[cfg(feature = "import")]
+pub mod catalog;
+#[cfg(feature = "import")]
+pub mod import;
+
+pub use kepler_types::api::Weakness;
+
+pub const SOURCE_NAME: &str = "CWE";
+
+/// Weakness or category of the catalog.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Entry {
+    /// Like `CWE-787`.
+    pub id: String,
+    pub name: String,
+    /// `Weakness` or `Category`.
+    pub kind: String,
+    /// `Pillar`, `Class`, `Base` or `Variant`, only for the weaknesses.
+    pub abstraction: Option<String>,
+    /// Like `Stable`, `Draft` or `Deprecated`.
+    pub status: String,
+    pub description: String,
+}
+
+/// CWE id of a weakness, uppercased like `CWE-787`, unless it's not one like the
+/// `NVD-CWE-Other` and `NVD-CWE-noinfo` placeholders of the NVD.
+pub fn normalize(id: &str) -> Option<String> {
+    let id = id.trim().to_uppercase();
+    let number = id.strip_prefix("CWE-")?;
+    if number.is_empty() || !number.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    Some(id)
+}
+
+/// Weaknesses of the CVEs among a set of records, named after the catalog when imported.
+#[derive(Debug, Default)]
+pub struct Weaknesses(HashMap<String, Vec<Weakness>>);
+
+impl Weaknesses {
+    pub fn with(entries: Vec<models::CveWeakness>, names: &HashMap<String, String>) -> Self {
+        let mut weaknesses: HashMap<String, Vec<Weakness>> = HashMap::new();
+        for entry in entries {
+            weaknesses.entry(entry.cve).or_default().push(Weakness {
+                name: names.get(&entry.cwe).cloned(),
+                id: entry.cwe,
+            });
+        }
+        Self(weaknesses)
+    }
+
+    /// Looks up the weaknesses of the CVEs of the records, and their names.
+    pub fn load(db: &Database, cves: &[models::CVE]) -> Result<Self, String> {
+        let mut ids: Vec<&str> = cves.iter().map(|cve| cve.cve.as_str()).collect();
+        ids.sort_unstable();
+        ids.dedup();
+        if ids.is_empty() {
+            return Ok(Self::default());
+        }
+
+        let entries = db.get_cve_weaknesses(&ids)?;
+        let mut cwes: Vec<&str> = entries.iter().map(|entry| entry.cwe.as_str()).collect();
+        cwes.sort_unstable();
+        cwes.dedup();
+        let names = if cwes.is_empty() {
+            HashMap::new()
+        } else {
+            db.get_cwes(&cwes)?
+                .into_iter()
+                .map(|cwe| (cwe.cwe, cwe.name))
+                .collect()
+        };
+        Ok(Self::with(entries, &names))
+    }
+
+    /// Whether the CVE has the weakness, like `CWE-787`.
+    pub fn has(&self, cve: &str, cwe: &str) -> bool {
+        self.0
+            .get(cve)
+            .map_or(false, |weaknesses| weaknesses.iter().any(|w| w.id == cwe))
+    }
+
+    pub fn of(&self, cve: &str) -> kepler_types::api::Weaknesses {
+        kepler_types::api::Weaknesses {
+            weaknesses: self.0.get(cve).cloned().unwrap_or_default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use chrono::Utc;
+
+    use super::{normalize, Weaknesses};
+    use crate::db::models;
+
+    fn entry(cve: &str, cwe: &str) -> models::CveWeakness {
+        models::CveWeakness {
+            id: 0,
+            created_at: Utc::now(),
+            cve: cve.into(),
+            cwe: cwe.into(),
+        }
+    }
+
+    #[test]
+    fn can_normalize_ids() {
+        assert_eq!(Some("CWE-787".to_string()), normalize(" cwe-787"));
+        assert_eq!(None, normalize("NVD-CWE-Other"));
+        assert_eq!(None, normalize("NVD-CWE-noinfo"));
+        assert_eq!(None, normalize("CWE-"));
+        assert_eq!(None, normalize("CWE-78x"));
+    }
+
+    #[test]
+    fn can_name_weaknesses() {
+        let names: HashMap<String, String> =
+            vec![("CWE-787".to_string(), "Out-of-bounds Write".to_string())]
+                .into_iter()
+                .collect();
+        let weaknesses = Weaknesses::with(
+            vec![
+                entry("CVE-2021-3517", "CWE-787"),
+                entry("CVE-2021-3517", "CWE-1321"),
+            ],
+            &names,
+        );
+
+        let found = weaknesses.of("CVE-2021-3517").weaknesses;
+        assert_eq!(2, found.len());
+        assert_eq!(Some("Out-of-bounds Write".to_string()), found[0].name);
+        assert_eq!(None, found[1].name);
+        assert!(weaknesses.has("CVE-2021-3517", "CWE-1321"));
+        assert!(!weaknesses.has("CVE-2021-44228", "CWE-787"));
+        assert!(weaknesses.of("CVE-2021-44228").weaknesses.is_empty());
+    }
+}
@@ -0,0 +1,157 @@
+//! Comparison of the Debian package versions, like `dpkg --compare-versions` does.
+
+use std::cmp::Ordering;
+
+/// Epoch, upstream version and Debian revision of a package, like `1:1.1.1n-0+deb11u3`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Version<'a> {
+    pub epoch: Option<u64>,
+    pub upstream: &'a str,
+    pub revision: Option<&'a str>,
+}
+
+impl<'a> Version<'a> {
+    pub fn parse(version: &'a str) -> Self {
+        let (epoch, rest) = match version.split_once(':') {
+            Some((epoch, rest)) => match epoch.parse::<u64>() {
+                Ok(epoch) => (Some(epoch), rest),
+                Err(_) => (None, version),
+            },
+            None => (None, version),
+        };
+        let (upstream, revision) = match rest.rsplit_once('-') {
+            Some((upstream, revision)) => (upstream, Some(revision)),
+            None => (rest, None),
+        };
+
+        Self {
+            epoch,
+            upstream,
+            revision,
+        }
+    }
+
+    /// Compares with another version of the same package. Like for the RPM packages, the
+    /// epochs are compared only if both are set, and the revisions only if both are set.
+    pub fn compare(&self, other: &Version) -> Ordering {
+        if let (Some(epoch), Some(other_epoch)) = (self.epoch, other.epoch) {
+            if epoch != other_epoch {
+                return epoch.cmp(&other_epoch);
+            }
+        }
+
+        match compare(self.upstream, other.upstream) {
+            Ordering::Equal => match (self.revision, other.revision) {
+                (Some(revision), Some(other_revision)) => compare(revision, other_revision),
+                _ => Ordering::Equal,
+            },
+            ordering => ordering,
+        }
+    }
+}
+
+/// Weight of a character of the non-numeric parts, letters sorting before the other
+/// characters, and `~` before anything, even the end of the part.
+fn order(c: Option<u8>) -> i32 {
+    match c {
+        Some(b'~') => -1,
+        Some(c) if c.is_ascii_digit() => 0,
+        Some(c) if c.is_ascii_alphabetic() => c as i32,
+        Some(c) => c as i32 + 256,
+        None => 0,
+    }
+}
+
+/// Compares two upstream versions or revisions, alternating the non-numeric parts compared
+/// character by character and the numeric parts compared as numbers.
+pub fn compare(a: &str, b: &str) -> Ordering {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    let (mut i, mut j) = (0, 0);
+
+    while i < a.len() || j < b.len() {
+        while (i < a.len() && !a[i].is_ascii_digit()) || (j < b.len() && !b[j].is_ascii_digit()) {
+            let (ac, bc) = (order(a.get(i).copied()), order(b.get(j).copied()));
+            if ac != bc {
+                return ac.cmp(&bc);
+            }
+            i += 1;
+            j += 1;
+        }
+
+        while i < a.len() && a[i] == b'0' {
+            i += 1;
+        }
+        while j < b.len() && b[j] == b'0' {
+            j += 1;
+        }
+
+        let mut first_diff = Ordering::Equal;
+        while i < a.len() && j < b.len() && a[i].is_ascii_digit() && b[j].is_ascii_digit() {
+            if first_diff == Ordering::Equal {
+                first_diff = a[i].cmp(&b[j]);
+            }
+            i += 1;
+            j += 1;
+        }
+        if i < a.len() && a[i].is_ascii_digit() {
+            return Ordering::Greater;
+        }
+        if j < b.len() && b[j].is_ascii_digit() {
+            return Ordering::Less;
+        }
+        if first_diff != Ordering::Equal {
+            return first_diff;
+        }
+    }
+
+    Ordering::Equal
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cmp::Ordering;
+
+    use super::{compare, Version};
+
+    #[test]
+    fn can_compare_versions() {
+        assert_eq!(Ordering::Equal, compare("1.0", "1.0"));
+        assert_eq!(Ordering::Less, compare("1.0", "1.0.1"));
+        assert_eq!(Ordering::Greater, compare("2.10", "2.9"));
+        assert_eq!(Ordering::Less, compare("1.1.1k", "1.1.1n"));
+        assert_eq!(Ordering::Equal, compare("1.01", "1.1"));
+        assert_eq!(Ordering::Less, compare("1.0~rc1", "1.0"));
+        assert_eq!(Ordering::Less, compare("1.0~rc1", "1.0~rc2"));
+        assert_eq!(Ordering::Less, compare("1.0", "1.0a"));
+        assert_eq!(Ordering::Less, compare("1.0a", "1.0+"));
+        assert_eq!(Ordering::Less, compare("0+deb11u2", "0+deb11u3"));
+        assert_eq!(Ordering::Less, compare("1", "1+deb11u1"));
+        assert_eq!(Ordering::Less, compare("1~bpo11+1", "1"));
+    }
+
+    #[test]
+    fn can_compare_package_versions() {
+        let fixed = Version::parse("1:1.1.1n-0+deb11u3");
+        assert_eq!(Some(1), fixed.epoch);
+        assert_eq!("1.1.1n", fixed.upstream);
+        assert_eq!(Some("0+deb11u3"), fixed.revision);
+
+        assert_eq!(
+            Ordering::Less,
+            Version::parse("1:1.1.1n-0+deb11u2").compare(&fixed)
+        );
+        assert_eq!(
+            Ordering::Equal,
+            Version::parse("1.1.1n-0+deb11u3").compare(&fixed)
+        );
+        assert_eq!(Ordering::Less, Version::parse("1.1.1k").compare(&fixed));
+        assert_eq!(
+            Ordering::Greater,
+            Version::parse("2:1.0.0-1").compare(&fixed)
+        );
+        assert_eq!(
+            Ordering::Less,
+            Version::parse("2.36-9+deb12u1").compare(&Version::parse("2.36-9+deb12u3"))
+        );
+    }
+}
@@ -0,0 +1,113 @@
+use std::fs;
+use std::path::Path;
+
+use anyhow::{anyhow, Context, Result};
+use log::info;
+
+use super::{cves, Cve, Tracker, SOURCE_NAME, VENDOR};
+
+use crate::db::{self, Pool};
+use crate::utils::{download_to_file, ImportError};
+
+pub const FEED_URL: &str = "https://security-tracker.debian.org/tracker/data/json";
+
+fn store(database: &db::Database, cve: &Cve) -> Result<u32> {
+    let products = cve.products();
+    let affected: Vec<(String, String)> = products
+        .iter()
+        .map(|product| (VENDOR.to_string(), product.clone()))
+        .collect();
+    // the packages no longer affected, or all of them, like after a rejection
+    database
+        .delete_other_products(SOURCE_NAME, &cve.name, &affected)
+        .map_err(|e| anyhow!(e))?;
+    if products.is_empty() {
+        return Ok(0);
+    }
+
+    let object_id = database
+        .upsert_object(db::models::NewObject::with(
+            Cve::object_key(&cve.name),
+            serde_json::to_string(cve)?,
+        ))
+        .map_err(|e| anyhow!(e))?;
+
+    let mut refs = db::models::References::default();
+    refs.push(db::models::Reference {
+        url: format!("https://security-tracker.debian.org/tracker/{}", cve.name),
+        tags: vec!["Debian".into()],
+    });
+    for bug in cve.packages.iter().filter_map(|package| package.debianbug) {
+        refs.push(db::models::Reference {
+            url: format!("https://bugs.debian.org/{}", bug),
+            tags: vec!["Issue Tracking".into()],
+        });
+    }
+
+    let mut num_imported = 0;
+    for product in products {
+        let new_cve = db::models::NewCVE::with(
+            SOURCE_NAME.into(),
+            VENDOR.into(),
+            product,
+            cve.name.clone(),
+            cve.summary(),
+            0.0,
+            cve.severity(),
+            cve.vector(),
+            refs.clone(),
+            Some(object_id),
+        );
+        if database.upsert_cve(new_cve).map_err(|e| anyhow!(e))? {
+            num_imported += 1;
+        }
+    }
+
+    Ok(num_imported)
+}
+
+/// Imports the CVEs of the Debian packages from the tracker data, downloaded again with
+/// `fresh`, creating the new records, updating the changed ones and deleting the ones of the
+/// packages no longer affected.
+pub fn run(pool: &Pool, data_path: &Path, fresh: bool) -> Result<u32> {
+    let mut debian_path = data_path.to_path_buf();
+    debian_path.push("debian");
+    fs::create_dir_all(&debian_path)
+        .with_context(|| format!("could not create {}", debian_path.display()))?;
+
+    let mut file_path = debian_path;
+    file_path.push("tracker.json");
+    if fresh || !file_path.exists() {
+        download_to_file(FEED_URL, &file_path)?;
+    }
+
+    let json = fs::read_to_string(&file_path)
+        .with_context(|| format!("could not read {}", file_path.display()))?;
+    let tracker: Tracker = serde_json::from_str(&json).map_err(|e| {
+        ImportError::from(format!("could not parse the tracker data: {}", e)).quarantine(&file_path)
+    })?;
+    // an empty dump is a broken feed rather than all the CVEs being fixed
+    if tracker.is_empty() {
+        return Err(ImportError::from("the tracker data is empty".to_string())
+            .quarantine(&file_path)
+            .into());
+    }
+
+    let cves = cves(tracker);
+    info!(
+        "importing the {} CVEs of the Debian packages ...",
+        cves.len()
+    );
+
+    let database = db::Database(pool.get()?);
+    let mut num_imported = 0;
+    for (index, cve) in cves.iter().enumerate() {
+        num_imported += store(&database, cve)?;
+
+        if index > 0 && index % 10000 == 0 {
+            info!("processed {} CVEs ...", index);
+        }
+    }
+
+    Ok(num_imported)
+}
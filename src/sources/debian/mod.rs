@@ -0,0 +1,357 @@
+//! CVEs of the Debian packages tracked by the Debian Security Tracker, with the versions
+//! fixing them in every release rather than the upstream version ranges.
+
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::scan::ecosystem_product;
+use crate::search::Query;
+
+pub mod dpkg;
+#[cfg(feature = "import")]
+pub mod import;
+
+pub const SOURCE_NAME: &str = "Debian";
+/// No vendors for the packages, the Debian release is part of the product name.
+pub const VENDOR: &str = "@debian";
+
+/// Version numbers of the releases, named by their codename in the tracker.
+pub const RELEASES: &[(&str, &str)] = &[
+    ("8", "jessie"),
+    ("9", "stretch"),
+    ("10", "buster"),
+    ("11", "bullseye"),
+    ("12", "bookworm"),
+    ("13", "trixie"),
+    ("14", "forky"),
+];
+
+/// Product of a source package of a Debian release, like `debian-bullseye-openssl`.
+pub fn product(release: &str, package: &str) -> String {
+    ecosystem_product(&format!("debian-{}", release), package)
+}
+
+/// Codename of a release given by its number, like `11` or `11.6`, or by its codename.
+pub fn codename(release: &str) -> Option<&str> {
+    let major = release.split('.').next().unwrap_or_default();
+    RELEASES
+        .iter()
+        .find(|(number, codename)| *number == major || *codename == release)
+        .map(|(_, codename)| *codename)
+        .or_else(|| Some(release).filter(|release| *release == "sid"))
+}
+
+/// State of a package in a release, as reported by the tracker.
+#[derive(Debug, Default, Clone, Deserialize, Serialize)]
+pub struct Release {
+    /// `resolved`, `open` or `undetermined`.
+    pub status: String,
+    /// Version fixing the CVE, `0` when the package of the release was never affected.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fixed_version: Option<String>,
+    /// `unimportant`, `low`, `medium`, `high` or `not yet assigned`, followed by `*` or `**`
+    /// when it's not been set by the security team.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub urgency: Option<String>,
+    /// Note of the security team when the CVE won't get a security advisory (DSA), being
+    /// minor or postponed to a point release.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nodsa: Option<String>,
+    /// `postponed` or `ignored` for the no-DSA CVEs.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nodsa_reason: Option<String>,
+}
+
+impl Release {
+    /// Whether the package of the release is affected, either fixed in a later version or not
+    /// fixed at all. The no-DSA CVEs are open until fixed by a point release.
+    pub fn is_affected(&self) -> bool {
+        match self.status.as_str() {
+            "resolved" => self.fixed().is_some(),
+            "open" => true,
+            _ => false,
+        }
+    }
+
+    /// Version fixing the CVE, if the package was affected.
+    pub fn fixed(&self) -> Option<&str> {
+        self.fixed_version
+            .as_deref()
+            .filter(|fixed| !fixed.is_empty() && *fixed != "0")
+    }
+
+    pub fn is_nodsa(&self) -> bool {
+        self.nodsa.is_some()
+    }
+
+    fn urgency(&self) -> &str {
+        self.urgency
+            .as_deref()
+            .unwrap_or_default()
+            .trim_end_matches('*')
+            .trim()
+    }
+}
+
+/// CVE of a source package as reported by the tracker.
+#[derive(Debug, Default, Clone, Deserialize)]
+pub struct Entry {
+    #[serde(default)]
+    pub description: Option<String>,
+    /// `local` or `remote`.
+    #[serde(default)]
+    pub scope: Option<String>,
+    #[serde(default)]
+    pub debianbug: Option<u64>,
+    #[serde(default)]
+    pub releases: BTreeMap<String, Release>,
+}
+
+/// Tracker data, the CVEs of every source package.
+pub type Tracker = BTreeMap<String, BTreeMap<String, Entry>>;
+
+/// Source package affected by a CVE, with its state in every release.
+#[derive(Debug, Default, Clone, Deserialize, Serialize)]
+pub struct Package {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub debianbug: Option<u64>,
+    pub releases: BTreeMap<String, Release>,
+}
+
+#[derive(Debug, Default, Clone, Deserialize, Serialize)]
+pub struct Cve {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
+    pub packages: Vec<Package>,
+}
+
+/// Groups the tracker data by CVE, leaving out the issues without a CVE id yet.
+pub fn cves(tracker: Tracker) -> Vec<Cve> {
+    let mut cves: BTreeMap<String, Cve> = BTreeMap::new();
+
+    for (package, entries) in tracker {
+        for (id, entry) in entries {
+            if !id.starts_with("CVE-") {
+                continue;
+            }
+            let cve = cves.entry(id.clone()).or_insert_with(|| Cve {
+                name: id,
+                ..Default::default()
+            });
+            if cve.description.is_none() {
+                cve.description = entry.description.filter(|d| !d.trim().is_empty());
+            }
+            if cve.scope.is_none() {
+                cve.scope = entry.scope;
+            }
+            cve.packages.push(Package {
+                name: package.clone(),
+                debianbug: entry.debianbug,
+                releases: entry.releases,
+            });
+        }
+    }
+
+    cves.into_iter().map(|(_, cve)| cve).collect()
+}
+
+impl Cve {
+    pub fn object_key(id: &str) -> String {
+        format!("{} (debian)", id)
+    }
+
+    pub fn summary(&self) -> String {
+        self.description
+            .as_deref()
+            .unwrap_or_default()
+            .trim()
+            .to_string()
+    }
+
+    /// Highest urgency of the CVE among the releases, named like the NIST severities.
+    pub fn severity(&self) -> String {
+        let releases = self.packages.iter().flat_map(|p| p.releases.values());
+        let rank = releases
+            .map(|release| match release.urgency() {
+                "high" => 3,
+                "medium" => 2,
+                "low" | "unimportant" => 1,
+                _ => 0,
+            })
+            .max()
+            .unwrap_or_default();
+
+        match rank {
+            3 => "HIGH",
+            2 => "MEDIUM",
+            1 => "LOW",
+            _ => "NONE",
+        }
+        .to_string()
+    }
+
+    pub fn vector(&self) -> Option<String> {
+        match self.scope.as_deref() {
+            Some("remote") => Some("NETWORK".to_string()),
+            Some("local") => Some("LOCAL".to_string()),
+            _ => None,
+        }
+    }
+
+    /// Unique products of the packages of the releases affected.
+    pub fn products(&self) -> Vec<String> {
+        let mut products = vec![];
+        for package in &self.packages {
+            for (release, state) in &package.releases {
+                let product = product(release, &package.name);
+                if state.is_affected() && !products.contains(&product) {
+                    products.push(product);
+                }
+            }
+        }
+        products
+    }
+
+    /// Matches the versions older than the fixed one, or any version while not fixed.
+    pub fn is_match(&self, query: &Query) -> bool {
+        // we need a version
+        let version = match &query.version {
+            Some(version) => dpkg::Version::parse(version),
+            None => return false,
+        };
+
+        self.packages.iter().any(|package| {
+            package.releases.iter().any(|(release, state)| {
+                if product(release, &package.name) != query.product || !state.is_affected() {
+                    return false;
+                }
+                match state.fixed() {
+                    Some(fixed) => version.compare(&dpkg::Version::parse(fixed)) == Ordering::Less,
+                    None => true,
+                }
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{codename, cves, Tracker};
+    use crate::search::{testing::query, Query};
+
+    const TRACKER: &str = r#"{
+        "openssl": {
+            "CVE-2022-0778": {
+                "description": "The BN_mod_sqrt() function can loop forever for non-prime moduli",
+                "scope": "remote",
+                "debianbug": 1007891,
+                "releases": {
+                    "bullseye": {
+                        "status": "resolved",
+                        "repositories": {"bullseye": "1.1.1n-0+deb11u3"},
+                        "fixed_version": "1.1.1n-0+deb11u1",
+                        "urgency": "not yet assigned"
+                    },
+                    "buster": {
+                        "status": "resolved",
+                        "repositories": {"buster": "1.1.1n-0+deb10u3"},
+                        "fixed_version": "1.1.1d-0+deb10u8",
+                        "urgency": "high"
+                    }
+                }
+            },
+            "TEMP-0000000-ABCDEF": {
+                "releases": {}
+            }
+        },
+        "openssl1.0": {
+            "CVE-2022-0778": {
+                "scope": "remote",
+                "releases": {
+                    "stretch": {
+                        "status": "open",
+                        "repositories": {"stretch": "1.0.2u-1~deb9u7"},
+                        "urgency": "low**",
+                        "nodsa": "Minor issue",
+                        "nodsa_reason": "postponed"
+                    },
+                    "bullseye": {
+                        "status": "resolved",
+                        "repositories": {},
+                        "fixed_version": "0",
+                        "urgency": "not yet assigned"
+                    },
+                    "bookworm": {
+                        "status": "undetermined",
+                        "repositories": {}
+                    }
+                }
+            }
+        }
+    }"#;
+
+    #[test]
+    fn can_group_cves() {
+        let tracker: Tracker = serde_json::from_str(TRACKER).unwrap();
+        let cves = cves(tracker);
+
+        assert_eq!(1, cves.len());
+        let cve = &cves[0];
+        assert_eq!("CVE-2022-0778", cve.name);
+        assert_eq!(2, cve.packages.len());
+        assert_eq!(
+            "The BN_mod_sqrt() function can loop forever for non-prime moduli",
+            cve.summary()
+        );
+        assert_eq!("HIGH", cve.severity());
+        assert_eq!(Some("NETWORK".to_string()), cve.vector());
+        assert_eq!(
+            vec![
+                "debian-bullseye-openssl",
+                "debian-buster-openssl",
+                "debian-stretch-openssl1.0",
+            ],
+            cve.products()
+        );
+        assert!(cve.packages[1].releases["stretch"].is_nodsa());
+    }
+
+    #[test]
+    fn can_match_fixed_versions() {
+        let tracker: Tracker = serde_json::from_str(TRACKER).unwrap();
+        let cve = &cves(tracker)[0];
+
+        assert!(cve.is_match(&query("debian-bullseye-openssl", "1.1.1k-1+deb11u2")));
+        assert!(!cve.is_match(&query("debian-bullseye-openssl", "1.1.1n-0+deb11u1")));
+        assert!(!cve.is_match(&query("debian-bullseye-openssl", "1.1.1n-0+deb11u3")));
+        // the fixes are backported, the upstream version alone doesn't tell
+        assert!(!cve.is_match(&query("debian-buster-openssl", "1.1.1d-0+deb10u8")));
+        assert!(cve.is_match(&query("debian-buster-openssl", "1.1.1d-0+deb10u7")));
+        // no-DSA issues stay open
+        assert!(cve.is_match(&query("debian-stretch-openssl1.0", "1.0.2u-1~deb9u7")));
+        // never affected or not determined yet
+        assert!(!cve.is_match(&query("debian-bullseye-openssl1.0", "1.0.2u-1")));
+        assert!(!cve.is_match(&query("debian-bookworm-openssl1.0", "1.0.2u-1")));
+        assert!(!cve.is_match(&Query {
+            vendor: None,
+            product: "debian-bullseye-openssl".into(),
+            version: None,
+            ..Default::default()
+        }));
+    }
+
+    #[test]
+    fn can_name_releases() {
+        assert_eq!(Some("bullseye"), codename("11"));
+        assert_eq!(Some("bullseye"), codename("11.6"));
+        assert_eq!(Some("bookworm"), codename("bookworm"));
+        assert_eq!(Some("sid"), codename("sid"));
+        assert_eq!(None, codename("ubuntu"));
+    }
+}
@@ -0,0 +1,68 @@
+use std::fs;
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+use log::{info, warn};
+
+use super::Cycle;
+
+use crate::db::{self, Pool};
+use crate::utils::{download_to_file, ImportError};
+
+pub const API_URL: &str = "https://endoflife.date/api";
+
+fn download(url: &str, file_path: &Path, fresh: bool) -> Result<String> {
+    if fresh || !file_path.exists() {
+        download_to_file(url, file_path)?;
+    }
+    fs::read_to_string(file_path).with_context(|| format!("could not read {}", file_path.display()))
+}
+
+pub fn run(pool: &Pool, data_path: &Path, fresh: bool) -> Result<u32> {
+    let mut eol_path = data_path.to_path_buf();
+    eol_path.push("eol");
+    fs::create_dir_all(&eol_path)
+        .with_context(|| format!("could not create {}", eol_path.display()))?;
+
+    let mut file_path = eol_path.clone();
+    file_path.push("all.json");
+    let products: Vec<String> = serde_json::from_str(&download(
+        &format!("{}/all.json", API_URL),
+        &file_path,
+        fresh,
+    )?)
+    .map_err(|e| {
+        ImportError::from(format!("could not parse the list of products: {}", e))
+            .quarantine(&file_path)
+    })?;
+
+    info!(
+        "importing the release cycles of {} products ...",
+        products.len()
+    );
+
+    let database = db::Database(pool.get()?);
+    let mut num_imported = 0;
+
+    for product in products {
+        let mut file_path = eol_path.clone();
+        file_path.push(format!("{}.json", product));
+
+        let json = download(&format!("{}/{}.json", API_URL, product), &file_path, fresh)?;
+        let cycles: Vec<Cycle> = match serde_json::from_str(&json) {
+            Ok(cycles) => cycles,
+            Err(e) => {
+                warn!("skipping {}: {}", product, e);
+                continue;
+            }
+        };
+
+        match database.upsert_eol_product(db::models::NewEolProduct::with(product, cycles)) {
+            Err(e) => bail!(e),
+            Ok(true) => num_imported += 1,
+            Ok(false) => {}
+        }
+    }
+
+    Ok(num_imported)
+}
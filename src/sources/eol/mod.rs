@@ -0,0 +1,200 @@
+use std::collections::HashMap;
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::db::Database;
+use crate::search::Query;
+
+#[cfg(feature = "import")]
+pub mod import;
+
+pub const SOURCE_NAME: &str = "endoflife.date";
+
+/// Prefixes of the products of the language ecosystem packages.
+const ECOSYSTEMS: &[&str] = &["node-", "rust-", "python-", "go-"];
+
+/// Products whose name on endoflife.date differs from their CPE product name.
+const PRODUCT_NAMES: &[(&str, &str)] = &[
+    ("linux_kernel", "linux"),
+    ("node.js", "nodejs"),
+    ("http_server", "apache"),
+];
+
+/// End of life of a release cycle, either a date or just a flag.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum Eol {
+    Flag(bool),
+    Date(NaiveDate),
+}
+
+/// Release cycle of a product as published by endoflife.date.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct Cycle {
+    #[serde(deserialize_with = "string_or_number")]
+    pub cycle: String,
+    pub eol: Eol,
+    #[serde(default, deserialize_with = "optional_string_or_number")]
+    pub latest: Option<String>,
+}
+
+fn string_or_number<'de, D: Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+    match serde_json::Value::deserialize(deserializer)? {
+        serde_json::Value::String(s) => Ok(s),
+        serde_json::Value::Number(n) => Ok(n.to_string()),
+        other => Err(serde::de::Error::custom(format!(
+            "expected a string or a number, found {}",
+            other
+        ))),
+    }
+}
+
+fn optional_string_or_number<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<String>, D::Error> {
+    match Option::<serde_json::Value>::deserialize(deserializer)? {
+        Some(serde_json::Value::String(s)) => Ok(Some(s)),
+        Some(serde_json::Value::Number(n)) => Ok(Some(n.to_string())),
+        _ => Ok(None),
+    }
+}
+
+impl Cycle {
+    /// Whether the version belongs to this cycle, so `1.1.1k` belongs to `1.1.1` and `1.1`
+    /// but `1.10` doesn't belong to `1.1`.
+    pub fn contains(&self, version: &str) -> bool {
+        match version.strip_prefix(self.cycle.as_str()) {
+            Some(rest) => !rest.starts_with(|c: char| c.is_ascii_digit()),
+            None => false,
+        }
+    }
+
+    pub fn is_eol(&self, today: NaiveDate) -> bool {
+        match self.eol {
+            Eol::Flag(eol) => eol,
+            Eol::Date(date) => date <= today,
+        }
+    }
+}
+
+/// Maps a CPE or ecosystem product name to its endoflife.date name.
+pub fn product_name(product: &str) -> String {
+    let product = ECOSYSTEMS
+        .iter()
+        .find_map(|prefix| product.strip_prefix(prefix))
+        .unwrap_or(product);
+
+    PRODUCT_NAMES
+        .iter()
+        .find(|(name, _)| *name == product)
+        .map(|(_, eol_name)| eol_name.to_string())
+        .unwrap_or_else(|| product.replace('_', "-"))
+}
+
+/// The most specific cycle containing the version.
+pub fn find_cycle<'a>(cycles: &'a [Cycle], version: &str) -> Option<&'a Cycle> {
+    cycles
+        .iter()
+        .filter(|cycle| cycle.contains(version))
+        .max_by_key(|cycle| cycle.cycle.len())
+}
+
+/// Component whose release cycle reached its end of life, so no more fixes are coming.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct EndOfLife {
+    pub product: String,
+    pub version: String,
+    pub cycle: String,
+    pub eol: Eol,
+    pub latest: Option<String>,
+}
+
+/// Returns the components whose release cycle reached its end of life by the given day.
+pub fn check(db: &Database, queries: &[Query], today: NaiveDate) -> Result<Vec<EndOfLife>, String> {
+    let names: Vec<String> = queries.iter().map(|q| product_name(&q.product)).collect();
+    let products: HashMap<String, Vec<Cycle>> = db
+        .get_eol_products(&names)?
+        .into_iter()
+        .map(|eol| (eol.product, eol.cycles.0))
+        .collect();
+
+    let mut eol = vec![];
+    for (query, name) in queries.iter().zip(names) {
+        let version = match &query.version {
+            Some(version) => version,
+            None => continue,
+        };
+        let cycle = products
+            .get(&name)
+            .and_then(|cycles| find_cycle(cycles, version));
+
+        if let Some(cycle) = cycle.filter(|cycle| cycle.is_eol(today)) {
+            eol.push(EndOfLife {
+                product: query.product.clone(),
+                version: version.clone(),
+                cycle: cycle.cycle.clone(),
+                eol: cycle.eol.clone(),
+                latest: cycle.latest.clone(),
+            });
+        }
+    }
+
+    Ok(eol)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::{find_cycle, product_name, Cycle, Eol};
+
+    #[test]
+    fn can_parse_cycles() {
+        let json = r#"[
+            {"cycle": "3.0", "releaseDate": "2021-09-07", "eol": "2026-09-07", "latest": "3.0.3", "lts": true},
+            {"cycle": "1.1.1", "releaseDate": "2018-09-11", "eol": "2023-09-11", "latest": "1.1.1o"},
+            {"cycle": 1.0, "eol": true, "latest": null}
+        ]"#;
+
+        let cycles: Vec<Cycle> = serde_json::from_str(json).unwrap();
+
+        assert_eq!("1.0", cycles[2].cycle);
+        assert_eq!(Eol::Flag(true), cycles[2].eol);
+        assert_eq!(None, cycles[2].latest);
+        assert_eq!(Eol::Date(NaiveDate::from_ymd(2023, 9, 11)), cycles[1].eol);
+    }
+
+    #[test]
+    fn can_find_cycles() {
+        let cycles: Vec<Cycle> = serde_json::from_str(
+            r#"[
+                {"cycle": "1.1", "eol": "2019-09-11"},
+                {"cycle": "1.1.1", "eol": "2023-09-11"},
+                {"cycle": "1.10", "eol": false}
+            ]"#,
+        )
+        .unwrap();
+
+        assert_eq!("1.1.1", find_cycle(&cycles, "1.1.1k").unwrap().cycle);
+        assert_eq!("1.1", find_cycle(&cycles, "1.1.0h").unwrap().cycle);
+        assert_eq!("1.10", find_cycle(&cycles, "1.10.2").unwrap().cycle);
+        assert!(find_cycle(&cycles, "2.0").is_none());
+
+        let cycle = find_cycle(&cycles, "1.1.1k").unwrap();
+        assert!(!cycle.is_eol(NaiveDate::from_ymd(2022, 5, 18)));
+        assert!(cycle.is_eol(NaiveDate::from_ymd(2023, 9, 11)));
+        assert!(!find_cycle(&cycles, "1.10.2")
+            .unwrap()
+            .is_eol(NaiveDate::from_ymd(2030, 1, 1)));
+    }
+
+    #[test]
+    fn can_map_product_names() {
+        assert_eq!("linux", product_name("linux_kernel"));
+        assert_eq!("nodejs", product_name("node.js"));
+        assert_eq!("openssl", product_name("openssl"));
+        assert_eq!("amazon-linux", product_name("amazon_linux"));
+        assert_eq!("django", product_name("python-django"));
+    }
+}
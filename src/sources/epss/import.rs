@@ -0,0 +1,68 @@
+use std::fs;
+use std::path::Path;
+
+use anyhow::{anyhow, Context, Result};
+use log::info;
+
+use crate::db::{self, Pool};
+use crate::utils::{self, download_to_file, ImportError};
+
+pub const FEED_URL: &str = "https://epss.cyentia.com/epss_scores-current.csv.gz";
+
+/// Imports the scores of the latest model run, replacing the previous ones, and returns the
+/// number of CVEs scored for the first time.
+pub fn run(pool: &Pool, data_path: &Path, fresh: bool) -> Result<u32> {
+    let mut epss_path = data_path.to_path_buf();
+    epss_path.push("epss");
+    fs::create_dir_all(&epss_path)
+        .with_context(|| format!("could not create {}", epss_path.display()))?;
+
+    let mut gzip_path = epss_path.clone();
+    gzip_path.push("epss_scores-current.csv.gz");
+    let mut file_path = epss_path;
+    file_path.push("epss_scores-current.csv");
+    if fresh || !gzip_path.exists() {
+        download_to_file(FEED_URL, &gzip_path)?;
+    }
+    if fresh || !file_path.exists() {
+        utils::gunzip(&gzip_path, &file_path)
+            .map_err(|e| ImportError::from(e).quarantine(&gzip_path))?;
+    }
+
+    let csv = fs::read_to_string(&file_path)
+        .with_context(|| format!("could not read {}", file_path.display()))?;
+    let feed = super::parse(&csv).map_err(|e| {
+        ImportError::from(format!("could not parse the scores: {}", e)).quarantine(&file_path)
+    })?;
+    // an empty feed is a broken download rather than all the scores being withdrawn
+    if feed.scores.is_empty() {
+        return Err(ImportError::from("the feed is empty".to_string())
+            .quarantine(&file_path)
+            .into());
+    }
+
+    info!(
+        "importing the EPSS scores of {} CVEs from {} ...",
+        feed.scores.len(),
+        feed.score_date
+    );
+
+    let score_date = feed.score_date;
+    let num_added = db::Database(pool.get()?)
+        .replace_epss_scores(
+            feed.scores
+                .into_iter()
+                .map(|score| {
+                    db::models::NewEpssScore::with(
+                        score.cve,
+                        score.epss,
+                        score.percentile,
+                        score_date.clone(),
+                    )
+                })
+                .collect(),
+        )
+        .map_err(|e| anyhow!(e))?;
+
+    Ok(num_added as u32)
+}
@@ -0,0 +1,173 @@
+//! Exploit Prediction Scoring System published daily by FIRST, estimating the probability of
+//! every CVE being exploited in the next 30 days.
+
+use std::collections::HashMap;
+
+use crate::db::{models, Database};
+
+#[cfg(feature = "import")]
+pub mod import;
+
+pub use kepler_types::api::Epss;
+
+pub const SOURCE_NAME: &str = "FIRST EPSS";
+
+/// Scores of a single model run, as published in the CSV feed.
+#[derive(Debug, Default)]
+pub struct Feed {
+    pub model_version: Option<String>,
+    /// Date formatted as `YYYY-MM-DD`.
+    pub score_date: String,
+    pub scores: Vec<Score>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Score {
+    pub cve: String,
+    pub epss: f64,
+    pub percentile: f64,
+}
+
+/// Parses the feed, made of an optional `#model_version:...,score_date:...` comment line, the
+/// `cve,epss,percentile` header and a line for every scored CVE.
+pub fn parse(csv: &str) -> Result<Feed, String> {
+    let mut feed = Feed::default();
+
+    for (i, line) in csv.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        if let Some(comment) = line.strip_prefix('#') {
+            for field in comment.split(',') {
+                match field.split_once(':') {
+                    Some(("model_version", value)) => feed.model_version = Some(value.to_string()),
+                    // the date is followed by the time of the run
+                    Some(("score_date", value)) => {
+                        feed.score_date = value.chars().take(10).collect()
+                    }
+                    _ => {}
+                }
+            }
+            continue;
+        }
+        if line.starts_with("cve,") {
+            continue;
+        }
+
+        let fields: Vec<&str> = line.split(',').collect();
+        if fields.len() != 3 {
+            return Err(format!(
+                "line {}: expected 3 fields, got {}",
+                i + 1,
+                fields.len()
+            ));
+        }
+        let parse_probability = |value: &str| -> Result<f64, String> {
+            value
+                .parse::<f64>()
+                .ok()
+                .filter(|value| (0.0..=1.0).contains(value))
+                .ok_or_else(|| format!("line {}: invalid probability '{}'", i + 1, value))
+        };
+        feed.scores.push(Score {
+            cve: fields[0].to_string(),
+            epss: parse_probability(fields[1])?,
+            percentile: parse_probability(fields[2])?,
+        });
+    }
+
+    Ok(feed)
+}
+
+/// EPSS scores of the CVEs among a set of records, to include them in the responses.
+#[derive(Debug, Default)]
+pub struct Scores(HashMap<String, models::EpssScore>);
+
+impl Scores {
+    pub fn with(entries: Vec<models::EpssScore>) -> Self {
+        Self(
+            entries
+                .into_iter()
+                .map(|entry| (entry.cve.clone(), entry))
+                .collect(),
+        )
+    }
+
+    /// Looks up the scores of the CVEs of the records.
+    pub fn load(db: &Database, cves: &[models::CVE]) -> Result<Self, String> {
+        let mut ids: Vec<&str> = cves.iter().map(|cve| cve.cve.as_str()).collect();
+        ids.sort_unstable();
+        ids.dedup();
+        if ids.is_empty() {
+            return Ok(Self::default());
+        }
+        db.get_epss_scores(&ids).map(Self::with)
+    }
+
+    pub fn of(&self, cve: &str) -> Epss {
+        match self.0.get(cve) {
+            Some(entry) => Epss {
+                epss_score: Some(entry.score),
+                epss_percentile: Some(entry.percentile),
+            },
+            None => Epss::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::{parse, Scores};
+    use crate::db::models;
+
+    const FEED: &str = "#model_version:v2022.01.01,score_date:2022-07-05T00:00:00+0000
+cve,epss,percentile
+CVE-2021-44228,0.97565,0.99994
+CVE-2021-3517,0.00885,0.81732
+";
+
+    #[test]
+    fn can_parse_feed() {
+        let feed = parse(FEED).unwrap();
+        assert_eq!(Some("v2022.01.01"), feed.model_version.as_deref());
+        assert_eq!("2022-07-05", feed.score_date);
+        assert_eq!(2, feed.scores.len());
+        assert_eq!("CVE-2021-44228", feed.scores[0].cve);
+        assert_eq!(0.97565, feed.scores[0].epss);
+        assert_eq!(0.81732, feed.scores[1].percentile);
+    }
+
+    #[test]
+    fn can_reject_invalid_scores() {
+        assert!(parse("cve,epss,percentile\nCVE-2021-44228,1.5,0.99\n").is_err());
+        assert!(parse("cve,epss,percentile\nCVE-2021-44228,high,0.99\n").is_err());
+        assert!(parse("cve,epss,percentile\nCVE-2021-44228,0.97\n").is_err());
+    }
+
+    #[test]
+    fn can_lookup_scores() {
+        let feed = parse(FEED).unwrap();
+        let entries = feed
+            .scores
+            .into_iter()
+            .enumerate()
+            .map(|(i, score)| models::EpssScore {
+                id: i as i32,
+                created_at: Utc::now(),
+                cve: score.cve,
+                score: score.epss,
+                percentile: score.percentile,
+                score_date: feed.score_date.clone(),
+            })
+            .collect();
+        let scores = Scores::with(entries);
+
+        let log4shell = scores.of("CVE-2021-44228");
+        assert_eq!(Some(0.97565), log4shell.epss_score);
+        assert_eq!(Some(0.99994), log4shell.epss_percentile);
+        assert_eq!(None, scores.of("CVE-2022-0001").epss_score);
+    }
+}
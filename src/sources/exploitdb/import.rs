@@ -0,0 +1,51 @@
+use std::fs;
+use std::path::Path;
+
+use anyhow::{anyhow, Context, Result};
+use log::info;
+
+use crate::db::{self, Pool};
+use crate::utils::{download_to_file, ImportError};
+
+pub const FEED_URL: &str =
+    "https://gitlab.com/exploit-database/exploitdb/-/raw/main/files_exploits.csv";
+
+/// Imports the exploits of the CVEs, replacing the previous ones, and returns the number of
+/// exploits added.
+pub fn run(pool: &Pool, data_path: &Path, fresh: bool) -> Result<u32> {
+    let mut exploitdb_path = data_path.to_path_buf();
+    exploitdb_path.push("exploitdb");
+    fs::create_dir_all(&exploitdb_path)
+        .with_context(|| format!("could not create {}", exploitdb_path.display()))?;
+
+    let mut file_path = exploitdb_path;
+    file_path.push("files_exploits.csv");
+    if fresh || !file_path.exists() {
+        download_to_file(FEED_URL, &file_path)?;
+    }
+
+    let csv =
+        fs::read(&file_path).with_context(|| format!("could not read {}", file_path.display()))?;
+    let exploits = super::parse(&csv).map_err(|e| {
+        ImportError::from(format!("could not parse the exploits: {}", e)).quarantine(&file_path)
+    })?;
+    // an empty list is a broken download rather than all the exploits being removed
+    if exploits.is_empty() {
+        return Err(ImportError::from("no exploit of a CVE found".to_string())
+            .quarantine(&file_path)
+            .into());
+    }
+
+    info!("importing the {} exploits of CVEs ...", exploits.len());
+
+    let num_added = db::Database(pool.get()?)
+        .replace_exploits(
+            exploits
+                .iter()
+                .flat_map(db::models::NewExploit::with)
+                .collect(),
+        )
+        .map_err(|e| anyhow!(e))?;
+
+    Ok(num_added as u32)
+}
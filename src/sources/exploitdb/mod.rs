@@ -0,0 +1,148 @@
+//! Archive of the public exploits maintained by Exploit-DB, flagging the CVEs with an exploit
+//! available.
+
+use std::collections::HashMap;
+
+use serde::Deserialize;
+
+use crate::db::{models, Database};
+
+#[cfg(feature = "import")]
+pub mod import;
+
+pub use kepler_types::api::Exploits;
+
+pub const SOURCE_NAME: &str = "Exploit-DB";
+
+pub fn url(id: i32) -> String {
+    format!("https://www.exploit-db.com/exploits/{}", id)
+}
+
+/// Exploit as listed in `files_exploits.csv`, the other columns being ignored.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Exploit {
+    pub id: i32,
+    pub description: String,
+    /// Date formatted as `YYYY-MM-DD`.
+    pub date_published: String,
+    /// Identifiers of the vulnerabilities targeted, separated by `;`.
+    #[serde(default)]
+    pub codes: String,
+}
+
+impl Exploit {
+    /// Returns the CVEs targeted by the exploit, among its other identifiers.
+    pub fn cves(&self) -> Vec<String> {
+        let mut cves: Vec<String> = self
+            .codes
+            .split(';')
+            .map(|code| code.trim().to_uppercase())
+            .filter(|code| code.starts_with("CVE-"))
+            .collect();
+        cves.sort_unstable();
+        cves.dedup();
+        cves
+    }
+}
+
+/// Parses the list of exploits, skipping the ones without a CVE.
+pub fn parse(data: &[u8]) -> Result<Vec<Exploit>, String> {
+    let mut exploits = vec![];
+    for (i, record) in csv::Reader::from_reader(data).deserialize().enumerate() {
+        let exploit: Exploit = record.map_err(|e| format!("record {}: {}", i + 1, e))?;
+        if !exploit.cves().is_empty() {
+            exploits.push(exploit);
+        }
+    }
+    Ok(exploits)
+}
+
+/// Exploits of the CVEs among a set of records, to flag them in the responses.
+#[derive(Debug, Default)]
+pub struct Archive(HashMap<String, Vec<models::Exploit>>);
+
+impl Archive {
+    pub fn with(entries: Vec<models::Exploit>) -> Self {
+        let mut exploits: HashMap<String, Vec<models::Exploit>> = HashMap::new();
+        for entry in entries {
+            exploits.entry(entry.cve.clone()).or_default().push(entry);
+        }
+        Self(exploits)
+    }
+
+    /// Looks up the exploits of the CVEs of the records.
+    pub fn load(db: &Database, cves: &[models::CVE]) -> Result<Self, String> {
+        let mut ids: Vec<&str> = cves.iter().map(|cve| cve.cve.as_str()).collect();
+        ids.sort_unstable();
+        ids.dedup();
+        if ids.is_empty() {
+            return Ok(Self::default());
+        }
+        db.get_exploits(&ids).map(Self::with)
+    }
+
+    pub fn of(&self, cve: &str) -> Exploits {
+        match self.0.get(cve) {
+            Some(entries) => Exploits {
+                exploit_available: true,
+                exploits: entries.iter().map(models::Exploit::url).collect(),
+            },
+            None => Exploits::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::{parse, Archive};
+    use crate::db::models;
+
+    const EXPLOITS: &str = r#"id,file,description,date_published,author,type,platform,port,date_added,date_updated,verified,codes,tags,aliases,screenshot_url,application_url,source_url
+50592,exploits/java/remote/50592.py,"Apache Log4j 2 - Remote Code Execution (RCE)",2021-12-14,kozmer,remote,java,,2021-12-14,2021-12-14,0,CVE-2021-44228;CVE-2021-45046,,,,,
+50593,exploits/linux/local/50593.txt,"Some tool, with a comma - Denial of Service",2021-12-15,someone,dos,linux,,2021-12-15,2021-12-15,0,OSVDB-12345,,,,,
+"#;
+
+    #[test]
+    fn can_parse_exploits() {
+        let exploits = parse(EXPLOITS.as_bytes()).unwrap();
+        assert_eq!(1, exploits.len());
+        assert_eq!(50592, exploits[0].id);
+        assert_eq!("2021-12-14", exploits[0].date_published);
+        assert_eq!(
+            vec!["CVE-2021-44228".to_string(), "CVE-2021-45046".to_string()],
+            exploits[0].cves()
+        );
+    }
+
+    #[test]
+    fn can_flag_cves_with_exploits() {
+        let exploits = parse(EXPLOITS.as_bytes()).unwrap();
+        let entries = exploits
+            .iter()
+            .flat_map(models::NewExploit::with)
+            .enumerate()
+            .map(|(i, new)| models::Exploit {
+                id: i as i32,
+                created_at: Utc::now(),
+                edb_id: new.edb_id,
+                cve: new.cve,
+                description: new.description,
+                published: new.published,
+            })
+            .collect();
+        let archive = Archive::with(entries);
+
+        let log4shell = archive.of("CVE-2021-44228");
+        assert!(log4shell.exploit_available);
+        assert_eq!(
+            vec!["https://www.exploit-db.com/exploits/50592".to_string()],
+            log4shell.exploits
+        );
+
+        let other = archive.of("CVE-2021-3517");
+        assert!(!other.exploit_available);
+        assert!(other.exploits.is_empty());
+    }
+}
@@ -0,0 +1,192 @@
+//! Parser of the GLSA XML documents.
+
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+
+use super::{Advisory, Package, Range};
+
+/// Element whose text is being read.
+enum Text {
+    None,
+    Title,
+    Synopsis,
+    Announced,
+    Access,
+    Bug,
+    Vulnerable(String),
+    Unaffected(String),
+    Uri(Option<String>),
+}
+
+fn attribute(element: &BytesStart, name: &[u8]) -> Option<String> {
+    element
+        .attributes()
+        .flatten()
+        .find(|attribute| attribute.key == name)
+        .and_then(|attribute| {
+            attribute
+                .unescaped_value()
+                .ok()
+                .map(|value| String::from_utf8_lossy(&value).into_owned())
+        })
+}
+
+/// Id of the CVE of a reference, given by its text or by its link, like
+/// `https://nvd.nist.gov/vuln/detail/CVE-2023-5678`.
+fn cve_id(text: &str, link: Option<&str>) -> Option<String> {
+    if let Some(id) = text.split_whitespace().next() {
+        if id.starts_with("CVE-") {
+            return Some(id.to_string());
+        }
+    }
+    link?
+        .rsplit(|c| c == '/' || c == '=')
+        .next()
+        .filter(|id| id.starts_with("CVE-"))
+        .map(str::to_string)
+}
+
+/// Parses a GLSA document, like `glsa-202401-01.xml`.
+pub fn parse(xml: &str) -> Result<Advisory, String> {
+    let mut reader = Reader::from_str(xml);
+    reader.trim_text(true);
+
+    let mut advisory = Advisory::default();
+    let mut package: Option<Package> = None;
+    let mut text = Text::None;
+
+    let mut buf = vec![];
+    loop {
+        let event = reader
+            .read_event(&mut buf)
+            .map_err(|e| format!("could not parse at {}: {}", reader.buffer_position(), e))?;
+
+        match &event {
+            Event::Start(element) => match element.local_name() {
+                b"glsa" => advisory.id = attribute(element, b"id").unwrap_or_default(),
+                b"title" => text = Text::Title,
+                b"synopsis" => text = Text::Synopsis,
+                b"announced" => text = Text::Announced,
+                b"access" => text = Text::Access,
+                b"bug" => text = Text::Bug,
+                b"impact" => advisory.impact = attribute(element, b"type"),
+                b"package" => {
+                    package = Some(Package {
+                        name: attribute(element, b"name").unwrap_or_default(),
+                        ..Default::default()
+                    })
+                }
+                b"vulnerable" => {
+                    text = Text::Vulnerable(attribute(element, b"range").unwrap_or_default())
+                }
+                b"unaffected" => {
+                    text = Text::Unaffected(attribute(element, b"range").unwrap_or_default())
+                }
+                b"uri" => text = Text::Uri(attribute(element, b"link")),
+                _ => {}
+            },
+            Event::Text(value) => {
+                let value = value
+                    .unescape_and_decode(&reader)
+                    .map_err(|e| format!("could not decode: {}", e))?;
+                match &text {
+                    Text::Title => advisory.title = value,
+                    Text::Synopsis => advisory.synopsis = value,
+                    Text::Announced => advisory.announced = value,
+                    Text::Access => advisory.access = Some(value),
+                    Text::Bug => advisory.bugs.push(value),
+                    Text::Vulnerable(range) | Text::Unaffected(range) => {
+                        if let Some(package) = &mut package {
+                            let range = Range {
+                                range: range.clone(),
+                                version: value,
+                            };
+                            if matches!(text, Text::Vulnerable(_)) {
+                                package.vulnerable.push(range);
+                            } else {
+                                package.unaffected.push(range);
+                            }
+                        }
+                    }
+                    Text::Uri(link) => {
+                        if let Some(id) = cve_id(&value, link.as_deref()) {
+                            if !advisory.cves.contains(&id) {
+                                advisory.cves.push(id);
+                            }
+                        }
+                    }
+                    Text::None => {}
+                }
+            }
+            Event::End(element) => {
+                text = Text::None;
+                if element.local_name() == b"package" {
+                    advisory.packages.extend(package.take());
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+
+        buf.clear();
+    }
+
+    if advisory.id.is_empty() {
+        return Err("missing the GLSA id".into());
+    }
+    Ok(advisory)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse;
+
+    const GLSA: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE glsa SYSTEM "http://www.gentoo.org/dtd/glsa.dtd">
+<glsa id="202401-01">
+  <title>OpenSSL: Multiple Vulnerabilities</title>
+  <synopsis>Multiple vulnerabilities have been discovered in OpenSSL, the worst of which could lead to a denial of service.</synopsis>
+  <product type="ebuild">openssl</product>
+  <announced>2024-01-05</announced>
+  <revised count="1">2024-01-05</revised>
+  <bug>916359</bug>
+  <access>remote</access>
+  <affected>
+    <package name="dev-libs/openssl" auto="yes" arch="*">
+      <unaffected range="ge">3.0.12</unaffected>
+      <unaffected range="rge">1.1.1w</unaffected>
+      <vulnerable range="lt">3.0.12</vulnerable>
+    </package>
+  </affected>
+  <impact type="normal">
+    <p>Please review the referenced CVE identifiers for details.</p>
+  </impact>
+  <references>
+    <uri link="https://nvd.nist.gov/vuln/detail/CVE-2023-5678">CVE-2023-5678</uri>
+    <uri link="https://nvd.nist.gov/vuln/detail/CVE-2023-6129">CVE-2023-6129 &amp; more</uri>
+    <uri link="https://www.openssl.org/news/secadv/20231106.txt">OpenSSL advisory</uri>
+  </references>
+</glsa>"#;
+
+    #[test]
+    fn can_parse_advisories() {
+        let advisory = parse(GLSA).unwrap();
+
+        assert_eq!("202401-01", advisory.id);
+        assert_eq!("OpenSSL: Multiple Vulnerabilities", advisory.title);
+        assert_eq!("2024-01-05", advisory.announced);
+        assert_eq!("MEDIUM", advisory.severity());
+        assert_eq!(Some("NETWORK".to_string()), advisory.vector());
+        assert_eq!(vec!["916359"], advisory.bugs);
+        assert_eq!(vec!["CVE-2023-5678", "CVE-2023-6129"], advisory.cves);
+
+        assert_eq!(1, advisory.packages.len());
+        let package = &advisory.packages[0];
+        assert_eq!("dev-libs/openssl", package.name);
+        assert_eq!(1, package.vulnerable.len());
+        assert_eq!(2, package.unaffected.len());
+        assert_eq!("rge", package.unaffected[1].range);
+        assert!(package.is_vulnerable("3.0.11"));
+        assert!(!package.is_vulnerable("1.1.1w-r1"));
+    }
+}
@@ -0,0 +1,133 @@
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::Path;
+
+use anyhow::{anyhow, Context, Result};
+use log::{info, warn};
+
+use super::{glsa, Advisory, SOURCE_NAME, VENDOR};
+
+use crate::db::{self, Pool};
+use crate::utils::{download_to_file, ImportError};
+
+pub const FEED_URL: &str = "https://github.com/gentoo/glsa/archive/refs/heads/master.zip";
+
+/// Reads the advisories of the archive of the GLSA repository.
+fn read_archive(file_path: &Path) -> Result<Vec<Advisory>> {
+    let file =
+        File::open(file_path).with_context(|| format!("could not open {}", file_path.display()))?;
+    let mut archive = zip::ZipArchive::new(file).map_err(|e| {
+        ImportError::from(format!("could not read {}: {}", file_path.display(), e))
+            .quarantine(file_path)
+    })?;
+
+    let mut advisories = vec![];
+    for index in 0..archive.len() {
+        let mut entry = archive.by_index(index)?;
+        let name = entry.name().rsplit('/').next().unwrap_or_default();
+        if !name.starts_with("glsa-") || !name.ends_with(".xml") {
+            continue;
+        }
+
+        let mut xml = String::new();
+        entry
+            .read_to_string(&mut xml)
+            .with_context(|| format!("could not read {}", entry.name()))?;
+        match glsa::parse(&xml) {
+            Ok(advisory) => advisories.push(advisory),
+            Err(e) => warn!("skipping {}: {}", entry.name(), e),
+        }
+    }
+
+    // the latest advisory of a CVE fixed several times is the one stored
+    advisories.sort_by(|a, b| a.id.cmp(&b.id));
+    Ok(advisories)
+}
+
+fn store(database: &db::Database, advisory: &Advisory) -> Result<u32> {
+    let products = advisory.products();
+    if products.is_empty() || advisory.cves.is_empty() {
+        return Ok(0);
+    }
+
+    let object_id = database
+        .upsert_object(db::models::NewObject::with(
+            Advisory::object_key(&advisory.id),
+            serde_json::to_string(advisory)?,
+        ))
+        .map_err(|e| anyhow!(e))?;
+
+    let mut refs = db::models::References::default();
+    refs.push(db::models::Reference {
+        url: format!("https://security.gentoo.org/glsa/{}", advisory.id),
+        tags: vec!["Vendor Advisory".into()],
+    });
+    for bug in &advisory.bugs {
+        refs.push(db::models::Reference {
+            url: format!("https://bugs.gentoo.org/{}", bug),
+            tags: vec!["Issue Tracking".into()],
+        });
+    }
+
+    let mut num_imported = 0;
+    for cve in &advisory.cves {
+        for product in &products {
+            let new_cve = db::models::NewCVE::with(
+                SOURCE_NAME.into(),
+                VENDOR.into(),
+                product.clone(),
+                cve.clone(),
+                advisory.summary(),
+                0.0,
+                advisory.severity(),
+                advisory.vector(),
+                refs.clone(),
+                Some(object_id),
+            );
+            if database.upsert_cve(new_cve).map_err(|e| anyhow!(e))? {
+                num_imported += 1;
+            }
+        }
+    }
+
+    Ok(num_imported)
+}
+
+/// Imports the CVEs of the Gentoo packages from the archive of the GLSA repository,
+/// downloaded again with `fresh`, creating the new records and updating the changed ones.
+pub fn run(pool: &Pool, data_path: &Path, fresh: bool) -> Result<u32> {
+    let mut gentoo_path = data_path.to_path_buf();
+    gentoo_path.push("gentoo");
+    fs::create_dir_all(&gentoo_path)
+        .with_context(|| format!("could not create {}", gentoo_path.display()))?;
+
+    let mut file_path = gentoo_path;
+    file_path.push("glsa.zip");
+    if fresh || !file_path.exists() {
+        download_to_file(FEED_URL, &file_path)?;
+    }
+
+    let advisories = read_archive(&file_path)?;
+    // an empty archive is a broken feed rather than no advisories ever published
+    if advisories.is_empty() {
+        return Err(
+            ImportError::from("the GLSA archive has no advisories".to_string())
+                .quarantine(&file_path)
+                .into(),
+        );
+    }
+
+    info!("importing {} Gentoo advisories ...", advisories.len());
+
+    let database = db::Database(pool.get()?);
+    let mut num_imported = 0;
+    for (index, advisory) in advisories.iter().enumerate() {
+        num_imported += store(&database, advisory)?;
+
+        if index > 0 && index % 1000 == 0 {
+            info!("processed {} advisories ...", index);
+        }
+    }
+
+    Ok(num_imported)
+}
@@ -0,0 +1,219 @@
+//! CVEs of the Gentoo packages fixed by the Gentoo Linux Security Advisories (GLSA), with the
+//! vulnerable and unaffected version ranges of every package atom.
+
+use std::cmp::Ordering;
+
+use serde::{Deserialize, Serialize};
+
+use super::alpine::apk;
+use crate::scan::ecosystem_product;
+use crate::search::Query;
+
+#[cfg(feature = "import")]
+pub mod glsa;
+#[cfg(feature = "import")]
+pub mod import;
+
+pub const SOURCE_NAME: &str = "Gentoo";
+/// No vendors for the packages, the category is part of the package atom.
+pub const VENDOR: &str = "@gentoo";
+
+/// Product of a package atom, like `gentoo-dev-libs/openssl`.
+pub fn product(atom: &str) -> String {
+    ecosystem_product("gentoo", atom)
+}
+
+/// Parses a version like `3.0.12-r1`, the versions without a revision being the `r0` one.
+/// The Gentoo versions are compared like the Alpine ones, `apk` following the same rules.
+fn version(version: &str) -> apk::Version {
+    let mut version = apk::Version::parse(version);
+    version.release.get_or_insert(0);
+    version
+}
+
+/// Versions of a package given by an operator and a version, like `lt 3.0.12`.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Range {
+    /// `lt`, `le`, `eq`, `ge` or `gt`, or `rlt`, `rle`, `rge` or `rgt` for the revisions of
+    /// the version only.
+    pub range: String,
+    pub version: String,
+}
+
+impl Range {
+    pub fn contains(&self, other: &str) -> bool {
+        // like `eq 1.2*`, any version starting with it
+        if let Some(prefix) = self.version.strip_suffix('*') {
+            return self.range == "eq" && other.starts_with(prefix);
+        }
+
+        let (other, this) = (version(other), version(&self.version));
+        let ordering = match self.range.strip_prefix('r') {
+            Some(_) if apk::compare(other.version, this.version) != Ordering::Equal => {
+                return false
+            }
+            _ => other.compare(&this),
+        };
+
+        match self.range.trim_start_matches('r') {
+            "lt" => ordering == Ordering::Less,
+            "le" => ordering != Ordering::Greater,
+            "eq" => ordering == Ordering::Equal,
+            "ge" => ordering != Ordering::Less,
+            "gt" => ordering == Ordering::Greater,
+            _ => false,
+        }
+    }
+}
+
+/// Package atom affected by an advisory.
+#[derive(Debug, Default, Clone, Deserialize, Serialize)]
+pub struct Package {
+    /// Like `dev-libs/openssl`.
+    pub name: String,
+    #[serde(default)]
+    pub vulnerable: Vec<Range>,
+    #[serde(default)]
+    pub unaffected: Vec<Range>,
+}
+
+impl Package {
+    /// Whether the version is in a vulnerable range and in none of the unaffected ones.
+    pub fn is_vulnerable(&self, version: &str) -> bool {
+        self.vulnerable.iter().any(|range| range.contains(version))
+            && !self.unaffected.iter().any(|range| range.contains(version))
+    }
+}
+
+#[derive(Debug, Default, Clone, Deserialize, Serialize)]
+pub struct Advisory {
+    /// Like `202401-01`.
+    pub id: String,
+    #[serde(default)]
+    pub title: String,
+    #[serde(default)]
+    pub synopsis: String,
+    #[serde(default)]
+    pub announced: String,
+    /// `high`, `normal` or `low`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub impact: Option<String>,
+    /// `remote` or `local`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub access: Option<String>,
+    #[serde(default)]
+    pub bugs: Vec<String>,
+    #[serde(default)]
+    pub cves: Vec<String>,
+    #[serde(default)]
+    pub packages: Vec<Package>,
+}
+
+impl Advisory {
+    pub fn object_key(id: &str) -> String {
+        format!("GLSA-{} (gentoo)", id)
+    }
+
+    pub fn summary(&self) -> String {
+        if self.synopsis.trim().is_empty() {
+            return self.title.trim().to_string();
+        }
+        self.synopsis.trim().to_string()
+    }
+
+    /// Impact rated by Gentoo, named like the NIST severities.
+    pub fn severity(&self) -> String {
+        match self.impact.as_deref() {
+            Some("high") => "HIGH",
+            Some("normal") => "MEDIUM",
+            Some("low") => "LOW",
+            _ => "NONE",
+        }
+        .to_string()
+    }
+
+    pub fn vector(&self) -> Option<String> {
+        match self.access.as_deref() {
+            Some("remote") => Some("NETWORK".to_string()),
+            Some("local") => Some("LOCAL".to_string()),
+            _ => None,
+        }
+    }
+
+    /// Unique products of the packages affected.
+    pub fn products(&self) -> Vec<String> {
+        let mut products = vec![];
+        for package in &self.packages {
+            let product = product(&package.name);
+            if !products.contains(&product) {
+                products.push(product);
+            }
+        }
+        products
+    }
+
+    pub fn is_match(&self, query: &Query) -> bool {
+        // we need a version
+        let version = match &query.version {
+            Some(version) => version,
+            None => return false,
+        };
+
+        self.packages.iter().any(|package| {
+            product(&package.name) == query.product && package.is_vulnerable(version)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Advisory, Package, Range};
+    use crate::search::Query;
+
+    fn range(range: &str, version: &str) -> Range {
+        Range {
+            range: range.into(),
+            version: version.into(),
+        }
+    }
+
+    #[test]
+    fn can_match_ranges() {
+        assert!(range("lt", "3.0.12").contains("3.0.11-r2"));
+        assert!(!range("lt", "3.0.12").contains("3.0.12"));
+        assert!(range("lt", "3.0.12-r1").contains("3.0.12"));
+        assert!(range("ge", "3.0.12").contains("3.0.12-r1"));
+        assert!(range("eq", "1.2*").contains("1.2.5"));
+        // only the revisions of the same version
+        assert!(range("rge", "1.1.1w").contains("1.1.1w-r1"));
+        assert!(!range("rge", "1.1.1w").contains("1.1.1x"));
+        assert!(range("rlt", "1.1.1w-r2").contains("1.1.1w-r1"));
+    }
+
+    #[test]
+    fn can_match_vulnerable_versions() {
+        let advisory = Advisory {
+            id: "202401-01".into(),
+            impact: Some("high".into()),
+            packages: vec![Package {
+                name: "dev-libs/openssl".into(),
+                vulnerable: vec![range("lt", "3.0.12")],
+                unaffected: vec![range("ge", "3.0.12"), range("rge", "1.1.1w")],
+            }],
+            ..Default::default()
+        };
+        let query = |version: &str| Query {
+            vendor: None,
+            product: "gentoo-dev-libs/openssl".into(),
+            version: Some(version.into()),
+            ..Default::default()
+        };
+
+        assert_eq!("HIGH", advisory.severity());
+        assert_eq!(vec!["gentoo-dev-libs/openssl"], advisory.products());
+        assert!(advisory.is_match(&query("3.0.11")));
+        assert!(advisory.is_match(&query("1.1.1v")));
+        assert!(!advisory.is_match(&query("1.1.1w-r1")));
+        assert!(!advisory.is_match(&query("3.0.12")));
+    }
+}
@@ -0,0 +1,226 @@
+use std::env;
+use std::time::Duration;
+
+use anyhow::{anyhow, bail, Context, Result};
+use chrono::{SecondsFormat, Utc};
+use log::info;
+use serde::Deserialize;
+use serde_json::json;
+
+use super::{Advisory, SOURCE_NAME, VENDOR};
+
+use crate::db::{self, Pool};
+use crate::utils::{parse_json, send_with_retries};
+
+pub const API_URL: &str = "https://api.github.com/graphql";
+
+const QUERY: &str = r#"
+query($after: String, $updatedSince: DateTime) {
+  securityAdvisories(first: 100, after: $after, updatedSince: $updatedSince, orderBy: {field: UPDATED_AT, direction: ASC}) {
+    pageInfo { hasNextPage endCursor }
+    nodes {
+      ghsaId summary description severity updatedAt withdrawnAt
+      identifiers { type value }
+      references { url }
+      cvss { score vectorString }
+      vulnerabilities(first: 100) {
+        nodes {
+          package { ecosystem name }
+          vulnerableVersionRange
+          firstPatchedVersion { identifier }
+        }
+      }
+    }
+  }
+}
+"#;
+
+#[derive(Debug, Deserialize)]
+struct Error {
+    message: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PageInfo {
+    has_next_page: bool,
+    end_cursor: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Advisories {
+    page_info: PageInfo,
+    nodes: Vec<Advisory>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Data {
+    security_advisories: Advisories,
+}
+
+#[derive(Debug, Deserialize)]
+struct Response {
+    data: Option<Data>,
+    #[serde(default)]
+    errors: Vec<Error>,
+}
+
+/// Client of the GitHub GraphQL API, authenticated with the token set in `GITHUB_TOKEN`.
+struct Client {
+    http: reqwest::blocking::Client,
+    token: String,
+}
+
+impl Client {
+    fn from_env() -> Result<Self> {
+        let token = env::var("GITHUB_TOKEN")
+            .ok()
+            .filter(|token| !token.is_empty())
+            .context("GITHUB_TOKEN is not set, the GitHub API requires a token")?;
+
+        Ok(Self {
+            http: reqwest::blocking::Client::builder()
+                .timeout(Some(Duration::from_secs(300)))
+                .user_agent(format!("kepler/{}", crate::version()))
+                .build()?,
+            token,
+        })
+    }
+
+    fn page(&self, after: Option<&str>, updated_since: Option<&str>) -> Result<Advisories> {
+        let body = json!({
+            "query": QUERY,
+            "variables": {
+                "after": after,
+                "updatedSince": updated_since,
+            },
+        });
+
+        let res = send_with_retries(API_URL, || {
+            self.http.post(API_URL).bearer_auth(&self.token).json(&body)
+        })?;
+        let res: Response = parse_json(API_URL, res)?;
+        if let Some(error) = res.errors.first() {
+            bail!("error querying {}: {}", API_URL, error.message);
+        }
+        res.data
+            .map(|data| data.security_advisories)
+            .ok_or_else(|| anyhow!("no data in the response of {}", API_URL))
+    }
+}
+
+fn store(database: &db::Database, advisory: &Advisory) -> Result<u32> {
+    let mut num_imported = 0;
+
+    if let Some(cve) = advisory.cve_id() {
+        database
+            .replace_advisory_aliases(&advisory.ghsa_id, &[cve])
+            .map_err(|e| anyhow!(e))?;
+    }
+
+    let products = advisory.products();
+    if products.is_empty() {
+        return Ok(0);
+    }
+
+    let object_id = database
+        .upsert_object(db::models::NewObject::with(
+            Advisory::object_key(&advisory.ghsa_id),
+            serde_json::to_string(advisory)?,
+        ))
+        .map_err(|e| anyhow!(e))?;
+
+    let mut refs = db::models::References::default();
+    refs.push(db::models::Reference {
+        url: format!("https://github.com/advisories/{}", advisory.ghsa_id),
+        tags: vec!["GHSA".into()],
+    });
+    for reference in &advisory.references {
+        refs.push(db::models::Reference {
+            url: reference.url.clone(),
+            tags: vec![],
+        })
+    }
+
+    for product in products {
+        let new_cve = db::models::NewCVE::with(
+            SOURCE_NAME.into(),
+            VENDOR.into(),
+            product,
+            advisory.cve().into(),
+            advisory.summary.clone(),
+            advisory.cvss.score,
+            advisory.severity(),
+            advisory.vector(),
+            refs.clone(),
+            Some(object_id),
+        );
+        if database.upsert_cve(new_cve).map_err(|e| anyhow!(e))? {
+            num_imported += 1;
+        }
+    }
+
+    Ok(num_imported)
+}
+
+/// Imports the advisories of the supported ecosystems updated since the last import, or
+/// all of them the first time and with `fresh`, creating the new records and updating the
+/// changed ones.
+pub fn run(pool: &Pool, fresh: bool) -> Result<u32> {
+    let client = Client::from_env()?;
+    let database = db::Database(pool.get()?);
+
+    let since = if fresh {
+        None
+    } else {
+        database
+            .get_synced_at(SOURCE_NAME)
+            .map_err(|e| anyhow!(e))?
+            .map(|since| since.to_rfc3339_opts(SecondsFormat::Secs, true))
+    };
+    let until = Utc::now();
+    match &since {
+        Some(since) => info!("importing the advisories updated since {}", since),
+        None => info!("importing all the advisories"),
+    }
+
+    let mut num_imported = 0;
+    let mut withdrawn = vec![];
+
+    let mut after: Option<String> = None;
+    let mut num_advisories = 0;
+    loop {
+        let page = client.page(after.as_deref(), since.as_deref())?;
+
+        for advisory in &page.nodes {
+            if advisory.withdrawn_at.is_some() {
+                withdrawn.push(advisory.cve().to_string());
+            } else {
+                num_imported += store(&database, advisory)?;
+            }
+        }
+        num_advisories += page.nodes.len();
+        info!("processed {} advisories ...", num_advisories);
+
+        match page.page_info.end_cursor {
+            Some(cursor) if page.page_info.has_next_page => after = Some(cursor),
+            _ => break,
+        }
+    }
+
+    let withdrawn: Vec<&str> = withdrawn.iter().map(String::as_str).collect();
+    let num_deleted = database
+        .delete_source_cves(SOURCE_NAME, &withdrawn)
+        .map_err(|e| anyhow!(e))?;
+    if num_deleted > 0 {
+        info!("deleted {} records of withdrawn advisories", num_deleted);
+    }
+
+    database
+        .set_synced_at(db::models::NewSyncState::with(SOURCE_NAME.into(), until))
+        .map_err(|e| anyhow!(e))?;
+
+    Ok(num_imported)
+}
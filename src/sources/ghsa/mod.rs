@@ -0,0 +1,281 @@
+//! Security advisories of the GitHub Advisory Database, cross-referenced to their CVE.
+
+use serde::{Deserialize, Serialize};
+use version_compare::Cmp;
+
+use super::osv::{self, cvss};
+use crate::search::Query;
+
+#[cfg(feature = "import")]
+pub mod import;
+
+pub const SOURCE_NAME: &str = "GHSA";
+/// No vendors for the packages, the ecosystem is part of the product name.
+pub const VENDOR: &str = "@ghsa";
+
+/// GitHub ecosystems imported, with their OSV name.
+pub const ECOSYSTEMS: &[(&str, &str)] = &[
+    ("PIP", "PyPI"),
+    ("NPM", "npm"),
+    ("RUST", "crates.io"),
+    ("GO", "Go"),
+];
+
+#[derive(Debug, Default, Clone, Deserialize, Serialize)]
+pub struct Package {
+    pub ecosystem: String,
+    pub name: String,
+}
+
+impl Package {
+    /// The same package in the OSV ecosystems, whose products are named like the scans name
+    /// the packages.
+    fn osv(&self) -> Option<osv::Package> {
+        let (_, ecosystem) = ECOSYSTEMS
+            .iter()
+            .find(|(ecosystem, _)| *ecosystem == self.ecosystem)?;
+        Some(osv::Package {
+            ecosystem: ecosystem.to_string(),
+            name: self.name.clone(),
+        })
+    }
+
+    pub fn product(&self) -> Option<String> {
+        self.osv()?.product()
+    }
+}
+
+#[derive(Debug, Default, Clone, Deserialize, Serialize)]
+pub struct Version {
+    pub identifier: String,
+}
+
+#[derive(Debug, Default, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Vulnerability {
+    pub package: Package,
+    /// Constraints separated by commas, like `>= 2.0.0, < 2.1.4`.
+    pub vulnerable_version_range: String,
+    pub first_patched_version: Option<Version>,
+}
+
+impl Vulnerability {
+    pub fn is_match(&self, version: &str) -> bool {
+        let package = match self.package.osv() {
+            Some(package) => package,
+            None => return false,
+        };
+        let version = package.normalize_version(version);
+
+        self.vulnerable_version_range
+            .split(',')
+            .map(str::trim)
+            .filter(|constraint| !constraint.is_empty())
+            .all(|constraint| {
+                let split = constraint
+                    .find(|c: char| !"<>=! ".contains(c))
+                    .unwrap_or(constraint.len());
+                let (operator, bound) = constraint.split_at(split);
+                let operator = match operator.trim() {
+                    "=" | "" => "==",
+                    operator => operator,
+                };
+                match Cmp::from_sign(operator) {
+                    Ok(operator) => {
+                        package.version_cmp(&version, &package.normalize_version(bound), operator)
+                    }
+                    Err(_) => false,
+                }
+            })
+    }
+}
+
+#[derive(Debug, Default, Clone, Deserialize, Serialize)]
+pub struct Identifier {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub value: String,
+}
+
+#[derive(Debug, Default, Clone, Deserialize, Serialize)]
+pub struct Reference {
+    pub url: String,
+}
+
+#[derive(Debug, Default, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Cvss {
+    pub score: f64,
+    pub vector_string: Option<String>,
+}
+
+#[derive(Debug, Default, Clone, Deserialize, Serialize)]
+pub struct Vulnerabilities {
+    pub nodes: Vec<Vulnerability>,
+}
+
+#[derive(Debug, Default, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Advisory {
+    pub ghsa_id: String,
+    pub summary: String,
+    #[serde(default)]
+    pub description: String,
+    /// `LOW`, `MODERATE`, `HIGH` or `CRITICAL`.
+    pub severity: String,
+    pub updated_at: String,
+    pub withdrawn_at: Option<String>,
+    #[serde(default)]
+    pub identifiers: Vec<Identifier>,
+    #[serde(default)]
+    pub references: Vec<Reference>,
+    #[serde(default)]
+    pub cvss: Cvss,
+    #[serde(default)]
+    pub vulnerabilities: Vulnerabilities,
+}
+
+impl Advisory {
+    pub fn object_key(id: &str) -> String {
+        format!("{} (ghsa)", id)
+    }
+
+    /// The CVE of the advisory, if it has one.
+    pub fn cve_id(&self) -> Option<&str> {
+        self.identifiers
+            .iter()
+            .find(|identifier| identifier.kind == "CVE")
+            .map(|identifier| identifier.value.as_str())
+    }
+
+    /// The CVE of the advisory if it has one, otherwise its GHSA id.
+    pub fn cve(&self) -> &str {
+        self.cve_id().unwrap_or(&self.ghsa_id)
+    }
+
+    pub fn severity(&self) -> String {
+        match self.severity.as_str() {
+            "MODERATE" => "MEDIUM".to_string(),
+            severity => severity.to_string(),
+        }
+    }
+
+    pub fn vector(&self) -> Option<String> {
+        self.cvss
+            .vector_string
+            .as_deref()
+            .and_then(cvss::attack_vector)
+            .map(str::to_string)
+    }
+
+    /// Unique products of the vulnerable packages of the imported ecosystems.
+    pub fn products(&self) -> Vec<String> {
+        let mut products = vec![];
+        for product in self
+            .vulnerabilities
+            .nodes
+            .iter()
+            .filter_map(|v| v.package.product())
+        {
+            if !products.contains(&product) {
+                products.push(product);
+            }
+        }
+        products
+    }
+
+    pub fn is_match(&self, query: &Query) -> bool {
+        // we need a version
+        let version = match &query.version {
+            Some(version) => version,
+            None => return false,
+        };
+
+        self.vulnerabilities
+            .nodes
+            .iter()
+            .filter(|v| v.package.product().as_deref() == Some(query.product.as_str()))
+            .any(|v| v.is_match(version))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Advisory;
+    use crate::search::Query;
+
+    const ADVISORY: &str = r#"{
+        "ghsaId": "GHSA-2gwj-7jmv-h26r",
+        "summary": "SQL Injection in Django",
+        "description": "An issue was discovered in Django.",
+        "severity": "CRITICAL",
+        "updatedAt": "2022-04-12T22:18:49Z",
+        "withdrawnAt": null,
+        "identifiers": [
+            { "type": "GHSA", "value": "GHSA-2gwj-7jmv-h26r" },
+            { "type": "CVE", "value": "CVE-2022-28346" }
+        ],
+        "references": [{ "url": "https://nvd.nist.gov/vuln/detail/CVE-2022-28346" }],
+        "cvss": {
+            "score": 9.8,
+            "vectorString": "CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H"
+        },
+        "vulnerabilities": {
+            "nodes": [
+                {
+                    "package": { "ecosystem": "PIP", "name": "Django" },
+                    "vulnerableVersionRange": ">= 3.0, < 3.2.13",
+                    "firstPatchedVersion": { "identifier": "3.2.13" }
+                },
+                {
+                    "package": { "ecosystem": "PIP", "name": "django" },
+                    "vulnerableVersionRange": "= 4.0.3",
+                    "firstPatchedVersion": { "identifier": "4.0.4" }
+                },
+                {
+                    "package": { "ecosystem": "MAVEN", "name": "org.example:django" },
+                    "vulnerableVersionRange": "<= 1.0",
+                    "firstPatchedVersion": null
+                }
+            ]
+        }
+    }"#;
+
+    fn query(version: &str) -> Query {
+        Query {
+            vendor: None,
+            product: "python-django".into(),
+            version: Some(version.into()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn can_describe_advisories() {
+        let advisory: Advisory = serde_json::from_str(ADVISORY).unwrap();
+        assert_eq!(Some("CVE-2022-28346"), advisory.cve_id());
+        assert_eq!("CVE-2022-28346", advisory.cve());
+        assert_eq!("CRITICAL", advisory.severity());
+        assert_eq!(Some("NETWORK".to_string()), advisory.vector());
+        assert_eq!(vec!["python-django"], advisory.products());
+
+        let mut advisory = advisory;
+        advisory.identifiers.truncate(1);
+        advisory.severity = "MODERATE".into();
+        assert_eq!("GHSA-2gwj-7jmv-h26r", advisory.cve());
+        assert_eq!("MEDIUM", advisory.severity());
+    }
+
+    #[test]
+    fn can_match_version_ranges() {
+        let advisory: Advisory = serde_json::from_str(ADVISORY).unwrap();
+        assert!(advisory.is_match(&query("3.2.12")));
+        assert!(advisory.is_match(&query("3.0")));
+        assert!(advisory.is_match(&query("4.0.3")));
+        assert!(!advisory.is_match(&query("3.2.13")));
+        assert!(advisory.is_match(&query("3.2.13rc1")));
+        assert!(!advisory.is_match(&query("3.0.dev1")));
+        assert!(!advisory.is_match(&query("2.2.27")));
+        assert!(!advisory.is_match(&query("4.0.4")));
+    }
+}
@@ -0,0 +1,165 @@
+use std::fs;
+use std::path::Path;
+
+use anyhow::{anyhow, bail, Context, Result};
+use log::info;
+use serde::Deserialize;
+
+use super::{Entry, SOURCE_NAME, VENDOR};
+
+use crate::db::{self, Pool};
+use crate::utils::{download_to_file, ImportError};
+
+pub const DATABASE_URL: &str = "https://vuln.go.dev";
+
+/// Entry of the index of the database, telling when a report last changed.
+#[derive(Debug, Deserialize)]
+struct Indexed {
+    id: String,
+    modified: String,
+}
+
+fn read_entry(file_path: &Path) -> Result<Entry> {
+    let json = fs::read_to_string(file_path)
+        .with_context(|| format!("could not read {}", file_path.display()))?;
+    serde_json::from_str(&json).map_err(|e| {
+        ImportError::from(format!("could not parse {}: {}", file_path.display(), e))
+            .quarantine(file_path)
+            .into()
+    })
+}
+
+/// Reads the reports listed by the index, downloading the ones changed since their last
+/// download, or all of them with `fresh`.
+fn read_entries(govulndb_path: &Path, fresh: bool) -> Result<Vec<Entry>> {
+    let mut index_path = govulndb_path.to_path_buf();
+    index_path.push("vulns.json");
+    download_to_file(&format!("{}/index/vulns.json", DATABASE_URL), &index_path)?;
+    let index: Vec<Indexed> = serde_json::from_str(
+        &fs::read_to_string(&index_path)
+            .with_context(|| format!("could not read {}", index_path.display()))?,
+    )
+    .map_err(|e| {
+        ImportError::from(format!("could not parse the index: {}", e)).quarantine(&index_path)
+    })?;
+
+    let mut entries_path = govulndb_path.to_path_buf();
+    entries_path.push("ID");
+    fs::create_dir_all(&entries_path)
+        .with_context(|| format!("could not create {}", entries_path.display()))?;
+
+    let mut entries = vec![];
+    let mut num_downloaded = 0;
+    for indexed in index {
+        let mut file_path = entries_path.clone();
+        file_path.push(format!("{}.json", indexed.id));
+
+        let known = if !fresh && file_path.exists() {
+            read_entry(&file_path).ok()
+        } else {
+            None
+        };
+        let entry = match known {
+            Some(entry) if entry.modified == indexed.modified => entry,
+            _ => {
+                download_to_file(
+                    &format!("{}/ID/{}.json", DATABASE_URL, indexed.id),
+                    &file_path,
+                )?;
+                num_downloaded += 1;
+                read_entry(&file_path)?
+            }
+        };
+        entries.push(entry);
+    }
+    info!("downloaded {} changed reports", num_downloaded);
+
+    Ok(entries)
+}
+
+/// Imports the reports of the Go vulnerability database, creating the new records and
+/// updating the changed ones.
+pub fn run(pool: &Pool, data_path: &Path, fresh: bool) -> Result<u32> {
+    let mut govulndb_path = data_path.to_path_buf();
+    govulndb_path.push("govulndb");
+    fs::create_dir_all(&govulndb_path)
+        .with_context(|| format!("could not create {}", govulndb_path.display()))?;
+
+    let entries = read_entries(&govulndb_path, fresh)?;
+    info!("importing {} reports ...", entries.len());
+
+    let database = db::Database(pool.get()?);
+    let mut num_imported = 0;
+    let mut withdrawn = vec![];
+
+    for entry in entries {
+        if entry.withdrawn.is_some() {
+            withdrawn.push(entry.cve().to_string());
+            continue;
+        }
+
+        let products = entry.products();
+        if products.is_empty() {
+            continue;
+        }
+
+        let object_id = match database.upsert_object(db::models::NewObject::with(
+            Entry::object_key(&entry.id),
+            serde_json::to_string(&entry)?,
+        )) {
+            Err(e) => bail!(e),
+            Ok(id) => id,
+        };
+
+        let mut refs = db::models::References::default();
+        if let Some(specific) = &entry.database_specific {
+            if !specific.url.is_empty() {
+                refs.push(db::models::Reference {
+                    url: specific.url.clone(),
+                    tags: vec!["REPORT".into()],
+                });
+            }
+        }
+        for reference in &entry.references {
+            refs.push(db::models::Reference {
+                url: reference.url.clone(),
+                tags: vec![reference.kind.clone()],
+            })
+        }
+
+        for product in products {
+            // the reports don't rate the vulnerabilities
+            let new_cve = db::models::NewCVE::with(
+                SOURCE_NAME.into(),
+                VENDOR.into(),
+                product,
+                entry.cve().into(),
+                entry.summary().into(),
+                0.0,
+                String::new(),
+                None,
+                refs.clone(),
+                Some(object_id),
+            );
+            match database.upsert_cve(new_cve) {
+                Err(e) => bail!(e),
+                Ok(true) => num_imported += 1,
+                Ok(false) => {}
+            }
+
+            if num_imported > 0 && num_imported % 100 == 0 {
+                info!("imported {} records ...", num_imported);
+            }
+        }
+    }
+
+    let withdrawn: Vec<&str> = withdrawn.iter().map(String::as_str).collect();
+    let num_deleted = database
+        .delete_source_cves(SOURCE_NAME, &withdrawn)
+        .map_err(|e| anyhow!(e))?;
+    if num_deleted > 0 {
+        info!("deleted {} records of withdrawn reports", num_deleted);
+    }
+
+    Ok(num_imported)
+}
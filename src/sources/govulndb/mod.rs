@@ -0,0 +1,210 @@
+//! Vulnerabilities of the Go modules published by the
+//! [Go vulnerability database](https://vuln.go.dev), in the OSV schema along with the packages
+//! and symbols affected.
+
+use serde::{Deserialize, Serialize};
+
+use crate::search::Query;
+use crate::sources::osv;
+
+#[cfg(feature = "import")]
+pub mod import;
+
+pub const SOURCE_NAME: &str = "GoVulnDB";
+/// No vendors for the modules, they're identified by their path.
+pub const VENDOR: &str = "@govulndb";
+
+/// Package of a module affected, with the symbols calling the vulnerable code.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Import {
+    /// Like `golang.org/x/net/http2`, or `net/http` for the standard library.
+    pub path: String,
+    /// Operating systems affected, all of them when empty.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub goos: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub goarch: Vec<String>,
+    /// Functions and methods affected, like `Server.ServeConn`, all of them when empty.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub symbols: Vec<String>,
+}
+
+#[derive(Debug, Default, Clone, Deserialize, Serialize)]
+pub struct EcosystemSpecific {
+    #[serde(default)]
+    pub imports: Vec<Import>,
+}
+
+#[derive(Debug, Default, Clone, Deserialize, Serialize)]
+pub struct Affected {
+    /// Module affected, its name being the module path, like `golang.org/x/net`, or `stdlib`
+    /// and `toolchain` for the standard library and the `go` command.
+    #[serde(flatten)]
+    pub affected: osv::Affected,
+    #[serde(default)]
+    pub ecosystem_specific: EcosystemSpecific,
+}
+
+impl Affected {
+    pub fn module(&self) -> &str {
+        &self.affected.package.name
+    }
+
+    /// Whether the query names the module, by path or like the scans name it (`go-net`).
+    fn is_module(&self, product: &str) -> bool {
+        self.module() == product || self.affected.package.product().as_deref() == Some(product)
+    }
+}
+
+#[derive(Debug, Default, Clone, Deserialize, Serialize)]
+pub struct DatabaseSpecific {
+    /// Page of the report, like `https://pkg.go.dev/vuln/GO-2022-0969`.
+    #[serde(default)]
+    pub url: String,
+}
+
+#[derive(Debug, Default, Clone, Deserialize, Serialize)]
+pub struct Entry {
+    /// Like `GO-2022-0969`.
+    pub id: String,
+    #[serde(default)]
+    pub modified: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub withdrawn: Option<String>,
+    #[serde(default)]
+    pub aliases: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+    #[serde(default)]
+    pub details: String,
+    #[serde(default)]
+    pub affected: Vec<Affected>,
+    #[serde(default)]
+    pub references: Vec<osv::Reference>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub database_specific: Option<DatabaseSpecific>,
+}
+
+impl Entry {
+    pub fn object_key(id: &str) -> String {
+        format!("{} (govulndb)", id)
+    }
+
+    /// The CVE of the vulnerability if it has one, otherwise its Go id.
+    pub fn cve(&self) -> &str {
+        self.aliases
+            .iter()
+            .find(|alias| alias.starts_with("CVE-"))
+            .unwrap_or(&self.id)
+    }
+
+    pub fn summary(&self) -> &str {
+        match &self.summary {
+            Some(summary) if !summary.is_empty() => summary,
+            _ => self.details.lines().next().unwrap_or_default(),
+        }
+    }
+
+    /// Unique products of the affected modules, both their path and their name in the scans,
+    /// like `golang.org/x/net` and `go-net`.
+    pub fn products(&self) -> Vec<String> {
+        let mut products = vec![];
+        for affected in &self.affected {
+            let names = std::iter::once(affected.module().to_string())
+                .chain(affected.affected.package.product());
+            for product in names {
+                if !product.is_empty() && !products.contains(&product) {
+                    products.push(product);
+                }
+            }
+        }
+        products
+    }
+
+    /// Packages and symbols of the module affected.
+    pub fn imports(&self, module: &str) -> Vec<&Import> {
+        self.affected
+            .iter()
+            .filter(|affected| affected.module() == module)
+            .flat_map(|affected| affected.ecosystem_specific.imports.iter())
+            .collect()
+    }
+
+    pub fn is_match(&self, query: &Query) -> bool {
+        // we need a version
+        let version = match &query.version {
+            Some(version) => version,
+            None => return false,
+        };
+
+        self.affected
+            .iter()
+            .filter(|affected| affected.is_module(&query.product))
+            .any(|affected| affected.affected.is_match(version))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Entry;
+    use crate::search::testing::query;
+
+    const ENTRY: &str = r#"{
+        "schema_version": "1.3.1",
+        "id": "GO-2022-0969",
+        "modified": "2023-06-12T18:45:41Z",
+        "published": "2022-09-12T20:23:06Z",
+        "aliases": ["CVE-2022-27664", "GHSA-69cg-p879-7622"],
+        "summary": "Denial of service in net/http and golang.org/x/net/http2",
+        "details": "HTTP/2 server connections can hang forever waiting for a clean shutdown.",
+        "affected": [
+            {
+                "package": { "name": "stdlib", "ecosystem": "Go" },
+                "ranges": [{ "type": "SEMVER", "events": [{ "introduced": "0" }, { "fixed": "1.18.6" }, { "introduced": "1.19.0" }, { "fixed": "1.19.1" }] }],
+                "ecosystem_specific": { "imports": [{ "path": "net/http", "symbols": ["ListenAndServe", "Server.Serve"] }] }
+            },
+            {
+                "package": { "name": "golang.org/x/net", "ecosystem": "Go" },
+                "ranges": [{ "type": "SEMVER", "events": [{ "introduced": "0" }, { "fixed": "0.0.0-20220906165146-f3363e06e74c" }] }],
+                "ecosystem_specific": { "imports": [{ "path": "golang.org/x/net/http2", "goos": ["linux"], "symbols": ["Server.ServeConn"] }] }
+            }
+        ],
+        "references": [{ "type": "FIX", "url": "https://go.dev/cl/428735" }],
+        "database_specific": { "url": "https://pkg.go.dev/vuln/GO-2022-0969", "review_status": "REVIEWED" }
+    }"#;
+
+    #[test]
+    fn can_describe_entries() {
+        let entry: Entry = serde_json::from_str(ENTRY).unwrap();
+        assert_eq!("CVE-2022-27664", entry.cve());
+        assert_eq!(
+            "Denial of service in net/http and golang.org/x/net/http2",
+            entry.summary()
+        );
+        assert_eq!(
+            vec!["stdlib", "go-stdlib", "golang.org/x/net", "go-net"],
+            entry.products()
+        );
+
+        let imports = entry.imports("golang.org/x/net");
+        assert_eq!(1, imports.len());
+        assert_eq!("golang.org/x/net/http2", imports[0].path);
+        assert_eq!(vec!["linux"], imports[0].goos);
+        assert_eq!(vec!["Server.ServeConn"], imports[0].symbols);
+    }
+
+    #[test]
+    fn can_match_modules() {
+        let entry: Entry = serde_json::from_str(ENTRY).unwrap();
+        assert!(entry.is_match(&query("stdlib", Some("1.19.0"))));
+        assert!(!entry.is_match(&query("stdlib", Some("1.19.1"))));
+        assert!(entry.is_match(&query("go-stdlib", Some("1.18.5"))));
+        assert!(entry.is_match(&query(
+            "golang.org/x/net",
+            Some("v0.0.0-20220425223048-2871e0cb64e4")
+        )));
+        assert!(!entry.is_match(&query("golang.org/x/net", Some("0.1.0"))));
+        assert!(!entry.is_match(&query("golang.org/x/text", Some("0.3.0"))));
+        assert!(!entry.is_match(&query("stdlib", None)));
+    }
+}
@@ -0,0 +1,113 @@
+use std::fs::{self, File};
+use std::io::BufReader;
+use std::path::Path;
+
+use anyhow::{anyhow, Context, Result};
+use chrono::{Datelike, Utc};
+use log::info;
+
+use super::{rss, Advisory, SOURCE_NAME, VENDOR};
+
+use crate::db::{self, Pool};
+use crate::utils::{download_to_file, ImportError};
+
+/// JVNRSS feeds of the vulnerabilities published every year, in English.
+pub const FEED_URL: &str = "https://jvndb.jvn.jp/en/rss/years";
+/// Year of the oldest feed.
+const FIRST_YEAR: i32 = 2002;
+
+fn store(database: &db::Database, advisory: &Advisory) -> Result<u32> {
+    // the JVN iPedia ids and the JVN ids both stand for the CVEs
+    if !advisory.cves.is_empty() {
+        let cves: Vec<&str> = advisory.cves.iter().map(String::as_str).collect();
+        for id in std::iter::once(&advisory.id).chain(&advisory.jvn_ids) {
+            database
+                .replace_advisory_aliases(id, &cves)
+                .map_err(|e| anyhow!(e))?;
+        }
+    }
+
+    let products = advisory.product_names();
+    if products.is_empty() {
+        return Ok(0);
+    }
+
+    let object_id = database
+        .upsert_object(db::models::NewObject::with(
+            Advisory::object_key(&advisory.id),
+            serde_json::to_string(advisory)?,
+        ))
+        .map_err(|e| anyhow!(e))?;
+
+    let mut refs = db::models::References::default();
+    if !advisory.url.is_empty() {
+        refs.push(db::models::Reference {
+            url: advisory.url.clone(),
+            tags: vec!["Third Party Advisory".into()],
+        });
+    }
+
+    let mut num_imported = 0;
+    for id in advisory.record_ids() {
+        for product in &products {
+            let new_cve = db::models::NewCVE::with(
+                SOURCE_NAME.into(),
+                VENDOR.into(),
+                product.clone(),
+                id.clone(),
+                advisory.summary(),
+                advisory.score.unwrap_or_default(),
+                advisory.severity(),
+                advisory.vector.clone(),
+                refs.clone(),
+                Some(object_id),
+            );
+            if database.upsert_cve(new_cve).map_err(|e| anyhow!(e))? {
+                num_imported += 1;
+            }
+        }
+    }
+
+    Ok(num_imported)
+}
+
+/// Imports the vulnerabilities of JVN iPedia from the feeds of every year, downloaded again
+/// for the current year and for all of them with `fresh`, creating the new records and
+/// updating the changed ones.
+pub fn run(pool: &Pool, data_path: &Path, fresh: bool) -> Result<u32> {
+    let mut jvn_path = data_path.to_path_buf();
+    jvn_path.push("jvn");
+    fs::create_dir_all(&jvn_path)
+        .with_context(|| format!("could not create {}", jvn_path.display()))?;
+
+    let database = db::Database(pool.get()?);
+    let current_year = Utc::now().year();
+    let mut num_imported = 0;
+    for year in FIRST_YEAR..=current_year {
+        let file_name = format!("jvndb_{}.rdf", year);
+        let mut file_path = jvn_path.clone();
+        file_path.push(&file_name);
+
+        // the feeds of the past years are still amended, though rarely
+        if fresh || year == current_year || !file_path.exists() {
+            download_to_file(&format!("{}/{}", FEED_URL, file_name), &file_path)?;
+        }
+
+        info!("parsing {} ...", file_path.display());
+        let file = File::open(&file_path)
+            .with_context(|| format!("could not open {}", file_path.display()))?;
+        let advisories = rss::parse(BufReader::new(file))
+            .map_err(|e| ImportError::from(e).quarantine(&file_path))?;
+
+        info!(
+            "importing {} vulnerabilities of {} ...",
+            advisories.len(),
+            year
+        );
+        for advisory in &advisories {
+            num_imported += store(&database, advisory)?;
+        }
+    }
+
+    Ok(num_imported)
+}
@@ -0,0 +1,157 @@
+//! Vulnerabilities of JVN iPedia, the Japanese vulnerability database, published with their
+//! JVN ids and their CVE ids when assigned, but without the versions affected.
+
+use serde::{Deserialize, Serialize};
+
+use crate::search::Query;
+
+#[cfg(feature = "import")]
+pub mod import;
+#[cfg(feature = "import")]
+pub mod rss;
+
+pub const SOURCE_NAME: &str = "JVN";
+/// Kept apart from the NIST vendors, the products being named by their CPE product only.
+pub const VENDOR: &str = "@jvn";
+
+/// Product affected, from its CPE like `cpe:/a:cybozu:garoon`.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Product {
+    /// Like `Cybozu, Inc.`.
+    #[serde(default)]
+    pub vendor_name: String,
+    /// Like `Garoon`.
+    #[serde(default)]
+    pub product_name: String,
+    pub cpe: String,
+}
+
+impl Product {
+    /// Product of the CPE, like `garoon`.
+    pub fn product(&self) -> Option<String> {
+        let mut parts = self.cpe.strip_prefix("cpe:/")?.split(':');
+        let (_part, _vendor) = (parts.next()?, parts.next()?);
+        parts
+            .next()
+            .map(str::to_lowercase)
+            .filter(|product| !product.is_empty())
+    }
+}
+
+#[derive(Debug, Default, Clone, Deserialize, Serialize)]
+pub struct Advisory {
+    /// Like `JVNDB-2023-001001`.
+    pub id: String,
+    /// Like `JVN#12345678` or `JVNVU#12345678`.
+    #[serde(default)]
+    pub jvn_ids: Vec<String>,
+    #[serde(default)]
+    pub title: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub url: String,
+    #[serde(default)]
+    pub cves: Vec<String>,
+    #[serde(default)]
+    pub products: Vec<Product>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub score: Option<f64>,
+    /// `Critical`, `High`, `Medium`, `Low` or `None`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub severity: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vector: Option<String>,
+    #[serde(default)]
+    pub issued: String,
+}
+
+impl Advisory {
+    pub fn object_key(id: &str) -> String {
+        format!("{} (jvn)", id)
+    }
+
+    /// CVEs of the records, or else the JVN iPedia id for the vulnerabilities assigned no CVE.
+    pub fn record_ids(&self) -> Vec<String> {
+        if self.cves.is_empty() {
+            return vec![self.id.clone()];
+        }
+        self.cves.clone()
+    }
+
+    pub fn summary(&self) -> String {
+        if self.description.trim().is_empty() {
+            return self.title.trim().to_string();
+        }
+        self.description.trim().to_string()
+    }
+
+    /// Severity rated by JVN, named like the NIST severities.
+    pub fn severity(&self) -> String {
+        match self.severity.as_deref().map(str::to_lowercase).as_deref() {
+            Some("critical") => "CRITICAL",
+            Some("high") => "HIGH",
+            Some("medium") => "MEDIUM",
+            Some("low") => "LOW",
+            _ => "NONE",
+        }
+        .to_string()
+    }
+
+    /// Unique products of the CPEs.
+    pub fn product_names(&self) -> Vec<String> {
+        let mut products: Vec<String> = vec![];
+        for product in self.products.iter().filter_map(Product::product) {
+            if !products.contains(&product) {
+                products.push(product);
+            }
+        }
+        products
+    }
+
+    /// Matches the searches of the product without a version only, the versions affected
+    /// being left out of the feeds.
+    pub fn is_match(&self, query: &Query) -> bool {
+        query.version.is_none() && self.product_names().contains(&query.product)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Advisory, Product};
+    use crate::search::testing::query;
+
+    #[test]
+    fn can_match_products() {
+        let mut advisory = Advisory {
+            id: "JVNDB-2023-001001".into(),
+            severity: Some("Medium".into()),
+            products: vec![
+                Product {
+                    vendor_name: "Cybozu, Inc.".into(),
+                    product_name: "Garoon".into(),
+                    cpe: "cpe:/a:cybozu:garoon".into(),
+                },
+                Product {
+                    cpe: "cpe:/a:cybozu:Garoon".into(),
+                    ..Default::default()
+                },
+                Product {
+                    cpe: "garoon".into(),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+
+        assert_eq!("MEDIUM", advisory.severity());
+        assert_eq!(vec!["garoon"], advisory.product_names());
+        assert_eq!(vec!["JVNDB-2023-001001"], advisory.record_ids());
+        assert!(advisory.is_match(&query("garoon", None)));
+        assert!(!advisory.is_match(&query("garoon", Some("5.0.0"))));
+        assert!(!advisory.is_match(&query("kintone", None)));
+
+        advisory.cves = vec!["CVE-2023-22282".into()];
+        assert_eq!(vec!["CVE-2023-22282"], advisory.record_ids());
+    }
+}
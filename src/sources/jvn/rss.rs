@@ -0,0 +1,208 @@
+//! Streaming parser of the JVNRSS feeds of JVN iPedia, RSS 1.0 documents whose items carry
+//! the identifiers, the CPEs and the CVSS scores of the vulnerabilities.
+
+use std::io::BufRead;
+
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+
+use super::{Advisory, Product};
+use crate::utils::normalize_utc;
+
+/// Element whose text is being read.
+enum Text {
+    None,
+    Title,
+    Link,
+    Description,
+    Identifier,
+    Cpe,
+    Issued,
+}
+
+fn attribute(element: &BytesStart, name: &[u8]) -> Option<String> {
+    element
+        .attributes()
+        .flatten()
+        .find(|attribute| attribute.key == name)
+        .and_then(|attribute| {
+            attribute
+                .unescaped_value()
+                .ok()
+                .map(|value| String::from_utf8_lossy(&value).into_owned())
+        })
+}
+
+/// Parses the items of the feed, keeping the CVSS score of the latest version when scored
+/// with several.
+pub fn parse<R: BufRead>(source: R) -> Result<Vec<Advisory>, String> {
+    let mut reader = Reader::from_reader(source);
+    reader.trim_text(true);
+
+    let mut advisories = vec![];
+    let mut advisory: Option<Advisory> = None;
+    let mut cvss_version = 0.0;
+    let mut text = Text::None;
+
+    let mut buf = vec![];
+    loop {
+        let event = reader.read_event(&mut buf).map_err(|e| {
+            format!(
+                "could not parse the JVNRSS feed at {}: {}",
+                reader.buffer_position(),
+                e
+            )
+        })?;
+
+        match &event {
+            Event::Start(element) | Event::Empty(element) => {
+                let is_start = matches!(event, Event::Start(_));
+                match element.local_name() {
+                    b"item" if is_start => {
+                        advisory = Some(Advisory::default());
+                        cvss_version = 0.0;
+                    }
+                    b"title" if advisory.is_some() => text = Text::Title,
+                    b"link" if advisory.is_some() => text = Text::Link,
+                    b"description" if advisory.is_some() => text = Text::Description,
+                    b"identifier" if advisory.is_some() => text = Text::Identifier,
+                    b"issued" if advisory.is_some() => text = Text::Issued,
+                    b"references" => {
+                        if let Some(advisory) = &mut advisory {
+                            let id = attribute(element, b"id").unwrap_or_default();
+                            let ids = match attribute(element, b"source").as_deref() {
+                                Some("CVE") if id.starts_with("CVE-") => Some(&mut advisory.cves),
+                                Some("JVN") if id.starts_with("JVN") => Some(&mut advisory.jvn_ids),
+                                _ => None,
+                            };
+                            if let Some(ids) = ids.filter(|ids| !ids.contains(&id)) {
+                                ids.push(id);
+                            }
+                        }
+                    }
+                    b"cpe" => {
+                        if let Some(advisory) = &mut advisory {
+                            advisory.products.push(Product {
+                                vendor_name: attribute(element, b"vendor").unwrap_or_default(),
+                                product_name: attribute(element, b"product").unwrap_or_default(),
+                                cpe: String::new(),
+                            });
+                            text = Text::Cpe;
+                        }
+                    }
+                    b"cvss" => {
+                        if let Some(advisory) = &mut advisory {
+                            let version = attribute(element, b"version")
+                                .and_then(|version| version.parse::<f64>().ok())
+                                .unwrap_or_default();
+                            if version >= cvss_version {
+                                cvss_version = version;
+                                advisory.score = attribute(element, b"score")
+                                    .and_then(|score| score.parse().ok());
+                                advisory.severity = attribute(element, b"severity");
+                                advisory.vector = attribute(element, b"vector");
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            Event::Text(value) => {
+                let value = value
+                    .unescape_and_decode(&reader)
+                    .map_err(|e| format!("could not decode the JVNRSS feed: {}", e))?;
+                if let Some(advisory) = &mut advisory {
+                    let value = value.trim().to_string();
+                    match text {
+                        Text::Title => advisory.title = value,
+                        Text::Link => advisory.url = value,
+                        Text::Description => advisory.description = value,
+                        Text::Identifier => advisory.id = value,
+                        Text::Cpe => {
+                            if let Some(product) = advisory.products.last_mut() {
+                                product.cpe = value;
+                            }
+                        }
+                        Text::Issued => advisory.issued = normalize_utc(&value),
+                        Text::None => {}
+                    }
+                }
+            }
+            Event::End(element) => {
+                text = Text::None;
+                if element.local_name() == b"item" {
+                    advisories.extend(advisory.take().filter(|advisory| !advisory.id.is_empty()));
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+
+        buf.clear();
+    }
+
+    Ok(advisories)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse;
+
+    const FEED: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<rdf:RDF xmlns="http://purl.org/rss/1.0/"
+    xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#"
+    xmlns:sec="http://jvn.jp/rss/mod_sec/3.0/"
+    xmlns:dcterms="http://purl.org/dc/terms/">
+  <channel rdf:about="https://jvndb.jvn.jp/en/rss/years/jvndb_2023.rdf">
+    <title>JVNDB Vulnerability Countermeasure Information 2023</title>
+    <link>https://jvndb.jvn.jp/en/</link>
+    <description>JVNDB Vulnerability Countermeasure Information</description>
+  </channel>
+  <item rdf:about="https://jvndb.jvn.jp/en/contents/2023/JVNDB-2023-001001.html">
+    <title>Multiple vulnerabilities in Cybozu Garoon</title>
+    <link>https://jvndb.jvn.jp/en/contents/2023/JVNDB-2023-001001.html</link>
+    <description>Cybozu Garoon provided by Cybozu, Inc. contains multiple vulnerabilities &amp; more.</description>
+    <sec:identifier>JVNDB-2023-001001</sec:identifier>
+    <sec:references source="JVN" id="JVN#12345678">https://jvn.jp/en/jp/JVN12345678/</sec:references>
+    <sec:references source="CVE" id="CVE-2023-22282">https://www.cve.org/CVERecord?id=CVE-2023-22282</sec:references>
+    <sec:references source="CVE" id="CVE-2023-22283">https://www.cve.org/CVERecord?id=CVE-2023-22283</sec:references>
+    <sec:cpe version="2.2" vendor="Cybozu, Inc." product="Garoon">cpe:/a:cybozu:garoon</sec:cpe>
+    <sec:cvss score="4.0" severity="Medium" vector="AV:N/AC:L/Au:S/C:P/I:N/A:N" version="2.0" type="Base" />
+    <sec:cvss score="4.3" severity="Medium" vector="CVSS:3.0/AV:N/AC:L/PR:L/UI:N/S:U/C:L/I:N/A:N" version="3.0" type="Base" />
+    <dcterms:issued>2023-01-11T14:13+09:00</dcterms:issued>
+    <dcterms:modified>2023-01-11T14:13+09:00</dcterms:modified>
+  </item>
+  <item rdf:about="https://jvndb.jvn.jp/en/contents/2023/JVNDB-2023-001002.html">
+    <title>Information disclosure in a Japanese product</title>
+    <sec:identifier>JVNDB-2023-001002</sec:identifier>
+    <sec:cpe version="2.2" vendor="Example" product="Example">cpe:/a:example:example</sec:cpe>
+  </item>
+</rdf:RDF>"#;
+
+    #[test]
+    fn can_parse_items() {
+        let advisories = parse(FEED.as_bytes()).unwrap();
+        assert_eq!(2, advisories.len());
+
+        let advisory = &advisories[0];
+        assert_eq!("JVNDB-2023-001001", advisory.id);
+        assert_eq!("Multiple vulnerabilities in Cybozu Garoon", advisory.title);
+        assert_eq!(
+            "Cybozu Garoon provided by Cybozu, Inc. contains multiple vulnerabilities & more.",
+            advisory.description
+        );
+        assert_eq!(vec!["JVN#12345678"], advisory.jvn_ids);
+        assert_eq!(vec!["CVE-2023-22282", "CVE-2023-22283"], advisory.cves);
+        assert_eq!("Cybozu, Inc.", advisory.products[0].vendor_name);
+        assert_eq!(vec!["garoon"], advisory.product_names());
+        assert_eq!(Some(4.3), advisory.score);
+        assert_eq!(
+            Some("CVSS:3.0/AV:N/AC:L/PR:L/UI:N/S:U/C:L/I:N/A:N".to_string()),
+            advisory.vector
+        );
+        assert_eq!("2023-01-11T05:13:00Z", advisory.issued);
+
+        assert!(advisories[1].cves.is_empty());
+        assert_eq!(vec!["JVNDB-2023-001002"], advisories[1].record_ids());
+    }
+}
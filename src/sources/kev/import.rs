@@ -0,0 +1,57 @@
+use std::fs;
+use std::path::Path;
+
+use anyhow::{anyhow, Context, Result};
+use log::info;
+
+use super::Catalog;
+
+use crate::db::{self, Pool};
+use crate::utils::{download_to_file, ImportError};
+
+pub const FEED_URL: &str =
+    "https://www.cisa.gov/sites/default/files/feeds/known_exploited_vulnerabilities.json";
+
+/// Imports the catalog, replacing the previous one, and returns the number of CVEs added.
+pub fn run(pool: &Pool, data_path: &Path, fresh: bool) -> Result<u32> {
+    let mut kev_path = data_path.to_path_buf();
+    kev_path.push("kev");
+    fs::create_dir_all(&kev_path)
+        .with_context(|| format!("could not create {}", kev_path.display()))?;
+
+    let mut file_path = kev_path;
+    file_path.push("known_exploited_vulnerabilities.json");
+    if fresh || !file_path.exists() {
+        download_to_file(FEED_URL, &file_path)?;
+    }
+
+    let json = fs::read_to_string(&file_path)
+        .with_context(|| format!("could not read {}", file_path.display()))?;
+    let catalog: Catalog = serde_json::from_str(&json).map_err(|e| {
+        ImportError::from(format!("could not parse the catalog: {}", e)).quarantine(&file_path)
+    })?;
+    // an empty catalog is a broken feed rather than all the CVEs being removed from it
+    if catalog.vulnerabilities.is_empty() {
+        return Err(ImportError::from("the catalog is empty".to_string())
+            .quarantine(&file_path)
+            .into());
+    }
+
+    info!(
+        "importing the {} known exploited CVEs of the catalog {} ...",
+        catalog.vulnerabilities.len(),
+        catalog.catalog_version
+    );
+
+    let num_added = db::Database(pool.get()?)
+        .replace_known_exploited(
+            catalog
+                .vulnerabilities
+                .into_iter()
+                .map(db::models::NewKnownExploited::with)
+                .collect(),
+        )
+        .map_err(|e| anyhow!(e))?;
+
+    Ok(num_added as u32)
+}
@@ -0,0 +1,175 @@
+//! Catalog of the Known Exploited Vulnerabilities published by CISA, flagging the CVEs
+//! exploited in the wild.
+
+use std::collections::HashMap;
+
+use serde::Deserialize;
+
+use crate::db::{models, Database};
+
+#[cfg(feature = "import")]
+pub mod import;
+
+pub use kepler_types::api::Exploitation;
+
+pub const SOURCE_NAME: &str = "CISA KEV";
+
+/// Catalog as published in the JSON feed.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Catalog {
+    pub catalog_version: String,
+    pub date_released: String,
+    pub vulnerabilities: Vec<Vulnerability>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Vulnerability {
+    #[serde(rename = "cveID")]
+    pub cve_id: String,
+    pub vendor_project: String,
+    pub product: String,
+    pub vulnerability_name: String,
+    /// Dates formatted as `YYYY-MM-DD`.
+    pub date_added: String,
+    pub due_date: String,
+    pub required_action: String,
+    /// `Known` when used by ransomware campaigns, `Unknown` otherwise.
+    #[serde(default)]
+    pub known_ransomware_campaign_use: Option<String>,
+}
+
+impl Vulnerability {
+    pub fn is_used_by_ransomware(&self) -> bool {
+        self.known_ransomware_campaign_use.as_deref() == Some("Known")
+    }
+}
+
+/// Known exploited CVEs among a set of records, to flag them in the responses.
+#[derive(Debug, Default)]
+pub struct Exploited(HashMap<String, models::KnownExploited>);
+
+impl Exploited {
+    pub fn with(entries: Vec<models::KnownExploited>) -> Self {
+        Self(
+            entries
+                .into_iter()
+                .map(|entry| (entry.cve.clone(), entry))
+                .collect(),
+        )
+    }
+
+    /// Looks up the CVEs of the records in the catalog.
+    pub fn load(db: &Database, cves: &[models::CVE]) -> Result<Self, String> {
+        let mut ids: Vec<&str> = cves.iter().map(|cve| cve.cve.as_str()).collect();
+        ids.sort_unstable();
+        ids.dedup();
+        if ids.is_empty() {
+            return Ok(Self::default());
+        }
+        db.get_known_exploited(&ids).map(Self::with)
+    }
+
+    pub fn of(&self, cve: &str) -> Exploitation {
+        match self.0.get(cve) {
+            Some(entry) => Exploitation {
+                known_exploited: true,
+                due_date: Some(entry.due_date.clone()),
+                required_action: Some(entry.required_action.clone()),
+            },
+            None => Exploitation::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::{Catalog, Exploited};
+    use crate::db::models;
+
+    const CATALOG: &str = r#"{
+        "title": "CISA Catalog of Known Exploited Vulnerabilities",
+        "catalogVersion": "2022.07.05",
+        "dateReleased": "2022-07-05T14:00:10.3254Z",
+        "count": 2,
+        "vulnerabilities": [
+            {
+                "cveID": "CVE-2021-44228",
+                "vendorProject": "Apache",
+                "product": "Log4j2",
+                "vulnerabilityName": "Apache Log4j2 Remote Code Execution Vulnerability",
+                "dateAdded": "2021-12-10",
+                "shortDescription": "Apache Log4j2 contains a vulnerability.",
+                "requiredAction": "Apply updates per vendor instructions.",
+                "dueDate": "2021-12-24",
+                "knownRansomwareCampaignUse": "Known",
+                "notes": ""
+            },
+            {
+                "cveID": "CVE-2021-27104",
+                "vendorProject": "Accellion",
+                "product": "FTA",
+                "vulnerabilityName": "Accellion FTA OS Command Injection Vulnerability",
+                "dateAdded": "2021-11-03",
+                "shortDescription": "Accellion FTA contains an OS command injection vulnerability.",
+                "requiredAction": "Apply updates per vendor instructions.",
+                "dueDate": "2021-11-17"
+            }
+        ]
+    }"#;
+
+    #[test]
+    fn can_parse_catalog() {
+        let catalog: Catalog = serde_json::from_str(CATALOG).unwrap();
+        assert_eq!("2022.07.05", catalog.catalog_version);
+        assert_eq!(2, catalog.vulnerabilities.len());
+
+        let log4shell = &catalog.vulnerabilities[0];
+        assert_eq!("CVE-2021-44228", log4shell.cve_id);
+        assert_eq!("2021-12-24", log4shell.due_date);
+        assert!(log4shell.is_used_by_ransomware());
+        assert!(!catalog.vulnerabilities[1].is_used_by_ransomware());
+    }
+
+    #[test]
+    fn can_flag_exploited_cves() {
+        let catalog: Catalog = serde_json::from_str(CATALOG).unwrap();
+        let entries = catalog
+            .vulnerabilities
+            .into_iter()
+            .enumerate()
+            .map(|(i, vulnerability)| {
+                let new = models::NewKnownExploited::with(vulnerability);
+                models::KnownExploited {
+                    id: i as i32,
+                    created_at: Utc::now(),
+                    updated_at: None,
+                    cve: new.cve,
+                    vendor: new.vendor,
+                    product: new.product,
+                    name: new.name,
+                    date_added: new.date_added,
+                    due_date: new.due_date,
+                    required_action: new.required_action,
+                    ransomware: new.ransomware,
+                }
+            })
+            .collect();
+        let exploited = Exploited::with(entries);
+
+        let log4shell = exploited.of("CVE-2021-44228");
+        assert!(log4shell.known_exploited);
+        assert_eq!(Some("2021-12-24"), log4shell.due_date.as_deref());
+        assert_eq!(
+            Some("Apply updates per vendor instructions."),
+            log4shell.required_action.as_deref()
+        );
+
+        let other = exploited.of("CVE-2021-3517");
+        assert!(!other.known_exploited);
+        assert_eq!(None, other.due_date);
+    }
+}
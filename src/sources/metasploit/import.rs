@@ -0,0 +1,56 @@
+use std::fs;
+use std::path::Path;
+
+use anyhow::{anyhow, Context, Result};
+use log::info;
+
+use crate::db::{self, Pool};
+use crate::utils::{download_to_file, ImportError};
+
+pub const FEED_URL: &str = "https://raw.githubusercontent.com/rapid7/metasploit-framework/master/db/modules_metadata_base.json";
+
+/// Imports the exploit modules of the CVEs, replacing the previous ones, and returns the
+/// number of modules added.
+pub fn run(pool: &Pool, data_path: &Path, fresh: bool) -> Result<u32> {
+    let mut metasploit_path = data_path.to_path_buf();
+    metasploit_path.push("metasploit");
+    fs::create_dir_all(&metasploit_path)
+        .with_context(|| format!("could not create {}", metasploit_path.display()))?;
+
+    let mut file_path = metasploit_path;
+    file_path.push("modules_metadata_base.json");
+    if fresh || !file_path.exists() {
+        download_to_file(FEED_URL, &file_path)?;
+    }
+
+    let json = fs::read_to_string(&file_path)
+        .with_context(|| format!("could not read {}", file_path.display()))?;
+    let modules = super::parse(&json).map_err(|e| {
+        ImportError::from(format!("could not parse the modules metadata: {}", e))
+            .quarantine(&file_path)
+    })?;
+    // no module at all is a broken download rather than all the modules being removed
+    if modules.is_empty() {
+        return Err(
+            ImportError::from("no exploit module of a CVE found".to_string())
+                .quarantine(&file_path)
+                .into(),
+        );
+    }
+
+    info!(
+        "importing the {} exploit modules of CVEs ...",
+        modules.len()
+    );
+
+    let num_added = db::Database(pool.get()?)
+        .replace_metasploit_modules(
+            modules
+                .iter()
+                .flat_map(db::models::NewMetasploitModule::with)
+                .collect(),
+        )
+        .map_err(|e| anyhow!(e))?;
+
+    Ok(num_added as u32)
+}
@@ -0,0 +1,183 @@
+//! Metadata of the modules of the Metasploit framework, flagging the CVEs with a weaponized
+//! exploit.
+
+use std::collections::HashMap;
+
+use serde::Deserialize;
+
+use crate::db::{models, Database};
+
+#[cfg(feature = "import")]
+pub mod import;
+
+pub use kepler_types::api::Metasploit;
+
+pub const SOURCE_NAME: &str = "Metasploit";
+
+/// Type of the modules running an exploit against a target.
+pub const EXPLOIT_TYPE: &str = "exploit";
+
+/// Module as described in `modules_metadata_base.json`, the other fields being ignored.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Module {
+    pub name: String,
+    /// Path of the module, like `exploit/multi/http/log4shell_header_injection`.
+    pub fullname: String,
+    #[serde(rename = "type")]
+    pub module_type: String,
+    /// Reliability of the module, from 0 (manual) to 600 (excellent).
+    #[serde(default)]
+    pub rank: i32,
+    /// Date formatted as `YYYY-MM-DD`.
+    #[serde(default)]
+    pub disclosure_date: Option<String>,
+    /// References like `CVE-2021-44228` or `URL-https://...`.
+    #[serde(default)]
+    pub references: Vec<String>,
+}
+
+impl Module {
+    /// Returns the CVEs referenced by the module.
+    pub fn cves(&self) -> Vec<String> {
+        let mut cves: Vec<String> = self
+            .references
+            .iter()
+            .map(|reference| reference.trim().to_uppercase())
+            .filter(|reference| reference.starts_with("CVE-"))
+            .collect();
+        cves.sort_unstable();
+        cves.dedup();
+        cves
+    }
+}
+
+/// Parses the metadata of all the modules, keeping the exploits referencing a CVE.
+pub fn parse(json: &str) -> Result<Vec<Module>, String> {
+    let modules: HashMap<String, Module> = serde_json::from_str(json).map_err(|e| e.to_string())?;
+
+    let mut exploits: Vec<Module> = modules
+        .into_iter()
+        .map(|(_, module)| module)
+        .filter(|module| module.module_type == EXPLOIT_TYPE && !module.cves().is_empty())
+        .collect();
+    exploits.sort_by(|a, b| a.fullname.cmp(&b.fullname));
+    Ok(exploits)
+}
+
+/// Metasploit modules of the CVEs among a set of records, to flag them in the responses.
+#[derive(Debug, Default)]
+pub struct Modules(HashMap<String, Vec<models::MetasploitModule>>);
+
+impl Modules {
+    pub fn with(entries: Vec<models::MetasploitModule>) -> Self {
+        let mut modules: HashMap<String, Vec<models::MetasploitModule>> = HashMap::new();
+        for entry in entries {
+            modules.entry(entry.cve.clone()).or_default().push(entry);
+        }
+        Self(modules)
+    }
+
+    /// Looks up the modules of the CVEs of the records.
+    pub fn load(db: &Database, cves: &[models::CVE]) -> Result<Self, String> {
+        let mut ids: Vec<&str> = cves.iter().map(|cve| cve.cve.as_str()).collect();
+        ids.sort_unstable();
+        ids.dedup();
+        if ids.is_empty() {
+            return Ok(Self::default());
+        }
+        db.get_metasploit_modules(&ids).map(Self::with)
+    }
+
+    pub fn of(&self, cve: &str) -> Metasploit {
+        match self.0.get(cve) {
+            Some(entries) => Metasploit {
+                metasploit_module: true,
+                metasploit_modules: entries.iter().map(|entry| entry.fullname.clone()).collect(),
+            },
+            None => Metasploit::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::{parse, Modules};
+    use crate::db::models;
+
+    const METADATA: &str = r#"{
+        "exploit_multi/http/log4shell_header_injection": {
+            "name": "Log4Shell HTTP Header Injection",
+            "fullname": "exploit/multi/http/log4shell_header_injection",
+            "aliases": [],
+            "rank": 600,
+            "disclosure_date": "2021-12-09",
+            "type": "exploit",
+            "references": ["CVE-2021-44228", "CVE-2021-45046", "URL-https://logging.apache.org"],
+            "platform": "Java,Linux,Unix"
+        },
+        "auxiliary_scanner/http/log4shell_scanner": {
+            "name": "Log4Shell HTTP Scanner",
+            "fullname": "auxiliary/scanner/http/log4shell_scanner",
+            "rank": 300,
+            "disclosure_date": "2021-12-09",
+            "type": "auxiliary",
+            "references": ["CVE-2021-44228"]
+        },
+        "exploit_multi/handler": {
+            "name": "Generic Payload Handler",
+            "fullname": "exploit/multi/handler",
+            "rank": 0,
+            "disclosure_date": null,
+            "type": "exploit",
+            "references": []
+        }
+    }"#;
+
+    #[test]
+    fn can_parse_metadata() {
+        let modules = parse(METADATA).unwrap();
+        assert_eq!(1, modules.len());
+        assert_eq!(
+            "exploit/multi/http/log4shell_header_injection",
+            modules[0].fullname
+        );
+        assert_eq!(600, modules[0].rank);
+        assert_eq!(
+            vec!["CVE-2021-44228".to_string(), "CVE-2021-45046".to_string()],
+            modules[0].cves()
+        );
+    }
+
+    #[test]
+    fn can_flag_cves_with_modules() {
+        let modules = parse(METADATA).unwrap();
+        let entries = modules
+            .iter()
+            .flat_map(models::NewMetasploitModule::with)
+            .enumerate()
+            .map(|(i, new)| models::MetasploitModule {
+                id: i as i32,
+                created_at: Utc::now(),
+                fullname: new.fullname,
+                cve: new.cve,
+                name: new.name,
+                rank: new.rank,
+                disclosure_date: new.disclosure_date,
+            })
+            .collect();
+        let modules = Modules::with(entries);
+
+        let log4shell = modules.of("CVE-2021-45046");
+        assert!(log4shell.metasploit_module);
+        assert_eq!(
+            vec!["exploit/multi/http/log4shell_header_injection".to_string()],
+            log4shell.metasploit_modules
+        );
+
+        let other = modules.of("CVE-2021-3517");
+        assert!(!other.metasploit_module);
+        assert!(other.metasploit_modules.is_empty());
+    }
+}
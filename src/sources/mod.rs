@@ -2,13 +2,58 @@ use serde::Deserialize;
 
 use crate::search::Query;
 
+pub mod allowlist;
+pub mod almalinux;
+pub mod alpine;
+pub mod amazon;
+pub mod capec;
+pub mod cisco;
+pub mod cnnvd;
+pub mod cvelist;
+pub mod cwe;
+pub mod debian;
+pub mod eol;
+pub mod epss;
+pub mod exploitdb;
+pub mod gentoo;
+pub mod ghsa;
+pub mod govulndb;
+pub mod jvn;
+pub mod kev;
+pub mod metasploit;
+pub mod msrc;
 pub mod nist;
 pub mod npm;
+pub mod oracle;
+pub mod osv;
+pub mod redhat;
+pub mod rocky;
+pub mod suse;
+pub mod ubuntu;
 
 #[derive(Debug, Deserialize)]
 pub enum Source {
     Nist(nist::cve::item::CVE),
     Npm(npm::Advisory),
+    Heuristic(nist::heuristic::Guess),
+    Osv(osv::Vulnerability),
+    GoVulnDb(govulndb::Entry),
+    Ghsa(ghsa::Advisory),
+    RedHat(redhat::Cve),
+    Debian(debian::Cve),
+    Ubuntu(ubuntu::Notice),
+    Alpine(alpine::Cve),
+    Suse(suse::Advisory),
+    Gentoo(gentoo::Advisory),
+    Amazon(amazon::Advisory),
+    Oracle(oracle::Advisory),
+    Rocky(rocky::Advisory),
+    AlmaLinux(almalinux::Advisory),
+    Msrc(msrc::Vulnerability),
+    Cisco(cisco::Advisory),
+    Jvn(jvn::Advisory),
+    Cnnvd(cnnvd::Entry),
+    CveList(cvelist::Vulnerability),
 }
 
 impl Source {
@@ -16,6 +61,25 @@ impl Source {
         match self {
             Self::Nist(cve) => cve.is_match(query),
             Self::Npm(advisory) => advisory.is_match(query),
+            Self::Heuristic(guess) => guess.is_match(query),
+            Self::Osv(vulnerability) => vulnerability.is_match(query),
+            Self::GoVulnDb(entry) => entry.is_match(query),
+            Self::Ghsa(advisory) => advisory.is_match(query),
+            Self::RedHat(cve) => cve.is_match(query),
+            Self::Debian(cve) => cve.is_match(query),
+            Self::Ubuntu(notice) => notice.is_match(query),
+            Self::Alpine(cve) => cve.is_match(query),
+            Self::Suse(cve) => cve.is_match(query),
+            Self::Gentoo(advisory) => advisory.is_match(query),
+            Self::Amazon(advisory) => advisory.is_match(query),
+            Self::Oracle(advisory) => advisory.is_match(query),
+            Self::Rocky(advisory) => advisory.is_match(query),
+            Self::AlmaLinux(advisory) => advisory.is_match(query),
+            Self::Msrc(vulnerability) => vulnerability.is_match(query),
+            Self::Cisco(advisory) => advisory.is_match(query),
+            Self::Jvn(advisory) => advisory.is_match(query),
+            Self::Cnnvd(entry) => entry.is_match(query),
+            Self::CveList(vulnerability) => vulnerability.is_match(query),
         }
     }
 }
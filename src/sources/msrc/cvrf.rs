@@ -0,0 +1,356 @@
+//! CVRF documents of the Security Update Guide in their JSON form, converted into the
+//! vulnerabilities of the products affected.
+
+use std::collections::HashMap;
+
+use serde::Deserialize;
+
+use super::{product, Affected, Vulnerability};
+use crate::utils::html_text;
+
+/// Type of the notes describing the vulnerability.
+const NOTE_DESCRIPTION: u8 = 2;
+/// Type of the threats rating the severity.
+const THREAT_SEVERITY: u8 = 3;
+/// Type of the threats naming the impact.
+const THREAT_IMPACT: u8 = 0;
+/// Type of the remediations fixing the vulnerability.
+const REMEDIATION_VENDOR_FIX: u8 = 2;
+
+/// Monthly documents listed by the API.
+#[derive(Debug, Deserialize)]
+pub struct Updates {
+    pub value: Vec<Update>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct Update {
+    /// Like `2022-Jul`.
+    #[serde(rename = "ID")]
+    pub id: String,
+    /// Like `2022-07-12T07:00:00Z`.
+    #[serde(default)]
+    pub current_release_date: String,
+    pub cvrf_url: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct Value {
+    #[serde(default)]
+    pub value: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct DocumentTracking {
+    pub identification: Identification,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct Identification {
+    #[serde(rename = "ID")]
+    pub id: Value,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FullProductName {
+    #[serde(rename = "ProductID")]
+    pub product_id: String,
+    #[serde(rename = "Value")]
+    pub value: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ProductTree {
+    #[serde(default)]
+    pub full_product_name: Vec<FullProductName>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct Note {
+    #[serde(default)]
+    pub value: String,
+    #[serde(rename = "Type")]
+    pub kind: u8,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct Threat {
+    #[serde(default)]
+    pub description: Value,
+    #[serde(rename = "Type")]
+    pub kind: u8,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ScoreSet {
+    pub base_score: f64,
+    #[serde(default)]
+    pub vector: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct Remediation {
+    /// The KB article of the security updates, like `5015811`.
+    #[serde(default)]
+    pub description: Value,
+    #[serde(rename = "ProductID", default)]
+    pub product_ids: Vec<String>,
+    #[serde(rename = "Type")]
+    pub kind: u8,
+    #[serde(default)]
+    pub fixed_build: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ProductStatus {
+    #[serde(rename = "ProductID", default)]
+    pub product_ids: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct CvrfVulnerability {
+    #[serde(rename = "CVE")]
+    pub cve: String,
+    #[serde(default)]
+    pub title: Value,
+    #[serde(default)]
+    pub notes: Vec<Note>,
+    #[serde(default)]
+    pub product_statuses: Vec<ProductStatus>,
+    #[serde(default)]
+    pub threats: Vec<Threat>,
+    #[serde(rename = "CVSSScoreSets", default)]
+    pub score_sets: Vec<ScoreSet>,
+    #[serde(default)]
+    pub remediations: Vec<Remediation>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct Document {
+    pub document_tracking: DocumentTracking,
+    #[serde(default)]
+    pub product_tree: ProductTree,
+    #[serde(default)]
+    pub vulnerability: Vec<CvrfVulnerability>,
+}
+
+impl Document {
+    pub fn vulnerabilities(&self) -> Vec<Vulnerability> {
+        let names: HashMap<&str, &str> = self
+            .product_tree
+            .full_product_name
+            .iter()
+            .map(|name| (name.product_id.as_str(), name.value.as_str()))
+            .collect();
+
+        self.vulnerability
+            .iter()
+            .filter(|vulnerability| vulnerability.cve.starts_with("CVE-"))
+            .map(|vulnerability| {
+                vulnerability.convert(&self.document_tracking.identification.id.value, &names)
+            })
+            .collect()
+    }
+}
+
+impl CvrfVulnerability {
+    fn convert(&self, release: &str, names: &HashMap<&str, &str>) -> Vulnerability {
+        let mut affected: Vec<Affected> = vec![];
+        let product_ids = self
+            .product_statuses
+            .iter()
+            .flat_map(|status| &status.product_ids);
+        for product_id in product_ids {
+            let name = match names.get(product_id.as_str()) {
+                Some(name) => name.to_string(),
+                None => continue,
+            };
+            let product = match product(&name) {
+                Some(product) => product,
+                None => continue,
+            };
+            if affected.iter().any(|affected| affected.name == name) {
+                continue;
+            }
+
+            let mut kbs: Vec<String> = vec![];
+            let mut fixed_build = None;
+            for remediation in self.remediations.iter().filter(|remediation| {
+                remediation.kind == REMEDIATION_VENDOR_FIX
+                    && remediation.product_ids.contains(product_id)
+            }) {
+                let kb = remediation.description.value.trim();
+                // the other fixes are described by a sentence rather than a KB article
+                if !kb.is_empty() && kb.chars().all(|c| c.is_ascii_digit()) {
+                    let kb = kb.to_string();
+                    if !kbs.contains(&kb) {
+                        kbs.push(kb);
+                    }
+                }
+                if fixed_build.is_none() {
+                    fixed_build = remediation
+                        .fixed_build
+                        .as_ref()
+                        .map(|build| build.trim().to_string())
+                        .filter(|build| !build.is_empty());
+                }
+            }
+
+            affected.push(Affected {
+                name,
+                product,
+                kbs,
+                fixed_build,
+            });
+        }
+
+        let threat = |kind| {
+            self.threats
+                .iter()
+                .find(|threat| threat.kind == kind && !threat.description.value.trim().is_empty())
+                .map(|threat| threat.description.value.trim().to_string())
+        };
+        let score_set = self.score_sets.iter().max_by(|a, b| {
+            a.base_score
+                .partial_cmp(&b.base_score)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+
+        Vulnerability {
+            cve: self.cve.trim().to_string(),
+            release: release.to_string(),
+            title: self.title.value.trim().to_string(),
+            description: self
+                .notes
+                .iter()
+                .find(|note| note.kind == NOTE_DESCRIPTION)
+                .map(|note| html_text(&note.value))
+                .unwrap_or_default(),
+            severity: threat(THREAT_SEVERITY),
+            impact: threat(THREAT_IMPACT),
+            score: score_set.map(|set| set.base_score),
+            vector: score_set.and_then(|set| set.vector.clone()),
+            affected,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Document, Updates};
+
+    const UPDATES: &str = r#"{
+        "@odata.context": "https://api.msrc.microsoft.com/cvrf/v3.0/$metadata#updates",
+        "value": [
+            {
+                "ID": "2022-Jul",
+                "Alias": "2022-Jul",
+                "DocumentTitle": "July 2022 Security Updates",
+                "Severity": null,
+                "InitialReleaseDate": "2022-07-12T07:00:00Z",
+                "CurrentReleaseDate": "2022-07-14T07:00:00Z",
+                "CvrfUrl": "https://api.msrc.microsoft.com/cvrf/v3.0/cvrf/2022-Jul"
+            }
+        ]
+    }"#;
+
+    const DOCUMENT: &str = r#"{
+        "DocumentTitle": { "Value": "July 2022 Security Updates" },
+        "DocumentTracking": {
+            "Identification": { "ID": { "Value": "2022-Jul" } },
+            "CurrentReleaseDate": "2022-07-14T07:00:00"
+        },
+        "ProductTree": {
+            "FullProductName": [
+                { "ProductID": "11568", "Value": "Windows 10 Version 1809 for x64-based Systems" },
+                { "ProductID": "11931", "Value": "Windows 10 Version 21H2 for x64-based Systems" },
+                { "ProductID": "11572", "Value": "Windows Server 2019" }
+            ]
+        },
+        "Vulnerability": [
+            {
+                "Title": { "Value": "Windows Print Spooler Elevation of Privilege Vulnerability" },
+                "Notes": [
+                    { "Title": "Description", "Type": 2, "Ordinal": "20", "Value": "<p>An attacker who <b>successfully</b> exploited this vulnerability could gain SYSTEM privileges.</p>" },
+                    { "Title": "Windows Print Spooler Components", "Type": 7, "Ordinal": "20" }
+                ],
+                "CVE": "CVE-2022-22022",
+                "ProductStatuses": [{ "ProductID": ["11568", "11931", "11572"], "Type": 3 }],
+                "Threats": [
+                    { "Description": { "Value": "Elevation of Privilege" }, "ProductID": ["11568"], "Type": 0 },
+                    { "Description": { "Value": "Important" }, "ProductID": ["11568"], "Type": 3 }
+                ],
+                "CVSSScoreSets": [
+                    { "BaseScore": 7.1, "TemporalScore": 6.2, "Vector": "CVSS:3.1/AV:L/AC:L/PR:L/UI:N/S:U/C:N/I:H/A:H/E:U/RL:O/RC:C", "ProductID": ["11568"] }
+                ],
+                "Remediations": [
+                    { "Description": { "Value": "5015811" }, "URL": "https://catalog.update.microsoft.com/v7/site/Search.aspx?q=KB5015811", "Supercedence": "5014692", "ProductID": ["11568", "11572"], "Type": 2, "SubType": "Security Update", "FixedBuild": "10.0.17763.3165" },
+                    { "Description": { "Value": "5015807" }, "ProductID": ["11931"], "Type": 2, "SubType": "Security Update", "FixedBuild": "10.0.19044.1826" },
+                    { "Description": { "Value": "Release Notes" }, "ProductID": ["11931"], "Type": 5 }
+                ]
+            },
+            {
+                "Title": { "Value": "Chromium: ADV220001" },
+                "CVE": "ADV220001",
+                "ProductStatuses": []
+            }
+        ]
+    }"#;
+
+    #[test]
+    fn can_parse_updates() {
+        let updates: Updates = serde_json::from_str(UPDATES).unwrap();
+        assert_eq!(1, updates.value.len());
+        assert_eq!("2022-Jul", updates.value[0].id);
+        assert_eq!(
+            "2022-07-14T07:00:00Z",
+            updates.value[0].current_release_date
+        );
+    }
+
+    #[test]
+    fn can_convert_documents() {
+        let document: Document = serde_json::from_str(DOCUMENT).unwrap();
+        let vulnerabilities = document.vulnerabilities();
+        assert_eq!(1, vulnerabilities.len());
+
+        let vulnerability = &vulnerabilities[0];
+        assert_eq!("CVE-2022-22022", vulnerability.cve);
+        assert_eq!("2022-Jul", vulnerability.release);
+        assert_eq!(
+            "An attacker who successfully exploited this vulnerability could gain SYSTEM privileges.",
+            vulnerability.description
+        );
+        assert_eq!(Some("Important".to_string()), vulnerability.severity);
+        assert_eq!(
+            Some("Elevation of Privilege".to_string()),
+            vulnerability.impact
+        );
+        assert_eq!(Some(7.1), vulnerability.score);
+        assert_eq!(vec!["5015811", "5015807"], vulnerability.kbs());
+        assert_eq!(
+            vec!["windows_10", "windows_server_2019"],
+            vulnerability.products()
+        );
+
+        let affected = &vulnerability.affected[1];
+        assert_eq!(
+            "Windows 10 Version 21H2 for x64-based Systems",
+            affected.name
+        );
+        assert_eq!(vec!["5015807"], affected.kbs);
+        assert_eq!(Some("10.0.19044.1826".to_string()), affected.fixed_build);
+    }
+}
@@ -0,0 +1,123 @@
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use chrono::Utc;
+use log::info;
+
+use super::{cvrf, Vulnerability, SOURCE_NAME, VENDOR};
+
+use crate::db::{self, Pool};
+use crate::utils::get_json;
+
+/// Monthly CVRF documents of the security updates.
+pub const API_URL: &str = "https://api.msrc.microsoft.com/cvrf/v3.0/updates";
+/// The documents revised shortly before the last import are imported again, as they keep
+/// being amended with the builds of the late updates.
+const REFRESH_WINDOW_DAYS: i64 = 30;
+
+fn store(database: &db::Database, vulnerability: &Vulnerability) -> Result<u32> {
+    let products = vulnerability.products();
+    if products.is_empty() {
+        return Ok(0);
+    }
+
+    let object_id = database
+        .upsert_object(db::models::NewObject::with(
+            Vulnerability::object_key(&vulnerability.cve),
+            serde_json::to_string(vulnerability)?,
+        ))
+        .map_err(|e| anyhow!(e))?;
+
+    let mut refs = db::models::References::default();
+    refs.push(db::models::Reference {
+        url: vulnerability.url(),
+        tags: vec!["Vendor Advisory".into()],
+    });
+    for kb in vulnerability.kbs() {
+        refs.push(db::models::Reference {
+            url: Vulnerability::kb_url(&kb),
+            tags: vec!["Patch".into(), "Vendor Advisory".into()],
+        });
+    }
+
+    let mut num_imported = 0;
+    for product in &products {
+        let new_cve = db::models::NewCVE::with(
+            SOURCE_NAME.into(),
+            VENDOR.into(),
+            product.clone(),
+            vulnerability.cve.clone(),
+            vulnerability.summary(),
+            vulnerability.score.unwrap_or_default(),
+            vulnerability.severity(),
+            vulnerability.vector.clone(),
+            refs.clone(),
+            Some(object_id),
+        );
+        if database.upsert_cve(new_cve).map_err(|e| anyhow!(e))? {
+            num_imported += 1;
+        }
+    }
+
+    Ok(num_imported)
+}
+
+/// Imports the CVRF documents revised since shortly before the last import, or all of them
+/// the first time and with `fresh`, creating the new records and updating the changed ones.
+pub fn run(pool: &Pool, fresh: bool) -> Result<u32> {
+    let client = reqwest::blocking::Client::builder()
+        .timeout(Some(Duration::from_secs(300)))
+        .user_agent(format!("kepler/{}", crate::version()))
+        .build()?;
+    let database = db::Database(pool.get()?);
+
+    let since = if fresh {
+        None
+    } else {
+        database
+            .get_synced_at(SOURCE_NAME)
+            .map_err(|e| anyhow!(e))?
+            .map(|since| {
+                (since - chrono::Duration::days(REFRESH_WINDOW_DAYS))
+                    .format("%Y-%m-%dT%H:%M:%S")
+                    .to_string()
+            })
+    };
+    let until = Utc::now();
+
+    // the API answers with XML unless asked for JSON
+    let updates: cvrf::Updates = get_json(&client, API_URL)?;
+    // the timestamps share the same format, so they sort like strings
+    let mut updates: Vec<cvrf::Update> = updates
+        .value
+        .into_iter()
+        .filter(|update| match &since {
+            Some(since) => update.current_release_date.as_str() >= since.as_str(),
+            None => true,
+        })
+        .collect();
+    // the oldest first, so that the later revisions of the same CVEs replace the previous ones
+    updates.sort_by(|a, b| a.current_release_date.cmp(&b.current_release_date));
+    info!("importing {} security update documents ...", updates.len());
+
+    let mut num_imported = 0;
+    for update in &updates {
+        let document: cvrf::Document = get_json(&client, &update.cvrf_url)?;
+        let vulnerabilities = document.vulnerabilities();
+        info!(
+            "importing {} vulnerabilities of {} ...",
+            vulnerabilities.len(),
+            update.id
+        );
+
+        for vulnerability in &vulnerabilities {
+            num_imported += store(&database, vulnerability)?;
+        }
+    }
+
+    database
+        .set_synced_at(db::models::NewSyncState::with(SOURCE_NAME.into(), until))
+        .map_err(|e| anyhow!(e))?;
+
+    Ok(num_imported)
+}
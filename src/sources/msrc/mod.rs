@@ -0,0 +1,252 @@
+//! CVEs of the Microsoft products published in the CVRF documents of the Microsoft Security
+//! Update Guide, with the KB articles and the builds fixing them in every affected product.
+
+use serde::{Deserialize, Serialize};
+use version_compare::Cmp;
+
+use crate::search::Query;
+use crate::utils::version_cmp;
+
+#[cfg(feature = "import")]
+pub mod cvrf;
+#[cfg(feature = "import")]
+pub mod import;
+
+pub const SOURCE_NAME: &str = "MSRC";
+/// Kept apart from the `microsoft` vendor of the NIST records.
+pub const VENDOR: &str = "@msrc";
+
+/// Product of a product name of the Security Update Guide, like `windows_10` for
+/// `Windows 10 Version 1809 for x64-based Systems`, named like the NIST products.
+pub fn product(name: &str) -> Option<String> {
+    let mut name = name.trim().to_lowercase();
+    if let Some(stripped) = name.strip_prefix("microsoft ") {
+        name = stripped.to_string();
+    }
+    for separator in &[" for ", " version ", " (", ","] {
+        if let Some(index) = name.find(separator) {
+            name.truncate(index);
+        }
+    }
+
+    let product = name
+        .trim()
+        .replace(|c: char| c.is_whitespace() || c == '-', "_");
+    if product.is_empty() {
+        return None;
+    }
+    Some(product)
+}
+
+/// Product affected by a vulnerability, fixed by the security updates of its KB articles.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Affected {
+    /// Like `Windows 10 Version 1809 for x64-based Systems`.
+    pub name: String,
+    /// Like `windows_10`.
+    pub product: String,
+    /// Like `5015811`.
+    #[serde(default)]
+    pub kbs: Vec<String>,
+    /// Like `10.0.17763.3165`, unknown for some products like the online services.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fixed_build: Option<String>,
+}
+
+#[derive(Debug, Default, Clone, Deserialize, Serialize)]
+pub struct Vulnerability {
+    pub cve: String,
+    /// Month of the security updates, like `2022-Jul`.
+    #[serde(default)]
+    pub release: String,
+    #[serde(default)]
+    pub title: String,
+    #[serde(default)]
+    pub description: String,
+    /// `Low`, `Moderate`, `Important` or `Critical`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub severity: Option<String>,
+    /// Like `Remote Code Execution`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub impact: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub score: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vector: Option<String>,
+    pub affected: Vec<Affected>,
+}
+
+impl Vulnerability {
+    pub fn object_key(cve: &str) -> String {
+        format!("{} (msrc)", cve)
+    }
+
+    /// Page of the vulnerability in the Security Update Guide.
+    pub fn url(&self) -> String {
+        format!(
+            "https://msrc.microsoft.com/update-guide/vulnerability/{}",
+            self.cve
+        )
+    }
+
+    /// Page of a KB article.
+    pub fn kb_url(kb: &str) -> String {
+        format!("https://support.microsoft.com/help/{}", kb)
+    }
+
+    /// Unique KB articles of the security updates.
+    pub fn kbs(&self) -> Vec<String> {
+        let mut kbs: Vec<String> = vec![];
+        for kb in self.affected.iter().flat_map(|affected| &affected.kbs) {
+            if !kbs.contains(kb) {
+                kbs.push(kb.clone());
+            }
+        }
+        kbs
+    }
+
+    pub fn summary(&self) -> String {
+        if self.description.trim().is_empty() {
+            return self.title.trim().to_string();
+        }
+        self.description.trim().to_string()
+    }
+
+    /// Severity rated by Microsoft, named like the NIST severities.
+    pub fn severity(&self) -> String {
+        match self.severity.as_deref().map(str::to_lowercase).as_deref() {
+            Some("critical") => "CRITICAL",
+            Some("important") => "HIGH",
+            Some("moderate") => "MEDIUM",
+            Some("low") => "LOW",
+            _ => "NONE",
+        }
+        .to_string()
+    }
+
+    /// Unique products affected.
+    pub fn products(&self) -> Vec<String> {
+        let mut products: Vec<String> = vec![];
+        for affected in &self.affected {
+            if !products.contains(&affected.product) {
+                products.push(affected.product.clone());
+            }
+        }
+        products
+    }
+
+    /// Matches the builds older than the one fixing their branch, like `10.0.19044` for
+    /// Windows 10 21H2, or older than any fixed build when their branch isn't listed, and any
+    /// version of the products without a known fixed build.
+    pub fn is_match(&self, query: &Query) -> bool {
+        // we need a version
+        let version = match &query.version {
+            Some(version) => version,
+            None => return false,
+        };
+
+        let mut fixed_builds = vec![];
+        for affected in &self.affected {
+            if affected.product != query.product {
+                continue;
+            }
+            match &affected.fixed_build {
+                Some(fixed_build) => fixed_builds.push(fixed_build),
+                None => return true,
+            }
+        }
+
+        let branch = |build: &str| build.rsplit_once('.').map(|(branch, _)| branch.to_string());
+        if let Some(version_branch) = branch(version) {
+            let same_branch: Vec<&String> = fixed_builds
+                .iter()
+                .copied()
+                .filter(|fixed_build| branch(fixed_build).as_ref() == Some(&version_branch))
+                .collect();
+            if !same_branch.is_empty() {
+                fixed_builds = same_branch;
+            }
+        }
+
+        fixed_builds
+            .iter()
+            .any(|fixed_build| version_cmp(version, fixed_build, Cmp::Lt))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{product, Affected, Vulnerability};
+    use crate::search::testing::query;
+
+    #[test]
+    fn can_name_products() {
+        assert_eq!(
+            Some("windows_10".to_string()),
+            product("Windows 10 Version 1809 for x64-based Systems")
+        );
+        assert_eq!(
+            Some("windows_server_2019".to_string()),
+            product("Windows Server 2019 (Server Core installation)")
+        );
+        assert_eq!(
+            Some("windows_server_2012_r2".to_string()),
+            product("Windows Server 2012 R2")
+        );
+        assert_eq!(
+            Some("office_2019".to_string()),
+            product("Microsoft Office 2019 for 32-bit editions")
+        );
+        assert_eq!(
+            Some("edge".to_string()),
+            product("Microsoft Edge (Chromium-based)")
+        );
+        assert_eq!(None, product(" "));
+    }
+
+    #[test]
+    fn can_match_fixed_builds() {
+        let vulnerability = Vulnerability {
+            cve: "CVE-2022-22022".into(),
+            severity: Some("Important".into()),
+            affected: vec![
+                Affected {
+                    name: "Windows 10 Version 1809 for x64-based Systems".into(),
+                    product: "windows_10".into(),
+                    kbs: vec!["5015811".into()],
+                    fixed_build: Some("10.0.17763.3165".into()),
+                },
+                Affected {
+                    name: "Windows 10 Version 21H2 for x64-based Systems".into(),
+                    product: "windows_10".into(),
+                    kbs: vec!["5015807".into()],
+                    fixed_build: Some("10.0.19044.1826".into()),
+                },
+                Affected {
+                    name: "Windows Server 2019".into(),
+                    product: "windows_server_2019".into(),
+                    kbs: vec!["5015811".into()],
+                    fixed_build: None,
+                },
+            ],
+            ..Default::default()
+        };
+
+        assert_eq!("HIGH", vulnerability.severity());
+        assert_eq!(
+            "https://msrc.microsoft.com/update-guide/vulnerability/CVE-2022-22022",
+            vulnerability.url()
+        );
+        assert_eq!(vec!["5015811", "5015807"], vulnerability.kbs());
+        assert_eq!(
+            vec!["windows_10", "windows_server_2019"],
+            vulnerability.products()
+        );
+        assert!(vulnerability.is_match(&query("windows_10", "10.0.19044.1766")));
+        assert!(!vulnerability.is_match(&query("windows_10", "10.0.19044.1826")));
+        assert!(!vulnerability.is_match(&query("windows_10", "10.0.17763.4000")));
+        assert!(vulnerability.is_match(&query("windows_10", "10.0.10240.1")));
+        assert!(vulnerability.is_match(&query("windows_server_2019", "10.0.17763.1")));
+        assert!(!vulnerability.is_match(&query("office_2019", "16.0.10386.20017")));
+    }
+}
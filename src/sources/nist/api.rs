@@ -0,0 +1,762 @@
+//! Client of the NVD CVE API 2.0, replacing the retired JSON 1.1 data feeds. The CVEs are
+//! converted to the 1.1 records, so that they're stored and matched like before.
+
+use std::fs::{self, File};
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use chrono::{Datelike, Duration as Days, NaiveDate, NaiveDateTime, Utc};
+use log::{info, warn};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use super::cve::{
+    item::{self, CVSSV2, CVSSV3},
+    list,
+    node::{self, Operator},
+};
+use crate::utils::{parse_json, send_with_retries, ImportError};
+
+pub const URL: &str = "https://services.nvd.nist.gov/rest/json/cves/2.0";
+pub const VERSION: &str = "2.0";
+
+/// Most CVEs the API returns per page.
+const RESULTS_PER_PAGE: usize = 2000;
+/// Longest range of dates the API accepts.
+const MAX_RANGE_DAYS: i64 = 120;
+/// Days covered by the `recent` and `modified` imports, like the feeds they replace.
+const RECENT_DAYS: i64 = 8;
+/// Pause between the requests to stay within the rate limits, 5 requests in 30 seconds
+/// without an API key and 50 with one.
+const DELAY: Duration = Duration::from_secs(6);
+const DELAY_WITH_KEY: Duration = Duration::from_millis(600);
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Page {
+    pub results_per_page: usize,
+    pub start_index: usize,
+    pub total_results: usize,
+    pub vulnerabilities: Vec<Vulnerability>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Vulnerability {
+    pub cve: Cve,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LangString {
+    pub lang: String,
+    pub value: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Weakness {
+    pub source: String,
+    #[serde(rename = "type")]
+    pub kind: String,
+    #[serde(default)]
+    pub description: Vec<LangString>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Reference {
+    pub url: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MetricV3 {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub cvss_data: CVSSV3,
+    #[serde(default)]
+    pub exploitability_score: f32,
+    #[serde(default)]
+    pub impact_score: f32,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MetricV2 {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub cvss_data: CVSSV2,
+    #[serde(default)]
+    pub base_severity: String,
+    #[serde(default)]
+    pub exploitability_score: f32,
+    #[serde(default)]
+    pub impact_score: f32,
+    pub ac_insuf_info: Option<bool>,
+    #[serde(default)]
+    pub obtain_all_privilege: bool,
+    #[serde(default)]
+    pub obtain_user_privilege: bool,
+    #[serde(default)]
+    pub obtain_other_privilege: bool,
+    pub user_interaction_required: Option<bool>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Metrics {
+    #[serde(default)]
+    pub cvss_metric_v31: Vec<MetricV3>,
+    #[serde(default)]
+    pub cvss_metric_v30: Vec<MetricV3>,
+    #[serde(default)]
+    pub cvss_metric_v2: Vec<MetricV2>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CpeMatch {
+    pub vulnerable: bool,
+    pub criteria: String,
+    pub version_start_including: Option<String>,
+    pub version_start_excluding: Option<String>,
+    pub version_end_including: Option<String>,
+    pub version_end_excluding: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Node {
+    #[serde(default)]
+    pub operator: Operator,
+    #[serde(default)]
+    pub negate: bool,
+    #[serde(default)]
+    pub cpe_match: Vec<CpeMatch>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Configuration {
+    pub operator: Option<Operator>,
+    #[serde(default)]
+    pub negate: bool,
+    pub nodes: Vec<Node>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Cve {
+    pub id: String,
+    pub source_identifier: Option<String>,
+    #[serde(default)]
+    pub vuln_status: String,
+    #[serde(default)]
+    pub descriptions: Vec<LangString>,
+    #[serde(default)]
+    pub metrics: Metrics,
+    #[serde(default)]
+    pub weaknesses: Vec<Weakness>,
+    #[serde(default)]
+    pub configurations: Vec<Configuration>,
+    #[serde(default)]
+    pub references: Vec<Reference>,
+}
+
+/// Primary metric of the NVD, or the first one provided.
+fn primary<T>(metrics: Vec<T>, kind: impl Fn(&T) -> &str) -> Option<T> {
+    let index = metrics
+        .iter()
+        .position(|m| kind(m) == "Primary")
+        .unwrap_or(0);
+    metrics.into_iter().nth(index)
+}
+
+impl From<Node> for node::Node {
+    fn from(node: Node) -> Self {
+        node::Node {
+            operator: node.operator,
+            children: vec![],
+            cpe_match: node
+                .cpe_match
+                .into_iter()
+                .map(|m| {
+                    let mut cpe_match = node::Match::with(m.vulnerable, m.criteria);
+                    cpe_match.version_start_including = m.version_start_including;
+                    cpe_match.version_start_excluding = m.version_start_excluding;
+                    cpe_match.version_end_including = m.version_end_including;
+                    cpe_match.version_end_excluding = m.version_end_excluding;
+                    cpe_match
+                })
+                .collect(),
+        }
+    }
+}
+
+impl Cve {
+    pub fn is_rejected(&self) -> bool {
+        self.vuln_status == "Rejected"
+    }
+
+    /// The CVE as a record of the 1.1 feeds. Each configuration becomes a root node, with
+    /// its nodes as children when they're combined, leaving out the negated ones the 1.1
+    /// records can't express.
+    pub fn into_item(self) -> item::CVE {
+        let nodes = self
+            .configurations
+            .into_iter()
+            .filter(|config| !config.negate)
+            .filter_map(|config| {
+                let mut nodes: Vec<node::Node> = config
+                    .nodes
+                    .into_iter()
+                    .filter(|node| !node.negate && !node.cpe_match.is_empty())
+                    .map(node::Node::from)
+                    .collect();
+                match nodes.len() {
+                    0 => None,
+                    1 => nodes.pop(),
+                    _ => Some(node::Node {
+                        operator: config.operator.unwrap_or_default(),
+                        children: nodes,
+                        cpe_match: vec![],
+                    }),
+                }
+            })
+            .collect();
+
+        let Metrics {
+            cvss_metric_v31,
+            cvss_metric_v30,
+            cvss_metric_v2,
+        } = self.metrics;
+        let metric_v3 = primary(cvss_metric_v31, |m| &m.kind)
+            .or_else(|| primary(cvss_metric_v30, |m| &m.kind))
+            .map(|m| item::ImpactMetricV3 {
+                cvss: m.cvss_data,
+                exploitability_score: m.exploitability_score,
+                impact_score: m.impact_score,
+            });
+        let metric_v2 = primary(cvss_metric_v2, |m| &m.kind).map(|m| item::ImpactMetricV2 {
+            cvss: m.cvss_data,
+            exploitability_score: m.exploitability_score,
+            impact_score: m.impact_score,
+            severity: m.base_severity,
+            ac_insuf_info: m.ac_insuf_info,
+            obtain_all_privilege: m.obtain_all_privilege,
+            obtain_user_privilege: m.obtain_user_privilege,
+            obtain_other_privilege: m.obtain_other_privilege,
+            user_interaction_required: m.user_interaction_required,
+        });
+
+        item::CVE {
+            cve: item::Info {
+                meta: item::Meta {
+                    id: self.id,
+                    assigner: self.source_identifier,
+                },
+                problemtype: item::ProblemType {
+                    problemtype_data: self
+                        .weaknesses
+                        .into_iter()
+                        .map(|w| item::ProblemTypeData {
+                            description: w
+                                .description
+                                .into_iter()
+                                .map(|d| item::DescriptionData {
+                                    lang: d.lang,
+                                    value: d.value,
+                                })
+                                .collect(),
+                        })
+                        .collect(),
+                },
+                references: item::References {
+                    reference_data: self
+                        .references
+                        .into_iter()
+                        .map(|r| item::Reference {
+                            name: r.url.clone(),
+                            url: r.url,
+                            tags: r.tags,
+                        })
+                        .collect(),
+                },
+                description: item::Description {
+                    description_data: self
+                        .descriptions
+                        .into_iter()
+                        .map(|d| item::DescriptionData {
+                            lang: d.lang,
+                            value: d.value,
+                        })
+                        .collect(),
+                },
+            },
+            impact: item::Impact {
+                metric_v2,
+                metric_v3,
+            },
+            configurations: item::Configurations {
+                data_version: "4.0".into(),
+                nodes,
+            },
+        }
+    }
+}
+
+/// Range of dates of the CVEs published, or modified, within it.
+#[derive(Debug, PartialEq)]
+pub struct Window {
+    pub modified: bool,
+    pub start: NaiveDateTime,
+    pub end: NaiveDateTime,
+}
+
+impl Window {
+    pub fn params(&self) -> [(&'static str, String); 2] {
+        let format = |time: &NaiveDateTime| time.format("%Y-%m-%dT%H:%M:%S%.3f").to_string();
+        if self.modified {
+            [
+                ("lastModStartDate", format(&self.start)),
+                ("lastModEndDate", format(&self.end)),
+            ]
+        } else {
+            [
+                ("pubStartDate", format(&self.start)),
+                ("pubEndDate", format(&self.end)),
+            ]
+        }
+    }
+}
+
+/// Splits the range in windows the API accepts.
+pub fn split(modified: bool, start: NaiveDateTime, end: NaiveDateTime) -> Vec<Window> {
+    let mut windows = vec![];
+    let mut from = start;
+    while from < end {
+        let to = (from + Days::days(MAX_RANGE_DAYS)).min(end);
+        windows.push(Window {
+            modified,
+            start: from,
+            end: to,
+        });
+        from = to;
+    }
+    windows
+}
+
+/// Windows of the CVEs of the import: published in the year, the 2002 one including the
+/// older CVEs like its feed did, published in the last days for `recent`, or modified in
+/// the last days for `modified`.
+pub fn windows(year: &str, now: NaiveDateTime) -> Result<Vec<Window>, String> {
+    match year {
+        "recent" => Ok(split(false, now - Days::days(RECENT_DAYS), now)),
+        "modified" => Ok(split(true, now - Days::days(RECENT_DAYS), now)),
+        _ => {
+            let year = year
+                .parse::<i32>()
+                .ok()
+                .filter(|year| *year >= 2002 && *year <= now.year())
+                .ok_or_else(|| format!("invalid year {}", year))?;
+            let first = if year == 2002 { 1988 } else { year };
+            Ok(split(
+                false,
+                NaiveDate::from_ymd(first, 1, 1).and_hms(0, 0, 0),
+                NaiveDate::from_ymd(year + 1, 1, 1)
+                    .and_hms(0, 0, 0)
+                    .min(now),
+            ))
+        }
+    }
+}
+
+/// Client paginating through the API, with the API key set in `NVD_API_KEY` if any.
+pub struct Client {
+    http: reqwest::blocking::Client,
+    api_key: Option<String>,
+    delay: Duration,
+    last_request: Option<Instant>,
+}
+
+impl Client {
+    pub fn from_env() -> Result<Self, ImportError> {
+        let api_key = std::env::var("NVD_API_KEY").ok().filter(|k| !k.is_empty());
+        if api_key.is_none() {
+            warn!("NVD_API_KEY is not set, the requests are rate limited to 5 in 30 seconds");
+        }
+
+        Ok(Self {
+            http: reqwest::blocking::Client::builder()
+                .timeout(Some(Duration::from_secs(300)))
+                .build()
+                .map_err(|e| format!("could not create http client: {}", e))?,
+            delay: if api_key.is_some() {
+                DELAY_WITH_KEY
+            } else {
+                DELAY
+            },
+            api_key,
+            last_request: None,
+        })
+    }
+
+    /// Waits for the pause between the requests.
+    fn throttle(&mut self) {
+        if let Some(last) = self.last_request {
+            let elapsed = last.elapsed();
+            if elapsed < self.delay {
+                thread::sleep(self.delay - elapsed);
+            }
+        }
+        self.last_request = Some(Instant::now());
+    }
+
+    fn page(&mut self, window: &Window, start_index: usize) -> Result<Page, ImportError> {
+        let mut params = window.params().to_vec();
+        params.push(("resultsPerPage", RESULTS_PER_PAGE.to_string()));
+        params.push(("startIndex", start_index.to_string()));
+        self.get(URL, &params)
+    }
+
+    /// Requests an API of the NVD, like the CPE one, retrying when refused because of the
+    /// rate limits.
+    pub fn get<T: DeserializeOwned>(
+        &mut self,
+        url: &str,
+        params: &[(&'static str, String)],
+    ) -> Result<T, ImportError> {
+        let res = send_with_retries(url, || {
+            self.throttle();
+            let req = self.http.get(url).query(params);
+            match &self.api_key {
+                Some(api_key) => req.header("apiKey", api_key),
+                None => req,
+            }
+        })?;
+        parse_json(url, res)
+    }
+
+    /// All the CVEs of the window, page after page.
+    pub fn fetch(&mut self, window: &Window) -> Result<Vec<Cve>, ImportError> {
+        let mut cves = vec![];
+        let mut start_index = 0;
+        loop {
+            let page = self.page(window, start_index)?;
+            let num_results = page.vulnerabilities.len();
+            cves.extend(page.vulnerabilities.into_iter().map(|v| v.cve));
+
+            start_index = page.start_index + num_results;
+            info!(
+                "fetched {} of {} CVEs from {} to {}",
+                start_index, page.total_results, window.start, window.end
+            );
+            if num_results == 0 || page.results_per_page == 0 || start_index >= page.total_results {
+                return Ok(cves);
+            }
+        }
+    }
+}
+
+/// The CVEs modified within the range, along with the ids of the ones rejected since.
+pub fn fetch_modified(
+    start: NaiveDateTime,
+    end: NaiveDateTime,
+) -> Result<(list::List, Vec<String>), ImportError> {
+    let mut client = Client::from_env()?;
+
+    let mut items = vec![];
+    let mut rejected = vec![];
+    for window in &split(true, start, end) {
+        for cve in client.fetch(window)? {
+            if cve.is_rejected() {
+                rejected.push(cve.id);
+            } else {
+                items.push(cve.into_item());
+            }
+        }
+    }
+
+    Ok((list::List::with(items), rejected))
+}
+
+#[derive(Serialize)]
+struct Feed<'a> {
+    #[serde(rename = "CVE_Items")]
+    items: &'a [item::CVE],
+}
+
+/// Fetches the CVEs of the year from the API, unless already fetched, into a file with the
+/// format of the 1.1 feeds, then parses it.
+pub fn setup(
+    year: &str,
+    data_path: &Path,
+    fresh: bool,
+) -> Result<(PathBuf, list::List), ImportError> {
+    let mut file_name = data_path.to_path_buf();
+    file_name.push(format!("nvdcve-{}-{}.json", VERSION, year));
+
+    if fresh && file_name.exists() {
+        info!("removing {}", file_name.display());
+        fs::remove_file(&file_name)
+            .map_err(|e| format!("could not remove {}: {}", file_name.display(), e))?;
+    }
+
+    if !file_name.exists() {
+        let windows = windows(year, Utc::now().naive_utc())?;
+        let mut client = Client::from_env()?;
+
+        let mut items = vec![];
+        for window in &windows {
+            for cve in client.fetch(window)? {
+                if !cve.is_rejected() {
+                    items.push(cve.into_item());
+                }
+            }
+        }
+
+        // written once complete, so that an interrupted import doesn't leave a partial file
+        let partial = file_name.with_extension("json.part");
+        let file = File::create(&partial)
+            .map_err(|e| format!("could not create {}: {}", partial.display(), e))?;
+        serde_json::to_writer(BufWriter::new(file), &Feed { items: &items })
+            .map_err(|e| format!("could not write {}: {}", partial.display(), e))?;
+        fs::rename(&partial, &file_name)
+            .map_err(|e| format!("could not write {}: {}", file_name.display(), e))?;
+    } else {
+        info!("found {}", file_name.display());
+    }
+
+    info!("reading {} ...", file_name.display());
+
+    let start = Instant::now();
+    let cve_list = list::List::parse(&file_name).map_err(|e| {
+        ImportError::from(format!("could not parse {}: {}", file_name.display(), e))
+            .quarantine(&file_name)
+    })?;
+
+    info!("loaded {} CVEs in {:?}", cve_list.len(), start.elapsed());
+
+    Ok((file_name, cve_list))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::{windows, Page, Window};
+    use crate::search::Query;
+
+    const PAGE: &str = r#"{
+        "resultsPerPage": 1,
+        "startIndex": 0,
+        "totalResults": 1,
+        "format": "NVD_CVE",
+        "version": "2.0",
+        "timestamp": "2022-06-20T10:00:00.000",
+        "vulnerabilities": [
+            {
+                "cve": {
+                    "id": "CVE-2021-3517",
+                    "sourceIdentifier": "secalert@redhat.com",
+                    "published": "2021-05-19T14:15:07.833",
+                    "lastModified": "2022-05-13T20:52:24.793",
+                    "vulnStatus": "Analyzed",
+                    "descriptions": [
+                        { "lang": "en", "value": "There is a flaw in the xml entity encoding functionality of libxml2." }
+                    ],
+                    "metrics": {
+                        "cvssMetricV31": [
+                            {
+                                "source": "nvd@nist.gov",
+                                "type": "Primary",
+                                "cvssData": {
+                                    "version": "3.1",
+                                    "vectorString": "CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:L/I:L/A:H",
+                                    "attackVector": "NETWORK",
+                                    "attackComplexity": "LOW",
+                                    "privilegesRequired": "NONE",
+                                    "userInteraction": "NONE",
+                                    "scope": "UNCHANGED",
+                                    "confidentialityImpact": "LOW",
+                                    "integrityImpact": "LOW",
+                                    "availabilityImpact": "HIGH",
+                                    "baseScore": 8.6,
+                                    "baseSeverity": "HIGH"
+                                },
+                                "exploitabilityScore": 3.9,
+                                "impactScore": 4.7
+                            }
+                        ],
+                        "cvssMetricV2": [
+                            {
+                                "source": "nvd@nist.gov",
+                                "type": "Primary",
+                                "cvssData": {
+                                    "version": "2.0",
+                                    "vectorString": "AV:N/AC:L/Au:N/C:P/I:P/A:P",
+                                    "accessVector": "NETWORK",
+                                    "accessComplexity": "LOW",
+                                    "authentication": "NONE",
+                                    "confidentialityImpact": "PARTIAL",
+                                    "integrityImpact": "PARTIAL",
+                                    "availabilityImpact": "PARTIAL",
+                                    "baseScore": 7.5
+                                },
+                                "baseSeverity": "HIGH",
+                                "exploitabilityScore": 10.0,
+                                "impactScore": 6.4,
+                                "acInsufInfo": false,
+                                "obtainAllPrivilege": false,
+                                "obtainUserPrivilege": false,
+                                "obtainOtherPrivilege": false,
+                                "userInteractionRequired": false
+                            }
+                        ]
+                    },
+                    "weaknesses": [
+                        {
+                            "source": "nvd@nist.gov",
+                            "type": "Primary",
+                            "description": [{ "lang": "en", "value": "CWE-787" }]
+                        },
+                        {
+                            "source": "secalert@redhat.com",
+                            "type": "Secondary",
+                            "description": [
+                                { "lang": "en", "value": "CWE-787" },
+                                { "lang": "en", "value": "NVD-CWE-noinfo" }
+                            ]
+                        }
+                    ],
+                    "configurations": [
+                        {
+                            "nodes": [
+                                {
+                                    "operator": "OR",
+                                    "negate": false,
+                                    "cpeMatch": [
+                                        {
+                                            "vulnerable": true,
+                                            "criteria": "cpe:2.3:a:xmlsoft:libxml2:*:*:*:*:*:*:*:*",
+                                            "versionEndExcluding": "2.9.11",
+                                            "matchCriteriaId": "C5E5B9E1-0F3B-4A8B-9E0C-3B6B5F3C1E55"
+                                        }
+                                    ]
+                                }
+                            ]
+                        },
+                        {
+                            "operator": "AND",
+                            "nodes": [
+                                {
+                                    "operator": "OR",
+                                    "negate": false,
+                                    "cpeMatch": [
+                                        {
+                                            "vulnerable": true,
+                                            "criteria": "cpe:2.3:o:netapp:h410c_firmware:-:*:*:*:*:*:*:*",
+                                            "matchCriteriaId": "234DEFE0-5CE5-4B0A-96B8-5D227CB8ED31"
+                                        }
+                                    ]
+                                },
+                                {
+                                    "operator": "OR",
+                                    "negate": false,
+                                    "cpeMatch": [
+                                        {
+                                            "vulnerable": false,
+                                            "criteria": "cpe:2.3:h:netapp:h410c:-:*:*:*:*:*:*:*",
+                                            "matchCriteriaId": "CDDF61B7-EC5C-467C-B710-B89F502CD04F"
+                                        }
+                                    ]
+                                }
+                            ]
+                        }
+                    ],
+                    "references": [
+                        {
+                            "url": "https://bugzilla.redhat.com/show_bug.cgi?id=1954232",
+                            "source": "secalert@redhat.com",
+                            "tags": ["Issue Tracking", "Patch", "Third Party Advisory"]
+                        }
+                    ]
+                }
+            }
+        ]
+    }"#;
+
+    #[test]
+    fn can_convert_to_feed_records() {
+        let page: Page = serde_json::from_str(PAGE).unwrap();
+        assert_eq!(1, page.total_results);
+
+        let cve = page.vulnerabilities.into_iter().next().unwrap().cve;
+        assert!(!cve.is_rejected());
+
+        let mut item = cve.into_item();
+        assert_eq!("CVE-2021-3517", item.id());
+        assert!(item.summary().starts_with("There is a flaw"));
+        assert_eq!(7.5, item.score());
+        assert_eq!("HIGH", item.severity());
+        assert_eq!(8.6, item.impact.metric_v3.as_ref().unwrap().cvss.base_score);
+        assert_eq!(
+            vec!["Issue Tracking", "Patch", "Third Party Advisory"],
+            item.cve.references.reference_data[0].tags
+        );
+        assert_eq!(vec!["CWE-787"], item.weaknesses());
+
+        assert_eq!(2, item.configurations.nodes.len());
+        assert_eq!(2, item.configurations.nodes[1].children.len());
+        assert_eq!(3, item.collect_unique_products().len());
+
+        let query = |version: &str| Query {
+            vendor: None,
+            product: "libxml2".into(),
+            version: Some(version.into()),
+            ..Default::default()
+        };
+        assert!(item.is_match(&query("2.9.10")));
+        assert!(!item.is_match(&query("2.9.11")));
+    }
+
+    #[test]
+    fn can_split_years_in_windows() {
+        let now = NaiveDate::from_ymd(2022, 6, 20).and_hms(10, 0, 0);
+
+        let year = windows("2021", now).unwrap();
+        assert_eq!(4, year.len());
+        assert_eq!(
+            NaiveDate::from_ymd(2021, 1, 1).and_hms(0, 0, 0),
+            year[0].start
+        );
+        assert_eq!(year[0].end, year[1].start);
+        assert_eq!(
+            NaiveDate::from_ymd(2022, 1, 1).and_hms(0, 0, 0),
+            year[3].end
+        );
+
+        // the current year stops now
+        let year = windows("2022", now).unwrap();
+        assert_eq!(now, year.last().unwrap().end);
+
+        assert_eq!(
+            vec![Window {
+                modified: true,
+                start: NaiveDate::from_ymd(2022, 6, 12).and_hms(10, 0, 0),
+                end: now,
+            }],
+            windows("modified", now).unwrap()
+        );
+
+        // the modifications since the last sync, however old
+        let since = NaiveDate::from_ymd(2021, 12, 1).and_hms(0, 0, 0);
+        let modified = super::split(true, since, now);
+        assert_eq!(2, modified.len());
+        assert!(modified.iter().all(|window| window.modified));
+        assert_eq!(now, modified[1].end);
+
+        assert!(windows("2001", now).is_err());
+        assert!(windows("2023", now).is_err());
+        assert!(windows("latest", now).is_err());
+    }
+}
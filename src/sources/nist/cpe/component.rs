@@ -34,13 +34,150 @@ impl FromStr for Component {
     }
 }
 
+/// Relation between the components of a source and of a target CPE, as defined by the
+/// CPE 2.3 name matching specification (NISTIR 7696).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Relation {
+    Disjoint,
+    Subset,
+    Superset,
+    Equal,
+    Undefined,
+}
+
+impl Relation {
+    /// Whether the source matches the target, being equal to it or a superset of it.
+    pub fn is_match(self) -> bool {
+        matches!(self, Relation::Superset | Relation::Equal)
+    }
+}
+
+/// Character of a value, either quoted by a backslash or not.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Token {
+    Literal(char),
+    Wildcard(char),
+}
+
+fn tokens(value: &str) -> Vec<Token> {
+    let mut tokens = vec![];
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        tokens.push(match c {
+            '\\' => Token::Literal(chars.next().unwrap_or('\\')),
+            '*' | '?' => Token::Wildcard(c),
+            _ => Token::Literal(c),
+        });
+    }
+    tokens
+}
+
+/// Unquoted, lowercase characters of a value.
+fn literal(tokens: &[Token]) -> Vec<char> {
+    tokens
+        .iter()
+        .flat_map(|token| match token {
+            Token::Literal(c) | Token::Wildcard(c) => c.to_lowercase(),
+        })
+        .collect()
+}
+
+/// Whether the value of a source, with wildcards at its beginning or at its end, matches the
+/// one of a target: `*` matches any number of characters, and `?` one character at most.
+fn is_wildcard_match(source: &str, target: &str) -> bool {
+    let mut source = tokens(source);
+    let target = literal(&tokens(target));
+
+    // the characters the wildcards match before and after the literal part, any if `None`
+    let mut leading = Some(0);
+    if source.first() == Some(&Token::Wildcard('*')) {
+        source.remove(0);
+        leading = None;
+    } else {
+        while source.first() == Some(&Token::Wildcard('?')) {
+            source.remove(0);
+            leading = leading.map(|n| n + 1);
+        }
+    }
+    let mut trailing = Some(0);
+    if source.last() == Some(&Token::Wildcard('*')) {
+        source.pop();
+        trailing = None;
+    } else {
+        while source.last() == Some(&Token::Wildcard('?')) {
+            source.pop();
+            trailing = trailing.map(|n| n + 1);
+        }
+    }
+
+    let middle = literal(&source);
+    if middle.len() > target.len() {
+        return false;
+    }
+    (0..=target.len() - middle.len()).any(|index| {
+        let after = target.len() - index - middle.len();
+        target[index..index + middle.len()] == middle[..]
+            && leading.map_or(true, |n| index <= n)
+            && trailing.map_or(true, |n| after <= n)
+    })
+}
+
 impl Component {
-    #[allow(dead_code)]
-    fn matches(&self, val: &str) -> bool {
+    /// Component of a value, quoting the characters special to the CPE formatted strings.
+    pub fn quoted(val: &str) -> Self {
+        let mut quoted = String::new();
+        for c in val.chars() {
+            if !c.is_alphanumeric() && c != '_' && c != '.' && c != '-' {
+                quoted.push('\\');
+            }
+            quoted.push(c);
+        }
+        Component::Value(quoted)
+    }
+
+    /// Whether the component matches the value given, taken literally.
+    pub fn matches(&self, val: &str) -> bool {
+        self.compare(&Component::quoted(val)).is_match()
+    }
+
+    /// Relation of the component of a source CPE to the one of a target CPE, the targets with
+    /// wildcards being undefined.
+    pub fn compare(&self, target: &Component) -> Relation {
+        if target.has_wildcards() {
+            return Relation::Undefined;
+        }
+
+        match (self, target) {
+            (Component::Any, Component::Any) => Relation::Equal,
+            (Component::Any, _) => Relation::Superset,
+            (Component::NotApplicable, Component::Any) => Relation::Subset,
+            (Component::NotApplicable, Component::NotApplicable) => Relation::Equal,
+            (Component::NotApplicable, Component::Value(_)) => Relation::Disjoint,
+            (Component::Value(_), Component::Any) => Relation::Subset,
+            (Component::Value(_), Component::NotApplicable) => Relation::Disjoint,
+            (Component::Value(source), Component::Value(target)) => {
+                if self.has_wildcards() {
+                    if is_wildcard_match(source, target) {
+                        Relation::Superset
+                    } else {
+                        Relation::Disjoint
+                    }
+                } else if literal(&tokens(source)) == literal(&tokens(target)) {
+                    Relation::Equal
+                } else {
+                    Relation::Disjoint
+                }
+            }
+        }
+    }
+
+    /// Whether the value has unquoted `*` or `?` wildcards.
+    pub fn has_wildcards(&self) -> bool {
         match self {
-            Component::Any => true,
-            Component::NotApplicable => false,
-            Component::Value(v) => v == val,
+            Component::Value(v) => tokens(v)
+                .iter()
+                .any(|token| matches!(token, Token::Wildcard(_))),
+            _ => false,
         }
     }
 
@@ -75,7 +212,7 @@ impl fmt::Display for Component {
 mod tests {
     use std::collections::HashMap;
 
-    use super::Component;
+    use super::{Component, Relation};
 
     #[test]
     fn can_parse_strings_correctly() {
@@ -121,4 +258,57 @@ mod tests {
             assert_eq!(m.1, c.matches(m.0));
         }
     }
+
+    #[test]
+    fn can_compare_components_per_the_specification() {
+        // table 6-2 of NISTIR 7696, CPE name matching
+        let table = vec![
+            ("*", "*", Relation::Equal),
+            ("*", "-", Relation::Superset),
+            ("*", "foo", Relation::Superset),
+            ("*", "foo*", Relation::Undefined),
+            ("-", "*", Relation::Subset),
+            ("-", "-", Relation::Equal),
+            ("-", "foo", Relation::Disjoint),
+            ("-", "foo*", Relation::Undefined),
+            ("foo", "*", Relation::Subset),
+            ("foo", "-", Relation::Disjoint),
+            ("foo", "foo", Relation::Equal),
+            ("foo", "FOO", Relation::Equal),
+            ("foo", "bar", Relation::Disjoint),
+            ("foo", "foo*", Relation::Undefined),
+            ("foo*", "foobar", Relation::Superset),
+            ("foo*", "barfoo", Relation::Disjoint),
+            ("*bar", "foobar", Relation::Superset),
+            ("foo?", "foo", Relation::Superset),
+            ("foo?", "foob", Relation::Superset),
+            ("foo?", "foobar", Relation::Disjoint),
+            ("??bar", "foobar", Relation::Disjoint),
+            ("???bar", "foobar", Relation::Superset),
+            ("foo*", "*", Relation::Subset),
+            ("foo*", "-", Relation::Disjoint),
+            ("foo*", "ba?", Relation::Undefined),
+            // quoted characters are no wildcards
+            ("foo\\*", "foo\\*", Relation::Equal),
+            ("foo\\*", "foobar", Relation::Disjoint),
+            ("mpx\\/sdx", "mpx\\/sdx", Relation::Equal),
+        ];
+
+        for (source, target, relation) in table {
+            let source = source.parse::<Component>().unwrap();
+            let target = target.parse::<Component>().unwrap();
+            assert_eq!(
+                relation,
+                source.compare(&target),
+                "{} to {}",
+                source,
+                target
+            );
+        }
+
+        assert!(Component::Value("mpx\\/sdx".into()).matches("mpx/sdx"));
+        assert!(Component::Value("1.*".into()).matches("1.2"));
+        assert!(!Component::Value("1.?".into()).matches("1.2.3"));
+        assert!(!Component::Value("foo".into()).matches("*"));
+    }
 }
@@ -16,6 +16,85 @@ pub struct Product {
     pub product: String,
 }
 
+/// Software a product runs on, like `wordpress` for its plugins.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct Target {
+    pub vendor: String,
+    pub product: String,
+    pub target_sw: String,
+}
+
+/// Operating system a product runs on, given by a CPE like `o:linux:linux_kernel:5.10` or
+/// `cpe:2.3:o:linux:linux_kernel:5.10:*:*:*:*:*:*:*`, any version if missing.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Platform {
+    pub vendor: String,
+    pub product: String,
+    pub version: Option<String>,
+}
+
+impl FromStr for Platform {
+    type Err = String;
+
+    fn from_str(val: &str) -> Result<Self, Self::Err> {
+        let val = val.strip_prefix("cpe:2.3:").unwrap_or(val);
+        let mut fields = val.split(':');
+        if fields.next() != Some("o") {
+            return Err("the operating system must be a CPE of part o".into());
+        }
+
+        let mut value = || {
+            fields
+                .next()
+                .map(str::to_lowercase)
+                .filter(|field| !field.is_empty() && field != "*" && field != "-")
+        };
+        let vendor = value().ok_or("the operating system CPE must have a vendor")?;
+        let product = value().ok_or("the operating system CPE must have a product")?;
+        let version = value();
+
+        Ok(Self {
+            vendor,
+            product,
+            version,
+        })
+    }
+}
+
+/// Splits a version like `7.4 update p1` or `7.4 p1` into the version and its update.
+pub fn split_update(version: &str) -> (&str, Option<&str>) {
+    let version = version.trim();
+    let (version, update) = match version.split_once(' ') {
+        Some((version, update)) => (version, update.trim()),
+        None => return (version, None),
+    };
+    let update = match update.get(..7) {
+        Some(prefix) if prefix.eq_ignore_ascii_case("update ") => update[7..].trim(),
+        _ => update,
+    };
+    (version, Some(update).filter(|update| !update.is_empty()))
+}
+
+/// Lowercase letters, digits and dots of a version or an update, like `sp1` for `SP_1`.
+fn normalize(value: &str) -> String {
+    value
+        .chars()
+        .filter(|c| c.is_alphanumeric() || *c == '.')
+        .collect::<String>()
+        .to_lowercase()
+}
+
+/// Whether the component matches the value, both normalized unless the component has
+/// wildcards.
+fn is_normalized_match(component: &Component, value: &str) -> bool {
+    match component {
+        Component::Value(my_value) if !component.has_wildcards() => {
+            normalize(my_value) == normalize(value)
+        }
+        _ => component.matches(value),
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct CPE23 {
     pub what: Type,
@@ -121,48 +200,103 @@ impl CPE23 {
         norm
     }
 
-    pub fn is_product_match(&self, product: &str) -> bool {
+    /// Whether the CPE is about the product, running on the target software when the CPE
+    /// specifies one.
+    pub fn is_product_match(&self, product: &str, target_sw: Option<&str>) -> bool {
         if self.product.is_any() {
             return true;
         } else if self.product.is_na() {
             return false;
         }
 
-        let my_product = if let Component::Value(software) = &self.target_sw {
+        if let (Component::Value(software), Some(target_sw)) = (&self.target_sw, target_sw) {
+            return self.product.matches(product)
+                && Self::normalize_target_software(software)
+                    == Self::normalize_target_software(&target_sw.to_lowercase());
+        }
+
+        if let Component::Value(software) = &self.target_sw {
             // if target_sw is set to a value, then the product name must be created from it
             // plus the actual product, so that if target_sw=node.js and pruduct=tar (<-- this
-            // one alone would false positive on gnu tar for instance), my_product becomes node-tar
-            format!(
-                "{}-{}",
-                Self::normalize_target_software(software),
-                self.product
-            )
-        } else {
-            self.product.to_string()
-        };
+            // one alone would false positive on gnu tar for instance), the product must be node-tar
+            let prefix = format!("{}-", Self::normalize_target_software(software));
+            return match product.strip_prefix(&prefix) {
+                Some(product) => self.product.matches(product),
+                None => false,
+            };
+        }
+
+        self.product.matches(product)
+    }
 
-        product == my_product
+    /// Whether the CPE is about the product of the operating system, whatever its version.
+    pub fn is_platform_product(&self, platform: &Platform) -> bool {
+        self.what == Type::OperatingSystem
+            && self.vendor.matches(&platform.vendor)
+            && self.product.matches(&platform.product)
     }
 
+    /// Whether the CPE is about the version, given along with its update like `7.4 p1`.
     pub fn is_version_match(&self, version: &str) -> bool {
+        let (version, update) = split_update(version);
+        self.is_release_match(version, update)
+    }
+
+    /// Whether the CPE is about the version and the update.
+    pub fn is_release_match(&self, version: &str, update: Option<&str>) -> bool {
         if self.version.is_any() {
-            return true;
+            return self.is_update_match(update);
         } else if self.version.is_na() {
             return false;
+        } else if self.version.has_wildcards() {
+            // like `5.4.*`
+            return self.version.matches(version) && self.is_update_match(update);
         }
-        let my_version = if self.update.is_value() {
-            format!("{} {}", self.version, self.update)
-        } else {
-            self.version.to_string()
+
+        // like `7.4p1` for the version `7.4` and the update `p1`
+        if let (Component::Value(my_update), None) = (&self.update, update) {
+            if normalize(version) == normalize(&format!("{}{}", self.version, my_update)) {
+                return true;
+            }
+        }
+
+        version_cmp(version, &self.version.to_string(), Cmp::Eq) && self.is_update_match(update)
+    }
+
+    /// Whether the CPE is about the update: the CPEs of any update match every one, the ones
+    /// without an update only the versions without one, and the ones of an update only that
+    /// update.
+    pub fn is_update_match(&self, update: Option<&str>) -> bool {
+        match update {
+            Some(update) => is_normalized_match(&self.update, update),
+            // the versions without an update are the not applicable ones
+            None => self.update.compare(&Component::NotApplicable).is_match(),
+        }
+    }
+
+    /// Whether the CPE is about the edition, when both specify one, given by the edition or the
+    /// software edition of the CPE.
+    pub fn is_edition_match(&self, edition: Option<&str>) -> bool {
+        let edition = match edition {
+            Some(edition) => edition,
+            None => return true,
         };
 
-        version_cmp(version, &my_version, Cmp::Eq)
+        let editions: Vec<&Component> = [&self.edition, &self.sw_edition]
+            .iter()
+            .copied()
+            .filter(|component| component.is_value())
+            .collect();
+        editions.is_empty()
+            || editions
+                .iter()
+                .any(|component| is_normalized_match(component, edition))
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::CPE23;
+    use super::{split_update, Platform, CPE23};
     use std::collections::HashMap;
 
     #[test]
@@ -283,10 +417,32 @@ mod tests {
         for (s, m) in table {
             let res = s.parse::<CPE23>();
             assert!(res.is_ok());
-            assert_eq!(m.1, res.unwrap().is_product_match(m.0));
+            assert_eq!(m.1, res.unwrap().is_product_match(m.0, None));
         }
     }
 
+    #[test]
+    fn can_match_target_software() {
+        let plugin: CPE23 = "cpe:2.3:a:woocommerce:woocommerce:*:*:*:*:*:wordpress:*:*"
+            .parse()
+            .unwrap();
+        assert!(plugin.is_product_match("woocommerce", Some("wordpress")));
+        assert!(plugin.is_product_match("woocommerce", Some("WordPress")));
+        assert!(plugin.is_product_match("wordpress-woocommerce", None));
+        assert!(!plugin.is_product_match("woocommerce", None));
+        assert!(!plugin.is_product_match("woocommerce", Some("joomla")));
+
+        let module: CPE23 = "cpe:2.3:a:isaacs:tar:*:*:*:*:*:node.js:*:*"
+            .parse()
+            .unwrap();
+        assert!(module.is_product_match("tar", Some("node.js")));
+
+        // CPEs not specifying the target software match on any
+        let library: CPE23 = "cpe:2.3:a:gnu:tar:*:*:*:*:*:*:*:*".parse().unwrap();
+        assert!(library.is_product_match("tar", Some("wordpress")));
+        assert!(library.is_product_match("tar", None));
+    }
+
     #[test]
     fn can_match_versions_correctly() {
         struct VersionMatch(&'static str, bool);
@@ -343,4 +499,94 @@ mod tests {
             assert_eq!(m.1, res.unwrap().is_version_match(m.0));
         }
     }
+
+    #[test]
+    fn can_match_updates() {
+        assert_eq!(("7.4", Some("p1")), split_update("7.4 update p1"));
+        assert_eq!(("7.4", Some("p1")), split_update("7.4 p1"));
+        assert_eq!(("7.4p1", None), split_update("7.4p1"));
+
+        let cpe: CPE23 = "cpe:2.3:a:openbsd:openssh:7.4:p1:*:*:*:*:*:*"
+            .parse()
+            .unwrap();
+        assert!(cpe.is_version_match("7.4 update p1"));
+        assert!(cpe.is_version_match("7.4p1"));
+        assert!(cpe.is_release_match("7.4", Some("P1")));
+        assert!(!cpe.is_release_match("7.4", Some("p2")));
+        assert!(!cpe.is_release_match("7.4", None));
+
+        let cpe: CPE23 = "cpe:2.3:a:openbsd:openssh:7.4:-:*:*:*:*:*:*"
+            .parse()
+            .unwrap();
+        assert!(cpe.is_release_match("7.4", None));
+        assert!(!cpe.is_release_match("7.4", Some("p1")));
+
+        let cpe: CPE23 = "cpe:2.3:a:openbsd:openssh:7.4:*:*:*:*:*:*:*"
+            .parse()
+            .unwrap();
+        assert!(cpe.is_release_match("7.4", Some("p1")));
+        assert!(!cpe.is_release_match("7.5", Some("p1")));
+    }
+
+    #[test]
+    fn can_match_editions() {
+        let cpe: CPE23 = "cpe:2.3:a:oracle:mysql:8.0.1:*:*:*:enterprise:*:*:*"
+            .parse()
+            .unwrap();
+        assert!(cpe.is_edition_match(None));
+        assert!(cpe.is_edition_match(Some("Enterprise")));
+        assert!(!cpe.is_edition_match(Some("community")));
+
+        let cpe: CPE23 = "cpe:2.3:a:oracle:mysql:8.0.1:*:*:*:*:*:*:*"
+            .parse()
+            .unwrap();
+        assert!(cpe.is_edition_match(Some("community")));
+    }
+
+    #[test]
+    fn can_match_wildcards() {
+        let cpe: CPE23 = "cpe:2.3:a:linux:linux_kernel:5.4.*:*:*:*:*:*:*:*"
+            .parse()
+            .unwrap();
+        assert!(cpe.is_version_match("5.4.10"));
+        assert!(!cpe.is_version_match("5.40"));
+        assert!(!cpe.is_version_match("5.5.1"));
+
+        let cpe: CPE23 = "cpe:2.3:a:microsoft:sql_server_20??:*:*:*:*:*:*:*:*"
+            .parse()
+            .unwrap();
+        assert!(cpe.is_product_match("sql_server_2019", None));
+        assert!(!cpe.is_product_match("sql_server_2019_express", None));
+
+        let cpe: CPE23 = "cpe:2.3:a:openbsd:openssh:7.4:p*:*:*:*:*:*:*"
+            .parse()
+            .unwrap();
+        assert!(cpe.is_release_match("7.4", Some("p2")));
+        assert!(!cpe.is_release_match("7.4", Some("rc1")));
+        assert!(!cpe.is_release_match("7.4", None));
+
+        // quoted characters are matched literally
+        let cpe: CPE23 = "cpe:2.3:h:citrix:mpx\\/sdx_14060_fips:-:*:*:*:*:*:*:*"
+            .parse()
+            .unwrap();
+        assert!(cpe.is_product_match("mpx/sdx_14060_fips", None));
+        assert!(!cpe.is_product_match("mpx", None));
+    }
+
+    #[test]
+    fn can_parse_platforms() {
+        let platform: Platform = "o:linux:linux_kernel:5.10".parse().unwrap();
+        assert_eq!("linux", platform.vendor);
+        assert_eq!("linux_kernel", platform.product);
+        assert_eq!(Some("5.10".to_string()), platform.version);
+
+        let platform: Platform = "cpe:2.3:o:microsoft:windows_10:*:*:*:*:*:*:*:*"
+            .parse()
+            .unwrap();
+        assert_eq!("windows_10", platform.product);
+        assert_eq!(None, platform.version);
+
+        assert!("a:zoom:meetings:5.9.0".parse::<Platform>().is_err());
+        assert!("o:linux".parse::<Platform>().is_err());
+    }
 }
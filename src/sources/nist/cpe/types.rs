@@ -8,6 +8,18 @@ pub enum Type {
     Application,
 }
 
+impl Type {
+    /// Whether it's the part given by its letter, `a`, `o` or `h`.
+    pub fn is(&self, part: &str) -> bool {
+        match self {
+            Self::Any => true,
+            Self::Hardware => part == "h",
+            Self::OperatingSystem => part == "o",
+            Self::Application => part == "a",
+        }
+    }
+}
+
 impl Default for Type {
     fn default() -> Self {
         Self::Any
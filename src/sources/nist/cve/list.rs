@@ -6,23 +6,30 @@ use serde::Deserialize;
 
 // use super::cpe;
 use super::item;
+use crate::sources::nist::cve_year;
 
 #[derive(Debug, Default, Deserialize)]
 pub struct List {
     #[serde(rename = "CVE_Items")]
     pub items: Vec<item::CVE>,
+    /// CVE without configurations as they're still being processed.
+    #[serde(skip)]
+    pub incomplete: Vec<item::CVE>,
 }
 
 impl List {
     pub fn parse(file_name: &Path) -> Result<Self, String> {
         let file = File::open(&file_name).map_err(|e| e.to_string())?;
         let reader = BufReader::new(file);
-        let mut list: Self = serde_json::from_reader(reader).map_err(|e| e.to_string())?;
+        let list: Self = serde_json::from_reader(reader).map_err(|e| e.to_string())?;
 
-        // remove CVE without configurations as they're still being processed
-        list.items.retain(|item| item.is_complete());
+        Ok(Self::with(list.items))
+    }
 
-        Ok(list)
+    pub fn with(items: Vec<item::CVE>) -> Self {
+        // set apart CVE without configurations as they're still being processed
+        let (items, incomplete) = items.into_iter().partition(|item| item.is_complete());
+        Self { items, incomplete }
     }
 
     pub fn len(&self) -> usize {
@@ -33,6 +40,15 @@ impl List {
         self.len() == 0
     }
 
+    /// Drops the CVEs of the years before the given one, returning how many were dropped.
+    pub fn retain_since(&mut self, year: i32) -> usize {
+        let before = self.items.len() + self.incomplete.len();
+        let is_recent = |item: &item::CVE| cve_year(item.id()).map_or(true, |y| y >= year);
+        self.items.retain(is_recent);
+        self.incomplete.retain(is_recent);
+        before - self.items.len() - self.incomplete.len()
+    }
+
     /*
     pub fn collect_unique_products(&self) -> Vec<cpe::Product> {
         let mut products = vec![];
@@ -1,10 +1,15 @@
-use std::fs;
-use std::path::{Path, PathBuf};
-use std::time::Instant;
+#[cfg(feature = "import")]
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    time::Instant,
+};
 
+#[cfg(feature = "import")]
 use log::info;
 
-use crate::utils;
+#[cfg(feature = "import")]
+use crate::utils::{self, ChecksumMismatch, ImportError};
 
 pub mod item;
 pub mod list;
@@ -12,38 +17,91 @@ pub mod node;
 
 pub const VERSION: &str = "1.1";
 
-pub fn setup(year: &str, data_path: &Path, fresh: bool) -> Result<(PathBuf, list::List), String> {
+/// Parses the SHA-256 of the uncompressed feed out of its `.meta` file.
+pub fn parse_meta_sha256(meta: &str) -> Option<String> {
+    meta.lines()
+        .find_map(|line| line.trim().strip_prefix("sha256:"))
+        .map(|sha256| sha256.trim().to_uppercase())
+}
+
+/// URL of a file of the feeds, downloaded from the NVD unless `KEPLER_NIST_FEED_URL` points
+/// to a mirror, like the one of another kepler instance.
+#[cfg(feature = "import")]
+fn feed_url(file_name: &str) -> String {
+    let base = env::var("KEPLER_NIST_FEED_URL")
+        .unwrap_or_else(|_| format!("https://nvd.nist.gov/feeds/json/cve/{}", VERSION));
+    format!("{}/{}", base.trim_end_matches('/'), file_name)
+}
+
+/// Downloads the `.meta` file of the feed, returning the SHA-256 it publishes.
+#[cfg(feature = "import")]
+fn download_meta_sha256(year: &str, data_path: &Path) -> Result<String, ImportError> {
+    let mut meta_file_name = data_path.to_path_buf();
+    meta_file_name.push(format!("nvdcve-{}-{}.meta", VERSION, year));
+
+    let url = feed_url(&format!("nvdcve-{}-{}.meta", VERSION, year));
+    utils::download_to_file(&url, &meta_file_name)?;
+
+    let meta = fs::read_to_string(&meta_file_name)
+        .map_err(|e| format!("could not read {}: {}", meta_file_name.display(), e))?;
+    parse_meta_sha256(&meta).ok_or_else(|| ImportError {
+        error: format!("no sha256 in {}", meta_file_name.display()),
+        url: Some(url),
+        ..Default::default()
+    })
+}
+
+#[cfg(feature = "import")]
+pub fn setup(
+    year: &str,
+    data_path: &Path,
+    fresh: bool,
+) -> Result<(PathBuf, list::List), ImportError> {
     let mut file_name = data_path.to_path_buf();
     file_name.push(format!("nvdcve-{}-{}.json", VERSION, year));
 
     let mut gzip_file_name = data_path.to_path_buf();
     gzip_file_name.push(format!("nvdcve-{}-{}.json.gz", VERSION, year));
 
-    if fresh {
-        if gzip_file_name.exists() {
-            info!("removing {}", gzip_file_name.display());
-            fs::remove_file(&gzip_file_name)
-                .map_err(|e| format!("could not remove {}: {}", gzip_file_name.display(), e))?;
-        }
+    let url = feed_url(&format!("nvdcve-{}-{}.json.gz", VERSION, year));
 
-        if file_name.exists() {
+    // the checksum is only verified for the feeds downloaded now, the ones refreshed being
+    // downloaded again only once changed
+    let mut expected_sha256 = None;
+    if fresh || !(file_name.exists() || gzip_file_name.exists()) {
+        expected_sha256 = Some(download_meta_sha256(year, data_path)?);
+        if utils::download_if_modified(&url, &gzip_file_name)? && file_name.exists() {
             info!("removing {}", file_name.display());
             fs::remove_file(&file_name)
                 .map_err(|e| format!("could not remove {}: {}", file_name.display(), e))?;
         }
+    } else if !file_name.exists() {
+        info!("found {}", gzip_file_name.display());
     }
 
     if !file_name.exists() {
-        if !gzip_file_name.exists() {
-            let url = format!(
-                "https://nvd.nist.gov/feeds/json/cve/{}/nvdcve-{}-{}.json.gz",
-                VERSION, VERSION, year
-            );
-            utils::download_to_file(&url, &gzip_file_name)?;
-        } else {
-            info!("found {}", gzip_file_name.display());
+        if let Err(e) = utils::gunzip(&gzip_file_name, &file_name) {
+            let _ = fs::remove_file(&file_name);
+            return Err(ImportError::from(e).quarantine(&gzip_file_name));
+        }
+
+        if let Some(expected) = expected_sha256 {
+            let actual = utils::sha256_file(&file_name)?;
+            if actual != expected {
+                let _ = fs::remove_file(&file_name);
+                return Err(ImportError {
+                    error: format!("checksum mismatch of {}", gzip_file_name.display()),
+                    url: Some(url),
+                    checksum: Some(ChecksumMismatch {
+                        file: file_name.display().to_string(),
+                        expected,
+                        actual,
+                    }),
+                    ..Default::default()
+                }
+                .quarantine(&gzip_file_name));
+            }
         }
-        utils::gunzip(&gzip_file_name, &file_name)?;
     } else {
         info!("found {}", file_name.display());
     }
@@ -51,9 +109,33 @@ pub fn setup(year: &str, data_path: &Path, fresh: bool) -> Result<(PathBuf, list
     info!("reading {} ...", file_name.display());
 
     let start = Instant::now();
-    let cve_list = list::List::parse(&file_name)?;
+    let cve_list = list::List::parse(&file_name).map_err(|e| {
+        ImportError::from(format!("could not parse {}: {}", file_name.display(), e))
+            .quarantine(&file_name)
+    })?;
 
     info!("loaded {} CVEs in {:?}", cve_list.len(), start.elapsed());
 
     Ok((file_name, cve_list))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::parse_meta_sha256;
+
+    #[test]
+    fn can_parse_meta_files() {
+        let meta = "lastModifiedDate:2022-05-25T03:00:01-04:00\r
+size:82751452\r
+zipSize:4295617\r
+gzSize:4295481\r
+sha256:c6e1b5cb1e2a5e3e0b8e4b3a2c8f0a1d7e9b6c5a4f3e2d1c0b9a8f7e6d5c4b3a\r
+";
+
+        assert_eq!(
+            Some("C6E1B5CB1E2A5E3E0B8E4B3A2C8F0A1D7E9B6C5A4F3E2D1C0B9A8F7E6D5C4B3A".to_string()),
+            parse_meta_sha256(meta)
+        );
+        assert_eq!(None, parse_meta_sha256("size:82751452"));
+    }
+}
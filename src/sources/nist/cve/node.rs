@@ -3,7 +3,8 @@ use std::convert::TryFrom;
 use serde::{Deserialize, Serialize};
 use version_compare::Cmp;
 
-use crate::sources::nist::cpe;
+use crate::search::{Query, Strictness};
+use crate::sources::nist::cpe::{self, types::Type};
 use crate::utils::version_cmp;
 
 #[derive(Debug, Default, Serialize, Deserialize, Clone)]
@@ -25,6 +26,14 @@ pub struct Match {
 }
 
 impl Match {
+    pub fn with(vulnerable: bool, cpe23: String) -> Self {
+        Self {
+            vulnerable,
+            cpe23,
+            ..Default::default()
+        }
+    }
+
     pub fn has_version_range(&self) -> bool {
         self.version_start_including.is_some()
             || self.version_start_excluding.is_some()
@@ -76,21 +85,106 @@ impl Match {
         }
     }
 
-    pub fn is_match(&mut self, product: &str, version: &str) -> bool {
+    /// Software the product runs on, when the CPE specifies one.
+    pub fn target(&mut self) -> Option<cpe::Target> {
+        self.parse().unwrap();
+        let cpe = self.cpe.as_ref().unwrap();
+        if !cpe.target_sw.is_value() || !cpe.product.is_value() {
+            return None;
+        }
+        Some(cpe::Target {
+            vendor: cpe.vendor.to_string(),
+            product: cpe.product.to_string(),
+            target_sw: cpe.target_sw.to_string(),
+        })
+    }
+
+    /// Whether the CPE is about the version, with the update given by the query or along with
+    /// the version, like `7.4 p1`.
+    fn is_version_match(&mut self, version: &str, update: Option<&str>) -> bool {
+        let (version, embedded_update) = cpe::split_update(version);
+        let update = update.or(embedded_update);
+        let cpe = self.cpe.as_ref().unwrap();
+        // match contains a version range
+        if self.has_version_range() {
+            return self.version_range_matches(version) && cpe.is_update_match(update);
+        }
+        // comparision match on cpe23 version
+        cpe.is_release_match(version, update)
+    }
+
+    pub fn is_match(&mut self, query: &Query, version: &str) -> bool {
         self.parse().unwrap();
         let cpe = self.cpe.as_ref().unwrap();
 
-        // product must match
-        if cpe.is_product_match(product) {
-            // match contains a version range
-            if self.has_version_range() {
-                return self.version_range_matches(version);
+        // part must match, if any
+        if let Some(part) = &query.part {
+            if !cpe.what.is(part) {
+                return false;
             }
-            // comparision match on cpe23 version
-            return cpe.is_version_match(version);
         }
 
-        false
+        // product and edition must match, on any target software with a fuzzy matching
+        let strictness = Strictness::of(query);
+        let is_product_match = cpe.is_product_match(&query.product, query.target_sw.as_deref())
+            || (strictness.allows_fuzzy() && cpe.product.matches(&query.product));
+        if !is_product_match || !cpe.is_edition_match(query.edition.as_deref()) {
+            return false;
+        }
+
+        // the CPEs of any version match every version
+        if cpe.version.is_any() && !self.has_version_range() && !strictness.allows_unversioned() {
+            return false;
+        }
+        self.is_version_match(version, query.update.as_deref())
+    }
+
+    /// Whether it's an operating system the vulnerable products run on.
+    fn is_os_platform(&mut self) -> bool {
+        self.parse().unwrap();
+        !self.vulnerable && self.cpe.as_ref().unwrap().what == Type::OperatingSystem
+    }
+
+    /// Whether it's the operating system given, at its version when both specify one.
+    fn is_platform_match(&mut self, platform: &cpe::Platform) -> bool {
+        self.parse().unwrap();
+        let cpe = self.cpe.as_ref().unwrap();
+        if !cpe.is_platform_product(platform) {
+            return false;
+        }
+
+        let version = match &platform.version {
+            Some(version) => version,
+            None => return true,
+        };
+        if self.has_version_range() {
+            return self.version_range_matches(version);
+        }
+        // the platforms without a version are any of them
+        if cpe.version.is_any() || cpe.version.is_na() {
+            return true;
+        }
+        cpe.is_version_match(version)
+    }
+
+    /// Vendor of the CPE if it's the hardware searched, whatever its version.
+    fn hardware_vendor(&mut self, query: &Query) -> Option<String> {
+        self.parse().unwrap();
+        let cpe = self.cpe.as_ref().unwrap();
+        if cpe.what == Type::Hardware && cpe.is_product_match(&query.product, None) {
+            return Some(cpe.vendor.to_string());
+        }
+        None
+    }
+
+    /// Whether it's a vulnerable firmware of the vendor at the given version.
+    fn is_firmware_match(&mut self, vendor: &str, version: &str) -> bool {
+        self.parse().unwrap();
+        let cpe = self.cpe.as_ref().unwrap();
+        self.vulnerable
+            && cpe.what == Type::OperatingSystem
+            && cpe.vendor.matches(vendor)
+            && self.is_version_match(version, None)
     }
 }
 
@@ -115,6 +209,21 @@ pub struct Node {
 }
 
 impl Node {
+    /// Returns the part (`a`, `o` or `h`) of the first CPE of the product in the node or its
+    /// children.
+    pub fn part_of(&self, vendor: &str, product: &str) -> Option<&str> {
+        for m in &self.cpe_match {
+            let fields: Vec<&str> = m.cpe23.splitn(6, ':').collect();
+            if fields.len() == 6 && fields[3] == vendor && fields[4] == product {
+                return Some(fields[2]);
+            }
+        }
+
+        self.children
+            .iter()
+            .find_map(|child| child.part_of(vendor, product))
+    }
+
     pub fn collect_unique_products(&mut self) -> Vec<cpe::Product> {
         let mut products = vec![];
 
@@ -136,14 +245,88 @@ impl Node {
         products
     }
 
-    pub fn is_match(&mut self, product: &str, version: &str) -> bool {
+    pub fn collect_unique_targets(&mut self) -> Vec<cpe::Target> {
+        let mut targets = vec![];
+
+        for m in &mut self.cpe_match {
+            if let Some(target) = m.target() {
+                if !targets.contains(&target) {
+                    targets.push(target);
+                }
+            }
+        }
+
+        for child in &mut self.children {
+            for target in child.collect_unique_targets() {
+                if !targets.contains(&target) {
+                    targets.push(target);
+                }
+            }
+        }
+
+        targets
+    }
+
+    /// Whether any CPE of the node is vulnerable, the other ones being the platform the
+    /// vulnerable products run on.
+    fn has_vulnerable(&self) -> bool {
+        self.cpe_match.iter().any(|m| m.vulnerable)
+            || self.children.iter().any(|child| child.has_vulnerable())
+    }
+
+    /// Whether the operating systems the vulnerable products run on include the one given,
+    /// `None` if the node lists none.
+    fn platform_match(&mut self, platform: &cpe::Platform) -> Option<bool> {
+        let mut result = None;
+        for m in &mut self.cpe_match {
+            if m.is_os_platform() {
+                if m.is_platform_match(platform) {
+                    return Some(true);
+                }
+                result = Some(false);
+            }
+        }
+        for child in &mut self.children {
+            match child.platform_match(platform) {
+                Some(true) => return Some(true),
+                Some(false) => result = Some(false),
+                None => {}
+            }
+        }
+        result
+    }
+
+    fn hardware_vendor(&mut self, query: &Query) -> Option<String> {
+        if let Some(vendor) = self
+            .cpe_match
+            .iter_mut()
+            .find_map(|m| m.hardware_vendor(query))
+        {
+            return Some(vendor);
+        }
+        self.children
+            .iter_mut()
+            .find_map(|child| child.hardware_vendor(query))
+    }
+
+    fn is_firmware_match(&mut self, vendor: &str, version: &str) -> bool {
+        self.cpe_match
+            .iter_mut()
+            .any(|m| m.is_firmware_match(vendor, version))
+            || self
+                .children
+                .iter_mut()
+                .any(|child| child.is_firmware_match(vendor, version))
+    }
+
+    pub fn is_match(&mut self, query: &Query, version: &str) -> bool {
         // leaf node
         if !self.cpe_match.is_empty() {
             match &self.operator {
                 Operator::Or => {
                     // any of them
                     for cpe_match in &mut self.cpe_match {
-                        if cpe_match.is_match(product, version) {
+                        if cpe_match.is_match(query, version) {
                             return true;
                         }
                     }
@@ -151,7 +334,7 @@ impl Node {
                 Operator::And => {
                     // all of them
                     for cpe_match in &mut self.cpe_match {
-                        if !cpe_match.is_match(product, version) {
+                        if !cpe_match.is_match(query, version) {
                             return false;
                         }
                     }
@@ -164,19 +347,46 @@ impl Node {
                 Operator::Or => {
                     // any of them
                     for child in &mut self.children {
-                        if child.is_match(product, version) {
+                        if child.is_match(query, version) {
                             return true;
                         }
                     }
                 }
                 Operator::And => {
-                    // all of them
+                    // the firmware running on a device is searched by the hardware, whose
+                    // version is the one of the firmware
+                    if query.part.as_deref().map_or(true, |part| part == "h") {
+                        let vendor = self
+                            .children
+                            .iter_mut()
+                            .find_map(|child| child.hardware_vendor(query));
+                        if let Some(vendor) = vendor {
+                            return self
+                                .children
+                                .iter_mut()
+                                .any(|child| child.is_firmware_match(&vendor, version));
+                        }
+                    }
+
+                    // the vulnerable products must match, the other ones are the platform
+                    // they run on, which must include the operating system searched if any
+                    let platform = query
+                        .os
+                        .as_deref()
+                        .and_then(|os| os.parse::<cpe::Platform>().ok());
+                    let mut matched = false;
                     for child in &mut self.children {
-                        if !child.is_match(product, version) {
+                        if child.is_match(query, version) {
+                            matched = true;
+                        } else if child.has_vulnerable() {
                             return false;
+                        } else if let Some(platform) = &platform {
+                            if child.platform_match(platform) == Some(false) {
+                                return false;
+                            }
                         }
                     }
-                    return true;
+                    return matched;
                 }
             }
         }
@@ -184,3 +394,175 @@ impl Node {
         false
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::Node;
+    use crate::search::{Query, Strictness};
+
+    /// Firmware versions of a device, running on its hardware.
+    const FIRMWARE: &str = r#"{
+        "operator": "AND",
+        "children": [
+            {
+                "operator": "OR",
+                "children": [],
+                "cpe_match": [
+                    {
+                        "vulnerable": true,
+                        "cpe23Uri": "cpe:2.3:o:tp-link:archer_c7_firmware:*:*:*:*:*:*:*:*",
+                        "versionEndExcluding": "3.15.3"
+                    }
+                ]
+            },
+            {
+                "operator": "OR",
+                "children": [],
+                "cpe_match": [
+                    {
+                        "vulnerable": false,
+                        "cpe23Uri": "cpe:2.3:h:tp-link:archer_c7:-:*:*:*:*:*:*:*"
+                    }
+                ]
+            }
+        ],
+        "cpe_match": []
+    }"#;
+
+    /// Application running on an operating system.
+    const APPLICATION: &str = r#"{
+        "operator": "AND",
+        "children": [
+            {
+                "operator": "OR",
+                "children": [],
+                "cpe_match": [
+                    {
+                        "vulnerable": true,
+                        "cpe23Uri": "cpe:2.3:a:zoom:meetings:*:*:*:*:*:*:*:*",
+                        "versionEndExcluding": "5.10.0"
+                    }
+                ]
+            },
+            {
+                "operator": "OR",
+                "children": [],
+                "cpe_match": [
+                    {
+                        "vulnerable": false,
+                        "cpe23Uri": "cpe:2.3:o:microsoft:windows:-:*:*:*:*:*:*:*"
+                    }
+                ]
+            }
+        ],
+        "cpe_match": []
+    }"#;
+
+    fn query(product: &str, part: Option<&str>) -> Query {
+        Query {
+            vendor: None,
+            product: product.into(),
+            version: None,
+            part: part.map(str::to_string),
+            ..Default::default()
+        }
+    }
+
+    fn query_on(product: &str, os: &str) -> Query {
+        Query {
+            os: Some(os.into()),
+            ..query(product, None)
+        }
+    }
+
+    #[test]
+    fn can_match_firmware_of_hardware() {
+        let mut node: Node = serde_json::from_str(FIRMWARE).unwrap();
+
+        assert!(node.is_match(&query("archer_c7", None), "3.15.1"));
+        assert!(node.is_match(&query("archer_c7", Some("h")), "3.15.1"));
+        assert!(!node.is_match(&query("archer_c7", None), "3.15.3"));
+        assert!(!node.is_match(&query("archer_c7", Some("a")), "3.15.1"));
+
+        assert!(node.is_match(&query("archer_c7_firmware", None), "3.15.1"));
+        assert!(node.is_match(&query("archer_c7_firmware", Some("o")), "3.15.1"));
+        assert!(!node.is_match(&query("archer_c7_firmware", Some("h")), "3.15.1"));
+        assert!(!node.is_match(&query("archer_c7_firmware", None), "3.16"));
+    }
+
+    #[test]
+    fn can_match_applications_on_platforms() {
+        let mut node: Node = serde_json::from_str(APPLICATION).unwrap();
+
+        assert!(node.is_match(&query("meetings", None), "5.9.0"));
+        assert!(node.is_match(&query("meetings", Some("a")), "5.9.0"));
+        assert!(!node.is_match(&query("meetings", Some("o")), "5.9.0"));
+        assert!(!node.is_match(&query("meetings", None), "5.10.0"));
+        // the platform alone is not vulnerable
+        assert!(!node.is_match(&query("windows", None), "-"));
+        assert!(!node.is_match(&query("windows", None), "10"));
+    }
+
+    #[test]
+    fn can_match_applications_on_operating_systems() {
+        let mut node: Node = serde_json::from_str(APPLICATION).unwrap();
+
+        assert!(node.is_match(&query_on("meetings", "o:microsoft:windows:10"), "5.9.0"));
+        assert!(node.is_match(&query_on("meetings", "o:microsoft:windows"), "5.9.0"));
+        assert!(node.is_match(
+            &query_on("meetings", "cpe:2.3:o:microsoft:windows:10:*:*:*:*:*:*:*"),
+            "5.9.0"
+        ));
+        // the application is vulnerable on another operating system only
+        assert!(!node.is_match(&query_on("meetings", "o:linux:linux_kernel:5.10"), "5.9.0"));
+        assert!(!node.is_match(&query_on("meetings", "o:microsoft:windows:10"), "5.10.0"));
+
+        // the platforms which are not operating systems don't depend on it
+        let mut node: Node = serde_json::from_str(FIRMWARE).unwrap();
+        assert!(node.is_match(
+            &query_on("archer_c7_firmware", "o:linux:linux_kernel:5.10"),
+            "3.15.1"
+        ));
+    }
+
+    #[test]
+    fn can_match_per_strictness() {
+        let with = |product: &str, strictness: &str| Query {
+            strictness: Some(strictness.parse().unwrap()),
+            ..query(product, None)
+        };
+
+        // the CPEs of any version
+        let mut node: Node = serde_json::from_str(
+            r#"{
+                "operator": "OR",
+                "children": [],
+                "cpe_match": [
+                    { "vulnerable": true, "cpe23Uri": "cpe:2.3:a:gnu:tar:*:*:*:*:*:*:*:*" }
+                ]
+            }"#,
+        )
+        .unwrap();
+        assert!(node.is_match(&query("tar", None), "1.34"));
+        assert!(!node.is_match(&with("tar", "strict"), "1.34"));
+
+        // the CPEs of another target software
+        let mut node: Node = serde_json::from_str(
+            r#"{
+                "operator": "OR",
+                "children": [],
+                "cpe_match": [
+                    {
+                        "vulnerable": true,
+                        "cpe23Uri": "cpe:2.3:a:isaacs:tar:*:*:*:*:*:node.js:*:*",
+                        "versionEndExcluding": "6.1.9"
+                    }
+                ]
+            }"#,
+        )
+        .unwrap();
+        assert!(!node.is_match(&query("tar", None), "1.34"));
+        assert!(node.is_match(&with("tar", "paranoid"), "1.34"));
+        assert!(node.is_match(&with("node-tar", "strict"), "6.1.0"));
+    }
+}
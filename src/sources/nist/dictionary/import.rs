@@ -0,0 +1,172 @@
+use anyhow::{anyhow, Result};
+use chrono::{NaiveDateTime, TimeZone, Utc};
+use log::info;
+use serde::Deserialize;
+
+use super::{fields, Reference, SOURCE_NAME};
+use crate::db::{self, Pool};
+use crate::sources::nist::api::{self, Client, Window};
+
+pub const URL: &str = "https://services.nvd.nist.gov/rest/json/cpes/2.0";
+
+/// Most CPEs the API returns per page.
+const RESULTS_PER_PAGE: usize = 10000;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Page {
+    results_per_page: usize,
+    start_index: usize,
+    total_results: usize,
+    #[serde(default)]
+    products: Vec<Product>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Product {
+    cpe: Cpe,
+}
+
+#[derive(Debug, Deserialize)]
+struct Title {
+    title: String,
+    lang: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Ref {
+    #[serde(rename = "ref")]
+    url: String,
+    #[serde(rename = "type", default)]
+    kind: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CpeName {
+    cpe_name: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Cpe {
+    #[serde(default)]
+    deprecated: bool,
+    cpe_name: String,
+    cpe_name_id: String,
+    last_modified: NaiveDateTime,
+    #[serde(default)]
+    titles: Vec<Title>,
+    #[serde(default)]
+    refs: Vec<Ref>,
+    #[serde(default)]
+    deprecated_by: Vec<CpeName>,
+}
+
+impl Cpe {
+    /// Entry of the dictionary, unless the CPE name is malformed.
+    fn into_entry(self) -> Option<db::models::NewCpeEntry> {
+        let (part, vendor, product, version) = fields(&self.cpe_name)?;
+        let title = self
+            .titles
+            .iter()
+            .find(|title| title.lang.starts_with("en"))
+            .or_else(|| self.titles.first())
+            .map(|title| title.title.trim().to_string())
+            .unwrap_or_default();
+
+        Some(db::models::NewCpeEntry {
+            part: part.to_string(),
+            vendor: vendor.to_string(),
+            product: product.to_string(),
+            version: version.to_string(),
+            cpe_name_id: self.cpe_name_id,
+            title,
+            deprecated: self.deprecated,
+            deprecated_by: diesel_json::Json::new(
+                self.deprecated_by
+                    .into_iter()
+                    .map(|name| name.cpe_name)
+                    .collect(),
+            ),
+            refs: diesel_json::Json::new(
+                self.refs
+                    .into_iter()
+                    .map(|r| Reference {
+                        url: r.url,
+                        kind: r.kind,
+                    })
+                    .collect(),
+            ),
+            last_modified: Utc.from_utc_datetime(&self.last_modified),
+            cpe: self.cpe_name,
+        })
+    }
+}
+
+/// Stores the CPEs of the whole dictionary, or of the window of modification, page after
+/// page, returning the number of entries stored.
+fn store(database: &db::Database, client: &mut Client, window: Option<&Window>) -> Result<u32> {
+    let mut num_stored = 0;
+    let mut start_index = 0;
+    loop {
+        let mut params = window.map(|w| w.params().to_vec()).unwrap_or_default();
+        params.push(("resultsPerPage", RESULTS_PER_PAGE.to_string()));
+        params.push(("startIndex", start_index.to_string()));
+        let page: Page = client.get(URL, &params)?;
+
+        let num_results = page.products.len();
+        let entries: Vec<db::models::NewCpeEntry> = page
+            .products
+            .into_iter()
+            .filter_map(|product| product.cpe.into_entry())
+            .collect();
+        num_stored += database
+            .upsert_cpe_entries(&entries)
+            .map_err(|e| anyhow!(e))? as u32;
+
+        start_index = page.start_index + num_results;
+        info!("fetched {} of {} CPEs", start_index, page.total_results);
+        if num_results == 0 || page.results_per_page == 0 || start_index >= page.total_results {
+            return Ok(num_stored);
+        }
+    }
+}
+
+/// Imports the official CPE dictionary from the NVD CPE API, only the CPEs modified since
+/// the last import unless `fresh` or never imported.
+pub fn run(pool: &Pool, fresh: bool) -> Result<u32> {
+    let database = db::Database(pool.get()?);
+    let mut client = Client::from_env()?;
+
+    let since = if fresh {
+        None
+    } else {
+        database
+            .get_synced_at(SOURCE_NAME)
+            .map_err(|e| anyhow!(e))?
+    };
+    let until = Utc::now();
+
+    let num_stored = match since {
+        Some(since) => {
+            info!("importing the CPEs modified since {}", since);
+            let mut num_stored = 0;
+            for window in &api::split(true, since.naive_utc(), until.naive_utc()) {
+                num_stored += store(&database, &mut client, Some(window))?;
+            }
+            num_stored
+        }
+        None => {
+            info!("importing the whole CPE dictionary ...");
+            store(&database, &mut client, None)?
+        }
+    };
+
+    // only once stored, so that a failed import is retried from the same time
+    database
+        .set_synced_at(db::models::NewSyncState::with(SOURCE_NAME.into(), until))
+        .map_err(|e| anyhow!(e))?;
+
+    Ok(num_stored)
+}
@@ -0,0 +1,99 @@
+//! Official CPE dictionary of the NVD, giving the canonical titles of the products, their
+//! references and whether they're deprecated, including the products without any CVE.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "import")]
+pub mod import;
+
+/// Name of the sync state of the dictionary.
+pub const SOURCE_NAME: &str = "CPE";
+
+/// Reference of a CPE, like the website of the vendor or the changelog of the version.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Reference {
+    pub url: String,
+    /// `Advisory`, `Change Log`, `Product`, `Project`, `Vendor` or `Version`.
+    #[serde(rename = "type", default, skip_serializing_if = "Option::is_none")]
+    pub kind: Option<String>,
+}
+
+/// Part, vendor, product and version of a CPE 2.3 name, like
+/// `cpe:2.3:a:apache:log4j:2.14.1:*:*:*:*:*:*:*`.
+pub fn fields(cpe: &str) -> Option<(&str, &str, &str, &str)> {
+    let mut parts = cpe.strip_prefix("cpe:2.3:")?.splitn(5, ':');
+    let (part, vendor, product, version) =
+        (parts.next()?, parts.next()?, parts.next()?, parts.next()?);
+    if vendor.is_empty() || product.is_empty() {
+        return None;
+    }
+    Some((part, vendor, product, version))
+}
+
+/// Title of a product, the one shared by most of its versions once the version is dropped
+/// from their titles, like `Apache Log4j` for `Apache Log4j 2.14.1`. The titles of the
+/// deprecated versions are only used when all of them are.
+pub fn product_title(versions: &[(String, String, bool)]) -> Option<String> {
+    let current: Vec<&(String, String, bool)> = versions
+        .iter()
+        .filter(|(_, _, deprecated)| !deprecated)
+        .collect();
+    let versions: Vec<&(String, String, bool)> = if current.is_empty() {
+        versions.iter().collect()
+    } else {
+        current
+    };
+
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for (version, title, _) in versions {
+        let title = title.trim();
+        let title = match title.strip_suffix(version.as_str()) {
+            Some(stripped) if version != "*" && version != "-" => stripped.trim_end(),
+            _ => title,
+        };
+        if !title.is_empty() {
+            *counts.entry(title).or_default() += 1;
+        }
+    }
+
+    counts
+        .into_iter()
+        .max_by(|a, b| a.1.cmp(&b.1).then_with(|| b.0.cmp(a.0)))
+        .map(|(title, _)| title.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{fields, product_title};
+
+    fn version(version: &str, title: &str, deprecated: bool) -> (String, String, bool) {
+        (version.into(), title.into(), deprecated)
+    }
+
+    #[test]
+    fn can_split_cpes() {
+        assert_eq!(
+            Some(("a", "apache", "log4j", "2.14.1")),
+            fields("cpe:2.3:a:apache:log4j:2.14.1:*:*:*:*:*:*:*")
+        );
+        assert_eq!(None, fields("cpe:/a:apache:log4j:2.14.1"));
+        assert_eq!(None, fields("cpe:2.3:a:apache"));
+    }
+
+    #[test]
+    fn can_title_products() {
+        let versions = vec![
+            version("2.14.0", "Apache Log4j 2.14.0", false),
+            version("2.14.1", "Apache Log4j 2.14.1", false),
+            version("2.15.0", "Apache Software Foundation Log4j 2.15.0", false),
+            version("1.0", "Log4j 1.0", true),
+        ];
+        assert_eq!(Some("Apache Log4j".to_string()), product_title(&versions));
+
+        let deprecated = vec![version("-", "Old Product", true)];
+        assert_eq!(Some("Old Product".to_string()), product_title(&deprecated));
+        assert_eq!(None, product_title(&[]));
+    }
+}
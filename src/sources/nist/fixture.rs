@@ -0,0 +1,361 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde_json::json;
+use version_compare::Cmp;
+
+use super::cve::{item, node, VERSION};
+use crate::utils::version_cmp;
+
+/// Real products the generated CVEs are spread over, so that fixtures can be queried with
+/// familiar names, along with synthetic ones to reach a realistic number of products.
+const PRODUCTS: &[(&str, &str, &str)] = &[
+    ("a", "xmlsoft", "libxml2"),
+    ("a", "openssl", "openssl"),
+    ("a", "gnu", "glibc"),
+    ("a", "busybox", "busybox"),
+    ("a", "haxx", "curl"),
+    ("a", "sqlite", "sqlite"),
+    ("a", "zlib", "zlib"),
+    ("a", "thekelleys", "dnsmasq"),
+    ("a", "lodash", "lodash"),
+    ("o", "linux", "linux_kernel"),
+];
+
+const SYNTHETIC_PRODUCTS: u64 = 500;
+
+const PLATFORMS: &[&str] = &[
+    "cpe:2.3:o:linux:linux_kernel:-:*:*:*:*:*:*:*",
+    "cpe:2.3:o:microsoft:windows:-:*:*:*:*:*:*:*",
+    "cpe:2.3:o:debian:debian_linux:11.0:*:*:*:*:*:*:*",
+];
+
+/// SplitMix64, a tiny generator with a stable output for a given seed, which is all the
+/// fixtures need to be reproducible across platforms and releases.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    fn below(&mut self, max: u64) -> u64 {
+        self.next() % max
+    }
+
+    fn chance(&mut self, percent: u64) -> bool {
+        self.below(100) < percent
+    }
+
+    fn version(&mut self) -> String {
+        format!("{}.{}.{}", self.below(5), self.below(20), self.below(30))
+    }
+
+    fn score(&mut self) -> f64 {
+        (self.below(91) + 10) as f64 / 10.0
+    }
+}
+
+fn severity_v2(score: f64) -> &'static str {
+    if score >= 7.0 {
+        "HIGH"
+    } else if score >= 4.0 {
+        "MEDIUM"
+    } else {
+        "LOW"
+    }
+}
+
+fn severity_v3(score: f64) -> &'static str {
+    if score >= 9.0 {
+        "CRITICAL"
+    } else if score >= 7.0 {
+        "HIGH"
+    } else if score >= 4.0 {
+        "MEDIUM"
+    } else {
+        "LOW"
+    }
+}
+
+fn product(rng: &mut Rng) -> (String, String, String) {
+    // most of the CVEs affect the well known products, like in the real feeds
+    if rng.chance(30) {
+        let (part, vendor, product) = PRODUCTS[rng.below(PRODUCTS.len() as u64) as usize];
+        (part.into(), vendor.into(), product.into())
+    } else {
+        let index = rng.below(SYNTHETIC_PRODUCTS);
+        (
+            "a".into(),
+            format!("vendor{}", index / 5),
+            format!("product{}", index),
+        )
+    }
+}
+
+/// A vulnerable CPE match, either for an exact version or a range of versions.
+fn cpe_match(rng: &mut Rng, part: &str, vendor: &str, product: &str) -> serde_json::Value {
+    let target_sw = if product == "lodash" { "node.js" } else { "*" };
+
+    if rng.chance(40) {
+        return json!({
+            "vulnerable": true,
+            "cpe23Uri": format!(
+                "cpe:2.3:{}:{}:{}:{}:*:*:*:*:{}:*:*",
+                part, vendor, product, rng.version(), target_sw
+            ),
+        });
+    }
+
+    let mut cpe_match = json!({
+        "vulnerable": true,
+        "cpe23Uri": format!(
+            "cpe:2.3:{}:{}:{}:*:*:*:*:*:{}:*:*",
+            part, vendor, product, target_sw
+        ),
+    });
+
+    let (start, end) = {
+        let (a, b) = (rng.version(), rng.version());
+        if version_cmp(&a, &b, Cmp::Gt) {
+            (b, a)
+        } else {
+            (a, b)
+        }
+    };
+    if rng.chance(50) {
+        let key = if rng.chance(50) {
+            "versionStartIncluding"
+        } else {
+            "versionStartExcluding"
+        };
+        cpe_match[key] = json!(start);
+    }
+    let key = if rng.chance(50) {
+        "versionEndIncluding"
+    } else {
+        "versionEndExcluding"
+    };
+    cpe_match[key] = json!(end);
+
+    cpe_match
+}
+
+fn configuration(rng: &mut Rng) -> node::Node {
+    let (part, vendor, product) = product(rng);
+    let matches: Vec<_> = (0..rng.below(3) + 1)
+        .map(|_| cpe_match(rng, &part, &vendor, &product))
+        .collect();
+
+    // some configurations are only vulnerable when running on a given platform
+    let node = if part == "a" && rng.chance(20) {
+        let platform = PLATFORMS[rng.below(PLATFORMS.len() as u64) as usize];
+        json!({
+            "operator": "AND",
+            "children": [
+                { "operator": "OR", "children": [], "cpe_match": matches },
+                {
+                    "operator": "OR",
+                    "children": [],
+                    "cpe_match": [{ "vulnerable": false, "cpe23Uri": platform }]
+                }
+            ],
+            "cpe_match": []
+        })
+    } else {
+        json!({ "operator": "OR", "children": [], "cpe_match": matches })
+    };
+
+    serde_json::from_value(node).expect("invalid generated configuration")
+}
+
+fn impact(rng: &mut Rng) -> item::Impact {
+    let av = ["NETWORK", "ADJACENT_NETWORK", "LOCAL"][rng.below(3) as usize];
+
+    // older CVEs only have CVSS v2 scores, recent ones only v3, most of them both
+    let (v2, v3) = match rng.below(10) {
+        0..=1 => (true, false),
+        2..=3 => (false, true),
+        _ => (true, true),
+    };
+
+    let metric_v2 = if v2 {
+        let score = rng.score();
+        Some(item::ImpactMetricV2 {
+            cvss: item::CVSSV2 {
+                version: "2.0".into(),
+                vector_string: format!("AV:{}/AC:L/Au:N/C:P/I:P/A:P", &av[..1]),
+                access_vector: av.into(),
+                access_complexity: "LOW".into(),
+                authentication: "NONE".into(),
+                confidentiality_impact: "PARTIAL".into(),
+                integrity_impact: "PARTIAL".into(),
+                availability_impact: "PARTIAL".into(),
+                base_score: score,
+            },
+            exploitability_score: 10.0,
+            impact_score: 6.4,
+            severity: severity_v2(score).into(),
+            ac_insuf_info: Some(false),
+            obtain_all_privilege: false,
+            obtain_user_privilege: false,
+            obtain_other_privilege: false,
+            user_interaction_required: Some(false),
+        })
+    } else {
+        None
+    };
+
+    let metric_v3 = if v3 {
+        let score = rng.score();
+        Some(item::ImpactMetricV3 {
+            cvss: item::CVSSV3 {
+                version: "3.1".into(),
+                vector_string: format!("CVSS:3.1/AV:{}/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H", &av[..1]),
+                attack_vector: av.into(),
+                attack_complexity: "LOW".into(),
+                privileges_required: "NONE".into(),
+                user_interaction: "NONE".into(),
+                scope: "UNCHANGED".into(),
+                confidentiality_impact: "HIGH".into(),
+                integrity_impact: "HIGH".into(),
+                availability_impact: "HIGH".into(),
+                base_score: score,
+                base_severity: severity_v3(score).into(),
+            },
+            exploitability_score: 3.9,
+            impact_score: 5.9,
+        })
+    } else {
+        None
+    };
+
+    item::Impact {
+        metric_v2,
+        metric_v3,
+    }
+}
+
+/// Generates the given number of synthetic CVEs, the same seed always generating the same
+/// CVEs.
+pub fn generate(year: &str, num_cves: u32, seed: u64) -> Vec<item::CVE> {
+    let mut rng = Rng(seed);
+
+    (0..num_cves)
+        .map(|index| {
+            let id = format!("CVE-{}-{:05}", year, index + 1);
+            let nodes = (0..rng.below(2) + 1)
+                .map(|_| configuration(&mut rng))
+                .collect();
+
+            item::CVE {
+                cve: item::Info {
+                    meta: item::Meta {
+                        id: id.clone(),
+                        assigner: Some("fixture@kepler".into()),
+                    },
+                    problemtype: item::ProblemType::default(),
+                    references: item::References {
+                        reference_data: vec![item::Reference {
+                            url: format!("https://example.com/advisories/{}", id),
+                            name: id.clone(),
+                            tags: vec!["Third Party Advisory".into()],
+                        }],
+                    },
+                    description: item::Description {
+                        description_data: vec![item::DescriptionData {
+                            lang: "en".into(),
+                            value: format!("Synthetic vulnerability {} generated for testing.", id),
+                        }],
+                    },
+                },
+                impact: impact(&mut rng),
+                configurations: item::Configurations {
+                    data_version: "4.0".into(),
+                    nodes,
+                },
+            }
+        })
+        .collect()
+}
+
+/// Writes a feed of synthetic CVEs to the data path, named like the NIST feed of the given
+/// year so that it can be imported with `import_nist --feed`.
+pub fn run(data_path: &Path, year: &str, num_cves: u32, seed: u64) -> Result<PathBuf, String> {
+    let items = generate(year, num_cves, seed);
+
+    let feed = json!({
+        "CVE_data_type": "CVE",
+        "CVE_data_format": "MITRE",
+        "CVE_data_version": "4.0",
+        "CVE_data_numberOfCVEs": num_cves.to_string(),
+        "CVE_Items": items,
+    });
+
+    let mut file_name = data_path.to_path_buf();
+    file_name.push(format!("nvdcve-{}-{}.json", VERSION, year));
+
+    let json = serde_json::to_string(&feed).map_err(|e| e.to_string())?;
+    fs::write(&file_name, json)
+        .map_err(|e| format!("could not write {}: {}", file_name.display(), e))?;
+
+    Ok(file_name)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use super::generate;
+    use crate::sources::nist::cpe;
+
+    #[test]
+    fn fixtures_are_deterministic() {
+        let a = serde_json::to_string(&generate("2099", 50, 42)).unwrap();
+        let b = serde_json::to_string(&generate("2099", 50, 42)).unwrap();
+        let c = serde_json::to_string(&generate("2099", 50, 43)).unwrap();
+
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+    }
+
+    #[test]
+    fn fixtures_are_valid() {
+        let mut items = generate("2099", 200, 42);
+        assert_eq!(200, items.len());
+        assert_eq!("CVE-2099-00001", items[0].id());
+
+        for item in &mut items {
+            assert!(item.is_complete());
+            assert!(item.score() > 0.0);
+            assert!(!item.severity().is_empty());
+            assert!(!item.collect_unique_products().is_empty());
+
+            let json = serde_json::to_value(&item.configurations).unwrap();
+            let mut uris = vec![];
+            collect_uris(&json, &mut uris);
+            for uri in uris {
+                assert!(cpe::CPE23::try_from(uri.as_str()).is_ok(), "{}", uri);
+            }
+        }
+    }
+
+    fn collect_uris(value: &serde_json::Value, uris: &mut Vec<String>) {
+        match value {
+            serde_json::Value::Object(map) => {
+                for (key, value) in map {
+                    match value.as_str() {
+                        Some(uri) if key == "cpe23Uri" => uris.push(uri.to_string()),
+                        _ => collect_uris(value, uris),
+                    }
+                }
+            }
+            serde_json::Value::Array(values) => values.iter().for_each(|v| collect_uris(v, uris)),
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,279 @@
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use version_compare::Cmp;
+
+use crate::search::{Query, Strictness};
+use crate::utils::version_cmp;
+
+/// Source of the records of the CVEs without configurations, matched through the products
+/// and versions mentioned in their summary, so with a low confidence.
+pub const SOURCE_NAME: &str = "NIST-HEURISTIC";
+
+/// Vendor of the records, since summaries don't tell the vendors apart from the products.
+pub const VENDOR: &str = "@heuristic";
+
+lazy_static! {
+    static ref VERSION: Regex = Regex::new(r"^v?\d+(\.\d+)+[a-z0-9\-]*$").unwrap();
+    static ref NAME: Regex = Regex::new(r"^[A-Za-z][A-Za-z0-9_\-\+\.]*$").unwrap();
+}
+
+/// Words that can't be, or be part of, a product name.
+const STOP_WORDS: &[&str] = &[
+    "a",
+    "all",
+    "an",
+    "and",
+    "before",
+    "component",
+    "for",
+    "from",
+    "in",
+    "is",
+    "library",
+    "module",
+    "of",
+    "on",
+    "plugin",
+    "prior",
+    "the",
+    "through",
+    "to",
+    "version",
+    "versions",
+    "via",
+    "when",
+    "with",
+    "within",
+];
+
+/// Product and versions range mentioned in a summary.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Mention {
+    pub product: String,
+    pub from: Option<String>,
+    pub operator: String,
+    pub version: String,
+}
+
+impl Mention {
+    pub fn is_match(&self, product: &str, version: &str) -> bool {
+        if self.product != product {
+            return false;
+        }
+        if let Some(from) = &self.from {
+            if !version_cmp(version, from, Cmp::Ge) {
+                return false;
+            }
+        }
+        match Cmp::from_sign(&self.operator) {
+            Ok(operator) => version_cmp(version, &self.version, operator),
+            Err(_) => false,
+        }
+    }
+}
+
+/// Mentions extracted from the summary of a CVE without configurations, stored as the
+/// object of its records.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Guess {
+    pub id: String,
+    pub mentions: Vec<Mention>,
+}
+
+impl Guess {
+    pub fn is_match(&self, query: &Query) -> bool {
+        if !Strictness::of(query).allows_incomplete() {
+            return false;
+        }
+        match &query.version {
+            Some(version) => self
+                .mentions
+                .iter()
+                .any(|mention| mention.is_match(&query.product, version)),
+            None => false,
+        }
+    }
+
+    /// Key of the object of the records, distinct from the one of the CVE itself.
+    pub fn object_key(id: &str) -> String {
+        format!("{} (heuristic)", id)
+    }
+
+    /// Unique products mentioned.
+    pub fn products(&self) -> Vec<String> {
+        let mut products: Vec<String> = vec![];
+        for mention in &self.mentions {
+            if !products.contains(&mention.product) {
+                products.push(mention.product.clone());
+            }
+        }
+        products
+    }
+}
+
+fn is_version(word: &str) -> bool {
+    VERSION.is_match(word)
+}
+
+fn normalize_version(word: &str) -> String {
+    word.strip_prefix('v').unwrap_or(word).to_string()
+}
+
+/// Candidate products named by the words ending at `end` (excluded): the last word, and
+/// the last two and three words joined by underscores like CPE products.
+fn products(words: &[&str], end: usize) -> Vec<String> {
+    let mut names = vec![];
+    let mut index = end;
+    while index > 0 && names.len() < 3 {
+        let word = words[index - 1];
+        if !NAME.is_match(word) || STOP_WORDS.contains(&word.to_lowercase().as_str()) {
+            break;
+        }
+        names.insert(0, word.to_lowercase());
+        index -= 1;
+    }
+
+    (0..names.len())
+        .rev()
+        .map(|start| names[start..].join("_"))
+        .collect()
+}
+
+/// Extracts the products and versions mentioned in a summary, like `libfoo before 1.2.3`,
+/// `libfoo through 1.2.3`, `libfoo 1.0 through 1.2`, `libfoo versions prior to 1.2.3`,
+/// `libfoo 1.2.3 and earlier` or just `libfoo 1.2.3`.
+pub fn extract(summary: &str) -> Vec<Mention> {
+    let words: Vec<&str> = summary
+        .split_whitespace()
+        .map(|w| w.trim_matches(|c: char| matches!(c, ',' | ';' | ':' | '(' | ')' | '"')))
+        .map(|w| w.strip_suffix('.').unwrap_or(w))
+        .collect();
+    let lower: Vec<String> = words.iter().map(|w| w.to_lowercase()).collect();
+    let word = |index: usize| lower.get(index).map(String::as_str).unwrap_or_default();
+
+    let mut mentions: Vec<Mention> = vec![];
+    for (index, candidate) in words.iter().enumerate() {
+        if !is_version(candidate) {
+            continue;
+        }
+        let version = normalize_version(candidate);
+
+        // the lower bound of a range is handled with its upper bound
+        if word(index + 1) == "through" && is_version(words.get(index + 2).unwrap_or(&"")) {
+            continue;
+        }
+
+        let (mut start, from, operator) = match index {
+            i if i >= 1 && word(i - 1) == "before" => (i - 1, None, "<"),
+            i if i >= 2 && word(i - 2) == "prior" && word(i - 1) == "to" => (i - 2, None, "<"),
+            i if i >= 2 && word(i - 2) == "up" && word(i - 1) == "to" => (i - 2, None, "<="),
+            i if i >= 1 && word(i - 1) == "through" => {
+                if i >= 2 && is_version(words[i - 2]) {
+                    (i - 2, Some(normalize_version(words[i - 2])), "<=")
+                } else {
+                    (i - 1, None, "<=")
+                }
+            }
+            i if word(i + 1) == "and" || word(i + 1) == "or" => match word(i + 2) {
+                "earlier" | "prior" | "below" | "before" => (i, None, "<="),
+                _ => (i, None, "=="),
+            },
+            i => (i, None, "=="),
+        };
+
+        // skip "version" and "versions" between the product and the versions
+        while start > 0 && (word(start - 1) == "version" || word(start - 1) == "versions") {
+            start -= 1;
+        }
+
+        for product in products(&words, start) {
+            let mention = Mention {
+                product,
+                from: from.clone(),
+                operator: operator.into(),
+                version: version.clone(),
+            };
+            if !mentions.contains(&mention) {
+                mentions.push(mention);
+            }
+        }
+    }
+
+    mentions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{extract, Guess, Mention};
+    use crate::search::{Query, Strictness};
+
+    fn mention(product: &str, from: Option<&str>, operator: &str, version: &str) -> Mention {
+        Mention {
+            product: product.into(),
+            from: from.map(str::to_string),
+            operator: operator.into(),
+            version: version.into(),
+        }
+    }
+
+    #[test]
+    fn can_extract_mentions() {
+        assert_eq!(
+            vec![mention("libxml2", None, "<", "2.9.11")],
+            extract("A use-after-free in libxml2 before 2.9.11 allows attackers to crash.")
+        );
+        assert_eq!(
+            vec![
+                mention("server", None, "<=", "2.4.49"),
+                mention("http_server", None, "<=", "2.4.49"),
+                mention("apache_http_server", None, "<=", "2.4.49"),
+            ],
+            extract("A path traversal in Apache HTTP Server through 2.4.49 was found.")
+        );
+        assert_eq!(
+            vec![mention("busybox", Some("1.30.0"), "<=", "1.33.1")],
+            extract("In BusyBox 1.30.0 through 1.33.1, a NULL dereference occurs.")
+        );
+        assert_eq!(
+            vec![mention("dnsmasq", None, "<", "2.83")],
+            extract("A flaw was found in dnsmasq versions prior to 2.83.")
+        );
+        assert_eq!(
+            vec![mention("foo", None, "<=", "3.1")],
+            extract("Foo 3.1 and earlier is affected by a stack overflow.")
+        );
+        assert_eq!(
+            vec![mention("tcpdump", None, "==", "4.9.3")],
+            extract("tcpdump 4.9.3 has a buffer over-read.")
+        );
+        assert!(extract("An issue was discovered in the kernel.").is_empty());
+    }
+
+    #[test]
+    fn can_match_guesses() {
+        let guess = Guess {
+            id: "CVE-2022-0001".into(),
+            mentions: extract("In BusyBox 1.30.0 through 1.33.1, a NULL dereference occurs."),
+        };
+        let query = |version: &str| Query {
+            vendor: None,
+            product: "busybox".into(),
+            version: Some(version.into()),
+            ..Default::default()
+        };
+
+        assert!(guess.is_match(&query("1.31.0")));
+        assert!(guess.is_match(&query("1.33.1")));
+        assert!(!guess.is_match(&query("1.29.3")));
+        assert!(!guess.is_match(&query("1.34.0")));
+        assert_eq!(vec!["busybox".to_owned()], guess.products());
+
+        // the strict matching leaves out the CVEs without configurations
+        let strict = Query {
+            strictness: Some(Strictness::Strict),
+            ..query("1.31.0")
+        };
+        assert!(!guess.is_match(&strict));
+    }
+}
@@ -1,29 +1,166 @@
+use std::env;
 use std::path::Path;
 
-use anyhow::{anyhow, bail, Result};
+use anyhow::{anyhow, Result};
+use chrono::Utc;
 use log::info;
 
-use super::{cve, SOURCE_NAME};
+use super::cve::list;
+use super::heuristic::{self, Guess};
+use super::{api, cve, SOURCE_NAME};
 use crate::db::{self, Pool};
+use crate::utils::memory;
 
-pub fn run(pool: &Pool, year: &str, data_path: &Path, fresh: bool) -> Result<u32> {
-    let (_, mut cve_list) = cve::setup(year, data_path, fresh).map_err(|err| anyhow!(err))?;
+/// First year of the CVEs imported, from the `--since-year` argument or else from
+/// `KEPLER_NIST_SINCE_YEAR`, the CVEs of the years before being skipped.
+pub fn since_year(arg: Option<&str>) -> Result<Option<i32>> {
+    let (name, value) = match (arg, env::var("KEPLER_NIST_SINCE_YEAR")) {
+        (Some(value), _) => ("--since-year", value.to_string()),
+        (None, Ok(value)) => ("KEPLER_NIST_SINCE_YEAR", value),
+        (None, Err(_)) => return Ok(None),
+    };
+    match value.parse::<i32>() {
+        Ok(year) if (1999..=9999).contains(&year) => Ok(Some(year)),
+        _ => Err(anyhow!("invalid {}: {}", name, value)),
+    }
+}
+
+/// Drops the CVEs of the years before `since_year`, if any.
+fn skip_before(cve_list: &mut list::List, since_year: Option<i32>) {
+    if let Some(since_year) = since_year {
+        let num_skipped = cve_list.retain_since(since_year);
+        if num_skipped > 0 {
+            info!("skipped {} CVEs before {}", num_skipped, since_year);
+        }
+    }
+}
+
+/// Imports the CVEs of the year from the NVD API, or from the retired JSON 1.1 data feed
+/// with `feed`, which still reads the files already in the data path like the fixtures.
+/// The CVEs of the years before `since_year` are skipped.
+pub fn run(
+    pool: &Pool,
+    year: &str,
+    data_path: &Path,
+    fresh: bool,
+    feed: bool,
+    since_year: Option<i32>,
+) -> Result<u32> {
+    // not even downloaded
+    if let (Ok(year), Some(since_year)) = (year.parse::<i32>(), since_year) {
+        if year < since_year {
+            info!(
+                "skipping {}, only importing the CVEs since {}",
+                year, since_year
+            );
+            return Ok(0);
+        }
+    }
+
+    let (_, mut cve_list) = if feed {
+        cve::setup(year, data_path, fresh)?
+    } else {
+        api::setup(year, data_path, fresh)?
+    };
+    skip_before(&mut cve_list, since_year);
+
+    memory::log_usage("parsing the feed");
 
     let database = db::Database(pool.get()?);
 
     info!("connected to database, importing records ...");
 
+    store(&database, &mut cve_list, false)
+}
+
+/// Imports the CVEs modified since the last sync, or since the newest record before the
+/// first one, updating the records already imported. The CVEs of the years before
+/// `since_year` are skipped.
+pub fn sync(pool: &Pool, since_year: Option<i32>) -> Result<u32> {
+    let database = db::Database(pool.get()?);
+
+    let since = match database
+        .get_synced_at(SOURCE_NAME)
+        .map_err(|e| anyhow!(e))?
+    {
+        Some(synced_at) => synced_at,
+        None => database
+            .get_latest_record_time(SOURCE_NAME)
+            .map_err(|e| anyhow!(e))?
+            .ok_or_else(|| anyhow!("no {} records to sync, import the years first", SOURCE_NAME))?,
+    };
+    let until = Utc::now();
+
+    info!("syncing the CVEs modified since {}", since);
+
+    let (mut cve_list, rejected) = api::fetch_modified(since.naive_utc(), until.naive_utc())?;
+
+    skip_before(&mut cve_list, since_year);
+
+    memory::log_usage("fetching the modified CVEs");
+
+    info!(
+        "fetched {} modified CVEs, {} rejected",
+        cve_list.len() + cve_list.incomplete.len(),
+        rejected.len()
+    );
+
+    let rejected: Vec<&str> = rejected.iter().map(String::as_str).collect();
+    let mut num_deleted = 0;
+    for source in &[SOURCE_NAME, heuristic::SOURCE_NAME] {
+        num_deleted += database
+            .delete_source_cves(source, &rejected)
+            .map_err(|e| anyhow!(e))?;
+    }
+    if num_deleted > 0 {
+        info!("deleted {} records of rejected CVEs", num_deleted);
+    }
+
+    let num_imported = store(&database, &mut cve_list, true)?;
+
+    // only once stored, so that a failed sync is retried from the same time
+    database
+        .set_synced_at(db::models::NewSyncState::with(SOURCE_NAME.into(), until))
+        .map_err(|e| anyhow!(e))?;
+
+    Ok(num_imported)
+}
+
+fn save_object(
+    database: &db::Database,
+    values: db::models::NewObject,
+    update: bool,
+) -> Result<i32> {
+    let result = if update {
+        database.upsert_object(values)
+    } else {
+        database.create_object_if_not_exist(values)
+    };
+    result.map_err(|e| anyhow!(e))
+}
+
+fn save_cve(database: &db::Database, values: db::models::NewCVE, update: bool) -> Result<bool> {
+    let result = if update {
+        database.upsert_cve(values)
+    } else {
+        database.create_cve_if_not_exist(values)
+    };
+    result.map_err(|e| anyhow!(e))
+}
+
+/// Stores the CVEs of the list, counting the records created, or updated with `update`
+/// which also deletes the records of the products no longer affected.
+fn store(database: &db::Database, cve_list: &mut list::List, update: bool) -> Result<u32> {
     let mut num_imported = 0;
 
     for item in &mut cve_list.items {
         let json = serde_json::to_string(item)?;
 
-        let object_id = match database
-            .create_object_if_not_exist(db::models::NewObject::with(item.id().into(), json))
-        {
-            Err(e) => bail!(e),
-            Ok(id) => id,
-        };
+        let object_id = save_object(
+            database,
+            db::models::NewObject::with(item.id().into(), json),
+            update,
+        )?;
 
         let mut refs = db::models::References::default();
         for data in &item.cve.references.reference_data {
@@ -33,7 +170,18 @@ pub fn run(pool: &Pool, year: &str, data_path: &Path, fresh: bool) -> Result<u32
             })
         }
 
-        for product in item.collect_unique_products() {
+        let products = item.collect_unique_products();
+        if update {
+            let affected: Vec<(String, String)> = products
+                .iter()
+                .map(|p| (p.vendor.clone(), p.product.clone()))
+                .collect();
+            database
+                .delete_other_products(SOURCE_NAME, item.id(), &affected)
+                .map_err(|e| anyhow!(e))?;
+        }
+
+        for product in products {
             let new_cve = db::models::NewCVE::with(
                 SOURCE_NAME.into(),
                 product.vendor,
@@ -46,17 +194,102 @@ pub fn run(pool: &Pool, year: &str, data_path: &Path, fresh: bool) -> Result<u32
                 refs.clone(),
                 Some(object_id),
             );
-            match database.create_cve_if_not_exist(new_cve) {
-                Err(e) => bail!(e),
-                Ok(true) => num_imported += 1,
-                Ok(false) => {}
+            if save_cve(database, new_cve, update)? {
+                num_imported += 1;
             }
 
             if num_imported > 0 && num_imported % 100 == 0 {
                 info!("imported {} records ...", num_imported);
             }
         }
+
+        let targets: Vec<db::models::NewProductTarget> = item
+            .collect_unique_targets()
+            .into_iter()
+            .map(db::models::NewProductTarget::with)
+            .collect();
+        if !targets.is_empty() {
+            database
+                .create_product_targets(&targets)
+                .map_err(|e| anyhow!(e))?;
+        }
+    }
+
+    // the configurations supersede the products guessed before they were available
+    let complete: Vec<&str> = cve_list.items.iter().map(|item| item.id()).collect();
+    let num_deleted = database
+        .delete_source_cves(heuristic::SOURCE_NAME, &complete)
+        .map_err(|e| anyhow!(e))?;
+    if num_deleted > 0 {
+        info!("deleted {} heuristic records", num_deleted);
+    }
+
+    let reconciled = database
+        .reconcile_pending_cves(&complete)
+        .map_err(|e| anyhow!(e))?;
+    if !reconciled.is_empty() {
+        info!(
+            "{} CVEs awaiting analysis got their configurations",
+            reconciled.len()
+        );
     }
 
+    // match the CVEs still being processed through the products mentioned in their summary
+    for item in &cve_list.incomplete {
+        database
+            .track_pending_cve(db::models::NewPendingCve::with(
+                item.id().into(),
+                item.summary().into(),
+            ))
+            .map_err(|e| anyhow!(e))?;
+
+        let guess = Guess {
+            id: item.id().into(),
+            mentions: heuristic::extract(item.summary()),
+        };
+        if guess.mentions.is_empty() {
+            continue;
+        }
+
+        let json = serde_json::to_string(&guess)?;
+        let object_id = save_object(
+            database,
+            db::models::NewObject::with(Guess::object_key(item.id()), json),
+            update,
+        )?;
+
+        for product in guess.products() {
+            let new_cve = db::models::NewCVE::with(
+                heuristic::SOURCE_NAME.into(),
+                heuristic::VENDOR.into(),
+                product,
+                item.id().into(),
+                item.summary().into(),
+                item.score(),
+                item.severity().into(),
+                Some(item.vector().into()),
+                db::models::References::default(),
+                Some(object_id),
+            );
+            if save_cve(database, new_cve, update)? {
+                num_imported += 1;
+            }
+        }
+    }
+
+    // the weaknesses are known before the configurations, so the incomplete ones are kept too
+    let items = cve_list.items.iter().chain(&cve_list.incomplete);
+    let ids: Vec<String> = items.clone().map(|item| item.id().to_string()).collect();
+    let weaknesses: Vec<db::models::NewCveWeakness> = items
+        .flat_map(|item| {
+            item.weaknesses()
+                .into_iter()
+                .map(move |cwe| db::models::NewCveWeakness::with(item.id().into(), cwe))
+        })
+        .collect();
+    database
+        .replace_cve_weaknesses(&ids, weaknesses)
+        .map_err(|e| anyhow!(e))?;
+
     Ok(num_imported)
 }
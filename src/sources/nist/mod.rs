@@ -1,5 +1,28 @@
+#[cfg(feature = "import")]
+pub mod api;
 pub mod cpe;
 pub mod cve;
+pub mod dictionary;
+pub mod fixture;
+pub mod heuristic;
+#[cfg(feature = "import")]
 pub mod import;
 
 pub const SOURCE_NAME: &str = "NIST";
+
+/// Year of a CVE id, like 2021 for `CVE-2021-44228`.
+pub fn cve_year(id: &str) -> Option<i32> {
+    id.strip_prefix("CVE-")?.split('-').next()?.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::cve_year;
+
+    #[test]
+    fn can_get_cve_years() {
+        assert_eq!(Some(2021), cve_year("CVE-2021-44228"));
+        assert_eq!(Some(1999), cve_year("CVE-1999-0001"));
+        assert_eq!(None, cve_year("GHSA-jfh8-c2jp-5v3q"));
+    }
+}
@@ -1,14 +1,14 @@
 use std::fs;
 use std::path::Path;
 
-use anyhow::{anyhow, bail, Result};
+use anyhow::{bail, Result};
 use log::info;
 use regex::Regex;
 
 use super::{Advisories, SOURCE_NAME};
 
 use crate::db::{self, Pool};
-use crate::utils::download_to_file;
+use crate::utils::{download_to_file, ImportError};
 
 fn process_file(pool: &Pool, file_path: &Path) -> Result<(u32, bool)> {
     info!("processing {} ...", file_path.display());
@@ -16,7 +16,10 @@ fn process_file(pool: &Pool, file_path: &Path) -> Result<(u32, bool)> {
     let mut num_imported = 0;
     let json = fs::read_to_string(&file_path)?;
 
-    let advisories: Advisories = serde_json::from_str(&json)?;
+    let advisories: Advisories = serde_json::from_str(&json).map_err(|e| {
+        ImportError::from(format!("could not parse {}: {}", file_path.display(), e))
+            .quarantine(file_path)
+    })?;
 
     let database = db::Database(pool.get()?);
 
@@ -119,8 +122,7 @@ pub fn run(pool: &Pool, recent_only: bool, data_path: &Path) -> Result<u32> {
         download_to_file(
             "https://registry.npmjs.org/-/npm/v1/security/advisories?perPage=100&page=1",
             &file_path,
-        )
-        .map_err(|err| anyhow!(err))?;
+        )?;
 
         let res = process_file(pool, &file_path)?;
         num_imported = res.0;
@@ -135,7 +137,7 @@ pub fn run(pool: &Pool, recent_only: bool, data_path: &Path) -> Result<u32> {
                     "https://registry.npmjs.org/-/npm/v1/security/advisories?perPage=100&page={}",
                     page
                 );
-                download_to_file(&url, &file_path).map_err(|err| anyhow!(err))?;
+                download_to_file(&url, &file_path)?;
             }
             let res = process_file(pool, &file_path)?;
             num_imported += res.0;
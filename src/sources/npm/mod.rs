@@ -7,6 +7,7 @@ use version_compare::Cmp;
 use crate::search::Query;
 use crate::utils::version_cmp;
 
+#[cfg(feature = "import")]
 pub mod import;
 
 lazy_static! {
@@ -134,6 +135,7 @@ mod tests {
             vendor: None,
             product: "".into(),
             version: None,
+            ..Default::default()
         }));
     }
 
@@ -146,12 +148,14 @@ mod tests {
             vendor: None,
             product: "".into(),
             version: Some("1.0.0".into()),
+            ..Default::default()
         }));
 
         assert!(adv.is_match(&Query {
             vendor: None,
             product: "".into(),
             version: Some("totally unrealistic but should match nevertheless".into()),
+            ..Default::default()
         }));
     }
 
@@ -164,18 +168,21 @@ mod tests {
             vendor: None,
             product: "".into(),
             version: Some("1.0.0".into()),
+            ..Default::default()
         }));
 
         assert!(adv.is_match(&Query {
             vendor: None,
             product: "".into(),
             version: Some("2.0.0".into()),
+            ..Default::default()
         }));
 
         assert!(!adv.is_match(&Query {
             vendor: None,
             product: "".into(),
             version: Some("3.0.0".into()),
+            ..Default::default()
         }));
     }
 
@@ -188,24 +195,28 @@ mod tests {
             vendor: None,
             product: "".into(),
             version: Some("1.0.0".into()),
+            ..Default::default()
         }));
 
         assert!(adv.is_match(&Query {
             vendor: None,
             product: "".into(),
             version: Some("1.0.1".into()),
+            ..Default::default()
         }));
 
         assert!(adv.is_match(&Query {
             vendor: None,
             product: "".into(),
             version: Some("2.0.0".into()),
+            ..Default::default()
         }));
 
         assert!(!adv.is_match(&Query {
             vendor: None,
             product: "".into(),
             version: Some("2.0.1".into()),
+            ..Default::default()
         }));
     }
 
@@ -218,30 +229,35 @@ mod tests {
             vendor: None,
             product: "".into(),
             version: Some("1.0.0".into()),
+            ..Default::default()
         }));
 
         assert!(adv.is_match(&Query {
             vendor: None,
             product: "".into(),
             version: Some("2.0.0".into()),
+            ..Default::default()
         }));
 
         assert!(adv.is_match(&Query {
             vendor: None,
             product: "".into(),
             version: Some("1.0.1".into()),
+            ..Default::default()
         }));
 
         assert!(adv.is_match(&Query {
             vendor: None,
             product: "".into(),
             version: Some("666".into()),
+            ..Default::default()
         }));
 
         assert!(!adv.is_match(&Query {
             vendor: None,
             product: "".into(),
             version: Some("2.0.1".into()),
+            ..Default::default()
         }));
     }
 }
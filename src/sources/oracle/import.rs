@@ -0,0 +1,75 @@
+use std::fs::{self, File};
+use std::io::BufReader;
+use std::path::Path;
+
+use anyhow::{anyhow, Context, Result};
+use log::info;
+
+use super::{object_key, oval, url, Advisory, SOURCE_NAME, VENDOR};
+
+use crate::db::{self, Pool};
+use crate::sources::redhat;
+use crate::utils::{bunzip2, download_to_file, ImportError};
+
+/// OVAL definitions of the errata of every Oracle Linux release.
+pub const FEED_URL: &str = "https://linux.oracle.com/security/oval/com.oracle.elsa-all.xml.bz2";
+
+fn store(database: &db::Database, advisory: &Advisory) -> Result<u32> {
+    redhat::advisory::import::store(
+        database,
+        SOURCE_NAME,
+        VENDOR,
+        object_key(&advisory.id),
+        url(advisory),
+        advisory,
+    )
+}
+
+/// Imports the CVEs of the Oracle Linux packages from the OVAL definitions of the errata,
+/// downloaded again with `fresh`, creating the new records and updating the changed ones.
+pub fn run(pool: &Pool, data_path: &Path, fresh: bool) -> Result<u32> {
+    let mut oracle_path = data_path.to_path_buf();
+    oracle_path.push("oracle");
+    fs::create_dir_all(&oracle_path)
+        .with_context(|| format!("could not create {}", oracle_path.display()))?;
+
+    let mut bz2_path = oracle_path.clone();
+    bz2_path.push("com.oracle.elsa-all.xml.bz2");
+    let mut file_path = oracle_path.clone();
+    file_path.push("com.oracle.elsa-all.xml");
+
+    if fresh || !file_path.exists() {
+        download_to_file(FEED_URL, &bz2_path)?;
+        bunzip2(&bz2_path, &file_path).map_err(|e| anyhow!(e))?;
+        fs::remove_file(&bz2_path)
+            .with_context(|| format!("could not remove {}", bz2_path.display()))?;
+    }
+
+    info!("parsing {} ...", file_path.display());
+    let file = File::open(&file_path)
+        .with_context(|| format!("could not open {}", file_path.display()))?;
+    let advisories = oval::parse(BufReader::new(file))
+        .map_err(|e| ImportError::from(e).quarantine(&file_path))?;
+    // empty definitions are a broken feed rather than no errata ever published
+    if advisories.is_empty() {
+        return Err(
+            ImportError::from("the OVAL definitions are empty".to_string())
+                .quarantine(&file_path)
+                .into(),
+        );
+    }
+
+    info!("importing {} Oracle Linux errata ...", advisories.len());
+
+    let database = db::Database(pool.get()?);
+    let mut num_imported = 0;
+    for (index, advisory) in advisories.iter().enumerate() {
+        num_imported += store(&database, advisory)?;
+
+        if index > 0 && index % 1000 == 0 {
+            info!("processed {} errata ...", index);
+        }
+    }
+
+    Ok(num_imported)
+}
@@ -0,0 +1,81 @@
+//! CVEs of the Oracle Linux packages fixed by the Oracle Linux errata (ELSA), published in the
+//! Oracle OVAL definitions with the versions fixing them in every release.
+
+pub use super::redhat::advisory::{product, Advisory, Fix};
+
+#[cfg(feature = "import")]
+pub mod import;
+#[cfg(feature = "import")]
+pub mod oval;
+
+pub const SOURCE_NAME: &str = "Oracle";
+/// No vendors for the packages, the Oracle Linux release is part of the product name.
+pub const VENDOR: &str = "@oracle";
+
+/// Release of an Oracle Linux version, like `ol-8` for `8.7`.
+pub fn release(version: &str) -> Option<String> {
+    version
+        .split('.')
+        .next()
+        .filter(|major| !major.is_empty() && major.chars().all(|c| c.is_ascii_digit()))
+        .map(|major| format!("ol-{}", major))
+}
+
+/// Release named by a platform of the definitions, like `ol-8` for `Oracle Linux 8`.
+pub fn platform(name: &str) -> Option<String> {
+    release(
+        name.trim()
+            .strip_prefix("Oracle Linux ")?
+            .split_whitespace()
+            .next()?,
+    )
+}
+
+pub fn object_key(id: &str) -> String {
+    format!("{} (oracle)", id)
+}
+
+/// Page of the erratum in the Oracle Linux errata.
+pub fn url(advisory: &Advisory) -> String {
+    format!("https://linux.oracle.com/errata/{}.html", advisory.id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{platform, release, url, Advisory, Fix};
+    use crate::search::testing::query;
+
+    #[test]
+    fn can_name_releases() {
+        assert_eq!(Some("ol-8".to_string()), release("8.7"));
+        assert_eq!(Some("ol-9".to_string()), release("9"));
+        assert_eq!(None, release("latest"));
+
+        assert_eq!(Some("ol-7".to_string()), platform("Oracle Linux 7"));
+        assert_eq!(None, platform("Red Hat Enterprise Linux 8"));
+    }
+
+    #[test]
+    fn can_match_fixed_versions() {
+        let advisory = Advisory {
+            id: "ELSA-2023-0001".into(),
+            severity: Some("IMPORTANT".into()),
+            fixes: vec![Fix {
+                release: "ol-8".into(),
+                package: "openssl".into(),
+                version: "1:1.1.1k-9.el8_7".into(),
+            }],
+            ..Default::default()
+        };
+
+        assert_eq!("HIGH", advisory.severity());
+        assert_eq!(
+            "https://linux.oracle.com/errata/ELSA-2023-0001.html",
+            url(&advisory)
+        );
+        assert_eq!(vec!["ol-8-openssl"], advisory.products());
+        assert!(advisory.is_match(&query("ol-8-openssl", "1:1.1.1k-7.el8_6")));
+        assert!(!advisory.is_match(&query("ol-8-openssl", "1:1.1.1k-9.el8_7")));
+        assert!(!advisory.is_match(&query("ol-9-openssl", "1:3.0.1-1.el9")));
+    }
+}
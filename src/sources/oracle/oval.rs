@@ -0,0 +1,275 @@
+//! Streaming parser of the Oracle OVAL definitions, resolving the criteria of every erratum
+//! into the packages fixed in every release.
+
+use std::io::BufRead;
+
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+
+use super::{platform, Advisory, Fix};
+
+/// Element whose text is being read.
+enum Text {
+    None,
+    Title,
+    Description,
+    Severity,
+    Platform,
+}
+
+/// Criterions of a criteria, the packages along with the release of the key signing them.
+#[derive(Default)]
+struct Criteria {
+    /// Packages with the versions fixing them.
+    packages: Vec<(String, String)>,
+    release: Option<String>,
+}
+
+fn attribute(element: &BytesStart, name: &[u8]) -> Option<String> {
+    element
+        .attributes()
+        .flatten()
+        .find(|attribute| attribute.key == name)
+        .and_then(|attribute| {
+            attribute
+                .unescaped_value()
+                .ok()
+                .map(|value| String::from_utf8_lossy(&value).into_owned())
+        })
+}
+
+/// Package and version fixing it of a criterion, like `openssl is earlier than
+/// 1:1.1.1k-9.el8_7`.
+fn package(comment: &str) -> Option<(String, String)> {
+    let (package, version) = comment.split_once(" is earlier than ")?;
+    Some((package.trim().to_string(), version.trim().to_string()))
+}
+
+/// Release of the key signing a package of a criterion, like `ol-8` for `openssl is signed
+/// with the Oracle Linux 8 key`.
+fn signing_release(comment: &str) -> Option<String> {
+    let (_, key) = comment.split_once(" is signed with the ")?;
+    platform(key.strip_suffix(" key")?)
+}
+
+/// Parses the errata of the OVAL definitions, the packages without a signing key belonging
+/// to the platform of the erratum when it has only one.
+pub fn parse<R: BufRead>(source: R) -> Result<Vec<Advisory>, String> {
+    let mut reader = Reader::from_reader(source);
+    reader.trim_text(true);
+
+    let mut advisories = vec![];
+    let mut advisory: Option<Advisory> = None;
+    let mut platforms: Vec<String> = vec![];
+    let mut criteria: Vec<Criteria> = vec![];
+    let mut text = Text::None;
+
+    let mut buf = vec![];
+    loop {
+        let event = reader.read_event(&mut buf).map_err(|e| {
+            format!(
+                "could not parse the OVAL definitions at {}: {}",
+                reader.buffer_position(),
+                e
+            )
+        })?;
+
+        match &event {
+            Event::Start(element) | Event::Empty(element) => {
+                let is_start = matches!(event, Event::Start(_));
+                match element.local_name() {
+                    b"definition" if is_start => {
+                        advisory = Some(Advisory::default());
+                        platforms.clear();
+                    }
+                    b"title" if advisory.is_some() => text = Text::Title,
+                    b"description" if advisory.is_some() => text = Text::Description,
+                    b"severity" if advisory.is_some() => text = Text::Severity,
+                    b"platform" if advisory.is_some() => text = Text::Platform,
+                    b"issued" => {
+                        if let Some(advisory) = &mut advisory {
+                            advisory.issued = attribute(element, b"date").unwrap_or_default();
+                        }
+                    }
+                    b"reference" => {
+                        if let Some(advisory) = &mut advisory {
+                            let id = attribute(element, b"ref_id").unwrap_or_default();
+                            match attribute(element, b"source").as_deref() {
+                                Some("elsa") | Some("ELSA") => advisory.id = id,
+                                Some("CVE") if !advisory.cves.contains(&id) => {
+                                    advisory.cves.push(id)
+                                }
+                                _ => {}
+                            }
+                        }
+                    }
+                    b"criteria" if is_start && advisory.is_some() => {
+                        criteria.push(Criteria::default())
+                    }
+                    b"criterion" => {
+                        if let Some(parent) = criteria.last_mut() {
+                            let comment = attribute(element, b"comment").unwrap_or_default();
+                            if let Some(package) = package(&comment) {
+                                parent.packages.push(package);
+                            } else if let Some(release) = signing_release(&comment) {
+                                parent.release = Some(release);
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            Event::Text(value) => {
+                let value = value
+                    .unescape_and_decode(&reader)
+                    .map_err(|e| format!("could not decode the OVAL definitions: {}", e))?;
+                if let Some(advisory) = &mut advisory {
+                    match text {
+                        Text::Title => advisory.title = value.trim().to_string(),
+                        Text::Description => advisory.description = value,
+                        Text::Severity => advisory.severity = Some(value),
+                        Text::Platform => platforms.extend(platform(&value)),
+                        Text::None => {}
+                    }
+                }
+            }
+            Event::End(element) => {
+                text = Text::None;
+                match element.local_name() {
+                    b"definition" => {
+                        advisories.extend(advisory.take().filter(|advisory| {
+                            !advisory.id.is_empty() && !advisory.fixes.is_empty()
+                        }));
+                    }
+                    b"criteria" => {
+                        if let (Some(ended), Some(advisory)) = (criteria.pop(), &mut advisory) {
+                            let release = match (ended.release, platforms.as_slice()) {
+                                (Some(release), _) => Some(release),
+                                (None, [platform]) => Some(platform.clone()),
+                                (None, _) => None,
+                            };
+                            for (package, version) in ended.packages {
+                                let fix = match &release {
+                                    Some(release) => Fix {
+                                        release: release.clone(),
+                                        package,
+                                        version,
+                                    },
+                                    None => continue,
+                                };
+                                // the same fix for every architecture
+                                if !advisory.fixes.contains(&fix) {
+                                    advisory.fixes.push(fix);
+                                }
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+
+        buf.clear();
+    }
+
+    Ok(advisories)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse;
+
+    const DEFINITIONS: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<oval_definitions xmlns="http://oval.mitre.org/XMLSchema/oval-definitions-5">
+  <definitions>
+    <definition id="oval:com.oracle.elsa:def:20230001" version="501" class="patch">
+      <metadata>
+        <title>
+ELSA-2023-0001:  openssl security update (IMPORTANT)</title>
+        <affected family="unix">
+          <platform>Oracle Linux 8</platform>
+        </affected>
+        <reference source="elsa" ref_id="ELSA-2023-0001" ref_url="https://linux.oracle.com/errata/ELSA-2023-0001.html"/>
+        <reference source="CVE" ref_id="CVE-2022-4304" ref_url="https://linux.oracle.com/cve/CVE-2022-4304.html"/>
+        <reference source="CVE" ref_id="CVE-2023-0286" ref_url="https://linux.oracle.com/cve/CVE-2023-0286.html"/>
+        <description>[1:1.1.1k-9] - Fixed X.400 address type confusion &amp; more</description>
+        <advisory>
+          <severity>IMPORTANT</severity>
+          <issued date="2023-01-03"/>
+        </advisory>
+      </metadata>
+      <criteria operator="AND">
+        <criteria operator="OR">
+          <criterion test_ref="oval:com.oracle.elsa:tst:20230001001" comment="Oracle Linux 8 is installed"/>
+        </criteria>
+        <criteria operator="OR">
+          <criteria operator="AND">
+            <criterion test_ref="oval:com.oracle.elsa:tst:20230001002" comment="Oracle Linux arch is aarch64"/>
+            <criteria operator="OR">
+              <criteria operator="AND">
+                <criterion test_ref="oval:com.oracle.elsa:tst:20230001003" comment="openssl is earlier than 1:1.1.1k-9.el8_7"/>
+                <criterion test_ref="oval:com.oracle.elsa:tst:20230001004" comment="openssl is signed with the Oracle Linux 8 key"/>
+              </criteria>
+              <criteria operator="AND">
+                <criterion test_ref="oval:com.oracle.elsa:tst:20230001005" comment="openssl-libs is earlier than 1:1.1.1k-9.el8_7"/>
+              </criteria>
+            </criteria>
+          </criteria>
+          <criteria operator="AND">
+            <criterion test_ref="oval:com.oracle.elsa:tst:20230001006" comment="Oracle Linux arch is x86_64"/>
+            <criteria operator="OR">
+              <criteria operator="AND">
+                <criterion test_ref="oval:com.oracle.elsa:tst:20230001003" comment="openssl is earlier than 1:1.1.1k-9.el8_7"/>
+                <criterion test_ref="oval:com.oracle.elsa:tst:20230001004" comment="openssl is signed with the Oracle Linux 8 key"/>
+              </criteria>
+            </criteria>
+          </criteria>
+        </criteria>
+      </criteria>
+    </definition>
+    <definition id="oval:com.oracle.elsa:def:20230002" version="501" class="patch">
+      <metadata>
+        <title>ELSA-2023-0002:  tzdata enhancement update (NONE)</title>
+        <reference source="elsa" ref_id="ELSA-2023-0002" ref_url="https://linux.oracle.com/errata/ELSA-2023-0002.html"/>
+      </metadata>
+    </definition>
+  </definitions>
+</oval_definitions>"#;
+
+    #[test]
+    fn can_parse_errata() {
+        let advisories = parse(DEFINITIONS.as_bytes()).unwrap();
+        assert_eq!(1, advisories.len());
+
+        let advisory = &advisories[0];
+        assert_eq!("ELSA-2023-0001", advisory.id);
+        assert_eq!(
+            "ELSA-2023-0001:  openssl security update (IMPORTANT)",
+            advisory.title
+        );
+        assert!(advisory.description.ends_with("& more"));
+        assert_eq!("HIGH", advisory.severity());
+        assert_eq!("2023-01-03", advisory.issued);
+        assert_eq!(vec!["CVE-2022-4304", "CVE-2023-0286"], advisory.cves);
+        assert_eq!(
+            vec!["ol-8-openssl", "ol-8-openssl-libs"],
+            advisory.products()
+        );
+        assert_eq!(2, advisory.fixes.len());
+        assert_eq!("1:1.1.1k-9.el8_7", advisory.fixes[0].version);
+    }
+
+    #[test]
+    fn can_skip_packages_of_unknown_releases() {
+        let advisories = parse(
+            DEFINITIONS
+                .replace("<platform>Oracle Linux 8</platform>", "")
+                .as_bytes(),
+        )
+        .unwrap();
+
+        assert_eq!(vec!["ol-8-openssl"], advisories[0].products());
+    }
+}
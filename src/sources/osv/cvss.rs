@@ -0,0 +1,154 @@
+//! Base score of the CVSS v3 vectors, as OSV provides only the vectors.
+
+use std::collections::HashMap;
+
+fn roundup(value: f64) -> f64 {
+    // as specified by CVSS 3.1, avoiding the floating point errors of a plain ceiling
+    let int_input = (value * 100_000.0).round() as i64;
+    if int_input % 10_000 == 0 {
+        int_input as f64 / 100_000.0
+    } else {
+        (int_input / 10_000 + 1) as f64 / 10.0
+    }
+}
+
+fn metrics(vector: &str) -> Option<HashMap<&str, &str>> {
+    let metrics = vector
+        .strip_prefix("CVSS:3.1/")
+        .or_else(|| vector.strip_prefix("CVSS:3.0/"))?;
+    Some(
+        metrics
+            .split('/')
+            .filter_map(|metric| metric.split_once(':'))
+            .collect(),
+    )
+}
+
+/// Base score of a `CVSS:3.x/AV:N/AC:L/...` vector, if valid.
+pub fn base_score(vector: &str) -> Option<f64> {
+    let metrics = metrics(vector)?;
+    let changed = match *metrics.get("S")? {
+        "U" => false,
+        "C" => true,
+        _ => return None,
+    };
+
+    let av = match *metrics.get("AV")? {
+        "N" => 0.85,
+        "A" => 0.62,
+        "L" => 0.55,
+        "P" => 0.2,
+        _ => return None,
+    };
+    let ac = match *metrics.get("AC")? {
+        "L" => 0.77,
+        "H" => 0.44,
+        _ => return None,
+    };
+    let pr = match (*metrics.get("PR")?, changed) {
+        ("N", _) => 0.85,
+        ("L", false) => 0.62,
+        ("L", true) => 0.68,
+        ("H", false) => 0.27,
+        ("H", true) => 0.5,
+        _ => return None,
+    };
+    let ui = match *metrics.get("UI")? {
+        "N" => 0.85,
+        "R" => 0.62,
+        _ => return None,
+    };
+    let impact = |metric: &str| match *metrics.get(metric)? {
+        "H" => Some(0.56),
+        "L" => Some(0.22),
+        "N" => Some(0.0),
+        _ => None,
+    };
+    let (c, i, a) = (impact("C")?, impact("I")?, impact("A")?);
+
+    let iss = 1.0 - (1.0 - c) * (1.0 - i) * (1.0 - a);
+    let impact = if changed {
+        7.52 * (iss - 0.029) - 3.25 * (iss - 0.02f64).powi(15)
+    } else {
+        6.42 * iss
+    };
+    if impact <= 0.0 {
+        return Some(0.0);
+    }
+
+    let exploitability = 8.22 * av * ac * pr * ui;
+    Some(if changed {
+        roundup((1.08 * (impact + exploitability)).min(10.0))
+    } else {
+        roundup((impact + exploitability).min(10.0))
+    })
+}
+
+/// Attack vector of the vector, named like the NIST records.
+pub fn attack_vector(vector: &str) -> Option<&'static str> {
+    match *metrics(vector)?.get("AV")? {
+        "N" => Some("NETWORK"),
+        "A" => Some("ADJACENT_NETWORK"),
+        "L" => Some("LOCAL"),
+        "P" => Some("PHYSICAL"),
+        _ => None,
+    }
+}
+
+/// Qualitative severity rating of the score.
+pub fn severity(score: f64) -> &'static str {
+    if score >= 9.0 {
+        "CRITICAL"
+    } else if score >= 7.0 {
+        "HIGH"
+    } else if score >= 4.0 {
+        "MEDIUM"
+    } else if score > 0.0 {
+        "LOW"
+    } else {
+        "NONE"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{attack_vector, base_score, severity};
+
+    #[test]
+    fn can_compute_base_scores() {
+        assert_eq!(
+            Some(9.8),
+            base_score("CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H")
+        );
+        assert_eq!(
+            Some(6.1),
+            base_score("CVSS:3.1/AV:N/AC:L/PR:N/UI:R/S:C/C:L/I:L/A:N")
+        );
+        assert_eq!(
+            Some(5.5),
+            base_score("CVSS:3.0/AV:L/AC:L/PR:L/UI:N/S:U/C:N/I:N/A:H")
+        );
+        assert_eq!(
+            Some(10.0),
+            base_score("CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:C/C:H/I:H/A:H")
+        );
+        assert_eq!(
+            Some(0.0),
+            base_score("CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:N/I:N/A:N")
+        );
+
+        assert_eq!(None, base_score("AV:N/AC:L/Au:N/C:P/I:P/A:P"));
+        assert_eq!(None, base_score("CVSS:3.1/AV:N/AC:L"));
+    }
+
+    #[test]
+    fn can_rate_scores() {
+        assert_eq!("CRITICAL", severity(9.8));
+        assert_eq!("MEDIUM", severity(6.1));
+        assert_eq!("NONE", severity(0.0));
+        assert_eq!(
+            Some("LOCAL"),
+            attack_vector("CVSS:3.0/AV:L/AC:L/PR:L/UI:N/S:U/C:N/I:N/A:H")
+        );
+    }
+}
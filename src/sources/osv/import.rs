@@ -0,0 +1,178 @@
+use std::collections::HashSet;
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::Path;
+
+use anyhow::{anyhow, bail, Context, Result};
+use log::{info, warn};
+
+use super::{Vulnerability, ECOSYSTEMS, SOURCE_NAME, VENDOR};
+
+use crate::db::{self, Pool};
+use crate::utils::{download_to_file, ImportError};
+
+pub const DUMPS_URL: &str = "https://osv-vulnerabilities.storage.googleapis.com";
+
+/// Reads the vulnerabilities of the dump of an ecosystem, a zip archive of OSV files.
+fn read_dump(file_path: &Path) -> Result<Vec<Vulnerability>> {
+    let file =
+        File::open(file_path).with_context(|| format!("could not open {}", file_path.display()))?;
+    let mut archive = zip::ZipArchive::new(file).map_err(|e| {
+        ImportError::from(format!("could not read {}: {}", file_path.display(), e))
+            .quarantine(file_path)
+    })?;
+
+    let mut vulnerabilities = vec![];
+    for index in 0..archive.len() {
+        let mut entry = archive.by_index(index)?;
+        if !entry.name().ends_with(".json") {
+            continue;
+        }
+
+        let mut json = String::new();
+        entry
+            .read_to_string(&mut json)
+            .with_context(|| format!("could not read {}", entry.name()))?;
+        match serde_json::from_str::<Vulnerability>(&json) {
+            Ok(vulnerability) => vulnerabilities.push(vulnerability),
+            Err(e) => warn!("skipping {}: {}", entry.name(), e),
+        }
+    }
+
+    Ok(vulnerabilities)
+}
+
+fn import_ecosystem(database: &db::Database, ecosystem: &str, file_path: &Path) -> Result<u32> {
+    let vulnerabilities = read_dump(file_path)?;
+    info!(
+        "importing {} {} vulnerabilities ...",
+        vulnerabilities.len(),
+        ecosystem
+    );
+
+    let mut num_imported = 0;
+    let mut seen = HashSet::new();
+    let mut withdrawn = vec![];
+
+    for vulnerability in vulnerabilities {
+        if vulnerability.withdrawn.is_some() {
+            withdrawn.push(vulnerability.cve().to_string());
+            continue;
+        }
+        // the same vulnerability is often published by several databases aliasing each other
+        if vulnerability
+            .aliases
+            .iter()
+            .any(|alias| seen.contains(alias))
+        {
+            continue;
+        }
+        seen.insert(vulnerability.id.clone());
+
+        let products = vulnerability.products();
+        if products.is_empty() {
+            continue;
+        }
+
+        let object_id = match database.upsert_object(db::models::NewObject::with(
+            Vulnerability::object_key(&vulnerability.id),
+            serde_json::to_string(&vulnerability)?,
+        )) {
+            Err(e) => bail!(e),
+            Ok(id) => id,
+        };
+
+        let mut refs = db::models::References::default();
+        for reference in &vulnerability.references {
+            refs.push(db::models::Reference {
+                url: reference.url.clone(),
+                tags: vec![reference.kind.clone()],
+            })
+        }
+
+        for product in products {
+            let new_cve = db::models::NewCVE::with(
+                SOURCE_NAME.into(),
+                VENDOR.into(),
+                product,
+                vulnerability.cve().into(),
+                vulnerability.summary().into(),
+                vulnerability.score(),
+                vulnerability.severity(),
+                vulnerability.vector(),
+                refs.clone(),
+                Some(object_id),
+            );
+            match database.upsert_cve(new_cve) {
+                Err(e) => bail!(e),
+                Ok(true) => num_imported += 1,
+                Ok(false) => {}
+            }
+
+            if num_imported > 0 && num_imported % 100 == 0 {
+                info!("imported {} records ...", num_imported);
+            }
+        }
+    }
+
+    let withdrawn: Vec<&str> = withdrawn.iter().map(String::as_str).collect();
+    let num_deleted = database
+        .delete_source_cves(SOURCE_NAME, &withdrawn)
+        .map_err(|e| anyhow!(e))?;
+    if num_deleted > 0 {
+        info!(
+            "deleted {} records of withdrawn vulnerabilities",
+            num_deleted
+        );
+    }
+
+    Ok(num_imported)
+}
+
+/// Imports the vulnerabilities of the given ecosystems, or all the supported ones, from
+/// the OSV.dev dumps, creating the new records and updating the changed ones.
+pub fn run(pool: &Pool, ecosystems: &[&str], data_path: &Path, fresh: bool) -> Result<u32> {
+    let ecosystems: Vec<&str> = if ecosystems.is_empty() {
+        ECOSYSTEMS.iter().map(|(ecosystem, _)| *ecosystem).collect()
+    } else {
+        ecosystems.to_vec()
+    };
+    for ecosystem in &ecosystems {
+        if !ECOSYSTEMS
+            .iter()
+            .any(|(supported, _)| supported == ecosystem)
+        {
+            bail!(
+                "unsupported ecosystem {}, expected one of {}",
+                ecosystem,
+                ECOSYSTEMS
+                    .iter()
+                    .map(|(ecosystem, _)| *ecosystem)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+        }
+    }
+
+    let mut osv_path = data_path.to_path_buf();
+    osv_path.push("osv");
+    fs::create_dir_all(&osv_path)
+        .with_context(|| format!("could not create {}", osv_path.display()))?;
+
+    let database = db::Database(pool.get()?);
+    let mut num_imported = 0;
+
+    for ecosystem in ecosystems {
+        let mut file_path = osv_path.clone();
+        file_path.push(format!("{}.zip", ecosystem));
+        if fresh || !file_path.exists() {
+            download_to_file(&format!("{}/{}/all.zip", DUMPS_URL, ecosystem), &file_path)?;
+        } else {
+            info!("found {}", file_path.display());
+        }
+
+        num_imported += import_ecosystem(&database, ecosystem, &file_path)?;
+    }
+
+    Ok(num_imported)
+}
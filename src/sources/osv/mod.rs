@@ -0,0 +1,373 @@
+//! Vulnerabilities of the language ecosystems published by [OSV.dev](https://osv.dev), in
+//! the OSV schema.
+
+use serde::{Deserialize, Serialize};
+
+use crate::scan::{ecosystem_product, go, python};
+use crate::search::Query;
+use crate::utils::version_cmp;
+use version_compare::Cmp;
+
+pub mod cvss;
+#[cfg(feature = "import")]
+pub mod import;
+
+pub const SOURCE_NAME: &str = "OSV";
+/// No vendors for the packages, the ecosystem is part of the product name.
+pub const VENDOR: &str = "@osv";
+
+/// OSV ecosystems imported, with the prefix of their products.
+pub const ECOSYSTEMS: &[(&str, &str)] = &[
+    ("PyPI", "python"),
+    ("npm", "node"),
+    ("crates.io", "rust"),
+    ("Go", "go"),
+];
+
+#[derive(Debug, Default, Clone, Deserialize, Serialize)]
+pub struct Package {
+    pub ecosystem: String,
+    pub name: String,
+}
+
+impl Package {
+    /// Product of the package, named like the scans name the components of its ecosystem.
+    pub fn product(&self) -> Option<String> {
+        let (_, prefix) = ECOSYSTEMS
+            .iter()
+            .find(|(ecosystem, _)| *ecosystem == self.ecosystem)?;
+        Some(match self.ecosystem.as_str() {
+            "PyPI" => ecosystem_product(prefix, &python::normalize_name(&self.name)),
+            "Go" => ecosystem_product(prefix, go::module_product(&self.name)),
+            _ => ecosystem_product(prefix, &self.name),
+        })
+    }
+
+    pub(crate) fn normalize_version(&self, version: &str) -> String {
+        if self.ecosystem == "PyPI" {
+            python::normalize_version(version)
+        } else {
+            version.strip_prefix('v').unwrap_or(version).to_string()
+        }
+    }
+
+    /// Compares two normalized versions, following PEP 440 for the PyPI packages.
+    pub(crate) fn version_cmp(&self, a: &str, b: &str, operator: Cmp) -> bool {
+        if self.ecosystem == "PyPI" {
+            python::version_cmp(a, b, operator)
+        } else {
+            version_cmp(a, b, operator)
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Deserialize, Serialize)]
+pub struct Event {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub introduced: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fixed: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_affected: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<String>,
+}
+
+#[derive(Debug, Default, Clone, Deserialize, Serialize)]
+pub struct Range {
+    /// `SEMVER`, `ECOSYSTEM` or `GIT`, whose commit hashes can't be matched.
+    #[serde(rename = "type")]
+    pub kind: String,
+    #[serde(default)]
+    pub events: Vec<Event>,
+}
+
+/// End of a range of affected versions.
+enum End {
+    Open,
+    Before(String),
+    Through(String),
+}
+
+impl Range {
+    /// Affected versions between each introduced event and the following fixed, last
+    /// affected or limit one.
+    fn intervals(&self, package: &Package) -> Vec<(String, End)> {
+        let mut intervals = vec![];
+        let mut start: Option<String> = None;
+
+        for event in &self.events {
+            if let Some(introduced) = &event.introduced {
+                if let Some(start) = start.take() {
+                    intervals.push((start, End::Open));
+                }
+                start = Some(package.normalize_version(introduced));
+            } else if let Some(end) = event.fixed.as_ref().or_else(|| event.limit.as_ref()) {
+                if let Some(start) = start.take() {
+                    intervals.push((start, End::Before(package.normalize_version(end))));
+                }
+            } else if let Some(end) = &event.last_affected {
+                if let Some(start) = start.take() {
+                    intervals.push((start, End::Through(package.normalize_version(end))));
+                }
+            }
+        }
+        if let Some(start) = start {
+            intervals.push((start, End::Open));
+        }
+
+        intervals
+    }
+
+    fn contains(&self, package: &Package, version: &str) -> bool {
+        self.intervals(package).into_iter().any(|(start, end)| {
+            (start == "0" || package.version_cmp(version, &start, Cmp::Ge))
+                && match end {
+                    End::Open => true,
+                    End::Before(end) => package.version_cmp(version, &end, Cmp::Lt),
+                    End::Through(end) => package.version_cmp(version, &end, Cmp::Le),
+                }
+        })
+    }
+}
+
+#[derive(Debug, Default, Clone, Deserialize, Serialize)]
+pub struct Affected {
+    pub package: Package,
+    #[serde(default)]
+    pub ranges: Vec<Range>,
+    /// Affected versions listed one by one.
+    #[serde(default)]
+    pub versions: Vec<String>,
+}
+
+impl Affected {
+    pub fn is_match(&self, version: &str) -> bool {
+        let version = self.package.normalize_version(version);
+        self.versions
+            .iter()
+            .any(|v| self.package.normalize_version(v) == version)
+            || self
+                .ranges
+                .iter()
+                .filter(|range| range.kind != "GIT")
+                .any(|range| range.contains(&self.package, &version))
+    }
+}
+
+#[derive(Debug, Default, Clone, Deserialize, Serialize)]
+pub struct Severity {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub score: String,
+}
+
+#[derive(Debug, Default, Clone, Deserialize, Serialize)]
+pub struct Reference {
+    #[serde(rename = "type", default)]
+    pub kind: String,
+    pub url: String,
+}
+
+#[derive(Debug, Default, Clone, Deserialize, Serialize)]
+pub struct Vulnerability {
+    pub id: String,
+    #[serde(default)]
+    pub modified: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub withdrawn: Option<String>,
+    #[serde(default)]
+    pub aliases: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+    #[serde(default)]
+    pub details: String,
+    #[serde(default)]
+    pub severity: Vec<Severity>,
+    #[serde(default)]
+    pub affected: Vec<Affected>,
+    #[serde(default)]
+    pub references: Vec<Reference>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub database_specific: Option<serde_json::Value>,
+}
+
+impl Vulnerability {
+    pub fn object_key(id: &str) -> String {
+        format!("{} (osv)", id)
+    }
+
+    /// The CVE of the vulnerability if it has one, otherwise its OSV id.
+    pub fn cve(&self) -> &str {
+        self.aliases
+            .iter()
+            .find(|alias| alias.starts_with("CVE-"))
+            .unwrap_or(&self.id)
+    }
+
+    pub fn summary(&self) -> &str {
+        match &self.summary {
+            Some(summary) if !summary.is_empty() => summary,
+            _ => self.details.lines().next().unwrap_or_default(),
+        }
+    }
+
+    fn cvss_v3(&self) -> Option<&str> {
+        self.severity
+            .iter()
+            .find(|severity| severity.kind == "CVSS_V3")
+            .map(|severity| severity.score.as_str())
+    }
+
+    pub fn score(&self) -> f64 {
+        self.cvss_v3().and_then(cvss::base_score).unwrap_or(0.0)
+    }
+
+    /// Severity rating of the score, or the one of the advisory database without a score.
+    pub fn severity(&self) -> String {
+        if let Some(score) = self.cvss_v3().and_then(cvss::base_score) {
+            return cvss::severity(score).to_string();
+        }
+        let severity = self
+            .database_specific
+            .as_ref()
+            .and_then(|specific| specific.get("severity"))
+            .and_then(|severity| severity.as_str())
+            .unwrap_or_default()
+            .to_ascii_uppercase();
+        match severity.as_str() {
+            "MODERATE" => "MEDIUM".to_string(),
+            _ => severity,
+        }
+    }
+
+    pub fn vector(&self) -> Option<String> {
+        self.cvss_v3()
+            .and_then(cvss::attack_vector)
+            .map(str::to_string)
+    }
+
+    /// Unique products of the affected packages of the imported ecosystems.
+    pub fn products(&self) -> Vec<String> {
+        let mut products = vec![];
+        for product in self.affected.iter().filter_map(|a| a.package.product()) {
+            if !products.contains(&product) {
+                products.push(product);
+            }
+        }
+        products
+    }
+
+    pub fn is_match(&self, query: &Query) -> bool {
+        // we need a version
+        let version = match &query.version {
+            Some(version) => version,
+            None => return false,
+        };
+
+        self.affected
+            .iter()
+            .filter(|a| a.package.product().as_deref() == Some(query.product.as_str()))
+            .any(|a| a.is_match(version))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Vulnerability;
+    use crate::search::{testing::query, Query};
+
+    const DJANGO: &str = r#"{
+        "id": "GHSA-2gwj-7jmv-h26r",
+        "modified": "2022-04-12T22:18:49Z",
+        "published": "2022-04-12T00:00:34Z",
+        "aliases": ["CVE-2022-28346"],
+        "summary": "SQL Injection in Django",
+        "details": "An issue was discovered in Django 2.2 before 2.2.28, 3.2 before 3.2.13, and 4.0 before 4.0.4.",
+        "severity": [
+            { "type": "CVSS_V3", "score": "CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H" }
+        ],
+        "affected": [
+            {
+                "package": { "ecosystem": "PyPI", "name": "Django", "purl": "pkg:pypi/django" },
+                "ranges": [
+                    {
+                        "type": "ECOSYSTEM",
+                        "events": [
+                            { "introduced": "2.2" }, { "fixed": "2.2.28" },
+                            { "introduced": "3.0" }, { "fixed": "3.2.13" },
+                            { "introduced": "4.0" }, { "fixed": "4.0.4" }
+                        ]
+                    }
+                ]
+            }
+        ],
+        "references": [
+            { "type": "ADVISORY", "url": "https://nvd.nist.gov/vuln/detail/CVE-2022-28346" }
+        ],
+        "database_specific": { "severity": "CRITICAL" }
+    }"#;
+
+    const MINIMIST: &str = r#"{
+        "id": "GHSA-xvch-5gv4-984h",
+        "modified": "2022-04-04T21:39:38Z",
+        "aliases": [],
+        "details": "Prototype pollution in minimist",
+        "affected": [
+            {
+                "package": { "ecosystem": "npm", "name": "minimist" },
+                "ranges": [
+                    {
+                        "type": "SEMVER",
+                        "events": [{ "introduced": "0" }, { "last_affected": "1.2.5" }]
+                    }
+                ],
+                "versions": ["0.0.1"]
+            },
+            {
+                "package": { "ecosystem": "Maven", "name": "org.webjars.npm:minimist" },
+                "ranges": [{ "type": "ECOSYSTEM", "events": [{ "introduced": "0" }] }]
+            }
+        ],
+        "database_specific": { "severity": "MODERATE" }
+    }"#;
+
+    #[test]
+    fn can_describe_vulnerabilities() {
+        let django: Vulnerability = serde_json::from_str(DJANGO).unwrap();
+        assert_eq!("CVE-2022-28346", django.cve());
+        assert_eq!("SQL Injection in Django", django.summary());
+        assert_eq!(9.8, django.score());
+        assert_eq!("CRITICAL", django.severity());
+        assert_eq!(Some("NETWORK".to_string()), django.vector());
+        assert_eq!(vec!["python-django"], django.products());
+
+        let minimist: Vulnerability = serde_json::from_str(MINIMIST).unwrap();
+        assert_eq!("GHSA-xvch-5gv4-984h", minimist.cve());
+        assert_eq!("Prototype pollution in minimist", minimist.summary());
+        assert_eq!(0.0, minimist.score());
+        assert_eq!("MEDIUM", minimist.severity());
+        // the ecosystems not imported are left out
+        assert_eq!(vec!["node-minimist"], minimist.products());
+    }
+
+    #[test]
+    fn can_match_ranges() {
+        let django: Vulnerability = serde_json::from_str(DJANGO).unwrap();
+        assert!(django.is_match(&query("python-django", "3.2.12")));
+        assert!(django.is_match(&query("python-django", "2.2")));
+        assert!(!django.is_match(&query("python-django", "3.2.13")));
+        assert!(!django.is_match(&query("python-django", "2.1")));
+        assert!(!django.is_match(&query("python-flask", "3.2.12")));
+        assert!(!django.is_match(&Query {
+            vendor: None,
+            product: "python-django".into(),
+            version: None,
+            ..Default::default()
+        }));
+
+        let minimist: Vulnerability = serde_json::from_str(MINIMIST).unwrap();
+        assert!(minimist.is_match(&query("node-minimist", "1.2.5")));
+        assert!(!minimist.is_match(&query("node-minimist", "1.2.6")));
+    }
+}
@@ -0,0 +1,57 @@
+use anyhow::{anyhow, Result};
+
+use super::Advisory;
+
+use crate::db;
+
+/// Stores the advisory under its object key and a record of the source for each of its CVEs
+/// and products fixed, referencing the page of the advisory.
+pub fn store(
+    database: &db::Database,
+    source_name: &str,
+    vendor: &str,
+    object_key: String,
+    url: String,
+    advisory: &Advisory,
+) -> Result<u32> {
+    let products = advisory.products();
+    if products.is_empty() || advisory.cves.is_empty() {
+        return Ok(0);
+    }
+
+    let object_id = database
+        .upsert_object(db::models::NewObject::with(
+            object_key,
+            serde_json::to_string(advisory)?,
+        ))
+        .map_err(|e| anyhow!(e))?;
+
+    let mut refs = db::models::References::default();
+    refs.push(db::models::Reference {
+        url,
+        tags: vec!["Vendor Advisory".into()],
+    });
+
+    let mut num_imported = 0;
+    for cve in &advisory.cves {
+        for product in &products {
+            let new_cve = db::models::NewCVE::with(
+                source_name.into(),
+                vendor.into(),
+                product.clone(),
+                cve.clone(),
+                advisory.summary(),
+                0.0,
+                advisory.severity(),
+                None,
+                refs.clone(),
+                Some(object_id),
+            );
+            if database.upsert_cve(new_cve).map_err(|e| anyhow!(e))? {
+                num_imported += 1;
+            }
+        }
+    }
+
+    Ok(num_imported)
+}
@@ -0,0 +1,159 @@
+//! Advisories of the RPM based distributions, listing the packages of their releases fixing
+//! the CVEs. Amazon Linux, Oracle Linux, Rocky Linux, AlmaLinux and SUSE publish them in
+//! different formats but they're matched alike, by the versions older than the fixed ones.
+
+use std::cmp::Ordering;
+
+use serde::{Deserialize, Serialize};
+
+use super::rpm;
+use crate::scan::ecosystem_product;
+use crate::search::Query;
+
+#[cfg(feature = "import")]
+pub mod import;
+
+/// Product of a package of a release, like `ol-8-openssl`.
+pub fn product(release: &str, package: &str) -> String {
+    ecosystem_product(release, package)
+}
+
+/// Package of a release fixed by an advisory.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Fix {
+    pub release: String,
+    pub package: String,
+    /// Like `1:1.1.1k-9.el8_7`.
+    pub version: String,
+}
+
+#[derive(Debug, Default, Clone, Deserialize, Serialize)]
+pub struct Advisory {
+    /// Like `ELSA-2023-0001`, or the CVE itself for the distributions publishing CVEs.
+    pub id: String,
+    #[serde(default)]
+    pub title: String,
+    #[serde(default)]
+    pub description: String,
+    /// `low`, `moderate` (or `medium`), `important` or `critical`, optionally prefixed by
+    /// `SEVERITY_`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub severity: Option<String>,
+    #[serde(default)]
+    pub issued: String,
+    #[serde(default)]
+    pub cves: Vec<String>,
+    pub fixes: Vec<Fix>,
+}
+
+impl Advisory {
+    /// Release of the packages fixed, for the distributions publishing an advisory for every
+    /// release.
+    pub fn release(&self) -> Option<&str> {
+        self.fixes.first().map(|fix| fix.release.as_str())
+    }
+
+    pub fn summary(&self) -> String {
+        if self.description.trim().is_empty() {
+            return self.title.trim().to_string();
+        }
+        self.description.trim().to_string()
+    }
+
+    /// Severity rated by the distribution, named like the NIST severities.
+    pub fn severity(&self) -> String {
+        let severity = self
+            .severity
+            .as_deref()
+            .map(|severity| severity.trim_start_matches("SEVERITY_").to_lowercase());
+        match severity.as_deref() {
+            Some("critical") => "CRITICAL",
+            Some("important") => "HIGH",
+            Some("moderate") | Some("medium") => "MEDIUM",
+            Some("low") => "LOW",
+            _ => "NONE",
+        }
+        .to_string()
+    }
+
+    /// Unique products of the packages fixed.
+    pub fn products(&self) -> Vec<String> {
+        let mut products = vec![];
+        for fix in &self.fixes {
+            let product = product(&fix.release, &fix.package);
+            if !products.contains(&product) {
+                products.push(product);
+            }
+        }
+        products
+    }
+
+    /// Matches the versions older than the fixed one.
+    pub fn is_match(&self, query: &Query) -> bool {
+        // we need a version
+        let version = match &query.version {
+            Some(version) => rpm::Evr::parse(version),
+            None => return false,
+        };
+
+        self.fixes.iter().any(|fix| {
+            product(&fix.release, &fix.package) == query.product
+                && version.compare(&rpm::Evr::parse(&fix.version)) == Ordering::Less
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Advisory, Fix};
+    use crate::search::{testing::query, Query};
+
+    #[test]
+    fn can_rate_severities() {
+        let mut advisory = Advisory::default();
+        for (severity, expected) in [
+            ("SEVERITY_IMPORTANT", "HIGH"),
+            ("Moderate", "MEDIUM"),
+            ("medium", "MEDIUM"),
+            ("critical", "CRITICAL"),
+            ("unknown", "NONE"),
+        ] {
+            advisory.severity = Some(severity.into());
+            assert_eq!(expected, advisory.severity(), "{}", severity);
+        }
+    }
+
+    #[test]
+    fn can_match_fixed_versions() {
+        let advisory = Advisory {
+            id: "ELSA-2023-0001".into(),
+            fixes: vec![
+                Fix {
+                    release: "ol-8".into(),
+                    package: "openssl".into(),
+                    version: "1:1.1.1k-9.el8_7".into(),
+                },
+                Fix {
+                    release: "ol-8".into(),
+                    package: "openssl-libs".into(),
+                    version: "1:1.1.1k-9.el8_7".into(),
+                },
+            ],
+            ..Default::default()
+        };
+
+        assert_eq!(Some("ol-8"), advisory.release());
+        assert_eq!(
+            vec!["ol-8-openssl", "ol-8-openssl-libs"],
+            advisory.products()
+        );
+        assert!(advisory.is_match(&query("ol-8-openssl", "1:1.1.1k-7.el8_6")));
+        assert!(!advisory.is_match(&query("ol-8-openssl", "1:1.1.1k-9.el8_7")));
+        assert!(!advisory.is_match(&query("ol-8-openssl", "1:1.1.1l-1.el8")));
+        assert!(!advisory.is_match(&query("ol-9-openssl", "1:3.0.1-1.el9")));
+        assert!(!advisory.is_match(&Query {
+            product: "ol-8-openssl".into(),
+            ..Default::default()
+        }));
+    }
+}
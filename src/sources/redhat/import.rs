@@ -0,0 +1,166 @@
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use chrono::Utc;
+use log::{info, warn};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+
+use super::{Cve, SOURCE_NAME, VENDOR};
+
+use crate::db::{self, Pool};
+use crate::utils::get_json;
+
+pub const API_URL: &str = "https://access.redhat.com/hydra/rest/securitydata";
+/// CVEs listed by page.
+const PAGE_SIZE: usize = 1000;
+/// The fixes are backported months after the CVEs are published, and the API can only list
+/// them by publication date, so the CVEs published in this window before the last import
+/// are imported again.
+const REFRESH_WINDOW_DAYS: i64 = 180;
+
+/// Entry of the list of CVEs.
+#[derive(Debug, Deserialize)]
+struct Entry {
+    #[serde(rename = "CVE")]
+    cve: String,
+}
+
+struct Client {
+    http: reqwest::blocking::Client,
+}
+
+impl Client {
+    fn new() -> Result<Self> {
+        Ok(Self {
+            http: reqwest::blocking::Client::builder()
+                .timeout(Some(Duration::from_secs(300)))
+                .user_agent(format!("kepler/{}", crate::version()))
+                .build()?,
+        })
+    }
+
+    fn get<T: DeserializeOwned>(&self, url: &str) -> Result<T> {
+        Ok(get_json(&self.http, url)?)
+    }
+
+    /// Lists the CVEs published after the date, formatted as `YYYY-MM-DD`.
+    fn list(&self, after: Option<&str>, page: usize) -> Result<Vec<Entry>> {
+        let mut url = format!("{}/cve.json?per_page={}&page={}", API_URL, PAGE_SIZE, page);
+        if let Some(after) = after {
+            url.push_str(&format!("&after={}", after));
+        }
+        self.get(&url)
+    }
+
+    fn cve(&self, id: &str) -> Result<Cve> {
+        self.get(&format!("{}/cve/{}.json", API_URL, id))
+    }
+}
+
+fn store(database: &db::Database, cve: &Cve) -> Result<u32> {
+    let products = cve.products();
+    if products.is_empty() {
+        return Ok(0);
+    }
+
+    let object_id = database
+        .upsert_object(db::models::NewObject::with(
+            Cve::object_key(&cve.name),
+            serde_json::to_string(cve)?,
+        ))
+        .map_err(|e| anyhow!(e))?;
+
+    let mut refs = db::models::References::default();
+    refs.push(db::models::Reference {
+        url: format!("https://access.redhat.com/security/cve/{}", cve.name),
+        tags: vec!["Red Hat".into()],
+    });
+    let mut advisories: Vec<&str> = cve
+        .affected_release
+        .iter()
+        .filter_map(|release| release.advisory.as_deref())
+        .collect();
+    advisories.sort_unstable();
+    advisories.dedup();
+    for advisory in advisories {
+        refs.push(db::models::Reference {
+            url: format!("https://access.redhat.com/errata/{}", advisory),
+            tags: vec!["Vendor Advisory".into()],
+        });
+    }
+
+    let mut num_imported = 0;
+    for product in products {
+        let new_cve = db::models::NewCVE::with(
+            SOURCE_NAME.into(),
+            VENDOR.into(),
+            product,
+            cve.name.clone(),
+            cve.summary(),
+            cve.score(),
+            cve.severity(),
+            cve.vector(),
+            refs.clone(),
+            Some(object_id),
+        );
+        if database.upsert_cve(new_cve).map_err(|e| anyhow!(e))? {
+            num_imported += 1;
+        }
+    }
+
+    Ok(num_imported)
+}
+
+/// Imports the CVEs of the RHEL packages published since shortly before the last import,
+/// or all of them the first time and with `fresh`, creating the new records and updating
+/// the changed ones.
+pub fn run(pool: &Pool, fresh: bool) -> Result<u32> {
+    let client = Client::new()?;
+    let database = db::Database(pool.get()?);
+
+    let after = if fresh {
+        None
+    } else {
+        database
+            .get_synced_at(SOURCE_NAME)
+            .map_err(|e| anyhow!(e))?
+            .map(|since| {
+                (since - chrono::Duration::days(REFRESH_WINDOW_DAYS))
+                    .format("%Y-%m-%d")
+                    .to_string()
+            })
+    };
+    let until = Utc::now();
+    match &after {
+        Some(after) => info!("importing the CVEs published after {}", after),
+        None => info!("importing all the CVEs"),
+    }
+
+    let mut num_imported = 0;
+    let mut num_cves = 0;
+    let mut page = 1;
+    loop {
+        let entries = client.list(after.as_deref(), page)?;
+
+        for entry in &entries {
+            match client.cve(&entry.cve) {
+                Ok(cve) => num_imported += store(&database, &cve)?,
+                Err(e) => warn!("skipping {}: {:#}", entry.cve, e),
+            }
+        }
+        num_cves += entries.len();
+        info!("processed {} CVEs ...", num_cves);
+
+        if entries.len() < PAGE_SIZE {
+            break;
+        }
+        page += 1;
+    }
+
+    database
+        .set_synced_at(db::models::NewSyncState::with(SOURCE_NAME.into(), until))
+        .map_err(|e| anyhow!(e))?;
+
+    Ok(num_imported)
+}
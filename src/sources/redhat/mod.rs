@@ -0,0 +1,327 @@
+//! CVEs of the Red Hat Enterprise Linux packages published by the Red Hat Security Data
+//! API, with the releases fixing them as backported by Red Hat rather than the upstream
+//! version ranges.
+
+use std::cmp::Ordering;
+
+use serde::{Deserialize, Serialize};
+
+use super::osv::cvss;
+use crate::search::Query;
+
+pub mod advisory;
+#[cfg(feature = "import")]
+pub mod import;
+pub mod rpm;
+
+pub const SOURCE_NAME: &str = "Red Hat";
+/// No vendors for the packages, the RHEL release is part of the product name.
+pub const VENDOR: &str = "@redhat";
+
+/// States of the packages affected and not fixed yet, the other ones being `Not affected`
+/// and `Under investigation`.
+pub const AFFECTED_STATES: &[&str] = &[
+    "Affected",
+    "Fix deferred",
+    "Will not fix",
+    "Out of support scope",
+];
+
+/// Release of a RHEL version, like `rhel-8` for `8.5`, the fixes being shared by the minor
+/// releases.
+pub fn release(version: &str) -> Option<String> {
+    version
+        .split('.')
+        .next()
+        .filter(|major| !major.is_empty() && major.chars().all(|c| c.is_ascii_digit()))
+        .map(|major| format!("rhel-{}", major))
+}
+
+/// Product of a package of a RHEL release, like `rhel-8-openssl`, named like the ones of the
+/// other RPM based distributions.
+pub fn product(release: &str, package: &str) -> String {
+    advisory::product(release, package)
+}
+
+/// Release of the RHEL CPEs like `cpe:/o:redhat:enterprise_linux:8` or
+/// `cpe:/a:redhat:enterprise_linux:8::appstream`, the other products being left out.
+fn rhel_release(cpe: &str) -> Option<String> {
+    let version = cpe.strip_prefix("cpe:/")?.split(':').nth(3)?;
+    let product = cpe.strip_prefix("cpe:/")?.split(':').nth(2)?;
+    if product != "enterprise_linux" {
+        return None;
+    }
+    release(version)
+}
+
+#[derive(Debug, Default, Clone, Deserialize, Serialize)]
+pub struct Bugzilla {
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub url: String,
+}
+
+#[derive(Debug, Default, Clone, Deserialize, Serialize)]
+pub struct Cvss3 {
+    pub cvss3_base_score: String,
+    pub cvss3_scoring_vector: String,
+}
+
+/// Package fixed by an advisory.
+#[derive(Debug, Default, Clone, Deserialize, Serialize)]
+pub struct Release {
+    pub product_name: String,
+    #[serde(default)]
+    pub advisory: Option<String>,
+    #[serde(default)]
+    pub cpe: String,
+    /// Package with the fixed version, like `openssl-1:1.1.1k-5.el8_5`.
+    #[serde(default)]
+    pub package: Option<String>,
+}
+
+impl Release {
+    /// Product and fixed version of the package, not set for the modules and the products
+    /// other than RHEL.
+    pub fn fixed(&self) -> Option<(String, &str)> {
+        let release = rhel_release(&self.cpe)?;
+        let (name, evr) = rpm::split_package(self.package.as_deref()?)?;
+        // module streams like `container-tools:rhel8-8040020210708131418.522a0ee4`
+        if name.contains(':') || (evr.contains(':') && rpm::Evr::parse(evr).epoch.is_none()) {
+            return None;
+        }
+        Some((product(&release, name), evr))
+    }
+}
+
+/// State of a package not fixed by an advisory.
+#[derive(Debug, Default, Clone, Deserialize, Serialize)]
+pub struct PackageState {
+    pub product_name: String,
+    pub fix_state: String,
+    pub package_name: String,
+    #[serde(default)]
+    pub cpe: String,
+}
+
+impl PackageState {
+    /// Product of the package, set when it's affected and not fixed.
+    pub fn unfixed(&self) -> Option<String> {
+        if !AFFECTED_STATES.contains(&self.fix_state.as_str()) || self.package_name.contains(':') {
+            return None;
+        }
+        let release = rhel_release(&self.cpe)?;
+        Some(product(&release, &self.package_name))
+    }
+}
+
+#[derive(Debug, Default, Clone, Deserialize, Serialize)]
+pub struct Cve {
+    pub name: String,
+    /// `Low`, `Moderate`, `Important` or `Critical`.
+    #[serde(default)]
+    pub threat_severity: Option<String>,
+    #[serde(default)]
+    pub public_date: Option<String>,
+    #[serde(default)]
+    pub bugzilla: Bugzilla,
+    #[serde(default)]
+    pub cvss3: Option<Cvss3>,
+    #[serde(default)]
+    pub details: Vec<String>,
+    #[serde(default)]
+    pub references: Vec<String>,
+    #[serde(default)]
+    pub affected_release: Vec<Release>,
+    #[serde(default)]
+    pub package_state: Vec<PackageState>,
+}
+
+impl Cve {
+    pub fn object_key(id: &str) -> String {
+        format!("{} (redhat)", id)
+    }
+
+    /// Title of the Bugzilla entry without the CVE and the component it starts with, or the
+    /// first line of the details.
+    pub fn summary(&self) -> String {
+        let description = self.bugzilla.description.trim();
+        let description = description
+            .strip_prefix(self.name.as_str())
+            .unwrap_or(description)
+            .trim();
+        let description = match description.split_once(": ") {
+            Some((component, title)) if !component.contains(' ') => title,
+            _ => description,
+        };
+        if !description.is_empty() {
+            return description.to_string();
+        }
+        self.details
+            .first()
+            .and_then(|details| details.lines().next())
+            .unwrap_or_default()
+            .trim()
+            .to_string()
+    }
+
+    pub fn score(&self) -> f64 {
+        self.cvss3
+            .as_ref()
+            .and_then(|cvss3| cvss3.cvss3_base_score.parse::<f64>().ok())
+            .unwrap_or(0.0)
+    }
+
+    /// Red Hat rating of the CVE named like the NIST severities, or the one of the score
+    /// without a rating.
+    pub fn severity(&self) -> String {
+        match self.threat_severity.as_deref() {
+            Some("Critical") => "CRITICAL".to_string(),
+            Some("Important") => "HIGH".to_string(),
+            Some("Moderate") => "MEDIUM".to_string(),
+            Some("Low") => "LOW".to_string(),
+            _ => cvss::severity(self.score()).to_string(),
+        }
+    }
+
+    pub fn vector(&self) -> Option<String> {
+        self.cvss3
+            .as_ref()
+            .and_then(|cvss3| cvss::attack_vector(&cvss3.cvss3_scoring_vector))
+            .map(str::to_string)
+    }
+
+    /// Unique products of the RHEL packages fixed or affected.
+    pub fn products(&self) -> Vec<String> {
+        let fixed = self
+            .affected_release
+            .iter()
+            .filter_map(|release| release.fixed().map(|(product, _)| product));
+        let unfixed = self.package_state.iter().filter_map(PackageState::unfixed);
+
+        let mut products = vec![];
+        for product in fixed.chain(unfixed) {
+            if !products.contains(&product) {
+                products.push(product);
+            }
+        }
+        products
+    }
+
+    /// Matches the versions older than the fixed one, or any version while not fixed.
+    pub fn is_match(&self, query: &Query) -> bool {
+        // we need a version
+        let version = match &query.version {
+            Some(version) => rpm::Evr::parse(version),
+            None => return false,
+        };
+
+        let is_fixed = self
+            .affected_release
+            .iter()
+            .any(|release| match release.fixed() {
+                Some((product, fixed)) if product == query.product => {
+                    version.compare(&rpm::Evr::parse(fixed)) == Ordering::Less
+                }
+                _ => false,
+            });
+
+        is_fixed
+            || self
+                .package_state
+                .iter()
+                .any(|state| state.unfixed().as_deref() == Some(query.product.as_str()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Cve;
+    use crate::search::{testing::query, Query};
+
+    const OPENSSL: &str = r#"{
+        "threat_severity": "Moderate",
+        "public_date": "2021-08-24T00:00:00Z",
+        "bugzilla": {
+            "description": "CVE-2021-3712 openssl: Read buffer overruns processing ASN.1 strings",
+            "id": "1995634",
+            "url": "https://bugzilla.redhat.com/show_bug.cgi?id=1995634"
+        },
+        "cvss3": {
+            "cvss3_base_score": "7.4",
+            "cvss3_scoring_vector": "CVSS:3.1/AV:N/AC:H/PR:N/UI:N/S:U/C:H/I:N/A:H",
+            "status": "verified"
+        },
+        "details": ["ASN.1 strings are represented internally within OpenSSL as an ASN1_STRING structure."],
+        "affected_release": [
+            {
+                "product_name": "Red Hat Enterprise Linux 8",
+                "release_date": "2021-11-09T00:00:00Z",
+                "advisory": "RHSA-2021:4614",
+                "cpe": "cpe:/o:redhat:enterprise_linux:8",
+                "package": "openssl-1:1.1.1k-5.el8_5"
+            },
+            {
+                "product_name": "Red Hat Enterprise Linux 8.4 Extended Update Support",
+                "advisory": "RHSA-2021:4613",
+                "cpe": "cpe:/o:redhat:rhel_eus:8.4",
+                "package": "openssl-1:1.1.1g-16.el8_4"
+            },
+            {
+                "product_name": "Red Hat Enterprise Linux 8",
+                "advisory": "RHSA-2021:4904",
+                "cpe": "cpe:/a:redhat:enterprise_linux:8",
+                "package": "container-tools:rhel8-8050020211112164321.abc"
+            }
+        ],
+        "package_state": [
+            {
+                "product_name": "Red Hat Enterprise Linux 7",
+                "fix_state": "Will not fix",
+                "package_name": "openssl",
+                "cpe": "cpe:/o:redhat:enterprise_linux:7"
+            },
+            {
+                "product_name": "Red Hat Enterprise Linux 9",
+                "fix_state": "Not affected",
+                "package_name": "openssl",
+                "cpe": "cpe:/o:redhat:enterprise_linux:9"
+            }
+        ],
+        "name": "CVE-2021-3712"
+    }"#;
+
+    #[test]
+    fn can_describe_cves() {
+        let cve: Cve = serde_json::from_str(OPENSSL).unwrap();
+        assert_eq!(
+            "Read buffer overruns processing ASN.1 strings",
+            cve.summary()
+        );
+        assert_eq!(7.4, cve.score());
+        assert_eq!("MEDIUM", cve.severity());
+        assert_eq!(Some("NETWORK".to_string()), cve.vector());
+        // the EUS streams, the modules and the releases not affected are left out
+        assert_eq!(vec!["rhel-8-openssl", "rhel-7-openssl"], cve.products());
+    }
+
+    #[test]
+    fn can_match_backported_fixes() {
+        let cve: Cve = serde_json::from_str(OPENSSL).unwrap();
+        assert!(cve.is_match(&query("rhel-8-openssl", "1:1.1.1k-4.el8")));
+        assert!(cve.is_match(&query("rhel-8-openssl", "1.1.1g-15.el8_3")));
+        // the upstream version is still vulnerable, but the fix is backported
+        assert!(!cve.is_match(&query("rhel-8-openssl", "1:1.1.1k-5.el8_5")));
+        assert!(!cve.is_match(&query("rhel-8-openssl", "1.1.1k-6.el8_5")));
+        // never fixed
+        assert!(cve.is_match(&query("rhel-7-openssl", "1:1.0.2k-22.el7_9")));
+        assert!(!cve.is_match(&query("rhel-9-openssl", "1:3.0.1-20.el9")));
+        assert!(!cve.is_match(&Query {
+            vendor: None,
+            product: "rhel-8-openssl".into(),
+            version: None,
+            ..Default::default()
+        }));
+    }
+}
@@ -0,0 +1,214 @@
+//! Comparison of the RPM package versions, like `rpmvercmp` does.
+
+use std::cmp::Ordering;
+
+/// Epoch, version and release of a package, like `1:1.1.1k-5.el8_5`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Evr<'a> {
+    pub epoch: Option<u64>,
+    pub version: &'a str,
+    pub release: Option<&'a str>,
+}
+
+impl<'a> Evr<'a> {
+    pub fn parse(evr: &'a str) -> Self {
+        let (epoch, rest) = match evr.split_once(':') {
+            Some((epoch, rest)) => match epoch.parse::<u64>() {
+                Ok(epoch) => (Some(epoch), rest),
+                Err(_) => (None, evr),
+            },
+            None => (None, evr),
+        };
+        let (version, release) = match rest.rsplit_once('-') {
+            Some((version, release)) => (version, Some(release)),
+            None => (rest, None),
+        };
+
+        Self {
+            epoch,
+            version,
+            release,
+        }
+    }
+
+    /// Compares with another version of the same package. The epochs are compared only if
+    /// both are set, as the versions of the packages of an inventory often lack them, and
+    /// the releases only if both are set.
+    pub fn compare(&self, other: &Evr) -> Ordering {
+        if let (Some(epoch), Some(other_epoch)) = (self.epoch, other.epoch) {
+            if epoch != other_epoch {
+                return epoch.cmp(&other_epoch);
+            }
+        }
+
+        match compare(self.version, other.version) {
+            Ordering::Equal => match (self.release, other.release) {
+                (Some(release), Some(other_release)) => compare(release, other_release),
+                _ => Ordering::Equal,
+            },
+            ordering => ordering,
+        }
+    }
+}
+
+/// Splits a package like `openssl-libs-1:1.1.1k-5.el8_5` into its name and its version.
+pub fn split_package(package: &str) -> Option<(&str, &str)> {
+    let mut parts = package.rsplitn(3, '-');
+    let release = parts.next()?;
+    let version = parts.next()?;
+    let name = parts.next()?;
+    let evr_len = version.len() + 1 + release.len();
+    Some((name, &package[package.len() - evr_len..]))
+}
+
+fn is_separator(c: char) -> bool {
+    !c.is_ascii_alphanumeric() && c != '~' && c != '^'
+}
+
+/// Compares two versions or releases segment by segment, numeric segments being newer than
+/// alphabetic ones, `~` sorting before anything (pre-releases) and `^` after the version it
+/// follows but before any longer one (snapshots).
+pub fn compare(a: &str, b: &str) -> Ordering {
+    if a == b {
+        return Ordering::Equal;
+    }
+
+    let (mut a, mut b) = (a, b);
+    loop {
+        a = a.trim_start_matches(is_separator);
+        b = b.trim_start_matches(is_separator);
+
+        match (a.starts_with('~'), b.starts_with('~')) {
+            (true, true) => {
+                a = &a[1..];
+                b = &b[1..];
+                continue;
+            }
+            (true, false) => return Ordering::Less,
+            (false, true) => return Ordering::Greater,
+            (false, false) => {}
+        }
+
+        if a.starts_with('^') || b.starts_with('^') {
+            if a.is_empty() {
+                return Ordering::Less;
+            }
+            if b.is_empty() {
+                return Ordering::Greater;
+            }
+            if !a.starts_with('^') {
+                return Ordering::Greater;
+            }
+            if !b.starts_with('^') {
+                return Ordering::Less;
+            }
+            a = &a[1..];
+            b = &b[1..];
+            continue;
+        }
+
+        if a.is_empty() || b.is_empty() {
+            break;
+        }
+
+        let numeric = a.starts_with(|c: char| c.is_ascii_digit());
+        let in_segment = |c: char| {
+            if numeric {
+                c.is_ascii_digit()
+            } else {
+                c.is_ascii_alphabetic()
+            }
+        };
+        let a_len = a.find(|c: char| !in_segment(c)).unwrap_or_else(|| a.len());
+        let b_len = b.find(|c: char| !in_segment(c)).unwrap_or_else(|| b.len());
+        let (a_segment, b_segment) = (&a[..a_len], &b[..b_len]);
+
+        // segments of different types, the numeric one is newer
+        if b_segment.is_empty() {
+            return if numeric {
+                Ordering::Greater
+            } else {
+                Ordering::Less
+            };
+        }
+
+        let ordering = if numeric {
+            let a_segment = a_segment.trim_start_matches('0');
+            let b_segment = b_segment.trim_start_matches('0');
+            a_segment
+                .len()
+                .cmp(&b_segment.len())
+                .then_with(|| a_segment.cmp(b_segment))
+        } else {
+            a_segment.cmp(b_segment)
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+
+        a = &a[a_len..];
+        b = &b[b_len..];
+    }
+
+    match (a.is_empty(), b.is_empty()) {
+        (true, true) => Ordering::Equal,
+        (true, false) => Ordering::Less,
+        (false, _) => Ordering::Greater,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cmp::Ordering;
+
+    use super::{compare, split_package, Evr};
+
+    #[test]
+    fn can_compare_versions() {
+        assert_eq!(Ordering::Equal, compare("1.0", "1.0"));
+        assert_eq!(Ordering::Less, compare("1.0", "1.0.1"));
+        assert_eq!(Ordering::Greater, compare("2.10", "2.9"));
+        assert_eq!(Ordering::Less, compare("1.1.1k", "1.1.1l"));
+        assert_eq!(Ordering::Greater, compare("1.0a", "1.0"));
+        assert_eq!(Ordering::Greater, compare("1.1", "1.a"));
+        assert_eq!(Ordering::Equal, compare("1.01", "1.1"));
+        assert_eq!(Ordering::Less, compare("1.0~rc1", "1.0"));
+        assert_eq!(Ordering::Less, compare("1.0~rc1", "1.0~rc2"));
+        assert_eq!(Ordering::Greater, compare("1.0^20220101", "1.0"));
+        assert_eq!(Ordering::Less, compare("1.0^20220101", "1.0.1"));
+        assert_eq!(Ordering::Less, compare("5.el8", "5.el8_5"));
+        assert_eq!(Ordering::Less, compare("5.el8_4", "5.el8_5"));
+    }
+
+    #[test]
+    fn can_compare_evrs() {
+        let fixed = Evr::parse("1:1.1.1k-5.el8_5");
+        assert_eq!(Some(1), fixed.epoch);
+        assert_eq!("1.1.1k", fixed.version);
+        assert_eq!(Some("5.el8_5"), fixed.release);
+
+        assert_eq!(Ordering::Less, Evr::parse("1:1.1.1k-4.el8").compare(&fixed));
+        assert_eq!(
+            Ordering::Equal,
+            Evr::parse("1.1.1k-5.el8_5").compare(&fixed)
+        );
+        assert_eq!(Ordering::Less, Evr::parse("1.1.1g").compare(&fixed));
+        assert_eq!(
+            Ordering::Greater,
+            Evr::parse("2:1.0.0-1.el8").compare(&fixed)
+        );
+    }
+
+    #[test]
+    fn can_split_packages() {
+        assert_eq!(
+            Some(("openssl-libs", "1:1.1.1k-5.el8_5")),
+            split_package("openssl-libs-1:1.1.1k-5.el8_5")
+        );
+        assert_eq!(
+            Some(("kernel", "4.18.0-348.el8")),
+            split_package("kernel-4.18.0-348.el8")
+        );
+        assert_eq!(None, split_package("kernel"));
+    }
+}
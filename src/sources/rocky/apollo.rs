@@ -0,0 +1,172 @@
+//! Responses of the Apollo errata API, converted into the advisories of the packages fixed in
+//! every release.
+
+use std::collections::BTreeMap;
+
+use serde::Deserialize;
+
+use super::{platform, Advisory, Fix};
+use crate::sources::redhat::rpm;
+use crate::utils::normalize_utc;
+
+/// Page of the advisories, the newest first.
+#[derive(Debug, Deserialize)]
+pub struct Page {
+    pub advisories: Vec<ApolloAdvisory>,
+    #[serde(default)]
+    pub total: usize,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ApolloCve {
+    pub name: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct ApolloRpms {
+    /// Like `openssl-1:1.1.1k-9.el8_7.x86_64.rpm`.
+    #[serde(default)]
+    pub nvras: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApolloAdvisory {
+    pub name: String,
+    #[serde(default)]
+    pub synopsis: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub severity: Option<String>,
+    #[serde(default)]
+    pub published_at: String,
+    #[serde(default)]
+    pub cves: Vec<ApolloCve>,
+    /// Packages of every product, like `Rocky Linux 8`.
+    #[serde(default)]
+    pub rpms: BTreeMap<String, ApolloRpms>,
+}
+
+/// Name and version of a binary package, like `openssl` and `1:1.1.1k-9.el8_7` for
+/// `openssl-1:1.1.1k-9.el8_7.x86_64.rpm`, the source packages being left out.
+fn package(nvra: &str) -> Option<(&str, &str)> {
+    let nevr_arch = nvra.strip_suffix(".rpm").unwrap_or(nvra);
+    let (nevr, arch) = nevr_arch.rsplit_once('.')?;
+    if arch == "src" {
+        return None;
+    }
+    rpm::split_package(nevr)
+}
+
+impl ApolloAdvisory {
+    pub fn advisory(&self) -> Advisory {
+        let mut fixes = vec![];
+        for (product, rpms) in &self.rpms {
+            let release = match platform(product) {
+                Some(release) => release,
+                None => continue,
+            };
+            for (package, version) in rpms.nvras.iter().filter_map(|nvra| package(nvra)) {
+                // the same fix for every architecture
+                let fix = Fix {
+                    release: release.clone(),
+                    package: package.to_string(),
+                    version: version.to_string(),
+                };
+                if !fixes.contains(&fix) {
+                    fixes.push(fix);
+                }
+            }
+        }
+
+        let mut cves: Vec<String> = vec![];
+        for cve in &self.cves {
+            if !cves.contains(&cve.name) {
+                cves.push(cve.name.clone());
+            }
+        }
+
+        Advisory {
+            id: self.name.clone(),
+            title: self.synopsis.clone(),
+            description: self.description.clone(),
+            severity: self.severity.clone(),
+            issued: normalize_utc(&self.published_at),
+            cves,
+            fixes,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{package, Page};
+
+    const PAGE: &str = r#"{
+        "advisories": [
+            {
+                "type": "TYPE_SECURITY",
+                "shortCode": "RL",
+                "name": "RLSA-2023:0946",
+                "synopsis": "Important: openssl security update",
+                "severity": "SEVERITY_IMPORTANT",
+                "topic": "An update is available for openssl.",
+                "description": "OpenSSL is a toolkit that implements the SSL and TLS protocols.",
+                "solution": null,
+                "affectedProducts": ["Rocky Linux 8"],
+                "fixes": [],
+                "cves": [
+                    { "name": "CVE-2023-0286", "sourceBy": "MITRE" },
+                    { "name": "CVE-2022-4304", "sourceBy": "MITRE" }
+                ],
+                "references": [],
+                "publishedAt": "2023-03-14T06:45:34.436911Z",
+                "rpms": {
+                    "Rocky Linux 8": {
+                        "nvras": [
+                            "openssl-1:1.1.1k-9.el8_7.src.rpm",
+                            "openssl-1:1.1.1k-9.el8_7.x86_64.rpm",
+                            "openssl-1:1.1.1k-9.el8_7.aarch64.rpm",
+                            "openssl-libs-1:1.1.1k-9.el8_7.x86_64.rpm"
+                        ]
+                    },
+                    "Rocky Linux SIG Cloud 8": {
+                        "nvras": ["openssl-1:1.1.1k-9.el8_7.cloud.x86_64.rpm"]
+                    }
+                },
+                "rebootSuggested": false,
+                "buildReferences": []
+            }
+        ],
+        "total": 1,
+        "page": 0,
+        "size": 100
+    }"#;
+
+    #[test]
+    fn can_split_packages() {
+        assert_eq!(
+            Some(("openssl-libs", "1:1.1.1k-9.el8_7")),
+            package("openssl-libs-1:1.1.1k-9.el8_7.x86_64.rpm")
+        );
+        assert_eq!(None, package("openssl-1:1.1.1k-9.el8_7.src.rpm"));
+    }
+
+    #[test]
+    fn can_convert_advisories() {
+        let page: Page = serde_json::from_str(PAGE).unwrap();
+        assert_eq!(1, page.total);
+
+        let advisory = page.advisories[0].advisory();
+        assert_eq!("RLSA-2023:0946", advisory.id);
+        assert_eq!("HIGH", advisory.severity());
+        assert_eq!("2023-03-14T06:45:34Z", advisory.issued);
+        assert_eq!(vec!["CVE-2023-0286", "CVE-2022-4304"], advisory.cves);
+        assert_eq!(
+            vec!["rocky-8-openssl", "rocky-8-openssl-libs"],
+            advisory.products()
+        );
+        assert_eq!("1:1.1.1k-9.el8_7", advisory.fixes[0].version);
+    }
+}
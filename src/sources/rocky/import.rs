@@ -0,0 +1,103 @@
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use chrono::Utc;
+use log::info;
+
+use super::{apollo, object_key, url, Advisory, SOURCE_NAME, VENDOR};
+
+use crate::db::{self, Pool};
+use crate::sources::redhat;
+use crate::utils::{get_json, parse_utc};
+
+pub const API_URL: &str = "https://errata.rockylinux.org/api/v2/advisories";
+/// Advisories listed by page.
+const PAGE_SIZE: usize = 100;
+/// The advisories published shortly before the last import are imported again, in case they
+/// were amended since.
+const REFRESH_WINDOW_DAYS: i64 = 7;
+
+fn get_page(client: &reqwest::blocking::Client, page: usize) -> Result<apollo::Page> {
+    let url = format!(
+        "{}?filters.type=TYPE_SECURITY&filters.fetchRelated=true&page={}&limit={}",
+        API_URL, page, PAGE_SIZE
+    );
+
+    Ok(get_json(client, &url)?)
+}
+
+fn store(database: &db::Database, advisory: &Advisory) -> Result<u32> {
+    redhat::advisory::import::store(
+        database,
+        SOURCE_NAME,
+        VENDOR,
+        object_key(&advisory.id),
+        url(advisory),
+        advisory,
+    )
+}
+
+/// Imports the Rocky Linux advisories published since shortly before the last import, or all
+/// of them the first time and with `fresh`, creating the new records and updating the
+/// changed ones.
+pub fn run(pool: &Pool, fresh: bool) -> Result<u32> {
+    let client = reqwest::blocking::Client::builder()
+        .timeout(Some(Duration::from_secs(300)))
+        .user_agent(format!("kepler/{}", crate::version()))
+        .build()?;
+    let database = db::Database(pool.get()?);
+
+    let since = if fresh {
+        None
+    } else {
+        database
+            .get_synced_at(SOURCE_NAME)
+            .map_err(|e| anyhow!(e))?
+            .map(|since| since - chrono::Duration::days(REFRESH_WINDOW_DAYS))
+    };
+    let until = Utc::now();
+    match &since {
+        Some(since) => info!("importing the advisories published since {}", since),
+        None => info!("importing all the advisories"),
+    }
+
+    let mut advisories = vec![];
+    let mut num_listed = 0;
+    let mut page = 0;
+    loop {
+        let listed = get_page(&client, page)?;
+        let num_advisories = listed.advisories.len();
+
+        num_listed += num_advisories;
+        let mut done = num_advisories < PAGE_SIZE || num_listed >= listed.total;
+        for advisory in listed.advisories {
+            // compared in UTC, whatever the offset of the timestamps
+            match (&since, parse_utc(&advisory.published_at)) {
+                (Some(since), Some(published)) if published < *since => done = true,
+                _ => advisories.push(advisory.advisory()),
+            }
+        }
+
+        info!("listed {} advisories ...", num_listed);
+        if done {
+            break;
+        }
+        page += 1;
+    }
+
+    // the oldest first, so that the fixes of the later advisories of the same CVEs replace
+    // the previous ones
+    advisories.reverse();
+    info!("importing {} advisories ...", advisories.len());
+
+    let mut num_imported = 0;
+    for advisory in &advisories {
+        num_imported += store(&database, advisory)?;
+    }
+
+    database
+        .set_synced_at(db::models::NewSyncState::with(SOURCE_NAME.into(), until))
+        .map_err(|e| anyhow!(e))?;
+
+    Ok(num_imported)
+}
@@ -0,0 +1,75 @@
+//! CVEs of the Rocky Linux packages fixed by the Rocky Linux Security Advisories (RLSA),
+//! rebuilt from the Red Hat ones and published by the Apollo errata API with the versions of
+//! the Rocky Linux packages.
+
+pub use super::redhat::advisory::{product, Advisory, Fix};
+
+#[cfg(feature = "import")]
+pub mod apollo;
+#[cfg(feature = "import")]
+pub mod import;
+
+pub const SOURCE_NAME: &str = "Rocky";
+/// No vendors for the packages, the Rocky Linux release is part of the product name.
+pub const VENDOR: &str = "@rocky";
+
+/// Release of a Rocky Linux version, like `rocky-8` for `8.7`.
+pub fn release(version: &str) -> Option<String> {
+    version
+        .split('.')
+        .next()
+        .filter(|major| !major.is_empty() && major.chars().all(|c| c.is_ascii_digit()))
+        .map(|major| format!("rocky-{}", major))
+}
+
+/// Release of a product of the advisories, like `rocky-8` for `Rocky Linux 8`, the other
+/// products like the SIGs being left out.
+pub fn platform(name: &str) -> Option<String> {
+    release(name.strip_prefix("Rocky Linux ")?)
+}
+
+pub fn object_key(id: &str) -> String {
+    format!("{} (rocky)", id)
+}
+
+/// Page of the advisory in the Rocky Linux errata.
+pub fn url(advisory: &Advisory) -> String {
+    format!("https://errata.rockylinux.org/{}", advisory.id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{platform, release, url, Advisory, Fix};
+    use crate::search::testing::query;
+
+    #[test]
+    fn can_name_releases() {
+        assert_eq!(Some("rocky-8".to_string()), release("8.7"));
+        assert_eq!(Some("rocky-9".to_string()), platform("Rocky Linux 9"));
+        assert_eq!(None, platform("Rocky Linux SIG Cloud 8"));
+    }
+
+    #[test]
+    fn can_match_fixed_versions() {
+        let advisory = Advisory {
+            id: "RLSA-2023:0946".into(),
+            severity: Some("SEVERITY_IMPORTANT".into()),
+            fixes: vec![Fix {
+                release: "rocky-8".into(),
+                package: "openssl".into(),
+                version: "1:1.1.1k-9.el8_7".into(),
+            }],
+            ..Default::default()
+        };
+
+        assert_eq!("HIGH", advisory.severity());
+        assert_eq!(
+            "https://errata.rockylinux.org/RLSA-2023:0946",
+            url(&advisory)
+        );
+        assert_eq!(vec!["rocky-8-openssl"], advisory.products());
+        assert!(advisory.is_match(&query("rocky-8-openssl", "1:1.1.1k-7.el8_6")));
+        assert!(!advisory.is_match(&query("rocky-8-openssl", "1:1.1.1k-9.el8_7")));
+        assert!(!advisory.is_match(&query("rocky-9-openssl", "1:3.0.1-1.el9")));
+    }
+}
@@ -0,0 +1,111 @@
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::BufReader;
+use std::path::Path;
+
+use anyhow::{anyhow, Context, Result};
+use log::info;
+
+use super::{object_key, oval, url, Advisory, SOURCE_NAME, VENDOR};
+
+use crate::db::{self, Pool};
+use crate::sources::redhat;
+use crate::utils::{download_to_file, gunzip, ImportError};
+
+pub const FEED_URL: &str = "https://ftp.suse.com/pub/projects/security/oval";
+
+/// OVAL definitions imported, with the release of the packages of the criteria without a
+/// platform.
+pub const DEFINITIONS: &[(&str, Option<&str>)] = &[
+    ("suse.linux.enterprise.server.12", None),
+    ("suse.linux.enterprise.server.15", None),
+    ("opensuse.leap.15.5", Some("opensuse-leap-15.5")),
+    ("opensuse.leap.15.6", Some("opensuse-leap-15.6")),
+    ("opensuse.tumbleweed", Some("opensuse-tumbleweed")),
+];
+
+fn store(database: &db::Database, cve: &Advisory) -> Result<u32> {
+    let affected: Vec<(String, String)> = cve
+        .products()
+        .into_iter()
+        .map(|product| (VENDOR.to_string(), product))
+        .collect();
+    // the fixes withdrawn from the definitions
+    database
+        .delete_other_products(SOURCE_NAME, &cve.id, &affected)
+        .map_err(|e| anyhow!(e))?;
+
+    redhat::advisory::import::store(
+        database,
+        SOURCE_NAME,
+        VENDOR,
+        object_key(&cve.id),
+        url(cve),
+        cve,
+    )
+}
+
+/// Imports the CVEs of the SUSE Linux Enterprise and openSUSE packages from the OVAL
+/// definitions, downloaded again with `fresh`, creating the new records, updating the changed
+/// ones and deleting the ones of the fixes withdrawn.
+pub fn run(pool: &Pool, data_path: &Path, fresh: bool) -> Result<u32> {
+    let mut suse_path = data_path.to_path_buf();
+    suse_path.push("suse");
+    fs::create_dir_all(&suse_path)
+        .with_context(|| format!("could not create {}", suse_path.display()))?;
+
+    // the same CVEs are defined for every release
+    let mut cves: BTreeMap<String, Advisory> = BTreeMap::new();
+    for (name, default_release) in DEFINITIONS {
+        let mut gz_path = suse_path.clone();
+        gz_path.push(format!("{}.xml.gz", name));
+        let mut file_path = suse_path.clone();
+        file_path.push(format!("{}.xml", name));
+
+        if fresh || !file_path.exists() {
+            download_to_file(&format!("{}/{}.xml.gz", FEED_URL, name), &gz_path)?;
+            gunzip(&gz_path, &file_path).map_err(|e| anyhow!(e))?;
+            fs::remove_file(&gz_path)
+                .with_context(|| format!("could not remove {}", gz_path.display()))?;
+        }
+
+        info!("parsing {} ...", file_path.display());
+        let file = File::open(&file_path)
+            .with_context(|| format!("could not open {}", file_path.display()))?;
+        let parsed = oval::parse(BufReader::new(file), *default_release)
+            .map_err(|e| ImportError::from(e).quarantine(&file_path))?;
+
+        for cve in parsed {
+            match cves.get_mut(&cve.id) {
+                Some(existing) => {
+                    for fix in cve.fixes {
+                        if !existing.fixes.contains(&fix) {
+                            existing.fixes.push(fix);
+                        }
+                    }
+                }
+                None => {
+                    cves.insert(cve.id.clone(), cve);
+                }
+            }
+        }
+    }
+    // empty definitions are a broken feed rather than no CVEs ever fixed
+    if cves.is_empty() {
+        return Err(anyhow!("the OVAL definitions are empty"));
+    }
+
+    info!("importing the {} CVEs of the SUSE packages ...", cves.len());
+
+    let database = db::Database(pool.get()?);
+    let mut num_imported = 0;
+    for (index, cve) in cves.values().enumerate() {
+        num_imported += store(&database, cve)?;
+
+        if index > 0 && index % 10000 == 0 {
+            info!("processed {} CVEs ...", index);
+        }
+    }
+
+    Ok(num_imported)
+}
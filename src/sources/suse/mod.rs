@@ -0,0 +1,144 @@
+//! CVEs of the SUSE Linux Enterprise and openSUSE packages published in the SUSE OVAL
+//! definitions, with the versions fixing them in every release rather than the upstream
+//! version ranges.
+
+pub use super::redhat::advisory::{product, Advisory, Fix};
+
+#[cfg(feature = "import")]
+pub mod import;
+#[cfg(feature = "import")]
+pub mod oval;
+
+pub const SOURCE_NAME: &str = "SUSE";
+/// No vendors for the packages, the SUSE release is part of the product name.
+pub const VENDOR: &str = "@suse";
+
+/// Release of a distribution given by its id and version, like `sles-15-sp4` for `sles` and
+/// `15.4`, or `opensuse-leap-15.5` for `opensuse-leap` and `15.5`.
+pub fn release(distro: &str, version: &str) -> Option<String> {
+    match distro {
+        "sles" | "sled" => {
+            let mut numbers = version.split('.');
+            let major = numbers
+                .next()
+                .filter(|major| !major.is_empty() && major.chars().all(|c| c.is_ascii_digit()))?;
+            match numbers.next() {
+                Some("0") | None => Some(format!("sles-{}", major)),
+                Some(sp) if sp.chars().all(|c| c.is_ascii_digit()) => {
+                    Some(format!("sles-{}-sp{}", major, sp))
+                }
+                Some(_) => None,
+            }
+        }
+        "opensuse-leap" if !version.is_empty() => Some(format!("opensuse-leap-{}", version)),
+        "opensuse-tumbleweed" => Some("opensuse-tumbleweed".to_string()),
+        _ => None,
+    }
+}
+
+/// Release named by the comment of an OVAL platform criterion, like `sles-15-sp4` for
+/// `SUSE Linux Enterprise Module for Basesystem 15 SP4 is installed`, the modules and the
+/// other products of SUSE Linux Enterprise sharing the packages of the server.
+pub fn platform(comment: &str) -> Option<String> {
+    let name = comment.strip_suffix(" is installed")?;
+
+    if let Some(version) = name.strip_prefix("openSUSE Leap ") {
+        return release("opensuse-leap", version.split_whitespace().next()?);
+    }
+    if name.starts_with("openSUSE Tumbleweed") {
+        return release("opensuse-tumbleweed", "");
+    }
+    if !name.starts_with("SUSE Linux Enterprise ") || name.contains("Micro") {
+        return None;
+    }
+
+    // like `15 SP4` or `12 SP5-LTSS`
+    let words: Vec<&str> = name.split_whitespace().collect();
+    let index = words
+        .iter()
+        .position(|word| word.chars().all(|c| c.is_ascii_digit()))?;
+    let sp = words
+        .get(index + 1)
+        .and_then(|word| word.strip_prefix("SP"))
+        .and_then(|sp| sp.split('-').next())
+        .unwrap_or("0");
+    release("sles", &format!("{}.{}", words[index], sp))
+}
+
+/// Key of the advisory of a CVE, SUSE defining the fixes of every CVE on its own.
+pub fn object_key(id: &str) -> String {
+    format!("{} (suse)", id)
+}
+
+/// Page of the CVE in the SUSE security database.
+pub fn url(advisory: &Advisory) -> String {
+    format!("https://www.suse.com/security/cve/{}.html", advisory.id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{platform, release, url, Advisory, Fix};
+    use crate::search::testing::query;
+
+    #[test]
+    fn can_name_releases() {
+        assert_eq!(Some("sles-15-sp4".to_string()), release("sles", "15.4"));
+        assert_eq!(Some("sles-15".to_string()), release("sles", "15"));
+        assert_eq!(Some("sles-12-sp5".to_string()), release("sles", "12.5"));
+        assert_eq!(
+            Some("opensuse-leap-15.5".to_string()),
+            release("opensuse-leap", "15.5")
+        );
+        assert_eq!(None, release("rhel", "8"));
+
+        assert_eq!(
+            Some("sles-15-sp4".to_string()),
+            platform("SUSE Linux Enterprise Module for Basesystem 15 SP4 is installed")
+        );
+        assert_eq!(
+            Some("sles-12".to_string()),
+            platform("SUSE Linux Enterprise Server 12 is installed")
+        );
+        assert_eq!(
+            Some("opensuse-leap-15.5".to_string()),
+            platform("openSUSE Leap 15.5 is installed")
+        );
+        assert_eq!(
+            Some("sles-12-sp5".to_string()),
+            platform("SUSE Linux Enterprise Server 12 SP5-LTSS is installed")
+        );
+        assert_eq!(
+            None,
+            platform("SUSE Linux Enterprise Micro 5.2 is installed")
+        );
+        assert_eq!(
+            None,
+            platform("libopenssl1_1-1.1.1d-150200.11.48.1 is installed")
+        );
+    }
+
+    #[test]
+    fn can_match_fixed_versions() {
+        let cve = Advisory {
+            id: "CVE-2022-1292".into(),
+            cves: vec!["CVE-2022-1292".into()],
+            severity: Some("moderate".into()),
+            fixes: vec![Fix {
+                release: "sles-15-sp4".into(),
+                package: "libopenssl1_1".into(),
+                version: "0:1.1.1l-150400.7.3.1".into(),
+            }],
+            ..Default::default()
+        };
+
+        assert_eq!("MEDIUM", cve.severity());
+        assert_eq!(
+            "https://www.suse.com/security/cve/CVE-2022-1292.html",
+            url(&cve)
+        );
+        assert_eq!(vec!["sles-15-sp4-libopenssl1_1"], cve.products());
+        assert!(cve.is_match(&query("sles-15-sp4-libopenssl1_1", "1.1.1l-150400.7.0.1")));
+        assert!(!cve.is_match(&query("sles-15-sp4-libopenssl1_1", "1.1.1l-150400.7.3.1")));
+        assert!(!cve.is_match(&query("sles-15-sp3-libopenssl1_1", "1.1.1d-1")));
+    }
+}
@@ -0,0 +1,398 @@
+//! Streaming parser of the SUSE OVAL definitions, resolving the criteria of every
+//! vulnerability into the packages fixed in every release.
+
+use std::collections::HashMap;
+use std::io::BufRead;
+
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+
+use super::{platform, Advisory, Fix};
+
+/// Criteria of a definition, in `OR` between the releases and in `AND` between a release and
+/// its packages.
+#[derive(Debug, Default)]
+struct Criteria {
+    criterions: Vec<Criterion>,
+    children: Vec<Criteria>,
+}
+
+#[derive(Debug, Default)]
+struct Criterion {
+    test_ref: String,
+    /// Like `SUSE Linux Enterprise Server 15 SP4 is installed` or
+    /// `libopenssl1_1-1.1.1l-150400.7.3.1 is installed`.
+    comment: String,
+}
+
+#[derive(Debug, Default)]
+struct Definition {
+    title: String,
+    description: String,
+    severity: Option<String>,
+    criteria: Option<Criteria>,
+}
+
+#[derive(Debug, Default)]
+struct Test {
+    object_ref: String,
+    state_ref: String,
+}
+
+/// Element whose text is being read.
+enum Text {
+    None,
+    Title,
+    Description,
+    Severity,
+    Name,
+    Evr,
+}
+
+#[derive(Default)]
+struct Definitions {
+    definitions: Vec<Definition>,
+    tests: HashMap<String, Test>,
+    /// Package names of the objects.
+    objects: HashMap<String, String>,
+    /// Versions fixing the packages, given by the `less than` states.
+    states: HashMap<String, String>,
+}
+
+impl Definitions {
+    /// Package and version fixing it of a test, if it's a package test.
+    fn fix(&self, test_ref: &str) -> Option<(&str, &str)> {
+        let test = self.tests.get(test_ref)?;
+        let package = self.objects.get(&test.object_ref)?;
+        let version = self.states.get(&test.state_ref)?;
+        Some((package, version))
+    }
+
+    fn fixes(&self, criteria: &Criteria, release: Option<&str>, fixes: &mut Vec<Fix>) {
+        // the platform criterion of a group names the release of its packages
+        let platform = criteria
+            .criterions
+            .iter()
+            .find_map(|criterion| platform(&criterion.comment));
+        let release = platform.as_deref().or(release);
+
+        if let Some(release) = release {
+            for criterion in &criteria.criterions {
+                if let Some((package, version)) = self.fix(&criterion.test_ref) {
+                    let fix = Fix {
+                        release: release.to_string(),
+                        package: package.to_string(),
+                        version: version.to_string(),
+                    };
+                    if !fixes.contains(&fix) {
+                        fixes.push(fix);
+                    }
+                }
+            }
+        }
+
+        for child in &criteria.children {
+            self.fixes(child, release, fixes);
+        }
+    }
+
+    fn cves(&self, default_release: Option<&str>) -> Vec<Advisory> {
+        let mut cves = vec![];
+
+        for definition in &self.definitions {
+            let name = match definition
+                .title
+                .split_whitespace()
+                .next()
+                .filter(|name| name.starts_with("CVE-"))
+            {
+                Some(name) => name,
+                None => continue,
+            };
+
+            let mut fixes = vec![];
+            if let Some(criteria) = &definition.criteria {
+                self.fixes(criteria, default_release, &mut fixes);
+            }
+            if fixes.is_empty() {
+                continue;
+            }
+
+            cves.push(Advisory {
+                id: name.to_string(),
+                description: definition.description.trim().to_string(),
+                severity: definition.severity.clone(),
+                cves: vec![name.to_string()],
+                fixes,
+                ..Default::default()
+            });
+        }
+
+        cves
+    }
+}
+
+fn attribute(element: &BytesStart, name: &[u8]) -> Option<String> {
+    element
+        .attributes()
+        .flatten()
+        .find(|attribute| attribute.key == name)
+        .and_then(|attribute| {
+            attribute
+                .unescaped_value()
+                .ok()
+                .map(|value| String::from_utf8_lossy(&value).into_owned())
+        })
+}
+
+/// Parses the OVAL definitions into the CVEs fixed, the packages of the criteria without a
+/// platform belonging to the default release, like for the rolling releases.
+pub fn parse<R: BufRead>(
+    source: R,
+    default_release: Option<&str>,
+) -> Result<Vec<Advisory>, String> {
+    let mut reader = Reader::from_reader(source);
+    reader.trim_text(true);
+
+    let mut parsed = Definitions::default();
+    let mut definition: Option<Definition> = None;
+    let mut criteria: Vec<Criteria> = vec![];
+    let mut test: Option<(String, Test)> = None;
+    let mut object: Option<String> = None;
+    let mut state: Option<String> = None;
+    let mut text = Text::None;
+
+    let mut buf = vec![];
+    loop {
+        let event = reader.read_event(&mut buf).map_err(|e| {
+            format!(
+                "could not parse the OVAL definitions at {}: {}",
+                reader.buffer_position(),
+                e
+            )
+        })?;
+
+        match &event {
+            Event::Start(element) | Event::Empty(element) => {
+                let is_start = matches!(event, Event::Start(_));
+                match element.local_name() {
+                    b"definition" if is_start => {
+                        if attribute(element, b"class").as_deref() == Some("vulnerability") {
+                            definition = Some(Definition::default());
+                        }
+                    }
+                    b"title" if definition.is_some() => text = Text::Title,
+                    b"description" if definition.is_some() => text = Text::Description,
+                    b"severity" if definition.is_some() => text = Text::Severity,
+                    b"criteria" if is_start && definition.is_some() => {
+                        criteria.push(Criteria::default())
+                    }
+                    b"criterion" => {
+                        if let Some(parent) = criteria.last_mut() {
+                            parent.criterions.push(Criterion {
+                                test_ref: attribute(element, b"test_ref").unwrap_or_default(),
+                                comment: attribute(element, b"comment").unwrap_or_default(),
+                            });
+                        }
+                    }
+                    b"rpminfo_test" if is_start => {
+                        if let Some(id) = attribute(element, b"id") {
+                            test = Some((id, Test::default()));
+                        }
+                    }
+                    b"object" => {
+                        if let Some((_, test)) = &mut test {
+                            test.object_ref = attribute(element, b"object_ref").unwrap_or_default();
+                        }
+                    }
+                    b"state" => {
+                        if let Some((_, test)) = &mut test {
+                            test.state_ref = attribute(element, b"state_ref").unwrap_or_default();
+                        }
+                    }
+                    b"rpminfo_object" if is_start => object = attribute(element, b"id"),
+                    b"name" if object.is_some() => text = Text::Name,
+                    b"rpminfo_state" if is_start => state = attribute(element, b"id"),
+                    b"evr" if state.is_some() => {
+                        if attribute(element, b"operation").as_deref() == Some("less than") {
+                            text = Text::Evr;
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            Event::Text(value) => {
+                let value = value
+                    .unescape_and_decode(&reader)
+                    .map_err(|e| format!("could not decode the OVAL definitions: {}", e))?;
+                match (&text, &mut definition) {
+                    (Text::Title, Some(definition)) => definition.title = value,
+                    (Text::Description, Some(definition)) => definition.description = value,
+                    (Text::Severity, Some(definition)) => definition.severity = Some(value),
+                    (Text::Name, _) => {
+                        if let Some(id) = &object {
+                            parsed.objects.insert(id.clone(), value);
+                        }
+                    }
+                    (Text::Evr, _) => {
+                        if let Some(id) = &state {
+                            parsed.states.insert(id.clone(), value);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            Event::End(element) => {
+                text = Text::None;
+                match element.local_name() {
+                    b"definition" => parsed.definitions.extend(definition.take()),
+                    b"criteria" => {
+                        if let Some(child) = criteria.pop() {
+                            match (criteria.last_mut(), &mut definition) {
+                                (Some(parent), _) => parent.children.push(child),
+                                (None, Some(definition)) => definition.criteria = Some(child),
+                                (None, None) => {}
+                            }
+                        }
+                    }
+                    b"rpminfo_test" => {
+                        if let Some((id, test)) = test.take() {
+                            parsed.tests.insert(id, test);
+                        }
+                    }
+                    b"rpminfo_object" => object = None,
+                    b"rpminfo_state" => state = None,
+                    _ => {}
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+
+        buf.clear();
+    }
+
+    Ok(parsed.cves(default_release))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse;
+
+    const DEFINITIONS: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<oval_definitions xmlns="http://oval.mitre.org/XMLSchema/oval-definitions-5">
+  <definitions>
+    <definition id="oval:org.opensuse.security:def:20221292" version="1" class="vulnerability">
+      <metadata>
+        <title>CVE-2022-1292</title>
+        <description>The c_rehash script does not properly sanitise shell metacharacters.</description>
+        <advisory from="security@suse.de">
+          <severity>Moderate</severity>
+        </advisory>
+      </metadata>
+      <criteria operator="OR">
+        <criteria operator="AND">
+          <criterion test_ref="oval:org.opensuse.security:tst:1" comment="SUSE Linux Enterprise Module for Basesystem 15 SP4 is installed"/>
+          <criteria operator="OR">
+            <criterion test_ref="oval:org.opensuse.security:tst:2" comment="libopenssl1_1-1.1.1l-150400.7.3.1 is installed"/>
+            <criterion test_ref="oval:org.opensuse.security:tst:3" comment="openssl-1_1-1.1.1l-150400.7.3.1 is installed"/>
+          </criteria>
+        </criteria>
+        <criteria operator="AND">
+          <criterion test_ref="oval:org.opensuse.security:tst:4" comment="SUSE Linux Enterprise Server 12 SP5 is installed"/>
+          <criterion test_ref="oval:org.opensuse.security:tst:5" comment="libopenssl1_0_0-1.0.2p-3.49.1 is installed"/>
+        </criteria>
+      </criteria>
+    </definition>
+    <definition id="oval:org.opensuse.security:def:1" version="1" class="patch">
+      <metadata><title>SUSE-SU-2022:1234-1</title></metadata>
+    </definition>
+  </definitions>
+  <tests>
+    <rpminfo_test xmlns="http://oval.mitre.org/XMLSchema/oval-definitions-5#linux" id="oval:org.opensuse.security:tst:1" check="at least one">
+      <object object_ref="oval:org.opensuse.security:obj:1"/>
+      <state state_ref="oval:org.opensuse.security:ste:1"/>
+    </rpminfo_test>
+    <rpminfo_test xmlns="http://oval.mitre.org/XMLSchema/oval-definitions-5#linux" id="oval:org.opensuse.security:tst:2" check="at least one">
+      <object object_ref="oval:org.opensuse.security:obj:2"/>
+      <state state_ref="oval:org.opensuse.security:ste:2"/>
+    </rpminfo_test>
+    <rpminfo_test xmlns="http://oval.mitre.org/XMLSchema/oval-definitions-5#linux" id="oval:org.opensuse.security:tst:3" check="at least one">
+      <object object_ref="oval:org.opensuse.security:obj:3"/>
+      <state state_ref="oval:org.opensuse.security:ste:2"/>
+    </rpminfo_test>
+    <rpminfo_test xmlns="http://oval.mitre.org/XMLSchema/oval-definitions-5#linux" id="oval:org.opensuse.security:tst:5" check="at least one">
+      <object object_ref="oval:org.opensuse.security:obj:4"/>
+      <state state_ref="oval:org.opensuse.security:ste:3"/>
+    </rpminfo_test>
+  </tests>
+  <objects>
+    <rpminfo_object xmlns="http://oval.mitre.org/XMLSchema/oval-definitions-5#linux" id="oval:org.opensuse.security:obj:1">
+      <name>sle-module-basesystem-release</name>
+    </rpminfo_object>
+    <rpminfo_object xmlns="http://oval.mitre.org/XMLSchema/oval-definitions-5#linux" id="oval:org.opensuse.security:obj:2">
+      <name>libopenssl1_1</name>
+    </rpminfo_object>
+    <rpminfo_object xmlns="http://oval.mitre.org/XMLSchema/oval-definitions-5#linux" id="oval:org.opensuse.security:obj:3">
+      <name>openssl-1_1</name>
+    </rpminfo_object>
+    <rpminfo_object xmlns="http://oval.mitre.org/XMLSchema/oval-definitions-5#linux" id="oval:org.opensuse.security:obj:4">
+      <name>libopenssl1_0_0</name>
+    </rpminfo_object>
+  </objects>
+  <states>
+    <rpminfo_state xmlns="http://oval.mitre.org/XMLSchema/oval-definitions-5#linux" id="oval:org.opensuse.security:ste:1">
+      <version operation="equals">15.4</version>
+    </rpminfo_state>
+    <rpminfo_state xmlns="http://oval.mitre.org/XMLSchema/oval-definitions-5#linux" id="oval:org.opensuse.security:ste:2">
+      <evr datatype="evr_string" operation="less than">0:1.1.1l-150400.7.3.1</evr>
+    </rpminfo_state>
+    <rpminfo_state xmlns="http://oval.mitre.org/XMLSchema/oval-definitions-5#linux" id="oval:org.opensuse.security:ste:3">
+      <evr datatype="evr_string" operation="less than">0:1.0.2p-3.49.1</evr>
+    </rpminfo_state>
+  </states>
+</oval_definitions>"#;
+
+    #[test]
+    fn can_parse_definitions() {
+        let cves = parse(DEFINITIONS.as_bytes(), None).unwrap();
+
+        assert_eq!(1, cves.len());
+        let cve = &cves[0];
+        assert_eq!("CVE-2022-1292", cve.id);
+        assert_eq!(
+            "The c_rehash script does not properly sanitise shell metacharacters.",
+            cve.description
+        );
+        assert_eq!("MEDIUM", cve.severity());
+        assert_eq!(
+            vec![
+                "sles-15-sp4-libopenssl1_1",
+                "sles-15-sp4-openssl-1_1",
+                "sles-12-sp5-libopenssl1_0_0",
+            ],
+            cve.products()
+        );
+        assert_eq!("0:1.0.2p-3.49.1", cve.fixes[2].version);
+    }
+
+    #[test]
+    fn can_parse_rolling_releases() {
+        let cves = parse(
+            DEFINITIONS
+                .replace("SUSE Linux Enterprise Server 12 SP5 is installed", "")
+                .as_bytes(),
+            Some("opensuse-tumbleweed"),
+        )
+        .unwrap();
+
+        assert_eq!(
+            vec![
+                "sles-15-sp4-libopenssl1_1",
+                "sles-15-sp4-openssl-1_1",
+                "opensuse-tumbleweed-libopenssl1_0_0",
+            ],
+            cves[0].products()
+        );
+    }
+}
@@ -0,0 +1,144 @@
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use chrono::Utc;
+use log::info;
+use serde::Deserialize;
+
+use super::{Notice, SOURCE_NAME, VENDOR};
+
+use crate::db::{self, Pool};
+use crate::utils::{get_json, normalize_utc, parse_utc};
+
+pub const API_URL: &str = "https://ubuntu.com/security/notices.json";
+/// Notices listed by page.
+const PAGE_SIZE: usize = 20;
+/// The notices published shortly before the last import are imported again, in case they
+/// were amended since.
+const REFRESH_WINDOW_DAYS: i64 = 7;
+
+/// Page of the notices, the newest first.
+#[derive(Debug, Deserialize)]
+struct Page {
+    notices: Vec<Notice>,
+    total_results: usize,
+}
+
+fn get_page(client: &reqwest::blocking::Client, offset: usize) -> Result<Page> {
+    let url = format!(
+        "{}?limit={}&offset={}&order=newest",
+        API_URL, PAGE_SIZE, offset
+    );
+
+    Ok(get_json(client, &url)?)
+}
+
+fn store(database: &db::Database, notice: &Notice) -> Result<u32> {
+    let products = notice.products();
+    let cves = notice.cves();
+    if products.is_empty() || cves.is_empty() {
+        return Ok(0);
+    }
+
+    let object_id = database
+        .upsert_object(db::models::NewObject::with(
+            Notice::object_key(&notice.id),
+            serde_json::to_string(notice)?,
+        ))
+        .map_err(|e| anyhow!(e))?;
+
+    let mut refs = db::models::References::default();
+    refs.push(db::models::Reference {
+        url: format!("https://ubuntu.com/security/notices/{}", notice.id),
+        tags: vec!["Vendor Advisory".into()],
+    });
+
+    let mut num_imported = 0;
+    for cve in cves {
+        for product in &products {
+            let new_cve = db::models::NewCVE::with(
+                SOURCE_NAME.into(),
+                VENDOR.into(),
+                product.clone(),
+                cve.into(),
+                notice.description(),
+                0.0,
+                "NONE".into(),
+                None,
+                refs.clone(),
+                Some(object_id),
+            );
+            if database.upsert_cve(new_cve).map_err(|e| anyhow!(e))? {
+                num_imported += 1;
+            }
+        }
+    }
+
+    Ok(num_imported)
+}
+
+/// Imports the notices published since shortly before the last import, or all of them the
+/// first time and with `fresh`, creating the new records and updating the changed ones.
+pub fn run(pool: &Pool, fresh: bool) -> Result<u32> {
+    let client = reqwest::blocking::Client::builder()
+        .timeout(Some(Duration::from_secs(300)))
+        .user_agent(format!("kepler/{}", crate::version()))
+        .build()?;
+    let database = db::Database(pool.get()?);
+
+    let since = if fresh {
+        None
+    } else {
+        database
+            .get_synced_at(SOURCE_NAME)
+            .map_err(|e| anyhow!(e))?
+            .map(|since| since - chrono::Duration::days(REFRESH_WINDOW_DAYS))
+    };
+    let until = Utc::now();
+    match &since {
+        Some(since) => info!("importing the notices published since {}", since),
+        None => info!("importing all the notices"),
+    }
+
+    let mut notices = vec![];
+    let mut offset = 0;
+    loop {
+        let page = get_page(&client, offset)?;
+        let num_notices = page.notices.len();
+        let total = page.total_results;
+
+        let mut done = num_notices < PAGE_SIZE || offset + num_notices >= total;
+        for mut notice in page.notices {
+            // compared in UTC, whatever the offset of the timestamps
+            match (&since, parse_utc(&notice.published)) {
+                (Some(since), Some(published)) if published < *since => done = true,
+                _ => {
+                    notice.published = normalize_utc(&notice.published);
+                    notices.push(notice)
+                }
+            }
+        }
+
+        offset += num_notices;
+        info!("listed {} notices ...", offset);
+        if done {
+            break;
+        }
+    }
+
+    // the oldest first, so that the fixes of the later notices of the same CVEs, like the
+    // regressions, replace the previous ones
+    notices.reverse();
+    info!("importing {} notices ...", notices.len());
+
+    let mut num_imported = 0;
+    for notice in &notices {
+        num_imported += store(&database, notice)?;
+    }
+
+    database
+        .set_synced_at(db::models::NewSyncState::with(SOURCE_NAME.into(), until))
+        .map_err(|e| anyhow!(e))?;
+
+    Ok(num_imported)
+}
@@ -0,0 +1,189 @@
+//! CVEs of the Ubuntu packages fixed by the Ubuntu Security Notices (USN), with the versions
+//! of every release fixing them rather than the upstream version ranges.
+
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::debian::dpkg;
+use crate::scan::ecosystem_product;
+use crate::search::Query;
+
+#[cfg(feature = "import")]
+pub mod import;
+
+pub const SOURCE_NAME: &str = "Ubuntu";
+/// No vendors for the packages, the Ubuntu release is part of the product name.
+pub const VENDOR: &str = "@ubuntu";
+
+/// Version numbers of the releases, named by their codename in the notices.
+pub const RELEASES: &[(&str, &str)] = &[
+    ("14.04", "trusty"),
+    ("16.04", "xenial"),
+    ("18.04", "bionic"),
+    ("20.04", "focal"),
+    ("21.10", "impish"),
+    ("22.04", "jammy"),
+    ("22.10", "kinetic"),
+    ("23.04", "lunar"),
+    ("23.10", "mantic"),
+    ("24.04", "noble"),
+];
+
+/// Product of a source package of an Ubuntu release, like `ubuntu-focal-openssl`.
+pub fn product(release: &str, package: &str) -> String {
+    ecosystem_product(&format!("ubuntu-{}", release), package)
+}
+
+/// Codename of a release given by its number, like `20.04`, or by its codename.
+pub fn codename(release: &str) -> Option<&str> {
+    RELEASES
+        .iter()
+        .find(|(number, codename)| *number == release || *codename == release)
+        .map(|(_, codename)| *codename)
+}
+
+/// Package fixed by a notice in a release.
+#[derive(Debug, Default, Clone, Deserialize, Serialize)]
+pub struct Package {
+    pub name: String,
+    pub version: String,
+    /// Whether it's the source package, the other ones being the binary packages built
+    /// from it.
+    #[serde(default)]
+    pub is_source: bool,
+}
+
+#[derive(Debug, Default, Clone, Deserialize, Serialize)]
+pub struct Notice {
+    /// Like `USN-5402-1`, the last number counting the notices of the same fixes, like the
+    /// regressions.
+    pub id: String,
+    #[serde(default)]
+    pub title: String,
+    #[serde(default)]
+    pub summary: String,
+    #[serde(default)]
+    pub published: String,
+    #[serde(default)]
+    pub cves_ids: Vec<String>,
+    /// Packages fixed in every release.
+    #[serde(default)]
+    pub release_packages: BTreeMap<String, Vec<Package>>,
+}
+
+impl Notice {
+    pub fn object_key(id: &str) -> String {
+        format!("{} (ubuntu)", id)
+    }
+
+    /// CVEs fixed by the notice, leaving out the Launchpad bugs it may list too.
+    pub fn cves(&self) -> Vec<&str> {
+        self.cves_ids
+            .iter()
+            .map(String::as_str)
+            .filter(|id| id.starts_with("CVE-"))
+            .collect()
+    }
+
+    pub fn description(&self) -> String {
+        if self.summary.trim().is_empty() {
+            return self.title.trim().to_string();
+        }
+        self.summary.trim().to_string()
+    }
+
+    /// Products and fixed versions of the source packages.
+    pub fn fixed(&self) -> Vec<(String, &str)> {
+        let mut fixed = vec![];
+        for (release, packages) in &self.release_packages {
+            for package in packages.iter().filter(|package| package.is_source) {
+                fixed.push((product(release, &package.name), package.version.as_str()));
+            }
+        }
+        fixed
+    }
+
+    /// Unique products of the source packages fixed.
+    pub fn products(&self) -> Vec<String> {
+        let mut products = vec![];
+        for (product, _) in self.fixed() {
+            if !products.contains(&product) {
+                products.push(product);
+            }
+        }
+        products
+    }
+
+    /// Matches the versions older than the fixed one.
+    pub fn is_match(&self, query: &Query) -> bool {
+        // we need a version
+        let version = match &query.version {
+            Some(version) => dpkg::Version::parse(version),
+            None => return false,
+        };
+
+        self.fixed().into_iter().any(|(product, fixed)| {
+            product == query.product
+                && version.compare(&dpkg::Version::parse(fixed)) == Ordering::Less
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{codename, Notice};
+    use crate::search::testing::query;
+
+    const NOTICE: &str = r#"{
+        "id": "USN-5402-1",
+        "title": "OpenSSL vulnerabilities",
+        "summary": "Several security issues were fixed in OpenSSL.",
+        "published": "2022-05-03T19:44:46.066931",
+        "cves_ids": ["CVE-2022-1292", "CVE-2022-1343", "https://launchpad.net/bugs/1970256"],
+        "release_packages": {
+            "focal": [
+                {"name": "openssl", "version": "1.1.1f-1ubuntu2.13", "is_source": true},
+                {"name": "libssl1.1", "version": "1.1.1f-1ubuntu2.13", "is_source": false}
+            ],
+            "jammy": [
+                {"name": "openssl", "version": "3.0.2-0ubuntu1.2", "is_source": true}
+            ]
+        }
+    }"#;
+
+    #[test]
+    fn can_describe_notices() {
+        let notice: Notice = serde_json::from_str(NOTICE).unwrap();
+
+        assert_eq!(vec!["CVE-2022-1292", "CVE-2022-1343"], notice.cves());
+        assert_eq!(
+            "Several security issues were fixed in OpenSSL.",
+            notice.description()
+        );
+        assert_eq!(
+            vec!["ubuntu-focal-openssl", "ubuntu-jammy-openssl"],
+            notice.products()
+        );
+    }
+
+    #[test]
+    fn can_match_fixed_versions() {
+        let notice: Notice = serde_json::from_str(NOTICE).unwrap();
+
+        assert!(notice.is_match(&query("ubuntu-focal-openssl", "1.1.1f-1ubuntu2.12")));
+        assert!(!notice.is_match(&query("ubuntu-focal-openssl", "1.1.1f-1ubuntu2.13")));
+        assert!(notice.is_match(&query("ubuntu-jammy-openssl", "3.0.2-0ubuntu1.1")));
+        // only the source packages are matched
+        assert!(!notice.is_match(&query("ubuntu-focal-libssl1.1", "1.1.1f-1ubuntu2.12")));
+        assert!(!notice.is_match(&query("ubuntu-bionic-openssl", "1.1.1-1ubuntu2.1")));
+    }
+
+    #[test]
+    fn can_name_releases() {
+        assert_eq!(Some("focal"), codename("20.04"));
+        assert_eq!(Some("jammy"), codename("jammy"));
+        assert_eq!(None, codename("11"));
+    }
+}
@@ -0,0 +1,67 @@
+use std::env;
+
+use log::info;
+
+use crate::db::Pool;
+use crate::search;
+
+/// Logs the settings the memory usage of kepler depends on, so that container limits can be
+/// sized accordingly.
+pub fn log_settings(pool: &Pool) {
+    info!(
+        "memory settings: {} database connections, {} cached search results, {}",
+        pool.max_size(),
+        search::CACHE_SIZE,
+        if cfg!(feature = "jemalloc") {
+            "jemalloc allocator"
+        } else {
+            "system allocator"
+        }
+    );
+    if let Some(budget) = budget() {
+        info!("memory budget: {} MiB", budget);
+    }
+}
+
+/// Memory budget in MiB set with the `KEPLER_MEMORY_BUDGET` environment variable.
+fn budget() -> Option<u64> {
+    env::var("KEPLER_MEMORY_BUDGET")
+        .ok()
+        .and_then(|b| b.parse::<u64>().ok())
+}
+
+/// Logs the memory allocated and resident after a stage of the work, warning when the
+/// resident memory exceeds the budget. Statistics are only available with the `jemalloc`
+/// feature.
+#[cfg(feature = "jemalloc")]
+pub fn log_usage(stage: &str) {
+    use tikv_jemalloc_ctl::{epoch, stats};
+
+    // statistics are cached until the epoch is advanced
+    if let Err(e) = epoch::advance() {
+        log::warn!("could not read memory statistics: {}", e);
+        return;
+    }
+
+    let allocated = stats::allocated::read().unwrap_or_default() / (1024 * 1024);
+    let resident = stats::resident::read().unwrap_or_default() / (1024 * 1024);
+
+    info!(
+        "memory after {}: {} MiB allocated, {} MiB resident",
+        stage, allocated, resident
+    );
+
+    if let Some(budget) = budget() {
+        if resident as u64 > budget {
+            log::warn!(
+                "memory after {} exceeds the budget: {} MiB resident, {} MiB budget",
+                stage,
+                resident,
+                budget
+            );
+        }
+    }
+}
+
+#[cfg(not(feature = "jemalloc"))]
+pub fn log_usage(_stage: &str) {}
@@ -1,31 +1,397 @@
-use std::fs::File;
-use std::path::Path;
+use std::fmt;
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+#[cfg(feature = "import")]
+use std::thread;
+#[cfg(feature = "import")]
+use std::time::Duration;
+use std::time::{SystemTime, UNIX_EPOCH};
 
+#[cfg(feature = "import")]
+use bzip2::read::MultiBzDecoder;
+use chrono::{DateTime, NaiveDateTime, SecondsFormat, TimeZone, Utc};
+#[cfg(feature = "import")]
 use flate2::read::GzDecoder;
-use log::{info, warn};
+use lazy_static::lazy_static;
+#[cfg(feature = "import")]
+use log::info;
+use log::warn;
+use ring::digest::{Context, SHA256};
+#[cfg(feature = "import")]
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
 use version_compare::Cmp;
 
-pub fn download_to_file(url: &str, file_name: &Path) -> Result<(), String> {
+pub mod memory;
+
+/// File downloaded by an import, recorded in the history of the import runs.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Download {
+    pub url: String,
+    /// Uppercase hex SHA-256 of the file.
+    pub sha256: String,
+}
+
+lazy_static! {
+    static ref DOWNLOADS: Mutex<Vec<Download>> = Mutex::new(vec![]);
+}
+
+/// Takes the files downloaded since the last call, to record them with the import run.
+pub fn take_downloads() -> Vec<Download> {
+    std::mem::take(&mut *DOWNLOADS.lock().unwrap())
+}
+
+/// Validators given by the server for a downloaded file, sent back by the next download so
+/// that the file is only transferred again once changed.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Validators {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub etag: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_modified: Option<String>,
+}
+
+impl Validators {
+    /// File of the validators next to the downloaded one, like
+    /// `nvdcve-1.1-2024.json.gz.validators`.
+    fn path(file_name: &Path) -> PathBuf {
+        let mut name = file_name.as_os_str().to_owned();
+        name.push(".validators");
+        PathBuf::from(name)
+    }
+
+    /// Validators of the file, unless it's missing or was downloaded without any.
+    pub fn load(file_name: &Path) -> Option<Self> {
+        if !file_name.exists() {
+            return None;
+        }
+        let json = fs::read_to_string(Self::path(file_name)).ok()?;
+        serde_json::from_str::<Self>(&json)
+            .ok()
+            .filter(|validators| validators.etag.is_some() || validators.last_modified.is_some())
+    }
+
+    pub fn save(&self, file_name: &Path) -> Result<(), String> {
+        let path = Self::path(file_name);
+        let json = serde_json::to_string(self).map_err(|e| e.to_string())?;
+        fs::write(&path, json).map_err(|e| format!("could not write {}: {}", path.display(), e))
+    }
+
+    /// Forgets the validators of the file, before it's replaced.
+    pub fn remove(file_name: &Path) {
+        let _ = fs::remove_file(Self::path(file_name));
+    }
+}
+
+/// Checksum of a downloaded file not matching the published one.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ChecksumMismatch {
+    pub file: String,
+    pub expected: String,
+    pub actual: String,
+}
+
+/// Failure of an import while downloading or validating its data, with the details needed
+/// to diagnose it without reading the logs.
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+pub struct ImportError {
+    pub error: String,
+    pub url: Option<String>,
+    pub status: Option<u16>,
+    pub checksum: Option<ChecksumMismatch>,
+    pub quarantined: Option<String>,
+}
+
+impl fmt::Display for ImportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.error)?;
+        if let Some(quarantined) = &self.quarantined {
+            write!(f, " (quarantined to {})", quarantined)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ImportError {}
+
+impl From<String> for ImportError {
+    fn from(error: String) -> Self {
+        Self {
+            error,
+            ..Default::default()
+        }
+    }
+}
+
+impl ImportError {
+    /// Moves the file that caused the failure to the quarantine, so that it's not picked
+    /// up again by the next import but can still be inspected.
+    pub fn quarantine(mut self, file_name: &Path) -> Self {
+        match quarantine(file_name) {
+            Ok(path) => self.quarantined = Some(path.display().to_string()),
+            Err(e) => warn!("{}", e),
+        }
+        self
+    }
+}
+
+/// Moves the file to the `quarantine` directory next to it, timestamping its name.
+pub fn quarantine(file_name: &Path) -> Result<PathBuf, String> {
+    let mut path = file_name
+        .parent()
+        .map(Path::to_path_buf)
+        .unwrap_or_default();
+    path.push("quarantine");
+    fs::create_dir_all(&path).map_err(|e| format!("could not create {}: {}", path.display(), e))?;
+
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    path.push(format!(
+        "{}.{}",
+        file_name
+            .file_name()
+            .map(|name| name.to_string_lossy())
+            .unwrap_or_default(),
+        timestamp
+    ));
+
+    fs::rename(file_name, &path).map_err(|e| {
+        format!(
+            "could not quarantine {} to {}: {}",
+            file_name.display(),
+            path.display(),
+            e
+        )
+    })?;
+    warn!("quarantined {} to {}", file_name.display(), path.display());
+
+    Ok(path)
+}
+
+/// Uppercase hex SHA-256 of the file, like the NIST feeds publish them.
+pub fn sha256_file(file_name: &Path) -> Result<String, String> {
+    let mut file = File::open(file_name)
+        .map_err(|e| format!("could not open {}: {}", file_name.display(), e))?;
+
+    let mut context = Context::new(&SHA256);
+    let mut buffer = [0u8; 64 * 1024];
+    loop {
+        let read = file
+            .read(&mut buffer)
+            .map_err(|e| format!("could not read {}: {}", file_name.display(), e))?;
+        if read == 0 {
+            break;
+        }
+        context.update(&buffer[..read]);
+    }
+
+    Ok(context
+        .finish()
+        .as_ref()
+        .iter()
+        .map(|b| format!("{:02X}", b))
+        .collect())
+}
+
+/// Attempts of a request refused because of the rate limits or failing because of an outage.
+#[cfg(feature = "import")]
+const MAX_ATTEMPTS: u32 = 5;
+/// Wait before the second attempt of a request, increased by as much before each next one
+/// unless the server tells how long to wait.
+#[cfg(feature = "import")]
+const RETRY_BACKOFF: Duration = Duration::from_secs(10);
+/// Longest wait between two attempts, the rate limits resetting hourly at worst.
+#[cfg(feature = "import")]
+const MAX_RETRY_WAIT: Duration = Duration::from_secs(15 * 60);
+
+/// Time to wait before retrying a request refused because of the rate limits, as told by the
+/// `Retry-After` header or by when the GitHub style `X-RateLimit-*` limits reset.
+#[cfg(feature = "import")]
+fn retry_after(res: &reqwest::blocking::Response) -> Option<Duration> {
+    let header = |name: &str| {
+        res.headers()
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.parse::<u64>().ok())
+    };
+    if let Some(seconds) = header("Retry-After") {
+        return Some(Duration::from_secs(seconds));
+    }
+    if header("X-RateLimit-Remaining") == Some(0) {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).ok()?.as_secs();
+        let reset = header("X-RateLimit-Reset")?;
+        return Some(Duration::from_secs(reset.saturating_sub(now) + 1));
+    }
+    None
+}
+
+/// Sends the request built by `request` again as long as it's refused because of the rate
+/// limits (`403` or `429`) or fails because of an outage (`5xx`), up to `MAX_ATTEMPTS` times,
+/// returning the last response whatever its status.
+#[cfg(feature = "import")]
+pub fn send_with_retries<F>(
+    url: &str,
+    mut request: F,
+) -> Result<reqwest::blocking::Response, ImportError>
+where
+    F: FnMut() -> reqwest::blocking::RequestBuilder,
+{
+    let mut attempt = 0;
+    loop {
+        attempt += 1;
+
+        let res = request().send().map_err(|e| ImportError {
+            error: format!("error requesting {}: {}", url, e),
+            url: Some(url.to_string()),
+            ..Default::default()
+        })?;
+        let status = res.status();
+
+        let retry = status.as_u16() == 403 || status.as_u16() == 429 || status.is_server_error();
+        if !retry || attempt >= MAX_ATTEMPTS {
+            return Ok(res);
+        }
+
+        let wait = retry_after(&res)
+            .unwrap_or(RETRY_BACKOFF * attempt)
+            .min(MAX_RETRY_WAIT);
+        warn!("{} answered {}, retrying in {:?}", url, status, wait);
+        thread::sleep(wait);
+    }
+}
+
+/// Gets the JSON document at the URL, retrying like `send_with_retries`.
+#[cfg(feature = "import")]
+pub fn get_json<T: DeserializeOwned>(
+    client: &reqwest::blocking::Client,
+    url: &str,
+) -> Result<T, ImportError> {
+    let res = send_with_retries(url, || {
+        client
+            .get(url)
+            .header(reqwest::header::ACCEPT, "application/json")
+    })?;
+    parse_json(url, res)
+}
+
+/// Parses the JSON body of a response, failing unless its status is a success.
+#[cfg(feature = "import")]
+pub fn parse_json<T: DeserializeOwned>(
+    url: &str,
+    res: reqwest::blocking::Response,
+) -> Result<T, ImportError> {
+    let status = res.status();
+    let failure = |error: String| ImportError {
+        error,
+        url: Some(url.to_string()),
+        status: Some(status.as_u16()),
+        ..Default::default()
+    };
+
+    if !status.is_success() {
+        return Err(failure(format!("error requesting {}: {}", url, status)));
+    }
+    res.json()
+        .map_err(|e| failure(format!("could not parse the response of {}: {}", url, e)))
+}
+
+#[cfg(feature = "import")]
+pub fn download_to_file(url: &str, file_name: &Path) -> Result<(), ImportError> {
+    download_if_modified(url, file_name).map(|_| ())
+}
+
+/// Downloads the file unless the server tells it's unchanged since its last download,
+/// according to its `ETag` or `Last-Modified` header then, returning whether it was.
+#[cfg(feature = "import")]
+pub fn download_if_modified(url: &str, file_name: &Path) -> Result<bool, ImportError> {
+    use reqwest::header::{ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED};
+
     info!("downloading {} to {} ...", url, file_name.display(),);
 
+    let failure = |error: String, status: Option<u16>| ImportError {
+        error,
+        url: Some(url.to_string()),
+        status,
+        ..Default::default()
+    };
+
     let client = reqwest::blocking::Client::builder()
         .timeout(Some(std::time::Duration::from_secs(300)))
         .build()
         .map_err(|e| format!("could not create http client: {}", e))?;
-    let mut res = client
-        .get(url)
+    let validators = Validators::load(file_name);
+    let mut request = client.get(url);
+    if let Some(validators) = &validators {
+        if let Some(etag) = &validators.etag {
+            request = request.header(IF_NONE_MATCH, etag.as_str());
+        }
+        if let Some(last_modified) = &validators.last_modified {
+            request = request.header(IF_MODIFIED_SINCE, last_modified.as_str());
+        }
+    }
+    let mut res = request
         .send()
-        .map_err(|e| format!("error downloading file: {}", e))?;
+        .map_err(|e| failure(format!("error downloading {}: {}", url, e), None))?;
+
+    let status = res.status();
+    if status == reqwest::StatusCode::NOT_MODIFIED && validators.is_some() {
+        info!("{} not modified, keeping {}", url, file_name.display());
+        return Ok(false);
+    }
+    if !status.is_success() {
+        return Err(failure(
+            format!("error downloading {}: {}", url, status),
+            Some(status.as_u16()),
+        ));
+    }
+
+    let header = |name| {
+        res.headers()
+            .get(name)
+            .and_then(|value: &reqwest::header::HeaderValue| value.to_str().ok())
+            .map(str::to_string)
+    };
+    let validators = Validators {
+        etag: header(ETAG),
+        last_modified: header(LAST_MODIFIED),
+    };
 
+    // not to be sent back for an interrupted download
+    Validators::remove(file_name);
     let mut file = File::create(file_name)
         .map_err(|e| format!("could not create {}: {}", file_name.display(), e))?;
 
-    res.copy_to(&mut file)
-        .map_err(|e| format!("could not download {}: {}", file_name.display(), e))?;
+    res.copy_to(&mut file).map_err(|e| {
+        // don't leave a truncated file behind for the next import
+        let _ = fs::remove_file(file_name);
+        failure(
+            format!("could not download {}: {}", file_name.display(), e),
+            Some(status.as_u16()),
+        )
+    })?;
 
-    Ok(())
+    if validators.etag.is_some() || validators.last_modified.is_some() {
+        if let Err(e) = validators.save(file_name) {
+            warn!("{}", e);
+        }
+    }
+
+    match sha256_file(file_name) {
+        Ok(sha256) => DOWNLOADS.lock().unwrap().push(Download {
+            url: url.to_string(),
+            sha256,
+        }),
+        Err(e) => warn!("{}", e),
+    }
+
+    Ok(true)
 }
 
+#[cfg(feature = "import")]
 pub fn gunzip(from: &Path, to: &Path) -> Result<(), String> {
     info!("extracting {} to {} ...", from.display(), to.display());
 
@@ -43,6 +409,79 @@ pub fn gunzip(from: &Path, to: &Path) -> Result<(), String> {
     Ok(())
 }
 
+#[cfg(feature = "import")]
+pub fn bunzip2(from: &Path, to: &Path) -> Result<(), String> {
+    info!("extracting {} to {} ...", from.display(), to.display());
+
+    let source =
+        File::open(from).map_err(|e| format!("could not open {}: {}", from.display(), e))?;
+
+    let mut archive = std::io::BufReader::new(MultiBzDecoder::new(source));
+
+    let mut dest =
+        File::create(to).map_err(|e| format!("could not create {}: {}", to.display(), e))?;
+
+    std::io::copy(&mut archive, &mut dest)
+        .map_err(|e| format!("could not extract {}: {}", from.display(), e))?;
+
+    Ok(())
+}
+
+/// Parses a timestamp of a source in UTC, the ones with an offset like
+/// `2023-01-11T14:13+09:00` being converted, and the ones without being in UTC already like
+/// `2021-05-19T14:15:07.833` or `2023-03-09 21:32`.
+pub fn parse_utc(value: &str) -> Option<DateTime<Utc>> {
+    let value = value.trim();
+    if let Ok(time) = DateTime::parse_from_rfc3339(value) {
+        return Some(time.with_timezone(&Utc));
+    }
+    for format in &[
+        "%Y-%m-%dT%H:%M%:z",
+        "%Y-%m-%dT%H:%M:%S%.f%z",
+        "%Y-%m-%d %H:%M:%S%.f %z",
+    ] {
+        if let Ok(time) = DateTime::parse_from_str(value, format) {
+            return Some(time.with_timezone(&Utc));
+        }
+    }
+    for format in &[
+        "%Y-%m-%dT%H:%M:%S%.f",
+        "%Y-%m-%d %H:%M:%S%.f",
+        "%Y-%m-%dT%H:%M",
+        "%Y-%m-%d %H:%M",
+    ] {
+        if let Ok(time) = NaiveDateTime::parse_from_str(value, format) {
+            return Some(Utc.from_utc_datetime(&time));
+        }
+    }
+    None
+}
+
+/// Timestamp of a source normalized to UTC, like `2023-01-11T05:13:00Z`, so that the
+/// timestamps of all the sources compare alike. The dates without a time, like
+/// `2023-01-11`, and the values not recognized are kept as they are.
+pub fn normalize_utc(value: &str) -> String {
+    match parse_utc(value) {
+        Some(time) => time.to_rfc3339_opts(SecondsFormat::Secs, true),
+        None => value.to_string(),
+    }
+}
+
+/// Text of an HTML fragment, without its tags and with its whitespace collapsed.
+pub fn html_text(html: &str) -> String {
+    let mut text = String::with_capacity(html.len());
+    let mut in_tag = false;
+    for c in html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            c if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
 pub fn version_cmp(a: &str, b: &str, operator: Cmp) -> bool {
     if let Ok(res) = version_compare::compare_to(a, b, operator) {
         return res;
@@ -54,3 +493,92 @@ pub fn version_cmp(a: &str, b: &str, operator: Cmp) -> bool {
     }
     false
 }
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::{html_text, normalize_utc, parse_utc, sha256_file, ImportError, Validators};
+
+    #[test]
+    fn can_normalize_timestamps() {
+        assert_eq!(
+            "2023-01-11T05:13:00Z",
+            normalize_utc("2023-01-11T14:13+09:00")
+        );
+        assert_eq!(
+            "2023-11-06T18:06:43Z",
+            normalize_utc("2023-11-06T18:06:43.052Z")
+        );
+        assert_eq!(
+            "2021-05-19T14:15:07Z",
+            normalize_utc("2021-05-19T14:15:07.833")
+        );
+        assert_eq!("2023-03-09T21:32:00Z", normalize_utc("2023-03-09 21:32"));
+        assert_eq!(
+            "2022-05-03T17:44:46Z",
+            normalize_utc("2022-05-03T19:44:46+0200")
+        );
+        assert_eq!("2023-01-11", normalize_utc("2023-01-11"));
+        assert_eq!("", normalize_utc(""));
+
+        // the same instant around the boundary of a day
+        assert_eq!(
+            parse_utc("2023-01-10T23:30:00Z"),
+            parse_utc("2023-01-11T08:30+09:00")
+        );
+    }
+
+    #[test]
+    fn can_strip_html() {
+        assert_eq!("a b c", html_text("<p>a <i>b</i></p>\n<p>c</p>"));
+    }
+
+    #[test]
+    fn can_quarantine_files() {
+        let mut dir = std::env::temp_dir();
+        dir.push(format!("kepler-quarantine-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let mut file_name = dir.clone();
+        file_name.push("nvdcve-1.1-recent.json");
+        fs::write(&file_name, "{}").unwrap();
+
+        assert_eq!(
+            "44136FA355B3678A1146AD16F7E8649E94FB4FC21FE77E8310C060F61CAAFF8A",
+            sha256_file(&file_name).unwrap()
+        );
+
+        let err = ImportError::from("could not parse".to_string()).quarantine(&file_name);
+        let quarantined = err.quarantined.unwrap();
+        assert!(!file_name.exists());
+        assert!(quarantined.contains("quarantine/nvdcve-1.1-recent.json."));
+        assert_eq!("{}", fs::read_to_string(&quarantined).unwrap());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn can_keep_validators() {
+        let mut dir = std::env::temp_dir();
+        dir.push(format!("kepler-validators-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let mut file_name = dir.clone();
+        file_name.push("known_exploited_vulnerabilities.json");
+
+        let validators = Validators {
+            etag: Some("\"5f3b-61a1\"".into()),
+            last_modified: Some("Tue, 14 Jun 2022 13:00:00 GMT".into()),
+        };
+        validators.save(&file_name).unwrap();
+        // the file itself is missing
+        assert_eq!(None, Validators::load(&file_name));
+
+        fs::write(&file_name, "{}").unwrap();
+        assert_eq!(Some(validators), Validators::load(&file_name));
+
+        Validators::remove(&file_name);
+        assert_eq!(None, Validators::load(&file_name));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}